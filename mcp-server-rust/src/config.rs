@@ -17,6 +17,230 @@ pub struct Config {
     
     /// Enable verbose logging
     pub verbose: bool,
+
+    /// Directory with project templates used by `POST /scaffold`
+    #[serde(default = "default_templates_dir")]
+    pub templates_dir: PathBuf,
+
+    /// Default TTL of temporary session workspaces in seconds
+    #[serde(default = "default_session_ttl")]
+    pub session_default_ttl_secs: u64,
+
+    /// Upper bound for a TTL requested by the client
+    #[serde(default = "default_session_max_ttl")]
+    pub session_max_ttl_secs: u64,
+
+    /// How often expired sessions are garbage-collected
+    #[serde(default = "default_session_gc_interval")]
+    pub session_gc_interval_secs: u64,
+
+    /// Root of temporary session workspaces; must be outside `base_dir`,
+    /// otherwise the main workspace could list and open other sessions' files
+    #[serde(default = "default_sessions_dir")]
+    pub sessions_dir: PathBuf,
+
+    /// Start the server in read-only mode (can be toggled at runtime via `/admin/read-only`)
+    #[serde(default)]
+    pub read_only: bool,
+
+    /// OTLP trace export (requires the `otel` feature)
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+
+    /// How long responses to writes with an `Idempotency-Key` are replayed; 0 disables the cache
+    #[serde(default = "default_idempotency_ttl")]
+    pub idempotency_ttl_secs: u64,
+
+    /// How long a `GET /usage` report is served from cache; 0 recomputes every time
+    #[serde(default = "default_usage_cache_ttl")]
+    pub usage_cache_ttl_secs: u64,
+
+    /// Per-path access rules, checked after `blocked_paths`
+    #[serde(default)]
+    pub acl: AclConfig,
+
+    /// Command execution in the workspace (`POST /exec`), disabled by default
+    #[serde(default)]
+    pub exec: ExecConfig,
+
+    /// Keys allowed to change server state via `/admin/`
+    #[serde(default)]
+    pub admin: AdminConfig,
+
+    /// `X-API-Key` of the current caller, set per request by `WorkspaceConfig`
+    #[serde(skip)]
+    pub api_key: Option<String>,
+}
+
+/// Access level granted by an ACL rule; levels are ordered `deny < read < write`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Access {
+    Deny,
+    Read,
+    Write,
+}
+
+/// `[acl]` section: rules are evaluated in order, the first matching one wins
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AclConfig {
+    /// Access when no rule matches
+    #[serde(default = "default_acl_access")]
+    pub default: Access,
+
+    #[serde(default)]
+    pub rules: Vec<AclRule>,
+}
+
+/// `[[acl.rules]]` entry
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct AclRule {
+    /// Glob relative to the workspace root (`src/**`, `**/.env`)
+    pub pattern: String,
+
+    pub access: Access,
+
+    /// Keys the rule applies to; empty = every caller
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+}
+
+impl std::fmt::Debug for AclRule {
+    // Keys end up in the startup log otherwise
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AclRule")
+            .field("pattern", &self.pattern)
+            .field("access", &self.access)
+            .field("api_keys", &format_args!("[{} hidden]", self.api_keys.len()))
+            .finish()
+    }
+}
+
+impl Default for AclConfig {
+    fn default() -> Self {
+        Self {
+            default: default_acl_access(),
+            rules: vec![],
+        }
+    }
+}
+
+fn default_acl_access() -> Access {
+    Access::Write
+}
+
+/// `[exec]` section
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Programs callers may run, matched exactly (`git`, `./gradlew`).
+    /// Arguments are passed without a shell
+    #[serde(default)]
+    pub allowed_commands: Vec<String>,
+
+    /// Keys allowed to execute commands; empty = every caller
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+
+    /// Upper bound for a command's run time
+    #[serde(default = "default_exec_timeout")]
+    pub timeout_secs: u64,
+
+    /// stdout and stderr are each cut to this many bytes
+    #[serde(default = "default_exec_max_output")]
+    pub max_output_bytes: usize,
+}
+
+impl std::fmt::Debug for ExecConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExecConfig")
+            .field("enabled", &self.enabled)
+            .field("allowed_commands", &self.allowed_commands)
+            .field("api_keys", &format_args!("[{} hidden]", self.api_keys.len()))
+            .field("timeout_secs", &self.timeout_secs)
+            .field("max_output_bytes", &self.max_output_bytes)
+            .finish()
+    }
+}
+
+impl Default for ExecConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allowed_commands: vec![],
+            api_keys: vec![],
+            timeout_secs: default_exec_timeout(),
+            max_output_bytes: default_exec_max_output(),
+        }
+    }
+}
+
+fn default_exec_timeout() -> u64 {
+    1800
+}
+
+fn default_exec_max_output() -> usize {
+    1024 * 1024
+}
+
+/// `[admin]` section
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdminConfig {
+    /// `X-API-Key` values allowed to switch read-only mode; empty = admin writes disabled
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+}
+
+impl std::fmt::Debug for AdminConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminConfig")
+            .field("api_keys", &format_args!("[{} hidden]", self.api_keys.len()))
+            .finish()
+    }
+}
+
+/// `[telemetry]` section; `OTEL_EXPORTER_OTLP_ENDPOINT` / `OTEL_SERVICE_NAME` override it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// OTLP/HTTP collector base URL, e.g. `http://tempo:4318`
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    #[serde(default)]
+    pub service_name: Option<String>,
+}
+
+fn default_templates_dir() -> PathBuf {
+    PathBuf::from("./templates")
+}
+
+fn default_session_ttl() -> u64 {
+    3600
+}
+
+fn default_session_max_ttl() -> u64 {
+    24 * 3600
+}
+
+fn default_session_gc_interval() -> u64 {
+    60
+}
+
+fn default_sessions_dir() -> PathBuf {
+    std::env::temp_dir().join("mcp-sessions")
+}
+
+fn default_idempotency_ttl() -> u64 {
+    24 * 3600
+}
+
+fn default_usage_cache_ttl() -> u64 {
+    60
 }
 
 impl Default for Config {
@@ -33,6 +257,19 @@ impl Default for Config {
                 String::from("C:\\System32"),
             ],
             verbose: false,
+            templates_dir: default_templates_dir(),
+            session_default_ttl_secs: default_session_ttl(),
+            session_max_ttl_secs: default_session_max_ttl(),
+            session_gc_interval_secs: default_session_gc_interval(),
+            sessions_dir: default_sessions_dir(),
+            read_only: false,
+            idempotency_ttl_secs: default_idempotency_ttl(),
+            usage_cache_ttl_secs: default_usage_cache_ttl(),
+            telemetry: TelemetryConfig::default(),
+            acl: AclConfig::default(),
+            exec: ExecConfig::default(),
+            admin: AdminConfig::default(),
+            api_key: None,
         }
     }
 }
@@ -51,15 +288,31 @@ impl Config {
             Self::default()
         };
 
+        crate::security::validate_acl(&config.acl).map_err(anyhow::Error::msg)?;
+
         // Override base_dir with environment variable if set
         if let Ok(base_dir_env) = std::env::var("MCP_BASE_DIR") {
             config.base_dir = PathBuf::from(base_dir_env);
             tracing::info!("Base directory overridden by MCP_BASE_DIR: {:?}", config.base_dir);
         }
+        config.check_sessions_dir()?;
 
         Ok(config)
     }
 
+    /// Reject a `sessions_dir` inside `base_dir`, where the main workspace could reach it
+    pub fn check_sessions_dir(&self) -> anyhow::Result<()> {
+        let cwd = std::env::current_dir()?;
+        if cwd.join(&self.sessions_dir).starts_with(cwd.join(&self.base_dir)) {
+            anyhow::bail!(
+                "sessions_dir {:?} must be outside base_dir {:?}",
+                self.sessions_dir,
+                self.base_dir
+            );
+        }
+        Ok(())
+    }
+
     /// Validate if path is allowed
     pub fn is_path_allowed(&self, path: &std::path::Path) -> bool {
         let path_str = path.to_string_lossy();
@@ -118,6 +371,19 @@ mod tests {
         assert!(!config.is_path_allowed(&blocked_path));
     }
 
+    #[test]
+    fn test_sessions_dir_outside_base_dir() {
+        let mut config = Config {
+            base_dir: PathBuf::from("./data"),
+            sessions_dir: PathBuf::from("./sessions"),
+            ..Config::default()
+        };
+        assert!(config.check_sessions_dir().is_ok());
+
+        config.sessions_dir = PathBuf::from("./data/.sessions");
+        assert!(config.check_sessions_dir().is_err());
+    }
+
     #[test]
     fn test_allowed_extensions() {
         let mut config = Config::default();
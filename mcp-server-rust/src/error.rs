@@ -19,6 +19,7 @@ pub enum AppError {
     NotFound(String),
     InvalidInput(String),
     PermissionDenied(String),
+    Conflict(String),
     FileTooLarge(usize, usize), // actual, max
     IoError(std::io::Error),
     ValidationError(String),
@@ -31,6 +32,7 @@ impl fmt::Display for AppError {
             AppError::NotFound(msg) => write!(f, "Not found: {}", msg),
             AppError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
             AppError::PermissionDenied(msg) => write!(f, "Permission denied: {}", msg),
+            AppError::Conflict(msg) => write!(f, "Conflict: {}", msg),
             AppError::FileTooLarge(actual, max) => {
                 write!(f, "File too large: {} bytes (max: {} bytes)", actual, max)
             }
@@ -80,6 +82,12 @@ impl IntoResponse for AppError {
                 "Permission denied",
                 Some(msg),
             ),
+            AppError::Conflict(msg) => (
+                StatusCode::CONFLICT,
+                "CONFLICT",
+                "Request conflicts with the current state",
+                Some(msg),
+            ),
             AppError::FileTooLarge(actual, max) => (
                 StatusCode::PAYLOAD_TOO_LARGE,
                 "FILE_TOO_LARGE",
@@ -0,0 +1,97 @@
+use axum::{extract::State, Json};
+
+use crate::{
+    error::{AppError, Result},
+    models::*,
+    security,
+    state::{ReadOnlyMode, WorkspaceConfig},
+};
+
+pub async fn get_read_only(State(mode): State<ReadOnlyMode>) -> Json<ReadOnlyResponse> {
+    Json(to_response(&mode))
+}
+
+pub async fn set_read_only(
+    State(mode): State<ReadOnlyMode>,
+    WorkspaceConfig(config): WorkspaceConfig,
+    Json(request): Json<ReadOnlyRequest>,
+) -> Result<Json<ReadOnlyResponse>> {
+    security::check_admin(&config).map_err(AppError::PermissionDenied)?;
+    if request.enabled {
+        mode.enable(request.reason);
+    } else {
+        mode.disable();
+    }
+
+    Ok(Json(to_response(&mode)))
+}
+
+fn to_response(mode: &ReadOnlyMode) -> ReadOnlyResponse {
+    let reason = mode.reason();
+    ReadOnlyResponse {
+        read_only: reason.is_some(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AdminConfig, Config};
+
+    #[tokio::test]
+    async fn test_toggle_read_only() {
+        let mode = ReadOnlyMode::default();
+        assert!(!get_read_only(State(mode.clone())).await.read_only);
+
+        let admin = Config {
+            admin: AdminConfig {
+                api_keys: vec!["ops".to_string()],
+            },
+            api_key: Some("ops".to_string()),
+            ..Config::default()
+        };
+        let request = |enabled| {
+            Json(ReadOnlyRequest {
+                enabled,
+                reason: None,
+            })
+        };
+
+        // Without an admin key the mode cannot be switched
+        for api_key in [None, Some("agent".to_string())] {
+            let caller = WorkspaceConfig(Config {
+                api_key,
+                ..admin.clone()
+            });
+            let result = set_read_only(State(mode.clone()), caller, request(true)).await;
+            assert!(matches!(result, Err(AppError::PermissionDenied(_))));
+        }
+        let disabled = WorkspaceConfig(Config {
+            admin: AdminConfig::default(),
+            ..admin.clone()
+        });
+        let result = set_read_only(State(mode.clone()), disabled, request(true)).await;
+        assert!(matches!(result, Err(AppError::PermissionDenied(_))));
+        assert!(mode.reason().is_none());
+
+        let response = set_read_only(
+            State(mode.clone()),
+            WorkspaceConfig(admin.clone()),
+            Json(ReadOnlyRequest {
+                enabled: true,
+                reason: Some("incident #42".to_string()),
+            }),
+        )
+        .await
+        .unwrap();
+        assert!(response.read_only);
+        assert_eq!(mode.reason().as_deref(), Some("incident #42"));
+
+        let response = set_read_only(State(mode.clone()), WorkspaceConfig(admin), request(false))
+            .await
+            .unwrap();
+        assert!(!response.read_only);
+        assert!(mode.reason().is_none());
+    }
+}
@@ -1,25 +1,30 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Path, Query},
     http::StatusCode,
     Json,
 };
 use serde::Deserialize;
+use serde_json::Value;
 use validator::Validate;
 
 use crate::{
-    config::Config,
     error::{AppError, Result},
     models::*,
-    services::FileService,
+    projection::Projection,
+    services::{FileService, ScaffoldService},
+    state::WorkspaceConfig,
 };
 
 #[derive(Debug, Deserialize)]
 pub struct ListQuery {
     pub path: Option<String>,
+    /// Also return entries hidden by .gitignore/.mcpignore
+    #[serde(default)]
+    pub include_ignored: bool,
 }
 
 pub async fn create_directory(
-    State(config): State<Config>,
+    WorkspaceConfig(config): WorkspaceConfig,
     Json(request): Json<CreateDirectoryRequest>,
 ) -> Result<(StatusCode, Json<DirectoryResponse>)> {
     request.validate().map_err(AppError::from)?;
@@ -29,24 +34,37 @@ pub async fn create_directory(
 }
 
 pub async fn delete_directory(
-    State(config): State<Config>,
+    WorkspaceConfig(config): WorkspaceConfig,
     Path(path): Path<String>,
 ) -> Result<Json<DeleteResponse>> {
     let response = FileService::delete_directory(&config, &path).await?;
     Ok(Json(response))
 }
 
+pub async fn copy_directory(
+    WorkspaceConfig(config): WorkspaceConfig,
+    Json(request): Json<CopyDirectoryRequest>,
+) -> Result<(StatusCode, Json<CopyDirectoryResponse>)> {
+    request.validate().map_err(AppError::from)?;
+
+    let response = ScaffoldService::copy_directory(&config, request).await?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
 pub async fn list_directories(
-    State(config): State<Config>,
+    WorkspaceConfig(config): WorkspaceConfig,
     Query(query): Query<ListQuery>,
-) -> Result<Json<DirectoryListResponse>> {
-    let response = FileService::list_files(&config, query.path.as_deref()).await?;
-    Ok(Json(response))
+    Query(projection): Query<Projection>,
+) -> Result<Json<Value>> {
+    let response =
+        FileService::list_files(&config, query.path.as_deref(), query.include_ignored).await?;
+    Ok(Json(projection.apply(&response)?))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::Config;
     use tempfile::TempDir;
 
     fn create_test_config() -> (Config, TempDir) {
@@ -66,7 +84,7 @@ mod tests {
             recursive: false,
         };
         
-        let result = create_directory(State(config), Json(request)).await;
+        let result = create_directory(WorkspaceConfig(config), Json(request)).await;
         assert!(result.is_ok());
     }
 }
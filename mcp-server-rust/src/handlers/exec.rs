@@ -0,0 +1,19 @@
+use axum::Json;
+use validator::Validate;
+
+use crate::{
+    error::{AppError, Result},
+    models::*,
+    services::ExecService,
+    state::WorkspaceConfig,
+};
+
+pub async fn exec(
+    WorkspaceConfig(config): WorkspaceConfig,
+    Json(request): Json<ExecRequest>,
+) -> Result<Json<ExecResponse>> {
+    request.validate().map_err(AppError::from)?;
+
+    let response = ExecService::run(&config, request).await?;
+    Ok(Json(response))
+}
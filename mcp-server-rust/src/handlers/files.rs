@@ -1,25 +1,36 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Path, Query},
     http::StatusCode,
     Json,
 };
 use serde::Deserialize;
+use serde_json::Value;
 use validator::Validate;
 
 use crate::{
-    config::Config,
     error::{AppError, Result},
     models::*,
-    services::FileService,
+    projection::Projection,
+    services::{EditService, FileService, OutlineService, ScaffoldService},
+    state::WorkspaceConfig,
 };
 
 #[derive(Debug, Deserialize)]
 pub struct ListQuery {
     pub dir: Option<String>,
+    /// Also return entries hidden by .gitignore/.mcpignore
+    #[serde(default)]
+    pub include_ignored: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReadQuery {
+    #[serde(default)]
+    pub encoding: ContentEncoding,
 }
 
 pub async fn create_file(
-    State(config): State<Config>,
+    WorkspaceConfig(config): WorkspaceConfig,
     Json(request): Json<CreateFileRequest>,
 ) -> Result<(StatusCode, Json<FileResponse>)> {
     request.validate().map_err(AppError::from)?;
@@ -28,16 +39,27 @@ pub async fn create_file(
     Ok((StatusCode::CREATED, Json(response)))
 }
 
+pub async fn create_file_from_template(
+    WorkspaceConfig(config): WorkspaceConfig,
+    Json(request): Json<CreateFromTemplateRequest>,
+) -> Result<(StatusCode, Json<FileResponse>)> {
+    request.validate().map_err(AppError::from)?;
+
+    let response = ScaffoldService::create_from_template(&config, request).await?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
 pub async fn read_file(
-    State(config): State<Config>,
+    WorkspaceConfig(config): WorkspaceConfig,
     Path(path): Path<String>,
+    Query(query): Query<ReadQuery>,
 ) -> Result<Json<FileContentResponse>> {
-    let response = FileService::read_file(&config, &path).await?;
+    let response = FileService::read_file_as(&config, &path, query.encoding).await?;
     Ok(Json(response))
 }
 
 pub async fn update_file(
-    State(config): State<Config>,
+    WorkspaceConfig(config): WorkspaceConfig,
     Path(path): Path<String>,
     Json(request): Json<UpdateFileRequest>,
 ) -> Result<Json<FileResponse>> {
@@ -45,8 +67,27 @@ pub async fn update_file(
     Ok(Json(response))
 }
 
+pub async fn file_outline(
+    WorkspaceConfig(config): WorkspaceConfig,
+    Path(path): Path<String>,
+) -> Result<Json<OutlineResponse>> {
+    let response = OutlineService::outline(&config, &path).await?;
+    Ok(Json(response))
+}
+
+pub async fn edit_file(
+    WorkspaceConfig(config): WorkspaceConfig,
+    Path(path): Path<String>,
+    Json(request): Json<EditFileRequest>,
+) -> Result<Json<EditFileResponse>> {
+    request.validate().map_err(AppError::from)?;
+
+    let response = EditService::edit_file(&config, &path, request).await?;
+    Ok(Json(response))
+}
+
 pub async fn delete_file(
-    State(config): State<Config>,
+    WorkspaceConfig(config): WorkspaceConfig,
     Path(path): Path<String>,
 ) -> Result<Json<DeleteResponse>> {
     let response = FileService::delete_file(&config, &path).await?;
@@ -54,17 +95,19 @@ pub async fn delete_file(
 }
 
 pub async fn list_files(
-    State(config): State<Config>,
+    WorkspaceConfig(config): WorkspaceConfig,
     Query(query): Query<ListQuery>,
-) -> Result<Json<DirectoryListResponse>> {
-    let response = FileService::list_files(&config, query.dir.as_deref()).await?;
-    Ok(Json(response))
+    Query(projection): Query<Projection>,
+) -> Result<Json<Value>> {
+    let response =
+        FileService::list_files(&config, query.dir.as_deref(), query.include_ignored).await?;
+    Ok(Json(projection.apply(&response)?))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use axum::extract::State;
+    use crate::config::Config;
     use tempfile::TempDir;
 
     fn create_test_config() -> (Config, TempDir) {
@@ -85,7 +128,7 @@ mod tests {
             overwrite: false,
         };
         
-        let result = create_file(State(config), Json(request)).await;
+        let result = create_file(WorkspaceConfig(config), Json(request)).await;
         assert!(result.is_ok());
     }
 }
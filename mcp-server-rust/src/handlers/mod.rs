@@ -1,3 +1,9 @@
+pub mod admin;
 pub mod directories;
+pub mod exec;
 pub mod files;
 pub mod health;
+pub mod refactor;
+pub mod scaffold;
+pub mod sessions;
+pub mod usage;
@@ -0,0 +1,22 @@
+use axum::{extract::Query, Json};
+use serde_json::Value;
+use validator::Validate;
+
+use crate::{
+    error::{AppError, Result},
+    models::*,
+    projection::Projection,
+    services::RefactorService,
+    state::WorkspaceConfig,
+};
+
+pub async fn replace(
+    WorkspaceConfig(config): WorkspaceConfig,
+    Query(projection): Query<Projection>,
+    Json(request): Json<ReplaceRequest>,
+) -> Result<Json<Value>> {
+    request.validate().map_err(AppError::from)?;
+
+    let response = RefactorService::replace(&config, request).await?;
+    Ok(Json(projection.apply(&response)?))
+}
@@ -0,0 +1,19 @@
+use axum::{http::StatusCode, Json};
+use validator::Validate;
+
+use crate::{
+    error::{AppError, Result},
+    models::*,
+    services::ScaffoldService,
+    state::WorkspaceConfig,
+};
+
+pub async fn scaffold(
+    WorkspaceConfig(config): WorkspaceConfig,
+    Json(request): Json<ScaffoldRequest>,
+) -> Result<(StatusCode, Json<ScaffoldResponse>)> {
+    request.validate().map_err(AppError::from)?;
+
+    let response = ScaffoldService::scaffold(&config, request).await?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
@@ -0,0 +1,31 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    config::Config,
+    error::Result,
+    models::*,
+    services::{SessionService, SessionStore},
+};
+
+pub async fn create_session(
+    State(config): State<Config>,
+    State(store): State<SessionStore>,
+    request: Option<Json<CreateSessionRequest>>,
+) -> Result<(StatusCode, Json<SessionResponse>)> {
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+
+    let response = SessionService::create_session(&config, &store, request).await?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+pub async fn delete_session(
+    State(store): State<SessionStore>,
+    Path(token): Path<String>,
+) -> Result<Json<DeleteResponse>> {
+    let response = SessionService::delete_session(&store, &token).await?;
+    Ok(Json(response))
+}
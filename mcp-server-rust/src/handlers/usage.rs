@@ -0,0 +1,45 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    error::Result,
+    projection::Projection,
+    services::{UsageCache, UsageService},
+    state::WorkspaceConfig,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    pub path: Option<String>,
+    /// Levels of subdirectories to report
+    #[serde(default = "default_depth")]
+    pub depth: usize,
+    /// Measure again instead of serving a cached report
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+fn default_depth() -> usize {
+    1
+}
+
+pub async fn disk_usage(
+    WorkspaceConfig(config): WorkspaceConfig,
+    State(cache): State<UsageCache>,
+    Query(query): Query<UsageQuery>,
+    Query(projection): Query<Projection>,
+) -> Result<Json<Value>> {
+    let response = UsageService::usage(
+        &config,
+        &cache,
+        query.path.as_deref(),
+        query.depth,
+        query.refresh,
+    )
+    .await?;
+    Ok(Json(projection.apply(&response)?))
+}
@@ -2,41 +2,67 @@ mod config;
 mod error;
 mod handlers;
 mod models;
+mod projection;
 mod security;
 mod services;
+mod state;
+mod telemetry;
 
 use axum::{
+    middleware,
     routing::{delete, get, post, put},
     Router,
 };
 use std::net::SocketAddr;
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "mcp_server_rust=debug,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    // Load configuration first: it decides whether traces are exported. The global
+    // subscriber does not exist yet, so loading logs through a scoped stderr one
+    let config = tracing::subscriber::with_default(
+        tracing_subscriber::fmt().with_writer(std::io::stderr).finish(),
+        config::Config::load,
+    )?;
 
-    // Load configuration
-    let config = config::Config::load()?;
+    // Initialize tracing (and OTLP export with the `otel` feature)
+    let _telemetry = telemetry::init(&config.telemetry);
     tracing::info!("Configuration loaded: {:?}", config);
 
-    // Build application router
-    let app = Router::new()
+    let state = state::AppState::new(config);
+    services::SessionService::spawn_gc(
+        state.sessions.clone(),
+        std::time::Duration::from_secs(state.config.session_gc_interval_secs.max(1)),
+    );
+
+    let app = router(state);
+
+    // Start server
+    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
+    tracing::info!("Starting MCP server on {}", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+/// Application router with all routes and middleware
+fn router(state: state::AppState) -> Router {
+    Router::new()
         // Health check
         .route("/health", get(handlers::health::health_check))
         // File operations
         .route("/files", post(handlers::files::create_file))
+        .route(
+            "/files/from-template",
+            post(handlers::files::create_file_from_template),
+        )
         .route("/files/:path", get(handlers::files::read_file))
         .route("/files/:path", put(handlers::files::update_file))
         .route("/files/:path", delete(handlers::files::delete_file))
+        .route("/files/:path/edits", post(handlers::files::edit_file))
+        .route("/files/:path/outline", get(handlers::files::file_outline))
         .route("/files", get(handlers::files::list_files))
         // Directory operations
         .route("/directories", post(handlers::directories::create_directory))
@@ -45,17 +71,100 @@ async fn main() -> anyhow::Result<()> {
             delete(handlers::directories::delete_directory),
         )
         .route("/directories", get(handlers::directories::list_directories))
+        .route(
+            "/directories/copy",
+            post(handlers::directories::copy_directory),
+        )
+        // Disk usage report
+        .route("/usage", get(handlers::usage::disk_usage))
+        // Template scaffolding
+        .route("/scaffold", post(handlers::scaffold::scaffold))
+        // Workspace-wide refactoring
+        .route("/refactor/replace", post(handlers::refactor::replace))
+        // Command execution in the workspace
+        .route("/exec", post(handlers::exec::exec))
+        // Temporary session workspaces
+        .route("/sessions", post(handlers::sessions::create_session))
+        .route("/sessions/:token", delete(handlers::sessions::delete_session))
+        // Administration
+        .route(
+            "/admin/read-only",
+            get(handlers::admin::get_read_only).put(handlers::admin::set_read_only),
+        )
         // Add middleware
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            state::replay_idempotent,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            state::enforce_read_only,
+        ))
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
-        .with_state(config);
+        .with_state(state)
+}
 
-    // Start server
-    let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
-    tracing::info!("Starting MCP server on {}", addr);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{HeaderName, HeaderValue, StatusCode};
+    use serde_json::json;
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    #[tokio::test]
+    async fn test_read_only_blocks_writes_but_not_admin() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let state = state::AppState::new(config::Config {
+            base_dir: temp_dir.path().to_path_buf(),
+            blocked_paths: vec![],
+            admin: config::AdminConfig {
+                api_keys: vec!["ops".to_string()],
+            },
+            ..config::Config::default()
+        });
+        let server = axum_test::TestServer::new(router(state)).unwrap();
+        let api_key = |key: &'static str| {
+            (
+                HeaderName::from_static(state::API_KEY_HEADER),
+                HeaderValue::from_static(key),
+            )
+        };
+        let switch = |enabled: bool| json!({ "enabled": enabled, "reason": "incident #42" });
 
-    Ok(())
+        // Switching the mode requires an admin key
+        let (name, value) = api_key("agent");
+        let denied = server
+            .put("/admin/read-only")
+            .add_header(name, value)
+            .json(&switch(true))
+            .expect_failure()
+            .await;
+        assert_eq!(denied.status_code(), StatusCode::FORBIDDEN);
+
+        let (name, value) = api_key("ops");
+        server.put("/admin/read-only").add_header(name, value).json(&switch(true)).await;
+
+        let rejected = server
+            .post("/directories")
+            .json(&json!({ "path": "docs" }))
+            .expect_failure()
+            .await;
+        assert_eq!(rejected.status_code(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            rejected.json::<serde_json::Value>()["details"],
+            "Server is in read-only mode: incident #42"
+        );
+        assert!(!temp_dir.path().join("docs").exists());
+
+        // `/admin/` stays writable, so the mode can be turned off again
+        let (name, value) = api_key("ops");
+        let response = server
+            .put("/admin/read-only")
+            .add_header(name, value)
+            .json(&switch(false))
+            .await;
+        assert_eq!(response.json::<serde_json::Value>()["read_only"], false);
+        server.post("/directories").json(&json!({ "path": "docs" })).await;
+        assert!(temp_dir.path().join("docs").is_dir());
+    }
 }
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use validator::Validate;
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
@@ -17,6 +18,94 @@ pub struct UpdateFileRequest {
     pub content: String,
 }
 
+/// Line operation of `POST /files/:path/edits`; line numbers are 1-based and refer to the
+/// file as it was before any edit of the request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum LineEdit {
+    /// Insert `content` after `line`; `0` inserts at the top of the file
+    InsertAfterLine { line: usize, content: String },
+    /// Replace lines `start_line..=end_line` with `content`
+    ReplaceRange {
+        start_line: usize,
+        end_line: usize,
+        content: String,
+    },
+    DeleteLines { start_line: usize, end_line: usize },
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct EditFileRequest {
+    #[validate(length(min = 1, max = 1000))]
+    pub edits: Vec<LineEdit>,
+
+    /// SHA256 of the content the edits were computed against; a mismatch is a 409
+    #[serde(default)]
+    pub expected_checksum: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EditFileResponse {
+    pub path: String,
+    pub size: u64,
+    pub line_count: usize,
+    pub edits_applied: usize,
+    pub checksum: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolKind {
+    Module,
+    Class,
+    Struct,
+    Enum,
+    Interface,
+    Trait,
+    Impl,
+    Function,
+    Method,
+    Field,
+    Variant,
+    Constant,
+    TypeAlias,
+}
+
+impl SymbolKind {
+    /// Kinds whose nested functions are methods
+    pub fn is_type(self) -> bool {
+        matches!(
+            self,
+            SymbolKind::Class
+                | SymbolKind::Struct
+                | SymbolKind::Enum
+                | SymbolKind::Interface
+                | SymbolKind::Trait
+                | SymbolKind::Impl
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutlineSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// 1-based, inclusive
+    pub start_line: usize,
+    pub end_line: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<OutlineSymbol>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutlineResponse {
+    pub path: String,
+    pub language: String,
+    /// The parser recovered from syntax errors; the outline may be incomplete
+    pub has_errors: bool,
+    pub symbols: Vec<OutlineSymbol>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FileResponse {
     pub path: String,
@@ -27,10 +116,23 @@ pub struct FileResponse {
     pub checksum: String,
 }
 
+/// Encoding of `content` in `GET /files/:path`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentEncoding {
+    /// Text; invalid UTF-8 sequences are replaced
+    #[default]
+    Utf8,
+    /// Exact bytes, for binary files such as build artifacts
+    Base64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FileContentResponse {
     pub path: String,
     pub content: String,
+    #[serde(default)]
+    pub encoding: ContentEncoding,
     pub size: u64,
     pub mime_type: String,
     pub checksum: String,
@@ -41,6 +143,9 @@ pub struct DirectoryListResponse {
     pub path: String,
     pub files: Vec<FileInfo>,
     pub directories: Vec<DirectoryInfo>,
+    /// Number of entries hidden by .gitignore/.mcpignore rules
+    #[serde(default)]
+    pub ignored: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -74,12 +179,213 @@ pub struct DirectoryResponse {
     pub created_at: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CopyDirectoryRequest {
+    #[validate(length(min = 1, max = 255))]
+    pub source: String,
+
+    #[validate(length(min = 1, max = 255))]
+    pub destination: String,
+
+    /// Glob patterns relative to the source root (e.g. "build/**", "*.log")
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CopyDirectoryResponse {
+    pub source: String,
+    pub destination: String,
+    pub files_copied: usize,
+    pub directories_created: usize,
+    pub excluded: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ScaffoldRequest {
+    /// Template name (subdirectory of `templates_dir`)
+    #[validate(length(min = 1, max = 255))]
+    pub template: String,
+
+    #[validate(length(min = 1, max = 255))]
+    pub destination: String,
+
+    /// Values for `{{name}}` placeholders in paths and file contents
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScaffoldResponse {
+    pub template: String,
+    pub destination: String,
+    pub files: Vec<String>,
+    pub directories_created: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateFromTemplateRequest {
+    /// Template file relative to `templates_dir` (e.g. "kotlin/Class.kt")
+    #[validate(length(min = 1, max = 255))]
+    pub template: String,
+
+    /// File to create in the workspace
+    #[validate(length(min = 1, max = 255))]
+    pub path: String,
+
+    /// Values for every `{{name}}` placeholder of the template
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ReplaceRequest {
+    /// Literal text, or a regular expression when `regex` is set
+    #[validate(length(min = 1, max = 1000))]
+    pub pattern: String,
+
+    /// Replacement text; in regex mode `$1`/`${name}` refer to capture groups
+    pub replacement: String,
+
+    #[serde(default)]
+    pub regex: bool,
+
+    /// Glob patterns relative to the workspace root; all files when empty
+    #[serde(default)]
+    pub include: Vec<String>,
+
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// Write the changes; otherwise only the diffs are returned
+    #[serde(default)]
+    pub apply: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplaceResponse {
+    pub applied: bool,
+    pub files_scanned: usize,
+    pub files_changed: usize,
+    pub replacements: usize,
+    /// Matching files skipped as binary or larger than `max_file_size`
+    pub skipped: Vec<String>,
+    pub files: Vec<FileReplacement>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileReplacement {
+    pub path: String,
+    pub replacements: usize,
+    /// Unified diff of the change
+    pub diff: String,
+    /// Checksum of the new content
+    pub checksum: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsageResponse {
+    pub path: String,
+    pub depth: usize,
+    /// Total size of regular files in bytes
+    pub size: u64,
+    pub files: u64,
+    /// The report was served from cache
+    pub cached: bool,
+    pub computed_at: String,
+    /// Subdirectories down to `depth`, largest first
+    pub directories: Vec<DirectoryUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryUsage {
+    pub path: String,
+    pub size: u64,
+    pub files: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub directories: Vec<DirectoryUsage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ExecRequest {
+    /// Program from `[exec] allowed_commands`
+    #[validate(length(min = 1, max = 255))]
+    pub command: String,
+
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Working directory relative to the workspace root
+    #[serde(default)]
+    pub cwd: Option<String>,
+
+    /// Shorter limit than the server's `[exec] timeout_secs`
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExecResponse {
+    pub command: String,
+    pub args: Vec<String>,
+    pub cwd: String,
+    /// None when the command was killed by the timeout or a signal
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+    /// Output was cut to `[exec] max_output_bytes`
+    pub truncated: bool,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CreateSessionRequest {
+    /// Session lifetime; server default is used when omitted
+    #[serde(default)]
+    pub ttl_seconds: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionResponse {
+    pub token: String,
+    pub created_at: String,
+    pub expires_at: String,
+    pub ttl_seconds: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DeleteResponse {
     pub success: bool,
     pub message: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReadOnlyRequest {
+    pub enabled: bool,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReadOnlyResponse {
+    pub read_only: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HealthResponse {
     pub status: String,
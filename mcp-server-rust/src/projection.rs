@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::{AppError, Result};
+
+/// Response trimming requested with `?fields=path,size&compact=true`.
+///
+/// `fields` keeps only the listed keys of list items (objects inside arrays);
+/// nested lists, like subdirectories of a usage tree, are kept and trimmed the
+/// same way. The envelope of the response (counts, the listed path) is never
+/// trimmed. `compact` drops null and empty values and turns a list whose items
+/// are left with a single field into a plain list of values.
+#[derive(Debug, Default, Deserialize)]
+pub struct Projection {
+    pub fields: Option<String>,
+    #[serde(default)]
+    pub compact: bool,
+}
+
+impl Projection {
+    /// Serialize a response and trim it
+    pub fn apply<T: Serialize>(&self, response: &T) -> Result<Value> {
+        let fields = self.field_list()?;
+        let mut value = serde_json::to_value(response)
+            .map_err(|e| AppError::InternalError(format!("Failed to serialize response: {}", e)))?;
+        Self::visit(&mut value, fields.as_deref(), self.compact);
+        Ok(value)
+    }
+
+    fn field_list(&self) -> Result<Option<Vec<String>>> {
+        let Some(fields) = &self.fields else {
+            return Ok(None);
+        };
+        let fields: Vec<String> = fields
+            .split(',')
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .map(str::to_string)
+            .collect();
+        if fields.is_empty() {
+            return Err(AppError::InvalidInput(
+                "fields must list at least one field name".to_string(),
+            ));
+        }
+        Ok(Some(fields))
+    }
+
+    fn visit(value: &mut Value, fields: Option<&[String]>, compact: bool) {
+        match value {
+            Value::Array(items) => {
+                for item in items.iter_mut() {
+                    if let (Value::Object(map), Some(fields)) = (&mut *item, fields) {
+                        map.retain(|key, v| fields.contains(key) || is_item_list(v));
+                    }
+                    Self::visit(item, fields, compact);
+                }
+                if compact {
+                    collapse_single_field(items);
+                }
+            }
+            Value::Object(map) => {
+                for v in map.values_mut() {
+                    Self::visit(v, fields, compact);
+                }
+                if compact {
+                    map.retain(|_, v| !is_empty(v));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_item_list(value: &Value) -> bool {
+    matches!(value, Value::Array(items) if items.first().is_some_and(Value::is_object))
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.is_empty(),
+        Value::Array(a) => a.is_empty(),
+        Value::Object(o) => o.is_empty(),
+        _ => false,
+    }
+}
+
+/// `[{"path": "a"}, {"path": "b"}]` becomes `["a", "b"]` when every item has the same single key
+fn collapse_single_field(items: &mut [Value]) {
+    let single_key = |item: &Value| match item {
+        Value::Object(map) if map.len() == 1 => map.keys().next().cloned(),
+        _ => None,
+    };
+    let Some(key) = items.first().and_then(single_key) else {
+        return;
+    };
+    if !items.iter().all(|item| single_key(item).as_ref() == Some(&key)) {
+        return;
+    }
+    for item in items.iter_mut() {
+        if let Value::Object(map) = item {
+            *item = std::mem::replace(map, Map::new())
+                .remove(&key)
+                .unwrap_or(Value::Null);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn projection(fields: Option<&str>, compact: bool) -> Projection {
+        Projection {
+            fields: fields.map(str::to_string),
+            compact,
+        }
+    }
+
+    #[test]
+    fn test_projection() {
+        let listing = json!({
+            "path": ".",
+            "ignored": 0,
+            "files": [
+                { "name": "a.txt", "path": "/data/a.txt", "size": 1, "is_readonly": false },
+                { "name": "b.txt", "path": "/data/b.txt", "size": 2, "is_readonly": false }
+            ],
+            "directories": []
+        });
+        assert_eq!(
+            projection(Some("name, size"), false).apply(&listing).unwrap(),
+            json!({
+                "path": ".",
+                "ignored": 0,
+                "files": [{ "name": "a.txt", "size": 1 }, { "name": "b.txt", "size": 2 }],
+                "directories": []
+            })
+        );
+        assert_eq!(
+            projection(Some("name"), true).apply(&listing).unwrap(),
+            json!({ "path": ".", "ignored": 0, "files": ["a.txt", "b.txt"] })
+        );
+
+        // Nested lists survive the projection
+        let tree = json!({
+            "directories": [
+                { "path": "build", "size": 5, "files": 2, "directories": [{ "path": "build/out", "size": 5, "files": 2 }] }
+            ]
+        });
+        assert_eq!(
+            projection(Some("path"), false).apply(&tree).unwrap(),
+            json!({ "directories": [{ "path": "build", "directories": [{ "path": "build/out" }] }] })
+        );
+
+        assert_eq!(projection(None, false).apply(&listing).unwrap(), listing);
+        assert!(projection(Some(" , "), false).apply(&listing).is_err());
+    }
+}
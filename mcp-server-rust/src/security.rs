@@ -1,6 +1,9 @@
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 
+use crate::config::{Access, AclConfig, Config};
+
 /// Calculate SHA256 checksum of file content
 pub fn calculate_checksum(content: &[u8]) -> String {
     let mut hasher = Sha256::new();
@@ -67,6 +70,96 @@ pub fn is_safe_path(base: &Path, target: &Path) -> bool {
     }
 }
 
+impl std::fmt::Display for Access {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Access::Deny => write!(f, "deny"),
+            Access::Read => write!(f, "read"),
+            Access::Write => write!(f, "write"),
+        }
+    }
+}
+
+/// Compile an ACL pattern: `*` stays within one path segment, `dir/**` also covers `dir` itself
+fn acl_matcher(pattern: &str) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    let mut add = |glob: &str| {
+        GlobBuilder::new(glob)
+            .literal_separator(true)
+            .build()
+            .map(|g| {
+                builder.add(g);
+            })
+            .map_err(|e| format!("Invalid ACL pattern '{}': {}", pattern, e))
+    };
+    add(pattern)?;
+    if let Some(dir) = pattern.strip_suffix("/**") {
+        add(dir)?;
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Invalid ACL pattern '{}': {}", pattern, e))
+}
+
+/// Check all ACL patterns at startup
+pub fn validate_acl(acl: &AclConfig) -> Result<(), String> {
+    for rule in &acl.rules {
+        acl_matcher(&rule.pattern)?;
+    }
+    Ok(())
+}
+
+/// Access granted to `api_key` for a workspace-relative path: the first rule that
+/// applies to the key and matches the path wins, otherwise `acl.default`
+pub fn acl_access(acl: &AclConfig, api_key: Option<&str>, relative: &Path) -> Access {
+    for rule in &acl.rules {
+        let applies = rule.api_keys.is_empty()
+            || api_key.is_some_and(|key| rule.api_keys.iter().any(|k| k == key));
+        if !applies {
+            continue;
+        }
+        match acl_matcher(&rule.pattern) {
+            Ok(matcher) if matcher.is_match(relative) => return rule.access,
+            Ok(_) => {}
+            Err(err) => tracing::warn!("Skipping ACL rule: {}", err),
+        }
+    }
+    acl.default
+}
+
+/// Check that the caller's `X-API-Key` is listed in `[admin] api_keys`
+pub fn check_admin(config: &Config) -> Result<(), String> {
+    let keys = &config.admin.api_keys;
+    if keys.is_empty() {
+        return Err("Admin API is disabled: configure [admin] api_keys".to_string());
+    }
+    if !config.api_key.as_ref().is_some_and(|key| keys.contains(key)) {
+        return Err("API key is not allowed to use the admin API".to_string());
+    }
+    Ok(())
+}
+
+/// Check the caller's ACL access to a resolved path.
+///
+/// Only paths inside the workspace are subject to the ACL; anything outside is
+/// governed by `blocked_paths` and the base directory restriction.
+pub fn check_access(config: &Config, full_path: &Path, required: Access) -> Result<(), String> {
+    let Ok(relative) = full_path.strip_prefix(&config.base_dir) else {
+        return Ok(());
+    };
+
+    let granted = acl_access(&config.acl, config.api_key.as_deref(), relative);
+    if granted < required {
+        return Err(format!(
+            "ACL grants '{}' access to '{}', '{}' required",
+            granted,
+            relative.display(),
+            required
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,6 +203,47 @@ mod tests {
         assert!(validate_filename("file|name.txt").is_err());
     }
 
+    #[test]
+    fn test_acl_rules_per_key() {
+        let acl: AclConfig = toml::from_str(
+            r#"
+default = "read"
+
+[[rules]]
+pattern = "**/.env"
+access = "deny"
+
+[[rules]]
+pattern = "src/**"
+access = "write"
+api_keys = ["agent"]
+
+[[rules]]
+pattern = "build.gradle.kts"
+access = "read"
+"#,
+        )
+        .unwrap();
+        let access = |key: Option<&str>, path: &str| acl_access(&acl, key, Path::new(path));
+
+        assert_eq!(access(Some("agent"), "src/main/App.kt"), Access::Write);
+        assert_eq!(access(Some("agent"), "src"), Access::Write);
+        assert_eq!(access(None, "src/main/App.kt"), Access::Read);
+        assert_eq!(access(Some("agent"), "build.gradle.kts"), Access::Read);
+        assert_eq!(access(Some("agent"), ".env"), Access::Deny);
+        assert_eq!(access(Some("agent"), "src/.env"), Access::Deny);
+
+        let config = Config {
+            base_dir: PathBuf::from("/workspace"),
+            acl,
+            api_key: Some("agent".to_string()),
+            ..Config::default()
+        };
+        assert!(check_access(&config, Path::new("/workspace/src/lib.rs"), Access::Write).is_ok());
+        assert!(check_access(&config, Path::new("/workspace/build.gradle.kts"), Access::Write).is_err());
+        assert!(check_access(&config, Path::new("/workspace/.env"), Access::Read).is_err());
+    }
+
     #[test]
     fn test_validate_filename_reserved() {
         assert!(validate_filename("CON").is_err());
@@ -0,0 +1,256 @@
+use crate::{
+    config::{Access, Config},
+    error::{AppError, Result},
+    models::*,
+    security,
+    services::FileService,
+};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+pub struct EditService;
+
+/// Edit normalized to a span of original lines: `lines[start..end]` is replaced
+struct Span<'a> {
+    start: usize,
+    end: usize,
+    replacement: Vec<&'a str>,
+    index: usize,
+}
+
+impl EditService {
+    /// Apply line edits to a file, optionally guarded by the checksum the client last saw
+    pub async fn edit_file(
+        config: &Config,
+        path: &str,
+        request: EditFileRequest,
+    ) -> Result<EditFileResponse> {
+        let sanitized_path = security::sanitize_path(path)
+            .map_err(AppError::InvalidInput)?;
+
+        let full_path = FileService::resolve_path(config, &sanitized_path);
+
+        if !config.is_path_allowed(&full_path) {
+            return Err(AppError::PermissionDenied(format!(
+                "Access to path '{}' is not allowed",
+                path
+            )));
+        }
+        security::check_access(config, &full_path, Access::Write)
+            .map_err(AppError::PermissionDenied)?;
+
+        if !full_path.exists() {
+            return Err(AppError::NotFound(format!("File '{}' not found", path)));
+        }
+
+        let original = fs::read(&full_path).await?;
+        if let Some(expected) = &request.expected_checksum {
+            let actual = security::calculate_checksum(&original);
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(AppError::Conflict(format!(
+                    "File '{}' has changed: checksum is {}, expected {}",
+                    path, actual, expected
+                )));
+            }
+        }
+        let text = String::from_utf8(original)
+            .map_err(|_| AppError::InvalidInput(format!("File '{}' is not valid UTF-8", path)))?;
+
+        let edited = Self::apply_edits(&text, &request.edits).map_err(AppError::InvalidInput)?;
+        if edited.len() > config.max_file_size {
+            return Err(AppError::FileTooLarge(edited.len(), config.max_file_size));
+        }
+
+        let mut file = fs::File::create(&full_path).await?;
+        file.write_all(edited.as_bytes()).await?;
+        file.sync_all().await?;
+
+        Ok(EditFileResponse {
+            path: path.to_string(),
+            size: edited.len() as u64,
+            line_count: edited.lines().count(),
+            edits_applied: request.edits.len(),
+            checksum: security::calculate_checksum(edited.as_bytes()),
+        })
+    }
+
+    /// Apply edits addressed by 1-based line numbers of the original text.
+    ///
+    /// Edits must not overlap; inserts after the same line keep their request order.
+    /// Line endings (`\n` or `\r\n`) and the trailing newline are preserved.
+    pub fn apply_edits(text: &str, edits: &[LineEdit]) -> std::result::Result<String, String> {
+        let eol = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        let lines: Vec<&str> = text.lines().collect();
+        let count = lines.len();
+
+        let range = |index: usize, start: usize, end: usize| {
+            if start == 0 || end < start || end > count {
+                Err(format!(
+                    "edit {}: invalid line range {}-{} (file has {} lines)",
+                    index + 1,
+                    start,
+                    end,
+                    count
+                ))
+            } else {
+                Ok((start - 1, end))
+            }
+        };
+
+        let mut spans = Vec::with_capacity(edits.len());
+        for (index, edit) in edits.iter().enumerate() {
+            let (start, end, replacement) = match edit {
+                LineEdit::InsertAfterLine { line, content } => {
+                    if *line > count {
+                        return Err(format!(
+                            "edit {}: line {} is past the end of the file ({} lines)",
+                            index + 1,
+                            line,
+                            count
+                        ));
+                    }
+                    (*line, *line, content.lines().collect())
+                }
+                LineEdit::ReplaceRange {
+                    start_line,
+                    end_line,
+                    content,
+                } => {
+                    let (start, end) = range(index, *start_line, *end_line)?;
+                    (start, end, content.lines().collect())
+                }
+                LineEdit::DeleteLines {
+                    start_line,
+                    end_line,
+                } => {
+                    let (start, end) = range(index, *start_line, *end_line)?;
+                    (start, end, vec![])
+                }
+            };
+            spans.push(Span {
+                start,
+                end,
+                replacement,
+                index,
+            });
+        }
+
+        spans.sort_by_key(|span| (span.start, span.end));
+        for pair in spans.windows(2) {
+            if pair[1].start < pair[0].end {
+                let (a, b) = (pair[0].index.min(pair[1].index), pair[0].index.max(pair[1].index));
+                return Err(format!("edits {} and {} overlap", a + 1, b + 1));
+            }
+        }
+
+        let mut output: Vec<&str> = Vec::with_capacity(count);
+        let mut cursor = 0;
+        for span in &spans {
+            output.extend_from_slice(&lines[cursor..span.start]);
+            output.extend_from_slice(&span.replacement);
+            cursor = cursor.max(span.end);
+        }
+        output.extend_from_slice(&lines[cursor..]);
+
+        if output.is_empty() {
+            return Ok(String::new());
+        }
+        let mut result = output.join(eol);
+        if text.is_empty() || text.ends_with('\n') {
+            result.push_str(eol);
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn insert(line: usize, content: &str) -> LineEdit {
+        LineEdit::InsertAfterLine {
+            line,
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_edits_uses_original_line_numbers() {
+        let text = "one\ntwo\nthree\nfour\n";
+        let edits = vec![
+            LineEdit::DeleteLines {
+                start_line: 4,
+                end_line: 4,
+            },
+            insert(0, "zero"),
+            LineEdit::ReplaceRange {
+                start_line: 2,
+                end_line: 3,
+                content: "TWO\nTHREE\nTHREE AND A HALF\n".to_string(),
+            },
+            insert(3, "after three"),
+        ];
+        assert_eq!(
+            EditService::apply_edits(text, &edits).unwrap(),
+            "zero\none\nTWO\nTHREE\nTHREE AND A HALF\nafter three\n"
+        );
+
+        assert_eq!(
+            EditService::apply_edits("a\r\nb", &[insert(1, "x")]).unwrap(),
+            "a\r\nx\r\nb"
+        );
+        assert_eq!(EditService::apply_edits("", &[insert(0, "x")]).unwrap(), "x\n");
+
+        let overlapping = vec![
+            LineEdit::DeleteLines {
+                start_line: 1,
+                end_line: 3,
+            },
+            insert(2, "x"),
+        ];
+        assert_eq!(
+            EditService::apply_edits(text, &overlapping).unwrap_err(),
+            "edits 1 and 2 overlap"
+        );
+        assert!(EditService::apply_edits(text, &[insert(5, "x")]).is_err());
+        assert!(EditService::apply_edits(
+            text,
+            &[LineEdit::DeleteLines {
+                start_line: 0,
+                end_line: 1
+            }]
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_edit_file_checks_checksum() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            base_dir: temp_dir.path().to_path_buf(),
+            blocked_paths: vec![],
+            ..Config::default()
+        };
+        std::fs::write(temp_dir.path().join("notes.txt"), "a\nb\n").unwrap();
+
+        let stale = EditFileRequest {
+            expected_checksum: Some(security::calculate_checksum(b"old")),
+            edits: vec![insert(1, "x")],
+        };
+        let result = EditService::edit_file(&config, "notes.txt", stale).await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+
+        let request = EditFileRequest {
+            expected_checksum: Some(security::calculate_checksum(b"a\nb\n")),
+            edits: vec![insert(1, "x")],
+        };
+        let response = EditService::edit_file(&config, "notes.txt", request).await.unwrap();
+        assert_eq!(response.line_count, 3);
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("notes.txt")).unwrap(),
+            "a\nx\nb\n"
+        );
+        assert_eq!(response.checksum, security::calculate_checksum(b"a\nx\nb\n"));
+    }
+}
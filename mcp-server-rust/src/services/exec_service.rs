@@ -0,0 +1,258 @@
+use crate::{
+    config::{Access, Config},
+    error::{AppError, Result},
+    models::*,
+    security,
+    services::{refactor_service::AUDIT_TARGET, FileService},
+};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+
+pub struct ExecService;
+
+impl ExecService {
+    /// Run an allowed program in the workspace without a shell.
+    ///
+    /// A command that outlives its timeout is killed; the response then has
+    /// `timed_out` set, no exit code and the output captured until then.
+    pub async fn run(config: &Config, request: ExecRequest) -> Result<ExecResponse> {
+        let exec = &config.exec;
+        if !exec.enabled {
+            return Err(AppError::PermissionDenied(
+                "Command execution is disabled".to_string(),
+            ));
+        }
+        if !exec.api_keys.is_empty()
+            && !config
+                .api_key
+                .as_ref()
+                .is_some_and(|key| exec.api_keys.contains(key))
+        {
+            return Err(AppError::PermissionDenied(
+                "API key is not allowed to execute commands".to_string(),
+            ));
+        }
+        if !exec.allowed_commands.contains(&request.command) {
+            return Err(AppError::PermissionDenied(format!(
+                "Command '{}' is not allowed",
+                request.command
+            )));
+        }
+
+        let cwd = match request.cwd.as_deref() {
+            Some(cwd) if !cwd.is_empty() && cwd != "." => {
+                let sanitized = security::sanitize_path(cwd).map_err(AppError::InvalidInput)?;
+                FileService::resolve_path(config, &sanitized)
+            }
+            _ => config.base_dir.clone(),
+        };
+        if !config.is_path_allowed(&cwd) {
+            return Err(AppError::PermissionDenied(
+                "Access to directory is not allowed".to_string(),
+            ));
+        }
+        // Commands change the tree they run in
+        security::check_access(config, &cwd, Access::Write).map_err(AppError::PermissionDenied)?;
+        if !cwd.is_dir() {
+            return Err(AppError::NotFound("Working directory not found".to_string()));
+        }
+
+        let program = Self::program(&request.command, &cwd)?;
+        let limit = request
+            .timeout_secs
+            .map_or(exec.timeout_secs, |secs| secs.min(exec.timeout_secs));
+        let relative_cwd = Self::relative(config, &cwd);
+
+        let started = Instant::now();
+        let mut child = Command::new(&program)
+            .args(&request.args)
+            .current_dir(&cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                AppError::InvalidInput(format!("Failed to start '{}': {}", request.command, e))
+            })?;
+
+        let mut stdout = Capture::default();
+        let mut stderr = Capture::default();
+        let (stdout_pipe, stderr_pipe) = (child.stdout.take(), child.stderr.take());
+        let finished = async {
+            let (status, _, _) = tokio::join!(
+                child.wait(),
+                stdout.read(stdout_pipe, exec.max_output_bytes),
+                stderr.read(stderr_pipe, exec.max_output_bytes),
+            );
+            status
+        };
+        let (exit_code, timed_out) =
+            match tokio::time::timeout(Duration::from_secs(limit), finished).await {
+                Ok(status) => (status?.code(), false),
+                Err(_) => {
+                    let _ = child.start_kill();
+                    (None, true)
+                }
+            };
+        let duration_ms = started.elapsed().as_millis() as u64;
+
+        tracing::info!(
+            target: AUDIT_TARGET,
+            base_dir = ?config.base_dir,
+            cwd = %relative_cwd,
+            command = %request.command,
+            args = ?request.args,
+            exit_code = ?exit_code,
+            timed_out,
+            duration_ms,
+            "Command executed"
+        );
+
+        Ok(ExecResponse {
+            command: request.command,
+            args: request.args,
+            cwd: relative_cwd,
+            exit_code,
+            timed_out,
+            truncated: stdout.truncated || stderr.truncated,
+            stdout: stdout.into_text(),
+            stderr: stderr.into_text(),
+            duration_ms,
+        })
+    }
+
+    /// Bare names are looked up in PATH; paths like `./gradlew` must stay inside the workspace
+    fn program(command: &str, cwd: &std::path::Path) -> Result<PathBuf> {
+        if !command.contains('/') {
+            return Ok(PathBuf::from(command));
+        }
+        let sanitized = security::sanitize_path(command).map_err(AppError::InvalidInput)?;
+        if sanitized.is_absolute() {
+            return Err(AppError::InvalidInput(
+                "Command path must be relative to the working directory".to_string(),
+            ));
+        }
+        Ok(cwd.join(sanitized))
+    }
+
+    fn relative(config: &Config, path: &std::path::Path) -> String {
+        match path.strip_prefix(&config.base_dir) {
+            Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+            Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+            Err(_) => path.to_string_lossy().to_string(),
+        }
+    }
+}
+
+/// Output of one pipe, bounded by `max_output_bytes`
+#[derive(Default)]
+struct Capture {
+    bytes: Vec<u8>,
+    truncated: bool,
+}
+
+impl Capture {
+    /// Keep the first `limit` bytes and drain the rest, so a chatty command
+    /// neither grows server memory nor blocks on a full pipe
+    async fn read(&mut self, pipe: Option<impl AsyncRead + Unpin>, limit: usize) {
+        let Some(mut pipe) = pipe else { return };
+        let mut buf = [0u8; 8192];
+        while let Ok(n) = pipe.read(&mut buf).await {
+            if n == 0 {
+                break;
+            }
+            let room = limit.saturating_sub(self.bytes.len());
+            self.bytes.extend_from_slice(&buf[..n.min(room)]);
+            self.truncated |= n > room;
+        }
+    }
+
+    fn into_text(self) -> String {
+        String::from_utf8_lossy(&self.bytes).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ExecConfig;
+    use tempfile::TempDir;
+
+    fn request(command: &str, args: &[&str], cwd: Option<&str>) -> ExecRequest {
+        ExecRequest {
+            command: command.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            cwd: cwd.map(str::to_string),
+            timeout_secs: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exec_allowlist_and_limits() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("project")).unwrap();
+        let mut config = Config {
+            base_dir: temp_dir.path().to_path_buf(),
+            blocked_paths: vec![],
+            ..Config::default()
+        };
+
+        assert!(matches!(
+            ExecService::run(&config, request("echo", &["hi"], None)).await,
+            Err(AppError::PermissionDenied(_))
+        ));
+
+        config.exec = ExecConfig {
+            enabled: true,
+            allowed_commands: vec!["sh".to_string()],
+            max_output_bytes: 4,
+            ..ExecConfig::default()
+        };
+        let response = ExecService::run(&config, request("sh", &["-c", "echo hello; exit 3"], Some("project")))
+            .await
+            .unwrap();
+        assert_eq!((response.exit_code, response.cwd.as_str()), (Some(3), "project"));
+        assert_eq!(response.stdout, "hell");
+        assert!(response.truncated);
+
+        let mut slow = request("sh", &["-c", "sleep 5"], None);
+        slow.timeout_secs = Some(0);
+        let response = ExecService::run(&config, slow).await.unwrap();
+        assert!(response.timed_out && response.exit_code.is_none());
+
+        // Output beyond the limit is drained, not buffered; a timeout keeps what was read
+        config.exec.max_output_bytes = 1024;
+        let chatty = request("sh", &["-c", "head -c 10000000 /dev/zero; echo done >&2"], None);
+        let response = ExecService::run(&config, chatty).await.unwrap();
+        assert_eq!((response.exit_code, response.stdout.len()), (Some(0), 1024));
+        assert_eq!(response.stderr, "done\n");
+        assert!(response.truncated);
+
+        let mut partial = request("sh", &["-c", "echo started; sleep 5"], None);
+        partial.timeout_secs = Some(1);
+        let response = ExecService::run(&config, partial).await.unwrap();
+        assert!(response.timed_out);
+        assert_eq!(response.stdout, "started\n");
+
+        assert!(matches!(
+            ExecService::run(&config, request("rm", &["-rf", "project"], None)).await,
+            Err(AppError::PermissionDenied(_))
+        ));
+        assert!(matches!(
+            ExecService::run(&config, request("sh", &[], Some("missing"))).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        config.exec.api_keys = vec!["ci".to_string()];
+        assert!(matches!(
+            ExecService::run(&config, request("sh", &["-c", "true"], None)).await,
+            Err(AppError::PermissionDenied(_))
+        ));
+        config.api_key = Some("ci".to_string());
+        assert!(ExecService::run(&config, request("sh", &["-c", "true"], None)).await.is_ok());
+    }
+}
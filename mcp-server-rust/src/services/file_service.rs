@@ -1,9 +1,11 @@
 use crate::{
-    config::Config,
+    config::{Access, Config},
     error::{AppError, Result},
     models::*,
     security,
+    services::IgnoreService,
 };
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use std::path::{Path, PathBuf};
 use tokio::fs;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -12,7 +14,7 @@ pub struct FileService;
 
 impl FileService {
     /// Resolve path relative to base_dir, handling both absolute and relative paths
-    fn resolve_path(config: &Config, sanitized_path: &Path) -> PathBuf {
+    pub(crate) fn resolve_path(config: &Config, sanitized_path: &Path) -> PathBuf {
         if sanitized_path.is_absolute() {
             // For absolute paths, use as-is when base_dir is root
             if config.base_dir == Path::new("/") || config.base_dir.as_os_str().is_empty() {
@@ -45,6 +47,8 @@ impl FileService {
                 request.path
             )));
         }
+        security::check_access(config, &full_path, Access::Write)
+            .map_err(AppError::PermissionDenied)?;
         
         // Validate extension
         if !config.is_extension_allowed(&full_path) {
@@ -95,8 +99,17 @@ impl FileService {
         })
     }
     
-    /// Read file content
+    /// Read file content as text
     pub async fn read_file(config: &Config, path: &str) -> Result<FileContentResponse> {
+        Self::read_file_as(config, path, ContentEncoding::Utf8).await
+    }
+
+    /// Read file content in the given encoding
+    pub async fn read_file_as(
+        config: &Config,
+        path: &str,
+        encoding: ContentEncoding,
+    ) -> Result<FileContentResponse> {
         let sanitized_path = security::sanitize_path(path)
             .map_err(|e| AppError::InvalidInput(e))?;
         
@@ -108,6 +121,8 @@ impl FileService {
                 path
             )));
         }
+        security::check_access(config, &full_path, Access::Read)
+            .map_err(AppError::PermissionDenied)?;
         
         if !full_path.exists() {
             return Err(AppError::NotFound(format!("File '{}' not found", path)));
@@ -131,7 +146,11 @@ impl FileService {
         
         Ok(FileContentResponse {
             path: path.to_string(),
-            content: String::from_utf8_lossy(&content).to_string(),
+            content: match encoding {
+                ContentEncoding::Utf8 => String::from_utf8_lossy(&content).to_string(),
+                ContentEncoding::Base64 => BASE64.encode(&content),
+            },
+            encoding,
             size: metadata.len(),
             mime_type,
             checksum,
@@ -155,6 +174,8 @@ impl FileService {
                 path
             )));
         }
+        security::check_access(config, &full_path, Access::Write)
+            .map_err(AppError::PermissionDenied)?;
         
         if !full_path.exists() {
             return Err(AppError::NotFound(format!("File '{}' not found", path)));
@@ -199,6 +220,8 @@ impl FileService {
                 path
             )));
         }
+        security::check_access(config, &full_path, Access::Write)
+            .map_err(AppError::PermissionDenied)?;
         
         if !full_path.exists() {
             return Err(AppError::NotFound(format!("File '{}' not found", path)));
@@ -212,8 +235,12 @@ impl FileService {
         })
     }
     
-    /// List files in directory
-    pub async fn list_files(config: &Config, dir_path: Option<&str>) -> Result<DirectoryListResponse> {
+    /// List files in directory, hiding entries matched by ignore files unless `include_ignored`
+    pub async fn list_files(
+        config: &Config,
+        dir_path: Option<&str>,
+        include_ignored: bool,
+    ) -> Result<DirectoryListResponse> {
         let base_path = if let Some(path) = dir_path {
             let sanitized = security::sanitize_path(path)
                 .map_err(|e| AppError::InvalidInput(e))?;
@@ -227,6 +254,8 @@ impl FileService {
                 "Access to directory is not allowed".to_string(),
             ));
         }
+        security::check_access(config, &base_path, Access::Read)
+            .map_err(AppError::PermissionDenied)?;
         
         if !base_path.exists() {
             return Err(AppError::NotFound("Directory not found".to_string()));
@@ -234,7 +263,9 @@ impl FileService {
         
         let mut files = Vec::new();
         let mut directories = Vec::new();
+        let mut ignored = 0;
         
+        let matcher = (!include_ignored).then(|| IgnoreService::matcher_for(config, &base_path));
         let mut entries = fs::read_dir(&base_path).await?;
         
         while let Some(entry) = entries.next_entry().await? {
@@ -242,6 +273,18 @@ impl FileService {
             let metadata = entry.metadata().await?;
             let name = entry.file_name().to_string_lossy().to_string();
             
+            if let Some(matcher) = &matcher {
+                if matcher.is_ignored(&path, metadata.is_dir()) {
+                    ignored += 1;
+                    continue;
+                }
+            }
+
+            // Entries the caller may not read are not listed at all
+            if security::check_access(config, &path, Access::Read).is_err() {
+                continue;
+            }
+            
             if metadata.is_file() {
                 files.push(FileInfo {
                     name: name.clone(),
@@ -263,6 +306,7 @@ impl FileService {
             path: dir_path.unwrap_or(".").to_string(),
             files,
             directories,
+            ignored,
         })
     }
     
@@ -282,6 +326,8 @@ impl FileService {
                 request.path
             )));
         }
+        security::check_access(config, &full_path, Access::Write)
+            .map_err(AppError::PermissionDenied)?;
         
         if full_path.exists() {
             return Err(AppError::InvalidInput(format!(
@@ -317,6 +363,8 @@ impl FileService {
                 path
             )));
         }
+        security::check_access(config, &full_path, Access::Write)
+            .map_err(AppError::PermissionDenied)?;
         
         if !full_path.exists() {
             return Err(AppError::NotFound(format!(
@@ -422,4 +470,72 @@ mod tests {
         let result = FileService::create_directory(&config, request).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_acl_limits_reads_writes_and_listing() {
+        let (mut config, _temp_dir) = create_test_config();
+        std::fs::write(config.base_dir.join(".env"), "SECRET=1").unwrap();
+        std::fs::write(config.base_dir.join("build.gradle.kts"), "plugins {}").unwrap();
+        config.acl = toml::from_str(
+            "default = \"deny\"\nrules = [\n  { pattern = \"src/**\", access = \"write\" },\n  { pattern = \"build.gradle.kts\", access = \"read\" },\n]",
+        )
+        .unwrap();
+
+        let create = |path: &str| CreateFileRequest {
+            path: path.to_string(),
+            content: "x".to_string(),
+            overwrite: true,
+        };
+        assert!(FileService::create_file(&config, create("src/main.rs")).await.is_ok());
+        assert!(FileService::read_file(&config, "build.gradle.kts").await.is_ok());
+        let denied = FileService::create_file(&config, create("build.gradle.kts")).await;
+        assert!(matches!(denied, Err(AppError::PermissionDenied(_))));
+        let denied = FileService::read_file(&config, ".env").await;
+        assert!(matches!(denied, Err(AppError::PermissionDenied(_))));
+
+        config.acl.default = Access::Read;
+        let listing = FileService::list_files(&config, None, true).await.unwrap();
+        assert!(listing.files.iter().any(|f| f.name == "build.gradle.kts"));
+        config.acl.rules.push(toml::from_str("pattern = \".env\"\naccess = \"deny\"").unwrap());
+        let listing = FileService::list_files(&config, None, true).await.unwrap();
+        assert!(!listing.files.iter().any(|f| f.name == ".env"));
+    }
+
+    #[tokio::test]
+    async fn test_list_files_honors_ignore_files() {
+        let (config, _temp_dir) = create_test_config();
+        std::fs::write(config.base_dir.join(".gitignore"), "build/\n").unwrap();
+        std::fs::write(config.base_dir.join(".mcpignore"), "*.log\n").unwrap();
+        std::fs::create_dir_all(config.base_dir.join("build")).unwrap();
+        std::fs::write(config.base_dir.join("debug.log"), "log").unwrap();
+        std::fs::write(config.base_dir.join("main.rs"), "fn main() {}").unwrap();
+
+        let listing = FileService::list_files(&config, None, false).await.unwrap();
+        assert_eq!(listing.ignored, 2);
+        assert!(listing.directories.is_empty());
+        assert!(listing.files.iter().any(|f| f.name == "main.rs"));
+        assert!(!listing.files.iter().any(|f| f.name == "debug.log"));
+
+        let listing = FileService::list_files(&config, None, true).await.unwrap();
+        assert_eq!(listing.ignored, 0);
+        assert_eq!(listing.directories.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_nested_directory_inside_ignored_one() {
+        let (config, _temp_dir) = create_test_config();
+        std::fs::write(config.base_dir.join(".gitignore"), "build/\n").unwrap();
+        let nested = config.base_dir.join("build").join("out");
+        std::fs::create_dir_all(nested.join("classes")).unwrap();
+        std::fs::write(nested.join("app.jar"), "jar").unwrap();
+
+        let listing = FileService::list_files(&config, Some("build/out"), false).await.unwrap();
+        assert_eq!(listing.ignored, 2);
+        assert!(listing.files.is_empty());
+        assert!(listing.directories.is_empty());
+
+        let listing = FileService::list_files(&config, Some("build/out"), true).await.unwrap();
+        assert_eq!(listing.ignored, 0);
+        assert_eq!(listing.files.len(), 1);
+    }
 }
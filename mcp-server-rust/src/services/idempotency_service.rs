@@ -0,0 +1,210 @@
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderMap, Method, StatusCode, Uri},
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Longest accepted `Idempotency-Key`
+pub const MAX_KEY_LEN: usize = 255;
+
+/// First response to a write request, replayed for retries with the same key
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl StoredResponse {
+    pub fn to_response(&self) -> Response {
+        let mut response = Response::new(Body::from(self.body.clone()));
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers.clone();
+        response
+    }
+}
+
+#[derive(Debug)]
+enum Entry {
+    /// The first request is still being executed
+    InFlight { fingerprint: String },
+    Done {
+        fingerprint: String,
+        response: StoredResponse,
+        expires_at: Instant,
+    },
+}
+
+/// Outcome of claiming an idempotency key
+#[derive(Debug)]
+pub enum Claim {
+    /// First request with this key: execute it and `complete` the guard
+    Execute(IdempotencyGuard),
+    /// Duplicate of a finished request
+    Replay(StoredResponse),
+    /// Key is in flight or was used for a different request
+    Conflict(String),
+}
+
+/// In-memory cache of responses to write requests keyed by `Idempotency-Key`
+#[derive(Debug, Clone, Default)]
+pub struct IdempotencyStore {
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+}
+
+impl IdempotencyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keys are 1-255 visible ASCII characters
+    pub fn is_valid_key(key: &str) -> bool {
+        !key.is_empty() && key.len() <= MAX_KEY_LEN && key.bytes().all(|b| b.is_ascii_graphic())
+    }
+
+    /// Hash of what makes two requests "the same": method, path with query and body
+    pub fn fingerprint(method: &Method, uri: &Uri, body: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(method.as_str());
+        hasher.update(b"\n");
+        hasher.update(uri.path_and_query().map(|p| p.as_str()).unwrap_or("/"));
+        hasher.update(b"\n");
+        hasher.update(body);
+        hex::encode(hasher.finalize())
+    }
+
+    /// Reserve `key` for a request or return the response cached for it
+    pub fn claim(&self, key: &str, fingerprint: String) -> Claim {
+        let mut entries = self.entries.lock().expect("idempotency store poisoned");
+        let now = Instant::now();
+        entries.retain(|_, entry| match entry {
+            Entry::InFlight { .. } => true,
+            Entry::Done { expires_at, .. } => *expires_at > now,
+        });
+
+        match entries.get(key) {
+            None => {
+                entries.insert(key.to_string(), Entry::InFlight { fingerprint });
+                Claim::Execute(IdempotencyGuard {
+                    store: self.clone(),
+                    key: key.to_string(),
+                    completed: false,
+                })
+            }
+            Some(Entry::InFlight { .. }) => Claim::Conflict(
+                "A request with this Idempotency-Key is still in progress".to_string(),
+            ),
+            Some(Entry::Done {
+                fingerprint: stored,
+                response,
+                ..
+            }) => {
+                if *stored == fingerprint {
+                    Claim::Replay(response.clone())
+                } else {
+                    Claim::Conflict(
+                        "Idempotency-Key was already used for a different request".to_string(),
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// Reservation of a key; dropping it without `complete` releases the key for a retry
+#[derive(Debug)]
+pub struct IdempotencyGuard {
+    store: IdempotencyStore,
+    key: String,
+    completed: bool,
+}
+
+impl IdempotencyGuard {
+    /// Cache the response of the executed request for `ttl`
+    pub fn complete(mut self, response: StoredResponse, ttl: Duration) {
+        let mut entries = self.store.entries.lock().expect("idempotency store poisoned");
+        if let Some(Entry::InFlight { fingerprint }) = entries.remove(&self.key) {
+            entries.insert(
+                self.key.clone(),
+                Entry::Done {
+                    fingerprint,
+                    response,
+                    expires_at: Instant::now() + ttl,
+                },
+            );
+        }
+        self.completed = true;
+    }
+}
+
+impl Drop for IdempotencyGuard {
+    fn drop(&mut self) {
+        if self.completed {
+            return;
+        }
+        if let Ok(mut entries) = self.store.entries.lock() {
+            if matches!(entries.get(&self.key), Some(Entry::InFlight { .. })) {
+                entries.remove(&self.key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn created(body: &'static str) -> StoredResponse {
+        StoredResponse {
+            status: StatusCode::CREATED,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(body.as_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_claim_replay_and_conflicts() {
+        let store = IdempotencyStore::new();
+        let uri: Uri = "/files".parse().unwrap();
+        let first = IdempotencyStore::fingerprint(&Method::POST, &uri, b"{\"path\":\"a.txt\"}");
+        let other = IdempotencyStore::fingerprint(&Method::POST, &uri, b"{\"path\":\"b.txt\"}");
+
+        let Claim::Execute(guard) = store.claim("k1", first.clone()) else {
+            panic!("first claim must execute");
+        };
+        assert!(matches!(store.claim("k1", first.clone()), Claim::Conflict(_)));
+
+        guard.complete(created("{\"success\":true}"), Duration::from_secs(60));
+        match store.claim("k1", first.clone()) {
+            Claim::Replay(response) => {
+                assert_eq!(response.status, StatusCode::CREATED);
+                assert_eq!(response.body, "{\"success\":true}");
+            }
+            other => panic!("expected replay, got {:?}", other),
+        }
+        assert!(matches!(store.claim("k1", other), Claim::Conflict(_)));
+
+        // Expired responses are forgotten
+        let Claim::Execute(guard) = store.claim("k2", first.clone()) else {
+            panic!("new key must execute");
+        };
+        guard.complete(created("{}"), Duration::ZERO);
+        assert!(matches!(store.claim("k2", first), Claim::Execute(_)));
+    }
+
+    #[test]
+    fn test_dropped_guard_releases_key() {
+        let store = IdempotencyStore::new();
+        drop(store.claim("k", "f".to_string()));
+        assert!(matches!(store.claim("k", "f".to_string()), Claim::Execute(_)));
+
+        assert!(IdempotencyStore::is_valid_key("retry-7f3a"));
+        assert!(!IdempotencyStore::is_valid_key(""));
+        assert!(!IdempotencyStore::is_valid_key("with space"));
+        assert!(!IdempotencyStore::is_valid_key(&"x".repeat(MAX_KEY_LEN + 1)));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::config::Config;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::path::Path;
+
+/// Server-side ignore file, same syntax as `.gitignore`
+pub const MCP_IGNORE_FILE: &str = ".mcpignore";
+
+const IGNORE_FILES: [&str; 2] = [".gitignore", MCP_IGNORE_FILE];
+
+/// Ignore rules collected from `base_dir` down to a listed directory
+pub struct IgnoreMatcher {
+    /// Matchers ordered from the deepest directory to `base_dir`
+    levels: Vec<Gitignore>,
+}
+
+impl IgnoreMatcher {
+    /// Check whether an entry is hidden by the ignore rules.
+    ///
+    /// Rules of the nearest directory win, like in git; a negated
+    /// pattern (`!keep.me`) re-includes the entry. An entry inside an
+    /// ignored directory (`build/` when listing `build/out`) is ignored too.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        for level in &self.levels {
+            match level.matched_path_or_any_parents(path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => continue,
+            }
+        }
+        false
+    }
+}
+
+pub struct IgnoreService;
+
+impl IgnoreService {
+    /// Build the matcher for entries of `dir`, which must lie inside `base_dir`
+    pub fn matcher_for(config: &Config, dir: &Path) -> IgnoreMatcher {
+        let mut levels = Vec::new();
+        let mut current = Some(dir);
+
+        while let Some(level_dir) = current {
+            let mut builder = GitignoreBuilder::new(level_dir);
+            for file in IGNORE_FILES {
+                let ignore_file = level_dir.join(file);
+                if ignore_file.is_file() {
+                    if let Some(err) = builder.add(&ignore_file) {
+                        tracing::warn!("Invalid rules in {:?}: {}", ignore_file, err);
+                    }
+                }
+            }
+            match builder.build() {
+                Ok(gitignore) if !gitignore.is_empty() => levels.push(gitignore),
+                Ok(_) => {}
+                Err(err) => tracing::warn!("Failed to build ignore rules for {:?}: {}", level_dir, err),
+            }
+
+            if level_dir == config.base_dir.as_path() {
+                break;
+            }
+            current = level_dir.parent().filter(|p| p.starts_with(&config.base_dir));
+        }
+
+        IgnoreMatcher { levels }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_test_config() -> (Config, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            base_dir: temp_dir.path().to_path_buf(),
+            blocked_paths: vec![],
+            ..Config::default()
+        };
+        (config, temp_dir)
+    }
+
+    #[test]
+    fn test_gitignore_and_mcpignore_combined() {
+        let (config, _temp_dir) = create_test_config();
+        let base = &config.base_dir;
+        std::fs::write(base.join(".gitignore"), "build/\n*.log\n").unwrap();
+        std::fs::write(base.join(MCP_IGNORE_FILE), "node_modules/\n").unwrap();
+
+        let matcher = IgnoreService::matcher_for(&config, base);
+        assert!(matcher.is_ignored(&base.join("build"), true));
+        assert!(matcher.is_ignored(&base.join("node_modules"), true));
+        assert!(matcher.is_ignored(&base.join("debug.log"), false));
+        assert!(!matcher.is_ignored(&base.join("src"), true));
+    }
+
+    #[test]
+    fn test_nested_rules_override_parent() {
+        let (config, _temp_dir) = create_test_config();
+        let base = &config.base_dir;
+        let nested = base.join("app");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(base.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(nested.join(".gitignore"), "!keep.log\n").unwrap();
+
+        let matcher = IgnoreService::matcher_for(&config, &nested);
+        assert!(matcher.is_ignored(&nested.join("debug.log"), false));
+        assert!(!matcher.is_ignored(&nested.join("keep.log"), false));
+    }
+}
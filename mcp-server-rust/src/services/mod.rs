@@ -1,3 +1,21 @@
+pub mod edit_service;
+pub mod exec_service;
 pub mod file_service;
+pub mod idempotency_service;
+pub mod ignore_service;
+pub mod outline_service;
+pub mod refactor_service;
+pub mod scaffold_service;
+pub mod session_service;
+pub mod usage_service;
 
+pub use edit_service::EditService;
+pub use exec_service::ExecService;
 pub use file_service::FileService;
+pub use idempotency_service::{Claim, IdempotencyStore, StoredResponse};
+pub use ignore_service::IgnoreService;
+pub use outline_service::OutlineService;
+pub use refactor_service::RefactorService;
+pub use scaffold_service::ScaffoldService;
+pub use session_service::{SessionService, SessionStore};
+pub use usage_service::{UsageCache, UsageService};
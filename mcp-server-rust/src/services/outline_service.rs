@@ -0,0 +1,311 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    models::*,
+    services::FileService,
+};
+use std::path::Path;
+use tree_sitter::{Language, Node, Parser};
+
+pub struct OutlineService;
+
+/// Parser and symbol node kinds of one language
+struct Grammar {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    language: fn() -> Language,
+    symbols: &'static [(&'static str, SymbolKind)],
+}
+
+const GRAMMARS: &[Grammar] = &[
+    Grammar {
+        name: "rust",
+        extensions: &["rs"],
+        language: || tree_sitter_rust::LANGUAGE.into(),
+        symbols: &[
+            ("mod_item", SymbolKind::Module),
+            ("struct_item", SymbolKind::Struct),
+            ("enum_item", SymbolKind::Enum),
+            ("union_item", SymbolKind::Struct),
+            ("trait_item", SymbolKind::Trait),
+            ("impl_item", SymbolKind::Impl),
+            ("function_item", SymbolKind::Function),
+            ("function_signature_item", SymbolKind::Method),
+            ("field_declaration", SymbolKind::Field),
+            ("enum_variant", SymbolKind::Variant),
+            ("const_item", SymbolKind::Constant),
+            ("static_item", SymbolKind::Constant),
+            ("type_item", SymbolKind::TypeAlias),
+            ("macro_definition", SymbolKind::Function),
+        ],
+    },
+    Grammar {
+        name: "python",
+        extensions: &["py", "pyi"],
+        language: || tree_sitter_python::LANGUAGE.into(),
+        symbols: &[
+            ("class_definition", SymbolKind::Class),
+            ("function_definition", SymbolKind::Function),
+        ],
+    },
+    Grammar {
+        name: "java",
+        extensions: &["java"],
+        language: || tree_sitter_java::LANGUAGE.into(),
+        symbols: &[
+            ("class_declaration", SymbolKind::Class),
+            ("record_declaration", SymbolKind::Class),
+            ("interface_declaration", SymbolKind::Interface),
+            ("annotation_type_declaration", SymbolKind::Interface),
+            ("enum_declaration", SymbolKind::Enum),
+            ("enum_constant", SymbolKind::Variant),
+            ("method_declaration", SymbolKind::Method),
+            ("constructor_declaration", SymbolKind::Method),
+            ("field_declaration", SymbolKind::Field),
+        ],
+    },
+    Grammar {
+        name: "javascript",
+        extensions: &["js", "jsx", "mjs", "cjs"],
+        language: || tree_sitter_javascript::LANGUAGE.into(),
+        symbols: &[
+            ("class_declaration", SymbolKind::Class),
+            ("function_declaration", SymbolKind::Function),
+            ("generator_function_declaration", SymbolKind::Function),
+            ("method_definition", SymbolKind::Method),
+            ("field_definition", SymbolKind::Field),
+        ],
+    },
+    Grammar {
+        name: "typescript",
+        extensions: &["ts", "mts", "cts"],
+        language: || tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        symbols: TYPESCRIPT_SYMBOLS,
+    },
+    Grammar {
+        name: "tsx",
+        extensions: &["tsx"],
+        language: || tree_sitter_typescript::LANGUAGE_TSX.into(),
+        symbols: TYPESCRIPT_SYMBOLS,
+    },
+    Grammar {
+        name: "go",
+        extensions: &["go"],
+        language: || tree_sitter_go::LANGUAGE.into(),
+        symbols: &[
+            ("function_declaration", SymbolKind::Function),
+            ("method_declaration", SymbolKind::Method),
+            ("method_elem", SymbolKind::Method),
+            // Refined by the underlying type in `symbol_kind`
+            ("type_spec", SymbolKind::TypeAlias),
+            ("field_declaration", SymbolKind::Field),
+            ("const_spec", SymbolKind::Constant),
+        ],
+    },
+];
+
+const TYPESCRIPT_SYMBOLS: &[(&str, SymbolKind)] = &[
+    ("class_declaration", SymbolKind::Class),
+    ("abstract_class_declaration", SymbolKind::Class),
+    ("interface_declaration", SymbolKind::Interface),
+    ("enum_declaration", SymbolKind::Enum),
+    ("type_alias_declaration", SymbolKind::TypeAlias),
+    ("internal_module", SymbolKind::Module),
+    ("function_declaration", SymbolKind::Function),
+    ("generator_function_declaration", SymbolKind::Function),
+    ("method_definition", SymbolKind::Method),
+    ("method_signature", SymbolKind::Method),
+    ("abstract_method_signature", SymbolKind::Method),
+    ("public_field_definition", SymbolKind::Field),
+    ("property_signature", SymbolKind::Field),
+];
+
+impl OutlineService {
+    /// Symbol tree of a source file
+    pub async fn outline(config: &Config, path: &str) -> Result<OutlineResponse> {
+        let grammar = Self::grammar_for(path)?;
+        let file = FileService::read_file(config, path).await?;
+
+        let mut parser = Parser::new();
+        parser
+            .set_language(&(grammar.language)())
+            .map_err(|e| AppError::InternalError(format!("Failed to load {} grammar: {}", grammar.name, e)))?;
+        let tree = parser
+            .parse(&file.content, None)
+            .ok_or_else(|| AppError::InternalError(format!("Failed to parse '{}'", path)))?;
+
+        let mut symbols = Vec::new();
+        Self::collect(tree.root_node(), &file.content, grammar, false, &mut symbols);
+
+        Ok(OutlineResponse {
+            path: path.to_string(),
+            language: grammar.name.to_string(),
+            has_errors: tree.root_node().has_error(),
+            symbols,
+        })
+    }
+
+    fn grammar_for(path: &str) -> Result<&'static Grammar> {
+        let extension = Path::new(path)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        GRAMMARS
+            .iter()
+            .find(|g| g.extensions.contains(&extension.as_str()))
+            .ok_or_else(|| {
+                let supported: Vec<&str> = GRAMMARS.iter().flat_map(|g| g.extensions.iter().copied()).collect();
+                AppError::InvalidInput(format!(
+                    "Outline is not supported for '{}'; supported extensions: {}",
+                    path,
+                    supported.join(", ")
+                ))
+            })
+    }
+
+    /// Walk named nodes, nesting symbols found below a symbol under it
+    fn collect(node: Node, source: &str, grammar: &Grammar, in_type: bool, out: &mut Vec<OutlineSymbol>) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            match Self::symbol_kind(grammar, child) {
+                Some(kind) => {
+                    let kind = if kind == SymbolKind::Function && in_type {
+                        SymbolKind::Method
+                    } else {
+                        kind
+                    };
+                    let mut symbol = OutlineSymbol {
+                        name: Self::symbol_name(child, source),
+                        kind,
+                        start_line: child.start_position().row + 1,
+                        end_line: child.end_position().row + 1,
+                        children: vec![],
+                    };
+                    Self::collect(child, source, grammar, kind.is_type(), &mut symbol.children);
+                    out.push(symbol);
+                }
+                None => Self::collect(child, source, grammar, in_type, out),
+            }
+        }
+    }
+
+    fn symbol_kind(grammar: &Grammar, node: Node) -> Option<SymbolKind> {
+        let kind = grammar
+            .symbols
+            .iter()
+            .find(|(node_kind, _)| *node_kind == node.kind())
+            .map(|(_, kind)| *kind)?;
+
+        if node.kind() == "type_spec" {
+            return Some(match node.child_by_field_name("type").map(|t| t.kind()) {
+                Some("struct_type") => SymbolKind::Struct,
+                Some("interface_type") => SymbolKind::Interface,
+                _ => kind,
+            });
+        }
+        Some(kind)
+    }
+
+    fn symbol_name(node: Node, source: &str) -> String {
+        let text = |n: Node| source[n.byte_range()].to_string();
+
+        // `impl Trait for Type` has no name of its own
+        if node.kind() == "impl_item" {
+            let target = node.child_by_field_name("type").map(text).unwrap_or_default();
+            return match node.child_by_field_name("trait") {
+                Some(t) => format!("{} for {}", text(t), target),
+                None => target,
+            };
+        }
+
+        if let Some(name) = node
+            .child_by_field_name("name")
+            .or_else(|| node.child_by_field_name("property"))
+        {
+            return text(name);
+        }
+
+        // Java `int x, y;` declares several fields at once
+        let mut cursor = node.walk();
+        let declarators: Vec<String> = node
+            .children_by_field_name("declarator", &mut cursor)
+            .filter_map(|d| d.child_by_field_name("name"))
+            .map(text)
+            .collect();
+        if !declarators.is_empty() {
+            return declarators.join(", ");
+        }
+
+        "<anonymous>".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn summary(symbols: &[OutlineSymbol]) -> Vec<String> {
+        symbols
+            .iter()
+            .map(|s| {
+                let children = summary(&s.children);
+                if children.is_empty() {
+                    format!("{:?} {} {}-{}", s.kind, s.name, s.start_line, s.end_line)
+                } else {
+                    format!("{:?} {} {}-{} [{}]", s.kind, s.name, s.start_line, s.end_line, children.join("; "))
+                }
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_outline_languages() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            base_dir: temp_dir.path().to_path_buf(),
+            blocked_paths: vec![],
+            ..Config::default()
+        };
+        std::fs::write(
+            temp_dir.path().join("lib.rs"),
+            "pub struct Server {\n    port: u16,\n}\n\nimpl Server {\n    pub fn start(&self) {}\n}\n\nfn main() {}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("App.java"),
+            "class App {\n    int x, y;\n    void run() {}\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("tool.py"),
+            "class Tool:\n    @staticmethod\n    def run():\n        pass\n",
+        )
+        .unwrap();
+
+        let rust = OutlineService::outline(&config, "lib.rs").await.unwrap();
+        assert_eq!(rust.language, "rust");
+        assert!(!rust.has_errors);
+        assert_eq!(
+            summary(&rust.symbols),
+            vec![
+                "Struct Server 1-3 [Field port 2-2]",
+                "Impl Server 5-7 [Method start 6-6]",
+                "Function main 9-9",
+            ]
+        );
+
+        let java = OutlineService::outline(&config, "App.java").await.unwrap();
+        assert_eq!(
+            summary(&java.symbols),
+            vec!["Class App 1-4 [Field x, y 2-2; Method run 3-3]"]
+        );
+
+        let python = OutlineService::outline(&config, "tool.py").await.unwrap();
+        assert_eq!(summary(&python.symbols), vec!["Class Tool 1-4 [Method run 3-4]"]);
+
+        let result = OutlineService::outline(&config, "notes.txt").await;
+        assert!(matches!(result, Err(AppError::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,341 @@
+use crate::{
+    config::{Access, Config},
+    error::{AppError, Result},
+    models::*,
+    security,
+    services::IgnoreService,
+};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::{Regex, RegexBuilder};
+use similar::TextDiff;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Tracing target of audit records for workspace-wide changes
+pub const AUDIT_TARGET: &str = "mcp_server_rust::audit";
+
+/// Directories never searched, even when not ignored
+const SKIPPED_DIRS: [&str; 1] = [".git"];
+
+/// Compiled regex size limit, keeps hostile patterns cheap to reject
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+pub struct RefactorService;
+
+enum Matcher {
+    Literal(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn new(request: &ReplaceRequest) -> Result<Self> {
+        if !request.regex {
+            return Ok(Self::Literal(request.pattern.clone()));
+        }
+        RegexBuilder::new(&request.pattern)
+            .size_limit(REGEX_SIZE_LIMIT)
+            .build()
+            .map(Self::Regex)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid regex '{}': {}", request.pattern, e)))
+    }
+
+    /// Replace every match; returns the new text and the number of matches
+    fn replace(&self, text: &str, replacement: &str) -> (String, usize) {
+        match self {
+            Self::Literal(pattern) => (
+                text.replace(pattern.as_str(), replacement),
+                text.matches(pattern.as_str()).count(),
+            ),
+            Self::Regex(regex) => (
+                regex.replace_all(text, replacement).into_owned(),
+                regex.find_iter(text).count(),
+            ),
+        }
+    }
+}
+
+/// File rewritten by a replace, kept in memory until the batch is written
+struct Change {
+    relative: String,
+    full_path: PathBuf,
+    original: Vec<u8>,
+    updated: String,
+    replacements: usize,
+}
+
+impl RefactorService {
+    /// Replace a literal or regex pattern in every matching file of the workspace.
+    ///
+    /// Without `apply` nothing is written and the response carries the diffs.
+    /// With `apply` either all files are rewritten or, on any failure, none are.
+    pub async fn replace(config: &Config, request: ReplaceRequest) -> Result<ReplaceResponse> {
+        let matcher = Matcher::new(&request)?;
+        let includes = Self::build_globset(&request.include, "include")?;
+        let excludes = Self::build_globset(&request.exclude, "exclude")?;
+
+        let mut files_scanned = 0;
+        let mut skipped = Vec::new();
+        let mut changes = Vec::new();
+        let mut pending = vec![config.base_dir.clone()];
+
+        while let Some(dir) = pending.pop() {
+            let ignore = IgnoreService::matcher_for(config, &dir);
+            let mut entries = fs::read_dir(&dir).await?;
+            let mut children = Vec::new();
+            while let Some(entry) = entries.next_entry().await? {
+                children.push(entry);
+            }
+            // Deterministic order keeps diffs stable between preview and apply
+            children.sort_by_key(|entry| entry.file_name());
+
+            for entry in children {
+                let path = entry.path();
+                let file_type = entry.file_type().await?;
+                if ignore.is_ignored(&path, file_type.is_dir()) || !config.is_path_allowed(&path) {
+                    continue;
+                }
+
+                let relative = Self::relative(config, &path);
+                if excludes.is_match(&relative) {
+                    continue;
+                }
+                if file_type.is_dir() {
+                    if !SKIPPED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()) {
+                        pending.push(path);
+                    }
+                    continue;
+                }
+                // Symlinks and special files are never followed
+                if !file_type.is_file() || !(includes.is_empty() || includes.is_match(&relative)) {
+                    continue;
+                }
+                if security::check_access(config, &path, Access::Read).is_err() {
+                    continue;
+                }
+
+                files_scanned += 1;
+                if entry.metadata().await?.len() > config.max_file_size as u64 {
+                    skipped.push(relative);
+                    continue;
+                }
+                let original = fs::read(&path).await?;
+                let Ok(text) = std::str::from_utf8(&original) else {
+                    skipped.push(relative);
+                    continue;
+                };
+
+                let (updated, replacements) = matcher.replace(text, &request.replacement);
+                if replacements == 0 || updated == text {
+                    continue;
+                }
+                changes.push(Change {
+                    relative,
+                    full_path: path,
+                    original,
+                    updated,
+                    replacements,
+                });
+            }
+        }
+        changes.sort_by(|a, b| a.relative.cmp(&b.relative));
+
+        if request.apply && !changes.is_empty() {
+            for change in &changes {
+                security::check_access(config, &change.full_path, Access::Write)
+                    .map_err(AppError::PermissionDenied)?;
+                if change.updated.len() > config.max_file_size {
+                    return Err(AppError::FileTooLarge(change.updated.len(), config.max_file_size));
+                }
+            }
+            Self::write_all(&changes).await?;
+        }
+
+        let files: Vec<FileReplacement> = changes
+            .iter()
+            .map(|change| FileReplacement {
+                path: change.relative.clone(),
+                replacements: change.replacements,
+                diff: Self::diff(change),
+                checksum: security::calculate_checksum(change.updated.as_bytes()),
+            })
+            .collect();
+        let replacements = files.iter().map(|f| f.replacements).sum();
+
+        if request.apply {
+            for file in &files {
+                tracing::info!(
+                    target: AUDIT_TARGET,
+                    path = %file.path,
+                    replacements = file.replacements,
+                    checksum = %file.checksum,
+                    "File rewritten by replace"
+                );
+            }
+            tracing::info!(
+                target: AUDIT_TARGET,
+                base_dir = ?config.base_dir,
+                pattern = %request.pattern,
+                regex = request.regex,
+                files_changed = files.len(),
+                replacements,
+                "Workspace replace applied"
+            );
+        }
+
+        Ok(ReplaceResponse {
+            applied: request.apply,
+            files_scanned,
+            files_changed: files.len(),
+            replacements,
+            skipped,
+            files,
+        })
+    }
+
+    /// Stage every new content next to its file, then swap them in; if a swap fails
+    /// the files already replaced get their original content back
+    async fn write_all(changes: &[Change]) -> Result<()> {
+        let mut staged: Vec<PathBuf> = Vec::with_capacity(changes.len());
+        for change in changes {
+            match Self::stage(change).await {
+                Ok(temp) => staged.push(temp),
+                Err(err) => {
+                    Self::remove_all(&staged).await;
+                    return Err(err);
+                }
+            }
+        }
+
+        for (index, (change, temp)) in changes.iter().zip(&staged).enumerate() {
+            if let Err(err) = fs::rename(temp, &change.full_path).await {
+                for done in &changes[..index] {
+                    if let Err(restore_err) = fs::write(&done.full_path, &done.original).await {
+                        tracing::error!("Failed to restore {:?}: {}", done.full_path, restore_err);
+                    }
+                }
+                Self::remove_all(&staged[index..]).await;
+                return Err(err.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the new content to a temporary file beside the target
+    async fn stage(change: &Change) -> Result<PathBuf> {
+        // The file must not have changed since it was scanned
+        if fs::read(&change.full_path).await? != change.original {
+            return Err(AppError::Conflict(format!(
+                "File '{}' changed during the replace",
+                change.relative
+            )));
+        }
+
+        let file_name = change.full_path.file_name().unwrap_or_default().to_string_lossy();
+        let temp = change.full_path.with_file_name(format!(
+            ".{}.{}.tmp",
+            file_name,
+            uuid::Uuid::new_v4().simple()
+        ));
+        let permissions = fs::metadata(&change.full_path).await?.permissions();
+        fs::write(&temp, change.updated.as_bytes()).await?;
+        if let Err(err) = fs::set_permissions(&temp, permissions).await {
+            Self::remove_all(std::slice::from_ref(&temp)).await;
+            return Err(err.into());
+        }
+        Ok(temp)
+    }
+
+    async fn remove_all(paths: &[PathBuf]) {
+        for path in paths {
+            let _ = fs::remove_file(path).await;
+        }
+    }
+
+    fn diff(change: &Change) -> String {
+        let original = String::from_utf8_lossy(&change.original);
+        TextDiff::from_lines(original.as_ref(), change.updated.as_str())
+            .unified_diff()
+            .context_radius(3)
+            .header(&format!("a/{}", change.relative), &format!("b/{}", change.relative))
+            .to_string()
+    }
+
+    fn relative(config: &Config, path: &Path) -> String {
+        path.strip_prefix(&config.base_dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    fn build_globset(patterns: &[String], kind: &str) -> Result<GlobSet> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            let glob = Glob::new(pattern).map_err(|e| {
+                AppError::InvalidInput(format!("Invalid {} pattern '{}': {}", kind, pattern, e))
+            })?;
+            builder.add(glob);
+        }
+        builder
+            .build()
+            .map_err(|e| AppError::InvalidInput(format!("Invalid {} patterns: {}", kind, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_replace_preview_and_apply() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            base_dir: temp_dir.path().to_path_buf(),
+            blocked_paths: vec![],
+            ..Config::default()
+        };
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "fn old_name() {}\nfn main() { old_name(); }\n").unwrap();
+        std::fs::write(root.join("src/notes.md"), "old_name\n").unwrap();
+        std::fs::write(root.join("src/blob.rs"), [0xff, 0xfe, b'o']).unwrap();
+        std::fs::write(root.join("target/gen.rs"), "old_name\n").unwrap();
+
+        let request = |apply| ReplaceRequest {
+            pattern: r"old_(\w+)".to_string(),
+            replacement: "new_$1".to_string(),
+            regex: true,
+            include: vec!["**/*.rs".to_string()],
+            exclude: vec![],
+            apply,
+        };
+
+        let preview = RefactorService::replace(&config, request(false)).await.unwrap();
+        assert!(!preview.applied);
+        assert_eq!(preview.files_scanned, 2);
+        assert_eq!(preview.skipped, vec!["src/blob.rs"]);
+        assert_eq!(preview.files.len(), 1);
+        assert_eq!(preview.replacements, 2);
+        assert!(preview.files[0].diff.starts_with("--- a/src/lib.rs\n+++ b/src/lib.rs\n"));
+        assert!(preview.files[0].diff.contains("+fn main() { new_name(); }\n"));
+        assert!(std::fs::read_to_string(root.join("src/lib.rs")).unwrap().contains("old_name"));
+
+        let applied = RefactorService::replace(&config, request(true)).await.unwrap();
+        assert_eq!(applied.files[0].diff, preview.files[0].diff);
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+            "fn new_name() {}\nfn main() { new_name(); }\n"
+        );
+        assert_eq!(std::fs::read_to_string(root.join("target/gen.rs")).unwrap(), "old_name\n");
+        assert_eq!(std::fs::read_dir(root.join("src")).unwrap().count(), 3);
+
+        let mut invalid = request(false);
+        invalid.pattern = "(".to_string();
+        assert!(matches!(
+            RefactorService::replace(&config, invalid).await,
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,518 @@
+use crate::{
+    config::{Access, Config},
+    error::{AppError, Result},
+    models::*,
+    security,
+    services::FileService,
+};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tokio::fs;
+
+pub struct ScaffoldService;
+
+/// Single step of a planned tree copy
+enum CopyStep {
+    Directory(PathBuf),
+    File { source: PathBuf, target: PathBuf, relative: String },
+}
+
+/// Copy plan built before touching the destination, so conflicts fail early
+#[derive(Default)]
+struct CopyPlan {
+    steps: Vec<CopyStep>,
+    excluded: Vec<String>,
+}
+
+impl ScaffoldService {
+    /// Recursively copy a directory inside the workspace
+    pub async fn copy_directory(
+        config: &Config,
+        request: CopyDirectoryRequest,
+    ) -> Result<CopyDirectoryResponse> {
+        let source = Self::resolve_allowed(config, &request.source, Access::Read)?;
+        let destination = Self::resolve_allowed(config, &request.destination, Access::Write)?;
+
+        if !source.is_dir() {
+            return Err(AppError::NotFound(format!(
+                "Directory '{}' not found",
+                request.source
+            )));
+        }
+
+        if destination.starts_with(&source) {
+            return Err(AppError::InvalidInput(
+                "Destination cannot be inside the source directory".to_string(),
+            ));
+        }
+
+        let excludes = Self::build_globset(&request.exclude)?;
+        let plan = Self::plan_copy(config, &source, &destination, &excludes, None).await?;
+        let (files, directories_created) =
+            Self::execute_plan(config, &plan, &destination, None, request.overwrite).await?;
+
+        Ok(CopyDirectoryResponse {
+            source: request.source,
+            destination: request.destination,
+            files_copied: files.len(),
+            directories_created,
+            excluded: plan.excluded,
+        })
+    }
+
+    /// Instantiate a template from `templates_dir` into the workspace
+    pub async fn scaffold(config: &Config, request: ScaffoldRequest) -> Result<ScaffoldResponse> {
+        let source = Self::template_path(config, &request.template)?;
+        if !source.is_dir() {
+            return Err(AppError::NotFound(format!(
+                "Template '{}' not found",
+                request.template
+            )));
+        }
+
+        let destination = Self::resolve_allowed(config, &request.destination, Access::Write)?;
+        let excludes = Self::build_globset(&request.exclude)?;
+        let plan = Self::plan_copy(
+            config,
+            &source,
+            &destination,
+            &excludes,
+            Some(&request.variables),
+        )
+        .await?;
+        let (files, directories_created) = Self::execute_plan(
+            config,
+            &plan,
+            &destination,
+            Some(&request.variables),
+            request.overwrite,
+        )
+        .await?;
+
+        Ok(ScaffoldResponse {
+            template: request.template,
+            destination: request.destination,
+            files,
+            directories_created,
+        })
+    }
+
+    /// Create a single file from a template file in `templates_dir`.
+    ///
+    /// Every `{{name}}` placeholder of the template must get a value, so a
+    /// half-filled boilerplate never lands in the workspace.
+    pub async fn create_from_template(
+        config: &Config,
+        request: CreateFromTemplateRequest,
+    ) -> Result<FileResponse> {
+        let source = Self::template_path(config, &request.template)?;
+        if !source.is_file() {
+            return Err(AppError::NotFound(format!(
+                "Template file '{}' not found",
+                request.template
+            )));
+        }
+
+        let template = String::from_utf8(fs::read(&source).await?).map_err(|_| {
+            AppError::InvalidInput(format!("Template '{}' is not a text file", request.template))
+        })?;
+        let missing: Vec<&str> = placeholders(&template)
+            .into_iter()
+            .filter(|name| !request.variables.contains_key(*name))
+            .collect();
+        if !missing.is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "Template '{}' needs values for: {}",
+                request.template,
+                missing.join(", ")
+            )));
+        }
+
+        FileService::create_file(
+            config,
+            CreateFileRequest {
+                path: request.path,
+                content: substitute_placeholders(&template, &request.variables),
+                overwrite: request.overwrite,
+            },
+        )
+        .await
+    }
+
+    /// Template location inside `templates_dir`
+    fn template_path(config: &Config, name: &str) -> Result<PathBuf> {
+        let template = security::sanitize_path(name).map_err(AppError::InvalidInput)?;
+        if template.is_absolute() {
+            return Err(AppError::InvalidInput(
+                "Template name must be relative to templates_dir".to_string(),
+            ));
+        }
+        Ok(config.templates_dir.join(template))
+    }
+
+    /// Sanitize a workspace path and check it against the access rules
+    fn resolve_allowed(config: &Config, path: &str, required: Access) -> Result<PathBuf> {
+        let sanitized = security::sanitize_path(path).map_err(AppError::InvalidInput)?;
+        let full_path = FileService::resolve_path(config, &sanitized);
+
+        if !config.is_path_allowed(&full_path) {
+            return Err(AppError::PermissionDenied(format!(
+                "Access to path '{}' is not allowed",
+                path
+            )));
+        }
+        security::check_access(config, &full_path, required).map_err(AppError::PermissionDenied)?;
+
+        Ok(full_path)
+    }
+
+    fn build_globset(patterns: &[String]) -> Result<GlobSet> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            let glob = Glob::new(pattern).map_err(|e| {
+                AppError::InvalidInput(format!("Invalid exclude pattern '{}': {}", pattern, e))
+            })?;
+            builder.add(glob);
+        }
+        builder
+            .build()
+            .map_err(|e| AppError::InvalidInput(format!("Invalid exclude patterns: {}", e)))
+    }
+
+    /// Walk the source tree and compute target paths without writing anything
+    async fn plan_copy(
+        config: &Config,
+        source_root: &Path,
+        destination_root: &Path,
+        excludes: &GlobSet,
+        variables: Option<&HashMap<String, String>>,
+    ) -> Result<CopyPlan> {
+        let mut plan = CopyPlan::default();
+        let mut pending = vec![PathBuf::new()];
+
+        while let Some(relative_dir) = pending.pop() {
+            let mut entries = fs::read_dir(source_root.join(&relative_dir)).await?;
+            let mut children = Vec::new();
+            while let Some(entry) = entries.next_entry().await? {
+                children.push(entry);
+            }
+            // Deterministic order keeps responses stable between runs
+            children.sort_by_key(|entry| entry.file_name());
+
+            for entry in children {
+                let relative = relative_dir.join(entry.file_name());
+                let relative_str = relative.to_string_lossy().replace('\\', "/");
+
+                if excludes.is_match(&relative_str) {
+                    plan.excluded.push(relative_str);
+                    continue;
+                }
+
+                let target_relative = match variables {
+                    Some(vars) => substitute_placeholders(&relative_str, vars),
+                    None => relative_str.clone(),
+                };
+                let target_relative = security::sanitize_path(&target_relative)
+                    .map_err(AppError::InvalidInput)?;
+                if target_relative.is_absolute() {
+                    return Err(AppError::InvalidInput(format!(
+                        "Entry '{}' resolves to an absolute path",
+                        relative_str
+                    )));
+                }
+                let target = destination_root.join(&target_relative);
+
+                // Template sources lie outside the workspace and are not subject to the ACL
+                security::check_access(config, &entry.path(), Access::Read)
+                    .and_then(|_| security::check_access(config, &target, Access::Write))
+                    .map_err(AppError::PermissionDenied)?;
+
+                let file_type = entry.file_type().await?;
+                if file_type.is_dir() {
+                    plan.steps.push(CopyStep::Directory(target));
+                    pending.push(relative);
+                } else if file_type.is_file() {
+                    if !config.is_extension_allowed(&target) {
+                        return Err(AppError::PermissionDenied(format!(
+                            "File extension not allowed: {:?}",
+                            target.extension()
+                        )));
+                    }
+                    plan.steps.push(CopyStep::File {
+                        source: entry.path(),
+                        target,
+                        relative: target_relative.to_string_lossy().replace('\\', "/"),
+                    });
+                } else {
+                    // Symlinks and special files are never followed
+                    tracing::debug!("Skipping non-regular entry: {}", relative_str);
+                }
+            }
+        }
+
+        Ok(plan)
+    }
+
+    /// Apply a copy plan; returns copied file paths and the number of created directories
+    async fn execute_plan(
+        config: &Config,
+        plan: &CopyPlan,
+        destination_root: &Path,
+        variables: Option<&HashMap<String, String>>,
+        overwrite: bool,
+    ) -> Result<(Vec<String>, usize)> {
+        if !overwrite {
+            for step in &plan.steps {
+                if let CopyStep::File { target, relative, .. } = step {
+                    if target.exists() {
+                        return Err(AppError::InvalidInput(format!(
+                            "File '{}' already exists",
+                            relative
+                        )));
+                    }
+                }
+            }
+        }
+
+        let mut directories_created = 0;
+        if !destination_root.exists() {
+            fs::create_dir_all(destination_root).await?;
+            directories_created += 1;
+        }
+
+        let mut files = Vec::new();
+        for step in &plan.steps {
+            match step {
+                CopyStep::Directory(target) => {
+                    if !target.exists() {
+                        fs::create_dir_all(target).await?;
+                        directories_created += 1;
+                    }
+                }
+                CopyStep::File { source, target, relative } => {
+                    let content = fs::read(source).await?;
+                    let content = match (variables, String::from_utf8(content)) {
+                        (Some(vars), Ok(text)) => substitute_placeholders(&text, vars).into_bytes(),
+                        (_, Ok(text)) => text.into_bytes(),
+                        // Binary files are copied verbatim
+                        (_, Err(raw)) => raw.into_bytes(),
+                    };
+
+                    if content.len() > config.max_file_size {
+                        return Err(AppError::FileTooLarge(content.len(), config.max_file_size));
+                    }
+
+                    if let Some(parent) = target.parent() {
+                        fs::create_dir_all(parent).await?;
+                    }
+                    fs::write(target, &content).await?;
+                    files.push(relative.clone());
+                }
+            }
+        }
+
+        Ok((files, directories_created))
+    }
+}
+
+static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+
+/// Replace `{{name}}` placeholders with values from `variables`; unknown ones are kept as is.
+///
+/// The text is scanned once, so a value that itself contains `{{other}}`
+/// is inserted literally instead of being expanded again.
+pub fn substitute_placeholders(text: &str, variables: &HashMap<String, String>) -> String {
+    let placeholder = PLACEHOLDER
+        .get_or_init(|| Regex::new(r"\{\{([A-Za-z0-9_]+)\}\}").expect("valid placeholder regex"));
+    placeholder
+        .replace_all(text, |caps: &Captures| match variables.get(&caps[1]) {
+            Some(value) => value.clone(),
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// Distinct `{{name}}` placeholder names in order of first use
+pub fn placeholders(text: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("}}") else {
+            break;
+        };
+        let name = &rest[..end];
+        if !name.is_empty()
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !names.contains(&name)
+        {
+            names.push(name);
+        }
+        rest = &rest[end + 2..];
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_test_config() -> (Config, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            base_dir: temp_dir.path().join("workspace"),
+            templates_dir: temp_dir.path().join("templates"),
+            blocked_paths: vec![],
+            ..Config::default()
+        };
+        std::fs::create_dir_all(&config.base_dir).unwrap();
+        (config, temp_dir)
+    }
+
+    #[test]
+    fn test_substitute_placeholders() {
+        let mut vars = HashMap::new();
+        vars.insert("name".to_string(), "demo".to_string());
+        assert_eq!(
+            substitute_placeholders("{{name}}-{{other}}", &vars),
+            "demo-{{other}}"
+        );
+    }
+
+    #[test]
+    fn test_substituted_values_are_not_expanded_again() {
+        let mut vars = HashMap::new();
+        vars.insert("greeting".to_string(), "hello {{name}}".to_string());
+        vars.insert("name".to_string(), "demo".to_string());
+        assert_eq!(
+            substitute_placeholders("{{greeting}} / {{name}}", &vars),
+            "hello {{name}} / demo"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_copy_directory_with_excludes() {
+        let (config, _temp_dir) = create_test_config();
+        let src = config.base_dir.join("src");
+        std::fs::create_dir_all(src.join("build")).unwrap();
+        std::fs::write(src.join("main.rs"), "fn main() {}").unwrap();
+        std::fs::write(src.join("debug.log"), "log").unwrap();
+        std::fs::write(src.join("build/out.bin"), "bin").unwrap();
+
+        let request = CopyDirectoryRequest {
+            source: "src".to_string(),
+            destination: "copy".to_string(),
+            exclude: vec!["build".to_string(), "*.log".to_string()],
+            overwrite: false,
+        };
+
+        let response = ScaffoldService::copy_directory(&config, request).await.unwrap();
+        assert_eq!(response.files_copied, 1);
+        assert!(config.base_dir.join("copy/main.rs").exists());
+        assert!(!config.base_dir.join("copy/debug.log").exists());
+        assert!(!config.base_dir.join("copy/build").exists());
+    }
+
+    #[tokio::test]
+    async fn test_copy_directory_into_itself_rejected() {
+        let (config, _temp_dir) = create_test_config();
+        std::fs::create_dir_all(config.base_dir.join("src")).unwrap();
+
+        let request = CopyDirectoryRequest {
+            source: "src".to_string(),
+            destination: "src/nested".to_string(),
+            exclude: vec![],
+            overwrite: false,
+        };
+
+        let result = ScaffoldService::copy_directory(&config, request).await;
+        assert!(matches!(result, Err(AppError::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn test_scaffold_substitutes_paths_and_contents() {
+        let (config, _temp_dir) = create_test_config();
+        let template = config.templates_dir.join("module");
+        std::fs::create_dir_all(template.join("src")).unwrap();
+        std::fs::write(template.join("src/{{name}}.kt"), "class {{name}}").unwrap();
+
+        let mut variables = HashMap::new();
+        variables.insert("name".to_string(), "Greeter".to_string());
+        let request = ScaffoldRequest {
+            template: "module".to_string(),
+            destination: "app".to_string(),
+            variables,
+            exclude: vec![],
+            overwrite: false,
+        };
+
+        let response = ScaffoldService::scaffold(&config, request).await.unwrap();
+        assert_eq!(response.files, vec!["src/Greeter.kt".to_string()]);
+        let content = std::fs::read_to_string(config.base_dir.join("app/src/Greeter.kt")).unwrap();
+        assert_eq!(content, "class Greeter");
+    }
+
+    #[tokio::test]
+    async fn test_create_from_template() {
+        let (config, _temp_dir) = create_test_config();
+        std::fs::create_dir_all(config.templates_dir.join("kotlin")).unwrap();
+        std::fs::write(
+            config.templates_dir.join("kotlin/Class.kt"),
+            "package {{package}}\n\nclass {{class_name}} {\n    // {{ not a placeholder }}\n}\n",
+        )
+        .unwrap();
+        assert_eq!(
+            placeholders("{{a}} {{b}} {{a}} {{ c }} {{"),
+            vec!["a", "b"]
+        );
+
+        let mut variables = HashMap::new();
+        variables.insert("package".to_string(), "com.example".to_string());
+        let mut request = CreateFromTemplateRequest {
+            template: "kotlin/Class.kt".to_string(),
+            path: "src/Greeter.kt".to_string(),
+            variables: variables.clone(),
+            overwrite: false,
+        };
+        match ScaffoldService::create_from_template(&config, request).await {
+            Err(AppError::InvalidInput(message)) => assert!(message.ends_with("class_name")),
+            other => panic!("expected missing variable error, got {:?}", other),
+        }
+
+        variables.insert("class_name".to_string(), "Greeter".to_string());
+        request = CreateFromTemplateRequest {
+            template: "kotlin/Class.kt".to_string(),
+            path: "src/Greeter.kt".to_string(),
+            variables,
+            overwrite: false,
+        };
+        ScaffoldService::create_from_template(&config, request).await.unwrap();
+        let content = std::fs::read_to_string(config.base_dir.join("src/Greeter.kt")).unwrap();
+        assert!(content.starts_with("package com.example\n\nclass Greeter {"));
+    }
+
+    #[tokio::test]
+    async fn test_scaffold_rejects_traversal_in_variables() {
+        let (config, _temp_dir) = create_test_config();
+        let template = config.templates_dir.join("module");
+        std::fs::create_dir_all(&template).unwrap();
+        std::fs::write(template.join("{{name}}.txt"), "x").unwrap();
+
+        let mut variables = HashMap::new();
+        variables.insert("name".to_string(), "../escape".to_string());
+        let request = ScaffoldRequest {
+            template: "module".to_string(),
+            destination: "app".to_string(),
+            variables,
+            exclude: vec![],
+            overwrite: false,
+        };
+
+        let result = ScaffoldService::scaffold(&config, request).await;
+        assert!(matches!(result, Err(AppError::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,291 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    models::*,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::fs;
+
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub token: String,
+    pub workspace: PathBuf,
+    pub created_at: SystemTime,
+    pub expires_at: SystemTime,
+}
+
+impl Session {
+    pub fn is_expired(&self) -> bool {
+        SystemTime::now() >= self.expires_at
+    }
+}
+
+/// In-memory registry of active sessions shared between handlers
+#[derive(Debug, Clone, Default)]
+pub struct SessionStore {
+    sessions: Arc<RwLock<HashMap<String, Session>>>,
+}
+
+impl SessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn insert(&self, session: Session) {
+        self.sessions
+            .write()
+            .expect("session store poisoned")
+            .insert(session.token.clone(), session);
+    }
+
+    fn remove(&self, token: &str) -> Option<Session> {
+        self.sessions
+            .write()
+            .expect("session store poisoned")
+            .remove(token)
+    }
+
+    pub fn get(&self, token: &str) -> Option<Session> {
+        self.sessions
+            .read()
+            .expect("session store poisoned")
+            .get(token)
+            .cloned()
+    }
+
+    /// Remove expired sessions from the registry and return them
+    fn drain_expired(&self) -> Vec<Session> {
+        let mut sessions = self.sessions.write().expect("session store poisoned");
+        let expired: Vec<String> = sessions
+            .values()
+            .filter(|s| s.is_expired())
+            .map(|s| s.token.clone())
+            .collect();
+        expired
+            .iter()
+            .filter_map(|token| sessions.remove(token))
+            .collect()
+    }
+}
+
+pub struct SessionService;
+
+impl SessionService {
+    /// Provision an isolated workspace directory for a new session
+    pub async fn create_session(
+        config: &Config,
+        store: &SessionStore,
+        request: CreateSessionRequest,
+    ) -> Result<SessionResponse> {
+        let ttl = request
+            .ttl_seconds
+            .unwrap_or(config.session_default_ttl_secs);
+        if ttl == 0 || ttl > config.session_max_ttl_secs {
+            return Err(AppError::InvalidInput(format!(
+                "ttl_seconds must be between 1 and {}",
+                config.session_max_ttl_secs
+            )));
+        }
+
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let workspace = config.sessions_dir.join(&token);
+        fs::create_dir_all(&workspace).await?;
+
+        let created_at = SystemTime::now();
+        let session = Session {
+            token: token.clone(),
+            workspace,
+            created_at,
+            expires_at: created_at + Duration::from_secs(ttl),
+        };
+        let response = Self::to_response(&session);
+        store.insert(session);
+
+        tracing::info!("Session {} created (ttl {}s)", token, ttl);
+        Ok(response)
+    }
+
+    /// Look up a live session by token
+    pub fn resolve(store: &SessionStore, token: &str) -> Result<Session> {
+        match store.get(token) {
+            Some(session) if !session.is_expired() => Ok(session),
+            _ => Err(AppError::NotFound(format!(
+                "Session '{}' not found or expired",
+                token
+            ))),
+        }
+    }
+
+    /// Close a session and delete its workspace
+    pub async fn delete_session(store: &SessionStore, token: &str) -> Result<DeleteResponse> {
+        let session = store
+            .remove(token)
+            .ok_or_else(|| AppError::NotFound(format!("Session '{}' not found", token)))?;
+
+        if session.workspace.exists() {
+            fs::remove_dir_all(&session.workspace).await?;
+        }
+
+        Ok(DeleteResponse {
+            success: true,
+            message: format!("Session '{}' closed", token),
+        })
+    }
+
+    /// Delete workspaces of all expired sessions; returns how many were collected
+    pub async fn collect_expired(store: &SessionStore) -> usize {
+        let expired = store.drain_expired();
+        for session in &expired {
+            if let Err(e) = fs::remove_dir_all(&session.workspace).await {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!(
+                        "Failed to remove workspace of expired session {}: {}",
+                        session.token,
+                        e
+                    );
+                }
+            }
+        }
+        expired.len()
+    }
+
+    /// Spawn the background task that garbage-collects expired sessions
+    pub fn spawn_gc(store: SessionStore, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let collected = Self::collect_expired(&store).await;
+                if collected > 0 {
+                    tracing::info!("Collected {} expired session(s)", collected);
+                }
+            }
+        })
+    }
+
+    fn to_response(session: &Session) -> SessionResponse {
+        let ttl_seconds = session
+            .expires_at
+            .duration_since(SystemTime::now())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        SessionResponse {
+            token: session.token.clone(),
+            created_at: format!("{:?}", session.created_at),
+            expires_at: format!("{:?}", session.expires_at),
+            ttl_seconds,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_test_config() -> (Config, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            base_dir: temp_dir.path().join("workspace"),
+            sessions_dir: temp_dir.path().join("sessions"),
+            blocked_paths: vec![],
+            ..Config::default()
+        };
+        std::fs::create_dir_all(&config.base_dir).unwrap();
+        (config, temp_dir)
+    }
+
+    #[tokio::test]
+    async fn test_create_and_delete_session() {
+        let (config, _temp_dir) = create_test_config();
+        let store = SessionStore::new();
+
+        let response = SessionService::create_session(
+            &config,
+            &store,
+            CreateSessionRequest { ttl_seconds: Some(60) },
+        )
+        .await
+        .unwrap();
+
+        let session = SessionService::resolve(&store, &response.token).unwrap();
+        assert!(session.workspace.starts_with(&config.sessions_dir));
+        assert!(session.workspace.exists());
+
+        SessionService::delete_session(&store, &response.token)
+            .await
+            .unwrap();
+        assert!(!session.workspace.exists());
+        assert!(SessionService::resolve(&store, &response.token).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_main_workspace_cannot_reach_sessions() {
+        use crate::services::FileService;
+
+        let (config, _temp_dir) = create_test_config();
+        let store = SessionStore::new();
+        let response = SessionService::create_session(
+            &config,
+            &store,
+            CreateSessionRequest { ttl_seconds: Some(60) },
+        )
+        .await
+        .unwrap();
+        let session = SessionService::resolve(&store, &response.token).unwrap();
+        std::fs::write(session.workspace.join("secret.txt"), "session data").unwrap();
+
+        let listing = FileService::list_files(&config, None, true).await.unwrap();
+        assert!(listing.files.is_empty() && listing.directories.is_empty());
+        for path in [
+            format!("../sessions/{}/secret.txt", response.token),
+            format!(".sessions/{}/secret.txt", response.token),
+        ] {
+            assert!(FileService::read_file(&config, &path).await.is_err(), "{}", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collect_expired_sessions() {
+        let (config, _temp_dir) = create_test_config();
+        let store = SessionStore::new();
+
+        let response = SessionService::create_session(
+            &config,
+            &store,
+            CreateSessionRequest { ttl_seconds: Some(1) },
+        )
+        .await
+        .unwrap();
+        let workspace = store.get(&response.token).unwrap().workspace;
+
+        // Force expiration without sleeping
+        let mut session = store.get(&response.token).unwrap();
+        session.expires_at = SystemTime::now() - Duration::from_secs(1);
+        store.insert(session);
+
+        assert_eq!(SessionService::collect_expired(&store).await, 1);
+        assert!(!workspace.exists());
+        assert!(store.get(&response.token).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_ttl_above_limit_rejected() {
+        let (config, _temp_dir) = create_test_config();
+        let store = SessionStore::new();
+
+        let result = SessionService::create_session(
+            &config,
+            &store,
+            CreateSessionRequest {
+                ttl_seconds: Some(config.session_max_ttl_secs + 1),
+            },
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::InvalidInput(_))));
+    }
+}
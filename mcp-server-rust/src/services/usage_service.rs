@@ -0,0 +1,245 @@
+use crate::{
+    config::{Access, Config},
+    error::{AppError, Result},
+    models::*,
+    security,
+    services::FileService,
+};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Deepest level of subdirectories a report may include
+pub const MAX_DEPTH: usize = 32;
+
+#[derive(Debug)]
+struct CachedUsage {
+    tree: Arc<DirectoryUsage>,
+    computed_at: SystemTime,
+    expires_at: Instant,
+}
+
+/// Full usage trees of recently measured directories, keyed by caller and path
+#[derive(Debug, Clone, Default)]
+pub struct UsageCache {
+    entries: Arc<Mutex<HashMap<String, CachedUsage>>>,
+}
+
+impl UsageCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn get(&self, key: &str) -> Option<(Arc<DirectoryUsage>, SystemTime)> {
+        let mut entries = self.entries.lock().expect("usage cache poisoned");
+        let now = Instant::now();
+        entries.retain(|_, entry| entry.expires_at > now);
+        entries
+            .get(key)
+            .map(|entry| (entry.tree.clone(), entry.computed_at))
+    }
+
+    fn insert(&self, key: String, tree: Arc<DirectoryUsage>, computed_at: SystemTime, ttl: Duration) {
+        self.entries.lock().expect("usage cache poisoned").insert(
+            key,
+            CachedUsage {
+                tree,
+                computed_at,
+                expires_at: Instant::now() + ttl,
+            },
+        );
+    }
+}
+
+pub struct UsageService;
+
+impl UsageService {
+    /// Aggregated size of `path` and its subdirectories down to `depth`, like `du`.
+    ///
+    /// The whole tree is measured on a blocking thread and cached for
+    /// `usage_cache_ttl_secs`; `refresh` forces a new measurement.
+    pub async fn usage(
+        config: &Config,
+        cache: &UsageCache,
+        path: Option<&str>,
+        depth: usize,
+        refresh: bool,
+    ) -> Result<UsageResponse> {
+        if depth > MAX_DEPTH {
+            return Err(AppError::InvalidInput(format!(
+                "depth must not exceed {}",
+                MAX_DEPTH
+            )));
+        }
+
+        let full_path = match path {
+            Some(path) => {
+                let sanitized = security::sanitize_path(path).map_err(AppError::InvalidInput)?;
+                FileService::resolve_path(config, &sanitized)
+            }
+            None => config.base_dir.clone(),
+        };
+        if !config.is_path_allowed(&full_path) {
+            return Err(AppError::PermissionDenied(
+                "Access to directory is not allowed".to_string(),
+            ));
+        }
+        security::check_access(config, &full_path, Access::Read)
+            .map_err(AppError::PermissionDenied)?;
+        if !full_path.is_dir() {
+            return Err(AppError::NotFound("Directory not found".to_string()));
+        }
+
+        // The ACL hides different entries from different callers
+        let key = format!(
+            "{}\n{}",
+            config.api_key.as_deref().unwrap_or_default(),
+            full_path.display()
+        );
+        let cached = if refresh { None } else { cache.get(&key) };
+        let is_cached = cached.is_some();
+
+        let (tree, computed_at) = match cached {
+            Some(hit) => hit,
+            None => {
+                let computed_at = SystemTime::now();
+                let walk_config = config.clone();
+                let root = full_path.clone();
+                let tree = tokio::task::spawn_blocking(move || Self::measure(&walk_config, &root))
+                    .await
+                    .map_err(|e| AppError::InternalError(format!("Disk usage walk failed: {}", e)))?;
+                let tree = Arc::new(tree);
+                if config.usage_cache_ttl_secs > 0 {
+                    cache.insert(
+                        key,
+                        tree.clone(),
+                        computed_at,
+                        Duration::from_secs(config.usage_cache_ttl_secs),
+                    );
+                }
+                (tree, computed_at)
+            }
+        };
+
+        Ok(UsageResponse {
+            path: tree.path.clone(),
+            depth,
+            size: tree.size,
+            files: tree.files,
+            cached: is_cached,
+            computed_at: format!("{:?}", computed_at),
+            directories: Self::truncate(&tree.directories, depth),
+        })
+    }
+
+    /// Sum sizes of regular files below `dir`. Symlinks are not followed, and
+    /// entries the caller may not read or that vanish during the walk are left out.
+    fn measure(config: &Config, dir: &Path) -> DirectoryUsage {
+        let mut usage = DirectoryUsage {
+            path: Self::relative(config, dir),
+            size: 0,
+            files: 0,
+            directories: vec![],
+        };
+
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return usage;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if security::check_access(config, &path, Access::Read).is_err() {
+                continue;
+            }
+
+            if metadata.is_dir() {
+                let child = Self::measure(config, &path);
+                usage.size += child.size;
+                usage.files += child.files;
+                usage.directories.push(child);
+            } else if metadata.is_file() {
+                usage.size += metadata.len();
+                usage.files += 1;
+            }
+        }
+
+        usage
+            .directories
+            .sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+        usage
+    }
+
+    fn truncate(directories: &[DirectoryUsage], depth: usize) -> Vec<DirectoryUsage> {
+        if depth == 0 {
+            return vec![];
+        }
+        directories
+            .iter()
+            .map(|dir| DirectoryUsage {
+                path: dir.path.clone(),
+                size: dir.size,
+                files: dir.files,
+                directories: Self::truncate(&dir.directories, depth - 1),
+            })
+            .collect()
+    }
+
+    fn relative(config: &Config, path: &Path) -> String {
+        match path.strip_prefix(&config.base_dir) {
+            Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+            Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+            Err(_) => path.to_string_lossy().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_usage_aggregates_and_caches() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            base_dir: temp_dir.path().to_path_buf(),
+            blocked_paths: vec![],
+            ..Config::default()
+        };
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("build/classes")).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("README.md"), [0u8; 10]).unwrap();
+        std::fs::write(root.join("build/app.jar"), [0u8; 300]).unwrap();
+        std::fs::write(root.join("build/classes/Main.class"), [0u8; 200]).unwrap();
+        std::fs::write(root.join("src/main.rs"), [0u8; 40]).unwrap();
+
+        let cache = UsageCache::new();
+        let report = UsageService::usage(&config, &cache, None, 1, false).await.unwrap();
+        assert_eq!((report.path.as_str(), report.size, report.files), (".", 550, 4));
+        assert!(!report.cached);
+        let top: Vec<(&str, u64, usize)> = report
+            .directories
+            .iter()
+            .map(|d| (d.path.as_str(), d.size, d.directories.len()))
+            .collect();
+        assert_eq!(top, vec![("build", 500, 0), ("src", 40, 0)]);
+
+        std::fs::write(root.join("src/lib.rs"), [0u8; 60]).unwrap();
+        let report = UsageService::usage(&config, &cache, None, 2, false).await.unwrap();
+        assert!(report.cached);
+        assert_eq!(report.size, 550);
+        assert_eq!(report.directories[0].directories[0].path, "build/classes");
+
+        let report = UsageService::usage(&config, &cache, Some("src"), 1, true).await.unwrap();
+        assert_eq!((report.path.as_str(), report.size, report.cached), ("src", 100, false));
+
+        assert!(matches!(
+            UsageService::usage(&config, &cache, None, MAX_DEPTH + 1, false).await,
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,346 @@
+use axum::{
+    async_trait,
+    body::Body,
+    extract::{FromRef, FromRequestParts, Request, State},
+    http::{request::Parts, HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::{
+    config::Config,
+    error::AppError,
+    services::{Claim, IdempotencyStore, SessionService, SessionStore, StoredResponse, UsageCache},
+};
+
+/// Header that scopes a request to a session workspace
+pub const SESSION_HEADER: &str = "x-session-token";
+
+/// Header identifying the caller for `[acl]` rules
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Header with the client's key for safely retrying a write request
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Set on responses replayed from the idempotency cache
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Routes that stay writable in read-only mode, so the switch can be turned off again
+const ADMIN_PREFIX: &str = "/admin/";
+
+/// Shared application state
+#[derive(Debug, Clone)]
+pub struct AppState {
+    pub config: Config,
+    pub sessions: SessionStore,
+    pub read_only: ReadOnlyMode,
+    pub idempotency: IdempotencyStore,
+    pub usage: UsageCache,
+}
+
+impl AppState {
+    pub fn new(config: Config) -> Self {
+        let read_only = ReadOnlyMode::default();
+        if config.read_only {
+            read_only.enable(Some("enabled in server configuration".to_string()));
+        }
+
+        Self {
+            config,
+            sessions: SessionStore::new(),
+            read_only,
+            idempotency: IdempotencyStore::new(),
+            usage: UsageCache::new(),
+        }
+    }
+}
+
+/// Runtime switch that rejects write operations; holds the reason while enabled
+#[derive(Debug, Clone, Default)]
+pub struct ReadOnlyMode {
+    reason: Arc<RwLock<Option<String>>>,
+}
+
+impl ReadOnlyMode {
+    pub fn enable(&self, reason: Option<String>) {
+        let reason = reason
+            .filter(|r| !r.trim().is_empty())
+            .unwrap_or_else(|| "no reason given".to_string());
+        tracing::warn!("Read-only mode enabled: {}", reason);
+        *self.reason.write().expect("read-only lock poisoned") = Some(reason);
+    }
+
+    pub fn disable(&self) {
+        tracing::info!("Read-only mode disabled");
+        *self.reason.write().expect("read-only lock poisoned") = None;
+    }
+
+    /// Reason of the active read-only mode, `None` when writes are allowed
+    pub fn reason(&self) -> Option<String> {
+        self.reason.read().expect("read-only lock poisoned").clone()
+    }
+}
+
+impl FromRef<AppState> for Config {
+    fn from_ref(state: &AppState) -> Self {
+        state.config.clone()
+    }
+}
+
+impl FromRef<AppState> for SessionStore {
+    fn from_ref(state: &AppState) -> Self {
+        state.sessions.clone()
+    }
+}
+
+impl FromRef<AppState> for ReadOnlyMode {
+    fn from_ref(state: &AppState) -> Self {
+        state.read_only.clone()
+    }
+}
+
+impl FromRef<AppState> for UsageCache {
+    fn from_ref(state: &AppState) -> Self {
+        state.usage.clone()
+    }
+}
+
+/// Middleware rejecting write requests with 403 while read-only mode is on
+pub async fn enforce_read_only(
+    State(mode): State<ReadOnlyMode>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if is_write(request.method()) && !request.uri().path().starts_with(ADMIN_PREFIX) {
+        if let Some(reason) = mode.reason() {
+            return Err(AppError::PermissionDenied(format!(
+                "Server is in read-only mode: {}",
+                reason
+            )));
+        }
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Middleware replaying the first response to a write request for retries with the same
+/// `Idempotency-Key`. Keys are scoped by `X-API-Key` and session; reusing a key for a
+/// different request or while the first one is running is a 409. Server errors are not
+/// cached, so the retry executes the operation again.
+pub async fn replay_idempotent(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let ttl = state.config.idempotency_ttl_secs;
+    if ttl == 0 || !is_write(request.method()) {
+        return Ok(next.run(request).await);
+    }
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(request).await);
+    };
+    let key = key
+        .to_str()
+        .ok()
+        .filter(|key| IdempotencyStore::is_valid_key(key))
+        .map(str::to_string)
+        .ok_or_else(|| {
+            AppError::InvalidInput(
+                "Idempotency-Key must be 1-255 visible ASCII characters".to_string(),
+            )
+        })?;
+
+    let (parts, body) = request.into_parts();
+    let header = |name: &str| {
+        parts
+            .headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+    // Header values cannot contain newlines, so the scope is unambiguous
+    let scope = format!(
+        "{}\n{}\n{}",
+        header(API_KEY_HEADER),
+        header(SESSION_HEADER),
+        key
+    );
+
+    let limit = state.config.max_file_size.saturating_mul(2);
+    let body = axum::body::to_bytes(body, limit).await.map_err(|_| {
+        AppError::InvalidInput(format!("Request body exceeds {} bytes", limit))
+    })?;
+    let fingerprint = IdempotencyStore::fingerprint(&parts.method, &parts.uri, &body);
+
+    match state.idempotency.claim(&scope, fingerprint) {
+        Claim::Replay(stored) => {
+            tracing::info!("Replaying response for Idempotency-Key {}", key);
+            let mut response = stored.to_response();
+            response
+                .headers_mut()
+                .insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+            Ok(response)
+        }
+        Claim::Conflict(reason) => Err(AppError::Conflict(reason)),
+        Claim::Execute(guard) => {
+            let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+            if response.status().is_server_error() {
+                return Ok(response);
+            }
+
+            let (parts, body) = response.into_parts();
+            let body = axum::body::to_bytes(body, usize::MAX)
+                .await
+                .map_err(|e| AppError::InternalError(e.to_string()))?;
+            guard.complete(
+                StoredResponse {
+                    status: parts.status,
+                    headers: parts.headers.clone(),
+                    body: body.clone(),
+                },
+                Duration::from_secs(ttl),
+            );
+            Ok(Response::from_parts(parts, Body::from(body)))
+        }
+    }
+}
+
+fn is_write(method: &Method) -> bool {
+    !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Configuration scoped to the caller's workspace.
+///
+/// Without a session header this is the server config as is; with
+/// `X-Session-Token` the base directory points to the session workspace.
+/// `X-API-Key` selects the caller's ACL rules.
+#[derive(Debug, Clone)]
+pub struct WorkspaceConfig(pub Config);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for WorkspaceConfig
+where
+    S: Send + Sync,
+    Config: FromRef<S>,
+    SessionStore: FromRef<S>,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let mut config = Config::from_ref(state);
+
+        if let Some(token) = parts.headers.get(SESSION_HEADER) {
+            let token = token
+                .to_str()
+                .map_err(|_| AppError::InvalidInput("Malformed session token".to_string()))?;
+            let session = SessionService::resolve(&SessionStore::from_ref(state), token)?;
+            config.base_dir = session.workspace;
+        }
+
+        if let Some(key) = parts.headers.get(API_KEY_HEADER) {
+            let key = key
+                .to_str()
+                .map_err(|_| AppError::InvalidInput("Malformed API key".to_string()))?;
+            config.api_key = Some(key.to_string());
+        }
+
+        Ok(WorkspaceConfig(config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CreateSessionRequest;
+    use axum::http::Request;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_workspace_config_scoped_by_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let state = AppState::new(Config {
+            base_dir: temp_dir.path().join("workspace"),
+            sessions_dir: temp_dir.path().join("sessions"),
+            blocked_paths: vec![],
+            ..Config::default()
+        });
+        let session = SessionService::create_session(
+            &state.config,
+            &state.sessions,
+            CreateSessionRequest::default(),
+        )
+        .await
+        .unwrap();
+
+        let (mut parts, _) = Request::builder()
+            .header(SESSION_HEADER, &session.token)
+            .body(())
+            .unwrap()
+            .into_parts();
+        let WorkspaceConfig(config) = WorkspaceConfig::from_request_parts(&mut parts, &state)
+            .await
+            .unwrap();
+        assert_eq!(config.base_dir, state.config.sessions_dir.join(&session.token));
+
+        let (mut parts, _) = Request::builder()
+            .header(SESSION_HEADER, "unknown")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let result = WorkspaceConfig::from_request_parts(&mut parts, &state).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_write_replayed() {
+        use axum::{routing::post, Router};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let state = AppState::new(Config::default());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let app = Router::new()
+            .route(
+                "/files",
+                post(move |body: String| async move {
+                    let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                    format!("{}#{}", body, n)
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                replay_idempotent,
+            ))
+            .with_state(state);
+        let server = axum_test::TestServer::new(app).unwrap();
+
+        let first = server
+            .post("/files")
+            .add_header(IDEMPOTENCY_KEY_HEADER.parse().unwrap(), "k1".parse().unwrap())
+            .text("a")
+            .await;
+        let retry = server
+            .post("/files")
+            .add_header(IDEMPOTENCY_KEY_HEADER.parse().unwrap(), "k1".parse().unwrap())
+            .text("a")
+            .await;
+        assert_eq!(first.text(), "a#1");
+        assert_eq!(retry.text(), "a#1");
+        assert_eq!(retry.header(IDEMPOTENT_REPLAYED_HEADER), "true");
+
+        let reused = server
+            .post("/files")
+            .add_header(IDEMPOTENCY_KEY_HEADER.parse().unwrap(), "k1".parse().unwrap())
+            .text("b")
+            .expect_failure()
+            .await;
+        assert_eq!(reused.status_code(), axum::http::StatusCode::CONFLICT);
+
+        // Without a key every request is executed
+        assert_eq!(server.post("/files").text("a").await.text(), "a#2");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
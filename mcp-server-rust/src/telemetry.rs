@@ -0,0 +1,153 @@
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use crate::config::TelemetryConfig;
+
+const DEFAULT_SERVICE_NAME: &str = "mcp-server-rust";
+const DEFAULT_ENDPOINT: &str = "http://localhost:4318";
+
+/// Effective export settings: `[telemetry]` merged with the standard `OTEL_*` variables
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetrySettings {
+    pub enabled: bool,
+    pub endpoint: String,
+    pub service_name: String,
+}
+
+impl TelemetrySettings {
+    /// Export is enabled by `telemetry.enabled` or by `OTEL_EXPORTER_OTLP_ENDPOINT`;
+    /// `OTEL_SDK_DISABLED=true` always turns it off
+    pub fn resolve(config: &TelemetryConfig, env: impl Fn(&str) -> Option<String>) -> Self {
+        let env_endpoint = env("OTEL_EXPORTER_OTLP_ENDPOINT").filter(|v| !v.trim().is_empty());
+        let disabled = env("OTEL_SDK_DISABLED").is_some_and(|v| v.eq_ignore_ascii_case("true"));
+
+        Self {
+            enabled: !disabled && (config.enabled || env_endpoint.is_some()),
+            endpoint: env_endpoint
+                .or_else(|| config.endpoint.clone())
+                .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string()),
+            service_name: env("OTEL_SERVICE_NAME")
+                .or_else(|| config.service_name.clone())
+                .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string()),
+        }
+    }
+}
+
+/// Flushes pending spans when dropped
+pub struct TelemetryGuard {
+    #[cfg(feature = "otel")]
+    active: bool,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if self.active {
+            opentelemetry::global::shutdown_tracer_provider();
+        }
+    }
+}
+
+/// Installs the global subscriber: env filter, fmt output and, when enabled, the OTLP exporter
+pub fn init(config: &TelemetryConfig) -> TelemetryGuard {
+    let settings = TelemetrySettings::resolve(config, |name| std::env::var(name).ok());
+
+    let registry = tracing_subscriber::registry()
+        .with(
+            EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "mcp_server_rust=debug,tower_http=debug".into()),
+        )
+        .with(tracing_subscriber::fmt::layer());
+
+    #[cfg(feature = "otel")]
+    {
+        let exporter = settings.enabled.then(|| otel::layer(&settings));
+        match exporter {
+            Some(Ok(layer)) => {
+                registry.with(layer).init();
+                tracing::info!(
+                    "Exporting traces to {} as {}",
+                    settings.endpoint,
+                    settings.service_name
+                );
+                TelemetryGuard { active: true }
+            }
+            Some(Err(e)) => {
+                registry.init();
+                tracing::warn!("Trace export disabled: {}", e);
+                TelemetryGuard { active: false }
+            }
+            None => {
+                registry.init();
+                TelemetryGuard { active: false }
+            }
+        }
+    }
+    #[cfg(not(feature = "otel"))]
+    {
+        registry.init();
+        if settings.enabled {
+            tracing::warn!("Trace export requested, but the server was built without the `otel` feature");
+        }
+        TelemetryGuard {}
+    }
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use opentelemetry::{trace::TraceError, KeyValue};
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::{runtime, trace, Resource};
+    use tracing_subscriber::registry::LookupSpan;
+
+    use super::TelemetrySettings;
+
+    /// tracing -> OTLP/HTTP layer with batched export
+    pub fn layer<S>(
+        settings: &TelemetrySettings,
+    ) -> Result<impl tracing_subscriber::Layer<S>, TraceError>
+    where
+        S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .http()
+                    .with_endpoint(&settings.endpoint),
+            )
+            .with_trace_config(trace::config().with_resource(Resource::new(vec![
+                KeyValue::new("service.name", settings.service_name.clone()),
+            ])))
+            .install_batch(runtime::Tokio)?;
+
+        Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_resolution() {
+        let config = TelemetryConfig {
+            enabled: true,
+            endpoint: Some("http://tempo:4318".to_string()),
+            service_name: None,
+        };
+
+        let settings = TelemetrySettings::resolve(&config, |_| None);
+        assert!(settings.enabled);
+        assert_eq!(settings.endpoint, "http://tempo:4318");
+        assert_eq!(settings.service_name, DEFAULT_SERVICE_NAME);
+
+        let env = |name: &str| match name {
+            "OTEL_EXPORTER_OTLP_ENDPOINT" => Some("http://collector:4318".to_string()),
+            "OTEL_SDK_DISABLED" => Some("TRUE".to_string()),
+            _ => None,
+        };
+        let settings = TelemetrySettings::resolve(&config, env);
+        assert!(!settings.enabled);
+        assert_eq!(settings.endpoint, "http://collector:4318");
+    }
+}
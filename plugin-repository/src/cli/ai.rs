@@ -1,9 +1,14 @@
+use chrono::NaiveDate;
 use clap::{Parser, Subcommand};
 
 #[derive(Parser, Debug)]
 pub struct AiCommand {
     #[command(subcommand)]
     pub subcommand: AiSubcommand,
+
+    /// Бюджет запуска в валюте [llm] currency: запросы сверх оценки отклоняются
+    #[arg(long, global = true, value_name = "AMOUNT")]
+    pub max_cost: Option<f64>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -14,18 +19,40 @@ pub enum AiSubcommand {
     SuggestVersion(SuggestVersionCommand),
     /// Генерация release notes
     ReleaseNotes(ReleaseNotesCommand),
+    /// Вопрос по истории репозитория с цитатами коммитов
+    Ask(AskCommand),
+    /// Код-ревью diff между двумя ref: риски, недостающие тесты, несовместимые изменения API
+    Review(ReviewCommand),
+    /// Проверка всех настроенных LLM провайдеров (для CI); код выхода 1 при сбое
+    Health(HealthCommand),
 }
 
 #[derive(Parser, Debug)]
 pub struct ChangelogCommand {
-    /// Начальный тег для анализа
+    /// Начальный тег или алиас (last-release, previous-release, last-minor, last-major, v1.2)
     #[arg(long)]
     pub since: Option<String>,
 
-    /// Конечный тег для анализа
+    /// Конечный тег или алиас
     #[arg(long)]
     pub to: Option<String>,
 
+    /// Диапазон вида v1.2..v1.4 (вместо --since/--to)
+    #[arg(long, conflicts_with_all = ["since", "to"])]
+    pub between: Option<String>,
+
+    /// Коммиты начиная с даты (YYYY-MM-DD); без --since/--between тег последнего релиза не учитывается
+    #[arg(long)]
+    pub since_date: Option<NaiveDate>,
+
+    /// Коммиты до даты (YYYY-MM-DD), не включая ее
+    #[arg(long)]
+    pub until_date: Option<NaiveDate>,
+
+    /// Только коммиты автора (имя или email, можно указать несколько раз)
+    #[arg(long = "author")]
+    pub authors: Vec<String>,
+
     /// Подробный вывод
     #[arg(long)]
     pub verbose: bool,
@@ -37,6 +64,14 @@ pub struct ChangelogCommand {
     /// Сохранить changelog в файл
     #[arg(long)]
     pub output: Option<String>,
+
+    /// Печатать ответ модели по мере генерации
+    #[arg(long)]
+    pub stream: bool,
+
+    /// Описать коммиты по их diff перед генерацией (как [changelog.diff_summary] enabled = true)
+    #[arg(long, conflicts_with = "use_git_analysis")]
+    pub diffs: bool,
 }
 
 #[derive(Parser, Debug)]
@@ -67,4 +102,60 @@ pub struct ReleaseNotesCommand {
     /// Сохранить release notes в файл
     #[arg(long)]
     pub output: Option<String>,
-}
\ No newline at end of file
+
+    /// Печатать ответ модели по мере генерации
+    #[arg(long)]
+    pub stream: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct AskCommand {
+    /// Вопрос, например "что изменилось в deployer с v0.3?"
+    pub question: String,
+
+    /// Начальный тег или алиас; по умолчанию версия из вопроса, если она упомянута
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Сколько последних коммитов просматривать
+    #[arg(long, default_value = "300")]
+    pub limit: usize,
+
+    /// Сколько релевантных коммитов передать LLM
+    #[arg(long, default_value = "25")]
+    pub top: usize,
+
+    /// Формат вывода
+    #[arg(long, default_value = "table")]
+    pub format: String,
+}
+
+#[derive(Parser, Debug)]
+pub struct ReviewCommand {
+    /// Начальный тег или алиас (last-release, previous-release, v1.2)
+    #[arg(long, default_value = "last-release")]
+    pub from: String,
+
+    /// Конечный ref
+    #[arg(long, default_value = "HEAD")]
+    pub to: String,
+
+    /// Предел одной части diff в символах: каждая часть — отдельный запрос к LLM
+    #[arg(long, default_value = "12000")]
+    pub chunk_chars: usize,
+
+    /// Формат вывода
+    #[arg(long, default_value = "table", value_parser = ["table", "json", "markdown"])]
+    pub format: String,
+
+    /// Сохранить ревью в файл (Markdown; JSON при --format json)
+    #[arg(long)]
+    pub output: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct HealthCommand {
+    /// Формат вывода
+    #[arg(long, default_value = "table", value_parser = ["table", "json"])]
+    pub format: String,
+}
@@ -9,4 +9,8 @@ pub struct BuildCommand {
     /// Профиль сборки
     #[arg(short, long, default_value = "release")]
     pub profile: String,
+
+    /// Собрать в workspace MCP-сервера из [build.mcp] вместо локальной машины
+    #[arg(long)]
+    pub mcp_workspace: bool,
 }
\ No newline at end of file
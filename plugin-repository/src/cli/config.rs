@@ -0,0 +1,24 @@
+use clap::{Parser, Subcommand};
+
+#[derive(Parser, Debug)]
+#[command(about = "Обслуживание config.toml")]
+pub struct ConfigCommand {
+    #[command(subcommand)]
+    pub action: ConfigAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Переписать config.toml под текущую схему: перенести устаревшие ключи
+    Migrate(MigrateCommand),
+}
+
+#[derive(Parser, Debug)]
+#[command(
+    long_about = "Переносит устаревшие ключи на новые места и проставляет schema_version. Комментарии, порядок секций и ссылки на переменные окружения (${VAR}) сохраняются; исходный файл копируется в <config>.bak."
+)]
+pub struct MigrateCommand {
+    /// Показать изменения и итоговый файл, не записывая его
+    #[arg(long)]
+    pub dry_run: bool,
+}
@@ -1,7 +1,10 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 #[derive(Parser, Debug)]
 pub struct DeployCommand {
+    #[command(subcommand)]
+    pub action: Option<DeployAction>,
+
     /// Принудительное развертывание
     #[arg(long)]
     pub force: bool,
@@ -13,4 +16,49 @@ pub struct DeployCommand {
     /// Пропуск валидации
     #[arg(long)]
     pub skip_validation: bool,
+
+    /// Публиковать несмотря на конфликты id/версии в updatePlugins.xml
+    #[arg(long)]
+    pub allow_republish: bool,
+
+    /// Канал публикации для {channel} в deploy_path (по умолчанию repository.channel)
+    #[arg(long)]
+    pub channel: Option<String>,
+
+    /// Перед деплоем перенести опубликованные артефакты в раскладку текущего deploy_path
+    #[arg(long)]
+    pub migrate_layout: bool,
+
+    /// Формат отчета деплоя: text или json
+    #[arg(long, default_value = "text", value_parser = ["text", "json"])]
+    pub output: String,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DeployAction {
+    /// Переключить updatePlugins.xml на ранее загруженную версию
+    Promote(PromoteCommand),
+}
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Переключение репозитория на ранее опубликованную версию",
+    long_about = "Перенаправляет запись плагина в updatePlugins.xml на уже загруженный артефакт указанной версии без пересборки (например, если новый релиз оказался сломан). Проверяет, что артефакт есть на сервере, сохраняет бэкап XML и пишет запись в журнал аудита .deploy-plugin/audit.jsonl."
+)]
+pub struct PromoteCommand {
+    /// Версия, на которую переключается репозиторий
+    #[arg(long)]
+    pub version: String,
+
+    /// Канал публикации для {channel} в deploy_path (по умолчанию repository.channel)
+    #[arg(long)]
+    pub channel: Option<String>,
+
+    /// Причина переключения для журнала аудита
+    #[arg(long)]
+    pub reason: Option<String>,
+
+    /// Показать итоговый updatePlugins.xml без изменений на сервере
+    #[arg(long)]
+    pub dry_run: bool,
 }
\ No newline at end of file
@@ -3,13 +3,17 @@ use clap::Parser;
 #[derive(Parser, Debug)]
 #[command(
     about = "Полный цикл публикации: build -> release -> deploy",
-    long_about = "Выполняет сборку артефакта с указанной или автоматически определенной версией, создает git-релиз и деплоит в репозиторий."
+    long_about = "Выполняет сборку артефакта с указанной или автоматически определенной версией, создает git-релиз и деплоит в репозиторий. Если после последнего тега нет коммитов, завершается до сборки с кодом выхода 3 (--force публикует все равно)."
 )]
 pub struct PublishCommand {
     /// Версия плагина (приоритетнее auto-version)
     #[arg(long)]
     pub version: Option<String>,
 
+    /// Файл с версией, вычисленной вне инструмента (альтернатива — env DEPLOY_PLUGIN_VERSION)
+    #[arg(long, value_name = "PATH", conflicts_with = "version")]
+    pub version_file: Option<std::path::PathBuf>,
+
     /// Автоматически определить следующую версию и создать релиз
     #[arg(long)]
     pub auto_version: bool,
@@ -18,7 +22,11 @@ pub struct PublishCommand {
     #[arg(short, long, default_value = "release")]
     pub profile: String,
 
-    /// Принудительное создание релиза/деплой
+    /// Собрать в workspace MCP-сервера из [build.mcp] вместо локальной машины
+    #[arg(long)]
+    pub mcp_workspace: bool,
+
+    /// Принудительное создание релиза/деплой, в том числе без новых коммитов после последнего тега
     #[arg(long)]
     pub force: bool,
 
@@ -26,15 +34,47 @@ pub struct PublishCommand {
     #[arg(long)]
     pub rollback_on_failure: bool,
 
+    /// Публиковать несмотря на конфликты id/версии в updatePlugins.xml
+    #[arg(long)]
+    pub allow_republish: bool,
+
+    /// Канал публикации для {channel} в deploy_path (по умолчанию repository.channel)
+    #[arg(long)]
+    pub channel: Option<String>,
+
     /// Пропустить валидацию конфигурации
     #[arg(long)]
     pub skip_validation: bool,
 
-    /// Пробный запуск (release с --dry-run, без деплоя)
+    /// Пробный запуск без релиза и деплоя: формирует бандл для ревью
     #[arg(long)]
     pub dry_run: bool,
 
+    /// Директория бандла dry-run (по умолчанию target/dry-run/<version>)
+    #[arg(long, requires = "dry_run")]
+    pub bundle_dir: Option<std::path::PathBuf>,
+
+    /// Не обогащать plugin.xml в бандле dry-run: оставить его как в артефакте
+    #[arg(long, requires = "dry_run", conflicts_with = "enrich_dry_run")]
+    pub no_enrich: bool,
+
+    /// Только показать дифф обогащения plugin.xml (plugin.xml.diff), сам plugin.xml в бандле не менять
+    #[arg(long, requires = "dry_run")]
+    pub enrich_dry_run: bool,
+
+    /// Загрузить стенограмму запросов к LLM на сервер рядом с артефактом
+    #[arg(long, conflicts_with = "no_ai")]
+    pub upload_transcript: bool,
+
     /// Отключить AI-обогащение (по умолчанию включено)
     #[arg(long = "no-ai")]
     pub no_ai: bool,
+
+    /// Общий лимит времени на весь цикл (например 20m, 1h30m); по истечении деплой откатывается
+    #[arg(long, value_name = "DURATION")]
+    pub max_duration: Option<String>,
+
+    /// Бюджет на запросы к LLM в валюте [llm] currency; при превышении публикация прерывается
+    #[arg(long, value_name = "AMOUNT", conflicts_with = "no_ai")]
+    pub max_cost: Option<f64>,
 }
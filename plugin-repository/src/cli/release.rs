@@ -1,7 +1,10 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 #[derive(Parser, Debug)]
 pub struct ReleaseCommand {
+    #[command(subcommand)]
+    pub action: Option<ReleaseAction>,
+
     /// Предварительный запуск без реальных изменений
     #[arg(long)]
     pub dry_run: bool,
@@ -42,11 +45,118 @@ pub struct ReleaseCommand {
     #[arg(long, default_value = "10")]
     pub limit: usize,
 
-    /// Откатить указанный релиз
+    /// Откатить указанный релиз (только тег; индекс и артефакт откатывает команда rollback)
     #[arg(long)]
     pub rollback: Option<String>,
 
     /// Принудительно создать релиз (игнорировать предупреждения)
     #[arg(long)]
     pub force: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ReleaseAction {
+    /// Проверка готовности к релизу (гейт для CI)
+    Readiness(ReadinessCommand),
+    /// Сохраненные материалы релиза
+    Show(ShowCommand),
+    /// Ветка релиза для patch-версий
+    Branch(BranchCommand),
+    /// Перенос исправлений в ветку релиза и черновик patch-релиза
+    Backport(BackportCommand),
+}
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Перенос исправлений в ветку релиза",
+    long_about = "Переносит коммиты в ветку релиза (cherry-pick -x) и оставляет ее текущей. Тривиальные конфликты разрешаются автоматически: конфликты в пробелах и в файлах из [git] backport_keep_paths (остается версия ветки). Затем собирает плагин со следующей patch-версией линии и сохраняет черновик релиза (changelog, release notes, build.json, backport.json) в .deploy-plugin/releases/<version>. Тег не создается: после проверки выполните release --version <version>."
+)]
+pub struct BackportCommand {
+    /// Коммиты для переноса (в порядке применения)
+    #[arg(required = true)]
+    pub commits: Vec<String>,
+
+    /// Ветка релиза: линия major.minor (2.1) или имя ветки (release/2.1)
+    #[arg(long)]
+    pub to: String,
+
+    /// Не собирать плагин
+    #[arg(long)]
+    pub no_build: bool,
+
+    /// Профиль сборки
+    #[arg(long, default_value = "release")]
+    pub profile: String,
+
+    /// Отправить ветку в origin после переноса
+    #[arg(long)]
+    pub push: bool,
+}
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Ветка релиза для patch-версий",
+    long_about = "Создает ветку release/<major.minor> от выбранного ref, переносит в нее коммиты (--pick и отмеченные трейлером `Release-Branch: 2.1` с --pick-marked) и отправляет в origin. На ветке релиза команды release, publish и run выпускают только patch-версии ее линии, а changelog считается от последнего тега линии."
+)]
+pub struct BranchCommand {
+    /// Линия релизов major.minor (например, 2.1)
+    #[arg(long)]
+    pub version: String,
+
+    /// Ref, от которого создается ветка (по умолчанию — тег <major.minor>.0, иначе main_branch)
+    #[arg(long)]
+    pub from: Option<String>,
+
+    /// Коммит для переноса в ветку (можно повторять)
+    #[arg(long = "pick")]
+    pub picks: Vec<String>,
+
+    /// Перенести коммиты main_branch с трейлером `Release-Branch: <major.minor>`
+    #[arg(long)]
+    pub pick_marked: bool,
+
+    /// Не отправлять ветку в origin
+    #[arg(long)]
+    pub no_push: bool,
+
+    /// Показать план без создания ветки
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Сохраненные материалы релиза",
+    long_about = "Показывает, что сохранено для версии в .deploy-plugin/releases/<version>: changelog, release notes, отчет о готовности, результат сборки, отчет деплоя, стенограмму LLM и контрольные суммы. С --file выводит содержимое одного файла. Форматы вывода: table, json."
+)]
+pub struct ShowCommand {
+    /// Версия релиза
+    pub version: String,
+
+    /// Вывести содержимое файла (например, changelog.md)
+    #[arg(long)]
+    pub file: Option<String>,
+
+    /// Формат вывода: table, json
+    #[arg(long, default_value = "table")]
+    pub format: String,
+}
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Проверка готовности к релизу",
+    long_about = "Оценивает изменения с последнего релиза (breaking changes, тесты, документация, сложность) и завершается с ошибкой, если релиз не готов. Форматы вывода: table, json, github (аннотации GitHub Actions)."
+)]
+pub struct ReadinessCommand {
+    /// Версия для отчета (по умолчанию — unreleased)
+    #[arg(long)]
+    pub version: Option<String>,
+
+    /// Формат вывода: table, json, github
+    #[arg(long, default_value = "table")]
+    pub format: String,
+
+    /// Считать «требует внимания» непройденным гейтом
+    #[arg(long)]
+    pub strict: bool,
 }
\ No newline at end of file
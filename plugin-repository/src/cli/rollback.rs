@@ -0,0 +1,35 @@
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Откат опубликованной версии",
+    long_about = "Возвращает запись плагина в updatePlugins.xml на предыдущий релиз (артефакт должен оставаться на сервере), удаляет тег версии локально и в origin, с --delete-artifact удаляет с сервера и сам артефакт. Операция записывается в журнал аудита .deploy-plugin/audit.jsonl. Вместо release --rollback (только тег) и deploy promote (только индекс)."
+)]
+pub struct RollbackCommand {
+    /// Откатываемая версия (сейчас опубликована в updatePlugins.xml)
+    pub version: String,
+
+    /// Версия, на которую возвращается индекс (по умолчанию предыдущий релизный тег)
+    #[arg(long)]
+    pub to: Option<String>,
+
+    /// Канал публикации для {channel} в deploy_path (по умолчанию repository.channel)
+    #[arg(long)]
+    pub channel: Option<String>,
+
+    /// Удалить с сервера артефакт откатываемой версии и его приложения
+    #[arg(long)]
+    pub delete_artifact: bool,
+
+    /// Оставить git тег версии
+    #[arg(long)]
+    pub keep_tag: bool,
+
+    /// Причина отката для журнала аудита
+    #[arg(long)]
+    pub reason: Option<String>,
+
+    /// Показать план отката без изменений на сервере и в git
+    #[arg(long)]
+    pub dry_run: bool,
+}
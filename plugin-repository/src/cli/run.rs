@@ -0,0 +1,31 @@
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Запуск пайплайна из конфигурации",
+    long_about = "Выполняет пайплайн, описанный в [pipelines.<имя>] config.toml: этапы build, verify, validate, notes, release и deploy в заданном порядке с опциями каждого этапа. Без имени выводит список пайплайнов."
+)]
+pub struct RunCommand {
+    /// Имя пайплайна из [pipelines]
+    pub pipeline: Option<String>,
+
+    /// Версия плагина (нужна этапам build, notes и release)
+    #[arg(long)]
+    pub version: Option<String>,
+
+    /// Файл с версией, вычисленной вне инструмента (альтернатива — env DEPLOY_PLUGIN_VERSION)
+    #[arg(long, value_name = "PATH", conflicts_with = "version")]
+    pub version_file: Option<std::path::PathBuf>,
+
+    /// Автоматически определить следующую версию
+    #[arg(long)]
+    pub auto_version: bool,
+
+    /// Показать этапы без выполнения
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Общий лимит времени на пайплайн (например 20m, 1h30m)
+    #[arg(long, value_name = "DURATION")]
+    pub max_duration: Option<String>,
+}
@@ -0,0 +1,12 @@
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "HTTP API для запуска публикации",
+    long_about = "Запускает HTTP API поверх пайплайна publish: запуск публикации, прогресс по этапам и отчеты о прошлых запусках. Запросы аутентифицируются Bearer-токеном из [server] token. Одновременно выполняется не более одной публикации."
+)]
+pub struct ServeCommand {
+    /// Адрес прослушивания (по умолчанию [server] bind)
+    #[arg(long)]
+    pub bind: Option<String>,
+}
@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -6,6 +6,9 @@ use clap::Parser;
     long_about = "Показывает сводку по текущему git-репозиторию (ветка, теги) и список последних релизов. Поддерживает форматы вывода: table, json."
 )]
 pub struct StatusCommand {
+    #[command(subcommand)]
+    pub action: Option<StatusAction>,
+
     /// Показать последние релизы
     #[arg(long)]
     pub releases: bool,
@@ -17,4 +20,21 @@ pub struct StatusCommand {
     /// Формат вывода
     #[arg(long, default_value = "table")]
     pub format: String,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum StatusAction {
+    /// Изменения с последнего релизного тега
+    Unreleased(UnreleasedCommand),
+}
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Изменения с последнего релизного тега",
+    long_about = "Показывает коммиты с последнего релизного тега, сгруппированные по типу изменений, и рекомендуемое повышение версии. Код выхода 0 — есть изменения, требующие релиза (breaking, feature, fix, improvement); 3 — релизить нечего."
+)]
+pub struct UnreleasedCommand {
+    /// Формат вывода: table, json
+    #[arg(long, default_value = "table")]
+    pub format: String,
 }
\ No newline at end of file
@@ -3,18 +3,31 @@ use clap::Parser;
 #[derive(Parser, Debug)]
 #[command(
     about = "Проверка конфигурации и окружения",
-    long_about = "Проверяет корректность config.toml и переменных окружения. Доступны частичные проверки: метаданные и совместимость."
+    long_about = "Проверяет корректность config.toml и переменных окружения. Доступны частичные проверки: метаданные и совместимость. --scope ограничивает проверку секциями, которые использует команда (build, ai, deploy). С --remote сверяет артефакты на сервере с индексом SHA256SUMS. --fix исправляет безопасные находки и сообщает, что изменено."
 )]
 pub struct ValidateCommand {
     /// Валидация метаданных плагина
     #[arg(long)]
     pub metadata: bool,
 
-    /// Проверка совместимости
+    /// Проверка совместимости since/until-build с IntelliJ Gradle DSL
     #[arg(long)]
     pub compatibility: bool,
 
     /// Полная валидация
     #[arg(long)]
     pub full: bool,
+
+    /// Проверять только секции для указанных команд: build, ai, deploy (через запятую)
+    #[arg(long, value_delimiter = ',')]
+    pub scope: Vec<String>,
+
+    /// Сверка опубликованных артефактов с SHA256SUMS на сервере
+    #[arg(long)]
+    pub remote: bool,
+
+    /// Исправить безопасные находки: <id> в plugin.xml, права SSH-ключа, а с --remote —
+    /// порядок записей updatePlugins.xml и недостающие суммы в SHA256SUMS
+    #[arg(long)]
+    pub fix: bool,
 }
\ No newline at end of file
@@ -0,0 +1,36 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+use crate::core::inspector::DEFAULT_MAX_RESOURCE_SIZE;
+use crate::core::integrity::DEFAULT_MANIFEST_PATH;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Глубокая проверка ZIP артефакта плагина",
+    long_about = "Проверяет целостность ZIP, наличие и корректность plugin.xml (в том числе внутри JAR), версию байткода относительно since-build, дубликаты классов между JAR и слишком большие ресурсы. С --integrity сверяет записи с встроенным манифестом целостности и проверяет его подпись. Поддерживает форматы вывода: table, json."
+)]
+pub struct VerifyArtifactCommand {
+    /// Путь к ZIP артефакту плагина
+    pub path: PathBuf,
+
+    /// Порог размера ресурса в байтах
+    #[arg(long, default_value_t = DEFAULT_MAX_RESOURCE_SIZE)]
+    pub max_resource_size: u64,
+
+    /// Формат вывода
+    #[arg(long, default_value = "table")]
+    pub format: String,
+
+    /// Сверить записи архива с манифестом целостности
+    #[arg(long)]
+    pub integrity: bool,
+
+    /// Путь манифеста внутри архива
+    #[arg(long, default_value = DEFAULT_MANIFEST_PATH, requires = "integrity")]
+    pub integrity_manifest: String,
+
+    /// Команда проверки подписи манифеста ({input} — манифест, {signature} — подпись),
+    /// например "gpg --verify {signature} {input}"
+    #[arg(long, requires = "integrity")]
+    pub verify_command: Option<String>,
+}
@@ -0,0 +1,25 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Проверка воспроизводимости сборки",
+    long_about = "Собирает артефакт дважды (или сравнивает со сборкой-эталоном) и сравнивает содержимое ZIP без учета времени модификации и порядка записей, включая содержимое вложенных JAR. Завершается ошибкой и выводит отличающиеся записи, если сборки не совпадают. Поддерживает форматы вывода: table, json."
+)]
+pub struct VerifyReproducibleCommand {
+    /// Эталонный артефакт: вместо второй сборки текущая сравнивается с ним
+    #[arg(long)]
+    pub reference: Option<PathBuf>,
+
+    /// Версия плагина для сборки
+    #[arg(long)]
+    pub version: Option<String>,
+
+    /// Профиль сборки
+    #[arg(short, long, default_value = "release")]
+    pub profile: String,
+
+    /// Формат вывода
+    #[arg(long, default_value = "table")]
+    pub format: String,
+}
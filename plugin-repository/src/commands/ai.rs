@@ -2,9 +2,15 @@ use anyhow::{Context, Result};
 use tracing::info;
 use colored::*;
 use crate::config::parser::Config;
-use crate::core::llm::agents::{LLMAgentManager, PluginInfo};
-use crate::cli::ai::{AiCommand, AiSubcommand, ChangelogCommand, SuggestVersionCommand, ReleaseNotesCommand};
-use crate::git::GitRepository;
+use crate::config::validator::Capability;
+use crate::core::llm::agents::{LLMAgentManager, PluginInfo, StreamSink};
+use crate::core::llm::cost::CostGuard;
+use crate::cli::ai::{AiCommand, AiSubcommand, AskCommand, ChangelogCommand, HealthCommand, ReviewCommand, SuggestVersionCommand, ReleaseNotesCommand};
+use crate::core::llm::health::{self, HealthReport};
+use crate::core::llm::qa::{self, RepoAnswer};
+use crate::core::llm::review::CodeReview;
+use crate::git::{GitRepository, LogWindow};
+use crate::utils::clock::system_clock;
 
 /// Обработчик AI команд
 pub async fn handle_ai_command(
@@ -14,36 +20,49 @@ pub async fn handle_ai_command(
     info!("🤖 Запуск AI команды");
 
     // Загружаем конфигурацию
-    let config = Config::load_from_file(config_file)
+    let mut config = Config::load_from_file(config_file)
         .with_context(|| format!("Не удалось загрузить конфигурацию из файла: {}", config_file))?;
+    if command.max_cost.is_some() {
+        config.llm.max_cost = command.max_cost;
+    }
+    if let AiSubcommand::Changelog(ChangelogCommand { diffs: true, .. }) = &command.subcommand {
+        config.changelog.diff_summary.enabled = true;
+    }
 
     // Валидируем конфигурацию
-    config.validate()
+    config.validate_for(&[Capability::Ai])
         .with_context(|| "Валидация конфигурации не пройдена")?;
 
+    // Диагностика провайдеров не требует git репозитория
+    let command = match command.subcommand {
+        AiSubcommand::Health(cmd) => return handle_health_command(cmd, &config).await,
+        subcommand => subcommand,
+    };
+
     // Создаем Git репозиторий
     let current_dir = std::env::current_dir()
         .context("Не удалось определить текущую директорию")?;
-    let git_repo = GitRepository::new(&current_dir);
+    let git_repo = GitRepository::from_config(&current_dir, &config)?;
 
     // Проверяем, что мы в git репозитории
     if !git_repo.is_valid_repository() {
-        eprintln!("{} Текущая директория не является git репозиторием", "❌".red());
+        out_errln!("{} Текущая директория не является git репозиторием", "❌".red());
         return Err(anyhow::anyhow!("Не git репозиторий"));
     }
 
     // Создаем менеджер LLM агентов
-    let agent_manager = LLMAgentManager::from_config(&config)
+    let agent_manager = LLMAgentManager::from_config(&config, system_clock())
         .context("Не удалось создать менеджер LLM агентов")?;
 
     // Проверяем доступность YandexGPT API
     if !agent_manager.health_check().await? {
-        eprintln!("{} YandexGPT API недоступен. Проверьте API ключ и подключение к интернету.", "❌".red());
+        out_errln!("{} YandexGPT API недоступен. Проверьте API ключ и подключение к интернету.", "❌".red());
         return Err(anyhow::anyhow!("YandexGPT API недоступен"));
     }
 
     // Обрабатываем подкоманды
-    match command.subcommand {
+    let cost = agent_manager.cost();
+    let result = match command {
         AiSubcommand::Changelog(cmd) => {
             handle_changelog_command(cmd, agent_manager, git_repo).await
         }
@@ -53,6 +72,53 @@ pub async fn handle_ai_command(
         AiSubcommand::ReleaseNotes(cmd) => {
             handle_release_notes_command(cmd, agent_manager, git_repo).await
         }
+        AiSubcommand::Ask(cmd) => {
+            handle_ask_command(cmd, agent_manager, git_repo, &config.project.name).await
+        }
+        AiSubcommand::Review(cmd) => {
+            handle_review_command(cmd, agent_manager, git_repo, &config.project.name).await
+        }
+        AiSubcommand::Health(_) => unreachable!("ai health обрабатывается до проверки репозитория"),
+    };
+    print_cost_summary(&cost);
+    result.and(cost.check())
+}
+
+/// Оценка стоимости запросов к LLM за запуск
+pub fn print_cost_summary(cost: &CostGuard) {
+    let report = cost.report();
+    if report.models.is_empty() {
+        return;
+    }
+    outln!("{} Оценка стоимости LLM:", "💸");
+    for line in report.render() {
+        outln!("  {}", line);
+    }
+}
+
+/// Обработчик команды health
+async fn handle_health_command(command: HealthCommand, config: &Config) -> Result<()> {
+    let report = health::check_all(config).await;
+    if command.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report).context("Не удалось сериализовать отчет")?);
+    } else {
+        print_health_report(&report);
+    }
+    if !report.healthy() {
+        return Err(anyhow::anyhow!("Проверка LLM провайдеров не пройдена"));
+    }
+    Ok(())
+}
+
+fn print_health_report(report: &HealthReport) {
+    outln!("🩺 Проверка LLM провайдеров");
+    for check in &report.checks {
+        let mark = if check.passed() { "✅".green() } else { "❌".red() };
+        let model = if check.model.is_empty() { String::new() } else { format!(" {}", check.model.cyan()) };
+        outln!("  {} {} [{}]{} — {} мс", mark, check.provider.bold(), check.target, model, check.latency_ms);
+        if let Some(error) = &check.error {
+            outln!("     {}", error.red());
+        }
     }
 }
 
@@ -62,7 +128,17 @@ async fn handle_changelog_command(
     agent_manager: LLMAgentManager,
     git_repo: GitRepository,
 ) -> Result<()> {
-    println!("🤖 Генерация changelog с анализом Git репозитория");
+    outln!("🤖 Генерация changelog с анализом Git репозитория");
+    let agent_manager = if command.stream { agent_manager.with_stream(stdout_stream()) } else { agent_manager };
+
+    // Окно дат и авторов для дайджестов и отчетов по команде
+    let window = LogWindow {
+        since: command.since_date,
+        until: command.until_date,
+        authors: command.authors.clone(),
+    };
+    window.validate()?;
+    let git_repo = if window.is_empty() { git_repo } else { git_repo.with_window(window.clone()) };
 
     // Получаем текущую ветку
     let current_branch = git_repo.history.get_current_branch().await
@@ -71,11 +147,20 @@ async fn handle_changelog_command(
     // Получаем последний тег
     let latest_tag = git_repo.tags.get_latest_tag().await?;
 
-    // Определяем from и to для анализа
-    let from_tag = command.since.as_ref().or_else(|| latest_tag.as_ref().map(|t| &t.name));
-    let to_tag = command.to.as_deref();
-
-    println!("📊 Анализ изменений: {:?} → {:?}", from_tag, to_tag);
+    // Определяем from и to для анализа (алиасы разрешаются через теги)
+    let (since, to) = git_repo
+        .tags
+        .resolve_range(command.since.as_deref(), command.to.as_deref(), command.between.as_deref())
+        .await?;
+    // Окно по датам само задает границы: от последнего тега отсчитывается только без него
+    let default_tag = if window.has_dates() { None } else { latest_tag.as_ref().map(|t| &t.name) };
+    let from_tag = since.as_ref().or(default_tag);
+    let to_tag = to.as_deref();
+
+    outln!("📊 Анализ изменений: {:?} → {:?}", from_tag, to_tag);
+    if !window.is_empty() {
+        outln!("🗓️ Окно: {}", window.git_args().join(" "));
+    }
 
     // Генерируем changelog через Git анализ
     let changelog = if command.use_git_analysis {
@@ -86,6 +171,9 @@ async fn handle_changelog_command(
         // Используем Git репозиторий для получения данных
         agent_manager.changelog_agent.generate_changelog_from_repo(&git_repo, from_tag.map(|s| s.as_str()), to_tag).await?
     };
+    if command.stream {
+        outln!();
+    }
 
     // Выводим результат
     print_changelog_result(&changelog, command.verbose);
@@ -93,7 +181,7 @@ async fn handle_changelog_command(
     // Если указан выходной файл, сохраняем результат
     if let Some(output_file) = &command.output {
         save_changelog_to_file(&changelog, output_file)?;
-        println!("💾 Changelog сохранен в файл: {}", output_file.green());
+        outln!("💾 Changelog сохранен в файл: {}", output_file.green());
     }
 
     Ok(())
@@ -105,7 +193,7 @@ async fn handle_suggest_version_command(
     agent_manager: LLMAgentManager,
     git_repo: GitRepository,
 ) -> Result<()> {
-    println!("🔍 Анализ изменений для предложения версии");
+    outln!("🔍 Анализ изменений для предложения версии");
 
     // Получаем текущую версию из последнего тега
     let current_version = if let Some(latest_tag) = git_repo.tags.get_latest_tag().await? {
@@ -114,7 +202,7 @@ async fn handle_suggest_version_command(
         command.current_version.clone().unwrap_or_else(|| "1.0.0".to_string())
     };
 
-    println!("📋 Текущая версия: {}", current_version.bright_blue());
+    outln!("📋 Текущая версия: {}", current_version.bright_blue());
 
     // Анализируем версию с использованием Git репозитория
     let analysis = if command.use_semantic_analysis {
@@ -130,7 +218,7 @@ async fn handle_suggest_version_command(
 
     // Если запрошено применение версии
     if command.apply {
-        println!("🚀 Версия обновлена до: {}", analysis.suggested_version.green());
+        outln!("🚀 Версия обновлена до: {}", analysis.suggested_version.green());
         // TODO: Здесь можно добавить логику применения версии (создание тега)
     }
 
@@ -143,7 +231,8 @@ async fn handle_release_notes_command(
     agent_manager: LLMAgentManager,
     git_repo: GitRepository,
 ) -> Result<()> {
-    println!("📝 Генерация release notes с анализом Git");
+    outln!("📝 Генерация release notes с анализом Git");
+    let agent_manager = if _command.stream { agent_manager.with_stream(stdout_stream()) } else { agent_manager };
 
     // Получаем информацию о последнем релизе
     let (analysis, _commits, latest_tag) = git_repo.get_changes_since_last_release().await?;
@@ -169,6 +258,9 @@ async fn handle_release_notes_command(
 
     // Генерируем release notes
     let release_notes = agent_manager.generate_release_notes(&version, &changelog, &plugin_info).await?;
+    if _command.stream {
+        outln!();
+    }
 
     // Выводим результат
     print_release_notes_result(&release_notes, &analysis);
@@ -176,57 +268,193 @@ async fn handle_release_notes_command(
     // Если указан выходной файл, сохраняем результат
     if let Some(output_file) = &_command.output {
         save_release_notes_to_file(&release_notes, output_file)?;
-        println!("💾 Release notes сохранены в файл: {}", output_file.green());
+        outln!("💾 Release notes сохранены в файл: {}", output_file.green());
     }
 
     Ok(())
 }
 
+/// Печатает фрагменты ответа модели в stdout сразу по получении
+fn stdout_stream() -> StreamSink {
+    std::sync::Arc::new(|chunk: &str| {
+        use std::io::Write;
+        let mut stdout = std::io::stdout().lock();
+        let _ = stdout.write_all(chunk.as_bytes());
+        let _ = stdout.flush();
+    })
+}
+
+/// Обработчик команды ask: выбирает релевантные коммиты и отвечает по ним с цитатами
+async fn handle_ask_command(
+    command: AskCommand,
+    agent_manager: LLMAgentManager,
+    git_repo: GitRepository,
+    project_name: &str,
+) -> Result<()> {
+    outln!("🔎 Поиск релевантных коммитов");
+
+    // Нижняя граница: --since или версия, упомянутая в вопросе
+    let since_alias = command.since.clone().or_else(|| qa::version_mention(&command.question));
+    let (since, _) = git_repo.tags.resolve_range(since_alias.as_deref(), None, None).await?;
+    if let Some(since) = &since {
+        outln!("📋 Коммиты начиная с {}", since.bright_blue());
+    }
+
+    let mut commits = match &since {
+        Some(since) => git_repo.history.get_commits_between(Some(since), None).await?,
+        None => git_repo.history.get_recent_commits(command.limit as u32).await?,
+    };
+    commits.truncate(command.limit);
+    let files = git_repo.history.get_commit_files(since.as_deref(), command.limit).await?;
+
+    let context = qa::select_context(&command.question, &commits, &files, command.top);
+    if context.is_empty() {
+        anyhow::bail!("В выбранном диапазоне нет коммитов");
+    }
+    outln!("🤖 Вопрос к LLM по {} из {} коммитов", context.len(), commits.len());
+
+    let answer = agent_manager.ask(project_name, &command.question, context).await?;
+
+    if command.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&answer).context("Не удалось сериализовать ответ")?);
+    } else {
+        print_ask_result(&answer);
+    }
+    Ok(())
+}
+
+/// Обработчик команды review
+async fn handle_review_command(
+    command: ReviewCommand,
+    agent_manager: LLMAgentManager,
+    git_repo: GitRepository,
+    project_name: &str,
+) -> Result<()> {
+    let (from, to) = git_repo.tags.resolve_range(Some(&command.from), Some(&command.to), None).await?;
+    let (from, to) = (from.unwrap_or(command.from), to.unwrap_or(command.to));
+    outln!("🔍 Ревью изменений {}..{}", from.bright_blue(), to.bright_blue());
+
+    let diff = git_repo.history.get_diff(&from, &to).await?;
+    let review = agent_manager
+        .with_review_chunk_chars(command.chunk_chars)
+        .review(project_name, &from, &to, &diff)
+        .await?;
+
+    let json = command.format == "json";
+    match command.format.as_str() {
+        "json" => println!("{}", serde_json::to_string_pretty(&review).context("Не удалось сериализовать ревью")?),
+        "markdown" => out!("{}", review.to_markdown()),
+        _ => print_review_result(&review),
+    }
+    if let Some(output) = &command.output {
+        let content = if json { serde_json::to_string_pretty(&review)? } else { review.to_markdown() };
+        std::fs::write(output, content).with_context(|| format!("Не удалось записать ревью в {}", output))?;
+        outln!("💾 Ревью сохранено в файл: {}", output.green());
+    }
+    Ok(())
+}
+
+/// Выводит замечания ревью по группам
+fn print_review_result(review: &CodeReview) {
+    outln!("{}", "=".repeat(60).bright_black());
+    outln!("🔍 РЕВЬЮ {}..{}: {} файлов, {} частей diff", review.from, review.to, review.files, review.chunks);
+    outln!("{}", "=".repeat(60).bright_black());
+    for risk in &review.risks {
+        let severity = risk.severity.as_deref().unwrap_or("low");
+        let severity = match severity {
+            "high" => severity.red().bold(),
+            "medium" => severity.yellow(),
+            _ => severity.normal(),
+        };
+        outln!("⚠️ [{}] {}: {}", severity, risk.file.bright_blue(), risk.description);
+    }
+    for finding in &review.missing_tests {
+        outln!("🧪 {}: {}", finding.file.bright_blue(), finding.description);
+    }
+    for finding in &review.breaking_api {
+        outln!("💥 {}: {}", finding.file.bright_blue(), finding.description);
+    }
+    if review.is_clean() {
+        outln!("{}", "✅ Замечаний нет".green());
+    }
+    outln!("{}", "=".repeat(60).bright_black());
+}
+
+/// Выводит ответ с источниками
+fn print_ask_result(answer: &RepoAnswer) {
+    outln!("{}", "=".repeat(60).bright_black());
+    outln!("❓ {}", answer.question.bright_white());
+    outln!("{}", "=".repeat(60).bright_black());
+    outln!("{}", answer.answer.trim());
+    outln!();
+
+    if !answer.citations.is_empty() {
+        outln!("📚 Источники:");
+        for commit in &answer.citations {
+            outln!("  {} {} {}", commit.short_hash.yellow(), commit.date.bright_black(), commit.message);
+        }
+    }
+    if !answer.unknown_citations.is_empty() {
+        outln!(
+            "{}",
+            format!("⚠️ Ссылки на коммиты вне контекста: {}", answer.unknown_citations.join(", ")).yellow()
+        );
+    }
+    outln!("{}", "=".repeat(60).bright_black());
+}
+
 /// Выводит результат генерации changelog
 fn print_changelog_result(changelog: &crate::core::llm::agents::GeneratedChangelog, verbose: bool) {
-    println!("{}", "=".repeat(60).bright_black());
-    println!("📋 CHANGELOG v{}", changelog.version);
-    println!("{}", "=".repeat(60).bright_black());
+    outln!("{}", "=".repeat(60).bright_black());
+    outln!("📋 CHANGELOG v{}", changelog.version);
+    outln!("{}", "=".repeat(60).bright_black());
 
     for section in &changelog.sections {
-        println!("\n{} {}", section.emoji, section.title.bright_blue());
-        println!("{}", "-".repeat(40).bright_black());
+        outln!("\n{} {}", section.emoji, section.title.bright_blue());
+        outln!("{}", "-".repeat(40).bright_black());
 
         if section.changes.is_empty() {
-            println!("  Нет изменений в этой категории");
+            outln!("  Нет изменений в этой категории");
+        } else if !section.modules.is_empty() {
+            for module in &section.modules {
+                outln!("  {}", module.name.bold());
+                for change in &module.changes {
+                    outln!("    • {}", change);
+                }
+            }
         } else {
             for change in &section.changes {
-                println!("  • {}", change);
+                outln!("  • {}", change);
             }
         }
     }
 
-    println!("\n📊 Всего изменений: {}", changelog.total_changes);
+    outln!("\n📊 Всего изменений: {}", changelog.total_changes);
 
     if verbose {
-        println!("📄 Полный changelog:");
-        println!("{}", changelog.changelog);
+        outln!("📄 Полный changelog:");
+        outln!("{}", changelog.changelog);
     }
 
-    println!("{}", "=".repeat(60).bright_black());
+    outln!("{}", "=".repeat(60).bright_black());
 }
 
 /// Выводит результат анализа версий
 fn print_version_analysis_result(analysis: &crate::core::llm::agents::VersionAnalysis, current_version: &str) {
-    println!("{}", "=".repeat(60).bright_black());
-    println!("🔍 АНАЛИЗ ВЕРСИИ");
-    println!("{}", "=".repeat(60).bright_black());
+    outln!("{}", "=".repeat(60).bright_black());
+    outln!("🔍 АНАЛИЗ ВЕРСИИ");
+    outln!("{}", "=".repeat(60).bright_black());
 
-    println!("📋 Текущая версия: {}", current_version.bright_blue());
-    println!("📈 Предлагаемая версия: {}", analysis.suggested_version.bright_green());
-    println!("🎯 Уверенность: {:.1}%", analysis.confidence * 100.0);
-    println!("📝 Обоснование: {}", analysis.reasoning);
+    outln!("📋 Текущая версия: {}", current_version.bright_blue());
+    outln!("📈 Предлагаемая версия: {}", analysis.suggested_version.bright_green());
+    outln!("🎯 Уверенность: {:.1}%", analysis.confidence * 100.0);
+    outln!("📝 Обоснование: {}", analysis.reasoning);
 
     if !analysis.change_types.is_empty() {
-        println!("📋 Типы изменений: {}", analysis.change_types.join(", "));
+        outln!("📋 Типы изменений: {}", analysis.change_types.join(", "));
     }
 
-    println!("{}", "=".repeat(60).bright_black());
+    outln!("{}", "=".repeat(60).bright_black());
 }
 
 /// Выводит результат генерации release notes
@@ -234,39 +462,39 @@ fn print_release_notes_result(
     notes: &crate::core::llm::agents::GeneratedReleaseNotes,
     analysis: &crate::git::ReleaseAnalysis,
 ) {
-    println!("{}", "=".repeat(60).bright_black());
-    println!("📝 RELEASE NOTES v{}", notes.version);
-    println!("{}", "=".repeat(60).bright_black());
+    outln!("{}", "=".repeat(60).bright_black());
+    outln!("📝 RELEASE NOTES v{}", notes.version);
+    outln!("{}", "=".repeat(60).bright_black());
 
-    println!("\n{}\n", notes.title.bright_blue().bold());
+    outln!("\n{}\n", notes.title.bright_blue().bold());
 
     if !notes.highlights.is_empty() {
-        println!("🌟 Основные улучшения:");
+        outln!("🌟 Основные улучшения:");
         for highlight in &notes.highlights {
-            println!("  • {}", highlight);
+            outln!("  • {}", highlight);
         }
-        println!();
+        outln!();
     }
 
     if !notes.body.is_empty() {
-        println!("{}", notes.body);
+        outln!("{}", notes.body);
     }
 
-    println!("\n{}", notes.subtitle);
+    outln!("\n{}", notes.subtitle);
 
     // Добавляем статистику из анализа
-    println!("\n📊 Статистика релиза:");
-    println!("  • Всего коммитов: {}", analysis.total_commits);
-    println!("  • Критических изменений: {}", analysis.breaking_changes.len());
+    outln!("\n📊 Статистика релиза:");
+    outln!("  • Всего коммитов: {}", analysis.total_commits);
+    outln!("  • Критических изменений: {}", analysis.breaking_changes.len());
 
     if !analysis.change_summary.is_empty() {
-        println!("  • Типы изменений:");
+        outln!("  • Типы изменений:");
         for (change_type, count) in &analysis.change_summary {
-            println!("    - {}: {}", change_type.name(), count);
+            outln!("    - {}: {}", change_type.name(), count);
         }
     }
 
-    println!("{}", "=".repeat(60).bright_black());
+    outln!("{}", "=".repeat(60).bright_black());
 }
 
 /// Сохраняет changelog в файл
@@ -2,6 +2,8 @@ use anyhow::{Context, Result};
 use tracing::info;
 use colored::*;
 use crate::config::parser::Config;
+use crate::config::validator::Capability;
+use crate::core::build_profile::BuildProfile;
 use crate::core::builder::PluginBuilder;
 use crate::cli::build::BuildCommand;
 
@@ -13,49 +15,54 @@ pub async fn handle_build_command(
     info!("🔨 Запуск команды сборки плагина");
 
     // Загружаем конфигурацию
-    let config = Config::load_from_file(config_file)
+    let mut config = Config::load_from_file(config_file)
         .with_context(|| format!("Не удалось загрузить конфигурацию из файла: {}", config_file))?;
+    if command.mcp_workspace {
+        config.build.mcp.enabled = true;
+    }
+    let profile = BuildProfile::select(&mut config, &command.profile)?;
+    let version = command.version.as_deref().map(|v| profile.version(v));
 
     // Валидируем конфигурацию
-    config.validate()
+    config.validate_for(&[Capability::Build])
         .with_context(|| "Валидация конфигурации не пройдена")?;
 
     // Определяем корневую директорию проекта
     let project_root = std::env::current_dir()
         .context("Не удалось определить текущую директорию")?;
 
-    println!("📁 Директория проекта: {}", project_root.display());
-    println!("🔧 Профиль сборки: {}", command.profile);
+    outln!("📁 Директория проекта: {}", project_root.display());
+    outln!("🔧 Профиль сборки: {}", command.profile);
 
-    if let Some(ref version) = command.version {
-        println!("🏷️  Версия: {}", version);
+    if let Some(ref version) = version {
+        outln!("🏷️  Версия: {}", version);
     }
 
-    println!();
+    outln!();
 
     // Создаем билдер
     let builder = PluginBuilder::new(config, project_root);
 
     // Выполняем сборку
-    let result = builder.build(command.version, &command.profile).await?;
+    let result = builder.build(version, &command.profile).await?;
 
     // Выводим результаты
     print_build_result(&result);
 
     if result.success {
-        println!("\n✅ Сборка успешно завершена!");
+        outln!("\n✅ Сборка успешно завершена!");
         Ok(())
     } else {
-        println!("\n❌ Сборка завершилась с ошибками!");
+        outln!("\n❌ Сборка завершилась с ошибками!");
         Err(anyhow::anyhow!("Сборка не удалась"))
     }
 }
 
 /// Выводит результат сборки в удобном формате
 fn print_build_result(result: &crate::models::plugin::BuildResult) {
-    println!("{}", "=".repeat(60).bright_black());
-    println!("📊 РЕЗУЛЬТАТЫ СБОРКИ");
-    println!("{}", "=".repeat(60).bright_black());
+    outln!("{}", "=".repeat(60).bright_black());
+    outln!("📊 РЕЗУЛЬТАТЫ СБОРКИ");
+    outln!("{}", "=".repeat(60).bright_black());
 
     // Статус
     let status = if result.success {
@@ -63,41 +70,56 @@ fn print_build_result(result: &crate::models::plugin::BuildResult) {
     } else {
         "❌ ОШИБКА".red()
     };
-    println!("Статус: {}", status);
+    outln!("Статус: {}", status);
 
     // Время сборки
-    println!("Время: {}", result.build_time.format("%Y-%m-%d %H:%M:%S"));
+    outln!("Время: {}", result.build_time.format("%Y-%m-%d %H:%M:%S"));
 
     // Артефакт
     if let Some(ref artifact) = result.artifact {
-        println!("\n📦 АРТЕФАКТ:");
-        println!("  Имя файла: {}", artifact.file_name.bright_blue());
-        println!("  Размер: {} bytes", artifact.file_size);
-        println!("  Версия: {}", artifact.version.bright_green());
-        println!("  SHA256: {}", artifact.checksum_sha256.bright_black());
-        println!("  Путь: {}", artifact.file_path.display());
+        outln!("\n📦 АРТЕФАКТ:");
+        outln!("  Имя файла: {}", artifact.file_name.bright_blue());
+        outln!("  Размер: {} bytes", artifact.file_size);
+        outln!("  Версия: {}", artifact.version.bright_green());
+        outln!("  SHA256: {}", artifact.checksum_sha256.bright_black());
+        outln!("  Путь: {}", artifact.file_path.display());
     } else {
-        println!("\n❌ Артефакт не создан");
+        outln!("\n❌ Артефакт не создан");
+    }
+
+    // Окружение сборки
+    if let Some(ref env) = result.environment {
+        outln!("\n🧭 ОКРУЖЕНИЕ:");
+        outln!("  ОС: {} {}", env.os, env.arch);
+        outln!("  JDK: {}", env.jdk.as_deref().unwrap_or("не определен"));
+        outln!("  Сборка: {}", env.build_tool.as_deref().unwrap_or("не определена"));
+        if let Some(ref platform) = env.target_platform {
+            outln!("  Платформа: {}", platform);
+        }
+        if let Some(ref commit) = env.git_commit {
+            let dirty = if env.git_dirty == Some(true) { " (есть незакоммиченные изменения)".yellow().to_string() } else { String::new() };
+            outln!("  Коммит: {}{}", commit.bright_black(), dirty);
+        }
     }
 
     // Логи
     if !result.logs.is_empty() {
-        println!("\n📝 ЛОГИ СБОРКИ:");
+        outln!("\n📝 ЛОГИ СБОРКИ:");
         for (i, log) in result.logs.iter().take(10).enumerate() {
-            println!("  {} {}", (i + 1).to_string().bright_black(), log);
+            outln!("  {} {}", (i + 1).to_string().bright_black(), log);
         }
         if result.logs.len() > 10 {
-            println!("  ... и еще {} сообщений", result.logs.len() - 10);
+            outln!("  ... и еще {} сообщений", result.logs.len() - 10);
         }
     }
 
     // Ошибки
     if !result.errors.is_empty() {
-        println!("\n❌ ОШИБКИ:");
+        outln!("\n❌ ОШИБКИ:");
         for (i, error) in result.errors.iter().enumerate() {
-            println!("  {}. {}", (i + 1).to_string().bright_red(), error.bright_red());
+            outln!("  {}. {}", (i + 1).to_string().bright_red(), error.bright_red());
         }
     }
 
-    println!("{}", "=".repeat(60).bright_black());
+    outln!("{}", "=".repeat(60).bright_black());
 }
\ No newline at end of file
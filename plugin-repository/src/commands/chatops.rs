@@ -0,0 +1,186 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::commands::serve::{ApiError, ApiState, PublishRequest, RunState};
+use crate::config::parser::ChatOpsConfig;
+use crate::core::chatops::{self, ReleaseCommand, TelegramClient, Update};
+use crate::core::deadline::format_duration;
+
+/// Сколько действует кнопка подтверждения
+const CONFIRMATION_TTL: Duration = Duration::from_secs(600);
+/// Период опроса статуса запуска для сообщений об этапах
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+/// Пауза после ошибки getUpdates
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Команда, ожидающая подтверждения кнопкой
+struct Pending {
+    release: ReleaseCommand,
+    /// Сообщение с командой: ответы о ходе релиза идут в его ветку
+    thread: i64,
+    created: Instant,
+}
+
+/// Бот: принимает `/release` из `[chatops] chat_id`, просит подтверждение и пишет этапы в ветку команды
+pub async fn run_bot(client: TelegramClient, config: ChatOpsConfig, state: ApiState) {
+    let username = match client.username().await {
+        Ok(username) => username,
+        Err(e) => {
+            warn!("⚠️ ChatOps: не удалось получить имя бота: {:#}", e);
+            None
+        }
+    };
+    info!("🤖 ChatOps запущен{}", username.as_deref().map(|u| format!(" (@{})", u)).unwrap_or_default());
+
+    let mut offset = 0;
+    let mut pending: HashMap<String, Pending> = HashMap::new();
+    loop {
+        let updates = match client.get_updates(offset).await {
+            Ok(updates) => updates,
+            Err(e) => {
+                warn!("⚠️ ChatOps: {:#}", e);
+                tokio::time::sleep(RETRY_DELAY).await;
+                continue;
+            }
+        };
+        for update in updates {
+            offset = offset.max(update.update_id + 1);
+            if let Err(e) = handle_update(&client, &config, &state, username.as_deref(), &mut pending, update).await {
+                warn!("⚠️ ChatOps: {:#}", e);
+            }
+        }
+    }
+}
+
+fn is_allowed(config: &ChatOpsConfig, user_id: Option<i64>) -> bool {
+    config.allowed_users.is_empty() || user_id.is_some_and(|id| config.allowed_users.contains(&id))
+}
+
+fn to_request(release: &ReleaseCommand) -> PublishRequest {
+    PublishRequest {
+        version: release.version.clone(),
+        auto_version: release.version.is_none(),
+        dry_run: release.dry_run,
+        ..PublishRequest::default()
+    }
+}
+
+async fn handle_update(
+    client: &TelegramClient,
+    config: &ChatOpsConfig,
+    state: &ApiState,
+    username: Option<&str>,
+    pending: &mut HashMap<String, Pending>,
+    update: Update,
+) -> Result<()> {
+    let chat_id = config.chat_id;
+
+    if let Some(message) = update.message.filter(|m| m.chat.id == chat_id) {
+        let Some(parsed) = message.text.as_deref().and_then(|text| chatops::parse_release_command(text, username)) else {
+            return Ok(());
+        };
+        let release = match parsed {
+            Ok(release) => release,
+            Err(usage) => {
+                client.send_message(chat_id, Some(message.message_id), &usage, &[]).await?;
+                return Ok(());
+            }
+        };
+        if !is_allowed(config, message.from.as_ref().map(|u| u.id)) {
+            client.send_message(chat_id, Some(message.message_id), "⛔ Нет прав на запуск релиза", &[]).await?;
+            return Ok(());
+        }
+
+        pending.retain(|_, p| p.created.elapsed() < CONFIRMATION_TTL);
+        let nonce = format!("{:08x}", rand::random::<u32>());
+        let buttons = [
+            ("✅ Запустить".to_string(), chatops::confirmation_data(&nonce, true)),
+            ("❌ Отмена".to_string(), chatops::confirmation_data(&nonce, false)),
+        ];
+        let question = format!("Опубликовать {}?", release.describe());
+        client.send_message(chat_id, Some(message.message_id), &question, &buttons).await?;
+        pending.insert(nonce, Pending { release, thread: message.message_id, created: Instant::now() });
+        return Ok(());
+    }
+
+    let Some(callback) = update.callback_query else { return Ok(()) };
+    let Some((nonce, confirmed)) = callback.data.as_deref().and_then(chatops::parse_confirmation) else {
+        return Ok(());
+    };
+    let Some(message) = callback.message.as_ref().filter(|m| m.chat.id == chat_id) else { return Ok(()) };
+    if !is_allowed(config, Some(callback.from.id)) {
+        client.answer_callback(&callback.id, "Нет прав на запуск релиза").await?;
+        return Ok(());
+    }
+    let Some(request) = pending.remove(nonce).filter(|p| p.created.elapsed() < CONFIRMATION_TTL) else {
+        client.answer_callback(&callback.id, "Запрос устарел: отправьте /release еще раз").await?;
+        client.edit_message(chat_id, message.message_id, "⌛ Запрос устарел").await?;
+        return Ok(());
+    };
+
+    let who = callback.from.username.as_deref().map(|u| format!(" (@{})", u)).unwrap_or_default();
+    if !confirmed {
+        client.answer_callback(&callback.id, "Отменено").await?;
+        client
+            .edit_message(chat_id, message.message_id, &format!("❌ Публикация {} отменена{}", request.release.describe(), who))
+            .await?;
+        return Ok(());
+    }
+
+    client.answer_callback(&callback.id, "Запускаю").await?;
+    match state.start(to_request(&request.release)) {
+        Ok(status) => {
+            let text = format!("🚀 Запуск {}: публикация {}{}", status.id, request.release.describe(), who);
+            client.edit_message(chat_id, message.message_id, &text).await?;
+            tokio::spawn(follow_run(client.clone(), chat_id, request.thread, state.clone(), status.id));
+        }
+        Err(ApiError(_, error)) => {
+            client.edit_message(chat_id, message.message_id, &format!("❌ {}", error)).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Пишет в ветку команды начало и итог каждого этапа, затем результат запуска
+async fn follow_run(client: TelegramClient, chat_id: i64, thread: i64, state: ApiState, id: u64) {
+    let mut reported = 0;
+    let mut current: Option<String> = None;
+    loop {
+        tokio::time::sleep(PROGRESS_INTERVAL).await;
+        let Some(status) = state.status(id) else { return };
+
+        let mut lines = Vec::new();
+        for stage in status.stages.iter().skip(reported) {
+            let duration = Duration::from_millis(stage.duration_ms.try_into().unwrap_or(u64::MAX));
+            lines.push(format!("{} {} — {}", if stage.completed { "✔" } else { "✘" }, stage.stage, format_duration(duration)));
+        }
+        reported = status.stages.len();
+        if status.stage != current {
+            if let Some(stage) = &status.stage {
+                lines.push(format!("▶️ {}", stage));
+            }
+            current = status.stage.clone();
+        }
+        match status.state {
+            RunState::Running => {}
+            RunState::Succeeded => lines.push(format!("✅ Запуск {} завершен", id)),
+            RunState::UpToDate => lines.push(format!("✅ Запуск {} пропущен: новых коммитов нет", id)),
+            RunState::Failed => lines.push(format!(
+                "❌ Запуск {} завершился ошибкой: {}",
+                id,
+                status.error.as_deref().unwrap_or("без описания")
+            )),
+        }
+
+        if !lines.is_empty() {
+            if let Err(e) = client.send_message(chat_id, Some(thread), &lines.join("\n"), &[]).await {
+                warn!("⚠️ ChatOps: {:#}", e);
+            }
+        }
+        if status.state != RunState::Running {
+            return;
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::{Context, Result};
+use colored::*;
+use tracing::info;
+
+use crate::cli::config::{ConfigAction, ConfigCommand, MigrateCommand};
+use crate::config::migrate::{self, CONFIG_SCHEMA_VERSION};
+
+/// Обработчик команды config
+pub async fn handle_config_command(cmd: ConfigCommand, config_file: &str) -> Result<()> {
+    match cmd.action {
+        ConfigAction::Migrate(migrate_cmd) => handle_migrate(migrate_cmd, config_file),
+    }
+}
+
+/// Миграция идет по исходному тексту: переменные окружения не подставляются
+fn handle_migrate(cmd: MigrateCommand, config_file: &str) -> Result<()> {
+    info!("🧹 Миграция конфигурации {}", config_file);
+    let content = std::fs::read_to_string(config_file)
+        .with_context(|| format!("Не удалось прочитать файл конфигурации: {}", config_file))?;
+    let mut document: toml_edit::DocumentMut = content.parse().with_context(|| format!("Ошибка парсинга TOML: {}", config_file))?;
+
+    let migration = migrate::migrate(&mut document)?;
+    if migration.is_noop() {
+        outln!("{} {} соответствует схеме {}", "✅".green(), config_file, CONFIG_SCHEMA_VERSION);
+        return Ok(());
+    }
+
+    outln!("🧹 {}: схема {} → {}", config_file, migration.from_schema, CONFIG_SCHEMA_VERSION);
+    for change in migration.describe() {
+        outln!("  • {}", change);
+    }
+    if cmd.dry_run {
+        outln!("\n🧪 DRY RUN — файл не изменен. Результат:\n");
+        outln!("{}", document);
+        return Ok(());
+    }
+
+    let backup = format!("{}.bak", config_file);
+    std::fs::copy(config_file, &backup).with_context(|| format!("Не удалось сохранить копию {}", backup))?;
+    std::fs::write(config_file, document.to_string()).with_context(|| format!("Не удалось записать {}", config_file))?;
+    outln!("{} {} обновлен, исходный файл сохранен в {}", "✅".green(), config_file, backup);
+    Ok(())
+}
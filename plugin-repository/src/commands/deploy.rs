@@ -1,8 +1,13 @@
 use anyhow::{Context, Result};
 use tracing::{info, warn, error};
-use crate::cli::deploy::DeployCommand;
+use crate::cli::deploy::{DeployAction, DeployCommand, PromoteCommand};
 use crate::config::parser::Config;
-use crate::core::deployer::Deployer;
+use crate::config::validator::Capability;
+use crate::core::audit::{self, AuditEntry};
+use crate::core::deployer::{DeployReport, Deployer};
+use crate::core::release_vault::{ReleaseVault, DEPLOY_REPORT_FILE};
+use crate::core::remote_exec;
+use crate::utils::clock::system_clock;
 
 /// Обработчик команды deploy
 pub async fn handle_deploy_command(
@@ -15,7 +20,13 @@ pub async fn handle_deploy_command(
     let config = Config::load_from_file(config_file)
         .with_context(|| format!("Не удалось загрузить конфигурацию из файла: {}", config_file))?;
 
-    let deployer = Deployer::new(config.clone());
+    if let Some(DeployAction::Promote(cmd)) = command.action {
+        return handle_promote_command(cmd, config).await;
+    }
+
+    let deployer = Deployer::new(config.clone())
+        .with_allow_republish(command.allow_republish)
+        .with_channel(command.channel.clone());
 
     // Валидация
     if !command.skip_validation {
@@ -29,16 +40,123 @@ pub async fn handle_deploy_command(
         }
     }
 
+    if command.migrate_layout {
+        deployer.migrate_layout().context("Миграция раскладки артефактов не удалась")?;
+    }
+
     // Выполняем деплой
-    if let Err(e) = deployer.deploy(command.force, command.rollback_on_failure).await {
-        error!("Ошибка деплоя: {}", e);
-        if command.rollback_on_failure {
-            warn!("Пробуем откатить изменения...");
-            let _ = deployer.rollback().await;
+    let report = match deployer.deploy(command.force, command.rollback_on_failure).await {
+        Ok(report) => report,
+        Err(e) => {
+            error!("Ошибка деплоя: {}", e);
+            if command.rollback_on_failure {
+                warn!("Пробуем откатить изменения...");
+                let _ = deployer.rollback().await;
+            }
+            return Err(e);
+        }
+    };
+
+    // Отчет сохраняется в хранилище релизов версии артефакта
+    let saved = match &report.version {
+        Some(version) => {
+            let project_root = std::env::current_dir().context("Не удалось определить текущую директорию")?;
+            let vault = ReleaseVault::new(&project_root);
+            vault.store_json(version, DEPLOY_REPORT_FILE, &report).context("Не удалось сохранить отчет деплоя")?;
+            Some(vault.dir(version).join(DEPLOY_REPORT_FILE))
+        }
+        None => {
+            warn!("Версия артефакта не определена по имени файла, отчет деплоя не сохранен");
+            None
+        }
+    };
+
+    if command.output == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_deploy_report(&report);
+        if let Some(path) = saved {
+            outln!("🗄️ Отчет деплоя: {}", path.display());
         }
-        return Err(e);
     }
 
     info!("✅ Деплой завершен");
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Итог деплоя: цель, загрузки, изменения индекса, бэкапы, проверки и вывод команд на сервере
+pub(crate) fn print_deploy_report(report: &DeployReport) {
+    outln!(
+        "Деплой {} {}: {} за {:.1} с",
+        report.plugin_id,
+        report.version.as_deref().unwrap_or("-"),
+        report.target,
+        report.duration_ms as f64 / 1000.0
+    );
+    if report.already_deployed {
+        outln!("ℹ️ Уже опубликовано: артефакт и запись в updatePlugins.xml не изменились");
+    }
+    if !report.uploads.is_empty() {
+        outln!("Загрузки:");
+        for upload in &report.uploads {
+            match &upload.sha256 {
+                Some(sha256) => outln!("  {} sha256 {}", upload, &sha256[..sha256.len().min(12)]),
+                None => outln!("  {}", upload),
+            }
+        }
+    }
+    if let Some(index) = &report.index {
+        outln!("updatePlugins.xml: {}", index);
+    }
+    if !report.backups.is_empty() {
+        outln!("Резервные копии (удалены после деплоя):");
+        for backup in &report.backups {
+            outln!("  {}", backup.display());
+        }
+    }
+    if !report.verification.is_empty() {
+        outln!("Проверки:");
+        for check in &report.verification {
+            outln!("  {} {}: {}", if check.passed { "✅" } else { "❌" }, check.name, check.detail);
+        }
+    }
+    if !report.remote_commands.is_empty() {
+        outln!("Команды на сервере:\n{}", remote_exec::render(&report.remote_commands));
+    }
+}
+
+/// Переключение индекса на ранее загруженную версию с записью в журнал аудита
+async fn handle_promote_command(command: PromoteCommand, config: Config) -> Result<()> {
+    config.validate_for(&[Capability::Deploy]).context("Валидация конфигурации не пройдена")?;
+
+    let clock = system_clock();
+    let deployer = Deployer::new(config).with_channel(command.channel.clone()).with_clock(clock.clone());
+    let promotion = deployer.promote(&command.version, command.dry_run)?;
+
+    if command.dry_run {
+        println!("{}", promotion.xml);
+        info!(
+            "🔍 Dry run: {} -> {} ({}), сервер не изменен",
+            promotion.from_version.as_deref().unwrap_or("-"),
+            promotion.to_version,
+            promotion.url
+        );
+        return Ok(());
+    }
+
+    let mut entry = AuditEntry::new("promote", &promotion.to_version, clock.as_ref());
+    entry.previous_version = promotion.from_version.clone();
+    entry.reason = command.reason;
+    entry.details = promotion.url.clone();
+    let project_root = std::env::current_dir().context("Не удалось определить текущую директорию")?;
+    let audit_file = audit::append(&project_root, &entry)?;
+
+    info!(
+        "✅ Репозиторий переключен: {} -> {} ({})",
+        promotion.from_version.as_deref().unwrap_or("-"),
+        promotion.to_version,
+        promotion.url
+    );
+    info!("📝 Запись аудита: {}", audit_file.display());
+    Ok(())
+}
@@ -4,4 +4,11 @@ pub mod deploy;
 pub mod ai;
 pub mod validate;
 pub mod status;
-pub mod publish;
\ No newline at end of file
+pub mod publish;
+pub mod verify_artifact;
+pub mod verify_reproducible;
+pub mod serve;
+pub mod chatops;
+pub mod run;
+pub mod config;
+pub mod rollback;
\ No newline at end of file
@@ -1,96 +1,378 @@
 use anyhow::{Context, Result};
 use colored::*;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use tracing::{info, warn};
 
 use crate::cli::publish::PublishCommand;
+use crate::commands::ai::print_cost_summary;
+use crate::commands::deploy::print_deploy_report;
 use crate::config::parser::Config;
+use crate::config::validator::Capability;
+use crate::core::build_profile::BuildProfile;
 use crate::core::builder::PluginBuilder;
-use crate::core::deployer::Deployer;
+use crate::core::change_notes::ChangeNotesFormatter;
+use crate::core::deadline::{format_duration, parse_duration, Deadline, DeadlineExceeded};
+use crate::core::deploy_layout::DeployLayout;
+use crate::core::deployer::{DeployReport, Deployer};
+use crate::core::interrupt::{self, Interrupted};
+use crate::core::dry_run::DryRunBundle;
+use crate::core::external_version::{self, VersionSource};
+use crate::core::localized_notes::LocalizedChangeNotes;
+use crate::core::plugin_xml;
+use crate::core::version_guard;
+use crate::core::postprocess::{PostProcessorChain, ProcessContext};
+use crate::core::release_vault::{
+    checksum_line, ReleaseVault, BUILD_FILE, CHANGELOG_FILE, CHANGE_NOTES_FILE, CHECKSUMS_FILE, DEPLOY_REPORT_FILE,
+    RELEASE_NOTES_FILE,
+};
 use crate::core::releaser::ReleaseManager;
 use crate::core::llm::agents::LLMAgentManager;
+use crate::core::llm::prompt_versions;
 use crate::git::GitRepository;
+use crate::models::plugin::BuildResult;
+use crate::utils::exit::{ExitStatus, NOTHING_TO_RELEASE};
+use crate::utils::clock::system_clock;
+
+/// Итог цикла публикации (отчет для `serve`)
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishOutcome {
+    pub version: String,
+    pub build: BuildResult,
+    pub changelog: Option<String>,
+    pub release_notes: Option<String>,
+    /// Директория бандла, если это был dry-run
+    pub dry_run_bundle: Option<PathBuf>,
+    pub deploy: Option<DeployReport>,
+}
 
 /// Обработчик команды полного цикла публикации
 pub async fn handle_publish_command(cmd: PublishCommand, config_file: &str) -> Result<()> {
     info!("🧩 Запуск полного цикла публикации");
 
+    let deadline = deadline_for(&cmd)?;
+    run_publish(cmd, config_file, &deadline).await.map(|_| ())
+}
+
+/// Дедлайн по `--max-duration`
+pub fn deadline_for(cmd: &PublishCommand) -> Result<Deadline> {
+    let budget = cmd.max_duration.as_deref().map(parse_duration).transpose()
+        .context("Некорректное значение --max-duration")?;
+    Ok(Deadline::new(budget))
+}
+
+/// Канал из `--channel` (и API) становится каталогом на сервере: проверяется до сборки и релиза
+pub fn check_channel(cmd: &PublishCommand) -> Result<()> {
+    match &cmd.channel {
+        Some(channel) => DeployLayout::validate_segment("channel", channel).context("Некорректное значение --channel"),
+        None => Ok(()),
+    }
+}
+
+/// Штатное завершение с кодом NOTHING_TO_RELEASE, если HEAD совпадает с последним тегом.
+/// Репозиторий без тегов — первый релиз, его не пропускаем
+async fn ensure_unreleased_commits(git_repo: &GitRepository) -> Result<()> {
+    let Some(tag) = git_repo.tags.get_latest_tag().await? else {
+        return Ok(());
+    };
+    if git_repo.tags.count_commits_since(&tag.name).await? > 0 {
+        return Ok(());
+    }
+    outln!("{} Плагин актуален: после {} новых коммитов нет", "✅".green(), tag.name.bright_green());
+    Err(ExitStatus::new(NOTHING_TO_RELEASE, format!("Нечего релизить: HEAD совпадает с {}", tag.name)).into())
+}
+
+/// Changelog, release notes и change-notes от LLM для публикуемой версии
+#[derive(Default)]
+struct GeneratedContent {
+    release_message: Option<String>,
+    change_notes: Option<String>,
+    changelog: Option<String>,
+    release_notes: Option<String>,
+    localized_change_notes: BTreeMap<String, String>,
+}
+
+/// Генерация контента; сбой LLM не прерывает публикацию, кроме исчерпанного --max-duration
+async fn generate_content(config: &Config, releaser: &ReleaseManager, version: &str, deadline: &Deadline) -> Result<GeneratedContent> {
+    let mut content = GeneratedContent::default();
+    match deadline.run("llm", releaser.prepare_release(Some(version.to_string()))).await {
+        Ok(prep) => {
+            content.changelog = prep.release.changelog;
+            content.release_notes = prep.release.release_notes;
+            if let Some(notes) = &content.release_notes {
+                content.change_notes = Some(render_change_notes(config, notes)?);
+                content.release_message = Some(notes.clone());
+                if !config.change_notes.locales.is_empty() {
+                    let translations = deadline
+                        .run("llm", async {
+                            Ok(releaser.localize_release_notes(version, notes, &config.change_notes.locales).await)
+                        })
+                        .await?;
+                    for (locale, text) in translations {
+                        content.localized_change_notes.insert(locale, render_change_notes(config, &text)?);
+                    }
+                }
+            } else if let Some(changelog) = &content.changelog {
+                content.release_message = Some(format!("Changelog for v{}\n\n{}", version, changelog));
+            }
+        }
+        Err(e) if e.is::<DeadlineExceeded>() => return Err(e),
+        Err(e) => {
+            warn!("AI-обогащение пропущено: {}", e);
+        }
+    }
+    Ok(content)
+}
+
+/// Полный цикл публикации; при исчерпании бюджета печатает отчет о расходе времени
+pub async fn run_publish(cmd: PublishCommand, config_file: &str, deadline: &Deadline) -> Result<PublishOutcome> {
+    let result = publish(cmd, config_file, deadline).await;
+    if let Err(e) = &result {
+        if let Some(exceeded) = e.downcast_ref::<DeadlineExceeded>() {
+            outln!("{} {}", "⏰".red(), exceeded.to_string().red());
+            outln!("{}", deadline.report());
+        }
+    }
+    result
+}
+
+async fn publish(cmd: PublishCommand, config_file: &str, deadline: &Deadline) -> Result<PublishOutcome> {
+    check_channel(&cmd)?;
+
     // 1) Загрузка и (опционально) валидация конфигурации
-    let config = Config::load_from_file(config_file)
+    let mut config = Config::load_from_file(config_file)
         .with_context(|| format!("Не удалось загрузить конфигурацию из файла: {}", config_file))?;
+    if cmd.mcp_workspace {
+        config.build.mcp.enabled = true;
+    }
+    if cmd.max_cost.is_some() {
+        config.llm.max_cost = cmd.max_cost;
+    }
+    let profile = BuildProfile::select(&mut config, &cmd.profile)?;
     if !cmd.skip_validation {
-        config.validate().context("Валидация конфигурации не пройдена")?;
+        let mut capabilities = vec![Capability::Build, Capability::Deploy];
+        if !cmd.no_ai {
+            capabilities.push(Capability::Ai);
+        }
+        config.validate_for(&capabilities).context("Валидация конфигурации не пройдена")?;
     }
 
     let project_root = std::env::current_dir().context("Не удалось определить текущую директорию")?;
-    let git_repo = GitRepository::new(&project_root);
+    let git_repo = GitRepository::from_config(&project_root, &config)?;
     if !git_repo.is_valid_repository() {
         anyhow::bail!("Текущая директория не является git репозиторием");
     }
+    // Без новых коммитов публиковать нечего: выходим до сборки и запросов к LLM
+    if !cmd.force {
+        ensure_unreleased_commits(&git_repo).await?;
+    }
 
-    // Инициализируем LLM/Release менеджеры один раз
-    let agent_manager = LLMAgentManager::from_config(&config)
+    // Инициализируем LLM/Release менеджеры один раз; часы общие для всех этапов публикации
+    let clock = system_clock();
+    let agent_manager = LLMAgentManager::from_config(&config, clock.clone())
         .context("Не удалось создать LLM агент менеджер")?;
-    let releaser = ReleaseManager::new(git_repo.clone(), agent_manager, config.project.clone());
+    let transcript = agent_manager.transcript();
+    let cost = agent_manager.cost();
+    let releaser = ReleaseManager::new(git_repo.clone(), agent_manager, config.project.clone())
+        .with_proofreading(&config.proofreading)
+        .with_release_branches(&config.git);
 
-    // 2) Определение версии
-    let version = if let Some(v) = cmd.version.clone() {
+    // 2) Определение версии: заданная извне версия отключает автоматический подбор
+    let external = external_version::resolve(
+        cmd.version.as_deref(),
+        cmd.version_file.as_deref(),
+        std::env::var(external_version::VERSION_ENV).ok(),
+    )?;
+    let version = if let Some((v, source)) = external {
+        if source != VersionSource::Flag {
+            info!("🏷️ Версия {} задана извне ({}), подбор версии пропущен", v, source);
+        }
         v
     } else if cmd.auto_version {
-        let prep = releaser.prepare_release(None).await?;
+        let prep = deadline.run("version", releaser.prepare_release(None)).await?;
         if !prep.success {
             anyhow::bail!("Подготовка релиза не удалась");
         }
         prep.release.version
     } else {
-        anyhow::bail!("Не указана версия. Используйте --version или --auto-version");
+        anyhow::bail!("Не указана версия. Используйте --version, --version-file, {} или --auto-version", external_version::VERSION_ENV);
     };
+    let version = profile.version(&version);
 
-    println!("{} Версия: {}", "🏷️", version.bright_green());
+    outln!("{} Версия: {}", "🏷️", version.bright_green());
 
-    // 3) Сборка артефакта с заданной версией
-    let builder = PluginBuilder::new(config.clone(), project_root.clone());
-    let build_res = builder.build(Some(version.clone()), &cmd.profile).await?;
-    if !build_res.success {
-        anyhow::bail!("Сборка завершилась с ошибками");
+    // 3-4) Сборка и генерация контента LLM не зависят друг от друга до создания тега, поэтому
+    // идут параллельно; сбой сборки или исчерпанный --max-duration отменяет второй этап
+    let builder = PluginBuilder::new(config.clone(), project_root.clone()).with_clock(clock.clone());
+    let build = async {
+        let build_res = deadline.run("build", builder.build(Some(version.clone()), &cmd.profile)).await?;
+        if !build_res.success {
+            anyhow::bail!("Сборка завершилась с ошибками");
+        }
+        outln!("{} Сборка завершена ({})", "✅", format_duration(deadline.stage_duration("build")));
+        Ok(build_res)
+    };
+    let content = async {
+        if cmd.no_ai {
+            info!("AI-обогащение отключено флагом --no-ai");
+            return Ok(GeneratedContent::default());
+        }
+        warn_prompt_drift(&project_root, &version);
+        let content = generate_content(&config, &releaser, &version, deadline).await?;
+        outln!("{} Контент LLM готов ({})", "✅", format_duration(deadline.stage_duration("llm")));
+        Ok(content)
+    };
+    if !cmd.no_ai {
+        outln!("{} Сборка и генерация changelog/release notes выполняются параллельно", "⏩");
     }
-    println!("{} Сборка завершена", "✅");
-
-    // 4) Создание и публикация релиза (если не dry-run)
+    let (build_res, content) = tokio::try_join!(build, content)?;
+    if !cmd.no_ai {
+        print_cost_summary(&cost);
+        // Агенты заменяют отклоненные ответы запасными, поэтому бюджет проверяется отдельно
+        cost.check()?;
+    }
+    let GeneratedContent { release_message, change_notes, changelog, release_notes, localized_change_notes } = content;
 
-    if cmd.dry_run {
-        println!("{} DRY RUN — релиз и деплой пропущены", "🧪");
-        return Ok(());
+    // Стенограмма запросов к LLM для аудита (в dry-run попадает только в бандл)
+    let mut attachments = Vec::new();
+    if !transcript.is_empty() && !cmd.dry_run {
+        let path = transcript.save(&project_root, &version)?;
+        outln!("📝 Стенограмма LLM: {}", path.display());
+        if cmd.upload_transcript {
+            attachments.push(path);
+        }
     }
 
-    // По умолчанию обогащаем релиз данными от LLM, если не отключено флагом
-    let mut release_message: Option<String> = None;
-    if !cmd.no_ai {
-        match releaser.prepare_release(Some(version.clone())).await {
-            Ok(prep) => {
-                if let Some(notes) = prep.release.release_notes {
-                    release_message = Some(notes);
-                } else if let Some(changelog) = prep.release.changelog {
-                    release_message = Some(format!("Changelog for v{}\n\n{}", version, changelog));
+    let deployer = Deployer::new(config.clone())
+        .with_clock(clock.clone())
+        .with_allow_republish(cmd.allow_republish)
+        .with_channel(cmd.channel.clone())
+        .with_change_notes(change_notes.clone())
+        .with_attachments(attachments)
+        .with_release_version(Some(version.clone()))
+        .with_deadline(Some(deadline.clone()));
+
+    if cmd.dry_run {
+        let artifact_xml = build_res.artifact.as_ref()
+            .and_then(|artifact| match plugin_xml::read_from_artifact(&artifact.file_path) {
+                Ok(xml) => {
+                    info!("plugin.xml для бандла взят из {}", xml.location);
+                    Some(xml)
                 }
+                Err(e) => {
+                    warn!("plugin.xml не добавлен в бандл: {}", e);
+                    None
+                }
+            });
+        let (plugin_xml, plugin_xml_diff) = match artifact_xml {
+            None => (None, None),
+            Some(xml) if cmd.no_enrich => (Some(xml.content), None),
+            Some(xml) => {
+                let enriched = plugin_xml::enrich(&xml.content, &version, change_notes.as_deref())?;
+                let diff = plugin_xml::unified_diff(&plugin_xml::normalize(&xml.content)?, &enriched, &xml.location);
+                if diff.is_empty() {
+                    outln!("🧩 Обогащение не меняет plugin.xml");
+                } else {
+                    outln!("🧩 Обогащение plugin.xml ({}):\n{}", xml.location, diff);
+                }
+                let plugin_xml = if cmd.enrich_dry_run { xml.content } else { enriched };
+                (Some(plugin_xml), Some(diff).filter(|d| !d.is_empty()))
             }
-            Err(e) => {
-                warn!("AI-обогащение пропущено: {}", e);
-            }
+        };
+
+        let bundle = DryRunBundle {
+            version: version.clone(),
+            changelog,
+            release_notes,
+            plugin_xml,
+            plugin_xml_diff,
+            transcript: if transcript.is_empty() { None } else { Some(transcript.to_json(&version)?) },
+            localized_change_notes: localized_change_notes.clone(),
+            deploy_plan: deployer.plan().context("Не удалось построить план деплоя")?,
+        };
+        let dir = cmd.bundle_dir.clone().unwrap_or_else(|| DryRunBundle::default_dir(&version));
+        let files = bundle.write(&dir)?;
+
+        outln!("🧪 DRY RUN — релиз и деплой пропущены");
+        outln!("📁 Бандл для ревью: {}", dir.display().to_string().bright_cyan());
+        for file in files {
+            outln!("  • {}", file.file_name().unwrap_or_default().to_string_lossy());
+        }
+        return Ok(PublishOutcome {
+            version,
+            build: build_res,
+            changelog: bundle.changelog,
+            release_notes: bundle.release_notes,
+            dry_run_bundle: Some(dir),
+            deploy: None,
+        });
+    }
+
+    // Локализованные change-notes попадают в артефакт до деплоя
+    if !localized_change_notes.is_empty() {
+        if let Some(artifact) = &build_res.artifact {
+            let locales: Vec<String> = localized_change_notes.keys().cloned().collect();
+            let ctx = ProcessContext {
+                project_root: project_root.clone(),
+                plugin_id: config.project.id.clone(),
+                version: version.clone(),
+                environment: build_res.environment.clone(),
+            };
+            PostProcessorChain::default()
+                .with(LocalizedChangeNotes { bundle: config.change_notes.bundle.clone(), notes: localized_change_notes.clone() })
+                .apply(&artifact.file_path, &ctx)
+                .context("Не удалось добавить локализованные change-notes в артефакт")?;
+            outln!("🌐 Change-notes для локалей: {}", locales.join(", "));
+        }
+    }
+
+    // Несовпадение версий обнаруживается до создания тега, а не на деплое после push
+    if let Some(artifact) = &build_res.artifact {
+        version_guard::check(&artifact.file_path, Some(&version))?;
+    }
+
+    // Материалы релиза сохраняются до создания тега: при сбое деплоя они остаются для разбора
+    let vault = ReleaseVault::new(&project_root).with_clock(clock.clone());
+    let mut files = vec![(BUILD_FILE.to_string(), serde_json::to_vec_pretty(&build_res)?)];
+    if let Some(artifact) = &build_res.artifact {
+        // Сумма пересчитывается: локализованные change-notes меняют артефакт после сборки
+        let content = std::fs::read(&artifact.file_path)
+            .with_context(|| format!("Не удалось прочитать артефакт {}", artifact.file_path.display()))?;
+        let sha256 = format!("{:x}", Sha256::digest(&content));
+        files.push((CHECKSUMS_FILE.to_string(), checksum_line(&sha256, &artifact.file_name).into_bytes()));
+    }
+    for (name, content) in [(CHANGELOG_FILE, &changelog), (RELEASE_NOTES_FILE, &release_notes), (CHANGE_NOTES_FILE, &change_notes)] {
+        if let Some(content) = content {
+            files.push((name.to_string(), content.clone().into_bytes()));
         }
-    } else {
-        info!("AI-обогащение отключено флагом --no-ai");
     }
+    for (locale, html) in &localized_change_notes {
+        files.push((format!("change-notes.{}.html", locale), html.clone().into_bytes()));
+    }
+    vault.store(&version, &files).context("Не удалось сохранить материалы релиза")?;
+
+    // 5) Создание и публикация релиза
 
-    println!("{} Создание релиза...", "🚀");
-    let _tag = releaser.create_release(&version, release_message).await?;
-    println!("{} Релиз создан", "✅");
+    let released = async {
+        outln!("{} Создание релиза...", "🚀");
+        deadline.run("release", releaser.create_release(&version, release_message)).await?;
+        outln!("{} Релиз создан", "✅");
 
-    println!("{} Публикация релиза...", "📤");
-    releaser.publish_release(&version).await?;
-    println!("{} Релиз опубликован", "✅");
+        outln!("{} Публикация релиза...", "📤");
+        deadline.run("publish", releaser.publish_release(&version)).await?;
+        outln!("{} Релиз опубликован", "✅");
+        Ok::<_, anyhow::Error>(())
+    };
+    if let Err(e) = released.await {
+        if e.is::<Interrupted>() {
+            releaser.discard_interrupted_release(&version).await;
+        }
+        return Err(e);
+    }
 
-    // 5) Деплой
-    let deployer = Deployer::new(config.clone());
+    // 6) Деплой
     if !cmd.skip_validation {
         if let Err(e) = deployer.validate().await {
             if cmd.force {
@@ -101,9 +383,65 @@ pub async fn handle_publish_command(cmd: PublishCommand, config_file: &str) -> R
         }
     }
 
-    println!("{} Деплой...", "🚚");
-    deployer.deploy(cmd.force, cmd.rollback_on_failure).await?;
-    println!("{} Деплой завершен", "✅");
+    outln!("{} Деплой...", "🚚");
+    let report = match deadline.run("deploy", deployer.deploy(cmd.force, cmd.rollback_on_failure)).await {
+        Ok(report) => report,
+        Err(e) => {
+            if e.is::<Interrupted>() {
+                interrupt::record(format!("Тег {}{} уже опубликован и оставлен: деплой прерван", config.git.tag_prefix, version), false);
+            }
+            return Err(e);
+        }
+    };
+    print_deploy_report(&report);
+    vault.store_json(&version, DEPLOY_REPORT_FILE, &report).context("Не удалось сохранить отчет деплоя")?;
+    outln!("{} Деплой завершен", "✅");
+    outln!("🗄️ Материалы релиза: {}", vault.dir(&version).display());
+
+    Ok(PublishOutcome {
+        version,
+        build: build_res,
+        changelog,
+        release_notes,
+        dry_run_bundle: None,
+        deploy: Some(report),
+    })
+}
+
+/// Предупреждает, что шаблоны промптов изменились с прошлых генераций (стенограммы в .deploy-plugin/releases)
+fn warn_prompt_drift(project_root: &std::path::Path, version: &str) {
+    let drift = prompt_versions::drift(project_root, version);
+    if !drift.same_release.is_empty() {
+        let changes: Vec<String> = drift.same_release.iter().map(|c| c.to_string()).collect();
+        warn!(
+            "⚠️ Контент v{} ранее сгенерирован другими промптами ({}): changelog и release notes будут сгенерированы заново",
+            version,
+            changes.join("; ")
+        );
+    }
+    if let Some((previous, changes)) = drift.previous_release {
+        let changes: Vec<String> = changes.iter().map(|c| c.to_string()).collect();
+        warn!(
+            "⚠️ Промпты изменились с релиза v{} ({}): стиль сгенерированного контента может отличаться",
+            previous,
+            changes.join("; ")
+        );
+    }
+}
 
-    Ok(())
+/// Готовит HTML change-notes из release notes с учетом бюджета длины
+pub(crate) fn render_change_notes(config: &Config, release_notes: &str) -> Result<String> {
+    let formatter = ChangeNotesFormatter::new(config.change_notes.clone());
+    let notes = formatter.format(release_notes);
+    let invalid = formatter.validate_tags(&notes.html);
+    if !invalid.is_empty() {
+        anyhow::bail!("Change-notes содержат неразрешенные теги: {}", invalid.join(", "));
+    }
+    if notes.truncated {
+        outln!("✂️ Change-notes усечены до {} символов", config.change_notes.max_length);
+    }
+    if !notes.removed_tags.is_empty() {
+        warn!("Из change-notes удалены неразрешенные теги: {}", notes.removed_tags.join(", "));
+    }
+    Ok(notes.html)
 }
@@ -4,10 +4,18 @@ use colored::*;
 use std::fs;
 
 use crate::config::parser::Config;
-use crate::cli::release::ReleaseCommand;
+use crate::config::validator::Capability;
+use crate::cli::release::{BackportCommand, BranchCommand, ReadinessCommand, ReleaseAction, ReleaseCommand, ShowCommand};
+use crate::core::readiness::{self, ReadinessFormat};
+use crate::core::build_profile::BuildProfile;
+use crate::core::builder::PluginBuilder;
+use crate::core::release_vault::{ReleaseVault, BACKPORT_FILE, BUILD_FILE, CHANGELOG_FILE, READINESS_FILE, RELEASE_NOTES_FILE};
 use crate::core::releaser::ReleaseManager;
-use crate::git::GitRepository;
+use crate::git::branches::PickResolution;
+use crate::git::{GitRepository, ReleaseBranches, ReleaseLine};
 use crate::core::llm::agents::LLMAgentManager;
+use crate::core::llm::transcript::Transcript;
+use crate::utils::clock::system_clock;
 
 /// Обработчик команды release
 pub async fn handle_release_command(
@@ -16,35 +24,56 @@ pub async fn handle_release_command(
 ) -> Result<()> {
     info!("🚀 Запуск команды релиза");
 
+    // Просмотр сохраненного релиза не требует конфигурации LLM
+    if let Some(ReleaseAction::Show(cmd)) = &command.action {
+        return handle_show(cmd);
+    }
+
     // Загружаем конфигурацию
     let config = Config::load_from_file(config_file)
         .with_context(|| format!("Не удалось загрузить конфигурацию из файла: {}", config_file))?;
 
+    // Ветка релиза создается без LLM
+    if let Some(ReleaseAction::Branch(cmd)) = &command.action {
+        return handle_branch(&config, cmd).await;
+    }
+
     // Валидируем конфигурацию
-    config.validate()
+    config.validate_for(&[Capability::Ai])
         .with_context(|| "Валидация конфигурации не пройдена")?;
 
     // Создаем Git репозиторий
     let current_dir = std::env::current_dir()
         .context("Не удалось определить текущую директорию")?;
-    let git_repo = GitRepository::new(&current_dir);
+    let git_repo = GitRepository::from_config(&current_dir, &config)?;
 
     // Проверяем, что мы в git репозитории
     if !git_repo.is_valid_repository() {
-        eprintln!("{} Текущая директория не является git репозиторием", "❌".red());
+        out_errln!("{} Текущая директория не является git репозиторием", "❌".red());
         return Err(anyhow::anyhow!("Не git репозиторий"));
     }
 
     // Создаем менеджер LLM агентов
-    let agent_manager = LLMAgentManager::from_config(&config)
+    let agent_manager = LLMAgentManager::from_config(&config, system_clock())
         .context("Не удалось создать менеджер LLM агентов")?;
+    let transcript = agent_manager.transcript();
+
+    if let Some(ReleaseAction::Readiness(cmd)) = command.action {
+        return handle_readiness(&agent_manager, &git_repo, cmd).await;
+    }
 
     // Создаем менеджер релизов
     let release_manager = ReleaseManager::new(
         git_repo.clone(),
         agent_manager,
         config.project.clone(),
-    );
+    )
+    .with_proofreading(&config.proofreading)
+    .with_release_branches(&config.git);
+
+    if let Some(ReleaseAction::Backport(cmd)) = command.action {
+        return handle_backport(&config, &release_manager, &transcript, cmd).await;
+    }
 
     // Обрабатываем флаги
     if let Some(version) = command.rollback {
@@ -56,18 +85,211 @@ pub async fn handle_release_command(
     }
 
     // Основной процесс релиза
-    handle_release_process(&release_manager, command).await
+    handle_release_process(&release_manager, &transcript, command).await
+}
+
+/// Отчет о готовности к релизу; ошибка, если гейт не пройден
+async fn handle_readiness(
+    agent_manager: &LLMAgentManager,
+    git_repo: &GitRepository,
+    command: ReadinessCommand,
+) -> Result<()> {
+    let format = ReadinessFormat::parse(&command.format)?;
+    let version = command.version.clone().unwrap_or_else(|| "unreleased".to_string());
+
+    let report = agent_manager
+        .analyze_release_readiness(git_repo, &version).await
+        .context("Не удалось оценить готовность к релизу")?;
+    match format {
+        ReadinessFormat::Json => println!("{}", readiness::render(&report, format)?),
+        _ => out!("{}", readiness::render(&report, format)?),
+    }
+
+    // Отчет для конкретной версии сохраняется вместе с остальными материалами релиза
+    if command.version.is_some() {
+        let project_root = std::env::current_dir().context("Не удалось определить текущую директорию")?;
+        ReleaseVault::new(&project_root)
+            .store_json(&version, READINESS_FILE, &report)
+            .context("Не удалось сохранить отчет о готовности")?;
+    }
+
+    if !readiness::passes_gate(&report, command.strict) {
+        return Err(anyhow::anyhow!("Релиз {} не готов: {}", version, report.readiness_level.name()));
+    }
+    Ok(())
+}
+
+/// Создание ветки релиза `release/<major.minor>`
+async fn handle_branch(config: &Config, command: &BranchCommand) -> Result<()> {
+    let line = ReleaseLine::parse(&command.version)?;
+    let current_dir = std::env::current_dir().context("Не удалось определить текущую директорию")?;
+    let branches = ReleaseBranches::new(&current_dir, &config.git.release_branch_prefix);
+
+    // По умолчанию ветка начинается с первого релиза линии, если он уже выпущен
+    let from = match &command.from {
+        Some(from) => from.clone(),
+        None => {
+            let first_release = format!("{}{}.0", config.git.tag_prefix, line);
+            let tags = GitRepository::new(&current_dir).tags.get_all_tags().await?;
+            if tags.iter().any(|tag| tag.name == first_release) {
+                first_release
+            } else {
+                config.git.main_branch.clone()
+            }
+        }
+    };
+
+    let plan = branches
+        .plan(line, &from, &config.git.main_branch, &command.picks, command.pick_marked)
+        .await?;
+    outln!("🌿 Ветка {} от {} ({})", plan.branch.bright_green(), plan.base_ref, &plan.base[..plan.base.len().min(8)]);
+    if plan.picks.is_empty() {
+        outln!("  Коммитов для переноса нет");
+    }
+    for (hash, subject) in &plan.picks {
+        outln!("  🍒 {} {}", hash[..hash.len().min(8)].bright_black(), subject);
+    }
+
+    if command.dry_run {
+        outln!("\n🔍 DRY RUN MODE - ветка не будет создана");
+        return Ok(());
+    }
+
+    branches.create(&plan).await?;
+    if command.no_push {
+        outln!("✅ Ветка {} создана локально", plan.branch.green());
+    } else {
+        branches.push(&plan.branch, "origin").await?;
+        outln!("✅ Ветка {} создана и отправлена в origin", plan.branch.green());
+    }
+    outln!("Следующие релизы на этой ветке: {}.x (release, publish, run)", line);
+    Ok(())
+}
+
+/// Перенос исправлений в ветку релиза, сборка и черновик patch-релиза (без тега)
+async fn handle_backport(
+    config: &Config,
+    release_manager: &ReleaseManager,
+    transcript: &Transcript,
+    command: BackportCommand,
+) -> Result<()> {
+    let prefix = &config.git.release_branch_prefix;
+    let line = match ReleaseLine::from_branch(&command.to, prefix) {
+        Some(line) => line,
+        None => ReleaseLine::parse(&command.to)?,
+    };
+    let branch = line.branch_name(prefix);
+    let project_root = std::env::current_dir().context("Не удалось определить текущую директорию")?;
+    let branches = ReleaseBranches::new(&project_root, prefix);
+
+    let picked = branches.backport(&branch, &command.commits, &config.git.backport_keep_paths).await?;
+    outln!("🍒 Ветка {}:", branch.bright_green());
+    for commit in &picked {
+        outln!("  {} {} — {}", commit.hash[..commit.hash.len().min(8)].bright_black(), commit.subject, commit.resolution);
+    }
+    if picked.iter().all(|c| c.resolution == PickResolution::AlreadyApplied) {
+        outln!("Все изменения уже есть в ветке {}: релизить нечего", branch);
+        return Ok(());
+    }
+    if command.push {
+        branches.push(&branch, "origin").await?;
+    }
+
+    let version = release_manager.suggest_next_version().await?.version;
+    outln!("🏷️ Версия patch-релиза: {}", version.bright_green());
+    let vault = ReleaseVault::new(&project_root);
+    let mut files = vec![(BACKPORT_FILE.to_string(), serde_json::to_vec_pretty(&picked)?)];
+
+    if !command.no_build {
+        let mut config = config.clone();
+        let version = BuildProfile::select(&mut config, &command.profile)?.version(&version);
+        let build = PluginBuilder::new(config, project_root.clone())
+            .build(Some(version.clone()), &command.profile)
+            .await?;
+        files.push((BUILD_FILE.to_string(), serde_json::to_vec_pretty(&build)?));
+        if !build.success {
+            vault.store(&version, &files).context("Не удалось сохранить результат сборки")?;
+            anyhow::bail!("Сборка {} на ветке {} завершилась с ошибками", version, branch);
+        }
+        if let Some(artifact) = &build.artifact {
+            outln!("📦 Артефакт: {}", artifact.file_path.display());
+        }
+    }
+
+    // Ошибка LLM не отменяет перенос и сборку: черновик остается без changelog
+    match release_manager.prepare_release(Some(version.clone())).await {
+        Ok(draft) => {
+            for issue in draft.errors.iter().chain(&draft.warnings) {
+                warn!("⚠️ {}", issue);
+            }
+            for (name, content) in [(CHANGELOG_FILE, draft.release.changelog), (RELEASE_NOTES_FILE, draft.release.release_notes)] {
+                if let Some(content) = content {
+                    files.push((name.to_string(), content.into_bytes()));
+                }
+            }
+        }
+        Err(e) => warn!("⚠️ Changelog и release notes не подготовлены: {:#}", e),
+    }
+    vault.store(&version, &files).context("Не удалось сохранить черновик релиза")?;
+    if !transcript.is_empty() {
+        transcript.save(&project_root, &version)?;
+    }
+
+    outln!("🗄️ Черновик релиза: {}", vault.dir(&version).display());
+    outln!("Проверьте черновик и выполните `release --version {}` на ветке {}", version, branch);
+    Ok(())
+}
+
+/// Сохраненные материалы релиза из `.deploy-plugin/releases/<version>`
+fn handle_show(command: &ShowCommand) -> Result<()> {
+    let project_root = std::env::current_dir().context("Не удалось определить текущую директорию")?;
+    let vault = ReleaseVault::new(&project_root);
+
+    if let Some(file) = &command.file {
+        print!("{}", vault.read(&command.version, file)?);
+        return Ok(());
+    }
+
+    let Some(entry) = vault.show(&command.version)? else {
+        let known: Vec<String> = vault.index()?.into_iter().map(|e| e.version).collect();
+        return Err(anyhow::anyhow!(
+            "Для v{} ничего не сохранено{}",
+            command.version,
+            if known.is_empty() { String::new() } else { format!(" (есть: {})", known.join(", ")) }
+        ));
+    };
+
+    match command.format.as_str() {
+        "json" => println!("{}", serde_json::to_string_pretty(&entry)?),
+        "table" => {
+            outln!("🗄️ Релиз {} ({})", entry.version.bright_green(), vault.dir(&entry.version).display());
+            outln!("Обновлен: {}", entry.updated_at.format("%Y-%m-%d %H:%M:%S"));
+            for file in &entry.files {
+                outln!("  {:<24} {:>10} байт  {}", file.name, file.size, file.sha256[..12].bright_black());
+            }
+            for (name, title) in [(RELEASE_NOTES_FILE, "📝 RELEASE NOTES"), (CHANGELOG_FILE, "📋 CHANGELOG")] {
+                if entry.files.iter().any(|f| f.name == name) {
+                    outln!("\n{}:", title);
+                    outln!("{}", "-".repeat(40).bright_black());
+                    println!("{}", vault.read(&entry.version, name)?.trim_end());
+                }
+            }
+        }
+        other => return Err(anyhow::anyhow!("Неизвестный формат '{}': ожидается table или json", other)),
+    }
+    Ok(())
 }
 
 /// Обработка основного процесса релиза
 async fn handle_release_process(
     release_manager: &ReleaseManager,
+    transcript: &Transcript,
     command: ReleaseCommand,
 ) -> Result<()> {
     info!("📋 Подготовка релиза");
 
     if command.verbose {
-        println!("{} 🚀 Подготовка релиза", "=".repeat(60).bright_black());
+        outln!("{} 🚀 Подготовка релиза", "=".repeat(60).bright_black());
     }
 
     // Подготавливаем релиз
@@ -86,46 +308,64 @@ async fn handle_release_process(
     if !command.skip_validation && !preparation_result.validation_issues.is_empty() && !command.force {
         warn!("⚠️ Найдены проблемы валидации:");
         for issue in &preparation_result.validation_issues {
-            println!("  • {}", issue.yellow());
+            outln!("  • {}", issue.yellow());
         }
 
         if !command.dry_run {
-            println!("\nИспользуйте --force для игнорирования или --skip-validation для пропуска валидации");
+            outln!("\nИспользуйте --force для игнорирования или --skip-validation для пропуска валидации");
             return Err(anyhow::anyhow!("Валидация не пройдена"));
         }
     }
 
     // Dry run режим
     if command.dry_run {
-        println!("\n🔍 DRY RUN MODE - релиз не будет создан");
+        outln!("\n🔍 DRY RUN MODE - релиз не будет создан");
         if command.verbose {
-            println!("Используйте команду без --dry-run для создания реального релиза");
+            outln!("Используйте команду без --dry-run для создания реального релиза");
         }
         return Ok(());
     }
 
     // Создание релиза
-    println!("\n🏷️ Создание релиза...");
+    outln!("\n🏷️ Создание релиза...");
     let tag_name = release_manager.create_release(&preparation_result.release.version, None).await?;
 
-    println!("✅ Релиз {} создан", tag_name.green());
+    outln!("✅ Релиз {} создан", tag_name.green());
 
     // Сохранение файлов
     save_artifacts(&preparation_result, &command)?;
+    let project_root = std::env::current_dir().context("Не удалось определить текущую директорию")?;
+    let version = &preparation_result.release.version;
+    if !transcript.is_empty() {
+        let path = transcript.save(&project_root, version)?;
+        outln!("📝 Стенограмма LLM: {}", path.display());
+    }
+    let vault = ReleaseVault::new(&project_root);
+    let files: Vec<(&str, Vec<u8>)> = [
+        (CHANGELOG_FILE, &preparation_result.release.changelog),
+        (RELEASE_NOTES_FILE, &preparation_result.release.release_notes),
+    ]
+    .into_iter()
+    .filter_map(|(name, content)| content.as_ref().map(|c| (name, c.clone().into_bytes())))
+    .collect();
+    if !files.is_empty() {
+        vault.store(version, &files).context("Не удалось сохранить материалы релиза")?;
+        outln!("🗄️ Материалы релиза: {}", vault.dir(version).display());
+    }
 
     // Публикация
     if !command.no_publish {
-        println!("\n📤 Публикация релиза...");
+        outln!("\n📤 Публикация релиза...");
         release_manager.publish_release(&preparation_result.release.version).await?;
-        println!("✅ Релиз опубликован");
+        outln!("✅ Релиз опубликован");
     } else {
-        println!("📦 Релиз создан локально (опция --no-publish)");
+        outln!("📦 Релиз создан локально (опция --no-publish)");
     }
 
     // Финальное сообщение
-    println!("\n{}", "=".repeat(60).bright_black());
-    println!("🎉 Релиз {} успешно завершен!", preparation_result.release.version.green());
-    println!("{}", "=".repeat(60).bright_black());
+    outln!("\n{}", "=".repeat(60).bright_black());
+    outln!("🎉 Релиз {} успешно завершен!", preparation_result.release.version.green());
+    outln!("{}", "=".repeat(60).bright_black());
 
     Ok(())
 }
@@ -139,12 +379,12 @@ async fn handle_rollback(
     warn!("⏪ Откат релиза v{}", version);
 
     if verbose {
-        println!("🔍 Проверка существования релиза v{}", version);
+        outln!("🔍 Проверка существования релиза v{}", version);
     }
 
     release_manager.rollback_release(version).await?;
 
-    println!("✅ Релиз v{} откачен", version.green());
+    outln!("✅ Релиз v{} откачен", version.green());
     Ok(())
 }
 
@@ -159,87 +399,87 @@ async fn handle_history(
     let releases = release_manager.get_release_history(Some(limit)).await?;
 
     if releases.is_empty() {
-        println!("📭 Релизы не найдены");
+        outln!("📭 Релизы не найдены");
         return Ok(());
     }
 
-    println!("{}", "=".repeat(60).bright_black());
-    println!("📚 ИСТОРИЯ РЕЛИЗОВ (последние {})", releases.len());
-    println!("{}", "=".repeat(60).bright_black());
+    outln!("{}", "=".repeat(60).bright_black());
+    outln!("📚 ИСТОРИЯ РЕЛИЗОВ (последние {})", releases.len());
+    outln!("{}", "=".repeat(60).bright_black());
 
     for (index, release) in releases.iter().enumerate() {
-        println!("\n{}. {} ({})",
+        outln!("\n{}. {} ({})",
             index + 1,
             release.tag.bright_blue(),
             release.version.bright_green()
         );
 
         if verbose {
-            println!("   📅 Дата: {}", release.date.format("%Y-%m-%d %H:%M:%S"));
-            println!("   📝 Коммит: {}", release.commit);
+            outln!("   📅 Дата: {}", release.date.format("%Y-%m-%d %H:%M:%S"));
+            outln!("   📝 Коммит: {}", release.commit);
             if let Some(message) = &release.message {
-                println!("   💬 Сообщение: {}", message);
+                outln!("   💬 Сообщение: {}", message);
             }
-            println!("   📊 Изменений: {}", release.changes_count);
+            outln!("   📊 Изменений: {}", release.changes_count);
         }
     }
 
-    println!("\n{}", "=".repeat(60).bright_black());
+    outln!("\n{}", "=".repeat(60).bright_black());
     Ok(())
 }
 
 /// Отображение результата подготовки релиза
 fn display_preparation_result(result: &crate::core::releaser::ReleasePreparationResult, verbose: bool) {
-    println!("\n{}", "=".repeat(60).bright_black());
-    println!("📋 ПОДГОТОВКА РЕЛИЗА v{}", result.release.version.bright_green());
-    println!("{}", "=".repeat(60).bright_black());
+    outln!("\n{}", "=".repeat(60).bright_black());
+    outln!("📋 ПОДГОТОВКА РЕЛИЗА v{}", result.release.version.bright_green());
+    outln!("{}", "=".repeat(60).bright_black());
 
-    println!("📈 Версия: {} ({:?})", result.release.version.bright_green(), result.release.version_type);
-    println!("📊 Изменений: {}", result.release.changes_count);
-    println!("💥 Критических: {}", result.release.breaking_changes);
-    println!("📅 Дата: {}", result.release.estimated_release_date.format("%Y-%m-%d %H:%M:%S"));
+    outln!("📈 Версия: {} ({:?})", result.release.version.bright_green(), result.release.version_type);
+    outln!("📊 Изменений: {}", result.release.changes_count);
+    outln!("💥 Критических: {}", result.release.breaking_changes);
+    outln!("📅 Дата: {}", result.release.estimated_release_date.format("%Y-%m-%d %H:%M:%S"));
 
     if verbose {
         // Отображаем release notes
         if let Some(notes) = &result.release.release_notes {
-            println!("\n📝 RELEASE NOTES:");
-            println!("{}", "-".repeat(40).bright_black());
-            println!("{}", notes);
+            outln!("\n📝 RELEASE NOTES:");
+            outln!("{}", "-".repeat(40).bright_black());
+            outln!("{}", notes);
         }
 
         // Отображаем changelog
         if let Some(changelog) = &result.release.changelog {
-            println!("\n📋 CHANGELOG:");
-            println!("{}", "-".repeat(40).bright_black());
-            println!("{}", changelog);
+            outln!("\n📋 CHANGELOG:");
+            outln!("{}", "-".repeat(40).bright_black());
+            outln!("{}", changelog);
         }
     }
 
     // Отображаем предупреждения
     if !result.warnings.is_empty() {
-        println!("\n⚠️ ПРЕДУПРЕЖДЕНИЯ:");
+        outln!("\n⚠️ ПРЕДУПРЕЖДЕНИЯ:");
         for warning in &result.warnings {
-            println!("  • {}", warning.yellow());
+            outln!("  • {}", warning.yellow());
         }
     }
 
     // Отображаем ошибки
     if !result.errors.is_empty() {
-        println!("\n❌ ОШИБКИ:");
+        outln!("\n❌ ОШИБКИ:");
         for error in &result.errors {
-            println!("  • {}", error.red());
+            outln!("  • {}", error.red());
         }
     }
 
     // Отображаем проблемы валидации
     if !result.validation_issues.is_empty() {
-        println!("\n🔍 ПРОБЛЕМЫ ВАЛИДАЦИИ:");
+        outln!("\n🔍 ПРОБЛЕМЫ ВАЛИДАЦИИ:");
         for issue in &result.validation_issues {
-            println!("  • {}", issue.bright_yellow());
+            outln!("  • {}", issue.bright_yellow());
         }
     }
 
-    println!("{}", "=".repeat(60).bright_black());
+    outln!("{}", "=".repeat(60).bright_black());
 }
 
 /// Сохранение артефактов релиза
@@ -252,7 +492,7 @@ fn save_artifacts(
         if let Some(notes) = &result.release.release_notes {
             fs::write(file_path, notes)
                 .with_context(|| format!("Не удалось сохранить release notes в файл: {}", file_path))?;
-            println!("💾 Release notes сохранены: {}", file_path.green());
+            outln!("💾 Release notes сохранены: {}", file_path.green());
         }
     }
 
@@ -262,7 +502,7 @@ fn save_artifacts(
             let content = format!("# CHANGELOG v{}\n\n{}", result.release.version, changelog);
             fs::write(file_path, content)
                 .with_context(|| format!("Не удалось сохранить changelog в файл: {}", file_path))?;
-            println!("💾 Changelog сохранен: {}", file_path.green());
+            outln!("💾 Changelog сохранен: {}", file_path.green());
         }
     }
 
@@ -0,0 +1,73 @@
+use anyhow::{Context, Result};
+use colored::*;
+use tracing::info;
+
+use crate::cli::rollback::RollbackCommand;
+use crate::config::parser::Config;
+use crate::config::validator::Capability;
+use crate::core::audit;
+use crate::core::deployer::Deployer;
+use crate::core::rollback::{self, RollbackPlan};
+use crate::git::GitRepository;
+use crate::utils::clock::system_clock;
+
+/// Откат версии: план по серверу и git, затем индекс, артефакт, тег и запись аудита
+pub async fn handle_rollback_command(command: RollbackCommand, config_file: &str) -> Result<()> {
+    let config = Config::load_from_file(config_file)
+        .with_context(|| format!("Не удалось загрузить конфигурацию из файла: {}", config_file))?;
+    config.validate_for(&[Capability::Deploy]).context("Валидация конфигурации не пройдена")?;
+
+    let project_root = std::env::current_dir().context("Не удалось определить текущую директорию")?;
+    let git_repo = GitRepository::from_config(&project_root, &config)?;
+    let tags = if git_repo.is_valid_repository() { git_repo.tags.get_all_tags().await? } else { Vec::new() };
+
+    let version = command.version.trim_start_matches('v').to_string();
+    let restore = match command.to.clone() {
+        Some(to) => to.trim_start_matches('v').to_string(),
+        None => rollback::previous_release(&tags, &version)
+            .with_context(|| format!("Нет релизного тега младше {}: укажите версию для индекса через --to", version))?,
+    };
+
+    // План: сервер только читается
+    let clock = system_clock();
+    let deployer = Deployer::new(config).with_channel(command.channel.clone()).with_clock(clock.clone());
+    let index = deployer.promote(&restore, true)?;
+    if index.from_version.as_deref() != Some(version.as_str()) {
+        anyhow::bail!(
+            "В updatePlugins.xml опубликована версия {}, а не {}",
+            index.from_version.as_deref().unwrap_or("-"),
+            version
+        );
+    }
+    let artifacts = if command.delete_artifact { deployer.remove_version_artifact(&version, true)? } else { Vec::new() };
+    let tag = format!("v{}", version);
+    let plan = RollbackPlan {
+        tag: (!command.keep_tag && tags.iter().any(|t| t.name == tag)).then_some(tag),
+        version,
+        index,
+        artifacts,
+    };
+
+    outln!("⏪ Откат {}:", plan.version.yellow());
+    for step in plan.steps() {
+        outln!("  • {}", step);
+    }
+    if command.dry_run {
+        info!("🔍 Dry run: сервер и git не изменены");
+        return Ok(());
+    }
+
+    // Индекс — первым: пользователи перестают получать версию, даже если дальше что-то не удастся
+    deployer.promote(&plan.index.to_version, false)?;
+    if command.delete_artifact {
+        deployer.remove_version_artifact(&plan.version, false)?;
+    }
+    if let Some(tag) = &plan.tag {
+        rollback::remove_tag(&git_repo.path, tag).await?;
+    }
+
+    let audit_file = audit::append(&project_root, &plan.audit_entry(command.reason, clock.as_ref()))?;
+    outln!("✅ Версия {} откачена, опубликована {}", plan.version, plan.index.to_version.green());
+    info!("📝 Запись аудита: {}", audit_file.display());
+    Ok(())
+}
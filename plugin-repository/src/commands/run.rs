@@ -0,0 +1,302 @@
+use anyhow::{Context, Result};
+use colored::*;
+use tracing::{info, warn};
+
+use crate::cli::run::RunCommand;
+use crate::commands::deploy::print_deploy_report;
+use crate::commands::publish::render_change_notes;
+use crate::config::parser::Config;
+use crate::core::build_profile::BuildProfile;
+use crate::core::builder::PluginBuilder;
+use crate::core::deadline::{parse_duration, Deadline, DeadlineExceeded};
+use crate::core::deployer::Deployer;
+use crate::core::external_version::{self, VersionSource};
+use crate::core::inspector::ArtifactInspector;
+use crate::core::interrupt::Interrupted;
+use crate::core::llm::agents::LLMAgentManager;
+use crate::core::pipeline::{parse_pipeline, Stage};
+use crate::core::release_vault::{ReleaseVault, CHANGELOG_FILE, DEPLOY_REPORT_FILE, RELEASE_NOTES_FILE};
+use crate::core::releaser::ReleaseManager;
+use crate::core::version_guard;
+use crate::git::GitRepository;
+use crate::models::plugin::BuildResult;
+use crate::utils::clock::{system_clock, SharedClock};
+
+/// Обработчик команды run: пайплайн из `[pipelines.<имя>]`
+pub async fn handle_run_command(cmd: RunCommand, config_file: &str) -> Result<()> {
+    let config = Config::load_from_file(config_file)
+        .with_context(|| format!("Не удалось загрузить конфигурацию из файла: {}", config_file))?;
+
+    let Some(name) = cmd.pipeline.clone() else {
+        return list_pipelines(&config);
+    };
+    let pipeline = config.pipelines.get(&name).with_context(|| {
+        let known: Vec<&str> = config.pipelines.keys().map(String::as_str).collect();
+        format!(
+            "Пайплайн '{}' не найден в [pipelines]{}",
+            name,
+            if known.is_empty() { String::new() } else { format!(": доступны {}", known.join(", ")) }
+        )
+    })?;
+    let stages = parse_pipeline(&name, pipeline)?;
+
+    outln!("🧩 Пайплайн {}: {}", name.bright_cyan(), render_stages(&stages));
+    if cmd.dry_run {
+        outln!("🧪 DRY RUN — этапы не выполняются");
+        return Ok(());
+    }
+
+    let mut capabilities: Vec<_> = stages.iter().filter_map(Stage::capability).collect();
+    capabilities.dedup();
+    config.validate_for(&capabilities).context("Валидация конфигурации не пройдена")?;
+
+    let budget = cmd.max_duration.as_deref().map(parse_duration).transpose()
+        .context("Некорректное значение --max-duration")?;
+    let deadline = Deadline::new(budget);
+    let result = PipelineRun::new(config, &cmd, &deadline)?.run(&stages).await;
+    match &result {
+        Ok(()) => {
+            outln!("{}", deadline.report());
+            outln!("✅ Пайплайн {} завершен", name.bright_cyan());
+        }
+        Err(e) => {
+            if let Some(exceeded) = e.downcast_ref::<DeadlineExceeded>() {
+                outln!("{} {}", "⏰".red(), exceeded.to_string().red());
+            }
+            outln!("{}", deadline.report());
+        }
+    }
+    result
+}
+
+fn list_pipelines(config: &Config) -> Result<()> {
+    if config.pipelines.is_empty() {
+        outln!("В конфигурации нет пайплайнов: добавьте [pipelines.<имя>] stages = [...]");
+        return Ok(());
+    }
+    outln!("🧩 Пайплайны:");
+    for (name, pipeline) in &config.pipelines {
+        let stages = parse_pipeline(name, pipeline)?;
+        outln!("  {:<16} {}", name.bright_cyan(), render_stages(&stages));
+        if let Some(description) = &pipeline.description {
+            outln!("  {:<16} {}", "", description.bright_black());
+        }
+    }
+    Ok(())
+}
+
+fn render_stages(stages: &[Stage]) -> String {
+    stages.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(" → ")
+}
+
+/// Состояние запуска: результаты этапов передаются следующим (артефакт, change-notes)
+struct PipelineRun<'a> {
+    config: Config,
+    cmd: &'a RunCommand,
+    deadline: &'a Deadline,
+    project_root: std::path::PathBuf,
+    /// Часы запуска для всех этапов
+    clock: SharedClock,
+    releaser: Option<ReleaseManager>,
+    version: Option<String>,
+    build: Option<BuildResult>,
+    change_notes: Option<String>,
+    release_message: Option<String>,
+}
+
+impl<'a> PipelineRun<'a> {
+    fn new(config: Config, cmd: &'a RunCommand, deadline: &'a Deadline) -> Result<Self> {
+        let project_root = std::env::current_dir().context("Не удалось определить текущую директорию")?;
+        Ok(Self {
+            config,
+            cmd,
+            deadline,
+            project_root,
+            clock: system_clock(),
+            releaser: None,
+            version: None,
+            build: None,
+            change_notes: None,
+            release_message: None,
+        })
+    }
+
+    async fn run(mut self, stages: &[Stage]) -> Result<()> {
+        // Профиль накладывается до всех этапов: деплой берет артефакт из его output_dir,
+        // а тег и релиз получают версию с суффиксом профиля
+        let profile = stages
+            .iter()
+            .find_map(|stage| match stage {
+                Stage::Build { profile } => Some(BuildProfile::select(&mut self.config, profile)),
+                _ => None,
+            })
+            .transpose()?;
+        if stages.iter().any(Stage::needs_version) {
+            let version = self.resolve_version().await?;
+            let version = profile.as_ref().map_or(version.clone(), |p| p.version(&version));
+            outln!("🏷️ Версия: {}", version.bright_green());
+            self.version = Some(version);
+        }
+
+        for (i, stage) in stages.iter().enumerate() {
+            outln!("\n▶️ [{}/{}] {}", i + 1, stages.len(), stage.to_string().bold());
+            self.deadline.check(stage.name())?;
+            self.run_stage(stage).await.with_context(|| format!("Этап {} не выполнен", stage))?;
+        }
+        Ok(())
+    }
+
+    /// Версия как у publish: `--version`, `--version-file`, DEPLOY_PLUGIN_VERSION или `--auto-version`
+    async fn resolve_version(&mut self) -> Result<String> {
+        let external = external_version::resolve(
+            self.cmd.version.as_deref(),
+            self.cmd.version_file.as_deref(),
+            std::env::var(external_version::VERSION_ENV).ok(),
+        )?;
+        match external {
+            Some((version, source)) => {
+                if source != VersionSource::Flag {
+                    info!("🏷️ Версия {} задана извне ({})", version, source);
+                }
+                Ok(version)
+            }
+            None if self.cmd.auto_version => {
+                let planned = self.deadline.run("version", self.releaser()?.suggest_next_version()).await?;
+                Ok(planned.version)
+            }
+            None => anyhow::bail!(
+                "Этапам пайплайна нужна версия. Используйте --version, --version-file, {} или --auto-version",
+                external_version::VERSION_ENV
+            ),
+        }
+    }
+
+    fn releaser(&mut self) -> Result<&ReleaseManager> {
+        if self.releaser.is_none() {
+            let git_repo = GitRepository::from_config(&self.project_root, &self.config)?;
+            if !git_repo.is_valid_repository() {
+                anyhow::bail!("Текущая директория не является git репозиторием");
+            }
+            let agent_manager = LLMAgentManager::from_config(&self.config, self.clock.clone()).context("Не удалось создать LLM агент менеджер")?;
+            self.releaser = Some(
+                ReleaseManager::new(git_repo, agent_manager, self.config.project.clone())
+                    .with_proofreading(&self.config.proofreading)
+                    .with_release_branches(&self.config.git),
+            );
+        }
+        Ok(self.releaser.as_ref().expect("releaser initialized"))
+    }
+
+    fn version(&self) -> Result<String> {
+        self.version.clone().context("Версия не определена")
+    }
+
+    async fn run_stage(&mut self, stage: &Stage) -> Result<()> {
+        let deadline = self.deadline;
+        match stage {
+            Stage::Build { profile } => {
+                let builder = PluginBuilder::new(self.config.clone(), self.project_root.clone()).with_clock(self.clock.clone());
+                let build = deadline.run("build", builder.build(Some(self.version()?), profile)).await?;
+                if !build.success {
+                    anyhow::bail!("Сборка завершилась с ошибками");
+                }
+                if let Some(artifact) = &build.artifact {
+                    outln!("📦 Артефакт: {}", artifact.file_path.display());
+                }
+                self.build = Some(build);
+            }
+            Stage::Verify { max_resource_size } => {
+                let artifact = self
+                    .build
+                    .as_ref()
+                    .and_then(|b| b.artifact.as_ref())
+                    .context("Сборка не создала артефакт для проверки")?;
+                let report = deadline
+                    .run("verify", async { ArtifactInspector::new(*max_resource_size).inspect(&artifact.file_path) })
+                    .await?;
+                let failed: Vec<&str> = report
+                    .checks
+                    .iter()
+                    .filter(|c| c.status == crate::core::inspector::CheckStatus::Failed)
+                    .map(|c| c.name.as_str())
+                    .collect();
+                if !failed.is_empty() {
+                    anyhow::bail!("Артефакт не прошел проверки: {}", failed.join(", "));
+                }
+                outln!("✅ Проверок пройдено: {}", report.checks.len());
+            }
+            Stage::Validate => {
+                let deployer = Deployer::new(self.config.clone());
+                deadline.run("validate", deployer.validate()).await.context("Валидация перед деплоем не пройдена")?;
+                outln!("✅ Валидация пройдена");
+            }
+            Stage::Notes => {
+                let version = self.version()?;
+                let prep = deadline.run("notes", self.releaser()?.prepare_release(Some(version.clone()))).await?;
+                for warning in &prep.warnings {
+                    warn!("{}", warning);
+                }
+                let changelog = prep.release.changelog;
+                let release_notes = prep.release.release_notes;
+                if let Some(notes) = &release_notes {
+                    self.change_notes = Some(render_change_notes(&self.config, notes)?);
+                    self.release_message = Some(notes.clone());
+                } else if let Some(changelog) = &changelog {
+                    self.release_message = Some(format!("Changelog for v{}\n\n{}", version, changelog));
+                }
+                let files: Vec<(&str, Vec<u8>)> = [(CHANGELOG_FILE, changelog), (RELEASE_NOTES_FILE, release_notes)]
+                    .into_iter()
+                    .filter_map(|(name, content)| content.map(|c| (name, c.into_bytes())))
+                    .collect();
+                if files.is_empty() {
+                    anyhow::bail!("Changelog и release notes не сгенерированы");
+                }
+                let vault = ReleaseVault::new(&self.project_root).with_clock(self.clock.clone());
+                vault.store(&version, &files).context("Не удалось сохранить материалы релиза")?;
+                outln!("🗄️ Материалы релиза: {}", vault.dir(&version).display());
+            }
+            Stage::Release => {
+                let version = self.version()?;
+                // Несовпадение версий обнаруживается до создания тега, а не на деплое после push
+                if let Some(artifact) = self.build.as_ref().and_then(|b| b.artifact.as_ref()) {
+                    version_guard::check(&artifact.file_path, Some(&version))?;
+                }
+                let message = self.release_message.clone();
+                let releaser = self.releaser()?;
+                let released = async {
+                    let tag = deadline.run("release", releaser.create_release(&version, message)).await?;
+                    deadline.run("publish", releaser.publish_release(&version)).await?;
+                    Ok::<_, anyhow::Error>(tag)
+                };
+                match released.await {
+                    Ok(tag) => outln!("✅ Релиз {} создан и опубликован", tag.green()),
+                    Err(e) => {
+                        if e.is::<Interrupted>() {
+                            releaser.discard_interrupted_release(&version).await;
+                        }
+                        return Err(e);
+                    }
+                }
+            }
+            Stage::Deploy { channel, force, rollback_on_failure, allow_republish } => {
+                let deployer = Deployer::new(self.config.clone())
+                    .with_clock(self.clock.clone())
+                    .with_allow_republish(*allow_republish)
+                    .with_channel(channel.clone())
+                    .with_change_notes(self.change_notes.clone())
+                    .with_release_version(self.version.clone())
+                    .with_deadline(Some(deadline.clone()));
+                let report = deadline.run("deploy", deployer.deploy(*force, *rollback_on_failure)).await?;
+                print_deploy_report(&report);
+                if let Some(version) = &self.version {
+                    ReleaseVault::new(&self.project_root)
+                        .with_clock(self.clock.clone())
+                        .store_json(version, DEPLOY_REPORT_FILE, &report)
+                        .context("Не удалось сохранить отчет деплоя")?;
+                }
+                outln!("✅ Деплой завершен");
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,462 @@
+use anyhow::{Context, Result};
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::cli::publish::PublishCommand;
+use crate::cli::serve::ServeCommand;
+use crate::commands::chatops;
+use crate::commands::publish::{check_channel, deadline_for, run_publish, PublishOutcome};
+use crate::config::parser::Config;
+use crate::config::validator::ConfigValidator;
+use crate::core::chatops::TelegramClient;
+use crate::core::deadline::Deadline;
+use crate::utils::exit::ExitStatus;
+
+/// Обработчик команды serve
+pub async fn handle_serve_command(cmd: ServeCommand, config_file: &str) -> Result<()> {
+    let config = Config::load_from_file(config_file)
+        .with_context(|| format!("Не удалось загрузить конфигурацию из файла: {}", config_file))?;
+    let mut server = config.server.clone();
+    if let Some(bind) = cmd.bind {
+        server.bind = bind;
+    }
+    ConfigValidator::validate_server(&server).context("Валидация [server] не пройдена")?;
+
+    ConfigValidator::validate_chatops(&config.chatops).context("Валидация [chatops] не пройдена")?;
+
+    let state = ApiState::new(config_file, server.token.unwrap_or_default().trim(), server.max_runs);
+    if config.chatops.enabled {
+        let client = TelegramClient::new(&config.chatops, &config.network.proxy)?;
+        tokio::spawn(chatops::run_bot(client, config.chatops.clone(), state.clone()));
+        outln!("🤖 ChatOps: команды /release из чата {}", config.chatops.chat_id);
+    }
+    let listener = tokio::net::TcpListener::bind(&server.bind)
+        .await
+        .with_context(|| format!("Не удалось занять адрес {}", server.bind))?;
+
+    outln!("🛰️ API публикации: http://{}", server.bind.bright_cyan());
+    axum::serve(listener, router(state))
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+            info!("Остановка API публикации");
+        })
+        .await
+        .context("Ошибка HTTP сервера")
+}
+
+/// Параметры публикации (тело `POST /api/v1/publish`), соответствуют флагам `publish`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PublishRequest {
+    pub version: Option<String>,
+    pub auto_version: bool,
+    pub profile: Option<String>,
+    pub channel: Option<String>,
+    pub force: bool,
+    pub rollback_on_failure: bool,
+    pub allow_republish: bool,
+    pub dry_run: bool,
+    pub no_ai: bool,
+    pub max_duration: Option<String>,
+    pub max_cost: Option<f64>,
+    pub mcp_workspace: bool,
+}
+
+impl PublishRequest {
+    fn to_command(&self) -> PublishCommand {
+        PublishCommand {
+            version: self.version.clone(),
+            version_file: None,
+            auto_version: self.auto_version,
+            profile: self.profile.clone().unwrap_or_else(|| "release".to_string()),
+            force: self.force,
+            rollback_on_failure: self.rollback_on_failure,
+            allow_republish: self.allow_republish,
+            channel: self.channel.clone(),
+            skip_validation: false,
+            dry_run: self.dry_run,
+            bundle_dir: None,
+            no_enrich: false,
+            enrich_dry_run: false,
+            upload_transcript: false,
+            no_ai: self.no_ai,
+            max_duration: self.max_duration.clone(),
+            max_cost: self.max_cost,
+            mcp_workspace: self.mcp_workspace,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunState {
+    Running,
+    Succeeded,
+    /// Публикация пропущена: после последнего тега нет коммитов
+    UpToDate,
+    Failed,
+}
+
+/// Запуск публикации через API
+struct Run {
+    id: u64,
+    request: PublishRequest,
+    state: RunState,
+    started_at: DateTime<Utc>,
+    finished_at: Option<DateTime<Utc>>,
+    deadline: Deadline,
+    error: Option<String>,
+    outcome: Option<PublishOutcome>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct StageStatus {
+    pub stage: String,
+    pub duration_ms: u128,
+    pub completed: bool,
+}
+
+/// Статус запуска: состояние, текущий и завершенные этапы пайплайна
+#[derive(Debug, Serialize)]
+pub(crate) struct RunStatus {
+    pub id: u64,
+    pub state: RunState,
+    pub request: PublishRequest,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub stage: Option<String>,
+    pub stages: Vec<StageStatus>,
+    pub error: Option<String>,
+}
+
+impl Run {
+    fn status(&self) -> RunStatus {
+        RunStatus {
+            id: self.id,
+            state: self.state,
+            request: self.request.clone(),
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+            stage: self.deadline.current_stage(),
+            stages: self
+                .deadline
+                .stages()
+                .into_iter()
+                .map(|s| StageStatus { stage: s.stage, duration_ms: s.duration.as_millis(), completed: s.completed })
+                .collect(),
+            error: self.error.clone(),
+        }
+    }
+}
+
+/// Последние запуски (новые в начале), не более `max_runs`
+struct Runs {
+    next_id: u64,
+    max_runs: usize,
+    items: VecDeque<Run>,
+}
+
+impl Runs {
+    fn get(&self, id: u64) -> Option<&Run> {
+        self.items.iter().find(|r| r.id == id)
+    }
+
+    fn get_mut(&mut self, id: u64) -> Option<&mut Run> {
+        self.items.iter_mut().find(|r| r.id == id)
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct ApiState {
+    config_file: Arc<str>,
+    token: Arc<str>,
+    runs: Arc<Mutex<Runs>>,
+}
+
+impl ApiState {
+    fn new(config_file: &str, token: &str, max_runs: usize) -> Self {
+        Self {
+            config_file: config_file.into(),
+            token: token.into(),
+            runs: Arc::new(Mutex::new(Runs { next_id: 1, max_runs, items: VecDeque::new() })),
+        }
+    }
+
+    fn runs(&self) -> std::sync::MutexGuard<'_, Runs> {
+        self.runs.lock().expect("api runs poisoned")
+    }
+
+    /// Запускает публикацию в фоне; одновременно выполняется только одна
+    pub(crate) fn start(&self, request: PublishRequest) -> Result<RunStatus, ApiError> {
+        let cmd = request.to_command();
+        let deadline = deadline_for(&cmd).map_err(|e| ApiError(StatusCode::BAD_REQUEST, format!("{:#}", e)))?;
+        check_channel(&cmd).map_err(|e| ApiError(StatusCode::BAD_REQUEST, format!("{:#}", e)))?;
+
+        let status = {
+            let mut runs = self.runs();
+            if let Some(active) = runs.items.iter().find(|r| r.state == RunState::Running) {
+                return Err(ApiError(
+                    StatusCode::CONFLICT,
+                    format!("Публикация уже выполняется (запуск {})", active.id),
+                ));
+            }
+            let id = runs.next_id;
+            runs.next_id += 1;
+            let run = Run {
+                id,
+                request,
+                state: RunState::Running,
+                started_at: Utc::now(),
+                finished_at: None,
+                deadline: deadline.clone(),
+                error: None,
+                outcome: None,
+            };
+            let status = run.status();
+            runs.items.push_front(run);
+            let max_runs = runs.max_runs;
+            runs.items.truncate(max_runs);
+            status
+        };
+
+        info!("🧩 Публикация через API, запуск {}", status.id);
+        let id = status.id;
+        let config_file = self.config_file.clone();
+        let task = tokio::spawn(async move { run_publish(cmd, &config_file, &deadline).await });
+        self.track(id, task);
+
+        Ok(status)
+    }
+
+    /// Дожидается задачи публикации и записывает итог запуска. Паника задачи тоже завершает
+    /// запуск ошибкой: иначе он навсегда остался бы `running` и отклонял новые публикации
+    fn track(&self, id: u64, task: JoinHandle<Result<PublishOutcome>>) {
+        let state = self.clone();
+        tokio::spawn(async move {
+            let result = task
+                .await
+                .unwrap_or_else(|e| Err(anyhow::anyhow!("Публикация аварийно завершилась: {}", e)));
+            let mut runs = state.runs();
+            if let Some(run) = runs.get_mut(id) {
+                run.finished_at = Some(Utc::now());
+                match result {
+                    Ok(outcome) => {
+                        run.state = RunState::Succeeded;
+                        run.outcome = Some(outcome);
+                    }
+                    Err(e) if e.is::<ExitStatus>() => {
+                        info!("Запуск {}: {}", id, e);
+                        run.state = RunState::UpToDate;
+                    }
+                    Err(e) => {
+                        warn!("Запуск {} завершился ошибкой: {:#}", id, e);
+                        run.state = RunState::Failed;
+                        run.error = Some(format!("{:#}", e));
+                    }
+                }
+            }
+        });
+    }
+
+    pub(crate) fn status(&self, id: u64) -> Option<RunStatus> {
+        self.runs().get(id).map(Run::status)
+    }
+}
+
+/// Ошибка API: код и сообщение в JSON `{"error": "..."}`
+pub(crate) struct ApiError(pub StatusCode, pub String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+fn router(state: ApiState) -> Router {
+    let api = Router::new()
+        .route("/publish", post(start_publish))
+        .route("/runs", get(list_runs))
+        .route("/runs/:id", get(get_run))
+        .route("/runs/:id/report", get(get_report))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authenticate));
+
+    Router::new()
+        .route("/health", get(|| async { Json(serde_json::json!({ "status": "ok" })) }))
+        .nest("/api/v1", api)
+        .with_state(state)
+}
+
+/// Проверка `Authorization: Bearer <token>`
+async fn authenticate(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    if !constant_time_eq(provided.trim().as_bytes(), state.token.as_bytes()) {
+        warn!("Отклонен запрос к API без действительного токена: {}", request.uri().path());
+        let mut response = ApiError(StatusCode::UNAUTHORIZED, "Требуется Bearer-токен".to_string()).into_response();
+        response.headers_mut().insert(header::WWW_AUTHENTICATE, header::HeaderValue::from_static("Bearer"));
+        return response;
+    }
+    next.run(request).await
+}
+
+/// Сравнение без раннего выхода: время не зависит от позиции первого расхождения
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn start_publish(
+    State(state): State<ApiState>,
+    Json(request): Json<PublishRequest>,
+) -> Result<(StatusCode, Json<RunStatus>), ApiError> {
+    state.start(request).map(|status| (StatusCode::ACCEPTED, Json(status)))
+}
+
+async fn list_runs(State(state): State<ApiState>) -> Json<Vec<RunStatus>> {
+    Json(state.runs().items.iter().map(Run::status).collect())
+}
+
+async fn get_run(State(state): State<ApiState>, Path(id): Path<u64>) -> Result<Json<RunStatus>, ApiError> {
+    state
+        .runs()
+        .get(id)
+        .map(|run| Json(run.status()))
+        .ok_or_else(|| not_found(id))
+}
+
+/// Отчет завершенного запуска: сборка (с окружением), changelog, release notes, деплой
+async fn get_report(State(state): State<ApiState>, Path(id): Path<u64>) -> Result<Json<PublishOutcome>, ApiError> {
+    let runs = state.runs();
+    let run = runs.get(id).ok_or_else(|| not_found(id))?;
+    match (&run.state, &run.outcome) {
+        (RunState::Succeeded, Some(outcome)) => Ok(Json(outcome.clone())),
+        (RunState::Running, _) => Err(ApiError(StatusCode::CONFLICT, format!("Запуск {} еще выполняется", id))),
+        (RunState::UpToDate, _) => Err(ApiError(StatusCode::CONFLICT, format!("Запуск {} пропущен: нечего релизить", id))),
+        _ => Err(ApiError(
+            StatusCode::CONFLICT,
+            format!("Запуск {} завершился ошибкой: {}", id, run.error.as_deref().unwrap_or("нет отчета")),
+        )),
+    }
+}
+
+fn not_found(id: u64) -> ApiError {
+    ApiError(StatusCode::NOT_FOUND, format!("Запуск {} не найден", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "test-token-0123456789";
+
+    #[tokio::test]
+    async fn test_api_requires_token_and_tracks_runs() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let state = ApiState::new("missing-config.toml", TOKEN, 10);
+        tokio::spawn(async move { axum::serve(listener, router(state)).await.unwrap() });
+        let client = reqwest::Client::new();
+
+        assert_eq!(client.get(format!("{}/health", base)).send().await.unwrap().status(), 200);
+        let unauthorized = client.get(format!("{}/api/v1/runs", base)).bearer_auth("wrong").send().await.unwrap();
+        assert_eq!(unauthorized.status(), 401);
+
+        let bad = client
+            .post(format!("{}/api/v1/publish", base))
+            .bearer_auth(TOKEN)
+            .json(&serde_json::json!({ "version": "1.2.0", "max_duration": "10x" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(bad.status(), 400);
+        let escape = client
+            .post(format!("{}/api/v1/publish", base))
+            .bearer_auth(TOKEN)
+            .json(&serde_json::json!({ "version": "1.2.0", "channel": "../../etc" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(escape.status(), 400);
+
+        // Конфигурации нет: запуск принимается и завершается ошибкой
+        let started: serde_json::Value = client
+            .post(format!("{}/api/v1/publish", base))
+            .bearer_auth(TOKEN)
+            .json(&serde_json::json!({ "version": "1.2.0", "no_ai": true }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(started["id"], 1);
+
+        let mut status = serde_json::Value::Null;
+        for _ in 0..50 {
+            status = client
+                .get(format!("{}/api/v1/runs/1", base))
+                .bearer_auth(TOKEN)
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            if status["state"] != "running" {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(status["state"], "failed");
+        assert!(status["error"].as_str().unwrap().contains("missing-config.toml"));
+
+        let report = client.get(format!("{}/api/v1/runs/1/report", base)).bearer_auth(TOKEN).send().await.unwrap();
+        assert_eq!(report.status(), 409);
+        let missing = client.get(format!("{}/api/v1/runs/7", base)).bearer_auth(TOKEN).send().await.unwrap();
+        assert_eq!(missing.status(), 404);
+    }
+
+    #[tokio::test]
+    async fn test_panicked_run_is_failed_and_unblocks_publish() {
+        let state = ApiState::new("missing-config.toml", TOKEN, 10);
+        state.runs().items.push_front(Run {
+            id: 1,
+            request: PublishRequest::default(),
+            state: RunState::Running,
+            started_at: Utc::now(),
+            finished_at: None,
+            deadline: Deadline::new(None),
+            error: None,
+            outcome: None,
+        });
+        assert_eq!(state.start(PublishRequest::default()).unwrap_err().0, StatusCode::CONFLICT);
+
+        let task: JoinHandle<Result<PublishOutcome>> = tokio::spawn(async { panic!("сбой в задаче") });
+        state.track(1, task);
+        let mut status = state.status(1).unwrap();
+        for _ in 0..50 {
+            if status.state != RunState::Running {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            status = state.status(1).unwrap();
+        }
+        assert_eq!(status.state, RunState::Failed);
+        assert!(status.error.unwrap().contains("сбой в задаче"));
+        assert!(state.start(PublishRequest::default()).is_ok());
+    }
+}
@@ -2,10 +2,12 @@ use anyhow::{Context, Result};
 use colored::*;
 use tracing::{info, warn};
 
-use crate::cli::status::StatusCommand;
+use crate::cli::status::{StatusAction, StatusCommand, UnreleasedCommand};
 use crate::config::parser::Config;
 use crate::core::releaser::ReleaseManager;
+use crate::core::unreleased::UnreleasedReport;
 use crate::git::GitRepository;
+use crate::utils::exit::{ExitStatus, NOTHING_TO_RELEASE};
 
 /// Обработчик команды status
 pub async fn handle_status_command(cmd: StatusCommand, config_file: &str) -> Result<()> {
@@ -16,37 +18,41 @@ pub async fn handle_status_command(cmd: StatusCommand, config_file: &str) -> Res
 
     // Git repo из текущей директории
     let current_dir = std::env::current_dir().context("Не удалось получить текущую директорию")?;
-    let git_repo = GitRepository::new(&current_dir);
+    let git_repo = GitRepository::from_config(&current_dir, &config)?;
+
+    if let Some(StatusAction::Unreleased(unreleased)) = cmd.action {
+        return handle_unreleased(unreleased, &git_repo, &config).await;
+    }
 
     if cmd.repository || (!cmd.releases) {
         // Минимальная сводка по репозиторию
         let is_repo = git_repo.is_valid_repository();
-        println!("{} Репозиторий: {}", "📁", if is_repo { "OK".green().to_string() } else { "NOT A GIT REPO".red().to_string() });
+        outln!("{} Репозиторий: {}", "📁", if is_repo { "OK".green().to_string() } else { "NOT A GIT REPO".red().to_string() });
         if is_repo {
             if let Ok(branch) = git_repo.history.get_current_branch().await {
-                println!("  • Текущая ветка: {}", branch.bright_blue());
+                outln!("  • Текущая ветка: {}", branch.bright_blue());
             }
             if let Ok(mut tags) = git_repo.tags.get_all_tags().await {
                 // берём только первые 5
                 tags.truncate(5);
-                println!("  • Теги: {}", tags.iter().map(|t| t.name.clone()).collect::<Vec<_>>().join(", "));
+                outln!("  • Теги: {}", tags.iter().map(|t| t.name.clone()).collect::<Vec<_>>().join(", "));
             }
         }
     }
 
     if cmd.releases {
-        let agent_manager = crate::core::llm::agents::LLMAgentManager::from_config(&config)
+        let agent_manager = crate::core::llm::agents::LLMAgentManager::from_config(&config, crate::utils::clock::system_clock())
             .with_context(|| "Не удалось создать LLM агент менеджер")?;
         let release_manager = ReleaseManager::new(git_repo.clone(), agent_manager, config.project.clone());
         match release_manager.get_release_history(Some(5)).await {
             Ok(list) => {
-                println!("\n{} Последние релизы:", "🏷️");
+                outln!("\n{} Последние релизы:", "🏷️");
                 if cmd.format == "json" {
                     let json = serde_json::to_string_pretty(&list).unwrap_or_else(|_| "[]".to_string());
                     println!("{}", json);
                 } else {
                     for (i, r) in list.iter().enumerate() {
-                        println!("{}. {} ({})", i + 1, r.tag.bright_blue(), r.version.bright_green());
+                        outln!("{}. {} ({})", i + 1, r.tag.bright_blue(), r.version.bright_green());
                     }
                 }
             }
@@ -59,13 +65,38 @@ pub async fn handle_status_command(cmd: StatusCommand, config_file: &str) -> Res
     Ok(())
 }
 
+/// Коммиты с последнего релизного тега; код выхода сообщает, нужен ли релиз
+async fn handle_unreleased(cmd: UnreleasedCommand, git_repo: &GitRepository, config: &Config) -> Result<()> {
+    if !git_repo.is_valid_repository() {
+        return Err(anyhow::anyhow!("Текущая директория не является git репозиторием"));
+    }
+
+    let last_tag = git_repo.tags.get_latest_tag().await?.map(|t| t.name);
+    let (analysis, commits) = git_repo
+        .get_full_analysis(last_tag.as_deref(), None)
+        .await
+        .context("Не удалось проанализировать изменения с последнего релиза")?;
+    let report = UnreleasedReport::build(last_tag.as_deref(), &config.git.tag_prefix, &commits, &analysis);
+
+    if cmd.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        out!("{}", report.render_table());
+    }
+
+    if !report.release_warranted {
+        return Err(ExitStatus::new(NOTHING_TO_RELEASE, "Нечего релизить").into());
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[tokio::test]
     async fn test_handle_status_command_runs() {
-        let cmd = StatusCommand { releases: true, repository: true, format: "table".to_string() };
+        let cmd = StatusCommand { action: None, releases: true, repository: true, format: "table".to_string() };
         let _ = handle_status_command(cmd, "plugin-repository/config.toml").await;
     }
 }
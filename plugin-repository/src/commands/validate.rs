@@ -4,7 +4,12 @@ use tracing::{info, warn, error};
 
 use crate::cli::validate::ValidateCommand;
 use crate::config::parser::Config;
-use crate::config::validator::ConfigValidator;
+use crate::config::validator::{Capability, ConfigValidator};
+use crate::core::checksums::SUMS_FILE;
+use crate::core::deployer::Deployer;
+use crate::core::gradle_dsl;
+use crate::core::remediation::{self, Remediation};
+use crate::core::inspector::CheckStatus;
 
 /// Обработчик команды validate
 pub async fn handle_validate_command(cmd: ValidateCommand, config_file: &str) -> Result<()> {
@@ -14,36 +19,135 @@ pub async fn handle_validate_command(cmd: ValidateCommand, config_file: &str) ->
     let config = Config::load_from_file(config_file)
         .with_context(|| format!("Не удалось загрузить конфигурацию из файла: {}", config_file))?;
 
-    // Пока реализуем полную валидацию. Флаги используются для вывода деталей.
-    match ConfigValidator::validate(&config) {
+    // Без --scope — полная валидация. Остальные флаги используются для вывода деталей.
+    let capabilities = if cmd.scope.is_empty() {
+        Capability::ALL.to_vec()
+    } else {
+        cmd.scope.iter().map(|s| Capability::parse(s)).collect::<Result<Vec<_>>>()?
+    };
+    match ConfigValidator::validate_for(&config, &capabilities) {
         Ok(_) => {
-            println!("{} Конфигурация валидна", "✅".green());
+            outln!("{} Конфигурация валидна", "✅".green());
             if cmd.metadata {
-                println!("  • {} Метаданные проверены", "metadata".bright_black());
+                outln!("  • {} Метаданные проверены", "metadata".bright_black());
             }
             if cmd.compatibility {
-                println!("  • {} Совместимость ок (базовые проверки)", "compatibility".bright_black());
+                validate_compatibility()?;
             }
             if cmd.full {
-                println!("  • {} Полная валидация выполнена", "full".bright_black());
+                outln!("  • {} Полная валидация выполнена", "full".bright_black());
+            }
+            let mut remediations = local_remediations(&config, cmd.fix)?;
+            if cmd.remote {
+                remediations.extend(Deployer::new(config.clone()).repair_index(cmd.fix)?);
+            }
+            print_remediations(&remediations, cmd.fix);
+            if cmd.remote {
+                validate_remote_checksums(&config)?;
             }
             Ok(())
         }
         Err(e) => {
             error!("Валидация не пройдена: {}", e);
-            println!("{} Валидация не пройдена: {}", "❌".red(), e);
+            outln!("{} Валидация не пройдена: {}", "❌".red(), e);
             Err(e)
         }
     }
 }
 
+/// Сверяет since/until-build из IntelliJ Gradle DSL с целевой платформой и plugin.xml
+fn validate_compatibility() -> Result<()> {
+    let project_root = std::env::current_dir().context("Не удалось определить текущую директорию")?;
+    let Some(dsl) = gradle_dsl::read(&project_root)? else {
+        outln!("  • {} Блок intellij {{ }} / intellijPlatform {{ }} не найден — проверка пропущена", "compatibility".bright_black());
+        return Ok(());
+    };
+    outln!("  • {} {} ({})", "compatibility".bright_black(), dsl.script, dsl.dsl);
+    if let Some(platform) = dsl.platform() {
+        outln!("    Платформа: {}", platform);
+    }
+    if !dsl.bundled_plugins.is_empty() {
+        outln!("    Встроенные плагины: {}", dsl.bundled_plugins.join(", "));
+    }
+    if !dsl.plugins.is_empty() {
+        outln!("    Плагины: {}", dsl.plugins.join(", "));
+    }
+    if let Some(sandbox) = &dsl.sandbox {
+        outln!("    Sandbox: {}", sandbox);
+    }
+
+    let plugin_xml_since = gradle_dsl::plugin_xml_since_build(&project_root);
+    let checks = gradle_dsl::check_compatibility(&dsl, plugin_xml_since.as_deref());
+    for check in &checks {
+        let icon = match check.status {
+            CheckStatus::Passed => "✅".green(),
+            CheckStatus::Warning => "⚠️".yellow(),
+            CheckStatus::Failed => "❌".red(),
+        };
+        outln!("    {} {}: {}", icon, check.name, check.details.join("; "));
+    }
+    let failed: Vec<&str> = checks.iter().filter(|c| c.status == CheckStatus::Failed).map(|c| c.name.as_str()).collect();
+    if !failed.is_empty() {
+        anyhow::bail!("Совместимость не согласована: {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// Находки в файлах проекта: `<id>` в plugin.xml и права SSH-ключа
+fn local_remediations(config: &Config, apply: bool) -> Result<Vec<Remediation>> {
+    let project_root = std::env::current_dir().context("Не удалось определить текущую директорию")?;
+    let mut remediations = Vec::new();
+    remediations.extend(remediation::plugin_xml_id(&project_root.join(gradle_dsl::PLUGIN_XML), &config.project.id, apply)?);
+    if let Some(key) = &config.repository.ssh_private_key_path {
+        remediations.extend(remediation::ssh_key_permissions(std::path::Path::new(key), apply)?);
+    }
+    Ok(remediations)
+}
+
+fn print_remediations(remediations: &[Remediation], fix: bool) {
+    for item in remediations {
+        if item.applied {
+            info!("Исправлено {}: {}", item.target, item.fix);
+            outln!("  • {} {}: {} → {}", "🔧".green(), item.target, item.problem, item.fix);
+        } else {
+            warn!("{}: {}", item.target, item.problem);
+            outln!("  • {} {}: {} (исправит validate --fix)", "⚠️".yellow(), item.target, item.problem);
+        }
+    }
+    if fix {
+        match remediations.len() {
+            0 => outln!("{} Исправлять нечего", "✅".green()),
+            count => outln!("{} Исправлено находок: {}", "🔧".green(), count),
+        }
+    }
+}
+
+/// Сверяет артефакты из updatePlugins.xml с SHA256SUMS на сервере
+fn validate_remote_checksums(config: &Config) -> Result<()> {
+    info!("🔐 Проверка {} на сервере", SUMS_FILE);
+    let checks = Deployer::new(config.clone()).verify_checksums()?;
+    let problems: Vec<String> = checks.iter().filter_map(|check| check.problem()).collect();
+    for check in checks.iter().filter(|check| check.ok()) {
+        outln!("  • {} {}", "sha256".bright_black(), check.path);
+    }
+    for problem in &problems {
+        warn!("{}", problem);
+        outln!("  • {} {}", "❌".red(), problem);
+    }
+    if !problems.is_empty() {
+        anyhow::bail!("{} не согласован с опубликованными артефактами: {} расхождений", SUMS_FILE, problems.len());
+    }
+    outln!("{} {} покрывает {} артефактов", "✅".green(), SUMS_FILE, checks.len());
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[tokio::test]
     async fn test_handle_validate_command_runs() {
-        let cmd = ValidateCommand { metadata: true, compatibility: true, full: true };
+        let cmd = ValidateCommand { metadata: true, compatibility: true, full: true, scope: vec![], remote: false, fix: false };
         let _ = handle_validate_command(cmd, "plugin-repository/config.toml").await;
     }
 }
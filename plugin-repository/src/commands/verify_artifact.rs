@@ -0,0 +1,86 @@
+use anyhow::{Context, Result};
+use colored::*;
+use tracing::info;
+
+use crate::cli::verify_artifact::VerifyArtifactCommand;
+use crate::core::inspector::{ArtifactInspector, ArtifactReport, CheckResult, CheckStatus};
+use crate::core::integrity::{self, IntegrityReport, SignatureStatus};
+
+/// Обработчик команды verify-artifact
+pub async fn handle_verify_artifact_command(cmd: VerifyArtifactCommand, _config_file: &str) -> Result<()> {
+    info!("🔍 Запуск проверки артефакта");
+
+    if !cmd.path.is_file() {
+        anyhow::bail!("Артефакт не найден: {}", cmd.path.display());
+    }
+
+    let mut report = ArtifactInspector::new(cmd.max_resource_size).inspect(&cmd.path)?;
+    if cmd.integrity {
+        let integrity = integrity::verify_artifact(&cmd.path, &cmd.integrity_manifest, cmd.verify_command.as_deref())?;
+        report.checks.push(integrity_check(&integrity));
+    }
+
+    if cmd.format == "json" {
+        let json = serde_json::to_string_pretty(&report).context("Не удалось сериализовать отчет")?;
+        println!("{}", json);
+    } else {
+        print_report(&report);
+    }
+
+    if report.has_failures() {
+        return Err(anyhow::anyhow!("Артефакт не прошел проверку"));
+    }
+    Ok(())
+}
+
+/// Подмененные записи и неверная подпись — провал, отсутствие проверенной подписи — предупреждение
+fn integrity_check(integrity: &IntegrityReport) -> CheckResult {
+    let mut details = integrity.problems.clone();
+    let mut status = if details.is_empty() { CheckStatus::Passed } else { CheckStatus::Failed };
+    if integrity.manifest_found {
+        match &integrity.signature {
+            SignatureStatus::Verified => details.push("подпись манифеста верна".to_string()),
+            SignatureStatus::Unsigned => details.push("манифест не подписан".to_string()),
+            SignatureStatus::NotChecked => details.push("подпись не проверена: не задан --verify-command".to_string()),
+            SignatureStatus::Invalid(error) => {
+                status = CheckStatus::Failed;
+                details.push(error.clone());
+            }
+        }
+        if status == CheckStatus::Passed && integrity.signature != SignatureStatus::Verified {
+            status = CheckStatus::Warning;
+        }
+        details.insert(0, format!("записей в манифесте: {}", integrity.entries));
+    }
+    CheckResult::new("integrity-manifest", status, details)
+}
+
+/// Выводит отчет о проверке в удобном формате
+fn print_report(report: &ArtifactReport) {
+    outln!("{}", "=".repeat(60).bright_black());
+    outln!("📦 ПРОВЕРКА АРТЕФАКТА: {}", report.artifact.display());
+    outln!("{}", "=".repeat(60).bright_black());
+
+    outln!("  Plugin ID: {}", report.plugin_id.as_deref().unwrap_or("-").bright_blue());
+    outln!("  Версия: {}", report.plugin_version.as_deref().unwrap_or("-").bright_green());
+    outln!("  since-build: {}", report.since_build.as_deref().unwrap_or("-"));
+    outln!("  Записей: {}, JAR: {}, классов: {}", report.entries, report.jars, report.classes);
+    outln!();
+
+    for check in &report.checks {
+        let status = match check.status {
+            CheckStatus::Passed => "✅ OK".green(),
+            CheckStatus::Warning => "⚠️ WARN".yellow(),
+            CheckStatus::Failed => "❌ FAIL".red(),
+        };
+        outln!("{} {}", status, check.name.bold());
+        for detail in check.details.iter().take(20) {
+            outln!("    • {}", detail);
+        }
+        if check.details.len() > 20 {
+            outln!("    ... и еще {}", check.details.len() - 20);
+        }
+    }
+
+    outln!("{}", "=".repeat(60).bright_black());
+}
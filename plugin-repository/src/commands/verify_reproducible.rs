@@ -0,0 +1,102 @@
+use anyhow::{Context, Result};
+use colored::*;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::cli::verify_reproducible::VerifyReproducibleCommand;
+use crate::config::parser::Config;
+use crate::core::build_profile::BuildProfile;
+use crate::core::builder::PluginBuilder;
+use crate::core::reproducible::{self, ReproducibilityReport};
+
+/// Обработчик команды verify-reproducible
+pub async fn handle_verify_reproducible_command(cmd: VerifyReproducibleCommand, config_file: &str) -> Result<()> {
+    info!("🔁 Запуск проверки воспроизводимости сборки");
+
+    let mut config = Config::load_from_file(config_file)
+        .with_context(|| format!("Не удалось загрузить конфигурацию из файла: {}", config_file))?;
+    BuildProfile::select(&mut config, &cmd.profile)?;
+    let project_root = std::env::current_dir().context("Не удалось определить текущую директорию")?;
+    let builder = PluginBuilder::new(config, project_root);
+
+    // Артефакты сохраняются во временную директорию: вторая сборка перезаписывает output_dir
+    let workdir = tempfile::tempdir().context("Не удалось создать временную директорию")?;
+
+    let first = match &cmd.reference {
+        Some(reference) => {
+            if !reference.is_file() {
+                anyhow::bail!("Эталонный артефакт не найден: {}", reference.display());
+            }
+            reference.clone()
+        }
+        None => build_and_keep(&builder, &cmd, workdir.path(), "first").await?,
+    };
+    let second = build_and_keep(&builder, &cmd, workdir.path(), "second").await?;
+
+    let report = reproducible::compare(&first, &second)?;
+
+    if cmd.format == "json" {
+        let json = serde_json::to_string_pretty(&report).context("Не удалось сериализовать отчет")?;
+        println!("{}", json);
+    } else {
+        print_report(&report);
+    }
+
+    if !report.is_reproducible() {
+        return Err(anyhow::anyhow!(
+            "Сборка не воспроизводима: отличается записей — {}",
+            report.differences.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Собирает артефакт и копирует его во временную директорию
+async fn build_and_keep(
+    builder: &PluginBuilder,
+    cmd: &VerifyReproducibleCommand,
+    workdir: &Path,
+    label: &str,
+) -> Result<PathBuf> {
+    outln!("🔨 Сборка ({})...", label);
+    let result = builder.build(cmd.version.clone(), &cmd.profile).await?;
+    let artifact = match (result.success, result.artifact) {
+        (true, Some(artifact)) => artifact,
+        _ => anyhow::bail!("Сборка ({}) завершилась с ошибками: {}", label, result.errors.join("; ")),
+    };
+
+    let kept = workdir.join(format!("{}-{}", label, artifact.file_name));
+    std::fs::copy(&artifact.file_path, &kept)
+        .with_context(|| format!("Не удалось сохранить артефакт {}", artifact.file_path.display()))?;
+    Ok(kept)
+}
+
+/// Выводит отчет о сравнении сборок
+fn print_report(report: &ReproducibilityReport) {
+    outln!("{}", "=".repeat(60).bright_black());
+    outln!("🔁 ВОСПРОИЗВОДИМОСТЬ СБОРКИ");
+    outln!("{}", "=".repeat(60).bright_black());
+    outln!("  Первая: {}", report.first.display());
+    outln!("  Вторая: {}", report.second.display());
+    outln!("  Записей: {}", report.entries);
+    outln!();
+
+    if report.is_reproducible() {
+        outln!("{}", "✅ Содержимое совпадает".green());
+    } else {
+        outln!("{}", format!("❌ Отличается записей: {}", report.differences.len()).red());
+        for diff in report.differences.iter().take(50) {
+            let kind = match (&diff.first, &diff.second) {
+                (None, _) => "только во второй",
+                (_, None) => "только в первой",
+                _ => "содержимое отличается",
+            };
+            outln!("    • {} ({})", diff.entry, kind);
+        }
+        if report.differences.len() > 50 {
+            outln!("    ... и еще {}", report.differences.len() - 50);
+        }
+    }
+
+    outln!("{}", "=".repeat(60).bright_black());
+}
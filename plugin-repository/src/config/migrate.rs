@@ -0,0 +1,194 @@
+//! Версия схемы config.toml и миграция устаревших ключей.
+//!
+//! Устаревшие ключи продолжают работать: при загрузке они переносятся на новое место в памяти
+//! с предупреждением, а `config migrate` переписывает файл, сохраняя комментарии и порядок секций.
+
+use anyhow::{Context, Result};
+use std::sync::Once;
+use toml_edit::{DocumentMut, Item, Table};
+use tracing::warn;
+
+/// Текущая версия схемы; конфигурация без `schema_version` считается схемой 1
+pub const CONFIG_SCHEMA_VERSION: u32 = 1;
+
+/// Устаревший ключ: пути через точку от корня документа
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Deprecation {
+    /// Схема, в которой ключ переехал
+    pub since_schema: u32,
+    pub key: &'static str,
+    pub replacement: &'static str,
+}
+
+/// Переезд ключа добавляется сюда вместе с повышением `CONFIG_SCHEMA_VERSION`
+pub const DEPRECATIONS: &[Deprecation] = &[];
+
+/// Итог миграции документа
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    /// Схема до миграции
+    pub from_schema: u32,
+    /// Перенесенные ключи
+    pub moved: Vec<Deprecation>,
+}
+
+impl Migration {
+    /// Документ уже соответствует текущей схеме
+    pub fn is_noop(&self) -> bool {
+        self.from_schema == CONFIG_SCHEMA_VERSION && self.moved.is_empty()
+    }
+
+    pub fn describe(&self) -> Vec<String> {
+        let mut changes: Vec<String> = self.moved.iter().map(|d| format!("{} → {}", d.key, d.replacement)).collect();
+        if self.from_schema != CONFIG_SCHEMA_VERSION {
+            changes.push(format!("schema_version = {} (было {})", CONFIG_SCHEMA_VERSION, self.from_schema));
+        }
+        changes
+    }
+}
+
+/// Версия схемы документа; версия новее утилиты — ошибка: ключи могли поменять смысл
+pub fn schema_version(doc: &DocumentMut) -> Result<u32> {
+    let version = match doc.get("schema_version") {
+        None => 1,
+        Some(item) => item
+            .as_integer()
+            .filter(|v| *v >= 1)
+            .context("schema_version должен быть положительным целым числом")? as u32,
+    };
+    if version > CONFIG_SCHEMA_VERSION {
+        anyhow::bail!(
+            "config.toml использует схему {}, а эта версия deploy-pugin поддерживает схему до {}: обновите утилиту",
+            version,
+            CONFIG_SCHEMA_VERSION
+        );
+    }
+    Ok(version)
+}
+
+/// Переносит устаревшие ключи и проставляет текущую `schema_version`
+pub fn migrate(doc: &mut DocumentMut) -> Result<Migration> {
+    migrate_with(doc, DEPRECATIONS)
+}
+
+fn migrate_with(doc: &mut DocumentMut, deprecations: &[Deprecation]) -> Result<Migration> {
+    let from_schema = schema_version(doc)?;
+    let mut moved = Vec::new();
+    for deprecation in deprecations {
+        let old: Vec<&str> = deprecation.key.split('.').collect();
+        let new: Vec<&str> = deprecation.replacement.split('.').collect();
+        if get(doc.as_table(), &old).is_none() {
+            continue;
+        }
+        if get(doc.as_table(), &new).is_some() {
+            anyhow::bail!(
+                "Заданы и устаревший ключ {}, и его замена {}: оставьте только {}",
+                deprecation.key,
+                deprecation.replacement,
+                deprecation.replacement
+            );
+        }
+        let item = remove(doc.as_table_mut(), &old).expect("key checked above");
+        insert(doc.as_table_mut(), &new, item)?;
+        moved.push(*deprecation);
+    }
+    if from_schema != CONFIG_SCHEMA_VERSION {
+        doc.insert("schema_version", toml_edit::value(CONFIG_SCHEMA_VERSION as i64));
+    }
+    Ok(Migration { from_schema, moved })
+}
+
+/// Миграция при загрузке конфигурации: предупреждение выводится один раз за запуск
+pub fn migrate_on_load(doc: &mut DocumentMut) -> Result<()> {
+    static WARNED: Once = Once::new();
+    let migration = migrate(doc)?;
+    // Секцию [telemetry] читают до инициализации логирования: предупреждение — при первой загрузке после нее
+    if !migration.moved.is_empty() && tracing::dispatcher::has_been_set() {
+        WARNED.call_once(|| {
+            warn!(
+                "⚠️ config.toml (схема {}) использует устаревшие ключи: {}. Обновите файл командой `deploy-pugin config migrate`",
+                migration.from_schema,
+                migration.moved.iter().map(|d| format!("{} → {}", d.key, d.replacement)).collect::<Vec<_>>().join(", ")
+            );
+        });
+    }
+    Ok(())
+}
+
+fn get<'a>(table: &'a Table, path: &[&str]) -> Option<&'a Item> {
+    let (last, parents) = path.split_last()?;
+    let mut current = table;
+    for key in parents {
+        current = current.get(key)?.as_table()?;
+    }
+    current.get(last)
+}
+
+fn remove(table: &mut Table, path: &[&str]) -> Option<Item> {
+    let (last, parents) = path.split_last()?;
+    let mut current = table;
+    for key in parents {
+        current = current.get_mut(key)?.as_table_mut()?;
+    }
+    current.remove(last)
+}
+
+/// Вставка с созданием промежуточных таблиц (неявных: без собственного заголовка)
+fn insert(table: &mut Table, path: &[&str], item: Item) -> Result<()> {
+    let (last, parents) = path.split_last().context("Пустой путь ключа")?;
+    let mut current = table;
+    for key in parents {
+        let entry = current.entry(key).or_insert_with(|| {
+            let mut implicit = Table::new();
+            implicit.set_implicit(true);
+            Item::Table(implicit)
+        });
+        current = entry.as_table_mut().with_context(|| format!("Ключ {} не является таблицей", key))?;
+    }
+    current.insert(last, item);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Перечень устаревших ключей пока пуст: механизм проверяется на вымышленном переезде
+    const MOVED: &[Deprecation] =
+        &[Deprecation { since_schema: CONFIG_SCHEMA_VERSION, key: "old_clustering", replacement: "changelog.clustering" }];
+
+    #[test]
+    fn test_migrate_moves_deprecated_keys() {
+        let source = "[project]\nname = \"ride\"\n\n[changelog]\ngroup_by_module = true\n\n\
+                      # Группировка близких коммитов\n[old_clustering]\nenabled = false\nmin_commits = 50\n";
+        let mut doc: DocumentMut = source.parse().unwrap();
+        let migration = migrate_with(&mut doc, MOVED).unwrap();
+        assert_eq!(migration.from_schema, 1);
+        assert_eq!(migration.describe(), ["old_clustering → changelog.clustering"]);
+        let migrated = doc.to_string();
+        assert!(migrated.contains("# Группировка близких коммитов\n[changelog.clustering]\nenabled = false\nmin_commits = 50\n"));
+        assert!(!migrated.contains("[old_clustering]"));
+
+        // Повторная миграция ничего не меняет
+        let mut again: DocumentMut = migrated.parse().unwrap();
+        assert!(migrate_with(&mut again, MOVED).unwrap().is_noop());
+        assert_eq!(again.to_string(), migrated);
+
+        // Без секции [changelog] она создается неявно
+        let mut doc: DocumentMut = "[old_clustering]\nenabled = false\n".parse().unwrap();
+        migrate_with(&mut doc, MOVED).unwrap();
+        assert!(doc.to_string().contains("[changelog.clustering]\nenabled = false\n"));
+        assert!(!doc.to_string().contains("[changelog]\n"));
+
+        let mut both: DocumentMut = "[old_clustering]\n[changelog.clustering]\n".parse().unwrap();
+        assert!(migrate_with(&mut both, MOVED).unwrap_err().to_string().contains("оставьте только changelog.clustering"));
+        let mut newer: DocumentMut = "schema_version = 99\n".parse().unwrap();
+        assert!(migrate(&mut newer).unwrap_err().to_string().contains("обновите утилиту"));
+    }
+
+    #[test]
+    fn test_current_config_needs_no_migration() {
+        let mut doc: DocumentMut = include_str!("../../config.toml.example").parse().unwrap();
+        assert!(migrate(&mut doc).unwrap().is_noop());
+    }
+}
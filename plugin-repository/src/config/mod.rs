@@ -1,3 +1,4 @@
 pub mod parser;
 pub mod env_loader;
-pub mod validator;
\ No newline at end of file
+pub mod validator;
+pub mod migrate;
\ No newline at end of file
@@ -1,23 +1,56 @@
 use anyhow::{Context, Result};
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use tracing::info;
 
+use crate::config::migrate;
+use crate::config::validator::{Capability, ConfigValidator};
+
 /// Структура для хранения конфигурации
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub project: ProjectConfig,
     pub build: BuildConfig,
+    /// Секции ниже нужны не каждой команде: отсутствующая секция проверяется
+    /// только командами, которые ее используют (см. `Capability`)
+    #[serde(default)]
     pub repository: RepositoryConfig,
+    #[serde(default)]
     pub llm: LlmConfig,
+    #[serde(default)]
     pub yandexgpt: YandexGptConfig,
     #[serde(default)]
     pub openai: Option<OpenAiConfig>,
     #[serde(default)]
     pub anthropic: Option<AnthropicConfig>,
+    #[serde(default)]
+    pub ollama: Option<OllamaConfig>,
+    #[serde(default)]
+    pub mock: MockConfig,
+    #[serde(default)]
     pub llm_agents: LlmAgentsConfig,
+    #[serde(default)]
     pub git: GitConfig,
+    #[serde(default)]
+    pub change_notes: ChangeNotesConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub changelog: ChangelogConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
+    #[serde(default)]
+    pub chatops: ChatOpsConfig,
+    #[serde(default)]
+    pub proofreading: ProofreadingConfig,
+    #[serde(default)]
+    pub pull_requests: PullRequestsConfig,
+    /// Пользовательские пайплайны для команды `run` (`[pipelines.nightly]`)
+    #[serde(default)]
+    pub pipelines: BTreeMap<String, PipelineConfig>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -36,6 +69,163 @@ pub struct BuildConfig {
     pub output_dir: String,
     #[serde(default)]
     pub build_args: Vec<String>,
+    /// Цепочка пост-обработки артефакта (`[[build.post_process]]`), выполняется по порядку
+    #[serde(default)]
+    pub post_process: Vec<PostProcessorConfig>,
+    /// Всегда добавлять build-info.json с окружением сборки, даже без шага `build-info` в post_process
+    #[serde(default = "default_true")]
+    pub embed_build_info: bool,
+    /// Проверка Gradle wrapper и загрузка Gradle без wrapper (`[build.wrapper]`)
+    #[serde(default)]
+    pub wrapper: WrapperConfig,
+    /// Проверка свободного места и inode перед сборкой (`[build.preflight]`)
+    #[serde(default)]
+    pub preflight: DiskPreflightConfig,
+    /// Сборка в workspace удаленного MCP-сервера (`[build.mcp]`)
+    #[serde(default)]
+    pub mcp: McpBuildConfig,
+    /// Профили сборки (`[build.profiles.<имя>]`), выбираются через `--profile`
+    #[serde(default)]
+    pub profiles: HashMap<String, BuildProfileConfig>,
+}
+
+/// Профиль сборки: незаданные поля берутся из `[build]`
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct BuildProfileConfig {
+    /// Gradle задача вместо `[build] gradle_task`
+    pub task: Option<String>,
+    /// Аргументы сборки вместо `[build] build_args`
+    pub args: Option<Vec<String>>,
+    /// Каталог артефактов профиля: сборка и деплой ищут ZIP в нем
+    pub output_dir: Option<String>,
+    /// Суффикс версии: `-nightly` превращает 1.3.0 в 1.3.0-nightly
+    pub version_suffix: Option<String>,
+}
+
+/// Сборка на MCP-сервере: checkout и Gradle выполняются в его workspace через `POST /exec`,
+/// локально остаются пост-обработка и проверка скачанного артефакта
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct McpBuildConfig {
+    pub enabled: bool,
+    /// Адрес MCP-сервера
+    pub url: String,
+    /// Ключ `X-API-Key`, которому разрешен `POST /exec`
+    pub api_key: Option<String>,
+    /// Директория проекта относительно корня workspace
+    pub workspace_dir: String,
+    /// Откуда клонировать и получать коммиты; по умолчанию `git remote get-url origin`
+    pub git_remote: Option<String>,
+    /// Лимит одной команды на сервере
+    pub timeout_secs: u64,
+}
+
+impl Default for McpBuildConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            api_key: None,
+            workspace_dir: ".".to_string(),
+            git_remote: None,
+            timeout_secs: 1800,
+        }
+    }
+}
+
+/// Пороги свободного места: сборка падает сразу, а не через несколько минут с "No space left on device"
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct DiskPreflightConfig {
+    pub enabled: bool,
+    /// Минимум свободного места для проекта и output_dir, МБ
+    pub min_free_mb: u64,
+    /// Минимум свободного места во временной директории, МБ
+    pub min_temp_free_mb: u64,
+    /// Минимум свободных inode на каждой файловой системе
+    pub min_free_inodes: u64,
+}
+
+impl Default for DiskPreflightConfig {
+    fn default() -> Self {
+        Self { enabled: true, min_free_mb: 2048, min_temp_free_mb: 512, min_free_inodes: 10_000 }
+    }
+}
+
+/// Gradle wrapper: сверка gradle-wrapper.jar и зафиксированная версия Gradle для проектов без wrapper
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct WrapperConfig {
+    /// Сверять gradle-wrapper.jar с официальной суммой перед сборкой
+    #[serde(default = "default_true")]
+    pub validate: bool,
+    /// Дополнительно разрешенные sha256 gradle-wrapper.jar (собственные сборки wrapper)
+    #[serde(default)]
+    pub allowed_checksums: Vec<String>,
+    /// Версия Gradle, которая скачивается, если в проекте нет gradlew (вместо системного gradle)
+    #[serde(default)]
+    pub gradle_version: Option<String>,
+    /// Источник дистрибутивов и сумм (зеркало services.gradle.org)
+    #[serde(default = "default_gradle_distributions_url")]
+    pub distributions_url: String,
+}
+
+fn default_gradle_distributions_url() -> String {
+    "https://services.gradle.org/distributions".to_string()
+}
+
+impl Default for WrapperConfig {
+    fn default() -> Self {
+        Self {
+            validate: true,
+            allowed_checksums: Vec::new(),
+            gradle_version: None,
+            distributions_url: default_gradle_distributions_url(),
+        }
+    }
+}
+
+/// Пост-обработчик артефакта после сборки
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum PostProcessorConfig {
+    /// Обнулить время модификации записей ZIP
+    StripTimestamps,
+    /// Отсортировать записи и нормализовать права доступа
+    DeterministicOrder,
+    /// Добавить файлы проекта (LICENSE, THIRD-PARTY) в каталог архива
+    InjectFiles {
+        files: Vec<String>,
+        #[serde(default)]
+        target_dir: String,
+    },
+    /// Добавить build-info.json
+    BuildInfo {
+        #[serde(default = "default_build_info_path")]
+        path: String,
+    },
+    /// Внешняя команда над распакованным артефактом
+    Command {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+    /// Добавить манифест целостности (SHA256 всех записей) и, с `sign_command`, его подпись.
+    /// Всегда выполняется последним шагом цепочки
+    Integrity {
+        #[serde(default = "default_integrity_path")]
+        path: String,
+        #[serde(default)]
+        sign_command: Option<String>,
+    },
+}
+
+pub(crate) fn default_build_info_path() -> String {
+    "build-info.json".to_string()
+}
+
+fn default_integrity_path() -> String {
+    crate::core::integrity::DEFAULT_MANIFEST_PATH.to_string()
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -47,27 +237,311 @@ pub struct RepositoryConfig {
     pub ssh_user: String,
     #[serde(rename = "ssh_private_key_path")]
     pub ssh_private_key_path: Option<String>,
+    /// Каталог артефактов; поддерживает шаблон с {id}, {name}, {version}, {channel}
     #[serde(rename = "deploy_path")]
     pub deploy_path: String,
     #[serde(rename = "xml_path")]
     pub xml_path: String,
+    /// Канал публикации по умолчанию для {channel}
+    #[serde(default = "default_channel")]
+    pub channel: String,
+    /// Иконка плагина, публикуемая вместе с артефактом
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Дополнительные файлы (скриншоты, документация), публикуемые вместе с артефактом
+    #[serde(default)]
+    pub resources: Vec<String>,
+    /// Команды на сервере после загрузки (`[[repository.post_deploy]]`)
+    #[serde(default)]
+    pub post_deploy: Vec<RemoteCommandConfig>,
+    /// Индекс SHA256SUMS в корне репозитория (`[repository.checksums]`)
+    #[serde(default)]
+    pub checksums: ChecksumsConfig,
+    /// Локальный кэш выпущенных артефактов (`[repository.cache]`)
+    #[serde(default)]
+    pub cache: ArtifactCacheConfig,
+    /// compatibility.json с диапазонами сборок IDE всех опубликованных версий
+    #[serde(default = "default_true")]
+    pub compatibility_matrix: bool,
+    /// Проверка updatePlugins.xml на сервере после замены (`[repository.validate_index]`)
+    #[serde(default)]
+    pub validate_index: IndexValidationConfig,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Проверка индекса на сервере сразу после атомарной замены: непрошедший проверку файл
+/// заменяется резервной копией `.bak`
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+pub struct IndexValidationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Команда на сервере (`{xml_path}` подставляется в одинарных кавычках), ненулевой код — ошибка проверки.
+    /// Без команды XML читается обратно по SFTP и проверяется встроенным парсером
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
+/// Настройки индекса контрольных сумм репозитория
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ChecksumsConfig {
+    /// Обновлять SHA256SUMS вместе с updatePlugins.xml
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Локальная команда подписи: {input} — SHA256SUMS, {output} — SHA256SUMS.sig
+    #[serde(default)]
+    pub sign_command: Option<String>,
+}
+
+impl Default for ChecksumsConfig {
+    fn default() -> Self {
+        Self { enabled: true, sign_command: None }
+    }
+}
+
+/// Кэш ранее выпущенных артефактов, скачанных из репозитория
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ArtifactCacheConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Каталог кэша относительно текущей директории
+    #[serde(default = "default_artifact_cache_dir")]
+    pub dir: String,
+    /// Предел размера; при превышении удаляются давно не использованные версии
+    #[serde(default = "default_artifact_cache_max_size_mb")]
+    pub max_size_mb: u64,
+}
+
+impl Default for ArtifactCacheConfig {
+    fn default() -> Self {
+        Self { enabled: true, dir: default_artifact_cache_dir(), max_size_mb: default_artifact_cache_max_size_mb() }
+    }
+}
+
+fn default_artifact_cache_dir() -> String {
+    ".deploy-plugin/cache/artifacts".to_string()
+}
+
+fn default_artifact_cache_max_size_mb() -> u64 {
+    1024
+}
+
+/// Команда, выполняемая на сервере по SSH после загрузки артефактов
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct RemoteCommandConfig {
+    /// Поддерживает {deploy_dir}, {xml_path}, {version}, {artifact}
+    pub command: String,
+    /// Ненулевой код выхода не прерывает деплой
+    #[serde(default)]
+    pub allow_failure: bool,
+}
+
+fn default_channel() -> String {
+    "stable".to_string()
+}
+
+impl Default for RepositoryConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            ssh_host: String::new(),
+            ssh_user: String::new(),
+            ssh_private_key_path: None,
+            deploy_path: String::new(),
+            xml_path: String::new(),
+            channel: default_channel(),
+            icon: None,
+            resources: Vec::new(),
+            post_deploy: Vec::new(),
+            checksums: ChecksumsConfig::default(),
+            cache: ArtifactCacheConfig::default(),
+            compatibility_matrix: true,
+            validate_index: IndexValidationConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct LlmConfig {
     pub provider: String,
     pub temperature: f32,
     #[serde(rename = "max_tokens")]
     pub max_tokens: u32,
+    /// Таймаут запроса к API YandexGPT и OpenAI, паузы в потоковом ответе и опроса
+    /// асинхронной операции (Ollama — `[ollama] timeout_secs`)
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+    /// Общий лимит запросов к API в минуту для всех агентов; 0 — без ограничения
+    #[serde(default)]
+    pub requests_per_minute: u32,
+    /// Сколько запросов к API может выполняться одновременно; 0 — без ограничения
+    #[serde(default)]
+    pub max_concurrent_requests: u32,
+    /// После стольких ошибок подряд запросы к провайдеру отклоняются сразу; 0 — отключено
+    #[serde(default = "default_circuit_breaker_threshold")]
+    pub circuit_breaker_threshold: u32,
+    /// Через сколько секунд после срабатывания пропускается пробный запрос
+    #[serde(default = "default_circuit_breaker_cooldown_secs")]
+    pub circuit_breaker_cooldown_secs: u64,
+    /// Changelog и release notes запрашиваются как JSON по схеме вместо свободного текста
+    #[serde(default)]
+    pub structured_output: bool,
+    /// Сколько раз просить модель исправить JSON, не прошедший проверку
+    #[serde(default = "default_json_fix_attempts")]
+    pub json_fix_attempts: u32,
+    /// Повторы запросов к LLM для всех агентов (`[llm.retry]`)
+    #[serde(default)]
+    pub retry: LlmRetryConfig,
+    /// Язык changelog и release notes: шаблоны промптов, заголовки секций, служебный текст
+    #[serde(default)]
+    pub output_language: OutputLanguage,
+    /// Цены за миллион токенов по модели или провайдеру (`[llm.pricing.<модель>]`)
+    #[serde(default)]
+    pub pricing: HashMap<String, LlmPricing>,
+    /// Валюта цен для отчета о стоимости
+    #[serde(default = "default_llm_currency")]
+    pub currency: String,
+    /// Бюджет одного запуска: запросы сверх него отклоняются, команда завершается ошибкой
+    /// (флаг `--max-cost` приоритетнее)
+    #[serde(default)]
+    pub max_cost: Option<f64>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Цена модели за миллион токенов (`[llm.pricing.<модель или провайдер>]`)
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct LlmPricing {
+    pub input_per_1m: f64,
+    pub output_per_1m: f64,
+}
+
+/// Язык сгенерированного контента (`[llm] output_language`)
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputLanguage {
+    #[default]
+    Ru,
+    En,
+}
+
+/// Повторы запросов к LLM (`[llm.retry]`): экспоненциальная задержка с потолком и джиттером
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct LlmRetryConfig {
+    /// Повторов после первой неудачной попытки
+    #[serde(default = "default_llm_max_retries")]
+    pub max_retries: u32,
+    /// Задержка перед первым повтором; дальше удваивается
+    #[serde(default = "default_llm_retry_base_delay_ms")]
+    pub base_delay_ms: u64,
+    /// Потолок задержки
+    #[serde(default = "default_llm_retry_max_delay_ms")]
+    pub max_delay_ms: u64,
+    /// Случайная добавка к задержке, доля от нее (0..1); 0 — без джиттера
+    #[serde(default)]
+    pub jitter: f64,
+}
+
+impl Default for LlmRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: default_llm_max_retries(),
+            base_delay_ms: default_llm_retry_base_delay_ms(),
+            max_delay_ms: default_llm_retry_max_delay_ms(),
+            jitter: 0.0,
+        }
+    }
+}
+
+fn default_llm_max_retries() -> u32 {
+    3
+}
+
+fn default_llm_retry_base_delay_ms() -> u64 {
+    1000
+}
+
+fn default_llm_retry_max_delay_ms() -> u64 {
+    30_000
+}
+
+fn default_llm_currency() -> String {
+    "USD".to_string()
+}
+
+fn default_circuit_breaker_threshold() -> u32 {
+    5
+}
+
+fn default_circuit_breaker_cooldown_secs() -> u64 {
+    60
+}
+
+fn default_request_timeout_secs() -> u64 {
+    60
+}
+
+fn default_json_fix_attempts() -> u32 {
+    2
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct YandexGptConfig {
     #[serde(rename = "api_key")]
     pub api_key: String,
     #[serde(rename = "folder_id")]
     pub folder_id: String,
     pub model: String,
+    /// Корень Foundation Models API; для корпоративного шлюза — его адрес
+    #[serde(default = "default_yandexgpt_base_url")]
+    pub base_url: String,
+    /// Endpoint операций для асинхронного режима
+    #[serde(default = "default_yandexgpt_operation_url")]
+    pub operation_url: String,
+    /// Промпты длиннее этого числа символов отправляются в асинхронный `completionAsync`;
+    /// 0 — всегда синхронный запрос
+    #[serde(default = "default_async_threshold_chars")]
+    pub async_threshold_chars: usize,
+    /// Интервал опроса асинхронной операции
+    #[serde(default = "default_async_poll_interval_secs")]
+    pub async_poll_interval_secs: u64,
+    /// Сколько ждать завершения асинхронной операции
+    #[serde(default = "default_async_timeout_secs")]
+    pub async_timeout_secs: u64,
+    /// Дополнительные аккаунты: после 429 от текущего ключа запросы идут со следующего
+    #[serde(default)]
+    pub accounts: Vec<YandexAccountConfig>,
+    /// На сколько откладывается ключ после 429, если API не прислал `Retry-After`
+    #[serde(default = "default_key_cooldown_secs")]
+    pub key_cooldown_secs: u64,
+}
+
+/// Аккаунт YandexGPT для ротации ключей (`[[yandexgpt.accounts]]`)
+#[derive(Debug, Deserialize, Clone)]
+pub struct YandexAccountConfig {
+    pub api_key: String,
+    pub folder_id: String,
+}
+
+fn default_yandexgpt_base_url() -> String {
+    crate::core::llm::yandexgpt::DEFAULT_BASE_URL.to_string()
+}
+
+fn default_yandexgpt_operation_url() -> String {
+    crate::core::llm::yandexgpt::DEFAULT_OPERATION_URL.to_string()
+}
+
+fn default_async_threshold_chars() -> usize {
+    20_000
+}
+
+fn default_async_poll_interval_secs() -> u64 {
+    2
+}
+
+fn default_async_timeout_secs() -> u64 {
+    600
+}
+
+fn default_key_cooldown_secs() -> u64 {
+    60
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -75,6 +549,16 @@ pub struct OpenAiConfig {
     #[serde(rename = "api_key")]
     pub api_key: String,
     pub model: String,
+    /// Корень OpenAI-совместимого API (корпоративный прокси, Azure-шлюз и т.п.)
+    #[serde(default = "default_openai_base_url")]
+    pub base_url: String,
+    /// Модель эмбеддингов (`text-embedding-3-small`); без нее провайдер эмбеддинги не отдает
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+}
+
+fn default_openai_base_url() -> String {
+    crate::core::llm::openai::DEFAULT_BASE_URL.to_string()
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -84,7 +568,40 @@ pub struct AnthropicConfig {
     pub model: String,
 }
 
+/// Локальная модель через Ollama (`[llm] provider = "ollama"`): без облачных ключей
 #[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct OllamaConfig {
+    /// Адрес сервера Ollama
+    pub host: String,
+    pub model: String,
+    /// Локальные модели отвечают заметно дольше облачных
+    pub timeout_secs: u64,
+    /// Модель эмбеддингов (`nomic-embed-text`); без нее провайдер эмбеддинги не отдает
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+}
+
+impl Default for OllamaConfig {
+    fn default() -> Self {
+        Self {
+            host: "http://localhost:11434".to_string(),
+            model: "llama3.1".to_string(),
+            timeout_secs: 300,
+            embedding_model: None,
+        }
+    }
+}
+
+/// Провайдер без сети (`[llm] provider = "mock"`) для тестов и офлайн dry-run
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct MockConfig {
+    /// Ответы по имени агента (`changelog`, `release`, `version`, `qa`, ...) вместо встроенных
+    #[serde(default)]
+    pub responses: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct LlmAgentsConfig {
     #[serde(rename = "changelog_agent")]
     pub changelog_agent: AgentConfig,
@@ -92,12 +609,25 @@ pub struct LlmAgentsConfig {
     pub version_agent: AgentConfig,
     #[serde(rename = "release_agent")]
     pub release_agent: AgentConfig,
+    /// Агент `ai review`; без секции — модель и температура провайдера
+    #[serde(rename = "review_agent", default)]
+    pub review_agent: AgentConfig,
+    /// Агент описаний коммитов по diff (`[changelog.diff_summary]`)
+    #[serde(rename = "diff_agent", default)]
+    pub diff_agent: AgentConfig,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Модель, температура и системный промпт одного агента; незаданные значения берутся у провайдера
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct AgentConfig {
+    /// Пусто — модель из секции провайдера
+    #[serde(default)]
     pub model: String,
-    pub temperature: f32,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// Системный промпт агента (стиль changelog, тон release notes); по умолчанию общий
+    #[serde(default)]
+    pub system_prompt: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -106,6 +636,485 @@ pub struct GitConfig {
     pub main_branch: String,
     #[serde(rename = "tag_prefix")]
     pub tag_prefix: String,
+    /// Префикс веток релизов: на `release/2.1` выпускаются только patch-версии 2.1.x
+    #[serde(default = "default_release_branch_prefix")]
+    pub release_branch_prefix: String,
+    /// Файлы, конфликты в которых при backport разрешаются в пользу ветки релиза (glob)
+    #[serde(default = "default_backport_keep_paths")]
+    pub backport_keep_paths: Vec<String>,
+    /// Кэш анализа диапазонов коммитов в `.deploy-plugin/cache/ranges`
+    #[serde(default = "default_true")]
+    pub range_cache: bool,
+    /// Маркеры в коммитах, задающие версию релиза явно (`[[git.version_rules]]`)
+    #[serde(default)]
+    pub version_rules: Vec<VersionRuleConfig>,
+}
+
+/// Правило явной версии: трейлер (`Release: 3.0.0-rc1`) или регулярное выражение по сообщению
+/// коммита. Срабатывает самый новый коммит с маркером, правила проверяются по порядку
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct VersionRuleConfig {
+    /// Ключ трейлера без учета регистра; версия — значение трейлера
+    pub trailer: Option<String>,
+    /// Регулярное выражение (многострочное); версия — группа `(?P<version>...)`
+    pub pattern: Option<String>,
+    /// Фиксированная версия вместо значения трейлера или группы
+    pub version: Option<String>,
+}
+
+fn default_release_branch_prefix() -> String {
+    "release/".to_string()
+}
+
+fn default_backport_keep_paths() -> Vec<String> {
+    vec!["CHANGELOG.md".to_string()]
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        Self {
+            main_branch: "main".to_string(),
+            tag_prefix: "v".to_string(),
+            release_branch_prefix: default_release_branch_prefix(),
+            backport_keep_paths: default_backport_keep_paths(),
+            range_cache: true,
+            version_rules: Vec::new(),
+        }
+    }
+}
+
+/// Настройки change-notes (HTML для Marketplace/updatePlugins.xml)
+#[derive(Debug, Deserialize, Clone)]
+pub struct ChangeNotesConfig {
+    /// Максимальная длина HTML в символах
+    #[serde(default = "default_change_notes_max_length")]
+    pub max_length: usize,
+    /// Ссылка на полный changelog, добавляется при усечении
+    #[serde(default)]
+    pub changelog_url: Option<String>,
+    /// Разрешенные HTML теги
+    #[serde(default = "default_change_notes_allowed_tags")]
+    pub allowed_tags: Vec<String>,
+    /// Дополнительные локали change-notes (`en`, `de`, `pt_BR`): переводятся LLM
+    /// и записываются в артефакт как `<bundle>_<locale>.properties`
+    #[serde(default)]
+    pub locales: Vec<String>,
+    /// Путь resource bundle внутри ZIP без суффикса локали
+    #[serde(default = "default_change_notes_bundle")]
+    pub bundle: String,
+}
+
+fn default_change_notes_max_length() -> usize {
+    4000
+}
+
+fn default_change_notes_bundle() -> String {
+    "messages/ChangeNotesBundle".to_string()
+}
+
+fn default_change_notes_allowed_tags() -> Vec<String> {
+    ["h3", "p", "br", "ul", "ol", "li", "b", "i", "em", "strong", "code", "a"]
+        .iter()
+        .map(|t| t.to_string())
+        .collect()
+}
+
+impl Default for ChangeNotesConfig {
+    fn default() -> Self {
+        Self {
+            max_length: default_change_notes_max_length(),
+            changelog_url: None,
+            allowed_tags: default_change_notes_allowed_tags(),
+            locales: Vec::new(),
+            bundle: default_change_notes_bundle(),
+        }
+    }
+}
+
+/// Сетевые настройки исходящих HTTP(S) запросов
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct NetworkConfig {
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+}
+
+/// Прокси (`[network.proxy]`). Явные настройки имеют приоритет над
+/// HTTPS_PROXY/HTTP_PROXY/ALL_PROXY; список `no_proxy` дополняет NO_PROXY
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+pub struct ProxyConfig {
+    /// URL прокси для HTTP и HTTPS, например `http://proxy.corp:3128`
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Хосты и домены без прокси (`localhost`, `.corp.example.com`, `10.0.0.0/8`)
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// HTTP API для запуска публикации ботами и ChatOps (`serve`, `[server]`)
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ServerConfig {
+    /// Адрес прослушивания; по умолчанию только localhost
+    #[serde(default = "default_server_bind")]
+    pub bind: String,
+    /// Bearer-токен клиентов API, обычно `${DEPLOY_PLUGIN_API_TOKEN}`
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Сколько последних запусков хранить для запросов статуса и отчетов
+    #[serde(default = "default_server_max_runs")]
+    pub max_runs: usize,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind: default_server_bind(),
+            token: None,
+            max_runs: default_server_max_runs(),
+        }
+    }
+}
+
+fn default_server_bind() -> String {
+    "127.0.0.1:8787".to_string()
+}
+
+fn default_server_max_runs() -> usize {
+    50
+}
+
+/// Бот для запуска релизов из чата (`[chatops]`, работает внутри `serve`)
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ChatOpsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Мессенджер; поддерживается `telegram`
+    #[serde(default = "default_chatops_provider")]
+    pub provider: String,
+    /// Токен бота, обычно `${DEPLOY_PLUGIN_BOT_TOKEN}`
+    #[serde(default)]
+    pub bot_token: Option<String>,
+    /// Чат, из которого принимаются команды; сообщения из других чатов игнорируются
+    #[serde(default)]
+    pub chat_id: i64,
+    /// Пользователи, которым разрешено запускать и подтверждать релизы; пусто — все участники чата
+    #[serde(default)]
+    pub allowed_users: Vec<i64>,
+    /// Адрес Bot API (для прокси-шлюзов и тестов)
+    #[serde(default = "default_telegram_api_url")]
+    pub api_url: String,
+}
+
+impl Default for ChatOpsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: default_chatops_provider(),
+            bot_token: None,
+            chat_id: 0,
+            allowed_users: Vec::new(),
+            api_url: default_telegram_api_url(),
+        }
+    }
+}
+
+fn default_chatops_provider() -> String {
+    "telegram".to_string()
+}
+
+fn default_telegram_api_url() -> String {
+    "https://api.telegram.org".to_string()
+}
+
+/// Экспорт трассировки в OTLP (`[telemetry]`, требуется сборка с фичей otel)
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+pub struct TelemetryConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Адрес OTLP/HTTP коллектора, например `http://tempo.corp:4318`
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub service_name: Option<String>,
+    /// Анонимные отчеты о результатах команд (`[telemetry.usage]`)
+    #[serde(default)]
+    pub usage: UsageReportConfig,
+}
+
+/// Отчеты о результатах команд: команда, длительность, категория сбоя. Выключены по умолчанию
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct UsageReportConfig {
+    pub enabled: bool,
+    /// Адрес, принимающий POST с JSON-отчетом
+    pub endpoint: Option<String>,
+    /// Метка агента сборки (`${CI_RUNNER_DESCRIPTION}`); без нее отчеты не различают агентов
+    pub agent: Option<String>,
+}
+
+/// Группировка близких по смыслу коммитов перед генерацией changelog (`[changelog.clustering]`)
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ClusteringConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Группировка включается, начиная с этого числа коммитов в релизе
+    #[serde(default = "default_clustering_min_commits")]
+    pub min_commits: usize,
+    /// Источник эмбеддингов: `local` (без сети) или провайдер LLM (`yandexgpt`, `openai`, `ollama`)
+    #[serde(default = "default_clustering_provider")]
+    pub provider: String,
+    /// Минимальное косинусное сходство коммита с группой
+    #[serde(default = "default_clustering_similarity")]
+    pub similarity: f32,
+    /// Сходство, начиная с которого коммиты считаются повторами и сворачиваются в одну
+    /// строку changelog в релизах любого размера
+    #[serde(default = "default_clustering_duplicate_similarity")]
+    pub duplicate_similarity: f32,
+}
+
+/// Оформление changelog (`[changelog]`)
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ChangelogConfig {
+    /// Вложенные подсекции по scope коммитов (`feat(editor): ...`) внутри секций типов изменений
+    #[serde(default)]
+    pub group_by_module: bool,
+    /// Заголовок подсекции для коммитов без scope
+    #[serde(default = "default_changelog_other_title")]
+    pub other_title: String,
+    /// Переименование scope в модули: `{ "editor-ui" = "editor" }`
+    #[serde(default)]
+    pub modules: HashMap<String, String>,
+    /// Группировка близких коммитов (`[changelog.clustering]`)
+    #[serde(default)]
+    pub clustering: ClusteringConfig,
+    /// Коммиты, которые не попадают в анализ и changelog (`[changelog.exclude]`)
+    #[serde(default)]
+    pub exclude: ChangelogExcludeConfig,
+    /// Предел длины changelog в символах: более длинный ответ модель сокращает в том же диалоге
+    #[serde(default)]
+    pub max_length: Option<usize>,
+    /// Описания коммитов по их diff вместо одних тем (`[changelog.diff_summary]`)
+    #[serde(default)]
+    pub diff_summary: DiffSummaryConfig,
+}
+
+/// Агент `diff`: перед генерацией changelog кратко описывает по diff, что на самом деле
+/// меняет каждый коммит, и добавляет описание к теме коммита
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiffSummaryConfig {
+    pub enabled: bool,
+    /// Сколько коммитов релиза описывать (от новых к старым); остальные остаются с темами
+    pub max_commits: usize,
+    /// Предел diff одного коммита в символах: `--stat` и начало патча
+    pub max_diff_chars: usize,
+}
+
+impl Default for DiffSummaryConfig {
+    fn default() -> Self {
+        Self { enabled: false, max_commits: 30, max_diff_chars: 6000 }
+    }
+}
+
+/// Правила исключения коммитов: достаточно совпадения с любым правилом
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChangelogExcludeConfig {
+    /// Регулярные выражения для автора `Имя <email>`, без учета регистра: `dependabot`, `renovate\[bot\]`
+    pub authors: Vec<String>,
+    /// Регулярные выражения для заголовка коммита: `^chore\(deps\)`, `^Merge branch`
+    pub messages: Vec<String>,
+    /// Glob-шаблоны путей: коммит исключается, если все его файлы совпадают (`**/*.lock`, `gradle/libs.versions.toml`)
+    pub paths: Vec<String>,
+}
+
+fn default_changelog_other_title() -> String {
+    "Общее".to_string()
+}
+
+impl Default for ChangelogConfig {
+    fn default() -> Self {
+        Self {
+            group_by_module: false,
+            other_title: default_changelog_other_title(),
+            modules: HashMap::new(),
+            clustering: ClusteringConfig::default(),
+            exclude: ChangelogExcludeConfig::default(),
+            max_length: None,
+            diff_summary: DiffSummaryConfig::default(),
+        }
+    }
+}
+
+/// Заголовки, метки и описания PR/MR вместо темы коммита (`[pull_requests]`)
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct PullRequestsConfig {
+    pub enabled: bool,
+    /// `github` или `gitlab`
+    pub provider: String,
+    /// `owner/repo` для GitHub, путь проекта (`group/project`) или id для GitLab
+    pub repository: String,
+    /// API провайдера; по умолчанию https://api.github.com или https://gitlab.com/api/v4
+    pub api_url: Option<String>,
+    /// Токен (`${GITHUB_TOKEN}`); без него действуют лимиты анонимного API
+    pub token: Option<String>,
+    /// Длина описания PR, передаваемого в LLM
+    pub max_body_length: usize,
+}
+
+impl Default for PullRequestsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: "github".to_string(),
+            repository: String::new(),
+            api_url: None,
+            token: None,
+            max_body_length: 1000,
+        }
+    }
+}
+
+/// Проверка новой версии deploy-pugin при запуске (`[updates]`)
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct UpdatesConfig {
+    /// Отключается также переменной DEPLOY_PLUGIN_NO_UPDATE_CHECK и в CI
+    pub check: bool,
+    /// GitHub API со списком релизов
+    pub url: String,
+    /// Префикс тегов релизов утилиты в репозитории
+    pub tag_prefix: String,
+    /// Как часто обращаться к `url`; между проверками используется кэш
+    pub interval_hours: u64,
+}
+
+impl Default for UpdatesConfig {
+    fn default() -> Self {
+        Self {
+            check: true,
+            url: "https://api.github.com/repos/Aristman/ride/releases".to_string(),
+            tag_prefix: "deploy-plugin-v".to_string(),
+            interval_hours: 24,
+        }
+    }
+}
+
+/// Вычитка сгенерированных changelog и release notes (`[proofreading]`)
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ProofreadingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// `fix` — исправлять найденное, `report` — только предупреждать
+    #[serde(default)]
+    pub mode: ProofreadingMode,
+    /// Термины в каноническом написании: `["IntelliJ IDEA", "GitHub", "Kotlin"]`
+    #[serde(default)]
+    pub terms: Vec<String>,
+    /// Дополнительный словарь опечаток: `{ "рефакторнг" = "рефакторинг" }`
+    #[serde(default)]
+    pub spelling: HashMap<String, String>,
+    /// Встроенный словарь частых опечаток английского языка
+    #[serde(default = "default_true")]
+    pub builtin_spelling: bool,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofreadingMode {
+    #[default]
+    Fix,
+    Report,
+}
+
+impl Default for ProofreadingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: ProofreadingMode::Fix,
+            terms: Vec::new(),
+            spelling: HashMap::new(),
+            builtin_spelling: true,
+        }
+    }
+}
+
+/// Пайплайн из существующих этапов: `stages = ["build", "validate", "deploy:staging"]`
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineConfig {
+    #[serde(default)]
+    pub description: Option<String>,
+    pub stages: Vec<PipelineStageConfig>,
+}
+
+/// Этап пайплайна: имя (`"deploy:staging"`) или таблица с опциями (`{ stage = "build", profile = "debug" }`)
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum PipelineStageConfig {
+    Name(String),
+    Options(PipelineStageOptions),
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineStageOptions {
+    pub stage: String,
+    /// build: профиль сборки
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// verify: порог размера ресурса в байтах
+    #[serde(default)]
+    pub max_resource_size: Option<u64>,
+    /// deploy: канал публикации
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// deploy: как `--force`
+    #[serde(default)]
+    pub force: Option<bool>,
+    /// deploy: как `--rollback-on-failure`
+    #[serde(default)]
+    pub rollback_on_failure: Option<bool>,
+    /// deploy: как `--allow-republish`
+    #[serde(default)]
+    pub allow_republish: Option<bool>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_clustering_min_commits() -> usize {
+    100
+}
+
+fn default_clustering_provider() -> String {
+    "local".to_string()
+}
+
+fn default_clustering_similarity() -> f32 {
+    0.6
+}
+
+fn default_clustering_duplicate_similarity() -> f32 {
+    0.9
+}
+
+impl Default for ClusteringConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_commits: default_clustering_min_commits(),
+            provider: default_clustering_provider(),
+            similarity: default_clustering_similarity(),
+            duplicate_similarity: default_clustering_duplicate_similarity(),
+        }
+    }
 }
 
 impl Config {
@@ -116,14 +1125,25 @@ impl Config {
         let content = fs::read_to_string(file_path)
             .with_context(|| format!("Не удалось прочитать файл конфигурации: {}", file_path))?;
 
+        let config = Self::from_toml_str(&content)?;
+
+        info!("Конфигурация успешно загружена");
+        Ok(config)
+    }
+
+    /// Разбирает конфигурацию из строки TOML с подстановкой переменных окружения
+    pub fn from_toml_str(content: &str) -> Result<Self> {
         // Подстановка переменных окружения
-        let processed_content = Self::substitute_env_vars(&content);
+        let processed_content = Self::substitute_env_vars(content);
 
-        let config: Config = toml::from_str(&processed_content)
+        // Устаревшие ключи переносятся на новое место до разбора в структуры
+        let mut document: toml_edit::DocumentMut = processed_content
+            .parse()
             .with_context(|| "Ошибка парсинга TOML конфигурации")?;
+        migrate::migrate_on_load(&mut document)?;
 
-        info!("Конфигурация успешно загружена");
-        Ok(config)
+        toml::from_str(&document.to_string())
+            .with_context(|| "Ошибка парсинга TOML конфигурации")
     }
 
     /// Подставляет переменные окружения в формате ${VAR_NAME}
@@ -144,23 +1164,52 @@ impl Config {
         result
     }
 
-    /// Валидирует конфигурацию
-    pub fn validate(&self) -> Result<()> {
-        // Проверка основных полей
-        if self.project.name.is_empty() {
-            return Err(anyhow::anyhow!("Имя проекта не может быть пустым"));
-        }
+    /// Валидирует секции, которые использует команда
+    pub fn validate_for(&self, capabilities: &[Capability]) -> Result<()> {
+        ConfigValidator::validate_for(self, capabilities)
+    }
+}
 
-        if self.project.id.is_empty() {
-            return Err(anyhow::anyhow!("ID проекта не может быть пустым"));
-        }
+/// Минимальная конфигурация для модульных тестов
+#[cfg(test)]
+pub fn test_config() -> Config {
+    Config::from_toml_str(
+        r#"
+[project]
+name = "ride"
+id = "ru.marslab.ide.ride"
+type = "intellij"
 
-        // Проверка URL репозитория
-        if !self.repository.url.starts_with("http") {
-            return Err(anyhow::anyhow!("URL репозитория должен начинаться с http/https"));
-        }
+[build]
+gradle_task = "buildPlugin"
+output_dir = "build/distributions"
 
-        info!("Валидация конфигурации пройдена успешно");
-        Ok(())
-    }
-}
\ No newline at end of file
+[repository]
+url = "https://plugins.example.com/updatePlugins.xml"
+ssh_host = "example.com"
+ssh_user = "deploy"
+deploy_path = "/var/www/plugins/ride"
+xml_path = "/var/www/plugins/updatePlugins.xml"
+
+[llm]
+provider = "yandexgpt"
+temperature = 0.3
+max_tokens = 2000
+
+[yandexgpt]
+api_key = "test_key"
+folder_id = "test_folder"
+model = "yandexgpt"
+
+[llm_agents]
+changelog_agent = { model = "yandexgpt", temperature = 0.3 }
+version_agent = { model = "yandexgpt-lite", temperature = 0.1 }
+release_agent = { model = "yandexgpt", temperature = 0.4 }
+
+[git]
+main_branch = "main"
+tag_prefix = "v"
+"#,
+    )
+    .expect("test config")
+}
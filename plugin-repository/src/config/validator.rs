@@ -2,39 +2,200 @@ use anyhow::{Context, Result};
 use crate::config::parser::Config;
 use tracing::info;
 
+/// Возможности, которые использует команда: каждая проверяет только свои секции конфигурации
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// Сборка: `[build]`
+    Build,
+    /// LLM и история git: `[llm]`, `[yandexgpt]`, `[llm_agents]`, `[git]`, `[changelog]`, `[proofreading]`, `[network]`
+    Ai,
+    /// Загрузка в репозиторий: `[repository]`, `[network]`
+    Deploy,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 3] = [Capability::Build, Capability::Ai, Capability::Deploy];
+
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "build" => Ok(Capability::Build),
+            "ai" => Ok(Capability::Ai),
+            "deploy" => Ok(Capability::Deploy),
+            other => Err(anyhow::anyhow!(
+                "Неизвестная область валидации '{}': ожидается build, ai или deploy",
+                other
+            )),
+        }
+    }
+}
+
 /// Валидатор конфигурации
 pub struct ConfigValidator;
 
 impl ConfigValidator {
-    /// Полная валидация конфигурации
-    pub fn validate(config: &Config) -> Result<()> {
-        info!("Начало валидации конфигурации");
+    /// Валидация секций, нужных указанным возможностям; `[project]` проверяется всегда
+    pub fn validate_for(config: &Config, capabilities: &[Capability]) -> Result<()> {
+        info!("Начало валидации конфигурации ({:?})", capabilities);
 
         // Валидация проекта
         Self::validate_project(&config.project)?;
 
-        // Валидация сборки
-        Self::validate_build(&config.build)?;
+        // Пайплайны проверяются всегда: ошибка в описании видна до запуска `run`
+        for (name, pipeline) in &config.pipelines {
+            crate::core::pipeline::parse_pipeline(name, pipeline)?;
+        }
+
+        if capabilities.contains(&Capability::Build) {
+            // Валидация сборки
+            Self::validate_build(&config.build)?;
+        }
+
+        if capabilities.contains(&Capability::Deploy) {
+            // Валидация репозитория
+            Self::validate_repository(&config.repository)?;
+        }
+
+        if capabilities.contains(&Capability::Ai) {
+            // Валидация LLM конфигурации
+            Self::validate_llm(&config.llm)?;
+
+            // Валидация секции выбранного провайдера
+            match config.llm.provider.as_str() {
+                "openai" => Self::validate_openai(config.openai.as_ref())?,
+                "ollama" => Self::validate_ollama(&config.ollama.clone().unwrap_or_default())?,
+                "yandexgpt" => Self::validate_yandexgpt(&config.yandexgpt)?,
+                _ => {}
+            }
+
+            // Валидация агентов
+            Self::validate_agents(&config.llm_agents)?;
+
+            // Валидация Git конфигурации
+            Self::validate_git(&config.git)?;
 
-        // Валидация репозитория
-        Self::validate_repository(&config.repository)?;
+            // Валидация группировки коммитов
+            Self::validate_clustering(&config.changelog.clustering)?;
+            if config.changelog.max_length == Some(0) {
+                return Err(anyhow::anyhow!("changelog.max_length должен быть больше 0"));
+            }
+            let diff_summary = &config.changelog.diff_summary;
+            if diff_summary.enabled && (diff_summary.max_commits == 0 || diff_summary.max_diff_chars == 0) {
+                return Err(anyhow::anyhow!("changelog.diff_summary: max_commits и max_diff_chars должны быть больше 0"));
+            }
 
-        // Валидация LLM конфигурации
-        Self::validate_llm(&config.llm)?;
+            // Валидация правил исключения коммитов
+            crate::git::CommitFilter::new(&config.changelog.exclude)?;
 
-        // Валидация YandexGPT
-        Self::validate_yandexgpt(&config.yandexgpt)?;
+            // Валидация интеграции с PR/MR
+            Self::validate_pull_requests(&config.pull_requests)?;
 
-        // Валидация агентов
-        Self::validate_agents(&config.llm_agents)?;
+            // Валидация локалей change-notes
+            Self::validate_change_notes(&config.change_notes)?;
 
-        // Валидация Git конфигурации
-        Self::validate_git(&config.git)?;
+            // Валидация словарей вычитки
+            Self::validate_proofreading(&config.proofreading)?;
+        }
+
+        if capabilities.contains(&Capability::Ai) || capabilities.contains(&Capability::Deploy) {
+            // Валидация сетевых настроек
+            crate::utils::network::NetworkUtils::validate_proxy(&config.network.proxy)?;
+        }
 
         info!("Валидация конфигурации успешно завершена");
         Ok(())
     }
 
+    /// Валидация `[server]` для команды serve: API без токена не запускается
+    pub fn validate_server(server: &crate::config::parser::ServerConfig) -> Result<()> {
+        server.bind.parse::<std::net::SocketAddr>()
+            .with_context(|| format!("Некорректный адрес [server] bind: '{}'", server.bind))?;
+
+        match server.token.as_deref().map(str::trim) {
+            None | Some("") => {
+                return Err(anyhow::anyhow!("Не задан [server] token: API публикации требует аутентификации"));
+            }
+            Some(token) if token.starts_with("${") => {
+                return Err(anyhow::anyhow!("Переменная окружения для [server] token не задана: {}", token));
+            }
+            Some(token) if token.len() < 16 => {
+                return Err(anyhow::anyhow!("[server] token слишком короткий: нужно не менее 16 символов"));
+            }
+            Some(_) => {}
+        }
+
+        if server.max_runs == 0 {
+            return Err(anyhow::anyhow!("[server] max_runs должен быть больше нуля"));
+        }
+
+        Ok(())
+    }
+
+    /// Валидация `[chatops]` (проверяется командой serve, только если бот включен)
+    fn validate_pull_requests(pull_requests: &crate::config::parser::PullRequestsConfig) -> Result<()> {
+        if !pull_requests.enabled {
+            return Ok(());
+        }
+
+        let repository = pull_requests.repository.trim();
+        match pull_requests.provider.as_str() {
+            "github" if repository.split('/').filter(|part| !part.is_empty()).count() != 2 => {
+                return Err(anyhow::anyhow!("[pull_requests] repository для GitHub задается как owner/repo"));
+            }
+            "gitlab" if repository.is_empty() => {
+                return Err(anyhow::anyhow!("Не задан [pull_requests] repository: путь проекта GitLab или его id"));
+            }
+            "github" | "gitlab" => {}
+            other => {
+                return Err(anyhow::anyhow!("Неподдерживаемый [pull_requests] provider '{}': ожидается github или gitlab", other));
+            }
+        }
+
+        if let Some(url) = &pull_requests.api_url {
+            if !url.starts_with("http") {
+                return Err(anyhow::anyhow!("[pull_requests] api_url должен начинаться с http или https"));
+            }
+        }
+
+        if let Some(token) = pull_requests.token.as_deref().map(str::trim) {
+            if token.starts_with("${") {
+                return Err(anyhow::anyhow!("Переменная окружения для [pull_requests] token не задана: {}", token));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_chatops(chatops: &crate::config::parser::ChatOpsConfig) -> Result<()> {
+        if !chatops.enabled {
+            return Ok(());
+        }
+
+        if chatops.provider != "telegram" {
+            return Err(anyhow::anyhow!(
+                "Неподдерживаемый [chatops] provider '{}': ожидается telegram",
+                chatops.provider
+            ));
+        }
+
+        match chatops.bot_token.as_deref().map(str::trim) {
+            None | Some("") => return Err(anyhow::anyhow!("Не задан [chatops] bot_token")),
+            Some(token) if token.starts_with("${") => {
+                return Err(anyhow::anyhow!("Переменная окружения для [chatops] bot_token не задана: {}", token));
+            }
+            Some(_) => {}
+        }
+
+        if chatops.chat_id == 0 {
+            return Err(anyhow::anyhow!("Не задан [chatops] chat_id: бот принимает команды только из одного чата"));
+        }
+
+        if !chatops.api_url.starts_with("http") {
+            return Err(anyhow::anyhow!("[chatops] api_url должен начинаться с http или https"));
+        }
+
+        Ok(())
+    }
+
     fn validate_project(project: &crate::config::parser::ProjectConfig) -> Result<()> {
         if project.name.is_empty() {
             return Err(anyhow::anyhow!("Имя проекта не может быть пустым"));
@@ -62,6 +223,64 @@ impl ConfigValidator {
             return Err(anyhow::anyhow!("Директория вывода не может быть пустой"));
         }
 
+        if let Some(version) = &build.wrapper.gradle_version {
+            if version.is_empty() || !version.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-') {
+                return Err(anyhow::anyhow!("Некорректная версия Gradle в [build.wrapper]: '{}'", version));
+            }
+        }
+
+        if !build.wrapper.distributions_url.starts_with("http") {
+            return Err(anyhow::anyhow!(
+                "URL дистрибутивов Gradle должен начинаться с http или https"
+            ));
+        }
+
+        for checksum in &build.wrapper.allowed_checksums {
+            if checksum.len() != 64 || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(anyhow::anyhow!("Некорректная sha256 в [build.wrapper] allowed_checksums: '{}'", checksum));
+            }
+        }
+
+        for (name, profile) in &build.profiles {
+            if profile.task.as_deref() == Some("") || profile.output_dir.as_deref() == Some("") {
+                return Err(anyhow::anyhow!("Профиль сборки '{}': task и output_dir не могут быть пустыми", name));
+            }
+            if let Some(suffix) = &profile.version_suffix {
+                if !suffix.starts_with('-') || !suffix[1..].split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric())) {
+                    return Err(anyhow::anyhow!(
+                        "Профиль сборки '{}': version_suffix должен иметь вид -nightly или -rc.1, получено '{}'", name, suffix
+                    ));
+                }
+            }
+        }
+
+        if build.mcp.enabled {
+            if !build.mcp.url.starts_with("http://") && !build.mcp.url.starts_with("https://") {
+                return Err(anyhow::anyhow!("[build.mcp] url должен начинаться с http:// или https://"));
+            }
+            if build.mcp.workspace_dir.split('/').any(|part| part == "..") {
+                return Err(anyhow::anyhow!("[build.mcp] workspace_dir не может выходить за пределы workspace"));
+            }
+            if build.mcp.timeout_secs == 0 {
+                return Err(anyhow::anyhow!("[build.mcp] timeout_secs не может быть 0"));
+            }
+        }
+
+        for step in &build.post_process {
+            if let crate::config::parser::PostProcessorConfig::Integrity { path, sign_command } = step {
+                if path.is_empty() || path.ends_with('/') {
+                    return Err(anyhow::anyhow!("Некорректный путь манифеста целостности: '{}'", path));
+                }
+                if let Some(command) = sign_command {
+                    if !command.contains("{input}") || !command.contains("{output}") {
+                        return Err(anyhow::anyhow!(
+                            "Команда подписи манифеста целостности должна содержать {{input}} и {{output}}"
+                        ));
+                    }
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -83,18 +302,87 @@ impl ConfigValidator {
         if repository.deploy_path.is_empty() {
             return Err(anyhow::anyhow!("Путь деплоя не может быть пустым"));
         }
+        crate::core::deploy_layout::DeployLayout::validate_template(&repository.deploy_path)?;
+        crate::core::deploy_layout::DeployLayout::validate_segment("channel", &repository.channel)
+            .context("Некорректный repository.channel")?;
 
         if repository.xml_path.is_empty() {
             return Err(anyhow::anyhow!("Путь к XML файлу не может быть пустым"));
         }
 
+        if let Some(command) = &repository.checksums.sign_command {
+            if !command.contains("{input}") || !command.contains("{output}") {
+                return Err(anyhow::anyhow!(
+                    "Команда подписи SHA256SUMS должна содержать {{input}} и {{output}}"
+                ));
+            }
+        }
+
+        if repository.validate_index.command.as_deref().is_some_and(|c| c.trim().is_empty()) {
+            return Err(anyhow::anyhow!("[repository.validate_index] command не может быть пустым"));
+        }
+
+        if repository.cache.enabled && repository.cache.max_size_mb == 0 {
+            return Err(anyhow::anyhow!("[repository.cache] max_size_mb не может быть 0; для отключения кэша укажите enabled = false"));
+        }
+
+        Ok(())
+    }
+
+    fn validate_clustering(clustering: &crate::config::parser::ClusteringConfig) -> Result<()> {
+        if !["local", "yandexgpt", "openai", "ollama"].contains(&clustering.provider.as_str()) {
+            return Err(anyhow::anyhow!(
+                "Провайдер эмбеддингов должен быть 'local', 'yandexgpt', 'openai' или 'ollama'"
+            ));
+        }
+
+        if clustering.similarity <= 0.0 || clustering.similarity > 1.0 {
+            return Err(anyhow::anyhow!(
+                "Порог сходства коммитов должен быть в диапазоне (0.0, 1.0]"
+            ));
+        }
+
+        if clustering.duplicate_similarity < clustering.similarity || clustering.duplicate_similarity > 1.0 {
+            return Err(anyhow::anyhow!(
+                "Порог повторов duplicate_similarity должен быть в диапазоне [similarity, 1.0]"
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn validate_change_notes(change_notes: &crate::config::parser::ChangeNotesConfig) -> Result<()> {
+        for locale in &change_notes.locales {
+            crate::core::localized_notes::validate_locale(locale)?;
+        }
+
+        if !change_notes.locales.is_empty() && change_notes.bundle.trim_matches('/').is_empty() {
+            return Err(anyhow::anyhow!("Путь resource bundle для change-notes не может быть пустым"));
+        }
+
+        Ok(())
+    }
+
+    fn validate_proofreading(proofreading: &crate::config::parser::ProofreadingConfig) -> Result<()> {
+        if !proofreading.enabled {
+            return Ok(());
+        }
+
+        if proofreading.terms.iter().any(|t| t.trim().is_empty()) {
+            return Err(anyhow::anyhow!("[proofreading] terms не может содержать пустые термины"));
+        }
+
+        if let Some((wrong, _)) = proofreading.spelling.iter().find(|(w, r)| w.trim().is_empty() || r.trim().is_empty()) {
+            return Err(anyhow::anyhow!("[proofreading] spelling: пустое слово или исправление для '{}'", wrong));
+        }
+
         Ok(())
     }
 
     fn validate_llm(llm: &crate::config::parser::LlmConfig) -> Result<()> {
-        if !["yandexgpt", "openai", "anthropic"].contains(&llm.provider.as_str()) {
+        if !["yandexgpt", "openai", "anthropic", "ollama", "mock"].contains(&llm.provider.as_str()) {
             return Err(anyhow::anyhow!(
-                "LLM провайдер должен быть 'yandexgpt', 'openai' или 'anthropic'"
+                "LLM провайдер должен быть 'yandexgpt', 'openai', 'anthropic', 'ollama' или 'mock'"
             ));
         }
 
@@ -108,6 +396,37 @@ impl ConfigValidator {
             return Err(anyhow::anyhow!("Максимальное количество токенов не может быть 0"));
         }
 
+        if llm.request_timeout_secs == 0 {
+            return Err(anyhow::anyhow!("[llm] request_timeout_secs не может быть 0"));
+        }
+
+        if llm.circuit_breaker_threshold > 0 && llm.circuit_breaker_cooldown_secs == 0 {
+            return Err(anyhow::anyhow!(
+                "[llm] circuit_breaker_cooldown_secs не может быть 0 при включенном circuit breaker"
+            ));
+        }
+
+        if llm.max_cost.is_some_and(|max_cost| max_cost <= 0.0) {
+            return Err(anyhow::anyhow!("[llm] max_cost должен быть больше 0"));
+        }
+        for (model, pricing) in &llm.pricing {
+            if pricing.input_per_1m < 0.0 || pricing.output_per_1m < 0.0 {
+                return Err(anyhow::anyhow!("[llm.pricing.{}] цена не может быть отрицательной", model));
+            }
+        }
+
+        let retry = &llm.retry;
+        if !(0.0..=1.0).contains(&retry.jitter) {
+            return Err(anyhow::anyhow!("[llm.retry] jitter должен быть в диапазоне от 0 до 1"));
+        }
+        if retry.base_delay_ms > retry.max_delay_ms {
+            return Err(anyhow::anyhow!(
+                "[llm.retry] base_delay_ms ({}) больше max_delay_ms ({})",
+                retry.base_delay_ms,
+                retry.max_delay_ms
+            ));
+        }
+
         Ok(())
     }
 
@@ -120,12 +439,65 @@ impl ConfigValidator {
             return Err(anyhow::anyhow!("Folder ID YandexGPT не может быть пустым"));
         }
 
+        for (i, account) in yandexgpt.accounts.iter().enumerate() {
+            if account.api_key.is_empty() || account.folder_id.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "[[yandexgpt.accounts]] #{}: api_key и folder_id не могут быть пустыми",
+                    i + 1
+                ));
+            }
+        }
+
         if !["yandexgpt", "yandexgpt-lite"].contains(&yandexgpt.model.as_str()) {
             return Err(anyhow::anyhow!(
                 "Модель YandexGPT должна быть 'yandexgpt' или 'yandexgpt-lite'"
             ));
         }
 
+        for url in [&yandexgpt.base_url, &yandexgpt.operation_url] {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(anyhow::anyhow!("URL YandexGPT должен начинаться с http:// или https://: '{}'", url));
+            }
+        }
+
+        if yandexgpt.async_threshold_chars > 0 && yandexgpt.async_poll_interval_secs == 0 {
+            return Err(anyhow::anyhow!("async_poll_interval_secs YandexGPT не может быть 0"));
+        }
+
+        Ok(())
+    }
+
+    fn validate_openai(openai: Option<&crate::config::parser::OpenAiConfig>) -> Result<()> {
+        let openai = openai.ok_or_else(|| anyhow::anyhow!("[llm] provider = \"openai\" требует секцию [openai]"))?;
+
+        if openai.api_key.is_empty() || openai.api_key.contains("${") {
+            return Err(anyhow::anyhow!("API ключ OpenAI не задан (OPENAI_API_KEY)"));
+        }
+
+        if openai.model.trim().is_empty() {
+            return Err(anyhow::anyhow!("Модель OpenAI не может быть пустой"));
+        }
+
+        if !openai.base_url.starts_with("http://") && !openai.base_url.starts_with("https://") {
+            return Err(anyhow::anyhow!("[openai] base_url должен начинаться с http:// или https://"));
+        }
+
+        Ok(())
+    }
+
+    fn validate_ollama(ollama: &crate::config::parser::OllamaConfig) -> Result<()> {
+        if !ollama.host.starts_with("http://") && !ollama.host.starts_with("https://") {
+            return Err(anyhow::anyhow!("[ollama] host должен начинаться с http:// или https://"));
+        }
+
+        if ollama.model.trim().is_empty() {
+            return Err(anyhow::anyhow!("Модель Ollama не может быть пустой"));
+        }
+
+        if ollama.timeout_secs == 0 {
+            return Err(anyhow::anyhow!("[ollama] timeout_secs не может быть 0"));
+        }
+
         Ok(())
     }
 
@@ -134,15 +506,23 @@ impl ConfigValidator {
             (&agents.changelog_agent, "changelog_agent"),
             (&agents.version_agent, "version_agent"),
             (&agents.release_agent, "release_agent"),
+            (&agents.review_agent, "review_agent"),
+            (&agents.diff_agent, "diff_agent"),
         ];
 
         for (agent_config, name) in agent_configs {
-            if agent_config.temperature < 0.0 || agent_config.temperature > 2.0 {
+            if agent_config.temperature.is_some_and(|t| !(0.0..=2.0).contains(&t)) {
                 return Err(anyhow::anyhow!(
                     "Температура для {} должна быть в диапазоне от 0.0 до 2.0",
                     name
                 ));
             }
+            if agent_config.system_prompt.as_ref().is_some_and(|p| p.trim().is_empty()) {
+                return Err(anyhow::anyhow!(
+                    "Системный промпт {} пуст: удалите system_prompt, чтобы использовать общий",
+                    name
+                ));
+            }
         }
 
         Ok(())
@@ -153,6 +533,37 @@ impl ConfigValidator {
             return Err(anyhow::anyhow!("Основная ветка не может быть пустой"));
         }
 
+        crate::git::version_rules::from_configs(&git.version_rules)?;
+
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_scope_ignores_missing_deploy_and_llm_sections() {
+        let config = Config::from_toml_str(
+            r#"
+[project]
+name = "Ride"
+id = "ru.marslab.ide.ride"
+type = "intellij"
+
+[build]
+gradle_task = "buildPlugin"
+output_dir = "build/distributions"
+"#,
+        )
+        .unwrap();
+
+        assert!(ConfigValidator::validate_for(&config, &[Capability::Build]).is_ok());
+        let err = ConfigValidator::validate_for(&config, &[Capability::Deploy]).unwrap_err();
+        assert!(err.to_string().contains("URL репозитория"));
+        assert!(ConfigValidator::validate_for(&config, &[Capability::Ai]).is_err());
+        assert!(ConfigValidator::validate_for(&config, &Capability::ALL).is_err());
+        assert_eq!(Capability::parse("Deploy").unwrap(), Capability::Deploy);
+        assert!(Capability::parse("ssh").is_err());
+    }
+}
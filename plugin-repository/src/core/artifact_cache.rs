@@ -0,0 +1,185 @@
+//! Локальный кэш выпущенных артефактов: старый ZIP скачивается из репозитория один раз
+//! и переиспользуется при сравнениях и переключении версий. Размер кэша ограничен: при
+//! превышении удаляются версии, к которым дольше всего не обращались.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::{debug, info};
+
+use crate::config::parser::Config;
+
+/// Откуда кэш получает отсутствующий артефакт (SFTP репозитория, HTTP, локальный каталог)
+pub trait ArtifactSource {
+    /// Загружает артефакт версии в каталог `dir` и возвращает путь к файлу
+    fn fetch(&self, version: &str, dir: &Path) -> Result<PathBuf>;
+}
+
+impl<F: Fn(&str, &Path) -> Result<PathBuf>> ArtifactSource for F {
+    fn fetch(&self, version: &str, dir: &Path) -> Result<PathBuf> {
+        self(version, dir)
+    }
+}
+
+/// Артефакт из кэша
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedArtifact {
+    pub path: PathBuf,
+    /// `false` — артефакт только что загружен
+    pub hit: bool,
+}
+
+/// Кэш артефактов одного плагина: `<dir>/<plugin_id>/<version>/<file>.zip`
+pub struct ArtifactCache {
+    root: PathBuf,
+    max_bytes: u64,
+}
+
+impl ArtifactCache {
+    /// Кэш из `[repository.cache]`; `None`, если кэш отключен
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let cache = &config.repository.cache;
+        cache
+            .enabled
+            .then(|| Self::new(Path::new(&cache.dir).join(&config.project.id), cache.max_size_mb * 1024 * 1024))
+    }
+
+    pub fn new(root: impl Into<PathBuf>, max_bytes: u64) -> Self {
+        Self { root: root.into(), max_bytes }
+    }
+
+    fn version_dir(&self, version: &str) -> Result<PathBuf> {
+        if version.is_empty() || version.starts_with('.') || version.contains(['/', '\\']) {
+            anyhow::bail!("Некорректная версия для кэша артефактов: '{}'", version);
+        }
+        Ok(self.root.join(version))
+    }
+
+    /// Артефакт версии из кэша; обращение откладывает его вытеснение
+    pub fn get(&self, version: &str) -> Option<PathBuf> {
+        let path = fs::read_dir(self.version_dir(version).ok()?)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .find(|path| path.extension().and_then(|e| e.to_str()) == Some("zip"))?;
+        if let Ok(file) = fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(path)
+    }
+
+    /// Артефакт из кэша или из `source`; загрузка идет во временный каталог, так что
+    /// прерванное скачивание не оставляет в кэше битый ZIP
+    pub fn get_or_fetch(&self, version: &str, source: &dyn ArtifactSource) -> Result<CachedArtifact> {
+        if let Some(path) = self.get(version) {
+            debug!("Артефакт {} взят из кэша: {}", version, path.display());
+            return Ok(CachedArtifact { path, hit: true });
+        }
+
+        let dir = self.version_dir(version)?;
+        fs::create_dir_all(&self.root)
+            .with_context(|| format!("Не удалось создать каталог кэша {}", self.root.display()))?;
+        let staging = tempfile::Builder::new()
+            .prefix(".fetch-")
+            .tempdir_in(&self.root)
+            .context("Не удалось создать временный каталог в кэше артефактов")?;
+        let fetched = source.fetch(version, staging.path())?;
+        let file_name = fetched.file_name().context("Источник вернул путь без имени файла")?;
+
+        fs::create_dir_all(&dir).with_context(|| format!("Не удалось создать каталог {}", dir.display()))?;
+        let path = dir.join(file_name);
+        fs::rename(&fetched, &path).with_context(|| format!("Не удалось сохранить артефакт в кэш: {}", path.display()))?;
+        info!("📥 Артефакт {} сохранен в кэш: {}", version, path.display());
+
+        self.evict(version)?;
+        Ok(CachedArtifact { path, hit: false })
+    }
+
+    /// Удаляет версию из кэша (например, если артефакт на сервере заменили)
+    pub fn remove(&self, version: &str) -> Result<()> {
+        let dir = self.version_dir(version)?;
+        if dir.exists() {
+            fs::remove_dir_all(&dir).with_context(|| format!("Не удалось удалить {}", dir.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Удаляет давно не использованные версии, пока кэш больше предела. Версия `keep`
+    /// остается, даже если одна превышает предел
+    pub fn evict(&self, keep: &str) -> Result<Vec<String>> {
+        let mut versions = Vec::new();
+        for entry in fs::read_dir(&self.root).with_context(|| format!("Не удалось прочитать {}", self.root.display()))? {
+            let entry = entry?;
+            let version = entry.file_name().to_string_lossy().to_string();
+            if version.starts_with('.') || !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let (mut size, mut last_used) = (0, SystemTime::UNIX_EPOCH);
+            for file in fs::read_dir(entry.path())?.filter_map(|f| f.ok()) {
+                let metadata = file.metadata()?;
+                size += metadata.len();
+                last_used = last_used.max(metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH));
+            }
+            versions.push((version, size, last_used));
+        }
+
+        let mut total: u64 = versions.iter().map(|(_, size, _)| size).sum();
+        versions.sort_by_key(|(_, _, last_used)| *last_used);
+        let mut evicted = Vec::new();
+        for (version, size, _) in versions {
+            if total <= self.max_bytes {
+                break;
+            }
+            if version == keep {
+                continue;
+            }
+            self.remove(&version)?;
+            total -= size;
+            evicted.push(version);
+        }
+        if !evicted.is_empty() {
+            info!("🧹 Из кэша артефактов удалены версии: {}", evicted.join(", "));
+        }
+        Ok(evicted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_artifact_cache_fetches_once_and_evicts_lru() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ArtifactCache::new(dir.path().join("ru.marslab.ide.ride"), 250);
+        let downloads = Cell::new(0);
+        let source = |version: &str, dir: &Path| {
+            downloads.set(downloads.get() + 1);
+            let path = dir.join(format!("ride-{}.zip", version));
+            fs::write(&path, [0u8; 100])?;
+            Ok(path)
+        };
+
+        let first = cache.get_or_fetch("1.0.0", &source).unwrap();
+        assert!(!first.hit);
+        assert!(first.path.ends_with("ru.marslab.ide.ride/1.0.0/ride-1.0.0.zip"));
+        assert_eq!(cache.get_or_fetch("1.0.0", &source).unwrap(), CachedArtifact { path: first.path, hit: true });
+        assert_eq!(downloads.get(), 1);
+
+        // Третья версия не помещается: вытесняется 1.1.0, к которой обращались раньше, чем к 1.0.0
+        cache.get_or_fetch("1.1.0", &source).unwrap();
+        assert!(cache.get("1.0.0").is_some());
+        cache.get_or_fetch("1.2.0", &source).unwrap();
+        assert!(cache.get("1.1.0").is_none());
+        assert!(cache.get("1.0.0").is_some() && cache.get("1.2.0").is_some());
+        assert_eq!(downloads.get(), 3);
+
+        // Прерванная загрузка не оставляет записи
+        let failing = |_: &str, _: &Path| -> Result<PathBuf> { anyhow::bail!("обрыв соединения") };
+        assert!(cache.get_or_fetch("1.3.0", &failing).is_err());
+        assert!(cache.get("1.3.0").is_none());
+        assert!(cache.get_or_fetch("../etc", &source).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::utils::clock::Clock;
+
+/// Журнал операций над репозиторием плагинов (JSON Lines)
+pub const AUDIT_FILE: &str = "audit.jsonl";
+
+/// Запись журнала аудита
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    /// Операция: promote, ...
+    pub action: String,
+    pub version: String,
+    /// Версия, опубликованная до операции
+    pub previous_version: Option<String>,
+    pub user: String,
+    pub reason: Option<String>,
+    pub details: String,
+}
+
+impl AuditEntry {
+    pub fn new(action: &str, version: &str, clock: &dyn Clock) -> Self {
+        Self {
+            timestamp: clock.now(),
+            action: action.to_string(),
+            version: version.to_string(),
+            previous_version: None,
+            user: current_user(),
+            reason: None,
+            details: String::new(),
+        }
+    }
+}
+
+/// Пользователь, выполнивший операцию (учитывает CI)
+fn current_user() -> String {
+    ["GITHUB_ACTOR", "GITLAB_USER_LOGIN", "USER", "USERNAME"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Путь к журналу аудита проекта
+pub fn audit_path(project_root: &Path) -> PathBuf {
+    project_root.join(".deploy-plugin").join(AUDIT_FILE)
+}
+
+/// Дописывает запись в журнал; возвращает путь к файлу
+pub fn append(project_root: &Path, entry: &AuditEntry) -> Result<PathBuf> {
+    let path = audit_path(project_root);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Не удалось создать директорию {}", dir.display()))?;
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Не удалось открыть журнал аудита {}", path.display()))?;
+    let line = serde_json::to_string(entry).context("Не удалось сериализовать запись аудита")?;
+    writeln!(file, "{}", line)
+        .with_context(|| format!("Не удалось записать журнал аудита {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clock::FixedClock;
+
+    #[test]
+    fn test_append_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let clock = FixedClock::new("2024-05-01T12:00:00Z".parse().unwrap());
+        let mut entry = AuditEntry::new("promote", "1.2.0", &clock);
+        entry.previous_version = Some("1.3.0".to_string());
+        entry.reason = Some("1.3.0 breaks indexing".to_string());
+
+        append(dir.path(), &entry).unwrap();
+        append(dir.path(), &AuditEntry::new("promote", "1.3.1", &clock)).unwrap();
+
+        let content = fs::read_to_string(audit_path(dir.path())).unwrap();
+        let entries: Vec<AuditEntry> = content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], entry);
+        assert_eq!(entries[1].version, "1.3.1");
+        assert!(content.starts_with("{\"timestamp\":\"2024-05-01T12:00:00Z\""));
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::Path;
+use std::time::Duration;
+use tokio::process::Command as AsyncCommand;
+use tokio::time::timeout;
+use tracing::debug;
+
+use crate::config::parser::WrapperConfig;
+use crate::core::builder::ProjectType;
+use crate::core::gradle_dsl;
+use crate::core::gradle_wrapper::{distribution_version, WRAPPER_PROPERTIES};
+use crate::git::GitCommand;
+use crate::models::plugin::BuildEnvironment;
+
+/// Таймаут `java -version` / `mvn -v` / `gradle --version`
+const PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Версия JDK из `java -version`: `openjdk version "17.0.9" 2023-10-17` -> `17.0.9`
+pub fn parse_java_version(output: &str) -> Option<String> {
+    let line = output.lines().find(|l| l.contains(" version \""))?;
+    let version = line.split('"').nth(1)?.trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+/// Версия из строки с префиксом: `Gradle 8.7` (`gradle --version`), `Apache Maven 3.9.6 (...)` (`mvn -v`)
+fn parse_tool_version(output: &str, prefix: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|l| l.trim().strip_prefix(prefix))
+        .and_then(|rest| rest.split_whitespace().next())
+        .map(|v| v.to_string())
+}
+
+pub fn parse_gradle_version(output: &str) -> Option<String> {
+    parse_tool_version(output, "Gradle ")
+}
+
+pub fn parse_maven_version(output: &str) -> Option<String> {
+    parse_tool_version(output, "Apache Maven ")
+}
+
+/// Собирает сведения об окружении сборки; недоступные значения остаются пустыми
+pub async fn capture(project_root: &Path, project_type: &ProjectType, wrapper: &WrapperConfig) -> BuildEnvironment {
+    let (git_commit, git_dirty) = git_state(project_root).await;
+    BuildEnvironment {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        jdk: jdk_version(project_root).await,
+        build_tool: build_tool(project_root, project_type, wrapper).await,
+        target_platform: match project_type {
+            ProjectType::Gradle => gradle_dsl::read(project_root).ok().flatten().and_then(|dsl| dsl.platform()),
+            ProjectType::Maven => None,
+        },
+        git_commit,
+        git_dirty,
+        tool: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+    }
+}
+
+/// JDK, которым соберет Gradle/Maven: `$JAVA_HOME/bin/java`, иначе `java` из PATH
+async fn jdk_version(project_root: &Path) -> Option<String> {
+    let java = std::env::var_os("JAVA_HOME")
+        .map(|home| Path::new(&home).join("bin").join("java"))
+        .unwrap_or_else(|| "java".into());
+    // java -version пишет в stderr
+    let output = probe(project_root, java.as_os_str(), &["-version"]).await?;
+    parse_java_version(&output)
+}
+
+async fn build_tool(project_root: &Path, project_type: &ProjectType, wrapper: &WrapperConfig) -> Option<String> {
+    let version = match project_type {
+        ProjectType::Gradle => {
+            let from_wrapper = if project_root.join("gradlew").exists() {
+                std::fs::read_to_string(project_root.join(WRAPPER_PROPERTIES))
+                    .ok()
+                    .and_then(|p| distribution_version(&p))
+            } else {
+                wrapper.gradle_version.clone()
+            };
+            match from_wrapper {
+                Some(version) => Some(version),
+                None => parse_gradle_version(&probe(project_root, "gradle".as_ref(), &["--version"]).await?),
+            }
+        }
+        ProjectType::Maven => parse_maven_version(&probe(project_root, "mvn".as_ref(), &["-v"]).await?),
+    }?;
+    let name = match project_type {
+        ProjectType::Gradle => "Gradle",
+        ProjectType::Maven => "Maven",
+    };
+    Some(format!("{} {}", name, version))
+}
+
+async fn git_state(project_root: &Path) -> (Option<String>, Option<bool>) {
+    let commit = GitCommand::new(project_root)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .await
+        .ok()
+        .filter(|o| o.success())
+        .map(|o| o.stdout.trim().to_string());
+    if commit.is_none() {
+        return (None, None);
+    }
+    let dirty = GitCommand::new(project_root)
+        .args(["status", "--porcelain"])
+        .output()
+        .await
+        .ok()
+        .filter(|o| o.success())
+        .map(|o| !o.stdout.trim().is_empty());
+    (commit, dirty)
+}
+
+/// stdout и stderr команды; None, если команда недоступна или завершилась с ошибкой
+async fn probe(project_root: &Path, program: &std::ffi::OsStr, args: &[&str]) -> Option<String> {
+    let mut cmd = AsyncCommand::new(program);
+    cmd.current_dir(project_root).args(args).kill_on_drop(true);
+    let output = timeout(PROBE_TIMEOUT, cmd.output()).await.ok()?.ok();
+    let Some(output) = output.filter(|o| o.status.success()) else {
+        debug!("Не удалось определить версию: {:?} {}", program, args.join(" "));
+        return None;
+    };
+    Some(format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tool_versions() {
+        let java = "openjdk version \"17.0.9\" 2023-10-17\n\
+                    OpenJDK Runtime Environment Temurin-17.0.9+9 (build 17.0.9+9)\n";
+        assert_eq!(parse_java_version(java).as_deref(), Some("17.0.9"));
+        assert_eq!(parse_java_version("java version \"1.8.0_392\"").as_deref(), Some("1.8.0_392"));
+        assert_eq!(parse_java_version("command not found"), None);
+
+        let gradle = "\n------------------------------------------------------------\nGradle 8.7\n\
+                      ------------------------------------------------------------\n\nBuild time: 2024-03-22\n";
+        assert_eq!(parse_gradle_version(gradle).as_deref(), Some("8.7"));
+        let maven = "Apache Maven 3.9.6 (bc0240f3c744dd6b6ec2920b3cd08dcc295161ae)\nJava version: 17.0.9\n";
+        assert_eq!(parse_maven_version(maven).as_deref(), Some("3.9.6"));
+        assert_eq!(parse_maven_version(gradle), None);
+    }
+}
@@ -0,0 +1,87 @@
+//! Профили сборки `[build.profiles.<имя>]`: задача, аргументы, каталог артефактов и суффикс
+//! версии. Профиль накладывается на `[build]` до сборки, поэтому builder и deployer видят один
+//! и тот же каталог артефактов.
+
+use anyhow::Result;
+use tracing::info;
+
+use crate::config::parser::Config;
+
+/// Профиль по умолчанию: `[build]` без изменений
+pub const DEFAULT_PROFILE: &str = "release";
+
+/// Выбранный профиль сборки
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildProfile {
+    pub name: String,
+    pub version_suffix: Option<String>,
+}
+
+impl BuildProfile {
+    /// Накладывает профиль `name` на `[build]`. Без `[build.profiles]` любое имя допустимо
+    /// (Maven получает его как `-P`); если профили описаны, неизвестное имя — ошибка
+    pub fn select(config: &mut Config, name: &str) -> Result<Self> {
+        let Some(profile) = config.build.profiles.get(name).cloned() else {
+            if !config.build.profiles.is_empty() && name != DEFAULT_PROFILE {
+                let mut known: Vec<&str> = config.build.profiles.keys().map(String::as_str).collect();
+                known.sort();
+                anyhow::bail!("Профиль сборки '{}' не описан в [build.profiles]; доступны: {}", name, known.join(", "));
+            }
+            return Ok(Self { name: name.to_string(), version_suffix: None });
+        };
+
+        let build = &mut config.build;
+        if let Some(task) = profile.task {
+            build.gradle_task = task;
+        }
+        if let Some(args) = profile.args {
+            build.build_args = args;
+        }
+        if let Some(output_dir) = profile.output_dir {
+            build.output_dir = output_dir;
+        }
+        info!("🔧 Профиль сборки {}: {} {} → {}", name, build.gradle_task, build.build_args.join(" "), build.output_dir);
+        Ok(Self { name: name.to_string(), version_suffix: profile.version_suffix })
+    }
+
+    /// Версия с суффиксом профиля; суффикс не дублируется, если уже есть
+    pub fn version(&self, version: &str) -> String {
+        match &self.version_suffix {
+            Some(suffix) if !version.ends_with(suffix.as_str()) => format!("{}{}", version, suffix),
+            _ => version.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parser::BuildProfileConfig;
+
+    #[test]
+    fn test_build_profile_overrides_build_section() {
+        let mut config = crate::config::parser::test_config();
+        let (task, output_dir) = (config.build.gradle_task.clone(), config.build.output_dir.clone());
+        config.build.profiles.insert(
+            "nightly".to_string(),
+            BuildProfileConfig {
+                args: Some(vec!["-Pchannel=nightly".to_string()]),
+                output_dir: Some("build/nightly".to_string()),
+                version_suffix: Some("-nightly".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let release = BuildProfile::select(&mut config.clone(), DEFAULT_PROFILE).unwrap();
+        assert_eq!(release.version("1.3.0"), "1.3.0");
+        assert!(BuildProfile::select(&mut config.clone(), "nigthly").unwrap_err().to_string().contains("доступны: nightly"));
+
+        let nightly = BuildProfile::select(&mut config, "nightly").unwrap();
+        assert_eq!(nightly.version("1.3.0"), "1.3.0-nightly");
+        assert_eq!(nightly.version("1.3.0-nightly"), "1.3.0-nightly");
+        assert_eq!(config.build.gradle_task, task);
+        assert_eq!(config.build.build_args, ["-Pchannel=nightly"]);
+        assert_ne!(config.build.output_dir, output_dir);
+        assert_eq!(config.build.output_dir, "build/nightly");
+    }
+}
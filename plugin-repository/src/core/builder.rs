@@ -5,14 +5,23 @@ use tokio::process::Command as AsyncCommand;
 use tokio::time::timeout;
 use tracing::{info, warn, debug, error};
 use indicatif::{ProgressBar, ProgressStyle};
-use crate::models::plugin::{PluginArtifact, BuildResult};
-use crate::config::parser::Config;
+use crate::models::plugin::{PluginArtifact, BuildResult, BuildEnvironment};
+use crate::config::parser::{default_build_info_path, BuildConfig, Config, PostProcessorConfig};
+use crate::core::build_env;
+use crate::core::disk_preflight;
+use crate::core::gradle_problems::{self, BuildProblem};
+use crate::core::gradle_wrapper::{GradleWrapper, WrapperTrust};
+use crate::core::mcp_workspace::{self, McpWorkspace};
+use crate::core::postprocess::{PostProcessorChain, ProcessContext};
+use crate::git::GitCommand;
+use crate::utils::clock::{system_clock, SharedClock};
 use sha2::{Sha256, Digest};
 
 /// Система сборки плагинов
 pub struct PluginBuilder {
     config: Config,
     project_root: PathBuf,
+    clock: SharedClock,
 }
 
 impl PluginBuilder {
@@ -21,10 +30,18 @@ impl PluginBuilder {
         Self {
             config,
             project_root,
+            clock: system_clock(),
         }
     }
 
+    /// Часы для меток времени сборки и артефакта
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Собирает плагин с указанной версией
+    #[tracing::instrument(name = "build", skip(self))]
     pub async fn build(&self, version: Option<String>, profile: &str) -> Result<BuildResult> {
         info!("🔨 Начало сборки плагина");
 
@@ -45,14 +62,53 @@ impl PluginBuilder {
                 success: false,
                 artifact: None,
                 metadata: None,
-                build_time: chrono::Utc::now(),
+                build_time: self.clock.now(),
                 logs,
                 errors,
+                environment: None,
             });
         }
 
-        // 3. Сборка
-        let mut artifact = match self.build_plugin(&project_type, profile, &mut logs, &mut errors).await {
+        // 2.1. Свободное место: длинная сборка не должна падать на середине из-за диска
+        if self.config.build.preflight.enabled {
+            let preflight = &self.config.build.preflight;
+            let locations = disk_preflight::locations(&self.project_root, &self.config.build.output_dir, preflight);
+            if let Err(e) = disk_preflight::check(&locations, preflight.min_free_inodes) {
+                let error_msg = format!("❌ {}", e);
+                error!("{}", error_msg);
+                errors.push(error_msg);
+                return Ok(BuildResult {
+                    success: false,
+                    artifact: None,
+                    metadata: None,
+                    build_time: self.clock.now(),
+                    logs,
+                    errors,
+                    environment: None,
+                });
+            }
+            logs.push("💾 Свободного места достаточно".to_string());
+        }
+
+        // 2.2. Окружение сборки снимается до сборки: git dirty-state не должен учитывать ее результаты
+        let environment = build_env::capture(&self.project_root, &project_type, &self.config.build.wrapper).await;
+        logs.push(format!(
+            "🧭 Окружение: {} {}, JDK {}, {}, коммит {}{}",
+            environment.os,
+            environment.arch,
+            environment.jdk.as_deref().unwrap_or("?"),
+            environment.build_tool.as_deref().unwrap_or("?"),
+            environment.git_commit.as_deref().map(|c| &c[..c.len().min(12)]).unwrap_or("?"),
+            if environment.git_dirty == Some(true) { " (есть незакоммиченные изменения)" } else { "" }
+        ));
+
+        // 3. Сборка: локально или в workspace MCP-сервера
+        let built = if self.config.build.mcp.enabled {
+            self.build_in_mcp_workspace(&project_type, profile, &environment, &mut logs, &mut errors).await
+        } else {
+            self.build_plugin(&project_type, profile, &mut logs, &mut errors).await
+        };
+        let mut artifact = match built {
             Ok(artifact) => {
                 logs.push("✅ Сборка завершена успешно".to_string());
                 Some(artifact)
@@ -84,6 +140,19 @@ impl PluginBuilder {
             }
         }
 
+        // 3.2. Пост-обработка артефакта (build.post_process)
+        if let Some(ref mut art) = artifact {
+            match self.post_process(art, &environment) {
+                Ok(steps) if steps > 0 => logs.push(format!("🧰 Пост-обработка выполнена ({} шагов)", steps)),
+                Ok(_) => {}
+                Err(e) => {
+                    let error_msg = format!("❌ Пост-обработка артефакта не удалась: {}", e);
+                    error!("{}", error_msg);
+                    errors.push(error_msg);
+                }
+            }
+        }
+
         // 4. Валидация артефакта
         if let Some(ref artifact) = artifact {
             if let Err(e) = self.validate_artifact(artifact).await {
@@ -94,14 +163,15 @@ impl PluginBuilder {
                     success: false,
                     artifact: Some(artifact.clone()),
                     metadata: None,
-                    build_time: chrono::Utc::now(),
+                    build_time: self.clock.now(),
                     logs,
                     errors,
+                    environment: Some(environment),
                 });
             }
         }
 
-        let build_time = chrono::Utc::now();
+        let build_time = self.clock.now();
         let duration = start_time.elapsed();
 
         logs.push(format!("⏱️ Время сборки: {:?}", duration));
@@ -115,6 +185,7 @@ impl PluginBuilder {
             build_time,
             logs,
             errors,
+            environment: Some(environment),
         })
     }
 
@@ -147,8 +218,8 @@ impl PluginBuilder {
         match project_type {
             ProjectType::Gradle => {
                 // Проверяем Gradle wrapper
-                if !self.project_root.join("gradlew").exists() {
-                    warn!("⚠️ Gradle wrapper не найден, будет использоваться системный gradle");
+                if !self.project_root.join("gradlew").exists() && self.config.build.wrapper.gradle_version.is_none() {
+                    warn!("⚠️ Gradle wrapper не найден, будет использоваться системный gradle (зафиксируйте версию в [build.wrapper] gradle_version)");
                 }
 
                 // Проверяем src/main/kotlin или src/main/java
@@ -212,11 +283,7 @@ impl PluginBuilder {
         info!("🔨 Запуск Gradle сборки с профилем: {}", profile);
         logs.push(format!("Запуск Gradle сборки: gradle {}", self.config.build.gradle_task));
 
-        let gradle_cmd = if self.project_root.join("gradlew").exists() {
-            "./gradlew"
-        } else {
-            "gradle"
-        };
+        let gradle_cmd = self.gradle_command().await?;
 
         let mut args: Vec<&str> = vec![&self.config.build.gradle_task];
         if !self.config.build.build_args.is_empty() {
@@ -225,10 +292,12 @@ impl PluginBuilder {
 
         let mut cmd = AsyncCommand::new(gradle_cmd);
         cmd.current_dir(&self.project_root)
-           .args(&args);
+           .args(&args)
+           .kill_on_drop(true);
 
         debug!("Выполняем команду: {:?}", cmd);
 
+        let started = std::time::SystemTime::now();
         let output = timeout(Duration::from_secs(300), cmd.output()).await
             .context("Таймаут сборки (5 минут)")?
             .context("Ошибка выполнения команды сборки")?;
@@ -244,19 +313,33 @@ impl PluginBuilder {
 
         if !stderr.is_empty() {
             for line in stderr.lines().take(10) {
-                if line.to_lowercase().contains("error") || line.to_lowercase().contains("failed") {
-                    errors.push(format!("❌ {}", line));
-                } else {
-                    logs.push(format!("⚠️ {}", line));
-                }
+                logs.push(format!("⚠️ {}", line));
             }
         }
 
+        if let Some(url) = gradle_problems::scan_url(&stdout).or_else(|| gradle_problems::scan_url(&stderr)) {
+            logs.push(format!("🔎 Build scan: {}", url));
+        }
+
         if !output.status.success() {
+            let causes = self.gradle_root_causes(&stdout, &stderr, started);
+            if causes.is_empty() {
+                errors.extend(
+                    stderr
+                        .lines()
+                        .filter(|l| l.to_lowercase().contains("error") || l.to_lowercase().contains("failed"))
+                        .take(gradle_problems::MAX_ROOT_CAUSES)
+                        .map(|l| format!("❌ {}", l)),
+                );
+            }
+            errors.extend(causes.iter().map(|c| format!("❌ {}", c)));
             return Err(anyhow::anyhow!(
                 "Gradle сборка завершилась с кодом {}: {}",
                 output.status,
-                stderr.lines().next().unwrap_or("нет вывода ошибок")
+                causes
+                    .first()
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|| stderr.lines().next().unwrap_or("нет вывода ошибок").to_string())
             ));
         }
 
@@ -264,6 +347,150 @@ impl PluginBuilder {
         self.find_artifact().await
     }
 
+    /// Сборка в workspace MCP-сервера: checkout текущего коммита, сборка там же
+    /// и загрузка нового ZIP в локальный output_dir
+    async fn build_in_mcp_workspace(
+        &self,
+        project_type: &ProjectType,
+        profile: &str,
+        environment: &BuildEnvironment,
+        logs: &mut Vec<String>,
+        errors: &mut Vec<String>,
+    ) -> Result<PluginArtifact> {
+        let mcp = &self.config.build.mcp;
+        let commit = environment
+            .git_commit
+            .clone()
+            .context("Сборка в MCP workspace собирает коммит, а текущий коммит не определен")?;
+        if environment.git_dirty == Some(true) {
+            warn!("⚠️ Незакоммиченные изменения не попадут в сборку на MCP-сервере");
+            logs.push("⚠️ Незакоммиченные изменения не попадут в сборку на MCP-сервере".to_string());
+        }
+        let remote = match &mcp.git_remote {
+            Some(remote) => remote.clone(),
+            None => self.origin_url().await?,
+        };
+
+        let workspace = McpWorkspace::new(mcp, &self.config.network.proxy)?;
+        info!("🌐 Сборка в MCP workspace {} ({})", mcp.url, workspace.path(""));
+        workspace.checkout(&remote, &commit).await?;
+        logs.push(format!("🔀 MCP workspace: {} на коммите {}", workspace.path(""), &commit[..commit.len().min(12)]));
+
+        let (program, args) = match project_type {
+            ProjectType::Gradle => {
+                let program = if self.project_root.join("gradlew").exists() { "./gradlew" } else { "gradle" };
+                let mut args = vec![self.config.build.gradle_task.clone()];
+                args.extend(self.config.build.build_args.iter().cloned());
+                (program, args)
+            }
+            ProjectType::Maven => {
+                let mut args = vec!["package".to_string(), "-DskipTests".to_string()];
+                if profile != "release" {
+                    args.extend(["-P".to_string(), profile.to_string()]);
+                }
+                ("mvn", args)
+            }
+        };
+        logs.push(format!("Запуск сборки в MCP workspace: {} {}", program, args.join(" ")));
+
+        let output_dir = workspace.path(&self.config.build.output_dir);
+        let before = workspace.list_zips(&output_dir).await?;
+        let output = workspace.exec(program, &args, None).await?;
+        logs.push(format!("⏱️ Сборка на сервере заняла {:.1} с", output.duration_ms as f64 / 1000.0));
+        for line in output.stdout.lines().take(20) {
+            logs.push(format!("📝 {}", line));
+        }
+        for line in output.stderr.lines().take(10) {
+            logs.push(format!("⚠️ {}", line));
+        }
+        if output.truncated {
+            logs.push("✂️ Вывод сборки на сервере обрезан".to_string());
+        }
+
+        if !output.success() {
+            let mut problems = gradle_problems::parse_output(&output.stdout);
+            problems.extend(gradle_problems::parse_output(&output.stderr));
+            let causes = gradle_problems::root_causes(problems, gradle_problems::MAX_ROOT_CAUSES);
+            errors.extend(causes.iter().map(|c| format!("❌ {}", c)));
+            return Err(anyhow::anyhow!(
+                "Сборка в MCP workspace завершилась с кодом {:?}: {}",
+                output.exit_code,
+                causes
+                    .first()
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|| output.stderr.lines().next().unwrap_or("нет вывода ошибок").to_string())
+            ));
+        }
+
+        let after = workspace.list_zips(&output_dir).await?;
+        let name = mcp_workspace::built_zips(&before, &after)
+            .pop()
+            .with_context(|| format!("Сборка в MCP workspace не создала ZIP в {}", output_dir))?;
+        let data = workspace.download(&format!("{}/{}", output_dir, name)).await?;
+
+        let local_dir = self.project_root.join(&self.config.build.output_dir);
+        std::fs::create_dir_all(&local_dir)
+            .with_context(|| format!("Не удалось создать {}", local_dir.display()))?;
+        let local_path = local_dir.join(&name);
+        std::fs::write(&local_path, &data)
+            .with_context(|| format!("Не удалось сохранить артефакт {}", local_path.display()))?;
+        logs.push(format!("📥 Артефакт скачан из MCP workspace: {} ({} bytes)", name, data.len()));
+
+        self.artifact_from_path(&local_path)
+    }
+
+    /// URL origin локального репозитория — источник коммита для MCP workspace
+    async fn origin_url(&self) -> Result<String> {
+        let url = GitCommand::new(&self.project_root)
+            .args(["remote", "get-url", "origin"])
+            .run()
+            .await
+            .context("Не удалось определить URL origin: задайте [build.mcp] git_remote")?;
+        Ok(url.trim().to_string())
+    }
+
+    /// Команда Gradle: проверенный wrapper, скачанная зафиксированная версия или системный gradle
+    async fn gradle_command(&self) -> Result<PathBuf> {
+        let wrapper_config = &self.config.build.wrapper;
+        let wrapper = GradleWrapper::new(wrapper_config, &self.config.network.proxy, &self.project_root);
+
+        if self.project_root.join("gradlew").exists() {
+            if wrapper_config.validate {
+                match wrapper.validate().await.context("Проверка Gradle wrapper не пройдена")? {
+                    WrapperTrust::Official { version } => info!("🔐 Gradle wrapper совпадает с официальным (Gradle {})", version),
+                    WrapperTrust::Allowed => info!("🔐 Gradle wrapper разрешен через [build.wrapper] allowed_checksums"),
+                }
+            }
+            return Ok(PathBuf::from("./gradlew"));
+        }
+
+        match &wrapper_config.gradle_version {
+            Some(version) => wrapper.provision(version).await,
+            None => Ok(PathBuf::from("gradle")),
+        }
+    }
+
+    /// Первопричины падения Gradle: ошибки компиляторов и `What went wrong` из вывода,
+    /// а также отчет Problems API, если он обновлен этой сборкой
+    fn gradle_root_causes(&self, stdout: &str, stderr: &str, started: std::time::SystemTime) -> Vec<BuildProblem> {
+        let mut problems = gradle_problems::parse_output(stdout);
+        problems.extend(gradle_problems::parse_output(stderr));
+
+        let report = self.project_root.join(gradle_problems::PROBLEMS_REPORT);
+        let fresh = std::fs::metadata(&report)
+            .and_then(|m| m.modified())
+            .is_ok_and(|modified| modified >= started);
+        if fresh {
+            match std::fs::read_to_string(&report).map_err(anyhow::Error::from)
+                .and_then(|html| gradle_problems::parse_problems_report(&html))
+            {
+                Ok(reported) => problems.extend(reported),
+                Err(e) => warn!("Не удалось разобрать {}: {}", report.display(), e),
+            }
+        }
+        gradle_problems::root_causes(problems, gradle_problems::MAX_ROOT_CAUSES)
+    }
+
     /// Сборка Maven проекта
     async fn build_maven(
         &self,
@@ -276,7 +503,8 @@ impl PluginBuilder {
 
         let mut cmd = AsyncCommand::new("mvn");
         cmd.current_dir(&self.project_root)
-           .args(&["package", "-DskipTests"]);
+           .args(&["package", "-DskipTests"])
+           .kill_on_drop(true);
 
         if profile != "release" {
             cmd.arg("-P").arg(profile);
@@ -357,7 +585,11 @@ impl PluginBuilder {
             std::fs::metadata(path).and_then(|m| m.modified()).unwrap_or(std::time::UNIX_EPOCH)
         });
 
-        let artifact_path = zip_files.last().unwrap();
+        self.artifact_from_path(zip_files.last().unwrap())
+    }
+
+    /// Описание артефакта по файлу: размер, SHA256 и версия из имени
+    fn artifact_from_path(&self, artifact_path: &Path) -> Result<PluginArtifact> {
         let file_name = artifact_path.file_name()
             .ok_or_else(|| anyhow::anyhow!("Неверное имя файла"))?
             .to_string_lossy()
@@ -376,15 +608,50 @@ impl PluginBuilder {
             .unwrap_or_else(|| "unknown".to_string());
 
         Ok(PluginArtifact {
-            file_path: artifact_path.clone(),
+            file_path: artifact_path.to_path_buf(),
             file_name,
             file_size,
             checksum_sha256: checksum,
             version,
-            build_time: chrono::Utc::now(),
+            build_time: self.clock.now(),
         })
     }
 
+    /// Применяет цепочку пост-обработки и обновляет размер/чексумму артефакта; возвращает число шагов
+    fn post_process(&self, artifact: &mut PluginArtifact, environment: &BuildEnvironment) -> Result<usize> {
+        let chain = PostProcessorChain::from_config(&Self::post_process_steps(&self.config.build));
+        if chain.is_empty() {
+            return Ok(0);
+        }
+
+        let ctx = ProcessContext {
+            project_root: self.project_root.clone(),
+            plugin_id: self.config.project.id.clone(),
+            version: artifact.version.clone(),
+            environment: Some(environment.clone()),
+        };
+        let applied = chain.apply(&artifact.file_path, &ctx)?;
+
+        artifact.file_size = std::fs::metadata(&artifact.file_path)?.len();
+        artifact.checksum_sha256 = self.calculate_checksum(&artifact.file_path)?;
+        Ok(applied.len())
+    }
+
+    /// Шаги пост-обработки: build-info.json добавляется первым шагом, если не настроен явно,
+    /// чтобы последующие шаги (strip-timestamps, deterministic-order) нормализовали и его
+    fn post_process_steps(build: &BuildConfig) -> Vec<PostProcessorConfig> {
+        let mut steps = build.post_process.clone();
+        let has_build_info = steps.iter().any(|s| matches!(s, PostProcessorConfig::BuildInfo { .. }));
+        if build.embed_build_info && !has_build_info {
+            steps.insert(0, PostProcessorConfig::BuildInfo { path: default_build_info_path() });
+        }
+        // Манифест целостности считается по окончательному содержимому архива
+        let (mut tail, mut steps): (Vec<_>, Vec<_>) =
+            steps.into_iter().partition(|s| matches!(s, PostProcessorConfig::Integrity { .. }));
+        steps.append(&mut tail);
+        steps
+    }
+
     /// Вычисляет SHA256 checksum файла
     fn calculate_checksum(&self, file_path: &Path) -> Result<String> {
         let mut file = std::fs::File::open(file_path)?;
@@ -0,0 +1,271 @@
+use regex::Regex;
+use tracing::warn;
+
+use crate::config::parser::ChangeNotesConfig;
+
+/// Результат подготовки change-notes для Marketplace/updatePlugins.xml
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeNotes {
+    pub html: String,
+    pub truncated: bool,
+    /// Теги, вырезанные при санитизации (не входят в allowed_tags)
+    pub removed_tags: Vec<String>,
+}
+
+/// Блок верхнего уровня: усечение идет только по границам блоков/пунктов списка,
+/// поэтому результат всегда остается корректным HTML
+#[derive(Debug, Clone)]
+enum Block {
+    Heading(String),
+    Paragraph(String),
+    List(Vec<String>),
+}
+
+impl Block {
+    fn render(&self) -> String {
+        match self {
+            Block::Heading(text) => format!("<h3>{}</h3>", text),
+            Block::Paragraph(text) => format!("<p>{}</p>", text),
+            Block::List(items) => Self::render_list(items),
+        }
+    }
+
+    fn render_list(items: &[String]) -> String {
+        let body: String = items.iter().map(|i| format!("<li>{}</li>", i)).collect();
+        format!("<ul>{}</ul>", body)
+    }
+}
+
+/// Конвертер release notes (Markdown) в HTML change-notes с бюджетом длины
+pub struct ChangeNotesFormatter {
+    config: ChangeNotesConfig,
+}
+
+impl ChangeNotesFormatter {
+    pub fn new(config: ChangeNotesConfig) -> Self {
+        Self { config }
+    }
+
+    /// Markdown -> санитизированный HTML, усеченный до `max_length` символов
+    pub fn format(&self, markdown: &str) -> ChangeNotes {
+        let mut removed_tags = Vec::new();
+        let blocks: Vec<Block> = parse_blocks(markdown)
+            .into_iter()
+            .map(|block| self.sanitize_block(block, &mut removed_tags))
+            .collect();
+
+        let full: String = blocks.iter().map(Block::render).collect();
+        if full.chars().count() <= self.config.max_length {
+            return ChangeNotes { html: full, truncated: false, removed_tags };
+        }
+
+        let suffix = self.truncation_suffix();
+        let budget = self.config.max_length.saturating_sub(suffix.chars().count());
+        let mut html = String::new();
+        let mut used = 0;
+
+        'blocks: for block in &blocks {
+            match block {
+                Block::List(items) => {
+                    // Список можно обрезать по пунктам
+                    let mut taken = Vec::new();
+                    for item in items {
+                        let mut candidate = taken.clone();
+                        candidate.push(item.clone());
+                        if used + Block::render_list(&candidate).chars().count() > budget {
+                            break;
+                        }
+                        taken = candidate;
+                    }
+                    if !taken.is_empty() {
+                        let rendered = Block::render_list(&taken);
+                        used += rendered.chars().count();
+                        html.push_str(&rendered);
+                    }
+                    if taken.len() < items.len() {
+                        break 'blocks;
+                    }
+                }
+                _ => {
+                    let rendered = block.render();
+                    let len = rendered.chars().count();
+                    if used + len > budget {
+                        break 'blocks;
+                    }
+                    used += len;
+                    html.push_str(&rendered);
+                }
+            }
+        }
+
+        html.push_str(&suffix);
+        ChangeNotes { html, truncated: true, removed_tags }
+    }
+
+    /// Проверяет, что HTML содержит только разрешенные теги; возвращает список нарушений
+    pub fn validate_tags(&self, html: &str) -> Vec<String> {
+        let mut invalid: Vec<String> = tag_regex()
+            .captures_iter(html)
+            .map(|c| c[1].to_lowercase())
+            .filter(|tag| !self.is_allowed(tag))
+            .collect();
+        invalid.sort();
+        invalid.dedup();
+        invalid
+    }
+
+    fn is_allowed(&self, tag: &str) -> bool {
+        self.config.allowed_tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    fn truncation_suffix(&self) -> String {
+        match &self.config.changelog_url {
+            Some(url) => {
+                let url = escape_html(url);
+                format!("<p>…full changelog at <a href=\"{}\">{}</a></p>", url, url)
+            }
+            None => "<p>…</p>".to_string(),
+        }
+    }
+
+    /// Разворачивает запрещенные теги (оставляя текст) и запоминает их
+    fn sanitize_block(&self, block: Block, removed: &mut Vec<String>) -> Block {
+        let mut clean = |text: String| -> String {
+            tag_regex()
+                .replace_all(&text, |caps: &regex::Captures| {
+                    let tag = caps[1].to_lowercase();
+                    if self.is_allowed(&tag) {
+                        caps[0].to_string()
+                    } else {
+                        if !removed.contains(&tag) {
+                            warn!("Тег <{}> не разрешен в change-notes и будет удален", tag);
+                            removed.push(tag);
+                        }
+                        String::new()
+                    }
+                })
+                .to_string()
+        };
+
+        match block {
+            Block::Heading(text) if !self.is_allowed("h3") => Block::Paragraph(clean(format!("<b>{}</b>", text))),
+            Block::Heading(text) => Block::Heading(clean(text)),
+            Block::Paragraph(text) => Block::Paragraph(clean(text)),
+            Block::List(items) if !self.is_allowed("ul") || !self.is_allowed("li") => {
+                Block::Paragraph(clean(items.into_iter().map(|i| format!("• {}", i)).collect::<Vec<_>>().join("<br/>")))
+            }
+            Block::List(items) => Block::List(items.into_iter().map(&mut clean).collect()),
+        }
+    }
+}
+
+fn tag_regex() -> Regex {
+    Regex::new(r"</?([a-zA-Z][a-zA-Z0-9]*)\b[^>]*>").unwrap()
+}
+
+/// Разбор упрощенного Markdown: заголовки, списки, абзацы
+fn parse_blocks(markdown: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut list: Vec<String> = Vec::new();
+
+    fn flush(blocks: &mut Vec<Block>, paragraph: &mut Vec<String>, list: &mut Vec<String>) {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(paragraph.join(" ")));
+            paragraph.clear();
+        }
+        if !list.is_empty() {
+            blocks.push(Block::List(std::mem::take(list)));
+        }
+    }
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            flush(&mut blocks, &mut paragraph, &mut list);
+        } else if let Some(heading) = trimmed.strip_prefix('#') {
+            flush(&mut blocks, &mut paragraph, &mut list);
+            blocks.push(Block::Heading(inline_markdown(heading.trim_start_matches('#').trim())));
+        } else if let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+            if !paragraph.is_empty() {
+                flush(&mut blocks, &mut paragraph, &mut list);
+            }
+            list.push(inline_markdown(item.trim()));
+        } else {
+            if !list.is_empty() {
+                flush(&mut blocks, &mut paragraph, &mut list);
+            }
+            paragraph.push(inline_markdown(trimmed));
+        }
+    }
+    flush(&mut blocks, &mut paragraph, &mut list);
+
+    blocks
+}
+
+/// Экранирование и inline-разметка: **bold**, *italic*, `code`, [text](url)
+fn inline_markdown(text: &str) -> String {
+    let escaped = escape_html(text);
+    let code = Regex::new(r"`([^`]+)`").unwrap();
+    let bold = Regex::new(r"\*\*([^*]+)\*\*").unwrap();
+    let italic = Regex::new(r"\*([^*]+)\*").unwrap();
+    let link = Regex::new(r"\[([^\]]+)\]\((https?://[^)\s]+)\)").unwrap();
+
+    let html = code.replace_all(&escaped, "<code>$1</code>");
+    let html = bold.replace_all(&html, "<b>$1</b>");
+    let html = italic.replace_all(&html, "<i>$1</i>");
+    link.replace_all(&html, "<a href=\"$2\">$1</a>").to_string()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formatter(max_length: usize) -> ChangeNotesFormatter {
+        ChangeNotesFormatter::new(ChangeNotesConfig {
+            max_length,
+            changelog_url: Some("https://example.com/CHANGELOG.md".to_string()),
+            ..ChangeNotesConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_markdown_to_html() {
+        let notes = formatter(10_000).format("# Ride 1.2.0\n\nНовый **чат**\n\n## Highlights\n- Быстрее `index`\n- <script>x</script>\n");
+        assert!(!notes.truncated);
+        assert_eq!(
+            notes.html,
+            "<h3>Ride 1.2.0</h3><p>Новый <b>чат</b></p><h3>Highlights</h3><ul><li>Быстрее <code>index</code></li><li>&lt;script&gt;x&lt;/script&gt;</li></ul>"
+        );
+        assert!(formatter(10_000).validate_tags(&notes.html).is_empty());
+    }
+
+    #[test]
+    fn test_truncation_keeps_whole_items_and_adds_link() {
+        let markdown = (1..=50).map(|i| format!("- change number {}", i)).collect::<Vec<_>>().join("\n");
+        let notes = formatter(300).format(&markdown);
+        assert!(notes.truncated);
+        assert!(notes.html.chars().count() <= 300);
+        assert!(notes.html.starts_with("<ul><li>change number 1</li>"));
+        assert!(notes.html.contains("</ul><p>…full changelog at <a href=\"https://example.com/CHANGELOG.md\">"));
+    }
+
+    #[test]
+    fn test_disallowed_tags_are_unwrapped() {
+        let f = ChangeNotesFormatter::new(ChangeNotesConfig {
+            allowed_tags: vec!["p".to_string(), "ul".to_string(), "li".to_string()],
+            ..ChangeNotesConfig::default()
+        });
+        let notes = f.format("# Title\n\n- **bold** item");
+        assert_eq!(notes.html, "<p>Title</p><ul><li>bold item</li></ul>");
+        assert_eq!(notes.removed_tags, vec!["b".to_string()]);
+        assert_eq!(f.validate_tags("<p><script></script></p>"), vec!["script".to_string()]);
+    }
+}
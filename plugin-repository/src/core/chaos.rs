@@ -0,0 +1,170 @@
+//! Режим хаоса для CI: случайные сбои на границах этапов (таймаут LLM, отказ rename по SFTP,
+//! отклоненный git push), чтобы проверять откат и возобновление пайплайна.
+//!
+//! Включается скрытым флагом `--chaos` и только при `DEPLOY_PLUGIN_CHAOS=1`: случайно
+//! оставленный в скрипте флаг не сломает настоящий релиз.
+
+use anyhow::{Context, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+use tracing::warn;
+
+/// Переменная, без которой `--chaos` отклоняется
+pub const CHAOS_ENV: &str = "DEPLOY_PLUGIN_CHAOS";
+/// Seed генератора для воспроизведения прогона
+pub const CHAOS_SEED_ENV: &str = "DEPLOY_PLUGIN_CHAOS_SEED";
+
+/// Место, где может быть внедрен сбой
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChaosPoint {
+    /// Запрос к LLM
+    Llm,
+    /// Замена XML репозитория через rename (SFTP и локально)
+    SftpRename,
+    /// Push тега релиза
+    GitPush,
+}
+
+impl ChaosPoint {
+    const ALL: [ChaosPoint; 3] = [ChaosPoint::Llm, ChaosPoint::SftpRename, ChaosPoint::GitPush];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ChaosPoint::Llm => "llm",
+            ChaosPoint::SftpRename => "sftp-rename",
+            ChaosPoint::GitPush => "git-push",
+        }
+    }
+
+    fn failure(self) -> &'static str {
+        match self {
+            ChaosPoint::Llm => "LLM не ответил за отведенное время",
+            ChaosPoint::SftpRename => "Сервер отклонил rename временного XML",
+            ChaosPoint::GitPush => "Удаленный репозиторий отклонил push",
+        }
+    }
+}
+
+impl fmt::Display for ChaosPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Внедренный сбой; по тексту его легко отличить от настоящего
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{} (сбой внедрен --chaos: {point})", point.failure())]
+pub struct ChaosFailure {
+    pub point: ChaosPoint,
+}
+
+/// Вероятности сбоев по точкам и генератор
+pub struct Chaos {
+    rates: Vec<(ChaosPoint, f64)>,
+    rng: Mutex<StdRng>,
+}
+
+impl Chaos {
+    /// Разбор спецификации вида `llm=0.5,sftp-rename,git-push`; точка без вероятности
+    /// отказывает всегда, `all` включает все точки
+    pub fn parse(spec: &str, seed: u64) -> Result<Self> {
+        let mut rates = Vec::new();
+        for item in spec.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            let (name, rate) = match item.split_once('=') {
+                Some((name, rate)) => {
+                    let rate: f64 = rate
+                        .trim()
+                        .parse()
+                        .with_context(|| format!("Некорректная вероятность сбоя '{}' в --chaos", item))?;
+                    (name.trim(), rate)
+                }
+                None => (item, 1.0),
+            };
+            if !(0.0..=1.0).contains(&rate) {
+                anyhow::bail!("Вероятность сбоя '{}' в --chaos должна быть от 0 до 1", item);
+            }
+            let points: Vec<ChaosPoint> = match name {
+                "all" => ChaosPoint::ALL.to_vec(),
+                _ => vec![ChaosPoint::ALL.into_iter().find(|p| p.name() == name).with_context(|| {
+                    format!("Неизвестная точка сбоя '{}' в --chaos (llm, sftp-rename, git-push, all)", name)
+                })?],
+            };
+            for point in points {
+                rates.retain(|(p, _)| *p != point);
+                rates.push((point, rate));
+            }
+        }
+        if rates.is_empty() {
+            anyhow::bail!("--chaos не задает ни одной точки сбоя");
+        }
+        Ok(Self { rates, rng: Mutex::new(StdRng::seed_from_u64(seed)) })
+    }
+
+    /// Решает, отказать ли в точке `point`
+    pub fn check(&self, point: ChaosPoint) -> std::result::Result<(), ChaosFailure> {
+        let Some((_, rate)) = self.rates.iter().find(|(p, _)| *p == point) else {
+            return Ok(());
+        };
+        if self.rng.lock().unwrap().gen_bool(*rate) {
+            warn!("🐒 Chaos: сбой в точке {}", point);
+            return Err(ChaosFailure { point });
+        }
+        Ok(())
+    }
+}
+
+static CHAOS: OnceLock<Chaos> = OnceLock::new();
+
+/// Включает режим хаоса для процесса; без `DEPLOY_PLUGIN_CHAOS=1` флаг отклоняется
+pub fn init(spec: &str) -> Result<()> {
+    if std::env::var(CHAOS_ENV).as_deref() != Ok("1") {
+        anyhow::bail!("--chaos — тестовый режим, он работает только при {}=1", CHAOS_ENV);
+    }
+    let seed = match std::env::var(CHAOS_SEED_ENV) {
+        Ok(seed) => seed.trim().parse().with_context(|| format!("Некорректный {}: {}", CHAOS_SEED_ENV, seed))?,
+        Err(_) => rand::thread_rng().gen(),
+    };
+    let chaos = Chaos::parse(spec, seed)?;
+    let points: Vec<String> = chaos.rates.iter().map(|(p, rate)| format!("{}={}", p, rate)).collect();
+    warn!("🐒 Режим хаоса: {} (воспроизведение: {}={})", points.join(", "), CHAOS_SEED_ENV, seed);
+    CHAOS.set(chaos).map_err(|_| anyhow::anyhow!("Режим хаоса уже включен"))
+}
+
+/// Граница этапа: в режиме хаоса может вернуть внедренный сбой, иначе ничего не делает
+pub fn inject(point: ChaosPoint) -> std::result::Result<(), ChaosFailure> {
+    match CHAOS.get() {
+        Some(chaos) => chaos.check(point),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chaos_spec_and_seeded_injection() {
+        let always = Chaos::parse("llm, git-push=1", 7).unwrap();
+        assert!(always.check(ChaosPoint::Llm).is_err());
+        let err = always.check(ChaosPoint::GitPush).unwrap_err();
+        assert_eq!(err.to_string(), "Удаленный репозиторий отклонил push (сбой внедрен --chaos: git-push)");
+        assert!(always.check(ChaosPoint::SftpRename).is_ok());
+
+        let never = Chaos::parse("all=0", 7).unwrap();
+        assert!(ChaosPoint::ALL.iter().all(|p| never.check(*p).is_ok()));
+
+        // Один seed — одна последовательность сбоев
+        let run = |seed| {
+            let chaos = Chaos::parse("sftp-rename=0.5", seed).unwrap();
+            (0..32).map(|_| chaos.check(ChaosPoint::SftpRename).is_err()).collect::<Vec<_>>()
+        };
+        assert_eq!(run(42), run(42));
+        assert!(run(42).contains(&true) && run(42).contains(&false));
+
+        assert!(Chaos::parse("dns", 1).is_err());
+        assert!(Chaos::parse("llm=2", 1).is_err());
+        assert!(Chaos::parse(" , ", 1).is_err());
+    }
+}
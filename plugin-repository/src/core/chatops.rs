@@ -0,0 +1,251 @@
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::config::parser::{ChatOpsConfig, ProxyConfig};
+use crate::utils::network::NetworkUtils;
+
+/// Long polling `getUpdates`: сколько секунд Telegram держит запрос открытым
+pub const POLL_TIMEOUT_SECS: u64 = 30;
+
+/// Команда релиза из чата: `/release 1.2.0`, `/release auto`, `/release 1.2.0 dry-run`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseCommand {
+    /// None — версия подбирается автоматически
+    pub version: Option<String>,
+    pub dry_run: bool,
+}
+
+impl ReleaseCommand {
+    pub fn describe(&self) -> String {
+        format!(
+            "{}{}",
+            self.version.as_deref().map(|v| format!("v{}", v)).unwrap_or_else(|| "авто-версии".to_string()),
+            if self.dry_run { " (dry-run)" } else { "" }
+        )
+    }
+}
+
+/// Разбирает `/release`; None — сообщение не является командой релиза (в том числе адресованной другому боту),
+/// `Some(Err)` — команда с ошибкой, текст ошибки отправляется в чат
+pub fn parse_release_command(text: &str, bot_username: Option<&str>) -> Option<Result<ReleaseCommand, String>> {
+    let mut words = text.split_whitespace();
+    let command = words.next()?;
+    let (name, addressee) = match command.split_once('@') {
+        Some((name, addressee)) => (name, Some(addressee)),
+        None => (command, None),
+    };
+    if name != "/release" {
+        return None;
+    }
+    if let (Some(addressee), Some(bot)) = (addressee, bot_username) {
+        if !addressee.eq_ignore_ascii_case(bot) {
+            return None;
+        }
+    }
+
+    const USAGE: &str = "Использование: /release <x.y.z|auto> [dry-run]";
+    let mut release = ReleaseCommand { version: None, dry_run: false };
+    let mut version_set = false;
+    for word in words {
+        match word {
+            "dry-run" | "--dry-run" => release.dry_run = true,
+            "auto" if !version_set => version_set = true,
+            version if !version_set => {
+                let version = version.trim_start_matches('v');
+                if semver::Version::parse(version).is_err() {
+                    return Some(Err(format!("Некорректная версия '{}'. {}", version, USAGE)));
+                }
+                release.version = Some(version.to_string());
+                version_set = true;
+            }
+            other => return Some(Err(format!("Лишний аргумент '{}'. {}", other, USAGE))),
+        }
+    }
+    if !version_set {
+        return Some(Err(USAGE.to_string()));
+    }
+    Some(Ok(release))
+}
+
+/// Данные кнопки подтверждения: `release:<nonce>:yes|no`
+pub fn confirmation_data(nonce: &str, confirmed: bool) -> String {
+    format!("release:{}:{}", nonce, if confirmed { "yes" } else { "no" })
+}
+
+/// Разбирает данные кнопки подтверждения: nonce и решение
+pub fn parse_confirmation(data: &str) -> Option<(&str, bool)> {
+    let rest = data.strip_prefix("release:")?;
+    let (nonce, answer) = rest.rsplit_once(':')?;
+    match answer {
+        "yes" => Some((nonce, true)),
+        "no" => Some((nonce, false)),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    ok: bool,
+    result: Option<T>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Update {
+    pub update_id: i64,
+    pub message: Option<Message>,
+    pub callback_query: Option<CallbackQuery>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Message {
+    pub message_id: i64,
+    pub chat: Chat,
+    pub from: Option<User>,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Chat {
+    pub id: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct User {
+    pub id: i64,
+    pub username: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CallbackQuery {
+    pub id: String,
+    pub from: User,
+    pub message: Option<Message>,
+    pub data: Option<String>,
+}
+
+/// Кнопка под сообщением: текст и данные, которые вернутся в callback_query
+pub type Button = (String, String);
+
+/// Клиент Telegram Bot API
+#[derive(Clone)]
+pub struct TelegramClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl TelegramClient {
+    pub fn new(config: &ChatOpsConfig, proxy: &ProxyConfig) -> Result<Self> {
+        let token = config.bot_token.as_deref().unwrap_or_default().trim();
+        Ok(Self {
+            client: NetworkUtils::http_client(proxy, Duration::from_secs(POLL_TIMEOUT_SECS + 15))?,
+            base_url: format!("{}/bot{}", config.api_url.trim_end_matches('/'), token),
+        })
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, body: serde_json::Value) -> Result<T> {
+        let response: ApiResponse<T> = self
+            .client
+            .post(format!("{}/{}", self.base_url, method))
+            .json(&body)
+            .send()
+            .await
+            // Токен — часть URL: в ошибку попадает только имя метода
+            .map_err(|e| anyhow::anyhow!("Запрос Telegram {} не удался: {}", method, e.without_url()))?
+            .json()
+            .await
+            .with_context(|| format!("Некорректный ответ Telegram {}", method))?;
+        if !response.ok {
+            anyhow::bail!(
+                "Telegram {} вернул ошибку: {}",
+                method,
+                response.description.unwrap_or_else(|| "без описания".to_string())
+            );
+        }
+        response.result.with_context(|| format!("Пустой ответ Telegram {}", method))
+    }
+
+    /// Имя бота для разбора `/release@bot`
+    pub async fn username(&self) -> Result<Option<String>> {
+        let me: User = self.call("getMe", serde_json::json!({})).await?;
+        Ok(me.username)
+    }
+
+    pub async fn get_updates(&self, offset: i64) -> Result<Vec<Update>> {
+        self.call(
+            "getUpdates",
+            serde_json::json!({
+                "offset": offset,
+                "timeout": POLL_TIMEOUT_SECS,
+                "allowed_updates": ["message", "callback_query"],
+            }),
+        )
+        .await
+    }
+
+    /// Сообщение в ветку (ответом на `reply_to`) с необязательными кнопками; возвращает id сообщения
+    pub async fn send_message(&self, chat_id: i64, reply_to: Option<i64>, text: &str, buttons: &[Button]) -> Result<i64> {
+        let mut body = serde_json::json!({ "chat_id": chat_id, "text": text });
+        if let Some(reply_to) = reply_to {
+            body["reply_to_message_id"] = reply_to.into();
+            body["allow_sending_without_reply"] = true.into();
+        }
+        if !buttons.is_empty() {
+            body["reply_markup"] = keyboard(buttons);
+        }
+        let message: Message = self.call("sendMessage", body).await?;
+        Ok(message.message_id)
+    }
+
+    /// Заменяет текст сообщения и убирает кнопки
+    pub async fn edit_message(&self, chat_id: i64, message_id: i64, text: &str) -> Result<()> {
+        let body = serde_json::json!({ "chat_id": chat_id, "message_id": message_id, "text": text });
+        self.call::<serde_json::Value>("editMessageText", body).await.map(|_| ())
+    }
+
+    pub async fn answer_callback(&self, callback_id: &str, text: &str) -> Result<()> {
+        let body = serde_json::json!({ "callback_query_id": callback_id, "text": text });
+        self.call::<bool>("answerCallbackQuery", body).await.map(|_| ())
+    }
+}
+
+fn keyboard(buttons: &[Button]) -> serde_json::Value {
+    let row: Vec<serde_json::Value> = buttons
+        .iter()
+        .map(|(text, data)| serde_json::json!({ "text": text, "callback_data": data }))
+        .collect();
+    serde_json::json!({ "inline_keyboard": [row] })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_release_command() {
+        let release = |version: Option<&str>, dry_run| ReleaseCommand { version: version.map(str::to_string), dry_run };
+
+        assert_eq!(parse_release_command("/release 1.2.0", None), Some(Ok(release(Some("1.2.0"), false))));
+        assert_eq!(
+            parse_release_command("/release@ride_bot v1.3.0-rc.1 dry-run", Some("Ride_Bot")),
+            Some(Ok(release(Some("1.3.0-rc.1"), true)))
+        );
+        assert_eq!(parse_release_command("/release auto", None), Some(Ok(release(None, false))));
+        assert_eq!(parse_release_command("/release@other_bot 1.2.0", Some("ride_bot")), None);
+        assert_eq!(parse_release_command("release 1.2.0", None), None);
+        assert_eq!(parse_release_command("/releases", None), None);
+
+        assert!(parse_release_command("/release", None).unwrap().is_err());
+        assert!(parse_release_command("/release 1.2", None).unwrap().unwrap_err().contains("Некорректная версия"));
+        assert!(parse_release_command("/release 1.2.0 1.3.0", None).unwrap().is_err());
+
+        assert_eq!(parse_confirmation(&confirmation_data("a1b2", true)), Some(("a1b2", true)));
+        assert_eq!(parse_confirmation(&confirmation_data("a1b2", false)), Some(("a1b2", false)));
+        assert_eq!(parse_confirmation("release:a1b2:maybe"), None);
+
+        assert_eq!(release(Some("1.2.0"), true).describe(), "v1.2.0 (dry-run)");
+        assert_eq!(release(None, false).describe(), "авто-версии");
+    }
+}
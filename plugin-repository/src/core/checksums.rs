@@ -0,0 +1,207 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Индекс контрольных сумм в корне репозитория
+pub const SUMS_FILE: &str = "SHA256SUMS";
+/// Отделенная подпись индекса
+pub const SIG_FILE: &str = "SHA256SUMS.sig";
+
+/// Индекс SHA256SUMS в формате `sha256sum`: `<hash>  <путь от корня репозитория>`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChecksumIndex {
+    entries: BTreeMap<String, String>,
+}
+
+impl ChecksumIndex {
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut entries = BTreeMap::new();
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            // Допускается бинарный маркер `*` перед именем файла
+            let (hash, path) = line
+                .split_once(char::is_whitespace)
+                .map(|(h, p)| (h, p.trim_start().trim_start_matches('*')))
+                .filter(|(h, p)| h.len() == 64 && h.chars().all(|c| c.is_ascii_hexdigit()) && !p.is_empty())
+                .ok_or_else(|| anyhow::anyhow!("Некорректная строка {} в {}: {}", index + 1, SUMS_FILE, line))?;
+            entries.insert(path.to_string(), hash.to_lowercase());
+        }
+        Ok(Self { entries })
+    }
+
+    /// Добавляет или обновляет сумму файла
+    pub fn upsert(&mut self, path: &str, sha256: &str) {
+        self.entries.insert(path.to_string(), sha256.to_lowercase());
+    }
+
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.entries.get(path).map(String::as_str)
+    }
+
+    pub fn render(&self) -> String {
+        self.entries.iter().map(|(path, hash)| format!("{}  {}\n", hash, path)).collect()
+    }
+}
+
+/// Подписывает индекс внешней командой (`{input}` — файл индекса, `{output}` — файл подписи)
+pub fn sign(content: &str, command: &str) -> Result<Vec<u8>> {
+    sign_detached(content.as_bytes(), SUMS_FILE, command)
+}
+
+/// Создает отделенную подпись `content`, сохраненного во временный файл `file_name`
+pub fn sign_detached(content: &[u8], file_name: &str, command: &str) -> Result<Vec<u8>> {
+    let dir = tempfile::tempdir().context("Не удалось создать временную директорию для подписи")?;
+    let input = dir.path().join(file_name);
+    let output = dir.path().join(format!("{}.sig", file_name));
+    std::fs::write(&input, content).with_context(|| format!("Не удалось записать {} для подписи", file_name))?;
+
+    let command = shell_command(command, &[("input", &input), ("output", &output)]);
+    let status = Command::new("sh")
+        .args(["-c", &command])
+        .status()
+        .with_context(|| format!("Не удалось запустить команду подписи: {}", command))?;
+    if !status.success() {
+        anyhow::bail!("Команда подписи {} завершилась с ошибкой: {}", file_name, status);
+    }
+    std::fs::read(&output).with_context(|| format!("Команда подписи не создала {}", output.display()))
+}
+
+/// Подставляет значения в шаблон команды для `sh -c` (`{name}` → значение). Значения берутся
+/// в одинарные кавычки: пробелы, `;` и `$(...)` не разбивают команду и не исполняются. Шаблон
+/// разбирается за один проход, поэтому `{name}` внутри подставленного значения не раскрывается
+pub(crate) fn shell_command<V: AsRef<OsStr>>(template: &str, values: &[(&str, V)]) -> String {
+    let mut command = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        command.push_str(&rest[..start]);
+        let placeholder = &rest[start + 1..];
+        let found = values
+            .iter()
+            .find_map(|(name, value)| Some((value, placeholder.strip_prefix(name)?.strip_prefix('}')?)));
+        match found {
+            Some((value, after)) => {
+                let value = value.as_ref().to_string_lossy();
+                command.push_str(&format!("'{}'", value.replace('\'', r"'\''")));
+                rest = after;
+            }
+            None => {
+                command.push('{');
+                rest = placeholder;
+            }
+        }
+    }
+    command.push_str(rest);
+    command
+}
+
+/// Артефакты из updatePlugins.xml, лежащие в нашем репозитории: (относительный путь, путь на сервере)
+#[cfg(any(feature = "ssh", test))]
+pub fn indexed_artifacts(xml: &str, layout: &crate::core::deploy_layout::DeployLayout) -> Result<Vec<(String, PathBuf)>> {
+    use xmltree::{Element, XMLNode};
+
+    let root = Element::parse(xml.as_bytes()).context("Не удалось разобрать updatePlugins.xml")?;
+    Ok(root
+        .children
+        .iter()
+        .filter_map(|child| match child {
+            XMLNode::Element(el) if el.name == "plugin" => el.attributes.get("url"),
+            _ => None,
+        })
+        .filter_map(|url| layout.remote_path_for_url(url))
+        .filter_map(|remote| layout.relative_path(&remote).map(|rel| (rel, remote)))
+        .collect())
+}
+
+/// Результат проверки одного артефакта
+#[derive(Debug, Clone)]
+pub struct ChecksumCheck {
+    pub path: String,
+    pub expected: Option<String>,
+    /// None — файл на сервере отсутствует
+    pub actual: Option<String>,
+}
+
+impl ChecksumCheck {
+    pub fn ok(&self) -> bool {
+        self.expected.is_some() && self.expected == self.actual
+    }
+
+    pub fn problem(&self) -> Option<String> {
+        match (&self.expected, &self.actual) {
+            (None, _) => Some(format!("{}: нет в {}", self.path, SUMS_FILE)),
+            (Some(_), None) => Some(format!("{}: файл не найден на сервере", self.path)),
+            (Some(expected), Some(actual)) if expected != actual => {
+                Some(format!("{}: sha256 {} не совпадает с {} ({})", self.path, actual, SUMS_FILE, expected))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Сверяет артефакты индекса с SHA256SUMS; `remote_sha256` считает сумму опубликованного файла
+pub fn verify(
+    artifacts: &[(String, PathBuf)],
+    index: &ChecksumIndex,
+    remote_sha256: impl Fn(&PathBuf) -> Option<String>,
+) -> Vec<ChecksumCheck> {
+    artifacts
+        .iter()
+        .map(|(path, remote)| ChecksumCheck {
+            path: path.clone(),
+            expected: index.get(path).map(str::to_string),
+            actual: remote_sha256(remote),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::deploy_layout::DeployLayout;
+    use std::path::Path;
+
+    const A: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    const B: &str = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+    #[test]
+    fn test_index_roundtrip_and_verify() {
+        let mut index = ChecksumIndex::parse(&format!("{}  other/other-1.0.0.zip\n{} *ride/ride-1.2.0.zip\n", A, A)).unwrap();
+        index.upsert("ride/ride-1.3.0.zip", B);
+        assert_eq!(
+            index.render(),
+            format!("{}  other/other-1.0.0.zip\n{}  ride/ride-1.2.0.zip\n{}  ride/ride-1.3.0.zip\n", A, A, B)
+        );
+        assert_eq!(ChecksumIndex::parse(&index.render()).unwrap(), index);
+        assert!(ChecksumIndex::parse("not-a-hash  file.zip").is_err());
+
+        let config = crate::config::parser::test_config();
+        let layout = DeployLayout::from_config(&config, None);
+        let xml = r#"<plugins><plugin id="ride" url="https://plugins.example.com/ride/ride-1.3.0.zip" version="1.3.0"/><plugin id="ext" url="https://cdn.other.org/ext.zip" version="1.0"/><plugin id="new" url="https://plugins.example.com/new/new-0.1.0.zip" version="0.1.0"/></plugins>"#;
+        let artifacts = indexed_artifacts(xml, &layout).unwrap();
+        assert_eq!(artifacts[0], ("ride/ride-1.3.0.zip".to_string(), PathBuf::from("/var/www/plugins/ride/ride-1.3.0.zip")));
+        assert_eq!(artifacts.len(), 2);
+
+        let checks = verify(&artifacts, &index, |remote| remote.ends_with("ride-1.3.0.zip").then(|| A.to_string()));
+        assert!(!checks[0].ok());
+        assert!(checks[0].problem().unwrap().contains("не совпадает"));
+        assert_eq!(checks[1].problem().unwrap(), "new/new-0.1.0.zip: нет в SHA256SUMS");
+    }
+
+    #[test]
+    fn test_sign_detached_quotes_paths() {
+        let command = shell_command("gpg --output {output} {input}", &[("input", Path::new("/tmp/a b/it's.zip")), ("output", Path::new("/tmp/o"))]);
+        assert_eq!(command, r"gpg --output '/tmp/o' '/tmp/a b/it'\''s.zip'");
+        // Плейсхолдер внутри значения остается текстом в кавычках
+        let command = shell_command("cp {input} {output}", &[("input", "/tmp/{output}"), ("output", "/tmp/$(id)")]);
+        assert_eq!(command, "cp '/tmp/{output}' '/tmp/$(id)'");
+
+        // Имя с пробелами, кавычкой и подстановкой команды доходит до команды одним аргументом
+        let name = "ride 1.2.0 $(touch pwned) 'beta'.zip";
+        let signature = sign_detached(b"content", name, "test -f {input} && cp {input} {output}").unwrap();
+        assert_eq!(signature, b"content");
+    }
+}
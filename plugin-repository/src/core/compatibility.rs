@@ -0,0 +1,99 @@
+//! Матрица совместимости `compatibility.json` рядом с updatePlugins.xml: диапазоны сборок IDE
+//! для каждой опубликованной версии плагина (из `<idea-version>` plugin.xml артефакта).
+//! В отличие от индекса, где остается только последняя версия, здесь хранятся все — по файлу
+//! сайт документации строит таблицу поддержки.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// Файл матрицы в корне репозитория
+pub const COMPATIBILITY_FILE: &str = "compatibility.json";
+
+/// Опубликованная версия и поддерживаемые ею сборки IDE
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompatibilityEntry {
+    pub version: String,
+    /// `None` — ограничения снизу нет
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since_build: Option<String>,
+    /// `None` — ограничения сверху нет
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until_build: Option<String>,
+    pub channel: String,
+    /// Путь артефакта от корня репозитория
+    pub file: String,
+    pub published_at: DateTime<Utc>,
+}
+
+/// Версии по id плагина, новые первыми; записи других плагинов репозитория сохраняются
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompatibilityMatrix {
+    pub plugins: BTreeMap<String, Vec<CompatibilityEntry>>,
+}
+
+impl CompatibilityMatrix {
+    pub fn parse(content: &str) -> Result<Self> {
+        serde_json::from_str(content).with_context(|| format!("Некорректный {}", COMPATIBILITY_FILE))
+    }
+
+    /// Добавляет версию; повторная публикация той же версии в том же канале заменяет запись
+    pub fn upsert(&mut self, plugin_id: &str, entry: CompatibilityEntry) {
+        let versions = self.plugins.entry(plugin_id.to_string()).or_default();
+        versions.retain(|e| e.version != entry.version || e.channel != entry.channel);
+        versions.push(entry);
+        versions.sort_by(|a, b| compare_versions(&b.version, &a.version));
+    }
+
+    pub fn render(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)? + "\n")
+    }
+}
+
+/// Сравнение по semver; версии не в формате semver — как строки
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (semver::Version::parse(a), semver::Version::parse(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(version: &str, since: &str, channel: &str) -> CompatibilityEntry {
+        CompatibilityEntry {
+            version: version.to_string(),
+            since_build: Some(since.to_string()),
+            until_build: None,
+            channel: channel.to_string(),
+            file: format!("ride/{0}/ride-{0}.zip", version),
+            published_at: "2026-10-01T12:00:00Z".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_compatibility_matrix_keeps_all_versions_newest_first() {
+        let existing = r#"{"plugins": {"ru.other": [{"version": "3.0.0", "channel": "stable", "file": "other.zip", "published_at": "2026-01-01T00:00:00Z"}]}}"#;
+        let mut matrix = CompatibilityMatrix::parse(existing).unwrap();
+        matrix.upsert("ru.marslab.ide.ride", entry("1.9.0", "233", "stable"));
+        matrix.upsert("ru.marslab.ide.ride", entry("1.10.0", "241", "stable"));
+        matrix.upsert("ru.marslab.ide.ride", entry("1.10.0", "241", "beta"));
+        // Повторная публикация заменяет запись
+        matrix.upsert("ru.marslab.ide.ride", entry("1.9.0", "232", "stable"));
+
+        let ride = &matrix.plugins["ru.marslab.ide.ride"];
+        let versions: Vec<(&str, &str)> = ride.iter().map(|e| (e.version.as_str(), e.channel.as_str())).collect();
+        assert_eq!(versions, [("1.10.0", "stable"), ("1.10.0", "beta"), ("1.9.0", "stable")]);
+        assert_eq!(ride[2].since_build.as_deref(), Some("232"));
+        assert_eq!(matrix.plugins["ru.other"].len(), 1);
+
+        let json = matrix.render().unwrap();
+        assert!(!json.contains("until_build"));
+        assert_eq!(CompatibilityMatrix::parse(&json).unwrap(), matrix);
+        assert!(CompatibilityMatrix::parse("<plugins/>").is_err());
+    }
+}
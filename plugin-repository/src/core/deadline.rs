@@ -0,0 +1,262 @@
+use anyhow::Result;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::core::interrupt::{self, Interrupted};
+
+/// Бюджет времени исчерпан: этап, на котором это произошло, и фактически прошедшее время
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Превышен лимит времени {} на этапе '{stage}' (прошло {})", format_duration(*budget), format_duration(*elapsed))]
+pub struct DeadlineExceeded {
+    pub stage: String,
+    pub elapsed: Duration,
+    pub budget: Duration,
+}
+
+/// Длительность завершенного (или прерванного) этапа пайплайна
+#[derive(Debug, Clone)]
+pub struct StageTiming {
+    pub stage: String,
+    pub duration: Duration,
+    pub completed: bool,
+}
+
+/// Общий дедлайн пайплайна (`publish --max-duration`), передается во все этапы.
+/// Клонируется дешево: клоны разделяют журнал этапов.
+#[derive(Debug, Clone)]
+pub struct Deadline {
+    started: Instant,
+    budget: Option<Duration>,
+    stages: Arc<Mutex<Vec<StageTiming>>>,
+    /// Выполняющиеся этапы: сборка и генерация контента в `publish` идут параллельно
+    current: Arc<Mutex<Vec<String>>>,
+}
+
+impl Deadline {
+    pub fn new(budget: Option<Duration>) -> Self {
+        Self {
+            started: Instant::now(),
+            budget,
+            stages: Arc::new(Mutex::new(Vec::new())),
+            current: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Остаток бюджета; `None` — без ограничения
+    pub fn remaining(&self) -> Option<Duration> {
+        self.budget.map(|b| b.saturating_sub(self.started.elapsed()))
+    }
+
+    /// Проверка между шагами: ошибка, если бюджет уже исчерпан
+    pub fn check(&self, stage: &str) -> std::result::Result<(), DeadlineExceeded> {
+        match self.budget {
+            Some(budget) if self.started.elapsed() >= budget => Err(DeadlineExceeded {
+                stage: stage.to_string(),
+                elapsed: self.started.elapsed(),
+                budget,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Выполняет этап с учетом остатка бюджета и записывает его длительность
+    #[tracing::instrument(name = "stage", skip_all, fields(stage = %stage))]
+    pub async fn run<T, F>(&self, stage: &str, fut: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        self.check(stage)?;
+        interrupt::check(stage)?;
+        let stage_started = Instant::now();
+        self.current.lock().expect("deadline journal poisoned").push(stage.to_string());
+        debug!("⏱️ Этап '{}', остаток бюджета: {:?}", stage, self.remaining());
+
+        // Ctrl-C отменяет этап: незавершенный future сбрасывается
+        let fut = async {
+            tokio::select! {
+                result = fut => result,
+                _ = interrupt::cancelled() => Err(Interrupted { stage: stage.to_string() }.into()),
+            }
+        };
+        let result = match self.remaining() {
+            Some(remaining) => match tokio::time::timeout(remaining, fut).await {
+                Ok(result) => result,
+                Err(_) => Err(self.check(stage).err().unwrap_or_else(|| DeadlineExceeded {
+                    stage: stage.to_string(),
+                    elapsed: self.started.elapsed(),
+                    budget: self.budget.unwrap_or_default(),
+                }).into()),
+            },
+            None => fut.await,
+        };
+
+        self.record(stage, stage_started.elapsed(), result.is_ok());
+        let mut current = self.current.lock().expect("deadline journal poisoned");
+        if let Some(index) = current.iter().position(|s| s == stage) {
+            current.remove(index);
+        }
+        drop(current);
+        result
+    }
+
+    fn record(&self, stage: &str, duration: Duration, completed: bool) {
+        crate::utils::usage::record_stage(stage, duration, completed);
+        self.stages.lock().expect("deadline journal poisoned").push(StageTiming {
+            stage: stage.to_string(),
+            duration,
+            completed,
+        });
+    }
+
+    /// Этап, выполняющийся прямо сейчас (для отслеживания прогресса извне);
+    /// параллельные этапы — через ` + `
+    pub fn current_stage(&self) -> Option<String> {
+        let current = self.current.lock().expect("deadline journal poisoned");
+        (!current.is_empty()).then(|| current.join(" + "))
+    }
+
+    /// Суммарная длительность завершенных запусков этапа
+    pub fn stage_duration(&self, stage: &str) -> Duration {
+        self.stages().iter().filter(|s| s.stage == stage).map(|s| s.duration).sum()
+    }
+
+    pub fn stages(&self) -> Vec<StageTiming> {
+        self.stages.lock().expect("deadline journal poisoned").clone()
+    }
+
+    /// Отчет о расходе бюджета по этапам (самый затратный этап помечается)
+    pub fn report(&self) -> String {
+        let stages = self.stages();
+        let heaviest = stages.iter().map(|s| s.duration).max().unwrap_or_default();
+        let mut lines = vec![format!(
+            "Расход времени (всего {}{}):",
+            format_duration(self.started.elapsed()),
+            self.budget.map(|b| format!(" из {}", format_duration(b))).unwrap_or_default()
+        )];
+        for s in &stages {
+            lines.push(format!(
+                "  {} {:<10} {}{}",
+                if s.completed { "✔" } else { "✘" },
+                s.stage,
+                format_duration(s.duration),
+                if s.duration == heaviest && stages.len() > 1 { "  ← основной расход" } else { "" }
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Разбор длительности вида `20m`, `90s`, `1h30m`, `1500ms`; число без единиц — секунды
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    if let Ok(secs) = input.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    let mut total = Duration::ZERO;
+    let mut rest = input;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if digits == 0 {
+            anyhow::bail!("Некорректная длительность '{}': ожидается число перед единицей", input);
+        }
+        let value: u64 = rest[..digits].parse()?;
+        rest = &rest[digits..];
+        let unit_len = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+        let unit = &rest[..unit_len];
+        rest = &rest[unit_len..];
+
+        total += match unit {
+            "ms" => Duration::from_millis(value),
+            "s" => Duration::from_secs(value),
+            "m" => Duration::from_secs(value * 60),
+            "h" => Duration::from_secs(value * 3600),
+            _ => anyhow::bail!("Некорректная длительность '{}': неизвестная единица '{}' (ms, s, m, h)", input, unit),
+        };
+    }
+
+    if total.is_zero() {
+        warn!("Нулевая длительность '{}' — пайплайн завершится на первом этапе", input);
+    }
+    Ok(total)
+}
+
+pub fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{:.1}s", d.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("20m").unwrap(), Duration::from_secs(1200));
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_duration("45").unwrap(), Duration::from_secs(45));
+        assert_eq!(parse_duration("1500ms").unwrap(), Duration::from_millis(1500));
+        assert!(parse_duration("10x").is_err());
+        assert!(parse_duration("m").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stage_exceeding_budget_is_reported() {
+        let deadline = Deadline::new(Some(Duration::from_millis(50)));
+        deadline.run("build", async { Ok(()) }).await.unwrap();
+
+        let err = deadline
+            .run("llm", async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(())
+            })
+            .await
+            .unwrap_err();
+        let exceeded = err.downcast_ref::<DeadlineExceeded>().unwrap();
+        assert_eq!(exceeded.stage, "llm");
+
+        // Следующий этап даже не стартует
+        assert!(deadline.run("deploy", async { Ok(()) }).await.is_err());
+        let stages = deadline.stages();
+        assert_eq!(stages.len(), 2);
+        assert!(!stages[1].completed);
+        assert!(deadline.report().contains("llm"));
+        assert_eq!(deadline.current_stage(), None);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_stages_are_tracked_together() {
+        let deadline = Deadline::new(None);
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+        let build = deadline.run("build", async {
+            let _ = release_rx.await;
+            Ok(1)
+        });
+        let llm = deadline.run("llm", async {
+            let _ = started_tx.send(());
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok(2)
+        });
+        let observe = async {
+            started_rx.await.unwrap();
+            let current = deadline.current_stage();
+            release_tx.send(()).unwrap();
+            current
+        };
+
+        let (build, llm, current) = tokio::join!(build, llm, observe);
+        assert_eq!((build.unwrap(), llm.unwrap()), (1, 2));
+        assert_eq!(current.as_deref(), Some("build + llm"));
+        assert_eq!(deadline.current_stage(), None);
+        let stages: Vec<String> = deadline.stages().into_iter().map(|s| s.stage).collect();
+        assert_eq!(stages, ["build", "llm"]);
+        assert!(deadline.stage_duration("llm") >= Duration::from_millis(20));
+    }
+}
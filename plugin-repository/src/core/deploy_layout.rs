@@ -0,0 +1,253 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use xmltree::{Element, XMLNode};
+
+use crate::config::parser::Config;
+
+/// Переменные, доступные в шаблоне `deploy_path`
+pub const LAYOUT_VARIABLES: [&str; 4] = ["id", "name", "version", "channel"];
+
+/// Значения переменных для одного артефакта
+#[derive(Debug, Clone)]
+pub struct LayoutVars<'a> {
+    pub id: &'a str,
+    pub name: &'a str,
+    pub version: &'a str,
+}
+
+/// Перенос артефакта из старой раскладки в новую
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArtifactMove {
+    pub plugin_id: String,
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub url: String,
+}
+
+/// План миграции: переносы файлов и updatePlugins.xml с новыми URL
+#[derive(Debug, Clone)]
+pub struct LayoutMigration {
+    pub moves: Vec<ArtifactMove>,
+    pub xml: String,
+}
+
+/// Раскладка артефактов на сервере: шаблон каталога и соответствующие URL.
+/// `deploy_path = "/var/www/plugins/{id}/{channel}/{version}/"` раскрывается для каждого артефакта.
+#[derive(Debug, Clone)]
+pub struct DeployLayout {
+    path_template: String,
+    base_url: String,
+    repo_root: PathBuf,
+    channel: String,
+}
+
+impl DeployLayout {
+    pub fn from_config(config: &Config, channel: Option<&str>) -> Self {
+        // Базовый URL каталога (если в repository.url указан файл XML — отрезаем его)
+        let mut base_url = config.repository.url.trim_end_matches('/').to_string();
+        if base_url.ends_with(".xml") {
+            if let Some(pos) = base_url.rfind('/') {
+                base_url.truncate(pos);
+            }
+        }
+        let repo_root = Path::new(&config.repository.xml_path)
+            .parent()
+            .unwrap_or_else(|| Path::new("/"))
+            .to_path_buf();
+
+        Self {
+            path_template: config.repository.deploy_path.clone(),
+            base_url,
+            repo_root,
+            channel: channel.unwrap_or(&config.repository.channel).to_string(),
+        }
+    }
+
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// Проверяет, что шаблон использует только известные переменные
+    pub fn validate_template(template: &str) -> Result<()> {
+        let unknown: Vec<String> = placeholder_regex()
+            .captures_iter(template)
+            .map(|c| c[1].to_string())
+            .filter(|name| !LAYOUT_VARIABLES.contains(&name.as_str()))
+            .collect();
+        if !unknown.is_empty() {
+            anyhow::bail!(
+                "Неизвестные переменные в deploy_path: {{{}}} (доступны: {})",
+                unknown.join("}, {"),
+                LAYOUT_VARIABLES.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Проверяет значение `{id}`, `{version}` или `{channel}`: оно становится каталогом на сервере,
+    /// поэтому допускаются только `[A-Za-z0-9._-]` и запрещен `..` (выход за корень репозитория)
+    pub fn validate_segment(name: &str, value: &str) -> Result<()> {
+        let allowed = Regex::new(r"^[A-Za-z0-9._-]+$").unwrap();
+        if !allowed.is_match(value) || value.contains("..") {
+            anyhow::bail!(
+                "Недопустимое значение {{{}}}: {:?} (допустимы латиница, цифры, `.`, `_` и `-`, без `..`)",
+                name,
+                value
+            );
+        }
+        Ok(())
+    }
+
+    /// Каталог для артефакта на сервере
+    pub fn deploy_dir(&self, vars: &LayoutVars) -> Result<PathBuf> {
+        Self::validate_segment("channel", &self.channel)?;
+        Self::validate_segment("id", vars.id)?;
+        Self::validate_segment("version", vars.version)?;
+        let expanded = placeholder_regex().replace_all(&self.path_template, |caps: &regex::Captures| {
+            match &caps[1] {
+                "id" => vars.id.to_string(),
+                "name" => vars.name.to_string(),
+                "version" => vars.version.to_string(),
+                "channel" => self.channel.clone(),
+                other => format!("{{{}}}", other),
+            }
+        });
+        Ok(PathBuf::from(expanded.trim_end_matches('/')))
+    }
+
+    /// Публичный URL файла из каталога `dir`
+    pub fn artifact_url(&self, dir: &Path, file_name: &str) -> String {
+        let rel = dir
+            .strip_prefix(&self.repo_root)
+            .ok()
+            .map(|p| p.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"))
+            .filter(|s| !s.is_empty());
+        match rel {
+            Some(rel) => format!("{}/{}/{}", self.base_url, rel, file_name),
+            None => format!("{}/{}", self.base_url, file_name),
+        }
+    }
+
+    /// Корень репозитория на сервере (каталог updatePlugins.xml)
+    pub fn repo_root(&self) -> &Path {
+        &self.repo_root
+    }
+
+    /// Путь файла относительно корня репозитория (`ride/1.2.0/ride-1.2.0.zip`)
+    pub fn relative_path(&self, remote: &Path) -> Option<String> {
+        let rel = remote.strip_prefix(&self.repo_root).ok()?;
+        let rel = rel.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+        (!rel.is_empty()).then_some(rel)
+    }
+
+    /// Путь на сервере для URL из updatePlugins.xml (если URL указывает в наш репозиторий)
+    pub fn remote_path_for_url(&self, url: &str) -> Option<PathBuf> {
+        let rel = url.strip_prefix(&self.base_url)?.trim_start_matches('/');
+        if rel.is_empty() {
+            return None;
+        }
+        Some(self.repo_root.join(rel))
+    }
+
+    /// Планирует перенос уже опубликованных артефактов (обычно из плоской раскладки)
+    /// в текущий шаблон и переписывает их URL в updatePlugins.xml
+    pub fn plan_migration(&self, xml: &str, plugin_name: &str) -> Result<LayoutMigration> {
+        let mut root = Element::parse(xml.as_bytes()).context("Не удалось разобрать updatePlugins.xml")?;
+        let mut moves = Vec::new();
+
+        for child in root.children.iter_mut() {
+            let XMLNode::Element(el) = child else { continue };
+            if el.name != "plugin" {
+                continue;
+            }
+            let (Some(id), Some(version), Some(url)) = (
+                el.attributes.get("id").cloned(),
+                el.attributes.get("version").cloned(),
+                el.attributes.get("url").cloned(),
+            ) else {
+                continue;
+            };
+            let Some(from) = self.remote_path_for_url(&url) else { continue };
+            let Some(file_name) = from.file_name().map(|f| f.to_string_lossy().to_string()) else { continue };
+
+            let name = el.get_child("name").and_then(|n| n.get_text()).map(|t| t.to_string())
+                .unwrap_or_else(|| plugin_name.to_string());
+            let dir = self
+                .deploy_dir(&LayoutVars { id: &id, name: &name, version: &version })
+                .with_context(|| format!("Запись {} {} в updatePlugins.xml", id, version))?;
+            let to = dir.join(&file_name);
+            if to == from {
+                continue;
+            }
+
+            let new_url = self.artifact_url(&dir, &file_name);
+            el.attributes.insert("url".to_string(), new_url.clone());
+            moves.push(ArtifactMove { plugin_id: id, from, to, url: new_url });
+        }
+
+        let mut buf = Vec::new();
+        root.write(&mut buf).context("Сериализация updatePlugins.xml не удалась")?;
+        Ok(LayoutMigration { moves, xml: String::from_utf8_lossy(&buf).to_string() })
+    }
+}
+
+fn placeholder_regex() -> Regex {
+    Regex::new(r"\{([a-zA-Z_]+)\}").unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(template: &str) -> DeployLayout {
+        let mut config = crate::config::parser::test_config();
+        config.repository.url = "https://plugins.example.com/updatePlugins.xml".to_string();
+        config.repository.xml_path = "/var/www/plugins/updatePlugins.xml".to_string();
+        config.repository.deploy_path = template.to_string();
+        DeployLayout::from_config(&config, Some("eap"))
+    }
+
+    #[test]
+    fn test_templated_dir_and_url() {
+        let layout = layout("/var/www/plugins/{id}/{channel}/{version}/");
+        let dir = layout.deploy_dir(&LayoutVars { id: "ru.ride", name: "Ride", version: "1.2.0" }).unwrap();
+        assert_eq!(dir, PathBuf::from("/var/www/plugins/ru.ride/eap/1.2.0"));
+        assert_eq!(
+            layout.artifact_url(&dir, "ride-1.2.0.zip"),
+            "https://plugins.example.com/ru.ride/eap/1.2.0/ride-1.2.0.zip"
+        );
+        assert!(DeployLayout::validate_template("/x/{id}/{branch}").is_err());
+    }
+
+    #[test]
+    fn test_deploy_dir_rejects_path_traversal() {
+        let vars = LayoutVars { id: "ru.ride", name: "Ride", version: "1.2.0" };
+        for channel in ["../../etc", "eap/..", "..", "a b", "eap;id", ""] {
+            let mut layout = layout("/var/www/plugins/{id}/{channel}/{version}/");
+            layout.channel = channel.to_string();
+            assert!(layout.deploy_dir(&vars).is_err(), "канал {:?} должен отклоняться", channel);
+        }
+
+        let layout = layout("/var/www/plugins/{id}/{channel}/{version}/");
+        assert!(layout.deploy_dir(&LayoutVars { version: "../1.2.0", ..vars.clone() }).is_err());
+        assert!(layout.deploy_dir(&LayoutVars { id: "ru/../../etc", ..vars.clone() }).is_err());
+        assert!(layout.deploy_dir(&LayoutVars { version: "1.3.0-beta_2", ..vars }).is_ok());
+    }
+
+    #[test]
+    fn test_migration_from_flat_layout() {
+        let layout = layout("/var/www/plugins/{id}/{channel}/{version}");
+        let xml = r#"<plugins><plugin id="ru.ride" url="https://plugins.example.com/files/ride-1.1.0.zip" version="1.1.0"><name>Ride</name></plugin><plugin id="ext" url="https://cdn.other.com/ext.zip" version="2.0.0"/></plugins>"#;
+
+        let migration = layout.plan_migration(xml, "Ride").unwrap();
+        assert_eq!(migration.moves, vec![ArtifactMove {
+            plugin_id: "ru.ride".to_string(),
+            from: PathBuf::from("/var/www/plugins/files/ride-1.1.0.zip"),
+            to: PathBuf::from("/var/www/plugins/ru.ride/eap/1.1.0/ride-1.1.0.zip"),
+            url: "https://plugins.example.com/ru.ride/eap/1.1.0/ride-1.1.0.zip".to_string(),
+        }]);
+        assert!(migration.xml.contains("https://plugins.example.com/ru.ride/eap/1.1.0/ride-1.1.0.zip"));
+        assert!(migration.xml.contains("https://cdn.other.com/ext.zip"));
+    }
+}
@@ -7,42 +7,528 @@ use sha2::{Sha256, Digest};
 use std::time::Duration;
 use xmltree::{Element, XMLNode};
 use std::fs::File;
+use serde::Serialize;
+use indicatif::HumanBytes;
 
 use crate::config::parser::Config;
+use crate::config::validator::Capability;
+use crate::core::artifact_cache::{ArtifactCache, ArtifactSource};
+use crate::core::chaos::{self, ChaosPoint};
+use crate::core::compatibility::{CompatibilityEntry, CompatibilityMatrix, COMPATIBILITY_FILE};
+use crate::core::checksums::{self, ChecksumCheck, ChecksumIndex, SIG_FILE, SUMS_FILE};
+use crate::core::deadline::{Deadline, DeadlineExceeded};
+use crate::core::interrupt::{self, Interrupted};
+use crate::core::remediation::{self, Remediation};
+use crate::core::deploy_layout::{DeployLayout, LayoutMigration, LayoutVars};
+use crate::core::remote_exec::{self, CommandVars, RemoteCommand, RemoteCommandResult};
+use crate::core::resources::{self, PluginResources, PublishedResource, RESOURCES_DIR};
+use crate::utils::clock::{system_clock, SharedClock};
+use crate::core::version_guard;
 
 /// Движок деплоя
 #[derive(Debug, Clone)]
 pub struct Deployer {
     config: Config,
+    allow_republish: bool,
+    change_notes: Option<String>,
+    deadline: Option<Deadline>,
+    channel: Option<String>,
+    /// Дополнительные файлы, загружаемые рядом с артефактом (например, стенограмма LLM)
+    attachments: Vec<PathBuf>,
+    /// Версия публикуемого релиза: тег `v<version>` сверяется с артефактом
+    release_version: Option<String>,
+    /// Часы для дат публикации в compatibility.json
+    clock: SharedClock,
+}
+
+/// Итог деплоя (`deploy-report.json` в хранилище релизов)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeployReport {
+    pub plugin_id: String,
+    /// Версия последнего артефакта (из имени файла)
+    pub version: Option<String>,
+    pub target: DeployTarget,
+    /// Загруженные файлы и время передачи
+    pub uploads: Vec<UploadTiming>,
+    /// Изменения updatePlugins.xml; None — индекс не менялся
+    pub index: Option<IndexDiff>,
+    /// Резервные копии на сервере, из которых восстанавливается откат; после успешного деплоя удаляются
+    pub backups: Vec<PathBuf>,
+    /// Проверки загруженных файлов и индекса
+    pub verification: Vec<VerificationCheck>,
+    /// Вывод команд `[[repository.post_deploy]]`
+    pub remote_commands: Vec<RemoteCommandResult>,
+    /// Артефакт и запись индекса уже были на сервере: деплой ничего не менял
+    pub already_deployed: bool,
+    pub duration_ms: u64,
+}
+
+/// Куда выполняется деплой
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeployTarget {
+    /// `ssh` — реальная загрузка, `mock` — локальная отладка без фичи ssh
+    pub transport: String,
+    pub host: String,
+    pub channel: String,
+    pub xml_path: PathBuf,
+}
+
+impl std::fmt::Display for DeployTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} (канал {}, индекс {})", self.transport, self.host, self.channel, self.xml_path.display())
+    }
+}
+
+/// Передача одного файла на сервер
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadTiming {
+    pub remote: PathBuf,
+    pub bytes: u64,
+    /// SHA256 загруженного содержимого
+    pub sha256: Option<String>,
+    /// None — файл не передавался (сборка без SSH)
+    pub duration_ms: Option<u64>,
+}
+
+impl UploadTiming {
+    /// Средняя скорость передачи, байт/с
+    pub fn bytes_per_sec(&self) -> Option<u64> {
+        self.duration_ms.map(|ms| self.bytes * 1000 / ms.max(1))
+    }
+}
+
+impl std::fmt::Display for UploadTiming {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} — {}", self.remote.display(), HumanBytes(self.bytes))?;
+        match (self.duration_ms, self.bytes_per_sec()) {
+            (Some(ms), Some(rate)) => write!(f, " за {:.1} с ({}/с)", ms as f64 / 1000.0, HumanBytes(rate)),
+            _ => write!(f, " (не передавался)"),
+        }
+    }
+}
+
+/// Сводка изменений updatePlugins.xml: наша запись до и после деплоя
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct IndexDiff {
+    /// Число записей `<plugin>` до и после
+    pub plugins_before: usize,
+    pub plugins_after: usize,
+    /// Версия нашей записи до деплоя; None — записи не было
+    pub previous_version: Option<String>,
+    pub version: Option<String>,
+    /// Измененные атрибуты и дочерние элементы нашей записи
+    pub changed: Vec<String>,
+}
+
+impl IndexDiff {
+    /// Сравнивает индекс до (`None` — файла не было) и после деплоя по записи `plugin_id`
+    pub fn between(before: Option<&str>, after: &str, plugin_id: &str) -> Self {
+        let parse = |xml: &str| Element::parse(xml.as_bytes()).ok();
+        let before = before.and_then(parse);
+        let after = parse(after);
+        let count = |root: &Option<Element>| {
+            root.as_ref().map_or(0, |root| {
+                root.children.iter().filter(|ch| matches!(ch, XMLNode::Element(el) if el.name == "plugin")).count()
+            })
+        };
+        let entry = |root: &Option<Element>| {
+            root.as_ref()?.children.iter().find_map(|ch| match ch {
+                XMLNode::Element(el) if el.name == "plugin" && el.attributes.get("id").map(String::as_str) == Some(plugin_id) => {
+                    Some(el.clone())
+                }
+                _ => None,
+            })
+        };
+        let (old, new) = (entry(&before), entry(&after));
+
+        let mut changed = std::collections::BTreeSet::new();
+        if let Some(new) = &new {
+            let empty = Element::new("plugin");
+            let old = old.as_ref().unwrap_or(&empty);
+            for key in old.attributes.keys().chain(new.attributes.keys()) {
+                if old.attributes.get(key) != new.attributes.get(key) {
+                    changed.insert(key.clone());
+                }
+            }
+            let names = |el: &Element| {
+                el.children.iter().filter_map(|ch| ch.as_element().map(|e| e.name.clone())).collect::<Vec<_>>()
+            };
+            for name in names(old).into_iter().chain(names(new)) {
+                if old.get_child(name.as_str()) != new.get_child(name.as_str()) {
+                    changed.insert(name);
+                }
+            }
+        }
+
+        Self {
+            plugins_before: count(&before),
+            plugins_after: count(&after),
+            previous_version: old.and_then(|el| el.attributes.get("version").cloned()),
+            version: new.and_then(|el| el.attributes.get("version").cloned()),
+            changed: changed.into_iter().collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for IndexDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} → {}",
+            self.previous_version.as_deref().unwrap_or("нет записи"),
+            self.version.as_deref().unwrap_or("-")
+        )?;
+        if !self.changed.is_empty() {
+            write!(f, ", изменено: {}", self.changed.join(", "))?;
+        }
+        write!(f, "; записей {} → {}", self.plugins_before, self.plugins_after)
+    }
+}
+
+/// Исправления индекса репозитория для `validate --fix`
+#[derive(Debug, Default)]
+struct IndexRepair {
+    /// updatePlugins.xml с упорядоченными записями
+    xml: Option<String>,
+    /// Новый SHA256SUMS и пути добавленных в него файлов
+    sums: Option<(String, Vec<String>)>,
+}
+
+impl IndexRepair {
+    fn remediations(&self, xml_path: &Path, sums_path: &Path, applied: bool) -> Vec<Remediation> {
+        let mut remediations = Vec::new();
+        if self.xml.is_some() {
+            remediations.push(Remediation::new(
+                xml_path.display().to_string(),
+                "записи <plugin> не упорядочены по id",
+                "записи упорядочены по id",
+                applied,
+            ));
+        }
+        if let Some((_, added)) = &self.sums {
+            remediations.push(Remediation::new(
+                sums_path.display().to_string(),
+                format!("нет sha256 опубликованных файлов: {}", added.join(", ")),
+                format!("добавлены sha256 {} файлов, посчитанные по опубликованным артефактам", added.len()),
+                applied,
+            ));
+        }
+        remediations
+    }
+}
+
+/// Проверка после загрузки: размер и sha256 файлов, содержимое индекса
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl VerificationCheck {
+    fn new(name: impl Into<String>, passed: bool, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), passed, detail: detail.into() }
+    }
+}
+
+/// Результат переключения индекса на ранее загруженную версию
+#[derive(Debug, Clone, Serialize)]
+pub struct Promotion {
+    pub from_version: Option<String>,
+    pub to_version: String,
+    pub url: String,
+    pub remote_path: PathBuf,
+    #[serde(skip)]
+    pub xml: String,
+}
+
+/// План деплоя без изменений на сервере (для `publish --dry-run`)
+#[derive(Debug, Clone, Serialize)]
+pub struct DeployPlan {
+    /// `ssh` — реальная загрузка, `mock` — локальная отладка без фичи ssh
+    pub transport: String,
+    pub host: String,
+    pub channel: String,
+    pub xml_path: PathBuf,
+    pub xml_backup: PathBuf,
+    /// Был ли найден текущий updatePlugins.xml (иначе будет создан новый)
+    pub existing_index: bool,
+    pub uploads: Vec<PlannedUpload>,
+    pub conflicts: Vec<String>,
+    /// Команды на сервере после обновления XML
+    pub remote_commands: Vec<RemoteCommand>,
+    /// Итоговый updatePlugins.xml после мёрджа
+    #[serde(skip)]
+    pub merged_xml: String,
+}
+
+/// Файл, который будет загружен на сервер
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedUpload {
+    pub local: PathBuf,
+    pub remote: PathBuf,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Конфликт публикации с существующей записью в updatePlugins.xml
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeployConflict {
+    /// Запись с нашим id принадлежит другому vendor
+    ForeignVendor {
+        plugin_id: String,
+        existing_vendor: String,
+        our_vendor: String,
+    },
+    /// Та же версия уже опубликована, но содержимое артефакта отличается (или его нельзя сверить)
+    Republish {
+        plugin_id: String,
+        version: String,
+        existing_sha256: Option<String>,
+        new_sha256: String,
+    },
+}
+
+impl std::fmt::Display for DeployConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeployConflict::ForeignVendor { plugin_id, existing_vendor, our_vendor } => write!(
+                f,
+                "id '{}' уже занят плагином vendor '{}' (наш vendor: '{}')",
+                plugin_id, existing_vendor, our_vendor
+            ),
+            DeployConflict::Republish { plugin_id, version, existing_sha256: Some(existing), new_sha256 } => write!(
+                f,
+                "версия {} плагина '{}' уже опубликована с другим содержимым (sha256 {} != {})",
+                version, plugin_id, existing, new_sha256
+            ),
+            DeployConflict::Republish { plugin_id, version, existing_sha256: None, .. } => write!(
+                f,
+                "версия {} плагина '{}' уже есть в индексе, но опубликованный артефакт недоступен для сверки",
+                version, plugin_id
+            ),
+        }
+    }
 }
 
 impl Deployer {
     pub fn new(config: Config) -> Self {
-        Self { config }
+        Self {
+            config,
+            allow_republish: false,
+            change_notes: None,
+            deadline: None,
+            channel: None,
+            attachments: Vec::new(),
+            release_version: None,
+            clock: system_clock(),
+        }
+    }
+
+    /// Часы запуска: время публикации версий в compatibility.json
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Канал публикации для шаблона deploy_path (по умолчанию repository.channel)
+    pub fn with_channel(mut self, channel: Option<String>) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    fn layout(&self) -> DeployLayout {
+        DeployLayout::from_config(&self.config, self.channel.as_deref())
+    }
+
+    /// Каталог артефакта на сервере с учетом шаблона deploy_path
+    fn remote_dir_for(&self, artifact: &Path) -> Result<PathBuf> {
+        let file_name = artifact.file_name().unwrap_or_default().to_string_lossy();
+        let version = self.extract_version_from_filename(&file_name).unwrap_or_else(|| "0.0.0".to_string());
+        self.remote_dir_for_version(&version)
+    }
+
+    /// Каталог версии на сервере
+    fn remote_dir_for_version(&self, version: &str) -> Result<PathBuf> {
+        self.layout().deploy_dir(&LayoutVars {
+            id: &self.config.project.id,
+            name: &self.config.project.name,
+            version,
+        })
+    }
+
+    /// Файлы, которые загружаются в каталог артефакта как `<артефакт>.<имя файла>`
+    pub fn with_attachments(mut self, attachments: Vec<PathBuf>) -> Self {
+        self.attachments = attachments;
+        self
+    }
+
+    pub fn with_release_version(mut self, version: Option<String>) -> Self {
+        self.release_version = version;
+        self
+    }
+
+    /// Путь на сервере для приложенного файла: рядом с артефактом, с его именем в префиксе
+    fn attachment_remote_path(&self, artifact: &Path, attachment: &Path) -> Result<PathBuf> {
+        let stem = artifact.file_stem().unwrap_or_default().to_string_lossy();
+        let name = attachment.file_name().unwrap_or_default().to_string_lossy();
+        Ok(self.remote_dir_for(artifact)?.join(format!("{}.{}", stem, name)))
+    }
+
+    /// Иконка и ресурсы плагина из `[repository]`
+    fn resources(&self) -> PluginResources {
+        PluginResources::from_config(&self.config.repository)
+    }
+
+    /// Путь на сервере для ресурса плагина: `<каталог артефакта>/resources/<имя файла>`
+    fn resource_remote_path(&self, artifact: &Path, resource: &Path) -> Result<PathBuf> {
+        Ok(self.remote_dir_for(artifact)?.join(RESOURCES_DIR).join(resources::file_name(resource)))
+    }
+
+    /// HTML-блок ресурсов для description записи плагина (None, если ресурсы не заданы)
+    fn resources_listing(&self, artifact: &Path) -> Result<Option<String>> {
+        let plugin_resources = self.resources();
+        if plugin_resources.is_empty() {
+            return Ok(None);
+        }
+        let dir = self.remote_dir_for(artifact)?.join(RESOURCES_DIR);
+        let published = |path: &PathBuf| {
+            let name = resources::file_name(path);
+            PublishedResource { url: self.layout().artifact_url(&dir, &name), name }
+        };
+        let icon = plugin_resources.icon.as_ref().map(published);
+        let files: Vec<_> = plugin_resources.files.iter().map(published).collect();
+        Ok(Some(resources::listing_html(icon.as_ref(), &files)))
+    }
+
+    /// Команды `[[repository.post_deploy]]` для артефакта с подставленными переменными
+    fn post_deploy_commands(&self, artifact: &Path) -> Result<Vec<RemoteCommand>> {
+        let file_name = artifact.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let version = self.extract_version_from_filename(&file_name).unwrap_or_else(|| "0.0.0".to_string());
+        let deploy_dir = self.remote_dir_for(artifact)?.display().to_string();
+        Ok(remote_exec::expand(&self.config.repository.post_deploy, &CommandVars {
+            deploy_dir: &deploy_dir,
+            xml_path: &self.config.repository.xml_path,
+            version: &version,
+            artifact: &file_name,
+        }))
+    }
+
+    /// Общий дедлайн пайплайна: проверяется между шагами деплоя
+    /// SHA256SUMS на сервере — в корне репозитория рядом с updatePlugins.xml
+    #[cfg(feature = "ssh")]
+    fn sums_remote_path(&self) -> PathBuf {
+        self.layout().repo_root().join(SUMS_FILE)
+    }
+
+    /// compatibility.json на сервере — тоже в корне репозитория; тот же путь для загрузки, бэкапа и отката
+    #[cfg(feature = "ssh")]
+    fn compatibility_remote_path(&self) -> PathBuf {
+        self.layout().repo_root().join(COMPATIBILITY_FILE)
+    }
+
+    /// Содержимое SHA256SUMS после загрузки: прежние записи плюс загруженные файлы
+    fn updated_checksums(&self, existing: Option<&str>, uploads: &[PlannedUpload]) -> Result<String> {
+        let mut index = match existing {
+            Some(content) => ChecksumIndex::parse(content)
+                .with_context(|| format!("Текущий {} на сервере поврежден", SUMS_FILE))?,
+            None => ChecksumIndex::default(),
+        };
+        let layout = self.layout();
+        for upload in uploads {
+            if let Some(path) = layout.relative_path(&upload.remote) {
+                index.upsert(&path, &upload.sha256);
+            }
+        }
+        Ok(index.render())
+    }
+
+    /// compatibility.json после загрузки: прежние версии плюс загруженные артефакты
+    fn updated_compatibility(&self, existing: Option<&str>, artifacts: &[PathBuf]) -> Result<String> {
+        let mut matrix = match existing {
+            Some(content) => CompatibilityMatrix::parse(content)
+                .with_context(|| format!("Текущий {} на сервере поврежден", COMPATIBILITY_FILE))?,
+            None => CompatibilityMatrix::default(),
+        };
+        let layout = self.layout();
+        for art in artifacts {
+            let meta = self.extract_meta_from_zip(art)?;
+            let file_name = art.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let remote = self.remote_dir_for(art)?.join(&file_name);
+            matrix.upsert(&self.config.project.id, CompatibilityEntry {
+                version: self.extract_version_from_filename(&file_name).unwrap_or_else(|| "0.0.0".to_string()),
+                since_build: meta.since_build,
+                until_build: meta.until_build,
+                channel: layout.channel().to_string(),
+                file: layout.relative_path(&remote).unwrap_or(file_name),
+                published_at: self.clock.now(),
+            });
+        }
+        matrix.render()
+    }
+
+    pub fn with_deadline(mut self, deadline: Option<Deadline>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    fn check_deadline(&self, step: &str) -> Result<()> {
+        let stage = format!("deploy: {}", step);
+        interrupt::check(&stage)?;
+        if let Some(deadline) = &self.deadline {
+            deadline.check(&stage)?;
+        }
+        Ok(())
+    }
+
+    /// HTML change-notes для записи плагина в updatePlugins.xml
+    pub fn with_change_notes(mut self, change_notes: Option<String>) -> Self {
+        self.change_notes = change_notes;
+        self
+    }
+
+    /// Разрешить публикацию несмотря на конфликты id/версии (--allow-republish)
+    pub fn with_allow_republish(mut self, allow: bool) -> Self {
+        self.allow_republish = allow;
+        self
     }
 
     /// Валидация перед деплоем
     pub async fn validate(&self) -> Result<()> {
         info!("🔍 Валидация перед деплоем");
-        self.config.validate().context("Валидация конфигурации деплоя не пройдена")?;
+        self.config.validate_for(&[Capability::Deploy]).context("Валидация конфигурации деплоя не пройдена")?;
+        DeployLayout::validate_segment("channel", self.layout().channel())?;
+        self.resources().validate()?;
         Ok(())
     }
 
     /// Выполнить деплой артефактов
-    pub async fn deploy(&self, force: bool, rollback_on_failure: bool) -> Result<()> {
+    #[tracing::instrument(name = "deploy", skip(self))]
+    pub async fn deploy(&self, force: bool, rollback_on_failure: bool) -> Result<DeployReport> {
         info!("📦 Запуск деплоя (force={}, rollback_on_failure={})", force, rollback_on_failure);
+        let started = std::time::Instant::now();
         // 1) Поиск артефактов
         let artifacts = self.find_artifacts()?;
         if artifacts.is_empty() {
             return Err(anyhow::anyhow!("Не найдены артефакты для деплоя"));
         }
+        // Версии имени ZIP, plugin.xml и тега сверяются до любых изменений на сервере
+        if let Some(art) = artifacts.iter().max() {
+            version_guard::check(art, self.release_version.as_deref())?;
+        }
 
         // 2) Подготовка XML будет сделана позже, после чтения существующего файла (merge)
 
         // 3) Загрузка артефактов и XML
         let mut uploaded: Vec<String> = Vec::new();
+        let latest_name = artifacts.iter().max().and_then(|art| art.file_name()).map(|n| n.to_string_lossy().to_string());
+        let mut report = DeployReport {
+            plugin_id: self.config.project.id.clone(),
+            version: latest_name.and_then(|name| self.extract_version_from_filename(&name)),
+            target: self.target(),
+            ..DeployReport::default()
+        };
         let xml_remote = PathBuf::from(&self.config.repository.xml_path);
-        let deploy_dir = PathBuf::from(&self.config.repository.deploy_path);
 
         // Резервная копия XML (remote, только для ssh фичи)
         #[cfg(feature = "ssh")]
@@ -56,9 +542,32 @@ impl Deployer {
 
                 // Гарантируем существование директорий для артефактов и XML
                 let xml_parent = xml_remote.parent().unwrap_or_else(|| Path::new("/"));
-                self.sftp_mkdirs(&sftp, &deploy_dir)?;
+                for art in &artifacts {
+                    self.sftp_mkdirs(&sftp, &self.remote_dir_for(art)?)?;
+                }
                 self.sftp_mkdirs(&sftp, xml_parent)?;
 
+                // Проверка конфликтов с текущим индексом до любых изменений на сервере
+                let existing_xml = self.read_remote_xml(&sftp, &xml_remote);
+                if let Some(existing_xml) = &existing_xml {
+                    let mut arts = artifacts.clone();
+                    arts.sort();
+                    let art = arts.last().unwrap();
+                    let file_name = art.file_name().unwrap().to_string_lossy().to_string();
+                    let remote_artifact = self.remote_dir_for(art)?.join(&file_name);
+                    // Повтор уже завершенного деплоя (ретрай в CI): ничего не трогаем
+                    if self.is_already_deployed(existing_xml, &artifacts, || self.sftp_sha256(&sftp, &remote_artifact).ok())? {
+                        report.already_deployed = true;
+                        return Ok(());
+                    }
+                    let conflicts = self.detect_conflicts(existing_xml, art, || {
+                        self.sftp_sha256(&sftp, &remote_artifact).ok()
+                    })?;
+                    self.resolve_conflicts(&conflicts)?;
+                }
+
+                self.check_deadline("backup xml")?;
+
                 // Бэкап XML, если существует
                 if sftp.stat(&xml_remote).is_ok() {
                     use ssh2::RenameFlags;
@@ -76,40 +585,164 @@ impl Deployer {
                             xml_backup_done = true;
                         }
                     }
+                    report.backups.push(bak_path);
 
                 }
                 // Загрузка артефактов
                 for art in &artifacts {
                     let file_name = art.file_name().unwrap().to_string_lossy().to_string();
-                    let remote_path = deploy_dir.join(&file_name);
+                    let remote_path = self.remote_dir_for(art)?.join(&file_name);
+                    self.check_deadline("upload")?;
                     // Сначала пробуем SCP
-                    match self.scp_upload(&session, art, &remote_path) {
-                        Ok(_) => {}
+                    let timing = match self.scp_upload(&session, art, &remote_path) {
+                        Ok(timing) => timing,
                         Err(e) => {
                             warn!("SCP не удался для {}: {} — пробуем SFTP", remote_path.display(), e);
                             // Фоллбек на SFTP
                             match self.sftp_upload(&sftp, art, &remote_path) {
-                                Ok(_) => {}
+                                Ok(timing) => timing,
                                 Err(e) => {
                                     warn!("SFTP не удался для {}: {}", remote_path.display(), e);
                                     return Err(anyhow::anyhow!("Загрузка артефакта {} не удалась: {}", remote_path.display(), e));
                                 }
                             }
                         }
-                    }
-                    // Проверка размера
+                    };
+                    // Проверка размера и содержимого загруженного артефакта
                     let local_size = fs::metadata(art)?.len();
                     let remote_md = sftp.stat(&remote_path)
                         .with_context(|| format!("Не удалось получить метаданные удаленного файла {}", remote_path.display()))?;
                     if remote_md.size.unwrap_or(0) != local_size as u64 {
                         anyhow::bail!("Размер загруженного файла не совпадает для {}", remote_path.display());
                     }
+                    let remote_sha256 = self.sftp_sha256(&sftp, &remote_path)?;
+                    if timing.sha256.as_deref() != Some(remote_sha256.as_str()) {
+                        anyhow::bail!("SHA256 загруженного файла не совпадает для {}", remote_path.display());
+                    }
+                    report.verification.push(VerificationCheck::new(
+                        remote_path.display().to_string(),
+                        true,
+                        format!("размер {} и sha256 совпадают", HumanBytes(local_size)),
+                    ));
+                    report.uploads.push(timing);
+                    uploaded.push(remote_path.display().to_string());
+                }
+
+                // Приложения — рядом с последним артефактом
+                if let Some(art) = artifacts.iter().max() {
+                    for attachment in &self.attachments {
+                        let remote_path = self.attachment_remote_path(art, attachment)?;
+                        self.check_deadline("upload")?;
+                        let timing = self.sftp_upload(&sftp, attachment, &remote_path)
+                            .with_context(|| format!("Загрузка {} не удалась", remote_path.display()))?;
+                        report.uploads.push(timing);
+                        uploaded.push(remote_path.display().to_string());
+                    }
+
+                    // Иконка и ресурсы — в подкаталог resources
+                    let plugin_resources = self.resources().all();
+                    if !plugin_resources.is_empty() {
+                        self.sftp_mkdirs(&sftp, &self.remote_dir_for(art)?.join(RESOURCES_DIR))?;
+                    }
+                    for resource in &plugin_resources {
+                        let remote_path = self.resource_remote_path(art, resource)?;
+                        self.check_deadline("upload")?;
+                        let timing = self.sftp_upload(&sftp, resource, &remote_path)
+                            .with_context(|| format!("Загрузка {} не удалась", remote_path.display()))?;
+                        report.uploads.push(timing);
+                        uploaded.push(remote_path.display().to_string());
+                    }
                 }
 
-                // Сборка итогового XML: читаем существующий, мёрджим новые плагины по id, оставляя только последнюю версию на id
-                let merged_xml = self.build_merged_repository_xml_ssh(&sftp, &xml_remote, &artifacts)?;
+                self.check_deadline("update xml")?;
+
+                // SHA256SUMS (и подпись) обновляются непосредственно перед XML; прежние версии — в .bak для отката
+                if self.config.repository.checksums.enabled {
+                    use ssh2::RenameFlags;
+                    let sums_remote = self.sums_remote_path();
+                    let sums = self.updated_checksums(
+                        self.read_remote_file(&sftp, &sums_remote).as_deref(),
+                        &self.planned_uploads(&artifacts)?,
+                    )?;
+                    let signature = match &self.config.repository.checksums.sign_command {
+                        Some(command) => Some(checksums::sign(&sums, command)?),
+                        None => None,
+                    };
+                    for name in [SUMS_FILE, SIG_FILE] {
+                        let path = sums_remote.with_file_name(name);
+                        if sftp.stat(&path).is_ok() {
+                            let bak_path = PathBuf::from(format!("{}.bak", path.display()));
+                            sftp.rename(&path, &bak_path, Some(RenameFlags::OVERWRITE))
+                                .with_context(|| format!("Не удалось создать бэкап {}", path.display()))?;
+                            report.backups.push(bak_path);
+                        }
+                    }
+                    self.remote_atomic_update_xml(&sftp, &sums_remote, &sums)?;
+                    if let Some(signature) = signature {
+                        use std::io::Write;
+                        let sig_remote = sums_remote.with_file_name(SIG_FILE);
+                        let mut file = sftp.create(&sig_remote)
+                            .with_context(|| format!("Не удалось создать {}", sig_remote.display()))?;
+                        file.write_all(&signature)
+                            .with_context(|| format!("Не удалось записать {}", sig_remote.display()))?;
+                    }
+                }
+
+                // compatibility.json — тоже перед XML, с бэкапом для отката
+                if self.config.repository.compatibility_matrix {
+                    let matrix_remote = self.compatibility_remote_path();
+                    let matrix = self.updated_compatibility(self.read_remote_file(&sftp, &matrix_remote).as_deref(), &artifacts)?;
+                    if sftp.stat(&matrix_remote).is_ok() {
+                        let bak_path = PathBuf::from(format!("{}.bak", matrix_remote.display()));
+                        sftp.rename(&matrix_remote, &bak_path, Some(ssh2::RenameFlags::OVERWRITE))
+                            .with_context(|| format!("Не удалось создать бэкап {}", matrix_remote.display()))?;
+                        report.backups.push(bak_path);
+                    }
+                    self.remote_atomic_update_xml(&sftp, &matrix_remote, &matrix)?;
+                }
+
+                // Сборка итогового XML: мёрджим новые плагины по id в прочитанный индекс, оставляя только последнюю версию на id
+                let merged_xml = self.merge_repository_xml(existing_xml.clone(), &artifacts)?;
                 // Атомарное обновление XML на удаленной стороне через временный файл и rename
                 self.remote_atomic_update_xml(&sftp, &xml_remote, &merged_xml)?;
+                if self.config.repository.validate_index.enabled {
+                    if let Err(reason) = self.validate_remote_index(&session, &sftp, &xml_remote) {
+                        let bak_path = PathBuf::from(format!("{}.bak", xml_remote.display()));
+                        let restored = xml_backup_done
+                            && sftp.rename(&bak_path, &xml_remote, Some(ssh2::RenameFlags::OVERWRITE)).is_ok();
+                        let outcome = match (xml_backup_done, restored) {
+                            (true, true) => format!("восстановлен из {}", bak_path.display()),
+                            (true, false) => format!("не удалось восстановить из {}", bak_path.display()),
+                            (false, _) => "резервной копии нет".to_string(),
+                        };
+                        anyhow::bail!("{} не прошел проверку после замены: {}; {}", xml_remote.display(), reason, outcome);
+                    }
+                    report.verification.push(VerificationCheck::new(xml_remote.display().to_string(), true, "проверка после замены пройдена"));
+                }
+                report.index = Some(IndexDiff::between(existing_xml.as_deref(), &merged_xml, &self.config.project.id));
+                report.verification.push(self.index_check(&xml_remote, self.read_remote_file(&sftp, &xml_remote), &merged_xml));
+
+                // Команды после загрузки (права, сброс кеша, reload): ошибка откатывает деплой
+                if let Some(art) = artifacts.iter().max() {
+                    for command in self.post_deploy_commands(art)? {
+                        self.check_deadline("post-deploy")?;
+                        info!("🖥️ $ {}", command.command);
+                        let result = remote_exec::exec(&session, &command)?;
+                        let failed = !result.succeeded() && !result.allow_failure;
+                        let detail = format!("код {:?}: {}", result.exit_code, result.stderr.trim());
+                        report.remote_commands.push(result);
+                        if failed {
+                            anyhow::bail!("Команда на сервере завершилась с ошибкой ({}): {}", command.command, detail);
+                        }
+                    }
+                }
+
+                // Деплой завершен: оставленные бэкапы восстановились бы при откате следующего деплоя
+                for bak_path in &report.backups {
+                    if let Err(e) = sftp.unlink(bak_path) {
+                        warn!("Не удалось удалить резервную копию {}: {}", bak_path.display(), e);
+                    }
+                }
             }
             #[cfg(not(feature = "ssh"))]
             {
@@ -117,32 +750,730 @@ impl Deployer {
                 // Локальная проверка: создадим локальный XML рядом с указанный путем (для отладки)
                 let local_xml = Path::new("./target/mock").join(xml_remote.file_name().unwrap_or_default());
                 std::fs::create_dir_all(local_xml.parent().unwrap()).ok();
+                let existing_xml = fs::read_to_string(&local_xml).ok();
+                if let Some(existing_xml) = &existing_xml {
+                    // mock-индекс содержит sha256 артефактов: совпадение означает повтор деплоя
+                    if *existing_xml == self.build_repository_xml(&artifacts)? {
+                        report.already_deployed = true;
+                        return Ok(());
+                    }
+                    let mut arts = artifacts.clone();
+                    arts.sort();
+                    let conflicts = self.detect_conflicts(existing_xml, arts.last().unwrap(), || None)?;
+                    self.resolve_conflicts(&conflicts)?;
+                }
+                if let Some(art) = artifacts.iter().max() {
+                    for attachment in &self.attachments {
+                        info!("📎 (mock) {} -> {}", attachment.display(), self.attachment_remote_path(art, attachment)?.display());
+                    }
+                    for resource in self.resources().all() {
+                        info!("🖼️ (mock) {} -> {}", resource.display(), self.resource_remote_path(art, &resource)?.display());
+                    }
+                }
+                report.uploads = self.planned_uploads(&artifacts)?
+                    .into_iter()
+                    .map(|upload| UploadTiming {
+                        remote: upload.remote,
+                        bytes: upload.size,
+                        sha256: Some(upload.sha256),
+                        duration_ms: None,
+                    })
+                    .collect();
                 let merged_xml = self.build_repository_xml(&artifacts)?;
+                self.check_deadline("update xml")?;
+                if self.config.repository.checksums.enabled {
+                    let local_sums = Path::new("./target/mock").join(SUMS_FILE);
+                    let sums = self.updated_checksums(
+                        fs::read_to_string(&local_sums).ok().as_deref(),
+                        &self.planned_uploads(&artifacts)?,
+                    )?;
+                    if let Some(command) = &self.config.repository.checksums.sign_command {
+                        fs::write(local_sums.with_file_name(SIG_FILE), checksums::sign(&sums, command)?)?;
+                    }
+                    self.atomic_update_xml(&local_sums, &sums)?;
+                }
+                if self.config.repository.compatibility_matrix {
+                    let local_matrix = Path::new("./target/mock").join(COMPATIBILITY_FILE);
+                    let matrix = self.updated_compatibility(fs::read_to_string(&local_matrix).ok().as_deref(), &artifacts)?;
+                    self.atomic_update_xml(&local_matrix, &matrix)?;
+                }
                 self.atomic_update_xml(&local_xml, &merged_xml)?;
+                if self.config.repository.validate_index.enabled {
+                    // Команда проверки выполняется только на сервере; локально — встроенная проверка
+                    check_index_xml(&fs::read_to_string(&local_xml)?)
+                        .map_err(|reason| anyhow::anyhow!("{} не прошел проверку после замены: {}", local_xml.display(), reason))?;
+                }
+                report.index = Some(IndexDiff::between(existing_xml.as_deref(), &merged_xml, &self.config.project.id));
+                report.verification.push(self.index_check(&local_xml, fs::read_to_string(&local_xml).ok(), &merged_xml));
+                if let Some(art) = artifacts.iter().max() {
+                    for command in self.post_deploy_commands(art)? {
+                        info!("🖥️ (mock) $ {}", command.command);
+                        report.remote_commands.push(RemoteCommandResult {
+                            command: command.command,
+                            exit_code: None,
+                            stdout: String::new(),
+                            stderr: String::new(),
+                            duration_ms: 0,
+                            allow_failure: command.allow_failure,
+                        });
+                    }
+                }
             }
             Ok(())
         })();
 
         if let Err(e) = res {
             warn!("Ошибка деплоя: {}", e);
-            if rollback_on_failure {
-                let _ = self.rollback_uploaded(uploaded);
+            // По истечении дедлайна и по Ctrl-C откатываемся всегда, чтобы не оставить сервер в промежуточном состоянии
+            let deadline_hit = e.is::<DeadlineExceeded>();
+            let interrupted = e.is::<Interrupted>();
+            if rollback_on_failure || deadline_hit || interrupted {
+                let removed = self.rollback_uploaded(uploaded);
+                if interrupted {
+                    for (path, ok) in removed {
+                        let action = if ok { "Удален загруженный файл" } else { "Не удалось удалить загруженный файл" };
+                        interrupt::record(format!("{} {}", action, path), ok);
+                    }
+                }
                 #[cfg(feature = "ssh")]
                 {
-                    // Попытаться восстановить xml из .bak
-                    if let Ok(session) = self.ssh_connect() {
-                        if let Ok(sftp) = session.sftp() {
-                            let bak_path = PathBuf::from(format!("{}.bak", xml_remote.display()));
-                            let _ = sftp.rename(&bak_path, &xml_remote, None);
+                    // Восстанавливаются только бэкапы этого деплоя: .bak от прошлых запусков устарели
+                    if let Some(sftp) = self.ssh_connect().ok().and_then(|session| session.sftp().ok()) {
+                        let xml_bak = PathBuf::from(format!("{}.bak", xml_remote.display()));
+                        for bak_path in report.backups.iter().filter(|bak| xml_backup_done || **bak != xml_bak) {
+                            let Some(path) = bak_path.to_str().and_then(|p| p.strip_suffix(".bak")).map(PathBuf::from) else {
+                                continue;
+                            };
+                            let restored = sftp.rename(bak_path, &path, Some(ssh2::RenameFlags::OVERWRITE)).is_ok();
+                            if interrupted {
+                                let action = if restored { "восстановлен" } else { "не удалось восстановить" };
+                                interrupt::record(format!("{}: {} из {}", path.display(), action, bak_path.display()), restored);
+                            }
                         }
                     }
                 }
+                if interrupted {
+                    self.remove_temp_files();
+                }
             }
             return Err(e);
         }
 
-        info!("✅ Деплой завершен");
-        Ok(())
+        if report.already_deployed {
+            info!("✅ Уже опубликовано: артефакт и запись в {} совпадают, деплой пропущен", xml_remote.display());
+        } else {
+            info!("✅ Деплой завершен");
+        }
+        report.duration_ms = started.elapsed().as_millis() as u64;
+        Ok(report)
+    }
+
+    /// Сервер, канал и индекс деплоя
+    fn target(&self) -> DeployTarget {
+        DeployTarget {
+            transport: if cfg!(feature = "ssh") { "ssh" } else { "mock" }.to_string(),
+            host: format!("{}@{}", self.config.repository.ssh_user, self.config.repository.ssh_host),
+            channel: self.layout().channel().to_string(),
+            xml_path: PathBuf::from(&self.config.repository.xml_path),
+        }
+    }
+
+    /// Индекс, прочитанный после записи, совпадает с записанным
+    fn index_check(&self, path: &Path, written: Option<String>, expected: &str) -> VerificationCheck {
+        let name = path.display().to_string();
+        match written {
+            Some(xml) if xml == expected => VerificationCheck::new(name, true, "индекс записан полностью"),
+            Some(_) => VerificationCheck::new(name, false, "содержимое индекса отличается от записанного"),
+            None => VerificationCheck::new(name, false, "индекс не удалось прочитать после записи"),
+        }
+    }
+
+    /// Проверка индекса на сервере после замены: команда `[repository.validate_index]`
+    /// или чтение по SFTP и встроенная проверка XML. `Err` — причина для отчета
+    #[cfg(feature = "ssh")]
+    fn validate_remote_index(&self, session: &ssh2::Session, sftp: &ssh2::Sftp, xml_remote: &Path) -> std::result::Result<(), String> {
+        let Some(command) = &self.config.repository.validate_index.command else {
+            let content = self.read_remote_file(sftp, xml_remote).ok_or("файл не удалось прочитать")?;
+            return check_index_xml(&content);
+        };
+        let command = RemoteCommand {
+            command: checksums::shell_command(command, &[("xml_path", xml_remote)]),
+            allow_failure: false,
+        };
+        info!("🖥️ $ {}", command.command);
+        let result = remote_exec::exec(session, &command).map_err(|e| format!("{:#}", e))?;
+        if result.succeeded() {
+            return Ok(());
+        }
+        Err(format!("команда `{}` завершилась с кодом {:?}: {}", command.command, result.exit_code, result.stderr.trim()))
+    }
+
+    /// Переносит опубликованные артефакты в раскладку текущего шаблона deploy_path
+    /// (например, из плоского каталога) и переписывает их URL в updatePlugins.xml
+    pub fn migrate_layout(&self) -> Result<LayoutMigration> {
+        let Some(existing) = self.read_current_xml() else {
+            info!("updatePlugins.xml не найден — миграция раскладки не требуется");
+            return Ok(LayoutMigration { moves: Vec::new(), xml: String::new() });
+        };
+        let migration = self.layout().plan_migration(&existing, &self.config.project.name)?;
+        if migration.moves.is_empty() {
+            info!("Все артефакты уже соответствуют шаблону deploy_path");
+            return Ok(migration);
+        }
+
+        let xml_path = PathBuf::from(&self.config.repository.xml_path);
+        #[cfg(feature = "ssh")]
+        {
+            let session = self.ssh_connect()?;
+            let sftp = session.sftp().context("Не удалось открыть SFTP сессию")?;
+            for mv in &migration.moves {
+                if let Some(parent) = mv.to.parent() {
+                    self.sftp_mkdirs(&sftp, parent)?;
+                }
+                sftp.rename(&mv.from, &mv.to, None)
+                    .with_context(|| format!("Не удалось перенести {} -> {}", mv.from.display(), mv.to.display()))?;
+                info!("📁 {} -> {}", mv.from.display(), mv.to.display());
+            }
+            self.remote_atomic_update_xml(&sftp, &xml_path, &migration.xml)?;
+        }
+        #[cfg(not(feature = "ssh"))]
+        {
+            for mv in &migration.moves {
+                warn!("SSH отключен, перенос пропущен: {} -> {}", mv.from.display(), mv.to.display());
+            }
+            let local_xml = Path::new("./target/mock").join(xml_path.file_name().unwrap_or_default());
+            self.atomic_update_xml(&local_xml, &migration.xml)?;
+        }
+
+        info!("✅ Раскладка мигрирована: перенесено артефактов — {}", migration.moves.len());
+        Ok(migration)
+    }
+
+    /// Переключает updatePlugins.xml на уже загруженный артефакт версии `version` без пересборки.
+    /// С `dry_run` сервер только читается.
+    pub fn promote(&self, version: &str, dry_run: bool) -> Result<Promotion> {
+        info!("⏪ Переключение индекса на версию {}", version);
+        let xml_path = PathBuf::from(&self.config.repository.xml_path);
+        let remote_dir = self.remote_dir_for_version(version)?;
+
+        #[cfg(feature = "ssh")]
+        {
+            let session = self.ssh_connect()?;
+            let sftp = session.sftp().context("Не удалось открыть SFTP сессию")?;
+
+            // Артефакт версии должен оставаться на сервере
+            let entries = sftp.readdir(&remote_dir)
+                .with_context(|| format!("Каталог версии {} не найден на сервере: {}", version, remote_dir.display()))?;
+            let remote_path = entries
+                .into_iter()
+                .map(|(path, _)| path)
+                .find(|path| {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy();
+                    self.extract_version_from_filename(&name).as_deref() == Some(version)
+                })
+                .ok_or_else(|| anyhow::anyhow!("Артефакт версии {} не найден в {}", version, remote_dir.display()))?;
+            let size = sftp.stat(&remote_path)?.size.unwrap_or(0);
+            if size == 0 {
+                anyhow::bail!("Артефакт {} на сервере пуст", remote_path.display());
+            }
+            let download = |_: &str, dir: &Path| -> Result<PathBuf> {
+                let local = dir.join(remote_path.file_name().unwrap_or_default());
+                let mut remote = sftp.open(&remote_path)
+                    .with_context(|| format!("Не удалось открыть {} на сервере", remote_path.display()))?;
+                std::io::copy(&mut remote, &mut File::create(&local)?)
+                    .with_context(|| format!("Не удалось скачать {}", remote_path.display()))?;
+                Ok(local)
+            };
+            self.verify_promoted_artifact(version, size, &download)?;
+
+            let existing = self.read_remote_xml(&sftp, &xml_path)
+                .ok_or_else(|| anyhow::anyhow!("updatePlugins.xml не найден на сервере: {}", xml_path.display()))?;
+            let promotion = self.promote_xml(&existing, version, &remote_path)?;
+
+            if !dry_run {
+                use std::io::Write;
+                let bak_path = PathBuf::from(format!("{}.bak", xml_path.display()));
+                let mut bak = sftp.create(&bak_path)
+                    .with_context(|| format!("Не удалось создать бэкап XML {}", bak_path.display()))?;
+                bak.write_all(existing.as_bytes())
+                    .with_context(|| format!("Не удалось записать бэкап XML {}", bak_path.display()))?;
+                self.remote_atomic_update_xml(&sftp, &xml_path, &promotion.xml)?;
+            }
+            Ok(promotion)
+        }
+        #[cfg(not(feature = "ssh"))]
+        {
+            // Без SSH «сервером» служат локальный каталог сборки и ./target/mock
+            warn!("SSH отключен: наличие артефакта проверяется в каталоге сборки");
+            let artifact = self.find_artifacts()?
+                .into_iter()
+                .find(|art| {
+                    let name = art.file_name().unwrap_or_default().to_string_lossy();
+                    self.extract_version_from_filename(&name).as_deref() == Some(version)
+                })
+                .ok_or_else(|| anyhow::anyhow!("Артефакт версии {} не найден", version))?;
+            let remote_path = remote_dir.join(artifact.file_name().unwrap_or_default());
+            let copy = |_: &str, dir: &Path| -> Result<PathBuf> {
+                let local = dir.join(artifact.file_name().unwrap_or_default());
+                fs::copy(&artifact, &local)?;
+                Ok(local)
+            };
+            self.verify_promoted_artifact(version, fs::metadata(&artifact)?.len(), &copy)?;
+
+            let local_xml = Path::new("./target/mock").join(xml_path.file_name().unwrap_or_default());
+            let existing = fs::read_to_string(&local_xml)
+                .with_context(|| format!("updatePlugins.xml не найден: {}", local_xml.display()))?;
+            let promotion = self.promote_xml(&existing, version, &remote_path)?;
+            if !dry_run {
+                fs::write(format!("{}.bak", local_xml.display()), &existing)?;
+                self.atomic_update_xml(&local_xml, &promotion.xml)?;
+            }
+            Ok(promotion)
+        }
+    }
+
+    /// Файлы версии на сервере: артефакт и его приложения (`<артефакт>.<файл>`).
+    /// Без `dry_run` файлы удаляются; индекс на них ссылаться уже не должен
+    pub fn remove_version_artifact(&self, version: &str, dry_run: bool) -> Result<Vec<PathBuf>> {
+        let remote_dir = self.remote_dir_for_version(version)?;
+        #[cfg(feature = "ssh")]
+        {
+            let session = self.ssh_connect()?;
+            let sftp = session.sftp().context("Не удалось открыть SFTP сессию")?;
+            let names: Vec<String> = sftp.readdir(&remote_dir)
+                .with_context(|| format!("Каталог версии {} не найден на сервере: {}", version, remote_dir.display()))?
+                .into_iter()
+                .filter_map(|(path, _)| path.file_name().map(|n| n.to_string_lossy().to_string()))
+                .collect();
+            let artifact = names.iter()
+                .find(|name| self.extract_version_from_filename(name).as_deref() == Some(version))
+                .ok_or_else(|| anyhow::anyhow!("Артефакт версии {} не найден в {}", version, remote_dir.display()))?;
+            let files: Vec<PathBuf> = names.iter()
+                .filter(|name| *name == artifact || name.starts_with(&format!("{}.", artifact)))
+                .map(|name| remote_dir.join(name))
+                .collect();
+            if !dry_run {
+                for file in &files {
+                    sftp.unlink(file).with_context(|| format!("Не удалось удалить {} на сервере", file.display()))?;
+                    info!("🗑️ Удален {}", file.display());
+                }
+            }
+            Ok(files)
+        }
+        #[cfg(not(feature = "ssh"))]
+        {
+            // Без SSH «сервером» служит каталог сборки: артефакт ищется, но не удаляется
+            let artifact = self.find_artifacts()?
+                .into_iter()
+                .find(|art| {
+                    let name = art.file_name().unwrap_or_default().to_string_lossy();
+                    self.extract_version_from_filename(&name).as_deref() == Some(version)
+                })
+                .ok_or_else(|| anyhow::anyhow!("Артефакт версии {} не найден", version))?;
+            if !dry_run {
+                warn!("SSH отключен: артефакт {} в каталоге сборки не удаляется", artifact.display());
+            }
+            Ok(vec![remote_dir.join(artifact.file_name().unwrap_or_default())])
+        }
+    }
+
+    /// Сверяет версию внутри артефакта перед переключением индекса. ZIP берется из
+    /// локального кэша `[repository.cache]`; если на сервере файл другого размера, кэш обновляется
+    fn verify_promoted_artifact(&self, version: &str, size: u64, source: &dyn ArtifactSource) -> Result<()> {
+        let Some(cache) = ArtifactCache::from_config(&self.config) else {
+            return Ok(());
+        };
+        let mut cached = cache.get_or_fetch(version, source)?;
+        if cached.hit && fs::metadata(&cached.path)?.len() != size {
+            warn!("Артефакт {} на сервере изменился, кэш обновляется", version);
+            cache.remove(version)?;
+            cached = cache.get_or_fetch(version, source)?;
+        }
+        info!("📦 Артефакт {} {}", version, if cached.hit { "взят из кэша" } else { "скачан в кэш" });
+        version_guard::check(&cached.path, Some(version))
+    }
+
+    /// Перенаправляет запись плагина в индексе на артефакт `remote_path`.
+    /// Change-notes удаляются: они описывали заменяемую версию.
+    pub fn promote_xml(&self, existing: &str, version: &str, remote_path: &Path) -> Result<Promotion> {
+        let mut root = Element::parse(existing.as_bytes()).context("Не удалось разобрать updatePlugins.xml")?;
+        let current_id = &self.config.project.id;
+        let file_name = remote_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let url = self.layout().artifact_url(remote_path.parent().unwrap_or_else(|| Path::new("/")), &file_name);
+
+        let plugin_el = root
+            .children
+            .iter_mut()
+            .find_map(|child| match child {
+                XMLNode::Element(el) if el.name == "plugin" && el.attributes.get("id") == Some(current_id) => Some(el),
+                _ => None,
+            })
+            .ok_or_else(|| anyhow::anyhow!("В updatePlugins.xml нет записи плагина {}", current_id))?;
+
+        let from_version = plugin_el.attributes.get("version").cloned();
+        if from_version.as_deref() == Some(version) {
+            anyhow::bail!("Версия {} уже опубликована в updatePlugins.xml", version);
+        }
+        plugin_el.attributes.insert("url".to_string(), url.clone());
+        plugin_el.attributes.insert("version".to_string(), version.to_string());
+        plugin_el.children.retain(|c| !matches!(c, XMLNode::Element(el) if el.name == "change-notes"));
+
+        let mut buf = Vec::new();
+        root.write(&mut buf).context("Сериализация updatePlugins.xml не удалась")?;
+        Ok(Promotion {
+            from_version,
+            to_version: version.to_string(),
+            url,
+            remote_path: remote_path.to_path_buf(),
+            xml: String::from_utf8_lossy(&buf).to_string(),
+        })
+    }
+
+    /// Строит план деплоя: какие файлы куда загрузятся и каким станет updatePlugins.xml.
+    /// Сервер только читается.
+    pub fn plan(&self) -> Result<DeployPlan> {
+        let mut artifacts = self.find_artifacts()?;
+        if artifacts.is_empty() {
+            return Err(anyhow::anyhow!("Не найдены артефакты для деплоя"));
+        }
+        artifacts.sort();
+
+        let xml_path = PathBuf::from(&self.config.repository.xml_path);
+        let uploads = self.planned_uploads(&artifacts)?;
+        let latest = artifacts.last().unwrap();
+
+        let existing = self.read_current_xml();
+        let conflicts = match &existing {
+            // Содержимое удаленного артефакта в плане не сверяется: republish той же версии отмечается всегда
+            Some(xml) => self.detect_conflicts(xml, artifacts.last().unwrap(), || None)?,
+            None => Vec::new(),
+        };
+        let merged_xml = self.merge_repository_xml(existing.clone(), &artifacts)?;
+        let target = self.target();
+
+        Ok(DeployPlan {
+            transport: target.transport,
+            host: target.host,
+            channel: target.channel,
+            xml_backup: PathBuf::from(format!("{}.bak", xml_path.display())),
+            xml_path,
+            existing_index: existing.is_some(),
+            uploads,
+            conflicts: conflicts.iter().map(|c| c.to_string()).collect(),
+            remote_commands: self.post_deploy_commands(latest)?,
+            merged_xml,
+        })
+    }
+
+    /// Файлы деплоя: артефакты, приложения и ресурсы (рядом с последним артефактом)
+    fn planned_uploads(&self, artifacts: &[PathBuf]) -> Result<Vec<PlannedUpload>> {
+        let mut uploads = Vec::new();
+        for art in artifacts {
+            let file_name = art.file_name().unwrap().to_string_lossy().to_string();
+            uploads.push(PlannedUpload {
+                local: art.clone(),
+                remote: self.remote_dir_for(art)?.join(&file_name),
+                size: fs::metadata(art)?.len(),
+                sha256: self.sha256_file(art)?,
+            });
+        }
+
+        let Some(latest) = artifacts.iter().max() else {
+            return Ok(uploads);
+        };
+        for attachment in &self.attachments {
+            uploads.push(PlannedUpload {
+                local: attachment.clone(),
+                remote: self.attachment_remote_path(latest, attachment)?,
+                size: fs::metadata(attachment)?.len(),
+                sha256: self.sha256_file(attachment)?,
+            });
+        }
+        for resource in self.resources().all() {
+            uploads.push(PlannedUpload {
+                remote: self.resource_remote_path(latest, &resource)?,
+                size: fs::metadata(&resource)
+                    .with_context(|| format!("Файл ресурса плагина не найден: {}", resource.display()))?
+                    .len(),
+                sha256: self.sha256_file(&resource)?,
+                local: resource,
+            });
+        }
+
+        Ok(uploads)
+    }
+
+    /// Текущий updatePlugins.xml: с сервера (feature "ssh") или из target/mock
+    fn read_current_xml(&self) -> Option<String> {
+        let xml_path = PathBuf::from(&self.config.repository.xml_path);
+        #[cfg(feature = "ssh")]
+        {
+            let session = self.ssh_connect().ok()?;
+            let sftp = session.sftp().ok()?;
+            self.read_remote_xml(&sftp, &xml_path)
+        }
+        #[cfg(not(feature = "ssh"))]
+        {
+            let local_xml = Path::new("./target/mock").join(xml_path.file_name().unwrap_or_default());
+            fs::read_to_string(local_xml).ok()
+        }
+    }
+
+    /// Безопасные исправления индекса (`validate --remote --fix`): порядок записей
+    /// updatePlugins.xml и суммы опубликованных файлов, которых нет в SHA256SUMS. Расхождение
+    /// сумм не исправляется: его нужно разбирать, а не переписывать индекс
+    pub fn repair_index(&self, apply: bool) -> Result<Vec<Remediation>> {
+        let xml_path = PathBuf::from(&self.config.repository.xml_path);
+        #[cfg(feature = "ssh")]
+        {
+            let session = self.ssh_connect()?;
+            let sftp = session.sftp().context("Не удалось открыть SFTP сессию")?;
+            let xml = self.read_remote_file(&sftp, &xml_path)
+                .ok_or_else(|| anyhow::anyhow!("updatePlugins.xml не найден на сервере: {}", xml_path.display()))?;
+            let sums_path = self.sums_remote_path();
+            let artifacts = checksums::indexed_artifacts(&xml, &self.layout())?;
+            let repair = self.plan_index_repair(
+                &xml,
+                self.read_remote_file(&sftp, &sums_path).as_deref(),
+                &artifacts,
+                |remote| self.sftp_sha256(&sftp, remote).ok(),
+            )?;
+            if apply {
+                if let Some(sorted) = &repair.xml {
+                    self.remote_atomic_update_xml(&sftp, &xml_path, sorted)?;
+                }
+                if let Some((sums, _)) = &repair.sums {
+                    if let Some(command) = &self.config.repository.checksums.sign_command {
+                        use std::io::Write;
+                        let sig_remote = sums_path.with_file_name(SIG_FILE);
+                        let mut file = sftp.create(&sig_remote)
+                            .with_context(|| format!("Не удалось создать {}", sig_remote.display()))?;
+                        file.write_all(&checksums::sign(sums, command)?)
+                            .with_context(|| format!("Не удалось записать {}", sig_remote.display()))?;
+                    }
+                    self.remote_atomic_update_xml(&sftp, &sums_path, sums)?;
+                }
+            }
+            Ok(repair.remediations(&xml_path, &sums_path, apply))
+        }
+        #[cfg(not(feature = "ssh"))]
+        {
+            warn!("SSH отключен: исправляется mock-индекс в ./target/mock");
+            let local_xml = Path::new("./target/mock").join(xml_path.file_name().unwrap_or_default());
+            let xml = fs::read_to_string(&local_xml)
+                .with_context(|| format!("updatePlugins.xml не найден: {}", local_xml.display()))?;
+            let local_sums = Path::new("./target/mock").join(SUMS_FILE);
+            let layout = self.layout();
+            let mut artifacts: Vec<(String, PathBuf)> = Vec::new();
+            for art in self.find_artifacts()? {
+                let remote = self.remote_dir_for(&art)?.join(art.file_name().unwrap_or_default());
+                if let Some(path) = layout.relative_path(&remote) {
+                    artifacts.push((path, art));
+                }
+            }
+            let repair = self.plan_index_repair(
+                &xml,
+                fs::read_to_string(&local_sums).ok().as_deref(),
+                &artifacts,
+                |local| self.sha256_file(local).ok(),
+            )?;
+            if apply {
+                if let Some(sorted) = &repair.xml {
+                    self.atomic_update_xml(&local_xml, sorted)?;
+                }
+                if let Some((sums, _)) = &repair.sums {
+                    if let Some(command) = &self.config.repository.checksums.sign_command {
+                        fs::write(local_sums.with_file_name(SIG_FILE), checksums::sign(sums, command)?)?;
+                    }
+                    self.atomic_update_xml(&local_sums, sums)?;
+                }
+            }
+            Ok(repair.remediations(&local_xml, &local_sums, apply))
+        }
+    }
+
+    fn plan_index_repair(
+        &self,
+        xml: &str,
+        sums: Option<&str>,
+        artifacts: &[(String, PathBuf)],
+        sha256: impl Fn(&PathBuf) -> Option<String>,
+    ) -> Result<IndexRepair> {
+        let mut repair = IndexRepair { xml: remediation::sort_index(xml)?, sums: None };
+        if !self.config.repository.checksums.enabled {
+            return Ok(repair);
+        }
+        let mut index = match sums {
+            Some(content) => ChecksumIndex::parse(content)
+                .with_context(|| format!("Текущий {} поврежден", SUMS_FILE))?,
+            None => ChecksumIndex::default(),
+        };
+        let mut added = Vec::new();
+        for check in checksums::verify(artifacts, &index, sha256) {
+            if let (None, Some(actual)) = (&check.expected, &check.actual) {
+                index.upsert(&check.path, actual);
+                added.push(check.path);
+            }
+        }
+        if !added.is_empty() {
+            repair.sums = Some((index.render(), added));
+        }
+        Ok(repair)
+    }
+
+    /// Сверяет опубликованные артефакты с SHA256SUMS (для `validate --remote`)
+    pub fn verify_checksums(&self) -> Result<Vec<ChecksumCheck>> {
+        #[cfg(feature = "ssh")]
+        {
+            let xml_path = PathBuf::from(&self.config.repository.xml_path);
+            let session = self.ssh_connect()?;
+            let sftp = session.sftp().context("Не удалось открыть SFTP сессию")?;
+            let xml = self.read_remote_file(&sftp, &xml_path)
+                .ok_or_else(|| anyhow::anyhow!("updatePlugins.xml не найден на сервере: {}", xml_path.display()))?;
+            let sums_remote = self.sums_remote_path();
+            let sums = self.read_remote_file(&sftp, &sums_remote)
+                .ok_or_else(|| anyhow::anyhow!("{} не найден на сервере: {}", SUMS_FILE, sums_remote.display()))?;
+            let index = ChecksumIndex::parse(&sums)?;
+            let artifacts = checksums::indexed_artifacts(&xml, &self.layout())?;
+            Ok(checksums::verify(&artifacts, &index, |remote| self.sftp_sha256(&sftp, remote).ok()))
+        }
+        #[cfg(not(feature = "ssh"))]
+        {
+            // В mock-режиме updatePlugins.xml не содержит URL: сверяем артефакты каталога сборки
+            warn!("SSH отключен: {} сверяется с артефактами каталога сборки", SUMS_FILE);
+            let local_sums = Path::new("./target/mock").join(SUMS_FILE);
+            let sums = fs::read_to_string(&local_sums)
+                .with_context(|| format!("{} не найден: {}", SUMS_FILE, local_sums.display()))?;
+            let index = ChecksumIndex::parse(&sums)?;
+            let layout = self.layout();
+            let mut artifacts: Vec<(String, PathBuf)> = Vec::new();
+            for art in self.find_artifacts()? {
+                let remote = self.remote_dir_for(&art)?.join(art.file_name().unwrap_or_default());
+                if let Some(path) = layout.relative_path(&remote) {
+                    artifacts.push((path, art));
+                }
+            }
+            Ok(checksums::verify(&artifacts, &index, |local| self.sha256_file(local).ok()))
+        }
+    }
+
+    /// Ищет конфликты публикации артефакта с существующим updatePlugins.xml.
+    /// `remote_sha256` вызывается только если та же версия уже есть в индексе.
+    pub fn detect_conflicts(
+        &self,
+        existing_xml: &str,
+        artifact: &Path,
+        remote_sha256: impl FnOnce() -> Option<String>,
+    ) -> Result<Vec<DeployConflict>> {
+        let root = match Element::parse(existing_xml.as_bytes()) {
+            Ok(root) => root,
+            Err(e) => {
+                warn!("Не удалось разобрать существующий updatePlugins.xml, проверка конфликтов пропущена: {}", e);
+                return Ok(Vec::new());
+            }
+        };
+
+        let plugin_id = &self.config.project.id;
+        let file_name = artifact.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let version = self.extract_version_from_filename(&file_name);
+        let our_vendor = self.extract_meta_from_zip(artifact).ok().and_then(|m| m.vendor);
+
+        let mut conflicts = Vec::new();
+        let mut same_version_published = false;
+        for ch in &root.children {
+            let XMLNode::Element(el) = ch else { continue };
+            if el.name != "plugin" || el.attributes.get("id") != Some(plugin_id) {
+                continue;
+            }
+
+            let existing_vendor = el.get_child("vendor").and_then(|v| v.get_text()).map(|t| t.trim().to_string());
+            if let (Some(existing), Some(ours)) = (existing_vendor, our_vendor.as_ref()) {
+                if !existing.is_empty() && existing != ours.trim() {
+                    conflicts.push(DeployConflict::ForeignVendor {
+                        plugin_id: plugin_id.clone(),
+                        existing_vendor: existing,
+                        our_vendor: ours.trim().to_string(),
+                    });
+                }
+            }
+
+            if version.is_some() && el.attributes.get("version") == version.as_ref() {
+                same_version_published = true;
+            }
+        }
+
+        if same_version_published {
+            let new_sha256 = self.sha256_file(artifact)?;
+            let existing_sha256 = remote_sha256();
+            if existing_sha256.as_deref() != Some(new_sha256.as_str()) {
+                conflicts.push(DeployConflict::Republish {
+                    plugin_id: plugin_id.clone(),
+                    version: version.unwrap_or_default(),
+                    existing_sha256,
+                    new_sha256,
+                });
+            } else {
+                info!("ℹ️ Версия уже опубликована с идентичным содержимым");
+            }
+        }
+
+        Ok(conflicts)
+    }
+
+    /// Деплой уже выполнен: запись плагина в индексе совпадает с той, что записал бы деплой,
+    /// а опубликованный артефакт — побайтно с локальным. `remote_sha256` вызывается только при совпадении записи.
+    #[cfg(any(feature = "ssh", test))]
+    fn is_already_deployed(
+        &self,
+        existing_xml: &str,
+        artifacts: &[PathBuf],
+        remote_sha256: impl FnOnce() -> Option<String>,
+    ) -> Result<bool> {
+        let Some(artifact) = artifacts.iter().max() else { return Ok(false) };
+        let merged = self.merge_repository_xml(Some(existing_xml.to_string()), artifacts)?;
+        let own_entry = |xml: &str| {
+            Element::parse(xml.as_bytes()).ok().and_then(|root| self.find_existing_plugin_by_id(&root, &self.config.project.id))
+        };
+        match (own_entry(existing_xml), own_entry(&merged)) {
+            (Some(existing), Some(expected)) if existing == expected => {}
+            _ => return Ok(false),
+        }
+        Ok(remote_sha256().is_some_and(|sha| sha == self.sha256_file(artifact).unwrap_or_default()))
+    }
+
+    /// Прерывает деплой с отчетом о конфликтах, если не задан --allow-republish
+    fn resolve_conflicts(&self, conflicts: &[DeployConflict]) -> Result<()> {
+        if conflicts.is_empty() {
+            return Ok(());
+        }
+
+        if self.allow_republish {
+            for conflict in conflicts {
+                warn!("⚠️ Конфликт публикации проигнорирован (--allow-republish): {}", conflict);
+            }
+            return Ok(());
+        }
+
+        let report = conflicts
+            .iter()
+            .map(|c| format!("  - {}", c))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Err(anyhow::anyhow!(
+            "Обнаружены конфликты публикации:\n{}\nИспользуйте --allow-republish, чтобы опубликовать несмотря на конфликты",
+            report
+        ))
+    }
+
+    /// SHA256 удаленного файла через SFTP (feature "ssh")
+    #[cfg(feature = "ssh")]
+    fn sftp_sha256(&self, sftp: &ssh2::Sftp, remote: &Path) -> Result<String> {
+        let mut file = sftp.open(remote)
+            .with_context(|| format!("Не удалось открыть удаленный файл {}", remote.display()))?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher)
+            .with_context(|| format!("Ошибка чтения удаленного файла {}", remote.display()))?;
+        Ok(format!("{:x}", hasher.finalize()))
     }
 
     /// Откат изменений
@@ -220,8 +1551,9 @@ impl Deployer {
 
     /// Загрузка файла по SCP (требует feature "ssh")
     #[cfg(feature = "ssh")]
-    fn scp_upload(&self, session: &ssh2::Session, local: &Path, remote: &Path) -> Result<()> {
-        use std::io::Read;
+    fn scp_upload(&self, session: &ssh2::Session, local: &Path, remote: &Path) -> Result<UploadTiming> {
+        use std::io::{Read, Write};
+        let started = std::time::Instant::now();
         let mut file = std::fs::File::open(local)
             .with_context(|| format!("Не удалось открыть локальный файл: {}", local.display()))?;
         let metadata = file.metadata().context("Не удалось получить метаданные файла")?;
@@ -229,33 +1561,61 @@ impl Deployer {
         let mut channel = session.scp_send(remote, 0o644, metadata.len(), None)
             .with_context(|| format!("Не удалось открыть SCP для {}", remote.display()))?;
 
-        std::io::copy(&mut file, &mut channel)
-            .with_context(|| format!("Ошибка отправки файла {}", local.display()))?;
+        let progress = crate::utils::progress::ProgressBar::new_transfer(metadata.len(), resources::file_name(local));
+        let mut hasher = Sha256::new();
+        let mut buf = [0u8; 64 * 1024];
+        loop {
+            let n = file.read(&mut buf)
+                .with_context(|| format!("Ошибка чтения файла {}", local.display()))?;
+            if n == 0 { break; }
+            channel.write_all(&buf[..n])
+                .with_context(|| format!("Ошибка отправки файла {}", local.display()))?;
+            hasher.update(&buf[..n]);
+            progress.inc(n as u64);
+        }
         channel.send_eof().ok();
         channel.wait_eof().ok();
         channel.wait_close().ok();
-        Ok(())
+        progress.finish_with_message(resources::file_name(local));
+        Ok(UploadTiming {
+            remote: remote.to_path_buf(),
+            bytes: metadata.len(),
+            sha256: Some(format!("{:x}", hasher.finalize())),
+            duration_ms: Some(started.elapsed().as_millis() as u64),
+        })
     }
 
     /// Загрузка файла по SFTP (требует feature "ssh")
     #[cfg(feature = "ssh")]
-    fn sftp_upload(&self, sftp: &ssh2::Sftp, local: &Path, remote: &Path) -> Result<()> {
+    fn sftp_upload(&self, sftp: &ssh2::Sftp, local: &Path, remote: &Path) -> Result<UploadTiming> {
         use std::io::{Read, Write};
+        let started = std::time::Instant::now();
         // Открываем локальный файл
         let mut src = std::fs::File::open(local)
             .with_context(|| format!("Не удалось открыть локальный файл: {}", local.display()))?;
+        let total = src.metadata().context("Не удалось получить метаданные файла")?.len();
         // Создаём/перезаписываем удалённый
         let mut dst = sftp.create(remote)
             .with_context(|| format!("Не удалось создать удалённый файл по SFTP: {}", remote.display()))?;
         // Передача содержимого
+        let progress = crate::utils::progress::ProgressBar::new_transfer(total, resources::file_name(local));
+        let mut hasher = Sha256::new();
         let mut buf = [0u8; 64 * 1024];
         loop {
             let n = src.read(&mut buf)?;
             if n == 0 { break; }
             dst.write_all(&buf[..n])?;
+            hasher.update(&buf[..n]);
+            progress.inc(n as u64);
         }
         dst.flush().ok();
-        Ok(())
+        progress.finish_with_message(resources::file_name(local));
+        Ok(UploadTiming {
+            remote: remote.to_path_buf(),
+            bytes: total,
+            sha256: Some(format!("{:x}", hasher.finalize())),
+            duration_ms: Some(started.elapsed().as_millis() as u64),
+        })
     }
 
     /// Загрузка артефакта на сервер (feature "ssh"), безопасный no-op без фичи
@@ -263,7 +1623,7 @@ impl Deployer {
         #[cfg(feature = "ssh")]
         {
             let session = self.ssh_connect()?;
-            self.scp_upload(&session, local.as_ref(), remote.as_ref())
+            self.scp_upload(&session, local.as_ref(), remote.as_ref()).map(|_| ())
         }
         #[cfg(not(feature = "ssh"))]
         {
@@ -278,8 +1638,7 @@ impl Deployer {
     fn remote_atomic_update_xml(&self, sftp: &ssh2::Sftp, xml_remote: &Path, content: &str) -> Result<()> {
         use std::io::Write;
         // временный файл в той же директории
-        let dir = xml_remote.parent().unwrap_or_else(|| Path::new("."));
-        let tmp_remote = dir.join(format!("{}.tmp", xml_remote.file_name().and_then(|n| n.to_str()).unwrap_or("updatePlugins.xml")));
+        let tmp_remote = temp_path(xml_remote);
         // запись контента
         {
             let mut file = sftp.create(&tmp_remote)
@@ -289,11 +1648,22 @@ impl Deployer {
             file.flush().ok();
         }
         // rename поверх целевого
+        chaos::inject(ChaosPoint::SftpRename)?;
         sftp.rename(&tmp_remote, xml_remote, None)
             .with_context(|| format!("Не удалось атомарно заменить удаленный XML {}", xml_remote.display()))?;
         Ok(())
     }
 
+    /// Читает текстовый файл по SFTP, если он есть
+    #[cfg(feature = "ssh")]
+    fn read_remote_file(&self, sftp: &ssh2::Sftp, remote: &Path) -> Option<String> {
+        use std::io::Read;
+        let mut file = sftp.open(remote).ok()?;
+        let mut buf = String::new();
+        file.read_to_string(&mut buf).ok()?;
+        Some(buf)
+    }
+
     /// Читает существующий updatePlugins.xml по SFTP если есть, возвращает содержимое как String
     #[cfg(feature = "ssh")]
     fn read_remote_xml(&self, sftp: &ssh2::Sftp, xml_remote: &Path) -> Option<String> {
@@ -318,35 +1688,8 @@ impl Deployer {
 
     /// Собирает финальный updatePlugins.xml: мёрджит текущий XML с новыми артефактами.
     /// Правила: по id оставляем только одну (последнюю) версию; остальные id сохраняем.
-    #[cfg(feature = "ssh")]
-    fn build_merged_repository_xml_ssh(
-        &self,
-        sftp: &ssh2::Sftp,
-        xml_remote: &Path,
-        artifacts: &[PathBuf],
-    ) -> Result<String> {
-        // Базовый URL каталога (если в repository.url указан файл XML — отрезаем его)
-        let mut base_dir_url = self.config.repository.url.trim_end_matches('/').to_string();
-        if base_dir_url.ends_with(".xml") {
-            if let Some(pos) = base_dir_url.rfind('/') { base_dir_url.truncate(pos); }
-        }
-        let repo_root_fs = Path::new(&self.config.repository.xml_path)
-            .parent()
-            .unwrap_or_else(|| Path::new("/"));
-        let deploy_fs = Path::new(&self.config.repository.deploy_path);
-        let rel_path = deploy_fs
-            .strip_prefix(repo_root_fs)
-            .ok()
-            .and_then(|p| {
-                let s = p.components()
-                    .map(|c| c.as_os_str().to_string_lossy())
-                    .collect::<Vec<_>>()
-                    .join("/");
-                if s.is_empty() { None } else { Some(s) }
-            });
-
-        // Пробуем прочитать существующий XML
-        let existing_raw_opt = self.read_remote_xml(sftp, xml_remote);
+    pub fn merge_repository_xml(&self, existing_raw_opt: Option<String>, artifacts: &[PathBuf]) -> Result<String> {
+        let layout = self.layout();
 
         // Попытка DOM-парсинга
         if let Some(existing_raw) = existing_raw_opt.clone() {
@@ -371,10 +1714,7 @@ impl Deployer {
                 arts.sort();
                 let art = arts.last().unwrap();
                 let file_name = art.file_name().unwrap().to_string_lossy().to_string();
-                let url = match rel_path {
-                    Some(rel) => format!("{}/{}/{}", base_dir_url, rel, file_name),
-                    None => format!("{}/{}", base_dir_url, file_name),
-                };
+                let url = layout.artifact_url(&self.remote_dir_for(art)?, &file_name);
                 let version = self.extract_version_from_filename(&file_name).unwrap_or_else(|| "0.0.0".to_string());
 
                 let mut plugin_el = Element::new("plugin");
@@ -403,11 +1743,11 @@ impl Deployer {
                     }
                 }
 
-                // Сохраняем vendor/idea-version/description из старой записи этого id если она была
+                // Сохраняем name/vendor/idea-version/description из старой записи этого id если она была
                 if let Some(existing_el) = self.find_existing_plugin_by_id(&root, current_id) {
                     for child in existing_el.children {
-                        if let XMLNode::Element(mut cel) = child {
-                            if cel.name == "vendor" || cel.name == "idea-version" || cel.name == "description" {
+                        if let XMLNode::Element(cel) = child {
+                            if cel.name == "name" || cel.name == "vendor" || cel.name == "idea-version" || cel.name == "description" {
                                 plugin_el.children.push(XMLNode::Element(cel));
                             }
                         }
@@ -432,6 +1772,22 @@ impl Deployer {
                     }
                 }
 
+                // Блок иконки и ресурсов в description: старый блок заменяется или удаляется
+                let description = plugin_el.get_child("description").and_then(|d| d.get_text()).map(|t| t.to_string());
+                let updated = match (self.resources_listing(art)?, description) {
+                    (Some(listing), description) => Some(resources::with_listing(&description.unwrap_or_default(), &listing)),
+                    (None, Some(description)) => Some(resources::strip_listing(&description)).filter(|d| *d != description),
+                    (None, None) => None,
+                };
+                if let Some(updated) = updated {
+                    plugin_el.children.retain(|c| !matches!(c, XMLNode::Element(el) if el.name == "description"));
+                    self.push_cdata_child(&mut plugin_el, "description", &updated);
+                }
+
+                if let Some(notes) = &self.change_notes {
+                    self.push_cdata_child(&mut plugin_el, "change-notes", notes);
+                }
+
                 new_children.push(XMLNode::Element(plugin_el));
                 root.children = new_children;
 
@@ -448,15 +1804,16 @@ impl Deployer {
         arts.sort();
         let art = arts.last().unwrap();
         let file_name = art.file_name().unwrap().to_string_lossy().to_string();
-        let url = match rel_path {
-            Some(rel) => format!("{}/{}/{}", base_dir_url, rel, file_name),
-            None => format!("{}/{}", base_dir_url, file_name),
-        };
+        let url = layout.artifact_url(&self.remote_dir_for(art)?, &file_name);
         let version = self.extract_version_from_filename(&file_name).unwrap_or_else(|| "0.0.0".to_string());
 
+        let description = self
+            .resources_listing(art)?
+            .map(|listing| format!("<description><![CDATA[{}]]></description>", listing))
+            .unwrap_or_default();
         let plugin_snippet = format!(
-            "<plugin id=\"{}\" url=\"{}\" version=\"{}\"><name>{}</name></plugin>",
-            current_id, url, version, self.config.project.name
+            "<plugin id=\"{}\" url=\"{}\" version=\"{}\"><name>{}</name>{}</plugin>",
+            current_id, url, version, self.config.project.name, description
         );
 
         if let Some(mut existing_raw) = existing_raw_opt {
@@ -488,7 +1845,6 @@ impl Deployer {
     }
 
     /// Поиск существующего элемента plugin по id
-    #[cfg(feature = "ssh")]
     fn find_existing_plugin_by_id<'a>(&self, root: &'a Element, id: &str) -> Option<Element> {
         for ch in &root.children {
             if let XMLNode::Element(el) = ch {
@@ -504,28 +1860,23 @@ impl Deployer {
 
     /// Извлекает версию из имени файла zip вида name-1.2.3.zip
     fn extract_version_from_filename(&self, filename: &str) -> Option<String> {
-        let re = regex::Regex::new(r"-(\d+\.\d+\.\d+(?:-[A-Za-z0-9.]+)*)\.zip$").ok()?;
-        if let Some(caps) = re.captures(filename) { Some(caps.get(1).unwrap().as_str().to_string()) } else { None }
+        version_guard::version_from_filename(filename)
     }
 
     /// Атомарное обновление XML файла репозитория: запись во временный файл и замена
     pub fn atomic_update_xml<P: AsRef<Path>>(&self, xml_path: P, content: &str) -> Result<()> {
         let xml_path = xml_path.as_ref();
-        let dir = xml_path.parent().unwrap_or_else(|| Path::new("."));
 
         // Создаем временный файл в той же директории, чтобы rename был атомарным на одном FS
-        let mut tmp_path = PathBuf::from(dir);
-        let file_name = xml_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("updatePlugins.xml");
-        tmp_path.push(format!("{}.tmp", file_name));
+        let tmp_path = temp_path(xml_path);
 
         // Записываем содержимое во временный файл и синхронизируем на диск
         fs::write(&tmp_path, content)
             .with_context(|| format!("Не удалось записать временный XML: {}", tmp_path.display()))?;
 
-        // Перемещаем временный файл поверх целевого (атомарная замена на одном FS)
+        // Перемещаем временный файл поверх целевого (атомарная замена на одном FS);
+        // в режиме хаоса здесь имитируется отказ SFTP rename, чтобы проверять откат без сервера
+        chaos::inject(ChaosPoint::SftpRename)?;
         fs::rename(&tmp_path, xml_path)
             .with_context(|| format!("Не удалось атомарно заменить XML {}", xml_path.display()))?;
 
@@ -573,20 +1924,54 @@ impl Deployer {
     }
 
     /// Локальный откат загруженных файлов (при ssh — пытаемся удалить удаленные файлы)
-    fn rollback_uploaded(&self, remote_paths: Vec<String>) {
+    /// Удаляет загруженные файлы; результат по каждому пути (`true` — удален)
+    fn rollback_uploaded(&self, remote_paths: Vec<String>) -> Vec<(String, bool)> {
         #[cfg(feature = "ssh")]
         {
-            if let Ok(session) = self.ssh_connect() {
-                if let Ok(sftp) = session.sftp() {
-                    for p in remote_paths {
-                        let _ = sftp.unlink(Path::new(&p));
-                    }
+            let sftp = self.ssh_connect().ok().and_then(|session| session.sftp().ok());
+            remote_paths
+                .into_iter()
+                .map(|p| {
+                    let removed = sftp.as_ref().is_some_and(|sftp| sftp.unlink(Path::new(&p)).is_ok());
+                    (p, removed)
+                })
+                .collect()
+        }
+        #[cfg(not(feature = "ssh"))]
+        {
+            let _ = remote_paths; // no-op
+            Vec::new()
+        }
+    }
+
+    /// Удаляет временные файлы атомарной замены XML и SHA256SUMS, оставшиеся после прерывания
+    fn remove_temp_files(&self) {
+        #[cfg(feature = "ssh")]
+        {
+            let targets = [PathBuf::from(&self.config.repository.xml_path), self.sums_remote_path()];
+            let Some(sftp) = self.ssh_connect().ok().and_then(|session| session.sftp().ok()) else {
+                interrupt::record("Нет SSH-соединения: временные файлы .tmp на сервере не проверены", false);
+                return;
+            };
+            for tmp in targets.iter().map(|path| temp_path(path)) {
+                if sftp.stat(&tmp).is_ok() {
+                    let removed = sftp.unlink(&tmp).is_ok();
+                    let action = if removed { "Удален временный файл" } else { "Не удалось удалить временный файл" };
+                    interrupt::record(format!("{} {}", action, tmp.display()), removed);
                 }
             }
         }
         #[cfg(not(feature = "ssh"))]
         {
-            let _ = remote_paths; // no-op
+            let xml_name = Path::new(&self.config.repository.xml_path).file_name().unwrap_or_default().to_owned();
+            for path in [Path::new("./target/mock").join(xml_name), Path::new("./target/mock").join(SUMS_FILE)] {
+                let tmp = temp_path(&path);
+                if tmp.exists() {
+                    let removed = fs::remove_file(&tmp).is_ok();
+                    let action = if removed { "Удален временный файл" } else { "Не удалось удалить временный файл" };
+                    interrupt::record(format!("{} {}", action, tmp.display()), removed);
+                }
+            }
         }
     }
 
@@ -640,6 +2025,27 @@ impl Deployer {
 
 }
 
+/// Временный файл атомарной замены: `<имя>.tmp` в той же директории
+fn temp_path(path: &Path) -> PathBuf {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("updatePlugins.xml");
+    path.with_file_name(format!("{}.tmp", file_name))
+}
+
+/// Встроенная проверка индекса после замены: XML разбирается целиком (обрезанная загрузка
+/// не проходит), корень — `<plugins>`, у каждой записи есть id, url и version
+fn check_index_xml(content: &str) -> std::result::Result<(), String> {
+    let root = Element::parse(content.as_bytes()).map_err(|e| format!("некорректный XML: {}", e))?;
+    if root.name != "plugins" {
+        return Err(format!("корневой элемент <{}> вместо <plugins>", root.name));
+    }
+    for plugin in root.children.iter().filter_map(XMLNode::as_element).filter(|e| e.name == "plugin") {
+        if let Some(attr) = ["id", "url", "version"].into_iter().find(|a| !plugin.attributes.contains_key(*a)) {
+            return Err(format!("у записи <plugin> нет атрибута {}", attr));
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone)]
 struct PluginMeta {
     name: Option<String>,
@@ -685,4 +2091,193 @@ mod tests {
             assert!(updated.contains("plugin id=\"x\""));
         }
     }
-}
\ No newline at end of file
+
+    fn write_plugin_zip(dir: &Path, file_name: &str, vendor: &str) -> PathBuf {
+        use std::io::Write;
+        let path = dir.join(file_name);
+        let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+        zip.start_file("META-INF/plugin.xml", zip::write::FileOptions::default()).unwrap();
+        write!(zip, "<idea-plugin><name>Ride</name><vendor>{}</vendor></idea-plugin>", vendor).unwrap();
+        zip.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_detect_conflicts_foreign_vendor() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let art = write_plugin_zip(tmpdir.path(), "ride-1.2.0.zip", "MarsLab");
+        let d = Deployer::new(crate::config::parser::test_config());
+        let xml = r#"<plugins><plugin id="ru.marslab.ide.ride" url="x" version="1.0.0"><vendor>Other Inc</vendor></plugin></plugins>"#;
+
+        let conflicts = d.detect_conflicts(xml, &art, || None).unwrap();
+        assert!(matches!(conflicts.as_slice(), [DeployConflict::ForeignVendor { existing_vendor, .. }] if existing_vendor == "Other Inc"));
+        assert!(d.resolve_conflicts(&conflicts).is_err());
+        assert!(d.clone().with_allow_republish(true).resolve_conflicts(&conflicts).is_ok());
+    }
+
+    #[test]
+    fn test_detect_conflicts_republish_same_version() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let art = write_plugin_zip(tmpdir.path(), "ride-1.2.0.zip", "MarsLab");
+        let d = Deployer::new(crate::config::parser::test_config());
+        let xml = r#"<plugins><plugin id="ru.marslab.ide.ride" url="x" version="1.2.0"><vendor>MarsLab</vendor></plugin></plugins>"#;
+
+        // Другое содержимое на сервере — конфликт
+        let conflicts = d.detect_conflicts(xml, &art, || Some("deadbeef".to_string())).unwrap();
+        assert!(matches!(conflicts.as_slice(), [DeployConflict::Republish { .. }]));
+
+        // Идентичный артефакт — повторная публикация допустима
+        let sha = d.sha256_file(&art).unwrap();
+        let conflicts = d.detect_conflicts(xml, &art, || Some(sha)).unwrap();
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_is_already_deployed_requires_same_entry_and_artifact() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let art = write_plugin_zip(tmpdir.path(), "ride-1.2.0.zip", "MarsLab");
+        let d = Deployer::new(crate::config::parser::test_config());
+        let artifacts = vec![art.clone()];
+        let published = d.merge_repository_xml(Some("<plugins/>".to_string()), &artifacts).unwrap();
+        let sha = d.sha256_file(&art).unwrap();
+
+        assert!(d.is_already_deployed(&published, &artifacts, || Some(sha.clone())).unwrap());
+        // Артефакт на сервере отличается или недоступен
+        assert!(!d.is_already_deployed(&published, &artifacts, || Some("deadbeef".to_string())).unwrap());
+        assert!(!d.is_already_deployed(&published, &artifacts, || None).unwrap());
+        // Запись изменилась бы (новые change-notes) — это не повтор
+        let with_notes = d.clone().with_change_notes(Some("<p>new</p>".to_string()));
+        assert!(!with_notes.is_already_deployed(&published, &artifacts, || Some(sha.clone())).unwrap());
+    }
+
+    #[test]
+    fn test_merge_repository_xml_replaces_own_entry() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let art = write_plugin_zip(tmpdir.path(), "ride-1.3.0.zip", "MarsLab");
+        let d = Deployer::new(crate::config::parser::test_config())
+            .with_change_notes(Some("<ul><li>fix</li></ul>".to_string()));
+        let existing = r#"<plugins><plugin id="other" url="o" version="1.0.0"/><plugin id="ru.marslab.ide.ride" url="x" version="1.2.0"><vendor>MarsLab</vendor></plugin></plugins>"#;
+
+        let merged = d.merge_repository_xml(Some(existing.to_string()), &[art]).unwrap();
+        assert!(merged.contains(r#"id="other""#));
+        assert!(merged.contains(r#"version="1.3.0""#));
+        assert!(!merged.contains(r#"version="1.2.0""#));
+        assert!(merged.contains("<![CDATA[<ul><li>fix</li></ul>]]>"));
+    }
+
+    #[test]
+    fn test_updated_compatibility_uses_injected_clock() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let art = write_plugin_zip(tmpdir.path(), "ride-1.3.0.zip", "MarsLab");
+        let frozen = chrono::DateTime::parse_from_rfc3339("2024-03-01T10:00:00Z").unwrap().with_timezone(&chrono::Utc);
+        let d = Deployer::new(crate::config::parser::test_config())
+            .with_clock(std::sync::Arc::new(crate::utils::clock::FixedClock::new(frozen)));
+
+        // Повторный расчет с теми же часами дает тот же файл
+        let matrix = d.updated_compatibility(None, std::slice::from_ref(&art)).unwrap();
+        assert_eq!(matrix, d.updated_compatibility(None, &[art]).unwrap());
+        assert_eq!(
+            matrix,
+            r#"{
+  "plugins": {
+    "ru.marslab.ide.ride": [
+      {
+        "version": "1.3.0",
+        "channel": "stable",
+        "file": "ride/ride-1.3.0.zip",
+        "published_at": "2024-03-01T10:00:00Z"
+      }
+    ]
+  }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_promote_xml_repoints_own_entry() {
+        let d = Deployer::new(crate::config::parser::test_config());
+        let existing = r#"<plugins><plugin id="other" url="o" version="1.0.0"/><plugin id="ru.marslab.ide.ride" url="x" version="1.3.0"><name>Ride</name><change-notes><![CDATA[broken]]></change-notes></plugin></plugins>"#;
+
+        let promotion = d
+            .promote_xml(existing, "1.2.0", Path::new("/var/www/plugins/ride/ride-1.2.0.zip"))
+            .unwrap();
+        assert_eq!(promotion.from_version.as_deref(), Some("1.3.0"));
+        assert_eq!(promotion.url, "https://plugins.example.com/ride/ride-1.2.0.zip");
+        assert!(promotion.xml.contains(r#"version="1.2.0""#));
+        assert!(promotion.xml.contains(r#"id="other""#));
+        assert!(promotion.xml.contains("<name>Ride</name>"));
+        assert!(!promotion.xml.contains("broken"));
+
+        assert!(d.promote_xml(&promotion.xml, "1.2.0", &promotion.remote_path).is_err());
+    }
+
+    #[test]
+    fn test_plan_includes_resources_and_commands() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let out = tmpdir.path().join("build/distributions");
+        fs::create_dir_all(&out).unwrap();
+        let art = write_plugin_zip(&out, "ride-1.3.0.zip", "MarsLab");
+        let icon = tmpdir.path().join("pluginIcon.svg");
+        fs::write(&icon, "<svg/>").unwrap();
+
+        let mut config = crate::config::parser::test_config();
+        config.build.output_dir = out.display().to_string();
+        config.repository.icon = Some(icon.display().to_string());
+        config.repository.post_deploy = vec![crate::config::parser::RemoteCommandConfig {
+            command: "chmod 644 {deploy_dir}/{artifact}".to_string(),
+            allow_failure: false,
+        }];
+        let d = Deployer::new(config);
+
+        let plan = d.plan().unwrap();
+        assert_eq!(plan.remote_commands[0].command, "chmod 644 '/var/www/plugins/ride'/'ride-1.3.0.zip'");
+        let icon_upload = plan.uploads.iter().find(|u| u.local == icon).unwrap();
+        assert_eq!(icon_upload.remote, PathBuf::from("/var/www/plugins/ride/resources/pluginIcon.svg"));
+        assert!(plan.merged_xml.contains("https://plugins.example.com/ride/resources/pluginIcon.svg"));
+
+        let existing = r#"<plugins><plugin id="ru.marslab.ide.ride" url="x" version="1.2.0"><description><![CDATA[<p>Ride</p><!-- deploy-plugin:resources --><p>old</p><!-- /deploy-plugin:resources -->]]></description></plugin></plugins>"#;
+        let merged = d.merge_repository_xml(Some(existing.to_string()), &[art]).unwrap();
+        assert!(merged.contains("<p>Ride</p>"));
+        assert!(!merged.contains("<p>old</p>"));
+        assert_eq!(merged.matches("<description>").count(), 1);
+    }
+
+    #[test]
+    fn test_index_diff_summarizes_own_entry() {
+        let before = r#"<plugins><plugin id="other" version="2.0"/><plugin id="ride" url="a/ride-1.2.0.zip" version="1.2.0"><name>Ride</name><change-notes>old</change-notes></plugin></plugins>"#;
+        let after = r#"<plugins><plugin id="other" version="2.0"/><plugin id="ride" url="a/ride-1.3.0.zip" version="1.3.0"><name>Ride</name><change-notes>new</change-notes></plugin></plugins>"#;
+        let diff = IndexDiff::between(Some(before), after, "ride");
+        assert_eq!(diff.changed, ["change-notes", "url", "version"]);
+        assert_eq!(diff.to_string(), "1.2.0 → 1.3.0, изменено: change-notes, url, version; записей 2 → 2");
+
+        let created = IndexDiff::between(None, after, "ride");
+        assert_eq!((created.plugins_before, created.previous_version.as_deref()), (0, None));
+        assert!(created.changed.contains(&"name".to_string()));
+        assert!(created.to_string().starts_with("нет записи → 1.3.0"));
+    }
+
+    #[test]
+    fn test_check_index_xml_rejects_truncated_index() {
+        let xml = r#"<plugins><plugin id="ru.marslab.ide.ride" url="https://plugins.example.com/ride-1.2.0.zip" version="1.2.0"><name>Ride</name></plugin></plugins>"#;
+        assert_eq!(check_index_xml(xml), Ok(()));
+        assert!(check_index_xml(&xml[..xml.len() - 20]).unwrap_err().starts_with("некорректный XML"));
+        assert_eq!(check_index_xml("<plugin-repository/>").unwrap_err(), "корневой элемент <plugin-repository> вместо <plugins>");
+        assert_eq!(check_index_xml(r#"<plugins><plugin id="x" version="1.0"/></plugins>"#).unwrap_err(), "у записи <plugin> нет атрибута url");
+    }
+
+    #[test]
+    fn test_upload_timing_display() {
+        let upload = UploadTiming {
+            remote: PathBuf::from("/var/www/plugins/ride/ride-1.3.0.zip"),
+            bytes: 150 * 1024 * 1024,
+            sha256: None,
+            duration_ms: Some(12_000),
+        };
+        assert_eq!(upload.bytes_per_sec(), Some(150 * 1024 * 1024 / 12));
+        assert_eq!(upload.to_string(), "/var/www/plugins/ride/ride-1.3.0.zip — 150.00 MiB за 12.0 с (12.50 MiB/с)");
+
+        let mock = UploadTiming { duration_ms: None, ..upload };
+        assert!(mock.to_string().ends_with("(не передавался)"));
+    }
+}
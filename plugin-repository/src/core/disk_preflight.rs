@@ -0,0 +1,148 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::config::parser::DiskPreflightConfig;
+
+/// Свободное место файловой системы, на которой лежит путь
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiskUsage {
+    /// Идентификатор устройства: пути на одной файловой системе проверяются один раз
+    pub device: u64,
+    /// Доступно непривилегированному пользователю
+    pub free_bytes: u64,
+    /// None — файловая система не сообщает число inode (btrfs, часть сетевых)
+    pub free_inodes: Option<u64>,
+}
+
+/// Проверяемое место: проект, output_dir или временная директория
+#[derive(Debug, Clone)]
+pub struct Location {
+    pub label: &'static str,
+    pub path: PathBuf,
+    pub min_free_mb: u64,
+}
+
+/// Места для проверки перед сборкой с порогами из `[build.preflight]`
+pub fn locations(project_root: &Path, output_dir: &str, config: &DiskPreflightConfig) -> Vec<Location> {
+    vec![
+        Location { label: "проект", path: project_root.to_path_buf(), min_free_mb: config.min_free_mb },
+        Location { label: "output_dir", path: project_root.join(output_dir), min_free_mb: config.min_free_mb },
+        Location { label: "временная директория", path: std::env::temp_dir(), min_free_mb: config.min_temp_free_mb },
+    ]
+}
+
+/// Проверяет свободное место и inode; ошибка перечисляет все файловые системы, где не хватает места
+pub fn check(locations: &[Location], min_free_inodes: u64) -> Result<()> {
+    let mut measured = Vec::new();
+    for location in locations {
+        match usage(&location.path)? {
+            Some(usage) => measured.push((location, usage)),
+            None => warn!("⚠️ Свободное место для {} не определено — проверка пропущена", location.path.display()),
+        }
+    }
+    let problems = evaluate(&measured, min_free_inodes);
+    if !problems.is_empty() {
+        anyhow::bail!(
+            "Недостаточно места для сборки:\n  - {}\nОсвободите место или измените пороги в [build.preflight]",
+            problems.join("\n  - ")
+        );
+    }
+    info!("💾 Свободного места достаточно: {}", measured.iter().map(|(l, u)| format!("{} {}", l.label, format_mb(u.free_bytes))).collect::<Vec<_>>().join(", "));
+    Ok(())
+}
+
+/// Нарушения порогов; места на одной файловой системе объединяются, порог берется наибольший
+fn evaluate(measured: &[(&Location, DiskUsage)], min_free_inodes: u64) -> Vec<String> {
+    let mut devices: Vec<(DiskUsage, Vec<&Location>)> = Vec::new();
+    for (location, usage) in measured {
+        match devices.iter_mut().find(|(u, _)| u.device == usage.device) {
+            Some((_, group)) => group.push(location),
+            None => devices.push((*usage, vec![location])),
+        }
+    }
+
+    let mut problems = Vec::new();
+    for (usage, group) in devices {
+        let required = group.iter().map(|l| l.min_free_mb).max().unwrap_or(0);
+        let places = group.iter().map(|l| format!("{} ({})", l.label, l.path.display())).collect::<Vec<_>>().join(", ");
+        if usage.free_bytes < required * 1024 * 1024 {
+            problems.push(format!("{}: свободно {}, требуется {}", places, format_mb(usage.free_bytes), format_mb(required * 1024 * 1024)));
+        }
+        if let Some(inodes) = usage.free_inodes.filter(|&i| i < min_free_inodes) {
+            problems.push(format!("{}: свободно {} inode, требуется {}", places, inodes, min_free_inodes));
+        }
+    }
+    problems
+}
+
+fn format_mb(bytes: u64) -> String {
+    let mb = bytes / (1024 * 1024);
+    if mb >= 1024 {
+        format!("{:.1} ГБ", mb as f64 / 1024.0)
+    } else {
+        format!("{} МБ", mb)
+    }
+}
+
+/// statvfs ближайшего существующего предка: output_dir до первой сборки может не существовать
+#[cfg(unix)]
+fn usage(path: &Path) -> Result<Option<DiskUsage>> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::MetadataExt;
+
+    let Some(existing) = path.ancestors().find(|p| p.exists()) else {
+        return Ok(None);
+    };
+    let device = std::fs::metadata(existing)
+        .with_context(|| format!("Не удалось прочитать {}", existing.display()))?
+        .dev();
+    let c_path = CString::new(existing.as_os_str().as_bytes()).context("Путь содержит нулевой байт")?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path — корректная C-строка, stat — буфер подходящего размера
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        warn!("⚠️ statvfs({}): {}", existing.display(), std::io::Error::last_os_error());
+        return Ok(None);
+    }
+    Ok(Some(DiskUsage {
+        device,
+        free_bytes: stat.f_bavail as u64 * stat.f_frsize as u64,
+        free_inodes: (stat.f_files > 0).then_some(stat.f_favail as u64),
+    }))
+}
+
+#[cfg(not(unix))]
+fn usage(_path: &Path) -> Result<Option<DiskUsage>> {
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_groups_by_filesystem() {
+        let location = |label, min_free_mb| Location { label, path: PathBuf::from("/work"), min_free_mb };
+        let (project, output, temp) = (location("проект", 2048), location("output_dir", 2048), location("временная директория", 512));
+        let disk = |device, free_mb: u64, free_inodes| DiskUsage { device, free_bytes: free_mb * 1024 * 1024, free_inodes };
+
+        let ok = [(&project, disk(1, 4096, Some(50_000))), (&output, disk(1, 4096, Some(50_000))), (&temp, disk(2, 600, None))];
+        assert!(evaluate(&ok, 10_000).is_empty());
+
+        let full = [(&project, disk(1, 1500, Some(3_000))), (&output, disk(1, 1500, Some(3_000))), (&temp, disk(2, 100, None))];
+        assert_eq!(
+            evaluate(&full, 10_000),
+            [
+                "проект (/work), output_dir (/work): свободно 1.5 ГБ, требуется 2.0 ГБ",
+                "проект (/work), output_dir (/work): свободно 3000 inode, требуется 10000",
+                "временная директория (/work): свободно 100 МБ, требуется 512 МБ",
+            ]
+        );
+
+        // Временная директория на диске проекта: действует больший порог
+        let shared = [(&project, disk(1, 1000, None)), (&temp, disk(1, 1000, None))];
+        assert_eq!(evaluate(&shared, 10_000).len(), 1);
+        assert!(usage(Path::new("/definitely/missing/dir")).unwrap().is_some_and(|u| u.free_bytes > 0) || cfg!(not(unix)));
+    }
+}
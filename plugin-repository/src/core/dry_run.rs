@@ -0,0 +1,158 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::deployer::DeployPlan;
+
+/// Набор файлов, которые публикация создала бы при реальном запуске
+#[derive(Debug, Clone)]
+pub struct DryRunBundle {
+    pub version: String,
+    pub changelog: Option<String>,
+    pub release_notes: Option<String>,
+    /// plugin.xml после обогащения (версия, change-notes); при `--no-enrich`/`--enrich-dry-run` — из артефакта
+    pub plugin_xml: Option<String>,
+    /// Unified diff обогащения plugin.xml относительно артефакта
+    pub plugin_xml_diff: Option<String>,
+    /// Стенограмма запросов к LLM (JSON)
+    pub transcript: Option<String>,
+    /// Локаль -> HTML change-notes (`[change_notes] locales`)
+    pub localized_change_notes: BTreeMap<String, String>,
+    pub deploy_plan: DeployPlan,
+}
+
+impl DryRunBundle {
+    /// Директория бандла по умолчанию
+    pub fn default_dir(version: &str) -> PathBuf {
+        PathBuf::from("target/dry-run").join(version)
+    }
+
+    /// Записывает бандл; возвращает список созданных файлов
+    pub fn write(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Не удалось создать директорию бандла: {}", dir.display()))?;
+
+        let mut files = vec![
+            ("updatePlugins.xml".to_string(), Some(self.deploy_plan.merged_xml.clone())),
+            ("deploy-plan.json".to_string(), Some(serde_json::to_string_pretty(&self.deploy_plan)?)),
+            ("changelog.md".to_string(), self.changelog.clone()),
+            ("release-notes.md".to_string(), self.release_notes.clone()),
+            ("plugin.xml".to_string(), self.plugin_xml.clone()),
+            ("plugin.xml.diff".to_string(), self.plugin_xml_diff.clone()),
+            ("transcript.json".to_string(), self.transcript.clone()),
+        ];
+        for (locale, html) in &self.localized_change_notes {
+            files.push((format!("change-notes.{}.html", locale), Some(html.clone())));
+        }
+        files.push(("SUMMARY.md".to_string(), Some(self.summary(&files))));
+
+        let mut written = Vec::new();
+        for (name, content) in files {
+            let Some(content) = content else { continue };
+            let path = dir.join(&name);
+            fs::write(&path, content)
+                .with_context(|| format!("Не удалось записать {}", path.display()))?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    /// Краткое описание бандла для ревьюера
+    fn summary(&self, files: &[(String, Option<String>)]) -> String {
+        let plan = &self.deploy_plan;
+        let mut out = format!("# Dry run: v{}\n\n", self.version);
+        out.push_str(&format!("Транспорт: {} ({}), канал: {}\n\n", plan.transport, plan.host, plan.channel));
+
+        out.push_str("## Загрузка\n\n");
+        for upload in &plan.uploads {
+            out.push_str(&format!(
+                "- {} -> {} ({} байт, sha256 {})\n",
+                upload.local.display(),
+                upload.remote.display(),
+                upload.size,
+                upload.sha256
+            ));
+        }
+        out.push_str(&format!(
+            "- {} {} (бэкап: {})\n\n",
+            if plan.existing_index { "обновление" } else { "создание" },
+            plan.xml_path.display(),
+            plan.xml_backup.display()
+        ));
+
+        if !plan.conflicts.is_empty() {
+            out.push_str("## Конфликты\n\n");
+            for conflict in &plan.conflicts {
+                out.push_str(&format!("- {}\n", conflict));
+            }
+            out.push('\n');
+        }
+
+        if !plan.remote_commands.is_empty() {
+            out.push_str("## Команды после загрузки\n\n");
+            for command in &plan.remote_commands {
+                let note = if command.allow_failure { " (ошибка допустима)" } else { "" };
+                out.push_str(&format!("- `{}`{}\n", command.command, note));
+            }
+            out.push('\n');
+        }
+
+        out.push_str("## Файлы\n\n");
+        for (name, content) in files {
+            match content {
+                Some(_) => out.push_str(&format!("- {}\n", name)),
+                None => out.push_str(&format!("- {} — не сформирован\n", name)),
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::deployer::PlannedUpload;
+
+    #[test]
+    fn test_write_bundle() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let bundle = DryRunBundle {
+            version: "1.2.0".to_string(),
+            changelog: Some("## 1.2.0\n- fix".to_string()),
+            release_notes: None,
+            plugin_xml: Some("<idea-plugin/>".to_string()),
+            plugin_xml_diff: None,
+            transcript: None,
+            localized_change_notes: BTreeMap::from([("en".to_string(), "<ul><li>fix</li></ul>".to_string())]),
+            deploy_plan: DeployPlan {
+                transport: "mock".to_string(),
+                host: "deploy@example.com".to_string(),
+                channel: "stable".to_string(),
+                xml_path: PathBuf::from("/var/www/updatePlugins.xml"),
+                xml_backup: PathBuf::from("/var/www/updatePlugins.xml.bak"),
+                existing_index: false,
+                uploads: vec![PlannedUpload {
+                    local: PathBuf::from("build/ride-1.2.0.zip"),
+                    remote: PathBuf::from("/var/www/plugins/ride-1.2.0.zip"),
+                    size: 42,
+                    sha256: "abc".to_string(),
+                }],
+                conflicts: vec![],
+                remote_commands: vec![],
+                merged_xml: "<plugins/>".to_string(),
+            },
+        };
+
+        let written = bundle.write(temp.path()).unwrap();
+        let names: Vec<_> = written.iter().map(|p| p.file_name().unwrap().to_string_lossy().to_string()).collect();
+        assert_eq!(names, ["updatePlugins.xml", "deploy-plan.json", "changelog.md", "plugin.xml", "change-notes.en.html", "SUMMARY.md"]);
+
+        let summary = fs::read_to_string(temp.path().join("SUMMARY.md")).unwrap();
+        assert!(summary.contains("release-notes.md — не сформирован"));
+        assert!(summary.contains("plugin.xml.diff — не сформирован"));
+        let plan = fs::read_to_string(temp.path().join("deploy-plan.json")).unwrap();
+        assert!(plan.contains("/var/www/plugins/ride-1.2.0.zip"));
+        assert!(!plan.contains("merged_xml"));
+    }
+}
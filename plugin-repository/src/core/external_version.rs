@@ -0,0 +1,89 @@
+use anyhow::{Context, Result};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Переменная окружения с версией, вычисленной вне инструмента (например, в CI)
+pub const VERSION_ENV: &str = "DEPLOY_PLUGIN_VERSION";
+
+/// Откуда взята заданная извне версия
+#[derive(Debug, Clone, PartialEq)]
+pub enum VersionSource {
+    Flag,
+    File(PathBuf),
+    Env,
+}
+
+impl fmt::Display for VersionSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionSource::Flag => write!(f, "--version"),
+            VersionSource::File(path) => write!(f, "{}", path.display()),
+            VersionSource::Env => write!(f, "{}", VERSION_ENV),
+        }
+    }
+}
+
+/// Версия, заданная извне: `--version`, затем `--version-file`, затем `DEPLOY_PLUGIN_VERSION`.
+/// None — версию нужно определить автоматически.
+pub fn resolve(
+    flag: Option<&str>,
+    file: Option<&Path>,
+    env: Option<String>,
+) -> Result<Option<(String, VersionSource)>> {
+    if let Some(version) = flag {
+        return Ok(Some((version.to_string(), VersionSource::Flag)));
+    }
+    if let Some(path) = file {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Не удалось прочитать файл версии: {}", path.display()))?;
+        let version = parse(&content).with_context(|| format!("Некорректная версия в {}", path.display()))?;
+        return Ok(Some((version, VersionSource::File(path.to_path_buf()))));
+    }
+    match env.filter(|v| !v.trim().is_empty()) {
+        Some(value) => {
+            let version = parse(&value).with_context(|| format!("Некорректная версия в {}", VERSION_ENV))?;
+            Ok(Some((version, VersionSource::Env)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Первая непустая строка без комментария `#`; префикс `v` допускается
+fn parse(content: &str) -> Result<String> {
+    let line = content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .ok_or_else(|| anyhow::anyhow!("Версия не указана"))?;
+    let version = line.strip_prefix('v').unwrap_or(line);
+    semver::Version::parse(version)
+        .with_context(|| format!("'{}' не является semver версией (ожидается X.Y.Z)", line))?;
+    Ok(version.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_precedence_and_validation() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("VERSION");
+        fs::write(&file, "# computed by CI\nv1.4.0-rc.1\n").unwrap();
+
+        let (version, source) = resolve(Some("2.0.0"), Some(&file), Some("3.0.0".into())).unwrap().unwrap();
+        assert_eq!((version.as_str(), source), ("2.0.0", VersionSource::Flag));
+
+        let (version, source) = resolve(None, Some(&file), Some("3.0.0".into())).unwrap().unwrap();
+        assert_eq!((version.as_str(), source), ("1.4.0-rc.1", VersionSource::File(file.clone())));
+
+        let (version, source) = resolve(None, None, Some(" 3.0.0\n".into())).unwrap().unwrap();
+        assert_eq!((version.as_str(), source), ("3.0.0", VersionSource::Env));
+
+        assert!(resolve(None, None, Some(String::new())).unwrap().is_none());
+        assert!(resolve(None, None, Some("1.2".into())).is_err());
+        fs::write(&file, "\n").unwrap();
+        assert!(resolve(None, Some(&file), None).is_err());
+    }
+}
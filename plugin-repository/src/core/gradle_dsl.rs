@@ -0,0 +1,501 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use xmltree::Element;
+
+use crate::core::inspector::{parse_branch, CheckResult, CheckStatus};
+
+/// Скрипты сборки в порядке приоритета
+pub const BUILD_SCRIPTS: [&str; 2] = ["build.gradle.kts", "build.gradle"];
+/// plugin.xml в исходниках: since-build в нем перезаписывается Gradle-плагином
+pub const PLUGIN_XML: &str = "src/main/resources/META-INF/plugin.xml";
+
+/// Настройки IntelliJ Platform Gradle Plugin, извлеченные из скрипта сборки без запуска Gradle
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct IntellijDsl {
+    /// Скрипт, из которого прочитаны настройки
+    pub script: String,
+    /// `intellijPlatform` (Gradle-плагин 2.x) или `intellij` (1.x)
+    pub dsl: String,
+    /// Тип IDE: IC, IU, AI, ...
+    pub platform_type: Option<String>,
+    pub platform_version: Option<String>,
+    pub since_build: Option<String>,
+    pub until_build: Option<String>,
+    pub bundled_plugins: Vec<String>,
+    /// Плагины из Marketplace: `id:version`
+    pub plugins: Vec<String>,
+    /// Выражение `sandboxContainer`/`sandboxDir`, если задано
+    pub sandbox: Option<String>,
+}
+
+impl IntellijDsl {
+    /// Ветка целевой платформы: `2024.2.5` -> 242, `AI-252.25557.131` -> 252
+    pub fn platform_branch(&self) -> Option<u32> {
+        self.platform_version.as_deref().and_then(platform_branch)
+    }
+
+    pub fn platform(&self) -> Option<String> {
+        let version = self.platform_version.as_deref()?;
+        Some(match &self.platform_type {
+            Some(kind) => format!("{} {}", kind, version),
+            None => version.to_string(),
+        })
+    }
+}
+
+/// Читает скрипт сборки проекта; None — скрипта нет или IntelliJ-плагин в нем не настроен
+pub fn read(project_root: &Path) -> Result<Option<IntellijDsl>> {
+    let Some(script) = BUILD_SCRIPTS.iter().find(|name| project_root.join(name).is_file()) else {
+        return Ok(None);
+    };
+    let path = project_root.join(script);
+    let content = std::fs::read_to_string(&path).with_context(|| format!("Не удалось прочитать {}", path.display()))?;
+    let properties = std::fs::read_to_string(project_root.join("gradle.properties"))
+        .map(|p| parse_properties(&p))
+        .unwrap_or_default();
+    Ok(parse(&content, &properties).map(|dsl| IntellijDsl { script: script.to_string(), ..dsl }))
+}
+
+/// Разбор Kotlin/Groovy DSL: блоки `intellijPlatform { ... }` / `intellij { ... }`, `ideaVersion`/`patchPluginXml`
+pub fn parse(script: &str, properties: &HashMap<String, String>) -> Option<IntellijDsl> {
+    let script = strip_comments(script);
+    let vals = collect_vals(&script, properties);
+    let resolve = |expr: &str| resolve_expr(expr, &vals, properties);
+
+    let platform_blocks = blocks(&script, "intellijPlatform");
+    let legacy_blocks = blocks(&script, "intellij");
+    if platform_blocks.is_empty() && legacy_blocks.is_empty() {
+        return None;
+    }
+
+    let mut dsl = IntellijDsl::default();
+    if !platform_blocks.is_empty() {
+        dsl.dsl = "intellijPlatform".to_string();
+        for block in &platform_blocks {
+            for (name, args) in calls(block) {
+                match (name.as_str(), args.as_slice()) {
+                    ("create", [kind, version, ..]) => {
+                        dsl.platform_type = resolve(kind);
+                        dsl.platform_version = resolve(version);
+                    }
+                    ("local", [path, ..]) => dsl.platform_version = resolve(path).map(|p| format!("local:{}", p)),
+                    ("bundledPlugin" | "bundledPlugins", args) => dsl.bundled_plugins.extend(args.iter().filter_map(|a| resolve(a))),
+                    ("plugin" | "plugins", args) => dsl.plugins.extend(args.iter().filter_map(|a| resolve(a))),
+                    (product, [version, ..]) => {
+                        if let Some(kind) = product_code(product) {
+                            dsl.platform_type = Some(kind.to_string());
+                            dsl.platform_version = resolve(version);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        dsl.sandbox = property(&script, "sandboxContainer").map(|e| resolve(&e).unwrap_or(e));
+    } else {
+        dsl.dsl = "intellij".to_string();
+        for block in &legacy_blocks {
+            dsl.platform_version = dsl.platform_version.take().or_else(|| property(block, "version").and_then(|e| resolve(&e)));
+            dsl.platform_type = dsl.platform_type.take().or_else(|| property(block, "type").and_then(|e| resolve(&e)));
+            if let Some(list) = property(block, "plugins") {
+                dsl.plugins.extend(list_items(&list).iter().filter_map(|a| resolve(a)));
+            }
+        }
+        dsl.sandbox = property(&script, "sandboxDir").map(|e| resolve(&e).unwrap_or(e));
+    }
+    dsl.since_build = property(&script, "sinceBuild").and_then(|e| resolve(&e));
+    dsl.until_build = property(&script, "untilBuild").and_then(|e| resolve(&e));
+    Some(dsl)
+}
+
+/// Ветка платформы по версии IDE или номеру сборки
+pub fn platform_branch(version: &str) -> Option<u32> {
+    let version = version.trim();
+    // Префикс продукта у номера сборки: IC-, AI-, ...
+    let version = match version.split_once('-') {
+        Some((prefix, rest)) if prefix.chars().all(|c| c.is_ascii_uppercase()) => rest,
+        _ => version,
+    };
+    let mut parts = version.split('.');
+    let first: u32 = parts.next()?.parse().ok()?;
+    match first {
+        2000..=2099 => {
+            let minor: u32 = parts.next()?.parse().ok()?;
+            Some((first - 2000) * 10 + minor)
+        }
+        100..=999 => Some(first),
+        _ => None,
+    }
+}
+
+/// since-build из plugin.xml в исходниках
+pub fn plugin_xml_since_build(project_root: &Path) -> Option<String> {
+    let content = std::fs::read(project_root.join(PLUGIN_XML)).ok()?;
+    let root = Element::parse(content.as_slice()).ok()?;
+    root.get_child("idea-version")?.attributes.get("since-build").cloned()
+}
+
+/// Согласованность since-build/until-build с целевой платформой и plugin.xml
+pub fn check_compatibility(dsl: &IntellijDsl, plugin_xml_since: Option<&str>) -> Vec<CheckResult> {
+    let mut checks = Vec::new();
+    let since = dsl.since_build.as_deref().or(plugin_xml_since);
+    let since_branch = since.and_then(parse_branch);
+    let platform = dsl.platform().unwrap_or_else(|| "-".to_string());
+
+    checks.push(match (since_branch, dsl.platform_branch()) {
+        (None, _) => CheckResult::new(
+            "since-build",
+            CheckStatus::Warning,
+            vec!["since-build не задан: Gradle-плагин подставит ветку целевой платформы".to_string()],
+        ),
+        (Some(since), None) => CheckResult::new(
+            "since-build",
+            CheckStatus::Warning,
+            vec![format!("since-build {}, ветку целевой платформы ({}) определить не удалось", since, platform)],
+        ),
+        (Some(since), Some(target)) if since > target => CheckResult::new(
+            "since-build",
+            CheckStatus::Failed,
+            vec![format!("since-build {} новее целевой платформы {} ({}): плагин не установится в IDE, под которую собран", since, platform, target)],
+        ),
+        (Some(since), Some(target)) if since < target => CheckResult::new(
+            "since-build",
+            CheckStatus::Warning,
+            vec![format!("since-build {} старше целевой платформы {} ({}): API ветки {} может отсутствовать в {}", since, platform, target, target, since)],
+        ),
+        (Some(since), Some(_)) => CheckResult::new(
+            "since-build",
+            CheckStatus::Passed,
+            vec![format!("since-build {} совпадает с целевой платформой {}", since, platform)],
+        ),
+    });
+
+    let until_branch = dsl.until_build.as_deref().and_then(parse_branch);
+    checks.push(match (since_branch, until_branch) {
+        (_, None) => CheckResult::new("until-build", CheckStatus::Passed, vec!["until-build не ограничен".to_string()]),
+        (Some(since), Some(until)) if until < since => CheckResult::new(
+            "until-build",
+            CheckStatus::Failed,
+            vec![format!("until-build {} раньше since-build {}", until, since)],
+        ),
+        (_, Some(_)) => CheckResult::new(
+            "until-build",
+            CheckStatus::Passed,
+            vec![format!("совместимость до {}", dsl.until_build.as_deref().unwrap_or_default())],
+        ),
+    });
+
+    if let (Some(gradle), Some(xml)) = (dsl.since_build.as_deref(), plugin_xml_since) {
+        if gradle != xml {
+            checks.push(CheckResult::new(
+                "plugin-xml",
+                CheckStatus::Warning,
+                vec![format!("since-build в {} ({}) расходится со скриптом сборки ({}): в артефакт попадет {}", PLUGIN_XML, xml, gradle, gradle)],
+            ));
+        }
+    }
+    checks
+}
+
+/// Код типа IDE для функций-продуктов Gradle-плагина 2.x
+fn product_code(function: &str) -> Option<&'static str> {
+    Some(match function {
+        "intellijIdeaCommunity" => "IC",
+        "intellijIdeaUltimate" => "IU",
+        "androidStudio" => "AI",
+        "clion" => "CL",
+        "goland" => "GO",
+        "phpstorm" => "PS",
+        "pycharmCommunity" => "PC",
+        "pycharmProfessional" => "PY",
+        "rider" => "RD",
+        "rubymine" => "RM",
+        "webstorm" => "WS",
+        _ => return None,
+    })
+}
+
+fn parse_properties(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.starts_with('#') && !l.starts_with('!'))
+        .filter_map(|l| l.split_once('=').or_else(|| l.split_once(':')))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect()
+}
+
+/// Убирает `//` и `/* */` комментарии вне строк
+fn strip_comments(script: &str) -> String {
+    let mut result = String::with_capacity(script.len());
+    let mut chars = script.chars().peekable();
+    let mut quote: Option<char> = None;
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), _) => {
+                result.push(c);
+                if c == '\\' {
+                    if let Some(escaped) = chars.next() {
+                        result.push(escaped);
+                    }
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                result.push(c);
+            }
+            (None, '/') if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|&n| n != '\n') {
+                    chars.next();
+                }
+            }
+            (None, '/') if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for n in chars.by_ref() {
+                    if previous == '*' && n == '/' {
+                        break;
+                    }
+                    previous = n;
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Содержимое всех блоков `name { ... }` (вложенные блоки входят в содержимое)
+fn blocks<'a>(script: &'a str, name: &str) -> Vec<&'a str> {
+    let re = Regex::new(&format!(r"(?:^|[^\w.]){}\s*\{{", regex::escape(name))).unwrap();
+    re.find_iter(script)
+        .filter_map(|m| {
+            let start = m.end();
+            let mut depth = 1;
+            for (i, c) in script[start..].char_indices() {
+                match c {
+                    '{' => depth += 1,
+                    '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(&script[start..start + i]);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            None
+        })
+        .collect()
+}
+
+/// Вызовы функций `name(arg, ...)` с аргументами верхнего уровня
+fn calls(block: &str) -> Vec<(String, Vec<String>)> {
+    let re = Regex::new(r"(?:^|[^\w.])([A-Za-z_]\w*)\s*\(").unwrap();
+    re.captures_iter(block)
+        .filter_map(|caps| {
+            let start = caps.get(0)?.end();
+            Some((caps[1].to_string(), split_args(&block[start..])?))
+        })
+        .collect()
+}
+
+/// Аргументы до закрывающей скобки; None — скобка не закрыта
+fn split_args(text: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for c in text.chars() {
+        match (quote, c) {
+            (Some(q), _) => {
+                if c == q {
+                    quote = None;
+                }
+                current.push(c);
+            }
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (None, '(' | '[' | '{') => {
+                depth += 1;
+                current.push(c);
+            }
+            (None, ')') if depth == 0 => {
+                if !current.trim().is_empty() {
+                    args.push(current.trim().to_string());
+                }
+                return Some(args);
+            }
+            (None, ')' | ']' | '}') => {
+                depth -= 1;
+                current.push(c);
+            }
+            (None, ',') if depth == 0 => args.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(c),
+        }
+    }
+    None
+}
+
+/// Значение свойства: `name = expr`, `name.set(expr)` или Groovy `name expr`; последнее присваивание побеждает
+fn property(script: &str, name: &str) -> Option<String> {
+    let re = Regex::new(&format!(r"(?m)(?:^|[^\w.]){}(?:\s*=\s*|\.set\(|\s+)(.+)$", regex::escape(name))).unwrap();
+    re.captures_iter(script)
+        .map(|caps| {
+            let value = caps[1].trim();
+            if caps[0].contains(".set(") {
+                split_args(value).and_then(|a| a.into_iter().next()).unwrap_or_default()
+            } else {
+                value.to_string()
+            }
+        })
+        .filter(|v| !v.is_empty() && !v.starts_with('{'))
+        .last()
+}
+
+/// Элементы `listOf("a", "b")`, `setOf(...)` или Groovy `['a', 'b']`
+fn list_items(expr: &str) -> Vec<String> {
+    let expr = expr.trim();
+    if let Some(inner) = expr.strip_prefix('[') {
+        return split_args(&inner.replacen(']', ")", 1)).unwrap_or_default();
+    }
+    match expr.find('(') {
+        Some(open) => split_args(&expr[open + 1..]).unwrap_or_default(),
+        None => vec![expr.to_string()],
+    }
+}
+
+/// `val name = expr` (Kotlin) и `def name = expr` (Groovy) по порядку объявления
+fn collect_vals(script: &str, properties: &HashMap<String, String>) -> HashMap<String, String> {
+    let re = Regex::new(r"(?m)^\s*(?:val|def)\s+(\w+)(?:\s*:\s*[\w?]+)?\s*=\s*(.+)$").unwrap();
+    let mut vals = HashMap::new();
+    for caps in re.captures_iter(script) {
+        if let Some(value) = resolve_expr(&caps[2], &vals, properties) {
+            vals.insert(caps[1].to_string(), value);
+        }
+    }
+    vals
+}
+
+/// Значение выражения: строка, ссылка на val, свойство Gradle (`findProperty`, `providers.gradleProperty`)
+/// и `?:` со значением по умолчанию; None — значение вычисляется только при запуске Gradle
+fn resolve_expr(expr: &str, vals: &HashMap<String, String>, properties: &HashMap<String, String>) -> Option<String> {
+    let expr = expr.trim();
+    if let Some((left, right)) = expr.split_once("?:") {
+        return resolve_expr(left, vals, properties).or_else(|| resolve_expr(right, vals, properties));
+    }
+    let expr = expr.strip_prefix('(').and_then(|e| e.strip_suffix(')')).unwrap_or(expr).trim();
+    let expr = expr.trim_end_matches(" as String?").trim_end_matches(" as String").trim();
+
+    let quoted = expr
+        .strip_prefix('"')
+        .and_then(|e| e.strip_suffix('"'))
+        .or_else(|| expr.strip_prefix('\'').and_then(|e| e.strip_suffix('\'')));
+    if let Some(text) = quoted {
+        if !text.contains('$') {
+            return Some(text.to_string());
+        }
+        let interpolation = Regex::new(r"\$\{?(\w+)\}?").unwrap();
+        let mut missing = false;
+        let result = interpolation.replace_all(text, |caps: &regex::Captures| {
+            vals.get(&caps[1]).or_else(|| properties.get(&caps[1])).cloned().unwrap_or_else(|| {
+                missing = true;
+                String::new()
+            })
+        });
+        return (!missing).then(|| result.to_string());
+    }
+
+    let gradle_property = Regex::new(r#"^(?:project\.|providers\.)?(?:findProperty|gradleProperty|property|properties)\(\s*["']([^"']+)["']\s*\)"#).unwrap();
+    if let Some(caps) = gradle_property.captures(expr) {
+        return properties.get(&caps[1]).cloned();
+    }
+    if expr.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return vals.get(expr).cloned();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_platform_and_legacy_dsl() {
+        let script = r#"
+            plugins { id("org.jetbrains.intellij.platform") version "2.7.1" }
+            repositories { intellijPlatform { defaultRepositories() } }
+            dependencies {
+                intellijPlatform {
+                    val ideProduct = (project.findProperty("ideProduct") as String?) ?: "IC"
+                    val ideVersion = (project.findProperty("ideVersion") as String?) ?: "2024.2.5"
+                    create(ideProduct, ideVersion)
+                    bundledPlugin("com.intellij.java")
+                    // bundledPlugin("org.jetbrains.kotlin")
+                    plugins("org.intellij.plugins.markdown:242.20224.159", "com.example:1.0")
+                }
+            }
+            intellijPlatform {
+                sandboxContainer = layout.projectDirectory.dir("sandbox")
+                pluginConfiguration {
+                    version = "1.0"
+                    ideaVersion {
+                        sinceBuild = "243"
+                        untilBuild = provider { null }
+                    }
+                }
+            }
+        "#;
+        let properties = HashMap::from([("ideVersion".to_string(), "2024.3.1".to_string())]);
+        let dsl = parse(script, &properties).unwrap();
+        assert_eq!(dsl.dsl, "intellijPlatform");
+        assert_eq!(dsl.platform().as_deref(), Some("IC 2024.3.1"));
+        assert_eq!(dsl.platform_branch(), Some(243));
+        assert_eq!(dsl.bundled_plugins, ["com.intellij.java"]);
+        assert_eq!(dsl.plugins, ["org.intellij.plugins.markdown:242.20224.159", "com.example:1.0"]);
+        assert_eq!(dsl.since_build.as_deref(), Some("243"));
+        assert_eq!(dsl.until_build, None);
+        assert_eq!(dsl.sandbox.as_deref(), Some("layout.projectDirectory.dir(\"sandbox\")"));
+
+        let statuses = |checks: Vec<CheckResult>| checks.into_iter().map(|c| (c.name, c.status)).collect::<Vec<_>>();
+        assert_eq!(
+            statuses(check_compatibility(&dsl, None)),
+            [("since-build".to_string(), CheckStatus::Passed), ("until-build".to_string(), CheckStatus::Passed)]
+        );
+        let older_platform = parse(script, &HashMap::new()).unwrap();
+        assert_eq!(check_compatibility(&older_platform, None)[0].status, CheckStatus::Failed);
+        assert_eq!(check_compatibility(&dsl, Some("231"))[2].name, "plugin-xml");
+
+        let legacy = r#"
+            def platformVersion = '2023.1.5'
+            intellij {
+                version = platformVersion
+                type = 'IU'
+                plugins = ['java', 'Kotlin']
+                sandboxDir = "$buildDir/sandbox"
+            }
+            patchPluginXml {
+                sinceBuild = '231'
+                untilBuild = '232.*'
+            }
+        "#;
+        let dsl = parse(legacy, &HashMap::new()).unwrap();
+        assert_eq!(dsl.dsl, "intellij");
+        assert_eq!(dsl.platform().as_deref(), Some("IU 2023.1.5"));
+        assert_eq!(dsl.plugins, ["java", "Kotlin"]);
+        assert_eq!(dsl.until_build.as_deref(), Some("232.*"));
+        assert_eq!(dsl.sandbox.as_deref(), Some("\"$buildDir/sandbox\""));
+        assert!(check_compatibility(&dsl, None).iter().all(|c| c.status == CheckStatus::Passed));
+
+        assert_eq!(platform_branch("AI-252.25557.131.2521.14344949"), Some(252));
+        assert_eq!(platform_branch("LATEST-EAP-SNAPSHOT"), None);
+        assert!(parse("plugins { id(\"java\") }", &HashMap::new()).is_none());
+    }
+}
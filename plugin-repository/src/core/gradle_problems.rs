@@ -0,0 +1,231 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fmt;
+
+/// Отчет Gradle Problems API (Gradle 8.x), относительно корня проекта
+pub const PROBLEMS_REPORT: &str = "build/reports/problems/problems-report.html";
+
+/// Сколько первопричин попадает в BuildResult.errors
+pub const MAX_ROOT_CAUSES: usize = 10;
+
+const REPORT_DATA_START: &str = "// begin-report-data";
+const REPORT_DATA_END: &str = "// end-report-data";
+
+/// Ошибка сборки с привязкой к исходнику (если известна)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildProblem {
+    pub message: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    /// Сколько раз ошибка встретилась в выводе и отчете
+    pub count: usize,
+}
+
+impl BuildProblem {
+    fn new(message: &str, file: Option<&str>, line: Option<u32>) -> Self {
+        Self {
+            message: message.trim().to_string(),
+            file: file.map(|f| f.trim_start_matches("file://").to_string()),
+            line,
+            count: 1,
+        }
+    }
+
+    fn same_as(&self, other: &BuildProblem) -> bool {
+        self.message == other.message && self.file == other.file && self.line == other.line
+    }
+}
+
+impl fmt::Display for BuildProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{}:{}: {}", file, line, self.message)?,
+            (Some(file), None) => write!(f, "{}: {}", file, self.message)?,
+            _ => write!(f, "{}", self.message)?,
+        }
+        if self.count > 1 {
+            write!(f, " (×{})", self.count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Ошибки компиляторов (Kotlin `e:`, javac `error:`) и блоки `* What went wrong:` из вывода Gradle
+pub fn parse_output(output: &str) -> Vec<BuildProblem> {
+    let mut problems = Vec::new();
+    let lines: Vec<&str> = output.lines().collect();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim_end();
+        if let Some(problem) = parse_compiler_line(line) {
+            problems.push(problem);
+        } else if line.trim() == "* What went wrong:" {
+            let block: Vec<&str> = lines[i + 1..]
+                .iter()
+                .map(|l| l.trim())
+                .take_while(|l| !l.is_empty() && !l.starts_with("* "))
+                .collect();
+            i += block.len();
+            if let Some(problem) = parse_failure_block(&block) {
+                problems.push(problem);
+            }
+        }
+        i += 1;
+    }
+    problems
+}
+
+/// `e: file:///src/Foo.kt:12:5 msg`, `e: /src/Foo.kt: (12, 5): msg`, `/src/Foo.java:12: error: msg`
+fn parse_compiler_line(line: &str) -> Option<BuildProblem> {
+    if let Some(rest) = line.strip_prefix("e: ") {
+        // Kotlin 1.9+: путь:строка:колонка сообщение
+        if let Some((location, message)) = rest.split_once(' ') {
+            let mut parts = location.rsplitn(3, ':');
+            let (_column, line_no, file) = (parts.next(), parts.next(), parts.next());
+            if let (Some(file), Some(line_no)) = (file, line_no.and_then(|l| l.parse().ok())) {
+                if !location.ends_with(':') {
+                    return Some(BuildProblem::new(message, Some(file), Some(line_no)));
+                }
+            }
+        }
+        // Старый формат: путь: (строка, колонка): сообщение
+        if let Some((file, rest)) = rest.split_once(": (") {
+            if let Some((position, message)) = rest.split_once("): ") {
+                let line_no = position.split(',').next().and_then(|l| l.trim().parse().ok());
+                return Some(BuildProblem::new(message, Some(file), line_no));
+            }
+        }
+        return Some(BuildProblem::new(rest, None, None));
+    }
+
+    let (location, message) = line.split_once(": error: ")?;
+    let (file, line_no) = location.rsplit_once(':')?;
+    Some(BuildProblem::new(message, Some(file), Some(line_no.parse().ok()?)))
+}
+
+/// Первая строка — задача, вложенные `> ...` — цепочка причин; берем самую глубокую
+fn parse_failure_block(block: &[&str]) -> Option<BuildProblem> {
+    let head = block.first()?.trim_end_matches('.');
+    let cause = block.iter().rev().find_map(|l| l.strip_prefix("> ")).map(|c| c.trim_start_matches("> ").trim());
+    let message = match cause {
+        Some(cause) if !cause.is_empty() => format!("{}: {}", head, cause),
+        _ => head.to_string(),
+    };
+    Some(BuildProblem::new(&message, None, None))
+}
+
+/// Ошибки из problems-report.html (JSON между маркерами report-data); предупреждения пропускаются
+pub fn parse_problems_report(html: &str) -> Result<Vec<BuildProblem>> {
+    let start = html.find(REPORT_DATA_START).context("В отчете Gradle нет данных о проблемах")?;
+    let data = &html[start + REPORT_DATA_START.len()..];
+    let data = &data[..data.find(REPORT_DATA_END).context("Данные отчета Gradle не завершены")?];
+    // Данные обернуты в JS-функцию: ищем первый JSON-объект со списком diagnostics
+    let report: Value = data
+        .match_indices('{')
+        .find_map(|(from, _)| {
+            let value = serde_json::Deserializer::from_str(&data[from..]).into_iter::<Value>().next()?.ok()?;
+            value.get("diagnostics").is_some().then_some(value)
+        })
+        .context("Не удалось разобрать JSON отчета Gradle")?;
+
+    let diagnostics = report["diagnostics"].as_array().cloned().unwrap_or_default();
+    Ok(diagnostics
+        .iter()
+        .filter(|d| d["severity"].as_str().is_none_or(|s| s.eq_ignore_ascii_case("error")))
+        .filter_map(|d| {
+            let message = text_of(&d["problem"]).or_else(|| text_of(&d["contextualLabel"]))?;
+            let location = d["locations"].as_array().and_then(|l| l.iter().find(|l| l["path"].is_string()));
+            Some(BuildProblem::new(
+                &message,
+                location.and_then(|l| l["path"].as_str()),
+                location.and_then(|l| l["line"].as_u64()).map(|l| l as u32),
+            ))
+        })
+        .collect())
+}
+
+/// Текст фрагментов `[{"text": ...}]` или строки
+fn text_of(value: &Value) -> Option<String> {
+    let text = match value {
+        Value::String(s) => s.clone(),
+        Value::Array(parts) => parts.iter().filter_map(|p| p["text"].as_str()).collect::<Vec<_>>().join(""),
+        _ => return None,
+    };
+    (!text.trim().is_empty()).then_some(text)
+}
+
+/// Ссылка на build scan из вывода `--scan` (scans.gradle.com или собственный Develocity)
+pub fn scan_url(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .find(|w| w.starts_with("https://") && w.contains("/s/"))
+        .map(str::to_string)
+}
+
+/// Первопричины: дубликаты схлопываются, ошибки с исходником идут раньше сообщений о задачах
+pub fn root_causes(problems: Vec<BuildProblem>, limit: usize) -> Vec<BuildProblem> {
+    let mut unique: Vec<BuildProblem> = Vec::new();
+    for problem in problems {
+        match unique.iter_mut().find(|p| p.same_as(&problem)) {
+            Some(existing) => existing.count += problem.count,
+            None => unique.push(problem),
+        }
+    }
+    // Стабильная сортировка сохраняет порядок появления внутри группы
+    unique.sort_by_key(|p| p.file.is_none());
+    unique.truncate(limit);
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "\
+> Task :compileKotlin FAILED
+e: file:///work/ride/src/main/kotlin/Chat.kt:12:5 Unresolved reference: sendAsync
+e: file:///work/ride/src/main/kotlin/Chat.kt:12:5 Unresolved reference: sendAsync
+w: file:///work/ride/src/main/kotlin/Old.kt:3:1 'Foo' is deprecated
+e: /work/ride/src/main/kotlin/Agent.kt: (40, 9): Type mismatch
+/work/ride/src/main/java/Util.java:7: error: cannot find symbol
+
+FAILURE: Build failed with an exception.
+
+* What went wrong:
+Execution failed for task ':compileKotlin'.
+> A failure occurred while executing KotlinCompileWorkAction
+   > Compilation error. See log for more details
+
+* Try:
+> Run with --stacktrace option to get the stack trace.
+
+Publishing build scan...
+https://gradle.com/s/abcd1234
+";
+
+    #[test]
+    fn test_root_causes_from_output_and_report() {
+        let mut problems = parse_output(OUTPUT);
+        let report = r#"<html><script>
+// begin-report-data
+function problemsReport() { return ({"diagnostics":[
+  {"severity":"ERROR","problem":[{"text":"Unresolved reference: sendAsync"}],"locations":[{"path":"/work/ride/src/main/kotlin/Chat.kt","line":12}]},
+  {"severity":"WARNING","problem":[{"text":"Deprecated Gradle feature"}]}
+]}); }
+// end-report-data
+</script></html>"#;
+        problems.extend(parse_problems_report(report).unwrap());
+
+        let causes: Vec<String> = root_causes(problems, MAX_ROOT_CAUSES).iter().map(|p| p.to_string()).collect();
+        assert_eq!(
+            causes,
+            [
+                "/work/ride/src/main/kotlin/Chat.kt:12: Unresolved reference: sendAsync (×3)",
+                "/work/ride/src/main/kotlin/Agent.kt:40: Type mismatch",
+                "/work/ride/src/main/java/Util.java:7: cannot find symbol",
+                "Execution failed for task ':compileKotlin': Compilation error. See log for more details",
+            ]
+        );
+        assert_eq!(scan_url(OUTPUT).as_deref(), Some("https://gradle.com/s/abcd1234"));
+    }
+}
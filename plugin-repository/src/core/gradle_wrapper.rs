@@ -0,0 +1,222 @@
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::config::parser::{ProxyConfig, WrapperConfig};
+use crate::utils::network::NetworkUtils;
+
+pub const WRAPPER_JAR: &str = "gradle/wrapper/gradle-wrapper.jar";
+pub const WRAPPER_PROPERTIES: &str = "gradle/wrapper/gradle-wrapper.properties";
+
+/// Таймаут загрузки дистрибутива Gradle (~150 МБ)
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+/// Таймаут запроса контрольной суммы
+const CHECKSUM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Чем подтвержден gradle-wrapper.jar
+#[derive(Debug, Clone, PartialEq)]
+pub enum WrapperTrust {
+    /// Совпал с официальной суммой для версии Gradle из gradle-wrapper.properties
+    Official { version: String },
+    /// Сумма явно разрешена в `[build.wrapper] allowed_checksums`
+    Allowed,
+}
+
+/// Версия Gradle из `distributionUrl` (`.../gradle-8.7-bin.zip` -> `8.7`)
+pub fn distribution_version(properties: &str) -> Option<String> {
+    let url = properties
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("distributionUrl"))
+        .find_map(|rest| rest.trim_start().strip_prefix('=').or_else(|| rest.trim_start().strip_prefix(':')))?
+        .trim()
+        .replace("\\:", ":");
+    let file = url.rsplit('/').next()?;
+    let version = file.strip_prefix("gradle-")?;
+    let version = version.strip_suffix("-bin.zip").or_else(|| version.strip_suffix("-all.zip"))?;
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+/// Сверяет сумму wrapper-а: сначала явно разрешенные, затем официальная (если удалось получить)
+pub fn check_wrapper(
+    actual: &str,
+    version: Option<&str>,
+    official: Option<&str>,
+    allowed: &[String],
+) -> Result<WrapperTrust> {
+    if allowed.iter().any(|sum| sum.trim().eq_ignore_ascii_case(actual)) {
+        return Ok(WrapperTrust::Allowed);
+    }
+    match (version, official) {
+        (Some(version), Some(official)) if official.trim().eq_ignore_ascii_case(actual) => {
+            Ok(WrapperTrust::Official { version: version.to_string() })
+        }
+        (Some(version), Some(official)) => anyhow::bail!(
+            "{} не совпадает с официальным wrapper Gradle {} (sha256 {} != {}). \
+             Перегенерируйте wrapper (`gradle wrapper`) или добавьте сумму в [build.wrapper] allowed_checksums, \
+             если jar получен из доверенного источника",
+            WRAPPER_JAR,
+            version,
+            actual,
+            official.trim()
+        ),
+        (None, _) => anyhow::bail!(
+            "Не удалось определить версию Gradle из {}: сумма {} ({}) не может быть сверена",
+            WRAPPER_PROPERTIES,
+            WRAPPER_JAR,
+            actual
+        ),
+        (Some(version), None) => anyhow::bail!(
+            "Официальная сумма wrapper Gradle {} недоступна: {} ({}) не может быть сверен",
+            version,
+            WRAPPER_JAR,
+            actual
+        ),
+    }
+}
+
+/// Проверка Gradle wrapper и загрузка зафиксированной версии Gradle
+pub struct GradleWrapper<'a> {
+    config: &'a WrapperConfig,
+    proxy: &'a ProxyConfig,
+    project_root: &'a Path,
+}
+
+impl<'a> GradleWrapper<'a> {
+    pub fn new(config: &'a WrapperConfig, proxy: &'a ProxyConfig, project_root: &'a Path) -> Self {
+        Self { config, proxy, project_root }
+    }
+
+    /// Проверяет gradle-wrapper.jar по официальному списку сумм (защита цепочки поставки)
+    pub async fn validate(&self) -> Result<WrapperTrust> {
+        let jar = self.project_root.join(WRAPPER_JAR);
+        let actual = sha256_file(&jar).with_context(|| format!("Gradle wrapper без {}", WRAPPER_JAR))?;
+        let version = std::fs::read_to_string(self.project_root.join(WRAPPER_PROPERTIES))
+            .ok()
+            .and_then(|p| distribution_version(&p));
+
+        // Разрешенная сумма не требует сети
+        if let Ok(trust @ WrapperTrust::Allowed) = check_wrapper(&actual, None, None, &self.config.allowed_checksums) {
+            return Ok(trust);
+        }
+
+        let official = match &version {
+            Some(version) => {
+                let url = format!("{}/gradle-{}-wrapper.jar.sha256", self.base_url(), version);
+                match self.fetch_text(&url).await {
+                    Ok(sum) => Some(sum),
+                    Err(e) => {
+                        warn!("⚠️ Не удалось получить официальную сумму wrapper: {:#}", e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+        check_wrapper(&actual, version.as_deref(), official.as_deref(), &self.config.allowed_checksums)
+    }
+
+    /// Скачивает и распаковывает Gradle `version` в `.deploy-plugin/gradle/`; возвращает путь к `bin/gradle`.
+    /// Архив сверяется с официальной суммой дистрибутива
+    pub async fn provision(&self, version: &str) -> Result<PathBuf> {
+        let root = self.project_root.join(".deploy-plugin").join("gradle");
+        let home = root.join(format!("gradle-{}", version));
+        let bin = home.join("bin").join(if cfg!(windows) { "gradle.bat" } else { "gradle" });
+        if bin.exists() {
+            info!("🐘 Gradle {} из {}", version, home.display());
+            return Ok(bin);
+        }
+
+        let url = format!("{}/gradle-{}-bin.zip", self.base_url(), version);
+        info!("🐘 Загрузка Gradle {}: {}", version, url);
+        let expected = self.fetch_text(&format!("{}.sha256", url)).await?;
+        let client = NetworkUtils::http_client(self.proxy, DOWNLOAD_TIMEOUT)?;
+        let archive = client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .with_context(|| format!("Не удалось скачать {}", url))?
+            .bytes()
+            .await
+            .with_context(|| format!("Ошибка загрузки {}", url))?;
+
+        let actual = format!("{:x}", Sha256::digest(&archive));
+        if !expected.trim().eq_ignore_ascii_case(&actual) {
+            anyhow::bail!("Дистрибутив Gradle {} поврежден или подменен (sha256 {} != {})", version, actual, expected.trim());
+        }
+
+        std::fs::create_dir_all(&root).with_context(|| format!("Не удалось создать {}", root.display()))?;
+        zip::ZipArchive::new(std::io::Cursor::new(archive))
+            .and_then(|mut zip| zip.extract(&root))
+            .with_context(|| format!("Не удалось распаковать Gradle {} в {}", version, root.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&bin, std::fs::Permissions::from_mode(0o755))
+                .with_context(|| format!("Не удалось сделать исполняемым {}", bin.display()))?;
+        }
+        if !bin.exists() {
+            anyhow::bail!("В дистрибутиве Gradle {} нет {}", version, bin.display());
+        }
+        Ok(bin)
+    }
+
+    fn base_url(&self) -> &str {
+        self.config.distributions_url.trim_end_matches('/')
+    }
+
+    async fn fetch_text(&self, url: &str) -> Result<String> {
+        let client = NetworkUtils::http_client(self.proxy, CHECKSUM_TIMEOUT)?;
+        let text = client
+            .get(url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .with_context(|| format!("Запрос {} не удался", url))?
+            .text()
+            .await
+            .with_context(|| format!("Не удалось прочитать ответ {}", url))?;
+        Ok(text.trim().to_string())
+    }
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path).with_context(|| format!("Не удалось открыть {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).with_context(|| format!("Ошибка чтения {}", path.display()))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distribution_version_and_checksum_policy() {
+        let properties = "distributionBase=GRADLE_USER_HOME\n\
+                          distributionUrl=https\\://services.gradle.org/distributions/gradle-8.7-bin.zip\n";
+        assert_eq!(distribution_version(properties).as_deref(), Some("8.7"));
+        assert_eq!(
+            distribution_version("distributionUrl = https://mirror.corp/gradle-8.10.2-all.zip").as_deref(),
+            Some("8.10.2")
+        );
+        assert_eq!(distribution_version("zipStorePath=wrapper/dists"), None);
+
+        let official = "a".repeat(64);
+        assert_eq!(
+            check_wrapper(&official, Some("8.7"), Some(&format!("{}\n", official)), &[]).unwrap(),
+            WrapperTrust::Official { version: "8.7".to_string() }
+        );
+        let tampered = "b".repeat(64);
+        let err = check_wrapper(&tampered, Some("8.7"), Some(&official), &[]).unwrap_err();
+        assert!(err.to_string().contains("не совпадает с официальным wrapper Gradle 8.7"));
+        assert_eq!(
+            check_wrapper(&tampered, Some("8.7"), Some(&official), &[tampered.to_uppercase()]).unwrap(),
+            WrapperTrust::Allowed
+        );
+        assert!(check_wrapper(&official, Some("8.7"), None, &[]).is_err());
+        assert!(check_wrapper(&official, None, None, &[]).is_err());
+    }
+}
@@ -0,0 +1,428 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+use xmltree::Element;
+
+/// Порог размера ресурса по умолчанию (10 MB)
+pub const DEFAULT_MAX_RESOURCE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Статус отдельной проверки артефакта
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Passed,
+    Warning,
+    Failed,
+}
+
+/// Результат одной проверки
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub details: Vec<String>,
+}
+
+impl CheckResult {
+    pub(crate) fn new(name: &str, status: CheckStatus, details: Vec<String>) -> Self {
+        Self { name: name.to_string(), status, details }
+    }
+}
+
+/// Отчет о глубокой проверке ZIP артефакта
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtifactReport {
+    pub artifact: PathBuf,
+    pub plugin_id: Option<String>,
+    pub plugin_version: Option<String>,
+    pub since_build: Option<String>,
+    pub entries: usize,
+    pub jars: usize,
+    pub classes: usize,
+    pub checks: Vec<CheckResult>,
+}
+
+impl ArtifactReport {
+    /// Есть ли проваленные проверки
+    pub fn has_failures(&self) -> bool {
+        self.checks.iter().any(|c| c.status == CheckStatus::Failed)
+    }
+}
+
+/// Данные plugin.xml, найденного в артефакте
+struct PluginDescriptor {
+    location: String,
+    id: Option<String>,
+    version: Option<String>,
+    since_build: Option<String>,
+}
+
+/// Собранная за один проход информация об архиве
+#[derive(Default)]
+struct ArchiveScan {
+    entries: usize,
+    jars: usize,
+    corrupted: Vec<String>,
+    descriptors: Vec<PluginDescriptor>,
+    descriptor_errors: Vec<String>,
+    /// класс -> список JAR, в которых он встречается
+    classes: BTreeMap<String, Vec<String>>,
+    /// максимальная major-версия class-файла и пример класса
+    max_class_version: Option<(u16, String)>,
+    oversized: Vec<(String, u64)>,
+}
+
+/// Глубокая инспекция ZIP артефакта плагина
+pub struct ArtifactInspector {
+    max_resource_size: u64,
+}
+
+impl ArtifactInspector {
+    pub fn new(max_resource_size: u64) -> Self {
+        Self { max_resource_size }
+    }
+
+    /// Выполняет все проверки и собирает структурированный отчет
+    pub fn inspect(&self, zip_path: &Path) -> Result<ArtifactReport> {
+        info!("🔍 Инспекция артефакта {}", zip_path.display());
+
+        let file = std::fs::File::open(zip_path)
+            .with_context(|| format!("Не удалось открыть артефакт: {}", zip_path.display()))?;
+
+        let mut scan = ArchiveScan::default();
+        let mut checks = Vec::new();
+
+        match zip::ZipArchive::new(file) {
+            Ok(mut archive) => self.scan_archive(&mut archive, None, &mut scan),
+            Err(e) => {
+                checks.push(CheckResult::new(
+                    "zip-integrity",
+                    CheckStatus::Failed,
+                    vec![format!("Файл не является корректным ZIP: {}", e)],
+                ));
+                return Ok(ArtifactReport {
+                    artifact: zip_path.to_path_buf(),
+                    plugin_id: None,
+                    plugin_version: None,
+                    since_build: None,
+                    entries: 0,
+                    jars: 0,
+                    classes: 0,
+                    checks,
+                });
+            }
+        }
+
+        checks.push(self.check_integrity(&scan));
+        checks.push(self.check_plugin_xml(&scan));
+        let since_build = scan.descriptors.first().and_then(|d| d.since_build.clone());
+        checks.push(self.check_class_versions(&scan, since_build.as_deref()));
+        checks.push(self.check_duplicate_classes(&scan));
+        checks.push(self.check_resource_sizes(&scan));
+
+        let descriptor = scan.descriptors.first();
+        Ok(ArtifactReport {
+            artifact: zip_path.to_path_buf(),
+            plugin_id: descriptor.and_then(|d| d.id.clone()),
+            plugin_version: descriptor.and_then(|d| d.version.clone()),
+            since_build,
+            entries: scan.entries,
+            jars: scan.jars,
+            classes: scan.classes.len(),
+            checks,
+        })
+    }
+
+    /// Обходит архив (и вложенные JAR) один раз, читая каждую запись целиком — это же проверяет CRC
+    fn scan_archive<R: Read + Seek>(
+        &self,
+        archive: &mut zip::ZipArchive<R>,
+        jar: Option<&str>,
+        scan: &mut ArchiveScan,
+    ) {
+        for i in 0..archive.len() {
+            let mut entry = match archive.by_index(i) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    scan.corrupted.push(format!("{}#{}: {}", jar.unwrap_or("<root>"), i, e));
+                    continue;
+                }
+            };
+            if entry.is_dir() {
+                continue;
+            }
+
+            let name = entry.name().to_string();
+            let location = match jar {
+                Some(jar) => format!("{}!/{}", jar, name),
+                None => name.clone(),
+            };
+            scan.entries += 1;
+
+            let mut buf = Vec::with_capacity(entry.size() as usize);
+            if let Err(e) = entry.read_to_end(&mut buf) {
+                scan.corrupted.push(format!("{}: {}", location, e));
+                continue;
+            }
+
+            if !name.ends_with(".jar") && entry.size() > self.max_resource_size {
+                scan.oversized.push((location.clone(), entry.size()));
+            }
+
+            if name.ends_with(".jar") && jar.is_none() {
+                scan.jars += 1;
+                match zip::ZipArchive::new(Cursor::new(buf)) {
+                    Ok(mut inner) => self.scan_archive(&mut inner, Some(&name), scan),
+                    Err(e) => scan.corrupted.push(format!("{}: поврежденный JAR ({})", location, e)),
+                }
+            } else if name.ends_with("META-INF/plugin.xml") {
+                match parse_descriptor(&location, &buf) {
+                    Ok(descriptor) => scan.descriptors.push(descriptor),
+                    Err(e) => scan.descriptor_errors.push(format!("{}: {}", location, e)),
+                }
+            } else if name.ends_with(".class") && !name.ends_with("module-info.class") {
+                let class_name = name.trim_end_matches(".class").replace('/', ".");
+                scan.classes
+                    .entry(class_name.clone())
+                    .or_default()
+                    .push(jar.unwrap_or("<root>").to_string());
+                if let Some(major) = class_major_version(&buf) {
+                    if scan.max_class_version.as_ref().is_none_or(|(max, _)| major > *max) {
+                        scan.max_class_version = Some((major, class_name));
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_integrity(&self, scan: &ArchiveScan) -> CheckResult {
+        if scan.corrupted.is_empty() {
+            let detail = format!("{} записей, {} JAR прочитаны без ошибок", scan.entries, scan.jars);
+            CheckResult::new("zip-integrity", CheckStatus::Passed, vec![detail])
+        } else {
+            CheckResult::new("zip-integrity", CheckStatus::Failed, scan.corrupted.clone())
+        }
+    }
+
+    fn check_plugin_xml(&self, scan: &ArchiveScan) -> CheckResult {
+        if !scan.descriptor_errors.is_empty() {
+            return CheckResult::new("plugin-xml", CheckStatus::Failed, scan.descriptor_errors.clone());
+        }
+
+        match scan.descriptors.as_slice() {
+            [] => CheckResult::new(
+                "plugin-xml",
+                CheckStatus::Failed,
+                vec!["META-INF/plugin.xml не найден ни в корне, ни внутри JAR".to_string()],
+            ),
+            [descriptor] => {
+                let mut details = vec![format!("найден в {}", descriptor.location)];
+                let mut status = CheckStatus::Passed;
+                if descriptor.id.is_none() {
+                    status = CheckStatus::Warning;
+                    details.push("не задан <id>".to_string());
+                }
+                if descriptor.since_build.is_none() {
+                    status = CheckStatus::Warning;
+                    details.push("не задан <idea-version since-build>".to_string());
+                }
+                CheckResult::new("plugin-xml", status, details)
+            }
+            many => CheckResult::new(
+                "plugin-xml",
+                CheckStatus::Warning,
+                many.iter().map(|d| format!("несколько plugin.xml: {}", d.location)).collect(),
+            ),
+        }
+    }
+
+    fn check_class_versions(&self, scan: &ArchiveScan, since_build: Option<&str>) -> CheckResult {
+        let Some((major, class_name)) = &scan.max_class_version else {
+            return CheckResult::new("class-version", CheckStatus::Passed, vec!["class-файлы не найдены".to_string()]);
+        };
+        let java = java_release(*major);
+
+        let Some(branch) = since_build.and_then(parse_branch) else {
+            return CheckResult::new(
+                "class-version",
+                CheckStatus::Warning,
+                vec![format!("максимальная версия байткода Java {}, since-build не задан — совместимость не проверена", java)],
+            );
+        };
+
+        let required_major = max_class_major_for_branch(branch);
+        if *major > required_major {
+            CheckResult::new(
+                "class-version",
+                CheckStatus::Failed,
+                vec![format!(
+                    "{} скомпилирован под Java {}, а since-build {} требует не выше Java {}",
+                    class_name, java, branch, java_release(required_major)
+                )],
+            )
+        } else {
+            CheckResult::new(
+                "class-version",
+                CheckStatus::Passed,
+                vec![format!("байткод Java {} совместим с since-build {}", java, branch)],
+            )
+        }
+    }
+
+    fn check_duplicate_classes(&self, scan: &ArchiveScan) -> CheckResult {
+        let duplicates: Vec<String> = scan
+            .classes
+            .iter()
+            .filter(|(_, jars)| jars.len() > 1)
+            .map(|(class, jars)| format!("{} в {}", class, jars.join(", ")))
+            .collect();
+
+        if duplicates.is_empty() {
+            CheckResult::new("duplicate-classes", CheckStatus::Passed, vec![format!("{} уникальных классов", scan.classes.len())])
+        } else {
+            CheckResult::new("duplicate-classes", CheckStatus::Warning, duplicates)
+        }
+    }
+
+    fn check_resource_sizes(&self, scan: &ArchiveScan) -> CheckResult {
+        if scan.oversized.is_empty() {
+            return CheckResult::new(
+                "resource-size",
+                CheckStatus::Passed,
+                vec![format!("нет ресурсов больше {} байт", self.max_resource_size)],
+            );
+        }
+        let details = scan
+            .oversized
+            .iter()
+            .map(|(name, size)| format!("{} — {} байт", name, size))
+            .collect();
+        CheckResult::new("resource-size", CheckStatus::Warning, details)
+    }
+}
+
+fn parse_descriptor(location: &str, content: &[u8]) -> Result<PluginDescriptor> {
+    let root = Element::parse(content).context("plugin.xml не является корректным XML")?;
+    if root.name != "idea-plugin" {
+        anyhow::bail!("корневой элемент <{}> вместо <idea-plugin>", root.name);
+    }
+    let text = |name: &str| root.get_child(name).and_then(|e| e.get_text()).map(|t| t.trim().to_string());
+    let since_build = root
+        .get_child("idea-version")
+        .and_then(|e| e.attributes.get("since-build").cloned());
+
+    debug!("plugin.xml разобран: {}", location);
+    Ok(PluginDescriptor {
+        location: location.to_string(),
+        id: text("id"),
+        version: text("version"),
+        since_build,
+    })
+}
+
+/// Major-версия class-файла (байты 6..8 после магии CAFEBABE)
+fn class_major_version(bytes: &[u8]) -> Option<u16> {
+    if bytes.len() < 8 || bytes[0..4] != [0xCA, 0xFE, 0xBA, 0xBE] {
+        return None;
+    }
+    Some(u16::from_be_bytes([bytes[6], bytes[7]]))
+}
+
+fn java_release(major: u16) -> u16 {
+    major.saturating_sub(44)
+}
+
+/// Ветка платформы из since-build вида "233", "233.11799" или "233.*"
+pub(crate) fn parse_branch(since_build: &str) -> Option<u32> {
+    since_build.split('.').next()?.trim().parse().ok()
+}
+
+/// Максимальная major-версия байткода, поддерживаемая JBR ветки платформы
+fn max_class_major_for_branch(branch: u32) -> u16 {
+    match branch {
+        243.. => 65, // Java 21
+        222..=242 => 61, // Java 17
+        203..=221 => 55, // Java 11
+        _ => 52, // Java 8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::FileOptions;
+
+    fn class_bytes(major: u16) -> Vec<u8> {
+        let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0];
+        bytes.extend_from_slice(&major.to_be_bytes());
+        bytes
+    }
+
+    fn jar(entries: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer.start_file(*name, FileOptions::default()).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn plugin_zip(dir: &Path, jars: &[(&str, Vec<u8>)]) -> PathBuf {
+        let path = dir.join("ride-1.0.0.zip");
+        std::fs::write(&path, jar(jars)).unwrap();
+        path
+    }
+
+    const PLUGIN_XML: &str = r#"<idea-plugin><id>ru.marslab.ide.ride</id><version>1.0.0</version><idea-version since-build="222"/></idea-plugin>"#;
+
+    #[test]
+    fn test_inspect_valid_artifact() {
+        let tmp = tempfile::tempdir().unwrap();
+        let main_jar = jar(&[
+            ("META-INF/plugin.xml", PLUGIN_XML.as_bytes().to_vec()),
+            ("ru/marslab/Ride.class", class_bytes(61)),
+        ]);
+        let zip = plugin_zip(tmp.path(), &[("ride/lib/ride.jar", main_jar)]);
+
+        let report = ArtifactInspector::new(DEFAULT_MAX_RESOURCE_SIZE).inspect(&zip).unwrap();
+        assert!(!report.has_failures(), "{:?}", report.checks);
+        assert_eq!(report.plugin_id.as_deref(), Some("ru.marslab.ide.ride"));
+        assert_eq!(report.jars, 1);
+        assert_eq!(report.classes, 1);
+    }
+
+    #[test]
+    fn test_inspect_detects_problems() {
+        let tmp = tempfile::tempdir().unwrap();
+        let main_jar = jar(&[
+            ("META-INF/plugin.xml", PLUGIN_XML.as_bytes().to_vec()),
+            ("ru/marslab/Ride.class", class_bytes(65)),
+        ]);
+        let dep_jar = jar(&[("ru/marslab/Ride.class", class_bytes(52)), ("big.bin", vec![0; 64])]);
+        let zip = plugin_zip(tmp.path(), &[("ride/lib/ride.jar", main_jar), ("ride/lib/dep.jar", dep_jar)]);
+
+        let report = ArtifactInspector::new(32).inspect(&zip).unwrap();
+        let status = |name: &str| report.checks.iter().find(|c| c.name == name).unwrap().status;
+        assert_eq!(status("class-version"), CheckStatus::Failed);
+        assert_eq!(status("duplicate-classes"), CheckStatus::Warning);
+        assert_eq!(status("resource-size"), CheckStatus::Warning);
+        assert!(report.has_failures());
+    }
+
+    #[test]
+    fn test_inspect_missing_plugin_xml_and_broken_zip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let zip = plugin_zip(tmp.path(), &[("ride/lib/ride.jar", jar(&[("a.txt", b"x".to_vec())]))]);
+        let report = ArtifactInspector::new(DEFAULT_MAX_RESOURCE_SIZE).inspect(&zip).unwrap();
+        assert!(report.checks.iter().any(|c| c.name == "plugin-xml" && c.status == CheckStatus::Failed));
+
+        let broken = tmp.path().join("broken.zip");
+        std::fs::write(&broken, b"not a zip").unwrap();
+        let report = ArtifactInspector::new(DEFAULT_MAX_RESOURCE_SIZE).inspect(&broken).unwrap();
+        assert!(report.has_failures());
+    }
+}
@@ -0,0 +1,238 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+
+use crate::core::checksums;
+
+/// Путь манифеста внутри архива по умолчанию
+pub const DEFAULT_MANIFEST_PATH: &str = "integrity.json";
+
+const FORMAT_VERSION: u32 = 1;
+const ALGORITHM: &str = "sha256";
+
+/// Отделенная подпись манифеста лежит рядом с ним
+pub fn signature_path(manifest_path: &str) -> String {
+    format!("{}.sig", manifest_path)
+}
+
+/// Хеш и размер одной записи архива
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryDigest {
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl EntryDigest {
+    pub fn of(data: &[u8]) -> Self {
+        Self { sha256: format!("{:x}", Sha256::digest(data)), size: data.len() as u64 }
+    }
+}
+
+/// Манифест целостности: SHA256 всех файлов ZIP, кроме самого манифеста и его подписи
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntegrityManifest {
+    pub format: u32,
+    pub algorithm: String,
+    pub plugin_id: String,
+    pub version: String,
+    pub entries: BTreeMap<String, EntryDigest>,
+}
+
+impl IntegrityManifest {
+    /// Строит манифест по файлам архива (записи директорий не учитываются)
+    pub fn build<'a>(
+        plugin_id: &str,
+        version: &str,
+        manifest_path: &str,
+        files: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+    ) -> Self {
+        let signature = signature_path(manifest_path);
+        let entries = files
+            .into_iter()
+            .filter(|(name, _)| *name != manifest_path && *name != signature)
+            .map(|(name, data)| (name.to_string(), EntryDigest::of(data)))
+            .collect();
+        Self {
+            format: FORMAT_VERSION,
+            algorithm: ALGORITHM.to_string(),
+            plugin_id: plugin_id.to_string(),
+            version: version.to_string(),
+            entries,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = serde_json::to_vec_pretty(self)?;
+        bytes.push(b'\n');
+        Ok(bytes)
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let manifest: Self = serde_json::from_slice(bytes).context("Некорректный манифест целостности")?;
+        if manifest.format != FORMAT_VERSION || manifest.algorithm != ALGORITHM {
+            anyhow::bail!(
+                "Неподдерживаемый манифест целостности: format {}, algorithm {}",
+                manifest.format,
+                manifest.algorithm
+            );
+        }
+        Ok(manifest)
+    }
+
+    /// Расхождения с фактическими файлами архива: измененные, удаленные и лишние записи
+    pub fn compare(&self, actual: &BTreeMap<String, EntryDigest>) -> Vec<String> {
+        let mut problems = Vec::new();
+        for (name, expected) in &self.entries {
+            match actual.get(name) {
+                None => problems.push(format!("{}: запись удалена", name)),
+                Some(digest) if digest != expected => {
+                    problems.push(format!("{}: sha256 {} не совпадает с манифестом ({})", name, digest.sha256, expected.sha256))
+                }
+                Some(_) => {}
+            }
+        }
+        for name in actual.keys().filter(|name| !self.entries.contains_key(*name)) {
+            problems.push(format!("{}: запись отсутствует в манифесте", name));
+        }
+        problems
+    }
+}
+
+/// Состояние подписи манифеста
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureStatus {
+    Verified,
+    /// В архиве нет подписи
+    Unsigned,
+    /// Подпись есть, но команда проверки не задана
+    NotChecked,
+    Invalid(String),
+}
+
+/// Результат проверки манифеста целостности артефакта
+#[derive(Debug, Clone)]
+pub struct IntegrityReport {
+    /// Манифест найден в архиве
+    pub manifest_found: bool,
+    /// Число записей манифеста
+    pub entries: usize,
+    pub problems: Vec<String>,
+    pub signature: SignatureStatus,
+}
+
+/// Сверяет записи ZIP с встроенным манифестом и, если задана `verify_command`,
+/// проверяет его подпись (`{input}` — манифест, `{signature}` — файл подписи)
+pub fn verify_artifact(zip_path: &Path, manifest_path: &str, verify_command: Option<&str>) -> Result<IntegrityReport> {
+    let file = std::fs::File::open(zip_path)
+        .with_context(|| format!("Не удалось открыть артефакт: {}", zip_path.display()))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .with_context(|| format!("Не удалось прочитать ZIP {}", zip_path.display()))?;
+
+    let signature_name = signature_path(manifest_path);
+    let mut manifest_bytes = None;
+    let mut signature = None;
+    let mut actual = BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        entry
+            .read_to_end(&mut data)
+            .with_context(|| format!("Не удалось прочитать запись {}", name))?;
+        if name == manifest_path {
+            manifest_bytes = Some(data);
+        } else if name == signature_name {
+            signature = Some(data);
+        } else {
+            actual.insert(name, EntryDigest::of(&data));
+        }
+    }
+
+    let Some(manifest_bytes) = manifest_bytes else {
+        return Ok(IntegrityReport {
+            manifest_found: false,
+            entries: 0,
+            problems: vec![format!("{} не найден в артефакте", manifest_path)],
+            signature: SignatureStatus::Unsigned,
+        });
+    };
+    let manifest = IntegrityManifest::parse(&manifest_bytes)?;
+
+    let signature = match (signature, verify_command) {
+        (None, _) => SignatureStatus::Unsigned,
+        (Some(_), None) => SignatureStatus::NotChecked,
+        (Some(signature), Some(command)) => match verify_signature(&manifest_bytes, &signature, command) {
+            Ok(()) => SignatureStatus::Verified,
+            Err(e) => SignatureStatus::Invalid(e.to_string()),
+        },
+    };
+
+    Ok(IntegrityReport {
+        manifest_found: true,
+        entries: manifest.entries.len(),
+        problems: manifest.compare(&actual),
+        signature,
+    })
+}
+
+/// Проверяет отделенную подпись внешней командой, например `gpg --verify {signature} {input}`
+pub fn verify_signature(manifest: &[u8], signature: &[u8], command: &str) -> Result<()> {
+    let dir = tempfile::tempdir().context("Не удалось создать временную директорию для проверки подписи")?;
+    let input = dir.path().join(DEFAULT_MANIFEST_PATH);
+    let signature_file = dir.path().join(signature_path(DEFAULT_MANIFEST_PATH));
+    std::fs::write(&input, manifest)?;
+    std::fs::write(&signature_file, signature)?;
+
+    let command = checksums::shell_command(command, &[("input", &input), ("signature", &signature_file)]);
+    let output = Command::new("sh")
+        .args(["-c", &command])
+        .output()
+        .with_context(|| format!("Не удалось запустить команду проверки подписи: {}", command))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "подпись манифеста не прошла проверку ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_detects_tampering() {
+        let files: [(&str, &[u8]); 3] = [
+            ("ride/lib/ride.jar", b"jar"),
+            ("ride/LICENSE", b"MIT"),
+            ("integrity.json", b"old manifest"),
+        ];
+        let manifest = IntegrityManifest::build("ride", "1.2.0", DEFAULT_MANIFEST_PATH, files);
+        assert_eq!(manifest.entries.len(), 2);
+        assert_eq!(IntegrityManifest::parse(&manifest.to_bytes().unwrap()).unwrap(), manifest);
+
+        let mut actual = manifest.entries.clone();
+        assert!(manifest.compare(&actual).is_empty());
+
+        actual.insert("ride/lib/ride.jar".to_string(), EntryDigest::of(b"patched"));
+        actual.remove("ride/LICENSE");
+        actual.insert("ride/lib/evil.jar".to_string(), EntryDigest::of(b"evil"));
+        let problems = manifest.compare(&actual);
+        assert_eq!(problems.len(), 3);
+        assert!(problems[0].starts_with("ride/LICENSE: запись удалена"));
+        assert!(problems[1].starts_with("ride/lib/ride.jar: sha256"));
+        assert_eq!(problems[2], "ride/lib/evil.jar: запись отсутствует в манифесте");
+
+        assert!(verify_signature(b"manifest", b"sig", "test -s {input} && test -s {signature}").is_ok());
+        assert!(verify_signature(b"manifest", b"sig", "false").is_err());
+    }
+}
@@ -0,0 +1,127 @@
+//! Прерывание пайплайна по Ctrl-C: текущий этап отменяется на ближайшей границе,
+//! этапы убирают за собой (временные файлы, неопубликованный тег), а в конце печатается,
+//! что удалось откатить, а что осталось.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tokio::sync::watch;
+use tracing::warn;
+
+/// Код выхода после прерывания (128 + SIGINT)
+pub const EXIT_CODE: i32 = 130;
+
+/// Этап отменен по Ctrl-C
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Прервано пользователем (Ctrl-C) на этапе '{stage}'")]
+pub struct Interrupted {
+    pub stage: String,
+}
+
+/// Действие очистки после прерывания
+#[derive(Debug, Clone, PartialEq)]
+pub struct CleanupStep {
+    pub action: String,
+    /// `false` — состояние осталось как есть и требует ручного разбора
+    pub undone: bool,
+}
+
+struct State {
+    requested: watch::Sender<bool>,
+    journal: Mutex<Vec<CleanupStep>>,
+}
+
+fn state() -> &'static State {
+    static STATE: OnceLock<State> = OnceLock::new();
+    STATE.get_or_init(|| State { requested: watch::channel(false).0, journal: Mutex::default() })
+}
+
+/// Перехватывает Ctrl-C: первое нажатие отменяет текущий этап, второе завершает процесс сразу
+pub fn install() {
+    static INSTALLED: AtomicBool = AtomicBool::new(false);
+    if INSTALLED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        warn!("⛔ Ctrl-C: текущий этап будет отменен, выполняется очистка (повторный Ctrl-C — выход без очистки)");
+        state().requested.send_replace(true);
+        if tokio::signal::ctrl_c().await.is_ok() {
+            out_errln!("Выход без очистки");
+            std::process::exit(EXIT_CODE);
+        }
+    });
+}
+
+/// Было ли прерывание
+pub fn requested() -> bool {
+    *state().requested.borrow()
+}
+
+/// Граница шага: ошибка, если пользователь прервал выполнение
+pub fn check(stage: &str) -> std::result::Result<(), Interrupted> {
+    if requested() {
+        return Err(Interrupted { stage: stage.to_string() });
+    }
+    Ok(())
+}
+
+/// Завершается, когда пользователь прервет выполнение
+pub async fn cancelled() {
+    let mut requested = state().requested.subscribe();
+    // Отправитель живет в статическом состоянии, канал не закрывается
+    let _ = requested.wait_for(|requested| *requested).await;
+}
+
+/// Записывает результат действия очистки для итогового отчета
+pub fn record(action: impl Into<String>, undone: bool) {
+    let step = CleanupStep { action: action.into(), undone };
+    if step.undone {
+        tracing::info!("↩️ {}", step.action);
+    } else {
+        warn!("⚠️ {}", step.action);
+    }
+    state().journal.lock().unwrap().push(step);
+}
+
+/// Отчет об очистке: что откачено и что осталось
+pub fn report() -> String {
+    let journal = state().journal.lock().unwrap();
+    format_report(&journal)
+}
+
+fn format_report(journal: &[CleanupStep]) -> String {
+    if journal.is_empty() {
+        return "Изменений, требующих отката, не было".to_string();
+    }
+    let mut lines = Vec::new();
+    for (title, undone) in [("Откачено:", true), ("Не откачено (проверьте вручную):", false)] {
+        let steps: Vec<&CleanupStep> = journal.iter().filter(|s| s.undone == undone).collect();
+        if !steps.is_empty() {
+            lines.push(title.to_string());
+            lines.extend(steps.iter().map(|s| format!("  {} {}", if undone { "✔" } else { "✘" }, s.action)));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cleanup_report_groups_steps() {
+        assert_eq!(format_report(&[]), "Изменений, требующих отката, не было");
+        let journal = [
+            CleanupStep { action: "Удален локальный тег v1.2.0".to_string(), undone: true },
+            CleanupStep { action: "Тег v1.2.0 уже в origin".to_string(), undone: false },
+            CleanupStep { action: "Удален временный файл updatePlugins.xml.tmp".to_string(), undone: true },
+        ];
+        assert_eq!(
+            format_report(&journal),
+            "Откачено:\n  ✔ Удален локальный тег v1.2.0\n  ✔ Удален временный файл updatePlugins.xml.tmp\n\
+             Не откачено (проверьте вручную):\n  ✘ Тег v1.2.0 уже в origin"
+        );
+    }
+}
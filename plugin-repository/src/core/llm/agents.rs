@@ -1,20 +1,130 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use tracing::{info, debug, error, warn};
-use super::yandexgpt::{YandexGPTClient, YandexGPTConfig, YandexGPTClientFactory};
+use super::provider::{self, LlmProvider, RetryPolicy};
+use super::yandexgpt::YandexGPTClientFactory;
 use super::prompts::*;
+use super::conversation::Conversation;
+use super::qa::{ContextCommit, QaAgent, RepoAnswer};
+use super::review::{CodeReview, ReviewAgent};
+use super::diff_summary::DiffSummaryAgent;
+use super::structured::{complete_structured, ChangelogJson, ReleaseNotesJson};
+use super::clustering::{cluster_commits, collapse_duplicates, format_clusters, Embedder};
+use super::modules::{format_by_module, group_by_module, should_group};
+use crate::config::parser::{AgentConfig, ChangelogConfig, ClusteringConfig, Config, OutputLanguage};
+use super::transcript::Transcript;
+use super::cost::{CostGuard, CostLimited};
 use crate::git::{GitRepository, GitCommit, ReleaseAnalysis, ChangeType};
+use crate::utils::clock::{system_clock, SharedClock};
 
 #[inline]
 fn preview(s: &str, n: usize) -> String {
     s.chars().take(n).collect::<String>()
 }
 
-/// Базовый трейт для LLM агентов
-pub trait LLMAgent {
-    async fn generate_response(&self, input: &str) -> Result<String>;
-    fn get_agent_name(&self) -> &'static str;
+/// Сколько раз модель просят сократить changelog, превысивший `[changelog] max_length`
+const SHORTEN_ATTEMPTS: usize = 2;
+
+/// Получатель фрагментов ответа агентов (`ai ... --stream`)
+pub type StreamSink = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Запрос к LLM; с включенным стримингом фрагменты уходят в `stream`,
+/// а при сбое потока запрос повторяется обычным способом с retry
+async fn complete(client: &dyn LlmProvider, stream: Option<&StreamSink>, prompt: &str, retry: &RetryPolicy) -> Result<String> {
+    if let Some(sink) = stream {
+        match client.chat_completion_stream(prompt, sink.as_ref()).await {
+            Ok(response) => return Ok(response),
+            Err(e) => warn!("⚠️ Потоковый запрос не удался, повтор без стриминга: {:#}", e),
+        }
+    }
+    client.chat_completion_with_retry(prompt, retry).await
+}
+
+/// Сколько раз агента версий просят повторить ответ в формате «X.Y.Z: обоснование»
+const VERSION_CLARIFICATIONS: usize = 1;
+
+/// Оценка ответа модели агентом
+#[derive(Debug)]
+pub enum Assessment<T> {
+    /// Ответ полный
+    Complete(T),
+    /// Ответ разобран, но неполон: модели уходит уточнение, а разобранный вариант
+    /// остается результатом, если уточнения не помогли
+    Incomplete(T, String),
+    /// Ответ не разобран: ошибка разбора и уточняющий запрос
+    Invalid { error: String, clarification: String },
+}
+
+/// Агент, который сам оценивает полноту ответа модели и задает уточняющие вопросы в том же
+/// диалоге, пока ответ не станет полным или не кончится лимит. Наружу отдается уже
+/// разобранный результат, а не текст модели
+pub trait ConversationalAgent {
+    /// Данные запроса, нужные для разбора ответа (версия, git log)
+    type Context: ?Sized;
+    /// Разобранный ответ
+    type Output;
+
+    fn client(&self) -> &dyn LlmProvider;
+
+    fn retry(&self) -> &RetryPolicy;
+
+    /// Лимит уточняющих запросов после первого ответа
+    fn max_clarifications(&self) -> usize;
+
+    /// Разбор ответа и критерий полноты
+    fn assess(&self, context: &Self::Context, response: &str) -> Assessment<Self::Output>;
+
+    /// Первый ответ модели; по умолчанию — запрос с повторами `[llm.retry]`
+    async fn first_response(&self, prompt: &str) -> Result<String> {
+        self.client().chat_completion_with_retry(prompt, self.retry()).await
+    }
+
+    /// Ошибка, когда ответ так и не удалось разобрать
+    fn unresolved(&self, attempts: usize, error: String) -> anyhow::Error {
+        anyhow::anyhow!("Ответ LLM не разобран за {} попыток: {}", attempts, error)
+    }
+
+    /// Запрос и уточнения до полного ответа. Если уточнения исчерпаны или не удались,
+    /// остается последний разобранный вариант; неразобранный ответ — ошибка `unresolved`
+    async fn converse(&self, prompt: &str, context: &Self::Context) -> Result<Self::Output> {
+        let response = self.first_response(prompt).await?;
+        let mut conversation = Conversation::new(prompt);
+        conversation.push_assistant(response.clone());
+        let mut assessment = self.assess(context, &response);
+        let mut attempts = 1;
+        loop {
+            let clarification = match assessment {
+                Assessment::Complete(output) => return Ok(output),
+                Assessment::Incomplete(_, ref clarification) => clarification.clone(),
+                Assessment::Invalid { ref error, ref clarification } => {
+                    warn!("⚠️ Ответ LLM не разобран (попытка {}): {}", attempts, error);
+                    debug!("Ответ LLM: {}", response);
+                    clarification.clone()
+                }
+            };
+            if attempts > self.max_clarifications() {
+                break;
+            }
+            info!("💬 Ответ неполный, уточняющий запрос {} из {}", attempts, self.max_clarifications());
+            match conversation.follow_up(self.client(), clarification).await {
+                Ok(response) => {
+                    attempts += 1;
+                    assessment = self.assess(context, &response);
+                }
+                Err(e) if matches!(assessment, Assessment::Incomplete(..)) => {
+                    warn!("⚠️ Уточняющий запрос не удался: {:#}", e);
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        match assessment {
+            Assessment::Complete(output) | Assessment::Incomplete(output, _) => Ok(output),
+            Assessment::Invalid { error, .. } => Err(self.unresolved(attempts, error)),
+        }
+    }
 }
 
 /// Информация о версии для анализа
@@ -51,6 +161,16 @@ pub struct ChangelogSection {
     pub title: String,
     pub changes: Vec<String>,
     pub emoji: String,
+    /// Те же изменения, разбитые по модулям (`[changelog] group_by_module`)
+    #[serde(default)]
+    pub modules: Vec<ChangelogModule>,
+}
+
+/// Подсекция changelog для одного модуля
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangelogModule {
+    pub name: String,
+    pub changes: Vec<String>,
 }
 
 /// Release notes сгенерированные AI
@@ -65,16 +185,77 @@ pub struct GeneratedReleaseNotes {
 
 /// Агент для генерации changelog
 pub struct ChangelogAgent {
-    client: YandexGPTClient,
+    client: Box<dyn LlmProvider>,
     cache: HashMap<String, String>,
+    clustering: Option<(ClusteringConfig, Embedder)>,
+    /// Описания коммитов по diff перед генерацией (`[changelog.diff_summary]`)
+    diff_summary: Option<DiffSummaryAgent>,
+    changelog: ChangelogConfig,
+    stream: Option<StreamSink>,
+    /// Попытки исправления JSON в режиме структурированного ответа
+    structured: Option<u32>,
+    retry: RetryPolicy,
+    language: OutputLanguage,
 }
 
 impl ChangelogAgent {
-    pub fn new(client: YandexGPTClient) -> Self {
+    pub fn new(client: Box<dyn LlmProvider>) -> Self {
         Self {
             client,
             cache: HashMap::new(),
+            clustering: None,
+            diff_summary: None,
+            changelog: ChangelogConfig::default(),
+            stream: None,
+            structured: None,
+            retry: RetryPolicy::default(),
+            language: OutputLanguage::default(),
+        }
+    }
+
+    /// Язык changelog: шаблон промпта, заголовки секций и статистика
+    pub fn with_language(mut self, language: OutputLanguage) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Потоковый вывод ответа модели
+    pub fn with_stream(mut self, sink: StreamSink) -> Self {
+        self.stream = Some(sink);
+        self
+    }
+
+    /// Ответ в виде JSON по схеме; ответ с ошибкой возвращается модели до `fix_attempts` раз
+    pub fn with_structured_output(mut self, fix_attempts: u32) -> Self {
+        self.structured = Some(fix_attempts);
+        self
+    }
+
+    /// Политика повторов запросов (`[llm.retry]`)
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Настройки оформления changelog (группировка по модулям)
+    pub fn with_changelog_config(mut self, config: &ChangelogConfig) -> Self {
+        self.changelog = config.clone();
+        self
+    }
+
+    /// Включает группировку близких коммитов для больших релизов и свертку повторов для остальных
+    pub fn with_clustering(mut self, config: &ClusteringConfig) -> Self {
+        if config.enabled {
+            let embedder = Embedder::from_config(config, self.client.as_ref());
+            self.clustering = Some((config.clone(), embedder));
         }
+        self
+    }
+
+    /// Дополняет темы коммитов описаниями их diff в `generate_changelog_from_repo`
+    pub fn with_diff_summary(mut self, agent: DiffSummaryAgent) -> Self {
+        self.diff_summary = Some(agent);
+        self
     }
 
     /// Генерирует changelog на основе git истории
@@ -83,7 +264,7 @@ impl ChangelogAgent {
 
         let git_log = version_info.git_log.as_deref().unwrap_or("Нет доступной истории изменений");
 
-        let prompt = CHANGELOG_PROMPT
+        let prompt = self.language.changelog_prompt()
             .replace("{new_version}", &version_info.new_version.as_deref().unwrap_or("unknown"))
             .replace("{old_version}", &version_info.current_version)
             .replace("{branch}", &version_info.branch)
@@ -91,33 +272,62 @@ impl ChangelogAgent {
 
         debug!("Отправка промпта в YandexGPT: {}", preview(&prompt, 200));
 
-        let response = self.client.chat_completion_with_retry(&prompt, 3).await
-            .context("Ошибка генерации changelog")?;
-
-        // Парсим ответ на секции
-        let sections = self.parse_changelog_sections(&response);
-        let total_changes = sections.iter().map(|s| s.changes.len()).sum();
+        let version = version_info.new_version.clone().unwrap_or_else(|| "unknown".to_string());
+        if let Some(fix_attempts) = self.structured {
+            // JSON не стримится: пользователю нечего показывать до окончания разбора
+            let json: ChangelogJson = complete_structured(self.client.as_ref(), &prompt, &self.retry, fix_attempts).await
+                .context("Ошибка генерации changelog")?;
+            let sections: Vec<ChangelogSection> = json.sections.iter()
+                .map(|s| ChangelogSection {
+                    title: format!("{} {}", s.emoji, s.title),
+                    changes: s.changes.clone(),
+                    emoji: s.emoji.clone(),
+                    modules: Vec::new(),
+                })
+                .collect();
+            return Ok(GeneratedChangelog {
+                changelog: json.to_markdown(&version, self.language),
+                total_changes: sections.iter().map(|s| s.changes.len()).sum(),
+                version,
+                sections,
+            });
+        }
 
-        Ok(GeneratedChangelog {
-            version: version_info.new_version.clone().unwrap_or_else(|| "unknown".to_string()),
-            changelog: response.clone(),
-            sections,
-            total_changes,
-        })
+        self.converse(&prompt, &version).await
+            .context("Ошибка генерации changelog")
     }
 
     /// Генерирует changelog на основе GitRepository анализа
     pub async fn generate_changelog_from_repo(&self, repo: &GitRepository, from_tag: Option<&str>, to_tag: Option<&str>) -> Result<GeneratedChangelog> {
         info!("🤖 Генерация changelog на основе анализа репозитория");
 
-        let (_, commits) = repo.get_full_analysis(from_tag, to_tag).await?;
+        let (_, mut commits) = repo.get_full_analysis(from_tag, to_tag).await?;
         let version = to_tag.unwrap_or("HEAD").to_string();
+        let changes_count = commits.len();
+        if let Some(agent) = &self.diff_summary {
+            agent.annotate(&repo.history, &mut commits).await;
+        }
 
-        // Формируем git лог из коммитов
-        let git_log = commits.iter()
-            .map(|commit| format!("{}: {}", commit.short_hash, commit.message))
-            .collect::<Vec<_>>()
-            .join("\n");
+        // Повторы сворачиваются и в небольших релизах; большие группируются целиком ниже
+        let commits = match &self.clustering {
+            Some((config, embedder)) if commits.len() < config.min_commits => {
+                collapse_duplicates(&commits, embedder, config.duplicate_similarity).await
+            }
+            _ => commits,
+        };
+
+        // Формируем git лог из коммитов; в больших релизах — по группам близких коммитов
+        let git_log = match &self.clustering {
+            Some((config, embedder)) if commits.len() >= config.min_commits => {
+                let clusters = cluster_commits(&commits, embedder, config.similarity).await;
+                format_clusters(&clusters)
+            }
+            _ if self.changelog.group_by_module => format_by_module(&commits, &self.changelog),
+            _ => commits.iter()
+                .map(|commit| format!("{}: {}", commit.short_hash, commit.changelog_input()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
 
         let old_version = from_tag.unwrap_or("previous").to_string();
         let branch = if repo.history.is_git_repository() {
@@ -131,7 +341,7 @@ impl ChangelogAgent {
             new_version: Some(version),
             branch,
             git_log: Some(git_log),
-            changes_count: commits.len(),
+            changes_count,
         };
 
         self.generate_changelog(&version_info).await
@@ -158,44 +368,64 @@ impl ChangelogAgent {
 
         // Заголовок
         let version = &analysis.version_to.as_deref().unwrap_or("latest");
-        changelog_content.push_str(&format!("## Изменения {}\n\n", version));
+        changelog_content.push_str(&format!("{}\n\n", self.language.changes_heading(version)));
 
         // Секции изменений в правильном порядке
         let section_order = [
-            (ChangeType::Breaking, "💥", "Критические изменения"),
-            (ChangeType::Feature, "🚀", "Новые возможности"),
-            (ChangeType::Fix, "🐛", "Исправления"),
-            (ChangeType::Improvement, "🔧", "Улучшения"),
-            (ChangeType::Refactoring, "♻️", "Рефакторинг"),
-            (ChangeType::Documentation, "📝", "Документация"),
-            (ChangeType::Testing, "🧪", "Тестирование"),
-            (ChangeType::Chore, "🧹", "Обслуживание"),
-            (ChangeType::Other, "📋", "Другое"),
+            ChangeType::Breaking,
+            ChangeType::Feature,
+            ChangeType::Fix,
+            ChangeType::Improvement,
+            ChangeType::Refactoring,
+            ChangeType::Documentation,
+            ChangeType::Testing,
+            ChangeType::Chore,
+            ChangeType::Other,
         ];
 
-        for (change_type, emoji, title) in &section_order {
+        for change_type in &section_order {
             if let Some(commits_of_type) = grouped_commits.get(change_type) {
                 if !commits_of_type.is_empty() {
-                    let section_title = format!("{} {}", emoji, title);
+                    let emoji = change_type.emoji();
+                    let section_title = format!("{} {}", emoji, change_type.title(self.language));
                     changelog_content.push_str(&format!("### {}\n\n", section_title));
 
+                    let change_desc = |commit: &GitCommit| format!("- {} ({}): {}\n",
+                        commit.short_hash,
+                        commit.date.format("%Y-%m-%d"),
+                        commit.message);
+
                     let mut changes = Vec::new();
-                    for commit in commits_of_type {
-                        let change_desc = format!("- {} ({}): {}",
-                            commit.short_hash,
-                            commit.date.format("%Y-%m-%d"),
-                            commit.message);
-                        changelog_content.push_str(&change_desc);
+                    let mut modules = Vec::new();
+                    let groups = group_by_module(commits_of_type, &self.changelog.modules);
+                    if should_group(&self.changelog, &groups) {
+                        // Вложенные подсекции по модулям; коммиты без scope — в конце
+                        for group in groups {
+                            let name = group.name.unwrap_or_else(|| self.changelog.other_title.clone());
+                            changelog_content.push_str(&format!("#### {}\n\n", name));
+                            for commit in &group.commits {
+                                changelog_content.push_str(&change_desc(commit));
+                                changes.push(commit.message.clone());
+                            }
+                            changelog_content.push('\n');
+                            modules.push(ChangelogModule {
+                                name,
+                                changes: group.commits.iter().map(|c| c.message.clone()).collect(),
+                            });
+                        }
+                    } else {
+                        for commit in commits_of_type {
+                            changelog_content.push_str(&change_desc(commit));
+                            changes.push(commit.message.clone());
+                        }
                         changelog_content.push('\n');
-
-                        changes.push(commit.message.clone());
                     }
-                    changelog_content.push('\n');
 
                     sections.push(ChangelogSection {
                         title: section_title,
                         changes,
                         emoji: emoji.to_string(),
+                        modules,
                     });
 
                     total_changes += commits_of_type.len();
@@ -204,15 +434,19 @@ impl ChangelogAgent {
         }
 
         // Добавляем статистику
+        let (statistics, commits_label, breaking) = match self.language {
+            OutputLanguage::Ru => ("Статистика", "коммитов", "Критические изменения"),
+            OutputLanguage::En => ("Statistics", "commits", "Breaking changes"),
+        };
         changelog_content.push_str("---\n");
-        changelog_content.push_str(&format!("**Статистика:** {} коммитов\n", analysis.total_commits));
+        changelog_content.push_str(&format!("**{}:** {} {}\n", statistics, analysis.total_commits, commits_label));
 
         for (change_type, count) in &analysis.change_summary {
-            changelog_content.push_str(&format!("- {}: {}\n", change_type.name(), count));
+            changelog_content.push_str(&format!("- {}: {}\n", change_type.title(self.language), count));
         }
 
         if !analysis.breaking_changes.is_empty() {
-            changelog_content.push_str(&format!("\n**⚠️ Критические изменения:** {}\n", analysis.breaking_changes.len()));
+            changelog_content.push_str(&format!("\n**⚠️ {}:** {}\n", breaking, analysis.breaking_changes.len()));
         }
 
         Ok(GeneratedChangelog {
@@ -229,49 +463,30 @@ impl ChangelogAgent {
         let mut current_section = None;
         let mut current_changes: Vec<String> = Vec::new();
 
+        // Секции определяются по эмодзи и заголовкам на любом из языков
+        let kinds = [
+            (ChangeType::Feature, &["Новые возможности", "Новые функции", "New features"][..]),
+            (ChangeType::Fix, &["Исправления", "Bug fixes"][..]),
+            (ChangeType::Improvement, &["Улучшения", "Improvements"][..]),
+            (ChangeType::Breaking, &["Критические изменения", "Breaking changes"][..]),
+        ];
+
         for line in changelog.lines() {
             let line = line.trim();
 
-            // Определяем секции по эмодзи и заголовкам
-            if line.starts_with("🚀") || line.contains("Новые возможности") || line.contains("Новые функции") {
-                if let Some(section) = current_section.take() {
-                    sections.push(section);
-                }
-                current_changes.clear();
-                current_section = Some(ChangelogSection {
-                    title: "🚀 Новые возможности".to_string(),
-                    changes: Vec::new(),
-                    emoji: "🚀".to_string(),
-                });
-            } else if line.starts_with("🐛") || line.contains("Исправления") {
-                if let Some(section) = current_section.take() {
-                    sections.push(section);
-                }
-                current_changes.clear();
-                current_section = Some(ChangelogSection {
-                    title: "🐛 Исправления".to_string(),
-                    changes: Vec::new(),
-                    emoji: "🐛".to_string(),
-                });
-            } else if line.starts_with("🔧") || line.contains("Улучшения") {
-                if let Some(section) = current_section.take() {
-                    sections.push(section);
-                }
-                current_changes.clear();
-                current_section = Some(ChangelogSection {
-                    title: "🔧 Улучшения".to_string(),
-                    changes: Vec::new(),
-                    emoji: "🔧".to_string(),
-                });
-            } else if line.starts_with("💥") || line.contains("Критические изменения") {
+            let kind = kinds.iter().find(|(change_type, titles)| {
+                line.starts_with(change_type.emoji()) || titles.iter().any(|title| line.contains(title))
+            });
+            if let Some((change_type, _)) = kind {
                 if let Some(section) = current_section.take() {
                     sections.push(section);
                 }
                 current_changes.clear();
                 current_section = Some(ChangelogSection {
-                    title: "💥 Критические изменения".to_string(),
+                    title: format!("{} {}", change_type.emoji(), change_type.title(self.language)),
                     changes: Vec::new(),
-                    emoji: "💥".to_string(),
+                    emoji: change_type.emoji().to_string(),
+                    modules: Vec::new(),
                 });
             } else if line.starts_with("- ") || line.starts_with("* ") {
                 let change = line.strip_prefix("- ").unwrap_or(line.strip_prefix("* ").unwrap_or(line)).to_string();
@@ -288,12 +503,16 @@ impl ChangelogAgent {
         // Если секции не определены, создаем общую секцию
         if sections.is_empty() {
             sections.push(ChangelogSection {
-                title: "📋 Изменения".to_string(),
+                title: match self.language {
+                    OutputLanguage::Ru => "📋 Изменения".to_string(),
+                    OutputLanguage::En => "📋 Changes".to_string(),
+                },
                 changes: changelog.lines()
                     .filter(|line| line.trim().starts_with("- ") || line.trim().starts_with("* "))
                     .map(|line| line.trim().strip_prefix("- ").unwrap_or(line.strip_prefix("* ").unwrap_or(line)).to_string())
                     .collect(),
                 emoji: "📋".to_string(),
+                modules: Vec::new(),
             });
         }
 
@@ -301,30 +520,73 @@ impl ChangelogAgent {
     }
 }
 
-impl LLMAgent for ChangelogAgent {
-    async fn generate_response(&self, input: &str) -> Result<String> {
-        self.client.chat_completion_with_retry(input, 3).await
+impl ConversationalAgent for ChangelogAgent {
+    /// Версия changelog
+    type Context = str;
+    type Output = GeneratedChangelog;
+
+    fn client(&self) -> &dyn LlmProvider {
+        self.client.as_ref()
+    }
+
+    fn retry(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    fn max_clarifications(&self) -> usize {
+        if self.changelog.max_length.is_some() { SHORTEN_ATTEMPTS } else { 0 }
     }
 
-    fn get_agent_name(&self) -> &'static str {
-        "ChangelogAgent"
+    async fn first_response(&self, prompt: &str) -> Result<String> {
+        complete(self.client.as_ref(), self.stream.as_ref(), prompt, &self.retry).await
+    }
+
+    /// Ответ разбирается на секции; changelog длиннее `[changelog] max_length` модель
+    /// сокращает в том же диалоге, видя свой ответ
+    fn assess(&self, version: &str, response: &str) -> Assessment<GeneratedChangelog> {
+        let sections = self.parse_changelog_sections(response);
+        let changelog = GeneratedChangelog {
+            version: version.to_string(),
+            changelog: response.to_string(),
+            total_changes: sections.iter().map(|s| s.changes.len()).sum(),
+            sections,
+        };
+        let length = response.chars().count();
+        match self.changelog.max_length {
+            Some(max_length) if length > max_length => {
+                debug!("Changelog длиннее предела: {} > {} символов", length, max_length);
+                let request = self.language.shorten_changelog_prompt()
+                    .replace("{length}", &length.to_string())
+                    .replace("{max_length}", &max_length.to_string());
+                Assessment::Incomplete(changelog, request)
+            }
+            _ => Assessment::Complete(changelog),
+        }
     }
 }
 
 /// Агент для анализа версий
 pub struct VersionAgent {
-    client: YandexGPTClient,
+    client: Box<dyn LlmProvider>,
     cache: HashMap<String, String>,
+    retry: RetryPolicy,
 }
 
 impl VersionAgent {
-    pub fn new(client: YandexGPTClient) -> Self {
+    pub fn new(client: Box<dyn LlmProvider>) -> Self {
         Self {
             client,
             cache: HashMap::new(),
+            retry: RetryPolicy::default(),
         }
     }
 
+    /// Политика повторов запросов (`[llm.retry]`)
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     /// Анализирует изменения и предлагает версию
     pub async fn suggest_version(&self, version_info: &VersionInfo) -> Result<VersionAnalysis> {
         info!("🤖 Анализ изменений для предложения версии");
@@ -338,28 +600,8 @@ impl VersionAgent {
 
         debug!("Отправка промпта в YandexGPT: {}", preview(&prompt, 200));
 
-        let response = self.client.chat_completion_with_retry(&prompt, 3).await
-            .context("Ошибка анализа версий")?;
-
-        // Парсим ответ: "1.2.3: обоснование"
-        if let Some(colon_pos) = response.find(':') {
-            let version = response[..colon_pos].trim().to_string();
-            let reasoning = response[colon_pos + 1..].trim().to_string();
-
-            Ok(VersionAnalysis {
-                suggested_version: version,
-                reasoning,
-                confidence: 0.8, // TODO: Улучшить анализ уверенности
-                change_types: self.extract_change_types(git_log),
-            })
-        } else {
-            Ok(VersionAnalysis {
-                suggested_version: "1.0.0".to_string(),
-                reasoning: response,
-                confidence: 0.5,
-                change_types: vec!["other".to_string()],
-            })
-        }
+        self.converse(&prompt, git_log).await
+            .context("Ошибка анализа версий")
     }
 
     /// Предлагает версию на основе анализа репозитория
@@ -390,7 +632,7 @@ impl VersionAgent {
 
         debug!("Отправка промпта в YandexGPT для версионного анализа");
 
-        let response = self.client.chat_completion_with_retry(&prompt, 2).await
+        let response = self.client.chat_completion_with_retry(&prompt, &self.retry).await
             .context("Ошибка LLM анализа версий")?;
 
         // Комбинируем результат LLM с анализом репозитория
@@ -439,7 +681,7 @@ impl VersionAgent {
                 (version, reason)
             }
             crate::git::VersionBump::Custom(ref version) => {
-                (version.clone(), "Использована кастомная версия".to_string())
+                (version.clone(), "Версия задана маркером в коммите ([[git.version_rules]])".to_string())
             }
         };
 
@@ -545,35 +787,100 @@ impl VersionAgent {
     }
 }
 
-impl LLMAgent for VersionAgent {
-    async fn generate_response(&self, input: &str) -> Result<String> {
-        self.client.chat_completion_with_retry(input, 3).await
+impl ConversationalAgent for VersionAgent {
+    /// Git log, по которому определяются типы изменений
+    type Context = str;
+    type Output = VersionAnalysis;
+
+    fn client(&self) -> &dyn LlmProvider {
+        self.client.as_ref()
+    }
+
+    fn retry(&self) -> &RetryPolicy {
+        &self.retry
     }
 
-    fn get_agent_name(&self) -> &'static str {
-        "VersionAgent"
+    fn max_clarifications(&self) -> usize {
+        VERSION_CLARIFICATIONS
+    }
+
+    /// Ответ "1.2.3: обоснование" полон, если версия корректна по semver
+    fn assess(&self, git_log: &str, response: &str) -> Assessment<VersionAnalysis> {
+        let Some((version, reasoning)) = response.split_once(':') else {
+            let fallback = VersionAnalysis {
+                suggested_version: "1.0.0".to_string(),
+                reasoning: response.to_string(),
+                confidence: 0.5,
+                change_types: vec!["other".to_string()],
+            };
+            return Assessment::Incomplete(fallback, VERSION_FORMAT_PROMPT.to_string());
+        };
+        let version = version.trim().trim_matches('"');
+        let analysis = VersionAnalysis {
+            suggested_version: version.to_string(),
+            reasoning: reasoning.trim().trim_end_matches('"').to_string(),
+            confidence: 0.8, // TODO: Улучшить анализ уверенности
+            change_types: self.extract_change_types(git_log),
+        };
+        match semver::Version::parse(version) {
+            Ok(_) => Assessment::Complete(analysis),
+            Err(_) => Assessment::Incomplete(analysis, VERSION_FORMAT_PROMPT.to_string()),
+        }
     }
 }
 
 /// Агент для генерации release notes
 pub struct ReleaseAgent {
-    client: YandexGPTClient,
+    client: Box<dyn LlmProvider>,
     cache: HashMap<String, String>,
+    stream: Option<StreamSink>,
+    /// Попытки исправления JSON в режиме структурированного ответа
+    structured: Option<u32>,
+    retry: RetryPolicy,
+    language: OutputLanguage,
 }
 
 impl ReleaseAgent {
-    pub fn new(client: YandexGPTClient) -> Self {
+    pub fn new(client: Box<dyn LlmProvider>) -> Self {
         Self {
             client,
             cache: HashMap::new(),
+            stream: None,
+            structured: None,
+            retry: RetryPolicy::default(),
+            language: OutputLanguage::default(),
         }
     }
 
+    /// Язык release notes: шаблон промпта и служебный текст
+    pub fn with_language(mut self, language: OutputLanguage) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Потоковый вывод ответа модели
+    pub fn with_stream(mut self, sink: StreamSink) -> Self {
+        self.stream = Some(sink);
+        self
+    }
+
+    /// Ответ в виде JSON по схеме; ответ с ошибкой возвращается модели до `fix_attempts` раз
+    pub fn with_structured_output(mut self, fix_attempts: u32) -> Self {
+        self.structured = Some(fix_attempts);
+        self
+    }
+
+    /// Политика повторов запросов (`[llm.retry]`)
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     /// Генерирует release notes
     pub async fn generate_release_notes(&self, version: &str, changelog: &str, plugin_info: &PluginInfo) -> Result<GeneratedReleaseNotes> {
         info!("🤖 Генерация release notes для версии {}", version);
 
-        let prompt = RELEASE_NOTES_PROMPT
+        let prompt = self.language.release_notes_prompt()
             .replace("{plugin_name}", &plugin_info.name)
             .replace("{plugin_id}", &plugin_info.id)
             .replace("{version}", version)
@@ -581,24 +888,40 @@ impl ReleaseAgent {
 
         debug!("Отправка промпта в YandexGPT: {}", preview(&prompt, 200));
 
-        let response = self.client.chat_completion_with_retry(&prompt, 3).await
+        let Some(fix_attempts) = self.structured else {
+            return self.converse(&prompt, version).await
+                .context("Ошибка генерации release notes");
+        };
+        let json: ReleaseNotesJson = complete_structured(self.client.as_ref(), &prompt, &self.retry, fix_attempts).await
             .context("Ошибка генерации release notes")?;
-
-        // Парсим ответ на структуру
-        let (title, highlights, body) = self.parse_release_notes(&response);
-
         Ok(GeneratedReleaseNotes {
-            title,
-            subtitle: format!("Версия {} теперь доступна!", version),
-            highlights,
-            body,
+            title: json.title,
+            subtitle: self.language.release_subtitle(version),
+            highlights: json.highlights,
+            body: json.body,
             version: version.to_string(),
         })
     }
 
+    /// Переводит release notes на язык локали (Markdown сохраняется)
+    pub async fn translate_release_notes(&self, version: &str, release_notes: &str, locale: &str, plugin_info: &PluginInfo) -> Result<String> {
+        info!("🌐 Перевод release notes {} на локаль {}", version, locale);
+
+        let prompt = TRANSLATE_RELEASE_NOTES_PROMPT
+            .replace("{plugin_name}", &plugin_info.name)
+            .replace("{version}", version)
+            .replace("{language}", crate::core::localized_notes::language_name(locale))
+            .replace("{locale}", locale)
+            .replace("{release_notes}", release_notes);
+
+        let response = self.client.chat_completion_with_retry(&prompt, &self.retry).await
+            .with_context(|| format!("Ошибка перевода release notes на локаль {}", locale))?;
+        Ok(response.trim().to_string())
+    }
+
     /// Парсит release notes на компоненты
     fn parse_release_notes(&self, notes: &str) -> (String, Vec<String>, String) {
-        let mut title = format!("Вышла новая версия плагина");
+        let mut title = self.language.default_release_title().to_string();
         let mut highlights = Vec::new();
         let mut body_lines = Vec::new();
         let mut _in_highlights = false;
@@ -619,20 +942,44 @@ impl ReleaseAgent {
         }
 
         if title.is_empty() {
-            title = "🎉 Вышла новая версия плагина".to_string();
+            title = format!("🎉 {}", self.language.default_release_title());
         }
 
         (title, highlights, body_lines.join("\n"))
     }
 }
 
-impl LLMAgent for ReleaseAgent {
-    async fn generate_response(&self, input: &str) -> Result<String> {
-        self.client.chat_completion_with_retry(input, 3).await
+impl ConversationalAgent for ReleaseAgent {
+    /// Версия релиза
+    type Context = str;
+    type Output = GeneratedReleaseNotes;
+
+    fn client(&self) -> &dyn LlmProvider {
+        self.client.as_ref()
+    }
+
+    fn retry(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// Текстовые release notes не уточняются: разбор принимает любой Markdown
+    fn max_clarifications(&self) -> usize {
+        0
     }
 
-    fn get_agent_name(&self) -> &'static str {
-        "ReleaseAgent"
+    async fn first_response(&self, prompt: &str) -> Result<String> {
+        complete(self.client.as_ref(), self.stream.as_ref(), prompt, &self.retry).await
+    }
+
+    fn assess(&self, version: &str, response: &str) -> Assessment<GeneratedReleaseNotes> {
+        let (title, highlights, body) = self.parse_release_notes(response);
+        Assessment::Complete(GeneratedReleaseNotes {
+            title,
+            subtitle: self.language.release_subtitle(version),
+            highlights,
+            body,
+            version: version.to_string(),
+        })
     }
 }
 
@@ -645,31 +992,93 @@ pub struct PluginInfo {
     pub description: Option<String>,
 }
 
+/// Модель, температура и системный промпт агента из `[llm_agents]` (`None` — как у провайдера).
+/// Модели YandexGPT из шаблона конфига другим провайдерам не передаются: OpenAI и Ollama их не знают
+fn agent_overrides<'a>(
+    config: &Config,
+    agent: &str,
+    settings: &'a AgentConfig,
+) -> (Option<&'a str>, Option<f32>, Option<&'a str>) {
+    let model = Some(settings.model.trim()).filter(|m| !m.is_empty());
+    let model = match model {
+        Some(m) if config.llm.provider != "yandexgpt" && m.starts_with("yandexgpt") => {
+            warn!(
+                "Модель {} агента {} относится к YandexGPT, а провайдер — {}; используется модель провайдера",
+                m, agent, config.llm.provider
+            );
+            None
+        }
+        model => model,
+    };
+    (model, settings.temperature, settings.system_prompt.as_deref().map(str::trim))
+}
+
 /// Менеджер LLM агентов
 pub struct LLMAgentManager {
     pub(crate) changelog_agent: ChangelogAgent,
     pub(crate) version_agent: VersionAgent,
     pub(crate) release_agent: ReleaseAgent,
+    pub(crate) qa_agent: QaAgent,
+    pub(crate) review_agent: ReviewAgent,
+    cost: CostGuard,
+    clock: SharedClock,
 }
 
 impl LLMAgentManager {
-    /// Создает менеджер агентов из конфигурации
-    pub fn from_config(config: &crate::config::parser::Config) -> Result<Self> {
-        let yandex_config = YandexGPTConfig {
-            api_key: config.yandexgpt.api_key.clone(),
-            folder_id: config.yandexgpt.folder_id.clone(),
-            model: config.yandexgpt.model.clone(),
-            temperature: 0.3,
-            max_tokens: 2000,
-            timeout: std::time::Duration::from_secs(30),
+    /// Создает менеджер агентов из конфигурации; запросы всех агентов учитываются общим `CostGuard`,
+    /// а `clock` ставит отметки времени в стенограмме и передается менеджеру релизов
+    pub fn from_config(config: &crate::config::parser::Config, clock: SharedClock) -> Result<Self> {
+        let client = provider::from_config(config, clock.clone())?;
+        info!("LLM провайдер: {} ({})", client.name(), client.get_model_info());
+        let cost = CostGuard::new(&config.llm, client.transcript().clone());
+        let client: Box<dyn LlmProvider> = Box::new(CostLimited::new(client, cost.clone()));
+
+        let retry = RetryPolicy::from(&config.llm.retry);
+        let agents = &config.llm_agents;
+        let language = config.llm.output_language;
+        let for_agent = |name: &str, settings: &AgentConfig| {
+            let (model, temperature, system_prompt) = agent_overrides(config, name, settings);
+            let agent_client = client.for_agent_with(
+                name,
+                model,
+                temperature,
+                Some(system_prompt.unwrap_or(language.system_prompt())),
+            );
+            if model.is_some() || temperature.is_some() || system_prompt.is_some() {
+                info!("Агент {}: модель {}, температура {}, системный промпт {}", name, agent_client.get_model_info(),
+                    temperature.map_or_else(|| "по умолчанию".to_string(), |t| t.to_string()),
+                    if system_prompt.is_some() { "свой" } else { "общий" });
+            }
+            agent_client
         };
-
-        let client = YandexGPTClient::new(yandex_config);
+        let mut changelog_agent = ChangelogAgent::new(for_agent("changelog", &agents.changelog_agent))
+            .with_clustering(&config.changelog.clustering)
+            .with_changelog_config(&config.changelog)
+            .with_language(language)
+            .with_retry(retry.clone());
+        let mut release_agent = ReleaseAgent::new(for_agent("release", &agents.release_agent))
+            .with_language(language)
+            .with_retry(retry.clone());
+        if config.changelog.diff_summary.enabled {
+            let diff_agent = DiffSummaryAgent::new(for_agent("diff", &agents.diff_agent), &config.changelog.diff_summary)
+                .with_retry(retry.clone());
+            changelog_agent = changelog_agent.with_diff_summary(diff_agent);
+        }
+        if config.llm.structured_output {
+            changelog_agent = changelog_agent.with_structured_output(config.llm.json_fix_attempts);
+            release_agent = release_agent.with_structured_output(config.llm.json_fix_attempts);
+        }
 
         Ok(Self {
-            changelog_agent: ChangelogAgent::new(client.clone()),
-            version_agent: VersionAgent::new(client.clone()),
-            release_agent: ReleaseAgent::new(client),
+            changelog_agent,
+            version_agent: VersionAgent::new(for_agent("version", &agents.version_agent)).with_retry(retry.clone()),
+            release_agent,
+            qa_agent: QaAgent::new(client.for_agent("qa")).with_retry(retry.clone()),
+            review_agent: ReviewAgent::new(for_agent("review", &agents.review_agent))
+                .with_retry(retry)
+                .with_fix_attempts(config.llm.json_fix_attempts),
+            cost,
+            clock,
         })
     }
 
@@ -678,12 +1087,30 @@ impl LLMAgentManager {
         let client = YandexGPTClientFactory::from_env()?;
 
         Ok(Self {
-            changelog_agent: ChangelogAgent::new(client.clone()),
-            version_agent: VersionAgent::new(client.clone()),
-            release_agent: ReleaseAgent::new(client),
+            changelog_agent: ChangelogAgent::new(client.for_agent("changelog"))
+                .with_clustering(&ClusteringConfig::default()),
+            version_agent: VersionAgent::new(client.for_agent("version")),
+            release_agent: ReleaseAgent::new(client.for_agent("release")),
+            qa_agent: QaAgent::new(client.for_agent("qa")),
+            review_agent: ReviewAgent::new(client.for_agent("review")),
+            cost: CostGuard::new(&Default::default(), client.transcript().clone()),
+            clock: system_clock(),
         })
     }
 
+    /// Включает потоковый вывод changelog и release notes
+    pub fn with_stream(mut self, sink: StreamSink) -> Self {
+        self.changelog_agent = self.changelog_agent.with_stream(sink.clone());
+        self.release_agent = self.release_agent.with_stream(sink);
+        self
+    }
+
+    /// Предел части diff для `ai review --chunk-chars`
+    pub fn with_review_chunk_chars(mut self, chunk_chars: usize) -> Self {
+        self.review_agent = self.review_agent.with_chunk_chars(chunk_chars);
+        self
+    }
+
     /// Генерирует changelog
     pub async fn generate_changelog(&self, version_info: &VersionInfo) -> Result<GeneratedChangelog> {
         self.changelog_agent.generate_changelog(version_info).await
@@ -699,6 +1126,36 @@ impl LLMAgentManager {
         self.release_agent.generate_release_notes(version, changelog, plugin_info).await
     }
 
+    /// Переводит release notes на язык локали
+    pub async fn translate_release_notes(&self, version: &str, release_notes: &str, locale: &str, plugin_info: &PluginInfo) -> Result<String> {
+        self.release_agent.translate_release_notes(version, release_notes, locale, plugin_info).await
+    }
+
+    /// Отвечает на вопрос по выбранным коммитам репозитория
+    pub async fn ask(&self, project_name: &str, question: &str, context: Vec<ContextCommit>) -> Result<RepoAnswer> {
+        self.qa_agent.ask(project_name, question, context).await
+    }
+
+    /// Код-ревью diff между `from` и `to`
+    pub async fn review(&self, project_name: &str, from: &str, to: &str, diff: &str) -> Result<CodeReview> {
+        self.review_agent.review(project_name, from, to, diff).await
+    }
+
+    /// Общая стенограмма запросов всех агентов
+    pub fn transcript(&self) -> Transcript {
+        self.changelog_agent.client.transcript().clone()
+    }
+
+    /// Учет стоимости запросов всех агентов и бюджет запуска
+    pub fn cost(&self) -> CostGuard {
+        self.cost.clone()
+    }
+
+    /// Часы запуска, общие для агентов и менеджера релизов
+    pub fn clock(&self) -> SharedClock {
+        self.clock.clone()
+    }
+
     /// Проверяет доступность всех агентов
     pub async fn health_check(&self) -> Result<bool> {
         match self.changelog_agent.client.health_check().await {
@@ -946,4 +1403,148 @@ impl ReadinessLevel {
             ReadinessLevel::NotReady => "Не готов к релизу",
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::conversation::ChatTurn;
+    use super::super::scripted::ScriptedProvider;
+    use crate::utils::clock::FixedClock;
+
+    /// Клиент агента с заготовленными ответами и провайдер для проверки полученных историй
+    fn scripted(responses: Vec<&'static str>) -> (Box<dyn LlmProvider>, ScriptedProvider) {
+        let provider = ScriptedProvider::replies(responses);
+        (provider.for_agent("test"), provider)
+    }
+
+    #[tokio::test]
+    async fn test_conversational_agent_clarifies_until_complete() {
+        let version_info = VersionInfo {
+            current_version: "1.2.0".to_string(),
+            new_version: None,
+            branch: "main".to_string(),
+            git_log: Some("abc1234: feat: экспорт".to_string()),
+            changes_count: 1,
+        };
+
+        // Ответ без версии уточняется в том же диалоге, наружу — разобранный результат
+        let (client, provider) = scripted(vec!["Думаю, нужен minor-релиз", "\"1.3.0: новые функции\""]);
+        let analysis = VersionAgent::new(client).suggest_version(&version_info).await.unwrap();
+        assert_eq!((analysis.suggested_version.as_str(), analysis.reasoning.as_str()), ("1.3.0", "новые функции"));
+        assert_eq!(analysis.change_types, ["feature"]);
+        let histories = provider.histories();
+        assert_eq!(histories.len(), 2);
+        assert_eq!(histories[1][1], ChatTurn::assistant("Думаю, нужен minor-релиз"));
+        assert_eq!(histories[1][2], ChatTurn::user(VERSION_FORMAT_PROMPT));
+
+        // Лимит уточнений исчерпан: остается последний разобранный вариант
+        let (client, provider) = scripted(vec!["minor", "minor, без сомнений"]);
+        let analysis = VersionAgent::new(client).suggest_version(&version_info).await.unwrap();
+        assert_eq!((analysis.suggested_version.as_str(), analysis.confidence), ("1.0.0", 0.5));
+        assert_eq!(provider.histories().len(), 1 + VERSION_CLARIFICATIONS);
+
+        // Changelog в пределах `max_length` принимается без уточнений
+        let (client, provider) = scripted(vec!["## Изменения\n### 🐛 Исправления\n- Падение при запуске"]);
+        let config = ChangelogConfig { max_length: Some(100), ..Default::default() };
+        let agent = ChangelogAgent::new(client).with_changelog_config(&config);
+        let changelog = agent.converse("changelog", "1.2.1").await.unwrap();
+        assert_eq!((changelog.version.as_str(), changelog.total_changes), ("1.2.1", 1));
+        assert_eq!(provider.histories().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_release_package_is_reproducible_with_frozen_clock() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git").args(args).current_dir(dir.path()).status().unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        std::fs::write(dir.path().join("search.rs"), "fn search() {}\n").unwrap();
+        git(&["add", "-A"]);
+        git(&["-c", "user.name=Dev", "-c", "user.email=dev@example.com", "commit", "-qm", "feat: поиск по истории"]);
+
+        let mut config = crate::config::parser::test_config();
+        config.llm.provider = "mock".to_string();
+        let repo = GitRepository::from_config(dir.path(), &config).unwrap();
+        let plugin = PluginInfo { name: "Ride".to_string(), id: "ru.marslab.ide.ride".to_string(), version: "1.2.0".to_string(), description: None };
+        let frozen = chrono::DateTime::parse_from_rfc3339("2024-03-01T10:00:00Z").unwrap().with_timezone(&chrono::Utc);
+
+        // Два независимых запуска с одними замороженными часами дают одинаковый пакет и стенограмму
+        let mut runs = Vec::new();
+        for _ in 0..2 {
+            let manager = LLMAgentManager::from_config(&config, Arc::new(FixedClock::new(frozen))).unwrap();
+            let package = manager.generate_release_package(&repo, "1.2.0", &plugin).await.unwrap();
+            assert!(manager.transcript().entries().iter().all(|e| e.timestamp == frozen));
+            runs.push((serde_json::to_value(&package).unwrap(), manager.transcript().to_json("1.3.0").unwrap()));
+        }
+        assert_eq!(runs[0], runs[1]);
+        assert_eq!(runs[0].0["version"], "1.3.0");
+        assert!(runs[0].1.contains("\"created_at\": \"2024-03-01T10:00:00Z\""));
+    }
+
+    #[test]
+    fn test_agents_use_models_from_llm_agents() {
+        let mut config = crate::config::parser::test_config();
+        let manager = LLMAgentManager::from_config(&config, system_clock()).unwrap();
+        assert_eq!(manager.changelog_agent.client.get_model_info(), "yandexgpt");
+        assert_eq!(manager.version_agent.client.get_model_info(), "yandexgpt-lite");
+        assert_eq!(manager.release_agent.client.get_model_info(), "yandexgpt");
+
+        // Пустая модель — модель провайдера; модели YandexGPT не уходят в Ollama
+        config.llm_agents.changelog_agent.model = String::new();
+        config.llm_agents.release_agent.model = "qwen2.5:14b".to_string();
+        config.llm.provider = "ollama".to_string();
+        let manager = LLMAgentManager::from_config(&config, system_clock()).unwrap();
+        assert_eq!(manager.changelog_agent.client.get_model_info(), "llama3.1");
+        assert_eq!(manager.version_agent.client.get_model_info(), "llama3.1");
+        assert_eq!(manager.release_agent.client.get_model_info(), "qwen2.5:14b");
+    }
+
+    #[tokio::test]
+    async fn test_output_language_switches_prompts_and_titles() {
+        let mut config = crate::config::parser::test_config();
+        config.llm.provider = "ollama".to_string();
+        config.ollama = Some(crate::config::parser::OllamaConfig {
+            host: "http://127.0.0.1:9".to_string(),
+            ..Default::default()
+        });
+        config.llm.output_language = OutputLanguage::En;
+        let manager = LLMAgentManager::from_config(&config, system_clock()).unwrap();
+
+        let sections = manager.changelog_agent.parse_changelog_sections("### 🐛 Bug fixes\n- Fixed crash\n### Improvements\n- Faster startup");
+        let titles: Vec<&str> = sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["🐛 Bug fixes", "🔧 Improvements"]);
+        assert_eq!(manager.release_agent.language.release_subtitle("1.2.0"), "Version 1.2.0 is now available!");
+
+        assert!(manager.changelog_agent.client.chat_completion("changelog").await.is_err());
+        assert_eq!(manager.transcript().entries()[0].system, OutputLanguage::En.system_prompt());
+        assert!(OutputLanguage::En.changelog_prompt().contains("in English"));
+        assert_eq!(ChangeType::Fix.title(OutputLanguage::Ru), "Исправления");
+    }
+
+    #[tokio::test]
+    async fn test_agents_use_system_prompts_from_llm_agents() {
+        let mut config = crate::config::parser::test_config();
+        config.llm.provider = "ollama".to_string();
+        config.ollama = Some(crate::config::parser::OllamaConfig {
+            host: "http://127.0.0.1:9".to_string(),
+            ..Default::default()
+        });
+        config.llm_agents.changelog_agent.system_prompt = Some("Пиши changelog в стиле Keep a Changelog".to_string());
+        let manager = LLMAgentManager::from_config(&config, system_clock()).unwrap();
+
+        // Сервер недоступен, но запрос со своим системным промптом попадает в стенограмму
+        assert!(manager.changelog_agent.client.chat_completion("changelog").await.is_err());
+        assert!(manager.release_agent.client.chat_completion("notes").await.is_err());
+        let systems: Vec<(String, String)> =
+            manager.transcript().entries().into_iter().map(|e| (e.agent, e.system)).collect();
+        assert_eq!(
+            systems,
+            [
+                ("changelog".to_string(), "Пиши changelog в стиле Keep a Changelog".to_string()),
+                ("release".to_string(), super::super::yandexgpt::SYSTEM_PROMPT.to_string()),
+            ]
+        );
+    }
+}
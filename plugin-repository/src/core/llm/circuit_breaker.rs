@@ -0,0 +1,234 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use super::provider::{ChunkCallback, LlmProvider};
+use super::conversation::ChatTurn;
+use super::health::ProviderCheck;
+use super::transcript::Transcript;
+
+/// Запрос отклонен без обращения к API: провайдер признан недоступным.
+/// Retry по умолчанию на этой ошибке прекращает попытки
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("LLM провайдер {provider} недоступен: {failures} ошибок подряд, повторная попытка через {} с", retry_in.as_secs())]
+pub struct CircuitOpen {
+    pub provider: String,
+    pub failures: u32,
+    pub retry_in: Duration,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+/// Общий для всех агентов автомат: после `threshold` ошибок подряд запросы отклоняются
+/// сразу, через `cooldown` пропускается пробный запрос, и успех снова открывает доступ
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self { threshold, cooldown, state: Arc::default() }
+    }
+
+    /// `Err` с оставшимся временем, пока после срабатывания не прошел `cooldown`
+    fn check(&self) -> std::result::Result<(), (u32, Duration)> {
+        let state = self.state.lock().unwrap();
+        match state.opened_at {
+            Some(opened_at) if opened_at.elapsed() < self.cooldown => {
+                Err((state.consecutive_failures, self.cooldown - opened_at.elapsed()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Учитывает результат запроса; `true`, если эта ошибка разомкнула цепь
+    fn record(&self, success: bool) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if success {
+            if state.opened_at.take().is_some() {
+                info!("✅ LLM провайдер снова отвечает");
+            }
+            state.consecutive_failures = 0;
+            return false;
+        }
+        state.consecutive_failures += 1;
+        if state.consecutive_failures < self.threshold {
+            return false;
+        }
+        let tripped = state.opened_at.is_none();
+        state.opened_at = Some(Instant::now());
+        tripped
+    }
+}
+
+/// Провайдер, запросы которого проходят через общий `CircuitBreaker`
+pub struct CircuitBreaking {
+    inner: Box<dyn LlmProvider>,
+    breaker: CircuitBreaker,
+}
+
+impl CircuitBreaking {
+    pub fn new(inner: Box<dyn LlmProvider>, breaker: CircuitBreaker) -> Self {
+        Self { inner, breaker }
+    }
+
+    fn open_error(&self, failures: u32, retry_in: Duration) -> CircuitOpen {
+        CircuitOpen { provider: self.inner.name().to_string(), failures, retry_in }
+    }
+
+    /// Пропускает запрос через автомат; ошибка, разомкнувшая цепь, помечается `CircuitOpen`,
+    /// чтобы retry не ждал следующей попытки
+    async fn guard<T>(&self, request: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+        if let Err((failures, retry_in)) = self.breaker.check() {
+            return Err(self.open_error(failures, retry_in).into());
+        }
+        let result = request.await;
+        if self.breaker.record(result.is_ok()) {
+            warn!(
+                "⛔ LLM провайдер {} отключен на {} с после {} ошибок подряд",
+                self.inner.name(),
+                self.breaker.cooldown.as_secs(),
+                self.breaker.threshold
+            );
+            return result.map_err(|e| e.context(self.open_error(self.breaker.threshold, self.breaker.cooldown)));
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl LlmProvider for CircuitBreaking {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn get_model_info(&self) -> &str {
+        self.inner.get_model_info()
+    }
+
+    fn for_agent(&self, agent: &str) -> Box<dyn LlmProvider> {
+        Box::new(Self::new(self.inner.for_agent(agent), self.breaker.clone()))
+    }
+
+    fn for_agent_with(&self, agent: &str, model: Option<&str>, temperature: Option<f32>, system_prompt: Option<&str>) -> Box<dyn LlmProvider> {
+        Box::new(Self::new(self.inner.for_agent_with(agent, model, temperature, system_prompt), self.breaker.clone()))
+    }
+
+    fn transcript(&self) -> &Transcript {
+        self.inner.transcript()
+    }
+
+    async fn chat_completion(&self, prompt: &str) -> Result<String> {
+        self.guard(self.inner.chat_completion(prompt)).await
+    }
+
+    async fn chat_conversation(&self, history: &[ChatTurn]) -> Result<String> {
+        self.guard(self.inner.chat_conversation(history)).await
+    }
+
+    /// Проверка доступности всегда обращается к API
+    async fn health_check(&self) -> Result<bool> {
+        self.inner.health_check().await
+    }
+
+    async fn diagnose(&self) -> Vec<ProviderCheck> {
+        self.inner.diagnose().await
+    }
+
+    async fn chat_completion_stream(&self, prompt: &str, on_chunk: ChunkCallback<'_>) -> Result<String> {
+        self.guard(self.inner.chat_completion_stream(prompt, on_chunk)).await
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        self.inner.supports_embeddings()
+    }
+
+    async fn text_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        self.guard(self.inner.text_embedding(text)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::provider::RetryPolicy;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+    /// Провайдер, который отвечает, пока `up` установлен
+    struct Switchable {
+        up: Arc<AtomicBool>,
+        calls: Arc<AtomicU32>,
+        transcript: Transcript,
+    }
+
+    #[async_trait]
+    impl LlmProvider for Switchable {
+        fn name(&self) -> &'static str {
+            "switchable"
+        }
+
+        fn get_model_info(&self) -> &str {
+            "model"
+        }
+
+        fn for_agent(&self, _agent: &str) -> Box<dyn LlmProvider> {
+            Box::new(Self { up: self.up.clone(), calls: self.calls.clone(), transcript: self.transcript.clone() })
+        }
+
+        fn transcript(&self) -> &Transcript {
+            &self.transcript
+        }
+
+        async fn chat_completion(&self, _prompt: &str) -> Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.up.load(Ordering::SeqCst) {
+                Ok("ok".to_string())
+            } else {
+                Err(anyhow::anyhow!("503"))
+            }
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(self.up.load(Ordering::SeqCst))
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_circuit_breaker_fails_fast_and_recovers() {
+        let up = Arc::new(AtomicBool::new(false));
+        let calls = Arc::new(AtomicU32::new(0));
+        let inner = Switchable { up: up.clone(), calls: calls.clone(), transcript: Transcript::new(vec![]) };
+        let provider = CircuitBreaking::new(Box::new(inner), CircuitBreaker::new(2, Duration::from_secs(60)));
+        let changelog = provider.for_agent("changelog");
+        let release = provider.for_agent("release");
+
+        // Вторая ошибка размыкает цепь, и retry прекращается без лишних задержек
+        let started = Instant::now();
+        let err = changelog.chat_completion_with_retry("q", &RetryPolicy::default()).await.unwrap_err();
+        assert!(err.is::<CircuitOpen>());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(Instant::now() - started, Duration::from_secs(1));
+
+        // Другой агент получает отказ сразу, без обращения к API
+        let err = release.chat_completion("q").await.unwrap_err();
+        assert!(err.to_string().starts_with("LLM провайдер switchable недоступен: 2 ошибок подряд"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // После паузы пробный запрос проходит и замыкает цепь
+        up.store(true, Ordering::SeqCst);
+        tokio::time::advance(Duration::from_secs(60)).await;
+        assert_eq!(release.chat_completion("q").await.unwrap(), "ok");
+        assert_eq!(changelog.chat_completion("q").await.unwrap(), "ok");
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+}
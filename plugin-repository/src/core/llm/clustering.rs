@@ -0,0 +1,260 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use tracing::{info, warn};
+
+use super::provider::LlmProvider;
+use crate::config::parser::ClusteringConfig;
+use crate::git::GitCommit;
+
+/// Размерность локального эмбеддинга
+const LOCAL_DIMENSIONS: usize = 256;
+
+/// Слова, не влияющие на смысл коммита
+const NOISE_WORDS: &[&str] = &[
+    "the", "and", "for", "with", "from", "into", "add", "adds", "added", "fix", "fixes", "fixed",
+    "update", "updated", "use", "when", "after", "before", "feat", "chore", "refactor", "docs", "test",
+    "tests", "merge", "branch", "pull", "request",
+];
+
+/// Источник эмбеддингов коммитов
+pub enum Embedder {
+    /// Хешированный мешок слов: работает без сети, детерминирован
+    Local,
+    /// Эмбеддинги LLM провайдера (YandexGPT text-search-doc, `embedding_model` OpenAI или
+    /// Ollama); при ошибке — локальный вариант
+    Remote(Box<dyn LlmProvider>),
+}
+
+impl Embedder {
+    pub fn from_config(config: &ClusteringConfig, client: &dyn LlmProvider) -> Self {
+        match config.provider.as_str() {
+            "local" => Embedder::Local,
+            provider if client.name() == provider && client.supports_embeddings() => {
+                Embedder::Remote(client.for_agent("clustering"))
+            }
+            provider => {
+                warn!(
+                    "⚠️ Эмбеддинги {} недоступны при провайдере {} (нужен тот же провайдер и embedding_model), используется локальная группировка",
+                    provider,
+                    client.name()
+                );
+                Embedder::Local
+            }
+        }
+    }
+
+    /// Эмбеддинги сообщений коммитов в том же порядке
+    pub async fn embed(&self, messages: &[String]) -> Vec<Vec<f32>> {
+        match self {
+            Embedder::Local => messages.iter().map(|m| local_embedding(m)).collect(),
+            Embedder::Remote(client) => {
+                let mut embeddings = Vec::with_capacity(messages.len());
+                for message in messages {
+                    match client.text_embedding(message).await {
+                        Ok(embedding) => embeddings.push(normalize(embedding)),
+                        Err(e) => {
+                            warn!("⚠️ Эмбеддинги {} недоступны, используется локальная группировка: {:#}", client.name(), e);
+                            return messages.iter().map(|m| local_embedding(m)).collect();
+                        }
+                    }
+                }
+                embeddings
+            }
+        }
+    }
+}
+
+/// Группа семантически близких коммитов
+#[derive(Debug, Clone)]
+pub struct CommitCluster {
+    /// Общий scope (`feat(deployer): ...`) или первое сообщение группы
+    pub label: String,
+    pub commits: Vec<GitCommit>,
+}
+
+/// Токены сообщения: scope conventional commit учитывается с повышенным весом
+fn tokens(message: &str) -> Vec<(String, f32)> {
+    let first_line = message.lines().next().unwrap_or_default().to_lowercase();
+    let mut out = Vec::new();
+    if let Some(scope) = scope(&first_line) {
+        out.push((format!("scope:{}", scope), 3.0));
+    }
+    for word in first_line.split(|c: char| !c.is_alphanumeric()) {
+        if word.chars().count() < 3 || NOISE_WORDS.contains(&word) || word.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        out.push((word.to_string(), 1.0));
+    }
+    out
+}
+
+/// Scope из заголовка `type(scope): ...`
+pub(crate) fn scope(first_line: &str) -> Option<String> {
+    let open = first_line.find('(')?;
+    let close = first_line[open..].find(')')? + open;
+    let colon = first_line.find(':')?;
+    (close < colon).then(|| first_line[open + 1..close].trim().to_string()).filter(|s| !s.is_empty())
+}
+
+/// Локальный эмбеддинг: хешированный мешок слов, нормализованный по длине
+pub fn local_embedding(message: &str) -> Vec<f32> {
+    let mut vector = vec![0.0; LOCAL_DIMENSIONS];
+    for (token, weight) in tokens(message) {
+        let mut hasher = DefaultHasher::new();
+        token.hash(&mut hasher);
+        vector[(hasher.finish() as usize) % LOCAL_DIMENSIONS] += weight;
+    }
+    normalize(vector)
+}
+
+fn normalize(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Жадная кластеризация по центроидам: коммит присоединяется к самой близкой группе,
+/// если сходство не ниже `threshold`, иначе открывает новую. Порядок групп — по первому коммиту.
+pub fn cluster(embeddings: &[Vec<f32>], threshold: f32) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut centroids: Vec<Vec<f32>> = Vec::new();
+
+    for (index, embedding) in embeddings.iter().enumerate() {
+        let best = centroids
+            .iter()
+            .enumerate()
+            .map(|(group, centroid)| (group, cosine(centroid, embedding)))
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        match best {
+            Some((group, _)) => {
+                groups[group].push(index);
+                let size = groups[group].len() as f32;
+                let centroid: Vec<f32> = centroids[group]
+                    .iter()
+                    .zip(embedding)
+                    .map(|(c, e)| c + (e - c) / size)
+                    .collect();
+                centroids[group] = normalize(centroid);
+            }
+            None => {
+                groups.push(vec![index]);
+                centroids.push(embedding.clone());
+            }
+        }
+    }
+    groups
+}
+
+/// Первые строки сообщений коммитов
+fn subjects(commits: &[GitCommit]) -> Vec<String> {
+    commits.iter().map(|c| c.message.lines().next().unwrap_or_default().to_string()).collect()
+}
+
+/// Сворачивает повторы (десяток «fix typo») в первый коммит группы с числом повторов,
+/// чтобы LLM написала по ним одну строку changelog. Порядок коммитов сохраняется
+pub async fn collapse_duplicates(commits: &[GitCommit], embedder: &Embedder, threshold: f32) -> Vec<GitCommit> {
+    let embeddings = embedder.embed(&subjects(commits)).await;
+    let groups = cluster(&embeddings, threshold);
+    if groups.len() < commits.len() {
+        info!("🧩 Свернуто повторяющихся коммитов: {}", commits.len() - groups.len());
+    }
+
+    groups
+        .into_iter()
+        .map(|group| {
+            let mut commit = commits[group[0]].clone();
+            if group.len() > 1 {
+                let subject = commit.message.lines().next().unwrap_or_default();
+                commit.message = format!("{} (повторов: {})", subject, group.len());
+            }
+            commit
+        })
+        .collect()
+}
+
+/// Группирует коммиты релиза для генерации changelog
+pub async fn cluster_commits(commits: &[GitCommit], embedder: &Embedder, threshold: f32) -> Vec<CommitCluster> {
+    let embeddings = embedder.embed(&subjects(commits)).await;
+    let groups = cluster(&embeddings, threshold);
+    info!("🧩 {} коммитов сгруппировано в {} тем", commits.len(), groups.len());
+
+    groups
+        .into_iter()
+        .map(|group| {
+            let commits: Vec<GitCommit> = group.into_iter().map(|i| commits[i].clone()).collect();
+            let first = commits[0].message.lines().next().unwrap_or_default().to_lowercase();
+            let label = scope(&first).unwrap_or_else(|| commits[0].message.lines().next().unwrap_or_default().to_string());
+            CommitCluster { label, commits }
+        })
+        .collect()
+}
+
+/// Git-лог для промпта: одна группа — одна запись changelog
+pub fn format_clusters(clusters: &[CommitCluster]) -> String {
+    let mut out = String::from(
+        "Коммиты сгруппированы по темам. Для каждой группы сформируй ОДНУ запись changelog, \
+         объединяющую все её коммиты.\n\n",
+    );
+    for (index, cluster) in clusters.iter().enumerate() {
+        out.push_str(&format!("Группа {} ({}, коммитов: {}):\n", index + 1, cluster.label, cluster.commits.len()));
+        for commit in &cluster.commits {
+            out.push_str(&format!("  {}: {}\n", commit.short_hash, commit.changelog_input()));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_clustering_groups_same_feature() {
+        let messages = [
+            "feat(deployer): add conflict detection",
+            "fix(deployer): conflict detection for channels",
+            "docs: describe proxy settings",
+            "feat(deployer): report conflicts in plan",
+            "docs: proxy settings example",
+        ];
+        let embeddings: Vec<_> = messages.iter().map(|m| local_embedding(m)).collect();
+
+        let groups = cluster(&embeddings, 0.6);
+        assert_eq!(groups, vec![vec![0, 1, 3], vec![2, 4]]);
+        assert_eq!(scope("feat(deployer): add conflict detection").as_deref(), Some("deployer"));
+        assert_eq!(scope("fix: typo (readme)"), None);
+    }
+
+    #[tokio::test]
+    async fn test_collapse_duplicates_keeps_one_line_per_repeat() {
+        let commit = |hash: &str, message: &str| -> GitCommit {
+            serde_json::from_value(serde_json::json!({
+                "hash": hash, "short_hash": hash, "message": message, "author": "dev", "email": "dev@example.com",
+                "date": "2026-01-01T00:00:00Z", "files_changed": 1, "insertions": 1, "deletions": 0
+            }))
+            .unwrap()
+        };
+        let mut commits = vec![commit("a1", "feat(deployer): add conflict detection")];
+        commits.extend((0..10).map(|i| commit(&format!("b{}", i), if i % 2 == 0 { "fix typo\n\nв README" } else { "Fix typo." })));
+        commits.push(commit("c1", "fix typo in deployer conflict report"));
+
+        let collapsed = collapse_duplicates(&commits, &Embedder::Local, 0.9).await;
+        let lines: Vec<(&str, &str)> = collapsed.iter().map(|c| (c.short_hash.as_str(), c.message.as_str())).collect();
+        assert_eq!(
+            lines,
+            [
+                ("a1", "feat(deployer): add conflict detection"),
+                ("b0", "fix typo (повторов: 10)"),
+                ("c1", "fix typo in deployer conflict report"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::Result;
+
+use super::provider::LlmProvider;
+
+/// Автор реплики; системный промпт провайдер добавляет сам
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+impl Role {
+    /// Роль в формате API (`user`, `assistant`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+        }
+    }
+}
+
+/// Реплика диалога
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatTurn {
+    pub role: Role,
+    pub text: String,
+}
+
+impl ChatTurn {
+    pub fn user(text: impl Into<String>) -> Self {
+        Self { role: Role::User, text: text.into() }
+    }
+
+    pub fn assistant(text: impl Into<String>) -> Self {
+        Self { role: Role::Assistant, text: text.into() }
+    }
+}
+
+/// История одним промптом: для стенограммы и провайдеров без истории сообщений
+pub fn flatten(history: &[ChatTurn]) -> String {
+    if let [turn] = history {
+        return turn.text.clone();
+    }
+    history
+        .iter()
+        .map(|turn| {
+            let author = match turn.role {
+                Role::User => "Пользователь",
+                Role::Assistant => "Ассистент",
+            };
+            format!("{}:\n{}", author, turn.text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Диалог агента с моделью: уточняющие запросы уходят вместе с предыдущими репликами,
+/// так что модель правит свой ответ, а не пишет его заново
+#[derive(Debug, Clone, Default)]
+pub struct Conversation {
+    turns: Vec<ChatTurn>,
+}
+
+impl Conversation {
+    /// Диалог, начатый запросом `prompt`
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self { turns: vec![ChatTurn::user(prompt)] }
+    }
+
+    /// Добавляет ответ модели, полученный вне диалога (например, потоковым запросом)
+    pub fn push_assistant(&mut self, text: impl Into<String>) {
+        self.turns.push(ChatTurn::assistant(text));
+    }
+
+    /// Следующий запрос в диалоге; ответ добавляется в историю. При ошибке история
+    /// остается прежней, и запрос можно повторить
+    pub async fn follow_up(&mut self, client: &dyn LlmProvider, text: impl Into<String>) -> Result<String> {
+        self.turns.push(ChatTurn::user(text));
+        match client.chat_conversation(&self.turns).await {
+            Ok(response) => {
+                self.push_assistant(response.clone());
+                Ok(response)
+            }
+            Err(e) => {
+                self.turns.pop();
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::scripted::ScriptedProvider;
+
+    #[tokio::test]
+    async fn test_conversation_sends_history_with_follow_up() {
+        // Отвечает длиной полученной истории; реплика «сбой» — ошибка провайдера
+        let client = ScriptedProvider::new(|_, history| match history.last().map(|turn| turn.text.as_str()) {
+            Some("сбой") => Err(anyhow::anyhow!("503")),
+            _ => Ok(format!("ответ {}", history.len())),
+        });
+        let mut conversation = Conversation::new("changelog");
+        conversation.push_assistant("длинный changelog");
+
+        assert_eq!(conversation.follow_up(&client, "сократи").await.unwrap(), "ответ 3");
+        assert_eq!(
+            client.histories()[0],
+            [ChatTurn::user("changelog"), ChatTurn::assistant("длинный changelog"), ChatTurn::user("сократи")]
+        );
+        assert_eq!(conversation.turns.last(), Some(&ChatTurn::assistant("ответ 3")));
+
+        // Неудачный запрос не остается в истории
+        assert!(conversation.follow_up(&client, "сбой").await.is_err());
+        assert_eq!(conversation.turns.len(), 4);
+
+        assert_eq!(
+            flatten(&conversation.turns[..2]),
+            "Пользователь:\nchangelog\n\nАссистент:\nдлинный changelog"
+        );
+    }
+}
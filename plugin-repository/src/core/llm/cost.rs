@@ -0,0 +1,330 @@
+//! Оценка стоимости запросов к LLM за запуск (`[llm.pricing]`) и бюджет `--max-cost`.
+//!
+//! Токены считаются по длине текста из стенограммы: провайдеры отдают usage в разном виде
+//! (а потоковые и асинхронные запросы — не всегда), поэтому отчет — оценка, а не счет.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+use super::conversation::{self, ChatTurn};
+use super::health::ProviderCheck;
+use super::provider::{ChunkCallback, LlmProvider};
+use super::transcript::{Transcript, TranscriptEntry};
+use crate::config::parser::{LlmConfig, LlmPricing};
+
+/// Символов на токен в среднем для смеси русского текста, английского и git log
+const CHARS_PER_TOKEN: usize = 3;
+
+/// Оценка числа токенов текста
+pub fn estimate_tokens(text: &str) -> u64 {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN) as u64
+}
+
+/// Запрос отклонен без обращения к API: оценка стоимости запуска превысила бюджет.
+/// Retry по умолчанию на этой ошибке прекращает попытки
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Бюджет LLM {max_cost:.4} {currency} исчерпан: потрачено ~{spent:.4}, запрос агента {agent} оценен в ~{request:.4}")]
+pub struct CostLimitExceeded {
+    pub agent: String,
+    pub spent: f64,
+    pub request: f64,
+    pub max_cost: f64,
+    pub currency: String,
+}
+
+/// Токены и стоимость одной модели
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelCost {
+    pub requests: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// `None` — для модели не задана цена в `[llm.pricing]`
+    pub cost: Option<f64>,
+}
+
+/// Сводка стоимости запуска по моделям провайдера
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostReport {
+    pub provider: String,
+    pub currency: String,
+    pub models: BTreeMap<String, ModelCost>,
+}
+
+impl CostReport {
+    pub fn total(&self) -> f64 {
+        self.models.values().filter_map(|m| m.cost).sum()
+    }
+
+    /// Строки сводки для вывода после команды
+    pub fn render(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (model, cost) in &self.models {
+            let price = cost
+                .cost
+                .map_or_else(|| "цена не задана".to_string(), |c| format!("~{:.4} {}", c, self.currency));
+            lines.push(format!(
+                "{}/{}: {} запросов, ~{} входных и ~{} выходных токенов, {}",
+                self.provider, model, cost.requests, cost.input_tokens, cost.output_tokens, price
+            ));
+        }
+        lines.push(format!("Итого: ~{:.4} {}", self.total(), self.currency));
+        lines
+    }
+}
+
+/// Учет стоимости запросов всех агентов по общей стенограмме и бюджет запуска.
+/// Клоны разделяют отметку о превышении: команда проверяет ее после этапа LLM,
+/// даже если агент заменил отклоненный ответ запасным вариантом
+#[derive(Clone)]
+pub struct CostGuard {
+    provider: String,
+    currency: String,
+    pricing: HashMap<String, LlmPricing>,
+    max_cost: Option<f64>,
+    transcript: Transcript,
+    exceeded: Arc<Mutex<Option<CostLimitExceeded>>>,
+}
+
+impl CostGuard {
+    pub fn new(llm: &LlmConfig, transcript: Transcript) -> Self {
+        Self {
+            provider: llm.provider.clone(),
+            currency: llm.currency.clone(),
+            pricing: llm.pricing.clone(),
+            max_cost: llm.max_cost,
+            transcript,
+            exceeded: Arc::default(),
+        }
+    }
+
+    /// Цена модели; если модель не описана — цена провайдера
+    fn pricing_for(&self, model: &str) -> Option<&LlmPricing> {
+        self.pricing.get(model).or_else(|| self.pricing.get(&self.provider))
+    }
+
+    fn entry_cost(&self, entry: &TranscriptEntry) -> (u64, u64, Option<f64>) {
+        let input = estimate_tokens(&entry.system) + estimate_tokens(&entry.prompt);
+        let output = entry.response.as_deref().map_or(0, estimate_tokens);
+        let cost = self.pricing_for(&entry.model).map(|p| p.cost(input, output));
+        (input, output, cost)
+    }
+
+    /// Сводка по всем запросам стенограммы
+    pub fn report(&self) -> CostReport {
+        let mut models: BTreeMap<String, ModelCost> = BTreeMap::new();
+        for entry in self.transcript.entries() {
+            let (input, output, cost) = self.entry_cost(&entry);
+            let model = models.entry(entry.model.clone()).or_default();
+            model.requests += 1;
+            model.input_tokens += input;
+            model.output_tokens += output;
+            if let Some(cost) = cost {
+                *model.cost.get_or_insert(0.0) += cost;
+            }
+        }
+        CostReport { provider: self.provider.clone(), currency: self.currency.clone(), models }
+    }
+
+    /// Пропускает запрос, если потраченное плюс оценка входа запроса укладываются в бюджет
+    fn admit(&self, agent: &str, model: &str, prompt: &str) -> Result<()> {
+        let Some(max_cost) = self.max_cost else {
+            return Ok(());
+        };
+        let request = self.pricing_for(model).map_or(0.0, |p| p.cost(estimate_tokens(prompt), 0));
+        let spent = self.report().total();
+        if spent + request <= max_cost {
+            return Ok(());
+        }
+        let exceeded = CostLimitExceeded {
+            agent: agent.to_string(),
+            spent,
+            request,
+            max_cost,
+            currency: self.currency.clone(),
+        };
+        warn!("💸 {}", exceeded);
+        self.exceeded.lock().unwrap().get_or_insert_with(|| exceeded.clone());
+        Err(exceeded.into())
+    }
+
+    /// Ошибка, если хотя бы один запрос был отклонен бюджетом
+    pub fn check(&self) -> Result<()> {
+        match self.exceeded.lock().unwrap().clone() {
+            Some(exceeded) => Err(exceeded.into()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl LlmPricing {
+    fn cost(&self, input_tokens: u64, output_tokens: u64) -> f64 {
+        (input_tokens as f64 * self.input_per_1m + output_tokens as f64 * self.output_per_1m) / 1_000_000.0
+    }
+}
+
+/// Провайдер, запросы которого проходят проверку бюджета `CostGuard`
+pub struct CostLimited {
+    inner: Box<dyn LlmProvider>,
+    guard: CostGuard,
+    agent: String,
+}
+
+impl CostLimited {
+    pub fn new(inner: Box<dyn LlmProvider>, guard: CostGuard) -> Self {
+        Self { inner, guard, agent: "default".to_string() }
+    }
+
+    fn admit(&self, prompt: &str) -> Result<()> {
+        self.guard.admit(&self.agent, self.inner.get_model_info(), prompt)
+    }
+}
+
+#[async_trait]
+impl LlmProvider for CostLimited {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn get_model_info(&self) -> &str {
+        self.inner.get_model_info()
+    }
+
+    fn for_agent(&self, agent: &str) -> Box<dyn LlmProvider> {
+        Box::new(Self { inner: self.inner.for_agent(agent), guard: self.guard.clone(), agent: agent.to_string() })
+    }
+
+    fn for_agent_with(&self, agent: &str, model: Option<&str>, temperature: Option<f32>, system_prompt: Option<&str>) -> Box<dyn LlmProvider> {
+        Box::new(Self {
+            inner: self.inner.for_agent_with(agent, model, temperature, system_prompt),
+            guard: self.guard.clone(),
+            agent: agent.to_string(),
+        })
+    }
+
+    fn transcript(&self) -> &Transcript {
+        self.inner.transcript()
+    }
+
+    async fn chat_completion(&self, prompt: &str) -> Result<String> {
+        self.admit(prompt)?;
+        self.inner.chat_completion(prompt).await
+    }
+
+    async fn chat_conversation(&self, history: &[ChatTurn]) -> Result<String> {
+        self.admit(&conversation::flatten(history))?;
+        self.inner.chat_conversation(history).await
+    }
+
+    /// Проверка доступности не учитывается в бюджете
+    async fn health_check(&self) -> Result<bool> {
+        self.inner.health_check().await
+    }
+
+    async fn diagnose(&self) -> Vec<ProviderCheck> {
+        self.inner.diagnose().await
+    }
+
+    async fn chat_completion_stream(&self, prompt: &str, on_chunk: ChunkCallback<'_>) -> Result<String> {
+        self.admit(prompt)?;
+        self.inner.chat_completion_stream(prompt, on_chunk).await
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        self.inner.supports_embeddings()
+    }
+
+    async fn text_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        self.inner.text_embedding(text).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::provider::RetryPolicy;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Провайдер, который отвечает фиксированным текстом и пишет стенограмму
+    struct Echo {
+        calls: Arc<AtomicU32>,
+        transcript: Transcript,
+    }
+
+    #[async_trait]
+    impl LlmProvider for Echo {
+        fn name(&self) -> &'static str {
+            "openai"
+        }
+
+        fn get_model_info(&self) -> &str {
+            "gpt-4o-mini"
+        }
+
+        fn for_agent(&self, _agent: &str) -> Box<dyn LlmProvider> {
+            Box::new(Self { calls: self.calls.clone(), transcript: self.transcript.clone() })
+        }
+
+        fn transcript(&self) -> &Transcript {
+            &self.transcript
+        }
+
+        async fn chat_completion(&self, prompt: &str) -> Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let response = "x".repeat(3000);
+            self.transcript.record(TranscriptEntry {
+                timestamp: chrono::Utc::now(),
+                agent: "changelog".to_string(),
+                model: "gpt-4o-mini".to_string(),
+                system: String::new(),
+                prompt: prompt.to_string(),
+                response: Some(response.clone()),
+                error: None,
+                duration_ms: 1,
+            });
+            Ok(response)
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cost_guard_reports_and_stops_at_budget() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let transcript = Transcript::new(vec![]);
+        let mut llm = LlmConfig { provider: "openai".to_string(), currency: "USD".to_string(), ..Default::default() };
+        // 1000 входных и 1000 выходных токенов за запрос: 0.001 + 0.004
+        llm.pricing.insert("openai".to_string(), LlmPricing { input_per_1m: 1.0, output_per_1m: 4.0 });
+        llm.max_cost = Some(0.0108);
+        let guard = CostGuard::new(&llm, transcript.clone());
+        let inner = Echo { calls: calls.clone(), transcript };
+        let provider = CostLimited::new(Box::new(inner), guard.clone()).for_agent("changelog");
+
+        let prompt = "p".repeat(3000);
+        provider.chat_completion(&prompt).await.unwrap();
+        provider.chat_completion(&prompt).await.unwrap();
+        guard.check().unwrap();
+
+        // Третий запрос вывел бы за бюджет: отказ без обращения к API и без повторов
+        let err = provider.chat_completion_with_retry(&prompt, &RetryPolicy::default()).await.unwrap_err();
+        assert!(err.is::<CostLimitExceeded>());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(guard.check().unwrap_err().to_string().starts_with("Бюджет LLM 0.0108 USD исчерпан: потрачено ~0.0100"));
+
+        let report = guard.report();
+        let model = &report.models["gpt-4o-mini"];
+        assert_eq!((model.requests, model.input_tokens, model.output_tokens), (2, 2000, 2000));
+        assert!((report.total() - 0.01).abs() < 1e-9);
+        assert_eq!(
+            report.render(),
+            [
+                "openai/gpt-4o-mini: 2 запросов, ~2000 входных и ~2000 выходных токенов, ~0.0100 USD",
+                "Итого: ~0.0100 USD"
+            ]
+        );
+    }
+}
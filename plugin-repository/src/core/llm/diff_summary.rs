@@ -0,0 +1,117 @@
+//! Описания коммитов по их diff (`[changelog.diff_summary]`, `ai changelog --diffs`):
+//! changelog-агент видит только темы коммитов, а «fix» или «wip» ничего не говорят о сути.
+//! Агент `diff` читает `--stat` и патч каждого коммита и описывает изменение одной-двумя
+//! фразами; описание попадает в строку коммита для changelog рядом с темой.
+
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+
+use super::cost::CostLimitExceeded;
+use super::prompts::DIFF_SUMMARY_PROMPT;
+use super::provider::{LlmProvider, RetryPolicy};
+use super::review::truncate;
+use crate::config::parser::DiffSummaryConfig;
+use crate::git::{GitCommit, GitHistory};
+
+/// Агент описаний коммитов по diff
+pub struct DiffSummaryAgent {
+    client: Box<dyn LlmProvider>,
+    retry: RetryPolicy,
+    max_commits: usize,
+    max_diff_chars: usize,
+}
+
+impl DiffSummaryAgent {
+    pub fn new(client: Box<dyn LlmProvider>, config: &DiffSummaryConfig) -> Self {
+        Self {
+            client,
+            retry: RetryPolicy::default(),
+            max_commits: config.max_commits,
+            max_diff_chars: config.max_diff_chars,
+        }
+    }
+
+    /// Политика повторов запросов (`[llm.retry]`)
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Описание изменений коммита одной строкой; diff длиннее `max_diff_chars` обрезается
+    pub async fn summarize(&self, commit: &GitCommit, diff: &str) -> Result<String> {
+        let prompt = DIFF_SUMMARY_PROMPT
+            .replace("{subject}", &commit.message)
+            .replace("{diff}", &truncate(diff, self.max_diff_chars));
+        let response = self.client.chat_completion_with_retry(&prompt, &self.retry).await
+            .with_context(|| format!("Ошибка описания diff коммита {}", commit.short_hash))?;
+        Ok(response.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    /// Добавляет описания diff к первым `max_commits` коммитам. Коммит, diff или описание
+    /// которого получить не удалось, остается с одной темой; исчерпанный бюджет LLM
+    /// останавливает описание остальных
+    pub async fn annotate(&self, history: &GitHistory, commits: &mut [GitCommit]) {
+        info!("🔬 Описание изменений по diff: {} из {} коммитов", self.max_commits.min(commits.len()), commits.len());
+        for commit in commits.iter_mut().take(self.max_commits) {
+            let diff = match history.get_commit_diff(&commit.hash).await {
+                Ok(diff) if !diff.trim().is_empty() => diff,
+                Ok(_) => continue,
+                Err(e) => {
+                    warn!("⚠️ Diff коммита {} не получен: {:#}", commit.short_hash, e);
+                    continue;
+                }
+            };
+            match self.summarize(commit, &diff).await {
+                Ok(summary) if !summary.is_empty() => commit.diff_summary = Some(summary),
+                Ok(_) => {}
+                Err(e) if e.is::<CostLimitExceeded>() => {
+                    warn!("⚠️ Описание коммитов по diff остановлено: {:#}", e);
+                    break;
+                }
+                Err(e) => warn!("⚠️ {:#}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::llm::mock::MockClient;
+    use crate::git::GitCommand;
+
+    #[tokio::test]
+    async fn test_annotate_adds_diff_summaries_to_newest_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git").args(args).current_dir(repo).status().unwrap();
+            assert!(status.success());
+        };
+        GitCommand::new(repo).args(["init", "-q"]).run().await.unwrap();
+        for (file, content) in [("search.rs", "fn search() {}\n"), ("chat.rs", "fn history() {}\n")] {
+            std::fs::write(repo.join(file), content).unwrap();
+            git(&["add", "-A"]);
+            git(&["-c", "user.name=Dev", "-c", "user.email=dev@example.com", "commit", "-qm", "wip"]);
+        }
+
+        let mut config = crate::config::parser::test_config();
+        config.mock.responses.insert("diff".to_string(), "Добавлена  история\nчата".to_string());
+        let client = MockClient::new(&config.mock).for_agent("diff");
+        let transcript = client.transcript().clone();
+        let limits = DiffSummaryConfig { enabled: true, max_commits: 1, max_diff_chars: 6000 };
+        let agent = DiffSummaryAgent::new(client, &limits);
+
+        let history = GitHistory::new(repo);
+        let mut commits = history.get_commits_between(None, None).await.unwrap();
+        agent.annotate(&history, &mut commits).await;
+
+        // Описан только новейший коммит, ответ модели сведен в одну строку
+        assert_eq!(commits[0].changelog_input(), "wip\n    Изменения в коде: Добавлена история чата");
+        assert_eq!(commits[1].diff_summary, None);
+        let prompts: Vec<String> = transcript.entries().into_iter().map(|e| e.prompt).collect();
+        assert_eq!(prompts.len(), 1);
+        assert!(prompts[0].contains("chat.rs | 1 +") && prompts[0].contains("+fn history() {}"));
+        assert!(!prompts[0].contains("search.rs"));
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::time::Instant;
+
+use super::provider::{self, LlmProvider};
+use crate::config::parser::Config;
+use crate::utils::clock::system_clock;
+
+/// Короткий запрос для проверки доступности
+pub const HEALTH_PROMPT: &str = "Ответь одним словом: ок";
+
+/// Результат одной проверки провайдера
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderCheck {
+    pub provider: String,
+    /// Что проверялось: endpoint, ключ и каталог
+    pub target: String,
+    /// Модель в том виде, в каком ее получает API (для YandexGPT — model URI)
+    pub model: String,
+    pub latency_ms: u64,
+    /// `None` — проверка пройдена
+    pub error: Option<String>,
+}
+
+impl ProviderCheck {
+    pub fn new(provider: &str, target: impl Into<String>, model: impl Into<String>, started: Instant, error: Option<String>) -> Self {
+        Self {
+            provider: provider.to_string(),
+            target: target.into(),
+            model: model.into(),
+            latency_ms: started.elapsed().as_millis() as u64,
+            error,
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Отчет `ai health`
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub checks: Vec<ProviderCheck>,
+}
+
+impl HealthReport {
+    pub fn healthy(&self) -> bool {
+        !self.checks.is_empty() && self.checks.iter().all(ProviderCheck::passed)
+    }
+}
+
+/// Провайдеры, для которых в конфиге есть настройки: основной из `[llm]` и остальные
+/// с заполненной секцией
+pub fn configured_providers(config: &Config) -> Vec<String> {
+    let mut providers = vec![config.llm.provider.clone()];
+    let configured = [
+        ("yandexgpt", !config.yandexgpt.api_key.is_empty()),
+        ("openai", config.openai.is_some()),
+        ("ollama", config.ollama.is_some()),
+    ];
+    for (name, present) in configured {
+        if present && !providers.iter().any(|p| p == name) {
+            providers.push(name.to_string());
+        }
+    }
+    providers
+}
+
+/// Проверяет все настроенные провайдеры; ошибка создания клиента тоже попадает в отчет
+pub async fn check_all(config: &Config) -> HealthReport {
+    let mut checks = Vec::new();
+    for name in configured_providers(config) {
+        let started = Instant::now();
+        match provider::provider_named(config, &name, system_clock()) {
+            Ok(client) => checks.extend(client.diagnose().await),
+            Err(e) => checks.push(ProviderCheck::new(&name, "конфигурация", "", started, Some(format!("{:#}", e)))),
+        }
+    }
+    HealthReport { checks }
+}
+
+/// Проверка по умолчанию: один короткий запрос
+pub async fn diagnose_with_prompt<P: LlmProvider + ?Sized>(client: &P, target: &str) -> Vec<ProviderCheck> {
+    let started = Instant::now();
+    let result: Result<String> = client.chat_completion(HEALTH_PROMPT).await;
+    let error = result.err().map(|e| format!("{:#}", e));
+    vec![ProviderCheck::new(client.name(), target, client.get_model_info(), started, error)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_health_checks_every_configured_provider() {
+        let mut config = crate::config::parser::test_config();
+        config.llm.provider = "openai".to_string();
+        config.ollama = Some(Default::default());
+        assert_eq!(configured_providers(&config), ["openai", "yandexgpt", "ollama"]);
+
+        // Секции [openai] нет: ошибка конфигурации попадает в отчет, а не прерывает проверку
+        config.ollama = None;
+        config.yandexgpt.api_key = String::new();
+        let report = check_all(&config).await;
+        assert_eq!(report.checks.len(), 1);
+        assert_eq!(report.checks[0].target, "конфигурация");
+        assert!(report.checks[0].error.as_deref().unwrap().contains("[openai]"));
+        assert!(!report.healthy());
+    }
+}
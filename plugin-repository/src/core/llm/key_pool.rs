@@ -0,0 +1,183 @@
+use reqwest::header::HeaderMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+/// Заголовки с остатком квоты; YandexGPT присылает их не всегда, корпоративные шлюзы — чаще
+const REMAINING_HEADERS: [&str; 2] = ["x-ratelimit-remaining-requests", "x-ratelimit-remaining"];
+
+/// Аккаунт YandexGPT: API-ключ и каталог, в квоту которого идут запросы
+#[derive(Debug, Clone, PartialEq)]
+pub struct YandexAccount {
+    pub api_key: String,
+    pub folder_id: String,
+}
+
+#[derive(Debug, Default)]
+struct KeyState {
+    /// Ключ не используется до этого момента (429 или нулевой остаток квоты)
+    exhausted_until: Option<Instant>,
+    /// Остаток квоты из последнего ответа, если API его сообщает
+    remaining: Option<u64>,
+}
+
+#[derive(Debug)]
+struct PoolState {
+    current: usize,
+    keys: Vec<KeyState>,
+}
+
+/// Состояние ключа для диагностики
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyStatus {
+    pub folder_id: String,
+    pub remaining: Option<u64>,
+    /// Сколько еще ключ отложен после исчерпания квоты
+    pub exhausted_for: Option<Duration>,
+}
+
+/// Общий для всех агентов пул аккаунтов: запросы идут с текущего ключа, а после 429
+/// или нулевого остатка квоты ключ откладывается на `cooldown` и пул переходит к следующему
+#[derive(Debug, Clone)]
+pub struct KeyPool {
+    accounts: Arc<Vec<YandexAccount>>,
+    cooldown: Duration,
+    state: Arc<Mutex<PoolState>>,
+}
+
+impl KeyPool {
+    /// `accounts` не пуст: первым идет основной аккаунт из `[yandexgpt]`
+    pub fn new(accounts: Vec<YandexAccount>, cooldown: Duration) -> Self {
+        assert!(!accounts.is_empty(), "пул ключей YandexGPT пуст");
+        let keys = accounts.iter().map(|_| KeyState::default()).collect();
+        Self { accounts: Arc::new(accounts), cooldown, state: Arc::new(Mutex::new(PoolState { current: 0, keys })) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    /// Основной аккаунт из `[yandexgpt]`
+    pub fn primary(&self) -> &YandexAccount {
+        &self.accounts[0]
+    }
+
+    /// Все аккаунты пула в порядке ротации
+    pub fn accounts(&self) -> &[YandexAccount] {
+        &self.accounts
+    }
+
+    /// Аккаунт для очередного запроса: текущий или следующий доступный. Если отложены все,
+    /// берется тот, что освободится раньше, — пусть решает API
+    pub fn acquire(&self) -> (usize, YandexAccount) {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let count = self.accounts.len();
+        let available = (0..count)
+            .map(|offset| (state.current + offset) % count)
+            .find(|&i| state.keys[i].exhausted_until.is_none_or(|until| until <= now));
+        let index = available.unwrap_or_else(|| {
+            (0..count).min_by_key(|&i| state.keys[i].exhausted_until).unwrap_or(0)
+        });
+        if index != state.current {
+            info!("🔑 Запросы к YandexGPT переключены на {}", self.label(index));
+            state.current = index;
+        }
+        (index, self.accounts[index].clone())
+    }
+
+    /// Учитывает остаток квоты из заголовков ответа; при нуле ключ откладывается заранее
+    pub fn observe(&self, index: usize, headers: &HeaderMap) {
+        let remaining = REMAINING_HEADERS
+            .iter()
+            .find_map(|name| headers.get(*name)?.to_str().ok()?.trim().parse::<u64>().ok());
+        let Some(remaining) = remaining else { return };
+        debug!("Остаток квоты {}: {}", self.label(index), remaining);
+        self.state.lock().unwrap().keys[index].remaining = Some(remaining);
+        if remaining == 0 {
+            self.exhaust(index, None);
+        }
+    }
+
+    /// Откладывает ключ после 429; `retry_after` из ответа важнее `cooldown` по умолчанию
+    pub fn exhaust(&self, index: usize, retry_after: Option<Duration>) {
+        let pause = retry_after.unwrap_or(self.cooldown);
+        let mut state = self.state.lock().unwrap();
+        state.keys[index].exhausted_until = Some(Instant::now() + pause);
+        if state.current == index {
+            state.current = (index + 1) % self.accounts.len();
+        }
+        warn!("⏳ Квота {} исчерпана, ключ отложен на {} с", self.label(index), pause.as_secs());
+    }
+
+    /// Остаток квоты и пауза по каждому ключу
+    pub fn status(&self) -> Vec<KeyStatus> {
+        let state = self.state.lock().unwrap();
+        let now = Instant::now();
+        self.accounts
+            .iter()
+            .zip(&state.keys)
+            .map(|(account, key)| KeyStatus {
+                folder_id: account.folder_id.clone(),
+                remaining: key.remaining,
+                exhausted_for: key.exhausted_until.filter(|until| *until > now).map(|until| until - now),
+            })
+            .collect()
+    }
+
+    /// Ключ в логах — по номеру и каталогу, сам ключ не выводится
+    fn label(&self, index: usize) -> String {
+        format!("ключ #{} (каталог {})", index + 1, self.accounts[index].folder_id)
+    }
+}
+
+/// Пауза из заголовка `Retry-After` (в секундах)
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let secs: u64 = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn account(folder_id: &str) -> YandexAccount {
+        YandexAccount { api_key: format!("key-{}", folder_id), folder_id: folder_id.to_string() }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_key_pool_rotates_on_exhausted_quota() {
+        let pool = KeyPool::new(vec![account("a"), account("b"), account("c")], Duration::from_secs(60));
+        let clone = pool.clone();
+        assert_eq!(pool.acquire(), (0, account("a")));
+
+        // 429 с Retry-After: ключ отложен, клоны агентов переходят на следующий
+        pool.exhaust(0, Some(Duration::from_secs(10)));
+        assert_eq!(clone.acquire(), (1, account("b")));
+
+        // Нулевой остаток квоты откладывает ключ до ошибки
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining-requests", HeaderValue::from_static("0"));
+        pool.observe(1, &headers);
+        assert_eq!(pool.acquire(), (2, account("c")));
+        headers.insert("x-ratelimit-remaining-requests", HeaderValue::from_static("42"));
+        pool.observe(2, &headers);
+
+        let status = pool.status();
+        assert_eq!(status[0].exhausted_for, Some(Duration::from_secs(10)));
+        assert_eq!(status[1].remaining, Some(0));
+        assert_eq!(status[2], KeyStatus { folder_id: "c".to_string(), remaining: Some(42), exhausted_for: None });
+
+        // Отложены все: берется ключ, который освободится раньше, а после паузы — снова первый
+        pool.exhaust(2, None);
+        assert_eq!(pool.acquire().0, 0);
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert_eq!(pool.acquire().0, 0);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, HeaderValue::from_static("30"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(30)));
+    }
+}
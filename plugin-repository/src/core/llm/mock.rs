@@ -0,0 +1,182 @@
+//! Провайдер без сети (`[llm] provider = "mock"`): отвечает заготовками по имени агента,
+//! чтобы `publish --dry-run` и тесты проходили весь конвейер агентов без API и ключей.
+//! Запросы попадают в стенограмму, как у настоящих провайдеров.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::conversation::{self, ChatTurn};
+use super::provider::LlmProvider;
+use super::transcript::{Transcript, TranscriptEntry};
+use crate::utils::clock::SharedClock;
+use super::yandexgpt::SYSTEM_PROMPT;
+use crate::config::parser::MockConfig;
+
+/// Модель в стенограмме и отчете о стоимости
+pub const MOCK_MODEL: &str = "mock";
+
+const CHANGELOG: &str = "## Изменения\n\n### 🔧 Улучшения\n- Changelog подготовлен mock-провайдером без обращения к LLM";
+
+const RELEASE_NOTES: &str = "# 🚀 Новая версия\n\n- Release notes подготовлены mock-провайдером без обращения к LLM\n\nТекст заменяется в [mock.responses] release.";
+
+const CHANGELOG_JSON: &str = r#"{"sections": [{"emoji": "🔧", "title": "Улучшения", "changes": ["Changelog подготовлен mock-провайдером без обращения к LLM"]}]}"#;
+
+const RELEASE_NOTES_JSON: &str = r#"{"title": "🚀 Новая версия", "highlights": ["Release notes подготовлены mock-провайдером без обращения к LLM"], "body": "Текст заменяется в [mock.responses] release."}"#;
+
+const REVIEW_JSON: &str = r#"{"risks": [{"file": "*", "severity": "low", "description": "Ревью подготовлено mock-провайдером без обращения к LLM"}], "missing_tests": [], "breaking_api": []}"#;
+
+/// Клиент с заготовленными ответами
+#[derive(Clone)]
+pub struct MockClient {
+    /// Ответы по имени агента из `[mock.responses]`; `{agent}` заменяется именем агента
+    responses: Arc<HashMap<String, String>>,
+    model: String,
+    /// Системный промпт агента: в ответ не влияет, но попадает в стенограмму
+    system_prompt: String,
+    agent: String,
+    transcript: Transcript,
+}
+
+impl MockClient {
+    pub fn new(config: &MockConfig) -> Self {
+        Self {
+            responses: Arc::new(config.responses.clone()),
+            model: MOCK_MODEL.to_string(),
+            system_prompt: SYSTEM_PROMPT.to_string(),
+            agent: "default".to_string(),
+            transcript: Transcript::new(vec![]),
+        }
+    }
+
+    /// Часы для отметок времени в стенограмме
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.transcript = self.transcript.with_clock(clock);
+        self
+    }
+
+    /// Ответ из конфига, иначе встроенный: в режиме `structured_output` — JSON по схеме из промпта
+    fn respond(&self, prompt: &str) -> String {
+        if let Some(template) = self.responses.get(&self.agent) {
+            return template.replace("{agent}", &self.agent);
+        }
+        let structured = prompt.contains("JSON Schema:");
+        let generic = || format!("Ответ mock-провайдера для агента {}", self.agent);
+        match self.agent.as_str() {
+            "changelog" if structured => CHANGELOG_JSON.to_string(),
+            "release" if structured => RELEASE_NOTES_JSON.to_string(),
+            "review" if structured => REVIEW_JSON.to_string(),
+            "changelog" => CHANGELOG.to_string(),
+            "release" => RELEASE_NOTES.to_string(),
+            "version" => next_patch(prompt)
+                .map(|version| format!("{}: patch-версия предложена mock-провайдером", version))
+                .unwrap_or_else(generic),
+            _ => generic(),
+        }
+    }
+}
+
+/// Следующая patch-версия после «Текущая версия: X» из промпта агента версий
+fn next_patch(prompt: &str) -> Option<String> {
+    let current = prompt.lines().find_map(|line| line.trim().strip_prefix("- Текущая версия:"))?;
+    let mut version = semver::Version::parse(current.trim().trim_start_matches('v')).ok()?;
+    version.patch += 1;
+    version.pre = semver::Prerelease::EMPTY;
+    Some(version.to_string())
+}
+
+#[async_trait]
+impl LlmProvider for MockClient {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn get_model_info(&self) -> &str {
+        &self.model
+    }
+
+    fn for_agent(&self, agent: &str) -> Box<dyn LlmProvider> {
+        Box::new(Self { agent: agent.to_string(), ..self.clone() })
+    }
+
+    fn for_agent_with(&self, agent: &str, model: Option<&str>, _temperature: Option<f32>, system_prompt: Option<&str>) -> Box<dyn LlmProvider> {
+        Box::new(Self {
+            agent: agent.to_string(),
+            model: model.map_or_else(|| self.model.clone(), str::to_string),
+            system_prompt: system_prompt.map_or_else(|| self.system_prompt.clone(), str::to_string),
+            ..self.clone()
+        })
+    }
+
+    fn transcript(&self) -> &Transcript {
+        &self.transcript
+    }
+
+    async fn chat_completion(&self, prompt: &str) -> Result<String> {
+        self.chat_conversation(&[ChatTurn::user(prompt)]).await
+    }
+
+    async fn chat_conversation(&self, history: &[ChatTurn]) -> Result<String> {
+        let prompt = conversation::flatten(history);
+        let response = self.respond(&prompt);
+        self.transcript.record(TranscriptEntry {
+            timestamp: self.transcript.now(),
+            agent: self.agent.clone(),
+            model: self.model.clone(),
+            system: self.system_prompt.clone(),
+            prompt,
+            response: Some(response.clone()),
+            error: None,
+            duration_ms: 0,
+        });
+        Ok(response)
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::validator::Capability;
+    use crate::core::llm::agents::{LLMAgentManager, PluginInfo, VersionInfo};
+    use crate::utils::clock::system_clock;
+
+    #[tokio::test]
+    async fn test_mock_provider_runs_agent_pipeline_offline() {
+        let mut config = crate::config::parser::test_config();
+        config.llm.provider = "mock".to_string();
+        config.validate_for(&[Capability::Ai]).unwrap();
+        let version_info = VersionInfo {
+            current_version: "1.2.0".to_string(),
+            new_version: Some("1.3.0".to_string()),
+            branch: "main".to_string(),
+            git_log: Some("abc1234: fix: сборка".to_string()),
+            changes_count: 1,
+        };
+        let plugin = PluginInfo { name: "Ride".to_string(), id: "ru.marslab.ide.ride".to_string(), version: "1.3.0".to_string(), description: None };
+
+        let manager = LLMAgentManager::from_config(&config, system_clock()).unwrap();
+        let changelog = manager.generate_changelog(&version_info).await.unwrap();
+        assert_eq!((changelog.sections[0].title.as_str(), changelog.total_changes), ("🔧 Улучшения", 1));
+        let notes = manager.generate_release_notes("1.3.0", &changelog.changelog, &plugin).await.unwrap();
+        assert_eq!(notes.title, "Новая версия");
+        assert_eq!(manager.suggest_version(&version_info).await.unwrap().suggested_version, "1.2.1");
+        let entries = manager.transcript().entries();
+        let agents: Vec<(&str, &str)> = entries.iter().map(|e| (e.agent.as_str(), e.model.as_str())).collect();
+        assert_eq!(agents, [("changelog", "mock"), ("release", "mock"), ("version", "mock")]);
+
+        // Режим JSON получает ответ по схеме, ответы из [mock.responses] заменяют встроенные
+        config.llm.structured_output = true;
+        config.mock.responses.insert("version".to_string(), "2.0.0: ответ агента {agent}".to_string());
+        let manager = LLMAgentManager::from_config(&config, system_clock()).unwrap();
+        let changelog = manager.generate_changelog(&version_info).await.unwrap();
+        assert!(changelog.changelog.contains("### 🔧 Улучшения\n\n- Changelog подготовлен mock-провайдером"));
+        let notes = manager.generate_release_notes("1.3.0", &changelog.changelog, &plugin).await.unwrap();
+        assert_eq!(notes.highlights.len(), 1);
+        let version = manager.suggest_version(&version_info).await.unwrap();
+        assert_eq!((version.suggested_version.as_str(), version.reasoning.as_str()), ("2.0.0", "ответ агента version"));
+    }
+}
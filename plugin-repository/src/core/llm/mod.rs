@@ -1,3 +1,23 @@
 pub mod yandexgpt;
+pub mod openai;
+pub mod ollama;
+pub mod mock;
+pub mod provider;
+pub mod conversation;
+pub mod rate_limit;
+pub mod circuit_breaker;
+pub mod health;
+pub mod key_pool;
+pub mod structured;
 pub mod agents;
-pub mod prompts;
\ No newline at end of file
+pub mod prompts;
+pub mod transcript;
+pub mod qa;
+pub mod review;
+pub mod diff_summary;
+pub mod clustering;
+pub mod modules;
+pub mod prompt_versions;
+pub mod cost;
+#[cfg(test)]
+pub(crate) mod scripted;
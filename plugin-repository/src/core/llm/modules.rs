@@ -0,0 +1,110 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::clustering::scope;
+use crate::config::parser::ChangelogConfig;
+use crate::git::GitCommit;
+
+/// Коммиты одного модуля внутри секции changelog
+#[derive(Debug, Clone)]
+pub struct ModuleGroup<'a> {
+    /// None — коммиты без scope
+    pub name: Option<String>,
+    pub commits: Vec<&'a GitCommit>,
+}
+
+/// Модуль коммита: scope из `type(scope): ...`, переименованный через `[changelog.modules]`
+pub fn module_of(message: &str, aliases: &HashMap<String, String>) -> Option<String> {
+    let first_line = message.lines().next().unwrap_or_default().to_lowercase();
+    let scope = scope(&first_line)?;
+    Some(aliases.get(&scope).cloned().unwrap_or(scope))
+}
+
+/// Группы по модулям: именованные по алфавиту, коммиты без scope — последней группой
+pub fn group_by_module<'a>(commits: &[&'a GitCommit], aliases: &HashMap<String, String>) -> Vec<ModuleGroup<'a>> {
+    let mut named: BTreeMap<String, Vec<&'a GitCommit>> = BTreeMap::new();
+    let mut unscoped = Vec::new();
+    for commit in commits {
+        match module_of(&commit.message, aliases) {
+            Some(module) => named.entry(module).or_default().push(*commit),
+            None => unscoped.push(*commit),
+        }
+    }
+
+    let mut groups: Vec<ModuleGroup> = named
+        .into_iter()
+        .map(|(name, commits)| ModuleGroup { name: Some(name), commits })
+        .collect();
+    if !unscoped.is_empty() {
+        groups.push(ModuleGroup { name: None, commits: unscoped });
+    }
+    groups
+}
+
+/// Группировка нужна, если в секции есть хотя бы один коммит со scope
+pub fn should_group(config: &ChangelogConfig, groups: &[ModuleGroup]) -> bool {
+    config.group_by_module && groups.iter().any(|g| g.name.is_some())
+}
+
+/// Git лог для LLM с разметкой модулей и просьбой вложить записи в подсекции
+pub fn format_by_module(commits: &[GitCommit], config: &ChangelogConfig) -> String {
+    let refs: Vec<&GitCommit> = commits.iter().collect();
+    let groups = group_by_module(&refs, &config.modules);
+    let mut out = String::from(
+        "Коммиты размечены модулями. Внутри каждой секции сгруппируй записи по модулям \
+         подзаголовками `#### <модуль>`; записи без модуля помести в конец секции.\n\n",
+    );
+    for group in &groups {
+        let title = group.name.as_deref().unwrap_or(&config.other_title);
+        out.push_str(&format!("Модуль {}:\n", title));
+        for commit in &group.commits {
+            out.push_str(&format!("  {}: {}\n", commit.short_hash, commit.changelog_input()));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn commit(message: &str) -> GitCommit {
+        GitCommit {
+            hash: String::new(),
+            short_hash: String::new(),
+            message: message.to_string(),
+            author: String::new(),
+            email: String::new(),
+            date: Utc::now(),
+            files_changed: 0,
+            insertions: 0,
+            deletions: 0,
+            files: Vec::new(),
+            pull_request: None,
+            diff_summary: None,
+        }
+    }
+
+    #[test]
+    fn test_group_by_module_with_aliases() {
+        let commits = [
+            commit("feat(Editor): inline completion"),
+            commit("feat: new settings page"),
+            commit("feat(chat): streaming answers"),
+            commit("feat(editor-ui): gutter icons"),
+        ];
+        let refs: Vec<&GitCommit> = commits.iter().collect();
+        let aliases = HashMap::from([("editor-ui".to_string(), "editor".to_string())]);
+
+        let groups = group_by_module(&refs, &aliases);
+        let names: Vec<Option<&str>> = groups.iter().map(|g| g.name.as_deref()).collect();
+        assert_eq!(names, [Some("chat"), Some("editor"), None]);
+        assert_eq!(groups[1].commits.len(), 2);
+
+        let mut config = ChangelogConfig::default();
+        assert!(!should_group(&config, &groups));
+        config.group_by_module = true;
+        assert!(should_group(&config, &groups));
+        assert!(!should_group(&config, &group_by_module(&refs[1..2], &aliases)));
+    }
+}
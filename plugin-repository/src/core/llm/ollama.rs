@@ -0,0 +1,309 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{info, warn, error, debug};
+use reqwest::Client;
+
+use crate::config::parser::ProxyConfig;
+use crate::core::llm::conversation::{self, ChatTurn};
+use crate::core::llm::provider::LlmProvider;
+use crate::core::llm::transcript::{Transcript, TranscriptEntry};
+use crate::utils::clock::SharedClock;
+use crate::core::llm::yandexgpt::SYSTEM_PROMPT;
+use crate::utils::network::NetworkUtils;
+
+/// HTTP клиент локального сервера Ollama (`/api/chat`, `/api/embed`)
+#[derive(Clone)]
+pub struct OllamaClient {
+    client: Client,
+    chat_url: String,
+    embed_url: String,
+    /// Модель эмбеддингов; `None` — эмбеддинги не поддерживаются
+    embedding_model: Option<String>,
+    model: String,
+    temperature: f32,
+    max_tokens: u32,
+    /// Системный промпт агента из `[llm_agents]`, по умолчанию общий
+    system_prompt: String,
+    /// Имя агента для стенограммы
+    agent: String,
+    transcript: Transcript,
+}
+
+/// Конфигурация Ollama
+#[derive(Debug, Clone)]
+pub struct OllamaClientConfig {
+    /// Адрес сервера, например `http://localhost:11434`
+    pub host: String,
+    pub model: String,
+    pub temperature: f32,
+    pub max_tokens: u32,
+    pub timeout: Duration,
+    /// Прокси (`[network.proxy]`); localhost обычно стоит добавить в `no_proxy`
+    pub proxy: ProxyConfig,
+    pub embedding_model: Option<String>,
+}
+
+/// Запрос к `/api/chat`
+#[derive(Debug, Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    stream: bool,
+    options: ChatOptions,
+}
+
+/// Параметры генерации
+#[derive(Debug, Serialize)]
+struct ChatOptions {
+    temperature: f32,
+    /// Лимит токенов ответа
+    num_predict: u32,
+}
+
+/// Сообщение в диалоге
+#[derive(Debug, Serialize, Deserialize)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+/// Ответ `/api/chat` без стриминга
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    message: ChatMessage,
+    #[serde(default)]
+    prompt_eval_count: Option<u32>,
+    #[serde(default)]
+    eval_count: Option<u32>,
+}
+
+/// Запрос к `/api/embed`
+#[derive(Debug, Serialize)]
+struct EmbedRequest {
+    model: String,
+    input: String,
+}
+
+/// Ответ `/api/embed`: по одному вектору на вход
+#[derive(Debug, Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// Тело ошибки: `{"error": "model 'x' not found"}`
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+impl OllamaClient {
+    /// Создает новый экземпляр клиента
+    pub fn new(config: OllamaClientConfig) -> Result<Self> {
+        let client = NetworkUtils::http_client(&config.proxy, config.timeout)
+            .context("Не удалось создать HTTP клиент для Ollama")?;
+
+        Ok(Self {
+            client,
+            transcript: Transcript::new(vec![]),
+            agent: "default".to_string(),
+            chat_url: format!("{}/api/chat", config.host.trim_end_matches('/')),
+            embed_url: format!("{}/api/embed", config.host.trim_end_matches('/')),
+            embedding_model: config.embedding_model,
+            model: config.model,
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+            system_prompt: SYSTEM_PROMPT.to_string(),
+        })
+    }
+
+    /// Часы для отметок времени в стенограмме
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.transcript = self.transcript.with_clock(clock);
+        self
+    }
+
+    async fn request_completion(&self, history: &[ChatTurn]) -> Result<String> {
+        info!("🤖 Запрос к Ollama (модель {})", self.model);
+
+        let request_body = ChatRequest {
+            model: self.model.clone(),
+            messages: std::iter::once(ChatMessage { role: "system".to_string(), content: self.system_prompt.clone() })
+                .chain(history.iter().map(|turn| ChatMessage { role: turn.role.as_str().to_string(), content: turn.text.clone() }))
+                .collect(),
+            stream: false,
+            options: ChatOptions { temperature: self.temperature, num_predict: self.max_tokens },
+        };
+
+        let response = self.client
+            .post(&self.chat_url)
+            .json(&request_body)
+            .send()
+            .await
+            .with_context(|| format!("Ollama недоступна по адресу {} (запущен ли `ollama serve`?)", self.chat_url))?;
+
+        let status = response.status();
+        debug!("Ответ статуса от Ollama: {}", status);
+        let response_text = response.text().await
+            .context("Не удалось прочитать ответ от Ollama")?;
+
+        if !status.is_success() {
+            let error = Self::api_error(status, response_text);
+            error!("{}", error);
+            return Err(error);
+        }
+
+        Self::parse_response(&response_text)
+    }
+
+    /// Текст ошибки сервера из тела ответа
+    fn api_error(status: reqwest::StatusCode, response_text: String) -> anyhow::Error {
+        let message = serde_json::from_str::<ErrorResponse>(&response_text)
+            .map(|e| e.error)
+            .unwrap_or(response_text);
+        anyhow::anyhow!("Ollama вернула ошибку {}: {}", status, message)
+    }
+
+    /// Извлекает текст ответа
+    fn parse_response(response_text: &str) -> Result<String> {
+        let api_response: ChatResponse = serde_json::from_str(response_text)
+            .context("Ошибка парсинга JSON ответа от Ollama")?;
+
+        let content = api_response.message.content;
+        if content.trim().is_empty() {
+            return Err(anyhow::anyhow!("Ollama вернула пустой ответ"));
+        }
+        info!(
+            "✅ Получен ответ от Ollama ({} + {} токенов)",
+            api_response.prompt_eval_count.unwrap_or(0),
+            api_response.eval_count.unwrap_or(0)
+        );
+        Ok(content)
+    }
+
+}
+
+#[async_trait]
+impl LlmProvider for OllamaClient {
+    fn name(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn get_model_info(&self) -> &str {
+        &self.model
+    }
+
+    fn for_agent(&self, agent: &str) -> Box<dyn LlmProvider> {
+        Box::new(Self { agent: agent.to_string(), ..self.clone() })
+    }
+
+    fn for_agent_with(&self, agent: &str, model: Option<&str>, temperature: Option<f32>, system_prompt: Option<&str>) -> Box<dyn LlmProvider> {
+        Box::new(Self {
+            agent: agent.to_string(),
+            model: model.map_or_else(|| self.model.clone(), str::to_string),
+            temperature: temperature.unwrap_or(self.temperature),
+            system_prompt: system_prompt.map_or_else(|| self.system_prompt.clone(), str::to_string),
+            ..self.clone()
+        })
+    }
+
+    fn transcript(&self) -> &Transcript {
+        &self.transcript
+    }
+
+    /// Выполняет chat completion запрос; пара запрос/ответ попадает в стенограмму
+    async fn chat_completion(&self, prompt: &str) -> Result<String> {
+        self.chat_conversation(&[ChatTurn::user(prompt)]).await
+    }
+
+    /// Диалог уходит списком сообщений; в стенограмму попадает вся история
+    #[tracing::instrument(name = "llm.request", skip_all, fields(agent = %self.agent, model = %self.model))]
+    async fn chat_conversation(&self, history: &[ChatTurn]) -> Result<String> {
+        let started = std::time::Instant::now();
+        let timestamp = self.transcript.now();
+        let result = self.request_completion(history).await;
+
+        self.transcript.record(TranscriptEntry {
+            timestamp,
+            agent: self.agent.clone(),
+            model: self.model.clone(),
+            system: self.system_prompt.clone(),
+            prompt: conversation::flatten(history),
+            response: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().map(|e| format!("{:#}", e)),
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+        result
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        self.embedding_model.is_some()
+    }
+
+    /// Возвращает эмбеддинг текста (модель `[ollama] embedding_model`)
+    #[tracing::instrument(name = "llm.embedding", skip_all, fields(agent = %self.agent))]
+    async fn text_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        let model = self.embedding_model.clone()
+            .context("Для эмбеддингов Ollama укажите [ollama] embedding_model")?;
+        let response = self.client
+            .post(&self.embed_url)
+            .json(&EmbedRequest { model, input: text.to_string() })
+            .send()
+            .await
+            .with_context(|| format!("Ollama недоступна по адресу {}", self.embed_url))?;
+
+        let status = response.status();
+        let response_text = response.text().await
+            .context("Не удалось прочитать ответ эмбеддинга от Ollama")?;
+        if !status.is_success() {
+            return Err(Self::api_error(status, response_text));
+        }
+        let parsed: EmbedResponse = serde_json::from_str(&response_text)
+            .context("Ошибка парсинга JSON ответа эмбеддинга от Ollama")?;
+        parsed.embeddings.into_iter().next().context("Ollama не вернула эмбеддинг")
+    }
+
+    /// Проверяет доступность сервера и модели
+    async fn health_check(&self) -> Result<bool> {
+        info!("🔍 Проверка доступности Ollama");
+
+        match self.chat_completion("Привет! Просто проверка доступности API.").await {
+            Ok(_) => {
+                info!("✅ Ollama доступна");
+                Ok(true)
+            }
+            Err(e) => {
+                warn!("❌ Ollama недоступна: {}", e);
+                Ok(false)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_request_and_response() {
+        let client = OllamaClient::new(OllamaClientConfig {
+            host: "http://localhost:11434/".to_string(),
+            model: "llama3.1".to_string(),
+            temperature: 0.3,
+            max_tokens: 2000,
+            timeout: Duration::from_secs(5),
+            proxy: ProxyConfig::default(),
+            embedding_model: Some("nomic-embed-text".to_string()),
+        })
+        .unwrap();
+        assert_eq!(client.chat_url, "http://localhost:11434/api/chat");
+        assert_eq!(client.embed_url, "http://localhost:11434/api/embed");
+        assert!(client.supports_embeddings());
+
+        let body = r#"{"model":"llama3.1","message":{"role":"assistant","content":"1.3.0"},"done":true,"eval_count":4}"#;
+        assert_eq!(OllamaClient::parse_response(body).unwrap(), "1.3.0");
+        let empty = r#"{"model":"llama3.1","message":{"role":"assistant","content":" "},"done":true}"#;
+        assert!(OllamaClient::parse_response(empty).is_err());
+    }
+}
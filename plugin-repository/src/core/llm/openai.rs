@@ -0,0 +1,334 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{info, warn, error, debug};
+use reqwest::Client;
+
+use crate::config::parser::ProxyConfig;
+use crate::core::llm::conversation::{self, ChatTurn};
+use crate::core::llm::provider::LlmProvider;
+use crate::core::llm::transcript::{Transcript, TranscriptEntry};
+use crate::utils::clock::SharedClock;
+use crate::core::llm::yandexgpt::SYSTEM_PROMPT;
+use crate::utils::network::NetworkUtils;
+
+/// Корень API; запросы идут в `{base_url}/chat/completions`
+pub const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// HTTP клиент для OpenAI Chat Completions API
+#[derive(Clone)]
+pub struct OpenAIClient {
+    client: Client,
+    api_key: String,
+    base_url: String,
+    model: String,
+    temperature: f32,
+    max_tokens: u32,
+    /// Системный промпт агента из `[llm_agents]`, по умолчанию общий
+    system_prompt: String,
+    /// Модель `{base_url}/embeddings`; `None` — эмбеддинги не поддерживаются
+    embedding_model: Option<String>,
+    /// Имя агента для стенограммы
+    agent: String,
+    transcript: Transcript,
+}
+
+/// Конфигурация OpenAI
+#[derive(Debug, Clone)]
+pub struct OpenAIConfig {
+    pub api_key: String,
+    pub model: String,
+    pub temperature: f32,
+    pub max_tokens: u32,
+    pub timeout: Duration,
+    /// Прокси для запросов к API (`[network.proxy]` и переменные окружения)
+    pub proxy: ProxyConfig,
+    /// Корень API, например OpenAI-совместимый корпоративный шлюз
+    pub base_url: String,
+    pub embedding_model: Option<String>,
+}
+
+/// Запрос к Chat Completions API
+#[derive(Debug, Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    temperature: f32,
+    max_tokens: u32,
+}
+
+/// Сообщение в диалоге
+#[derive(Debug, Serialize, Deserialize)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+/// Ответ Chat Completions API
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+/// Вариант ответа
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: ChatMessage,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+/// Статистика использования токенов
+#[derive(Debug, Deserialize)]
+struct Usage {
+    prompt_tokens: u32,
+    completion_tokens: u32,
+    total_tokens: u32,
+}
+
+/// Запрос к Embeddings API
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest {
+    model: String,
+    input: String,
+}
+
+/// Ответ Embeddings API: по одному вектору на вход
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+}
+
+/// Тело ошибки API: `{"error": {"message": ..., "type": ...}}`
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: ApiError,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+impl OpenAIClient {
+    /// Создает новый экземпляр клиента
+    pub fn new(config: OpenAIConfig) -> Result<Self> {
+        let client = NetworkUtils::http_client(&config.proxy, config.timeout)
+            .context("Не удалось создать HTTP клиент для OpenAI")?;
+
+        Ok(Self {
+            client,
+            transcript: Transcript::new(vec![config.api_key.clone()]),
+            agent: "default".to_string(),
+            api_key: config.api_key,
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            model: config.model,
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+            system_prompt: SYSTEM_PROMPT.to_string(),
+            embedding_model: config.embedding_model,
+        })
+    }
+
+    /// Часы для отметок времени в стенограмме
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.transcript = self.transcript.with_clock(clock);
+        self
+    }
+
+    async fn request_completion(&self, history: &[ChatTurn]) -> Result<String> {
+        info!("🤖 Запрос к OpenAI API (модель {})", self.model);
+        if self.api_key.contains("${") {
+            warn!("api_key содержит плейсхолдер переменной окружения. Проверьте OPENAI_API_KEY");
+        }
+
+        let request_body = ChatRequest {
+            model: self.model.clone(),
+            messages: std::iter::once(ChatMessage { role: "system".to_string(), content: self.system_prompt.clone() })
+                .chain(history.iter().map(|turn| ChatMessage { role: turn.role.as_str().to_string(), content: turn.text.clone() }))
+                .collect(),
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+        };
+
+        let response = self.client
+            .post(format!("{}/chat/completions", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&request_body)
+            .send()
+            .await
+            .context("Ошибка выполнения запроса к OpenAI API")?;
+
+        let status = response.status();
+        debug!("Ответ статуса от OpenAI: {}", status);
+        let response_text = response.text().await
+            .context("Не удалось прочитать ответ от OpenAI")?;
+
+        if !status.is_success() {
+            let error = Self::api_error(status, response_text);
+            error!("{}", error);
+            return Err(error);
+        }
+
+        Self::parse_response(&response_text)
+    }
+
+    /// Текст ошибки API из тела ответа
+    fn api_error(status: reqwest::StatusCode, response_text: String) -> anyhow::Error {
+        let message = serde_json::from_str::<ErrorResponse>(&response_text)
+            .map(|e| e.error.message)
+            .unwrap_or(response_text);
+        anyhow::anyhow!("OpenAI API вернул ошибку {}: {}", status, message)
+    }
+
+    /// Извлекает текст первого варианта ответа
+    fn parse_response(response_text: &str) -> Result<String> {
+        let api_response: ChatResponse = serde_json::from_str(response_text)
+            .context("Ошибка парсинга JSON ответа от OpenAI")?;
+
+        let choice = api_response.choices.into_iter().next()
+            .context("OpenAI не вернул вариантов в ответе")?;
+        if choice.finish_reason.as_deref() == Some("length") {
+            warn!("Ответ OpenAI обрезан по max_tokens");
+        }
+        if let Some(usage) = api_response.usage {
+            info!("✅ Получен ответ от OpenAI ({} токенов)", usage.total_tokens);
+            debug!("Использование токенов: {} + {}", usage.prompt_tokens, usage.completion_tokens);
+        }
+        Ok(choice.message.content)
+    }
+
+}
+
+#[async_trait]
+impl LlmProvider for OpenAIClient {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn get_model_info(&self) -> &str {
+        &self.model
+    }
+
+    fn for_agent(&self, agent: &str) -> Box<dyn LlmProvider> {
+        Box::new(Self { agent: agent.to_string(), ..self.clone() })
+    }
+
+    fn for_agent_with(&self, agent: &str, model: Option<&str>, temperature: Option<f32>, system_prompt: Option<&str>) -> Box<dyn LlmProvider> {
+        Box::new(Self {
+            agent: agent.to_string(),
+            model: model.map_or_else(|| self.model.clone(), str::to_string),
+            temperature: temperature.unwrap_or(self.temperature),
+            system_prompt: system_prompt.map_or_else(|| self.system_prompt.clone(), str::to_string),
+            ..self.clone()
+        })
+    }
+
+    fn transcript(&self) -> &Transcript {
+        &self.transcript
+    }
+
+    /// Выполняет chat completion запрос; пара запрос/ответ попадает в стенограмму
+    async fn chat_completion(&self, prompt: &str) -> Result<String> {
+        self.chat_conversation(&[ChatTurn::user(prompt)]).await
+    }
+
+    /// Диалог уходит списком сообщений; в стенограмму попадает вся история
+    #[tracing::instrument(name = "llm.request", skip_all, fields(agent = %self.agent, model = %self.model))]
+    async fn chat_conversation(&self, history: &[ChatTurn]) -> Result<String> {
+        let started = std::time::Instant::now();
+        let timestamp = self.transcript.now();
+        let result = self.request_completion(history).await;
+
+        self.transcript.record(TranscriptEntry {
+            timestamp,
+            agent: self.agent.clone(),
+            model: self.model.clone(),
+            system: self.system_prompt.clone(),
+            prompt: conversation::flatten(history),
+            response: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().map(|e| format!("{:#}", e)),
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+        result
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        self.embedding_model.is_some()
+    }
+
+    /// Возвращает эмбеддинг текста (модель `[openai] embedding_model`)
+    #[tracing::instrument(name = "llm.embedding", skip_all, fields(agent = %self.agent))]
+    async fn text_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        let model = self.embedding_model.clone()
+            .context("Для эмбеддингов OpenAI укажите [openai] embedding_model")?;
+        let response = self.client
+            .post(format!("{}/embeddings", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&EmbeddingRequest { model, input: text.to_string() })
+            .send()
+            .await
+            .context("Запрос эмбеддинга к OpenAI API не удался")?;
+
+        let status = response.status();
+        let response_text = response.text().await
+            .context("Не удалось прочитать ответ эмбеддинга от OpenAI")?;
+        if !status.is_success() {
+            return Err(Self::api_error(status, response_text));
+        }
+        let parsed: EmbeddingResponse = serde_json::from_str(&response_text)
+            .context("Ошибка парсинга JSON ответа эмбеддинга от OpenAI")?;
+        parsed.data.into_iter().next().map(|d| d.embedding)
+            .context("OpenAI не вернул эмбеддинг")
+    }
+
+    /// Проверяет доступность API
+    async fn health_check(&self) -> Result<bool> {
+        info!("🔍 Проверка доступности OpenAI API");
+
+        match self.chat_completion("Привет! Просто проверка доступности API.").await {
+            Ok(_) => {
+                info!("✅ OpenAI API доступен");
+                Ok(true)
+            }
+            Err(e) => {
+                warn!("❌ OpenAI API недоступен: {}", e);
+                Ok(false)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chat_response() {
+        let body = r#"{
+            "id": "chatcmpl-1",
+            "choices": [{"index": 0, "message": {"role": "assistant", "content": "- Исправлена сборка"}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 12, "completion_tokens": 5, "total_tokens": 17}
+        }"#;
+        assert_eq!(OpenAIClient::parse_response(body).unwrap(), "- Исправлена сборка");
+        assert!(OpenAIClient::parse_response(r#"{"choices": []}"#).is_err());
+
+        let request = ChatRequest {
+            model: "gpt-4o-mini".to_string(),
+            messages: vec![ChatMessage { role: "user".to_string(), content: "hi".to_string() }],
+            temperature: 0.3,
+            max_tokens: 100,
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["messages"][0]["content"], "hi");
+        assert_eq!(json["max_tokens"], 100);
+    }
+}
@@ -0,0 +1,162 @@
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use super::prompts::*;
+use super::transcript::{ReleaseTranscript, TRANSCRIPT_FILE};
+use super::yandexgpt::SYSTEM_PROMPT;
+
+/// Шаблоны промптов, влияющие на сгенерированный контент релиза
+fn templates() -> [(&'static str, &'static str); 8] {
+    [
+        ("system", SYSTEM_PROMPT),
+        ("changelog", CHANGELOG_PROMPT),
+        ("version", VERSION_PROMPT),
+        ("release_notes", RELEASE_NOTES_PROMPT),
+        ("translate_release_notes", TRANSLATE_RELEASE_NOTES_PROMPT),
+        ("release_readiness", RELEASE_READINESS_PROMPT),
+        ("impact_analysis", IMPACT_ANALYSIS_PROMPT),
+        ("ask", ASK_PROMPT),
+    ]
+}
+
+/// Версия шаблона: первые 12 символов sha256
+pub fn prompt_hash(template: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(template.as_bytes()));
+    digest[..12].to_string()
+}
+
+/// Версии всех шаблонов текущей сборки инструмента
+pub fn current() -> BTreeMap<String, String> {
+    templates()
+        .into_iter()
+        .map(|(name, template)| (name.to_string(), prompt_hash(template)))
+        .collect()
+}
+
+/// Шаблон, изменившийся между генерациями
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptChange {
+    pub name: String,
+    pub previous: Option<String>,
+    pub current: Option<String>,
+}
+
+impl fmt::Display for PromptChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.previous, &self.current) {
+            (Some(previous), Some(current)) => write!(f, "{}: {} → {}", self.name, previous, current),
+            (None, Some(current)) => write!(f, "{}: новый ({})", self.name, current),
+            (Some(previous), None) => write!(f, "{}: удален ({})", self.name, previous),
+            (None, None) => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// Различия версий промптов; шаблоны, которых не было в старой стенограмме, тоже считаются изменением
+pub fn diff(previous: &BTreeMap<String, String>, current: &BTreeMap<String, String>) -> Vec<PromptChange> {
+    let mut names: Vec<&String> = previous.keys().chain(current.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter(|name| previous.get(*name) != current.get(*name))
+        .map(|name| PromptChange {
+            name: name.clone(),
+            previous: previous.get(name).cloned(),
+            current: current.get(name).cloned(),
+        })
+        .collect()
+}
+
+/// Сохраненные стенограммы релизов `.deploy-plugin/releases/*/transcript.json` (с версиями промптов)
+fn saved_transcripts(project_root: &Path) -> Vec<ReleaseTranscript> {
+    let dir = project_root.join(".deploy-plugin").join("releases");
+    let Ok(entries) = std::fs::read_dir(&dir) else { return Vec::new() };
+    entries
+        .flatten()
+        .filter_map(|entry| std::fs::read_to_string(entry.path().join(TRANSCRIPT_FILE)).ok())
+        .filter_map(|content| serde_json::from_str::<ReleaseTranscript>(&content).ok())
+        .filter(|transcript| !transcript.prompts.is_empty())
+        .collect()
+}
+
+/// Изменения промптов относительно сохраненных генераций
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PromptDrift {
+    /// Тот же релиз уже генерировался другими промптами: контент будет сгенерирован заново
+    pub same_release: Vec<PromptChange>,
+    /// Версия и изменения относительно последнего другого релиза
+    pub previous_release: Option<(String, Vec<PromptChange>)>,
+}
+
+/// Сравнивает текущие промпты со стенограммами этой версии и последнего другого релиза
+pub fn drift(project_root: &Path, version: &str) -> PromptDrift {
+    let current = current();
+    let transcripts = saved_transcripts(project_root);
+
+    let same_release = transcripts
+        .iter()
+        .find(|t| t.version == version)
+        .map(|t| diff(&t.prompts, &current))
+        .unwrap_or_default();
+    let previous_release = transcripts
+        .iter()
+        .filter(|t| t.version != version)
+        .max_by_key(|t| t.created_at)
+        .map(|t| (t.version.clone(), diff(&t.prompts, &current)))
+        .filter(|(_, changes)| !changes.is_empty());
+
+    PromptDrift { same_release, previous_release }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn save(root: &Path, version: &str, prompts: BTreeMap<String, String>, age_days: i64) {
+        let dir = root.join(".deploy-plugin/releases").join(version);
+        std::fs::create_dir_all(&dir).unwrap();
+        let transcript = ReleaseTranscript {
+            version: version.to_string(),
+            created_at: Utc::now() - Duration::days(age_days),
+            prompts,
+            entries: vec![],
+        };
+        std::fs::write(dir.join(TRANSCRIPT_FILE), serde_json::to_string(&transcript).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_drift_against_saved_transcripts() {
+        let current = current();
+        assert_eq!(current["release_notes"], prompt_hash(RELEASE_NOTES_PROMPT));
+        assert_eq!(current["release_notes"].len(), 12);
+
+        let mut old = current.clone();
+        old.insert("release_notes".to_string(), "000000000000".to_string());
+        old.remove("ask");
+
+        let dir = tempfile::tempdir().unwrap();
+        save(dir.path(), "1.0.0", current.clone(), 10);
+        save(dir.path(), "1.1.0", old.clone(), 5);
+        save(dir.path(), "1.2.0", old, 1);
+
+        let drift = drift(dir.path(), "1.2.0");
+        let same: Vec<String> = drift.same_release.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            same,
+            [
+                format!("ask: новый ({})", current["ask"]),
+                format!("release_notes: 000000000000 → {}", current["release_notes"]),
+            ]
+        );
+        let (previous, changes) = drift.previous_release.unwrap();
+        assert_eq!((previous.as_str(), changes.len()), ("1.1.0", 2));
+
+        // Последний релиз сгенерирован текущими промптами — предупреждать не о чем
+        save(dir.path(), "1.3.0", current, 0);
+        assert_eq!(super::drift(dir.path(), "1.4.0"), PromptDrift::default());
+    }
+}
@@ -1,4 +1,7 @@
-//! Промпты для LLM агентов на русском языке
+//! Промпты для LLM агентов; changelog и release notes — на языке `[llm] output_language`
+
+use crate::config::parser::OutputLanguage;
+use super::yandexgpt::{SYSTEM_PROMPT, SYSTEM_PROMPT_EN};
 
 /// Промпт для генерации changelog
 pub const CHANGELOG_PROMPT: &str = r#"
@@ -124,6 +127,20 @@ pub const RELEASE_NOTES_PROMPT: &str = r#"
 Создай release notes на основе предоставленного changelog.
 "#;
 
+/// Промпт для перевода release notes на язык локали change-notes
+pub const TRANSLATE_RELEASE_NOTES_PROMPT: &str = r#"
+Ты - технический переводчик. Переведи release notes плагина {plugin_name} версии {version} на {language} язык (локаль {locale}).
+
+Требования:
+1. Сохрани Markdown-разметку: заголовки, списки, выделение, ссылки
+2. Не переводи имена классов, команд, настроек, фрагменты кода и версии
+3. Не добавляй ничего от себя и не сокращай текст
+4. Верни только перевод без пояснений
+
+Release notes:
+{release_notes}
+"#;
+
 /// Промпт для анализа commit message
 pub const COMMIT_ANALYSIS_PROMPT: &str = r#"
 Ты - эксперт по анализу git коммитов. Проанализируй commit message и определи тип изменения, его важность и влияние.
@@ -299,4 +316,248 @@ pub const IMPACT_ANALYSIS_PROMPT: &str = r#"
 }
 
 Проанализируй влияние изменений и предоставь детальную оценку.
-"#;
\ No newline at end of file
+"#;
+/// Промпт для ответа на вопрос по истории репозитория (`ai ask`)
+pub const ASK_PROMPT: &str = r#"
+Ты - разработчик проекта {project_name}. Ответь на вопрос о репозитории, используя только приведенные ниже коммиты.
+
+Вопрос: {question}
+
+Релевантные коммиты (hash | дата | автор | сообщение, затем измененные файлы):
+{context}
+
+Требования:
+1. Отвечай только на основе приведенных коммитов; если данных недостаточно, прямо скажи об этом
+2. Каждое утверждение подкрепляй ссылкой на коммит в квадратных скобках с коротким hash, например [a1b2c3d]
+3. Не придумывай коммиты и hash, которых нет в списке
+4. Отвечай кратко и по делу, списком если изменений несколько
+
+Ответ:
+"#;
+
+/// Промпт код-ревью одной части diff (`ai review`); ответ всегда в JSON по схеме
+pub const REVIEW_PROMPT: &str = r#"
+Ты - опытный ревьюер кода проекта {project_name}. Проверь изменения между {from} и {to}: часть {index} из {total}, файлы: {files}.
+
+Найди:
+- risks — ошибки и риски: некорректная логика, гонки, утечки ресурсов, безопасность, потерянные ошибки; severity: high, medium или low
+- missing_tests — измененное поведение, которое не покрыто тестами в этом diff
+- breaking_api — несовместимые изменения публичного API, CLI, формата конфигурации и данных
+
+Требования:
+1. Пиши только о том, что видно в diff; file — путь файла из diff
+2. Не пересказывай изменения и не хвали код: только замечания
+3. Если замечаний нет, верни пустые списки
+
+Diff:
+{diff}
+"#;
+
+pub const DIFF_SUMMARY_PROMPT: &str = r#"
+Ты - опытный разработчик. По diff коммита опиши, что он на самом деле меняет.
+
+Тема коммита: {subject}
+
+Требования:
+1. Одно-два предложения о поведении и возможностях, а не о строках кода
+2. Если тема неточна или ничего не говорит («fix», «wip», «правки»), опиши изменение заново
+3. Назови затронутую часть проекта, если она понятна из путей файлов
+4. Без Markdown, списков, кода и вступлений
+
+Статистика и diff:
+{diff}
+"#;
+
+/// Дополнение к промпту агента в режиме структурированного ответа (`[llm] structured_output`)
+pub const JSON_OUTPUT_INSTRUCTIONS: &str = r#"
+Формат ответа: верни только JSON-объект, соответствующий JSON Schema ниже, без Markdown-обрамления и пояснений.
+Текстовые поля пиши на русском языке, эмодзи допускаются.
+
+JSON Schema:
+{schema}
+"#;
+
+/// Уточнение, когда ответ агента версий не в формате «X.Y.Z: обоснование»
+pub const VERSION_FORMAT_PROMPT: &str = r#"
+Ответ не в требуемом формате. Верни одну строку "X.Y.Z: [обоснование]", где X.Y.Z — версия по Semantic Versioning (например, 1.2.0), без текста перед версией.
+"#;
+
+/// Повторный запрос в том же диалоге, когда ответ не разобрался как JSON по схеме
+pub const FIX_JSON_PROMPT: &str = r#"
+Твой предыдущий ответ не соответствует требуемому формату.
+
+Ошибка разбора: {error}
+
+Исправь ответ: верни только JSON-объект по JSON Schema ниже, без Markdown-обрамления и пояснений. Содержание сохрани.
+
+JSON Schema:
+{schema}
+"#;
+
+/// Уточняющий запрос в диалоге, когда changelog длиннее `[changelog] max_length`
+pub const SHORTEN_CHANGELOG_PROMPT: &str = r#"
+Changelog получился слишком длинным: {length} символов при пределе {max_length}.
+
+Сократи его до {max_length} символов: объедини похожие пункты, убери второстепенные детали и повторы.
+Сохрани формат Markdown, заголовки секций и все значимые изменения. Верни только changelog.
+"#;
+
+/// Промпт для генерации changelog на английском (`[llm] output_language = "en"`)
+pub const CHANGELOG_PROMPT_EN: &str = r#"
+You are an expert at writing changelogs for software projects. Analyze the git history below and write a professional changelog in English.
+
+Context:
+- Current version: {old_version}
+- New version: {new_version}
+- Development branch: {branch}
+- Change history: {git_log}
+
+Requirements:
+1. Organize the changelog into sections:
+   - 🚀 New features (feat, feature)
+   - 🐛 Bug fixes (fix, bugfix)
+   - 🔧 Improvements (improve, refactor)
+   - 💥 Breaking changes (breaking, breaking changes)
+   - 📝 Documentation (docs, documentation)
+   - 🧪 Testing (test, tests)
+
+2. For each change:
+   - Use clear, plain language
+   - Add technical details when they matter
+   - Mention the impact on users where applicable
+   - Use emoji for visual structure
+
+3. Format:
+   - Section headings with emoji
+   - Bulleted lists of changes
+   - A short description of each change
+   - Statistics at the end
+
+Example format:
+## Changes {new_version}
+
+### 🚀 New features
+- Added OAuth2 authentication
+- Implemented PDF export
+
+### 🐛 Bug fixes
+- Fixed a sign-in issue
+- Fixed a crash when working with large files
+
+---
+**Statistics:** 5 commits, 2 new features, 3 fixes
+
+Write the changelog based on the history above. Commit messages may be in another language: write the changelog in English regardless.
+"#;
+
+/// Промпт для генерации release notes на английском
+pub const RELEASE_NOTES_PROMPT_EN: &str = r#"
+You are a release notes writer. Write engaging and informative release notes for a plugin in English.
+
+Context:
+- Plugin name: {plugin_name}
+- Plugin ID: {plugin_id}
+- Version: {version}
+- Changelog: {changelog}
+
+Requirements:
+1. Structure:
+   - A catchy title with emoji
+   - A short subtitle
+   - Highlights as a list
+   - A detailed description of the changes
+   - A call to action (update, try, etc.)
+
+2. Style:
+   - Friendly and professional tone
+   - Clear, plain language
+   - Focus on the benefit for users
+   - Emoji for visual structure
+
+3. Format:
+   - Title (# 🎉 or 🚀)
+   - Subtitle with the version
+   - Highlights (bulleted list)
+   - Detailed description
+   - Closing call to action
+
+Example:
+# 🎉 {plugin_name} {version} is out!
+
+We are happy to present a new update with improved functionality and fixes.
+
+## Highlights:
+- ✨ New authorization system
+- 🐛 Critical bugs fixed
+- 🚀 50% faster
+
+## What's new:
+[A detailed description of the changes, focused on the benefit]
+
+Update to the latest version and enjoy the improved plugin!
+
+Write the release notes based on the changelog above. The changelog may be in another language: write the release notes in English regardless.
+"#;
+
+/// Уточняющий запрос на сокращение changelog на английском
+pub const SHORTEN_CHANGELOG_PROMPT_EN: &str = r#"
+The changelog is too long: {length} characters with a limit of {max_length}.
+
+Shorten it to {max_length} characters: merge similar items, drop minor details and repetition.
+Keep the Markdown format, the section headings and all significant changes. Return only the changelog.
+"#;
+
+impl OutputLanguage {
+    /// Системный промпт по умолчанию для всех агентов
+    pub fn system_prompt(self) -> &'static str {
+        match self {
+            OutputLanguage::Ru => SYSTEM_PROMPT,
+            OutputLanguage::En => SYSTEM_PROMPT_EN,
+        }
+    }
+
+    pub fn changelog_prompt(self) -> &'static str {
+        match self {
+            OutputLanguage::Ru => CHANGELOG_PROMPT,
+            OutputLanguage::En => CHANGELOG_PROMPT_EN,
+        }
+    }
+
+    pub fn release_notes_prompt(self) -> &'static str {
+        match self {
+            OutputLanguage::Ru => RELEASE_NOTES_PROMPT,
+            OutputLanguage::En => RELEASE_NOTES_PROMPT_EN,
+        }
+    }
+
+    pub fn shorten_changelog_prompt(self) -> &'static str {
+        match self {
+            OutputLanguage::Ru => SHORTEN_CHANGELOG_PROMPT,
+            OutputLanguage::En => SHORTEN_CHANGELOG_PROMPT_EN,
+        }
+    }
+
+    /// Заголовок changelog версии: `## Изменения 1.2.0`
+    pub fn changes_heading(self, version: &str) -> String {
+        match self {
+            OutputLanguage::Ru => format!("## Изменения {}", version),
+            OutputLanguage::En => format!("## Changes {}", version),
+        }
+    }
+
+    /// Подзаголовок release notes
+    pub fn release_subtitle(self, version: &str) -> String {
+        match self {
+            OutputLanguage::Ru => format!("Версия {} теперь доступна!", version),
+            OutputLanguage::En => format!("Version {} is now available!", version),
+        }
+    }
+
+    /// Заголовок release notes, если модель его не вернула
+    pub fn default_release_title(self) -> &'static str {
+        match self {
+            OutputLanguage::Ru => "Вышла новая версия плагина",
+            OutputLanguage::En => "A new version of the plugin is out",
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::conversation::{self, ChatTurn};
+use super::circuit_breaker::{CircuitBreaker, CircuitBreaking, CircuitOpen};
+use super::cost::CostLimitExceeded;
+use super::health::{self, ProviderCheck};
+use super::key_pool::YandexAccount;
+use super::mock::MockClient;
+use super::ollama::{OllamaClient, OllamaClientConfig};
+use super::openai::{OpenAIClient, OpenAIConfig};
+use super::rate_limit::{RateLimited, RateLimiter};
+use super::transcript::Transcript;
+use super::yandexgpt::{YandexGPTClient, YandexGPTConfig};
+use crate::config::parser::{Config, LlmRetryConfig};
+use crate::core::chaos::{self, ChaosPoint};
+use crate::utils::clock::{RandomSource, SharedClock, ThreadRandom};
+
+/// Получатель фрагментов ответа в потоковом режиме
+pub type ChunkCallback<'a> = &'a (dyn Fn(&str) + Send + Sync);
+
+/// Бэкенд LLM, которым пользуются агенты; новый провайдер подключается реализацией трейта
+/// и веткой в `from_config`, код агентов при этом не меняется
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Имя провайдера для логов (`[llm] provider`)
+    fn name(&self) -> &'static str;
+
+    /// Получает информацию о модели
+    fn get_model_info(&self) -> &str;
+
+    /// Клон клиента с общей стенограммой, запросы которого подписываются именем агента
+    fn for_agent(&self, agent: &str) -> Box<dyn LlmProvider>;
+
+    /// То же, что `for_agent`, но с моделью, температурой и системным промптом агента из
+    /// `[llm_agents]`; `None` оставляет значение провайдера
+    fn for_agent_with(
+        &self,
+        agent: &str,
+        _model: Option<&str>,
+        _temperature: Option<f32>,
+        _system_prompt: Option<&str>,
+    ) -> Box<dyn LlmProvider> {
+        self.for_agent(agent)
+    }
+
+    /// Стенограмма запросов всех клонов клиента
+    fn transcript(&self) -> &Transcript;
+
+    /// Выполняет chat completion запрос; пара запрос/ответ попадает в стенограмму
+    async fn chat_completion(&self, prompt: &str) -> Result<String>;
+
+    /// Продолжает диалог: `history` — реплики по порядку, последняя от пользователя.
+    /// Провайдеры без истории сообщений получают реплики одним промптом
+    async fn chat_conversation(&self, history: &[ChatTurn]) -> Result<String> {
+        self.chat_completion(&conversation::flatten(history)).await
+    }
+
+    /// Проверяет доступность API
+    async fn health_check(&self) -> Result<bool>;
+
+    /// Проверки для `ai health` с задержкой и текстом ошибки; по умолчанию — один короткий запрос
+    async fn diagnose(&self) -> Vec<ProviderCheck> {
+        health::diagnose_with_prompt(self, "chat completion").await
+    }
+
+    /// Выполняет запрос, передавая текст ответа в `on_chunk` по мере генерации.
+    /// Провайдеры без потокового режима отдают весь ответ одним фрагментом
+    async fn chat_completion_stream(&self, prompt: &str, on_chunk: ChunkCallback<'_>) -> Result<String> {
+        let response = self.chat_completion(prompt).await?;
+        on_chunk(&response);
+        Ok(response)
+    }
+
+    /// Поддерживает ли провайдер `text_embedding`
+    fn supports_embeddings(&self) -> bool {
+        false
+    }
+
+    /// Возвращает эмбеддинг текста
+    async fn text_embedding(&self, _text: &str) -> Result<Vec<f32>> {
+        Err(anyhow::anyhow!("Провайдер {} не поддерживает эмбеддинги", self.name()))
+    }
+
+    /// Выполняет запрос с повторами по `retry`; при разомкнутом circuit breaker или исчерпанном
+    /// бюджете попытки прекращаются
+    async fn chat_completion_with_retry(&self, prompt: &str, retry: &RetryPolicy) -> Result<String> {
+        chaos::inject(ChaosPoint::Llm)?;
+        let mut last_error = None;
+
+        for attempt in 0..=retry.max_retries {
+            match self.chat_completion(prompt).await {
+                Ok(response) => return Ok(response),
+                Err(e) if e.is::<CircuitOpen>() || e.is::<CostLimitExceeded>() => return Err(e),
+                Err(e) => {
+                    warn!("Попытка {} не удалась: {}", attempt + 1, e);
+                    last_error = Some(e);
+
+                    if attempt < retry.max_retries {
+                        let delay = retry.delay(attempt);
+                        info!("Повторная попытка через {:?}", delay);
+                        tokio::time::sleep(delay).await;
+                    }
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("Все попытки провалились")))
+    }
+}
+
+/// Повторы запросов агентов (`[llm.retry]`); по умолчанию 3 повтора через 1, 2 и 4 с
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Доля задержки, до которой добавляется случайная прибавка
+    pub jitter: f64,
+    /// Источник случайной прибавки; тесты подставляют фиксированный
+    pub random: Arc<dyn RandomSource>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::from(&LlmRetryConfig::default())
+    }
+}
+
+impl From<&LlmRetryConfig> for RetryPolicy {
+    fn from(config: &LlmRetryConfig) -> Self {
+        Self {
+            max_retries: config.max_retries,
+            base_delay: Duration::from_millis(config.base_delay_ms),
+            max_delay: Duration::from_millis(config.max_delay_ms),
+            jitter: config.jitter,
+            random: Arc::new(ThreadRandom),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Задержка перед повтором после попытки `attempt` (с нуля): base * 2^attempt, не больше
+    /// `max_delay`, плюс случайная добавка до `jitter` от нее
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let delay = self.base_delay.saturating_mul(2u32.saturating_pow(attempt)).min(self.max_delay);
+        let jitter_ms = (delay.as_millis() as f64 * self.jitter) as u64;
+        if jitter_ms == 0 {
+            return delay;
+        }
+        delay + Duration::from_millis(self.random.below(jitter_ms + 1))
+    }
+}
+
+/// Создает клиент провайдера из `[llm]` и секции провайдера; при заданных лимитах
+/// запросы всех агентов проходят через общий `RateLimiter`, а недоступный провайдер
+/// отключается общим `CircuitBreaker`. `clock` ставит отметки времени в стенограмме
+pub fn from_config(config: &Config, clock: SharedClock) -> Result<Box<dyn LlmProvider>> {
+    let mut client = provider_named(config, &config.llm.provider, clock)?;
+    let llm = &config.llm;
+    if llm.requests_per_minute > 0 || llm.max_concurrent_requests > 0 {
+        info!(
+            "Лимит запросов к LLM: {} в минуту, {} одновременно (0 — без ограничения)",
+            llm.requests_per_minute, llm.max_concurrent_requests
+        );
+        let limiter = RateLimiter::new(llm.requests_per_minute, llm.max_concurrent_requests);
+        client = Box::new(RateLimited::new(client, limiter));
+    }
+    // Снаружи лимитера: отказ при разомкнутой цепи не ждет свободного слота
+    if llm.circuit_breaker_threshold > 0 {
+        let cooldown = Duration::from_secs(llm.circuit_breaker_cooldown_secs);
+        client = Box::new(CircuitBreaking::new(client, CircuitBreaker::new(llm.circuit_breaker_threshold, cooldown)));
+    }
+    Ok(client)
+}
+
+/// Клиент провайдера `name` без лимитов и circuit breaker (для `ai health` — любой настроенный)
+pub(crate) fn provider_named(config: &Config, name: &str, clock: SharedClock) -> Result<Box<dyn LlmProvider>> {
+    match name {
+        "yandexgpt" => Ok(Box::new(YandexGPTClient::new(YandexGPTConfig {
+            api_key: config.yandexgpt.api_key.clone(),
+            folder_id: config.yandexgpt.folder_id.clone(),
+            extra_accounts: config.yandexgpt.accounts.iter()
+                .map(|a| YandexAccount { api_key: a.api_key.clone(), folder_id: a.folder_id.clone() })
+                .collect(),
+            key_cooldown: Duration::from_secs(config.yandexgpt.key_cooldown_secs),
+            model: config.yandexgpt.model.clone(),
+            temperature: 0.3,
+            max_tokens: 2000,
+            timeout: Duration::from_secs(config.llm.request_timeout_secs),
+            proxy: config.network.proxy.clone(),
+            base_url: config.yandexgpt.base_url.clone(),
+            operation_url: config.yandexgpt.operation_url.clone(),
+            async_threshold_chars: config.yandexgpt.async_threshold_chars,
+            async_poll_interval: Duration::from_secs(config.yandexgpt.async_poll_interval_secs),
+            async_timeout: Duration::from_secs(config.yandexgpt.async_timeout_secs),
+        }).with_clock(clock))),
+        "openai" => {
+            let openai = config.openai.as_ref()
+                .context("[llm] provider = \"openai\" требует секцию [openai]")?;
+            Ok(Box::new(OpenAIClient::new(OpenAIConfig {
+                api_key: openai.api_key.clone(),
+                model: openai.model.clone(),
+                temperature: config.llm.temperature,
+                max_tokens: config.llm.max_tokens,
+                timeout: Duration::from_secs(config.llm.request_timeout_secs),
+                proxy: config.network.proxy.clone(),
+                base_url: openai.base_url.clone(),
+                embedding_model: openai.embedding_model.clone(),
+            })?.with_clock(clock)))
+        }
+        "ollama" => {
+            let ollama = config.ollama.clone().unwrap_or_default();
+            // Локальный сервер не должен ходить через корпоративный прокси
+            let mut proxy = config.network.proxy.clone();
+            proxy.no_proxy.extend(["localhost".to_string(), "127.0.0.1".to_string()]);
+            Ok(Box::new(OllamaClient::new(OllamaClientConfig {
+                host: ollama.host,
+                model: ollama.model,
+                temperature: config.llm.temperature,
+                max_tokens: config.llm.max_tokens,
+                timeout: Duration::from_secs(ollama.timeout_secs),
+                proxy,
+                embedding_model: ollama.embedding_model,
+            })?.with_clock(clock)))
+        }
+        "mock" => Ok(Box::new(MockClient::new(&config.mock).with_clock(clock))),
+        other => Err(anyhow::anyhow!("LLM провайдер '{}' пока не поддерживается", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parser::OpenAiConfig;
+    use crate::core::llm::scripted::ScriptedProvider;
+    use crate::utils::clock::system_clock;
+
+    #[test]
+    fn test_provider_selection() {
+        let mut config = crate::config::parser::test_config();
+        let provider = from_config(&config, system_clock()).unwrap();
+        assert_eq!(provider.name(), "yandexgpt");
+        assert!(provider.supports_embeddings());
+
+        config.llm.provider = "openai".to_string();
+        assert!(from_config(&config, system_clock()).is_err());
+        config.openai = Some(OpenAiConfig {
+            api_key: "sk-test".to_string(),
+            model: "gpt-4o-mini".to_string(),
+            base_url: "https://llm-proxy.corp.example/v1".to_string(),
+            embedding_model: None,
+        });
+        let provider = from_config(&config, system_clock()).unwrap();
+        assert_eq!((provider.name(), provider.get_model_info()), ("openai", "gpt-4o-mini"));
+
+        config.llm.provider = "ollama".to_string();
+        let provider = from_config(&config, system_clock()).unwrap().for_agent("version");
+        assert_eq!((provider.name(), provider.get_model_info()), ("ollama", "llama3.1"));
+        assert!(!provider.supports_embeddings());
+
+        config.llm.max_concurrent_requests = 2;
+        let provider = from_config(&config, system_clock()).unwrap().for_agent("qa");
+        assert_eq!((provider.name(), provider.get_model_info()), ("ollama", "llama3.1"));
+
+        config.llm.provider = "anthropic".to_string();
+        assert!(from_config(&config, system_clock()).is_err());
+    }
+
+    /// Провайдер, который отвечает только с заданной попытки
+    fn flaky(succeed_on: u32) -> ScriptedProvider {
+        ScriptedProvider::new(move |call, _| {
+            if call >= succeed_on {
+                Ok(format!("ответ {}", call))
+            } else {
+                Err(anyhow::anyhow!("сбой {}", call))
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_default_retry() {
+        let provider = flaky(2).for_agent("version");
+        assert_eq!(provider.chat_completion_with_retry("q", &RetryPolicy::default()).await.unwrap(), "ответ 2");
+        assert!(provider.text_embedding("q").await.is_err());
+
+        let provider = flaky(10);
+        let no_retries = RetryPolicy { max_retries: 0, ..RetryPolicy::default() };
+        let err = provider.chat_completion_with_retry("q", &no_retries).await.unwrap_err();
+        assert_eq!(err.to_string(), "сбой 1");
+    }
+
+    #[test]
+    fn test_retry_policy_delay() {
+        use crate::utils::clock::FixedRandom;
+
+        let with_random = |policy: RetryPolicy, value: u64| RetryPolicy { random: Arc::new(FixedRandom(value)), ..policy };
+
+        let default = with_random(RetryPolicy::default(), 999);
+        let delays: Vec<u64> = (0..3).map(|a| default.delay(a).as_millis() as u64).collect();
+        assert_eq!(delays, [1000, 2000, 4000]);
+
+        let config = LlmRetryConfig { max_retries: 8, base_delay_ms: 500, max_delay_ms: 3000, jitter: 0.2 };
+        let policy = RetryPolicy::from(&config);
+        assert_eq!(with_random(policy.clone(), 0).delay(1), Duration::from_millis(1000));
+        assert_eq!(with_random(policy.clone(), 150).delay(1), Duration::from_millis(1150));
+        // Потолок ограничивает экспоненту, джиттер считается от ограниченной задержки
+        assert_eq!(with_random(policy.clone(), 600).delay(7), Duration::from_millis(3600));
+        assert_eq!(with_random(policy, 0).delay(40), Duration::from_millis(3000));
+    }
+}
@@ -0,0 +1,213 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+use super::prompts::ASK_PROMPT;
+use super::provider::{LlmProvider, RetryPolicy};
+use crate::git::GitCommit;
+
+/// Слова, не несущие смысла для поиска по истории
+const STOP_WORDS: &[&str] = &[
+    "what", "which", "when", "where", "why", "how", "the", "and", "for", "with", "from", "since",
+    "into", "was", "were", "has", "have", "changed", "change", "changes", "this", "that",
+    "что", "как", "где", "когда", "почему", "зачем", "какие", "какой", "было", "были",
+    "изменилось", "изменения", "после", "для", "это", "или", "при",
+];
+
+/// Коммит, переданный LLM как контекст
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextCommit {
+    pub short_hash: String,
+    pub date: String,
+    pub author: String,
+    pub message: String,
+    pub files: Vec<String>,
+    #[serde(skip)]
+    pub score: u32,
+}
+
+/// Ответ на вопрос с цитатами коммитов
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoAnswer {
+    pub question: String,
+    pub answer: String,
+    /// Процитированные коммиты из контекста
+    pub citations: Vec<ContextCommit>,
+    /// Hash-и в ответе, которых не было в контексте (вероятные галлюцинации)
+    pub unknown_citations: Vec<String>,
+}
+
+/// Ключевые слова вопроса для поиска коммитов
+pub fn keywords(question: &str) -> Vec<String> {
+    let mut words: Vec<String> = question
+        .split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+        .map(|w| w.trim_matches('-').to_lowercase())
+        .filter(|w| w.chars().count() >= 3 && !STOP_WORDS.contains(&w.as_str()))
+        .filter(|w| !w.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// Ссылка на версию в тексте вопроса ("since v0.3", "после 1.2.0")
+pub fn version_mention(question: &str) -> Option<String> {
+    let re = Regex::new(r"\bv?\d+\.\d+(?:\.\d+)?\b").unwrap();
+    re.find(question).map(|m| m.as_str().to_string())
+}
+
+/// Выбирает до `top` наиболее релевантных коммитов: совпадения в сообщении весят больше,
+/// чем в путях файлов; при равенстве — более свежие. Если совпадений нет, берутся последние коммиты.
+pub fn select_context(
+    question: &str,
+    commits: &[GitCommit],
+    files: &HashMap<String, Vec<String>>,
+    top: usize,
+) -> Vec<ContextCommit> {
+    let words = keywords(question);
+    let mut scored: Vec<ContextCommit> = commits
+        .iter()
+        .map(|commit| {
+            let commit_files = files.get(&commit.hash).cloned().unwrap_or_default();
+            let message = commit.message.to_lowercase();
+            let score = words
+                .iter()
+                .map(|word| {
+                    let in_message = if message.contains(word.as_str()) { 3 } else { 0 };
+                    let in_files = commit_files.iter().filter(|f| f.to_lowercase().contains(word.as_str())).count().min(3) as u32;
+                    in_message + in_files
+                })
+                .sum();
+            ContextCommit {
+                short_hash: commit.short_hash.clone(),
+                date: commit.date.format("%Y-%m-%d").to_string(),
+                author: commit.author.clone(),
+                message: commit.message.clone(),
+                files: commit_files,
+                score,
+            }
+        })
+        .collect();
+
+    // Сортировка стабильна: при равном весе сохраняется порядок git log (новые первыми)
+    if scored.iter().any(|c| c.score > 0) {
+        scored.retain(|c| c.score > 0);
+        scored.sort_by_key(|c| std::cmp::Reverse(c.score));
+    }
+    scored.truncate(top);
+    scored
+}
+
+/// Извлекает ссылки вида [a1b2c3d] из ответа
+pub fn cited_hashes(answer: &str) -> Vec<String> {
+    let re = Regex::new(r"\[([0-9a-f]{7,40})\]").unwrap();
+    let mut hashes: Vec<String> = Vec::new();
+    for caps in re.captures_iter(answer) {
+        let hash = caps[1].to_string();
+        if !hashes.contains(&hash) {
+            hashes.push(hash);
+        }
+    }
+    hashes
+}
+
+/// Агент вопросов и ответов по истории репозитория
+pub struct QaAgent {
+    client: Box<dyn LlmProvider>,
+    retry: RetryPolicy,
+}
+
+impl QaAgent {
+    pub fn new(client: Box<dyn LlmProvider>) -> Self {
+        Self { client, retry: RetryPolicy::default() }
+    }
+
+    /// Политика повторов запросов (`[llm.retry]`)
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Отвечает на вопрос по выбранным коммитам и сверяет цитаты с контекстом
+    pub async fn ask(&self, project_name: &str, question: &str, context: Vec<ContextCommit>) -> Result<RepoAnswer> {
+        info!("🤖 Ответ на вопрос по {} коммитам", context.len());
+
+        let context_text = context
+            .iter()
+            .map(|c| {
+                let files = c.files.iter().take(15).cloned().collect::<Vec<_>>().join(", ");
+                format!("{} | {} | {} | {}\n  файлы: {}", c.short_hash, c.date, c.author, c.message, files)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let prompt = ASK_PROMPT
+            .replace("{project_name}", project_name)
+            .replace("{question}", question)
+            .replace("{context}", &context_text);
+        debug!("Контекст вопроса: {} символов", prompt.chars().count());
+
+        let answer = self.client.chat_completion_with_retry(&prompt, &self.retry).await
+            .context("Ошибка получения ответа на вопрос")?;
+
+        let mut citations = Vec::new();
+        let mut unknown_citations = Vec::new();
+        for hash in cited_hashes(&answer) {
+            match context.iter().find(|c| c.short_hash.starts_with(&hash) || hash.starts_with(&c.short_hash)) {
+                Some(commit) => citations.push(commit.clone()),
+                None => unknown_citations.push(hash),
+            }
+        }
+
+        Ok(RepoAnswer { question: question.to_string(), answer, citations, unknown_citations })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn commit(hash: &str, message: &str) -> GitCommit {
+        GitCommit {
+            hash: format!("{}000000000", hash),
+            short_hash: hash.to_string(),
+            message: message.to_string(),
+            author: "dev".to_string(),
+            email: "dev@example.com".to_string(),
+            date: Utc::now(),
+            files_changed: 1,
+            insertions: 1,
+            deletions: 0,
+            files: Vec::new(),
+            pull_request: None,
+            diff_summary: None,
+        }
+    }
+
+    #[test]
+    fn test_select_context_ranks_by_message_and_files() {
+        let commits = vec![
+            commit("aaaaaaa", "docs: update readme"),
+            commit("bbbbbbb", "fix: rollback uploaded files"),
+            commit("ccccccc", "feat: deployer conflict detection"),
+        ];
+        let mut files = HashMap::new();
+        files.insert("bbbbbbb000000000".to_string(), vec!["src/core/deployer.rs".to_string()]);
+        files.insert("aaaaaaa000000000".to_string(), vec!["README.md".to_string()]);
+
+        let context = select_context("What changed in the deployer since v0.3?", &commits, &files, 10);
+        let hashes: Vec<_> = context.iter().map(|c| c.short_hash.as_str()).collect();
+        assert_eq!(hashes, ["ccccccc", "bbbbbbb"]);
+        assert_eq!(version_mention("What changed in the deployer since v0.3?").as_deref(), Some("v0.3"));
+        assert_eq!(keywords("What changed in the deployer since v0.3?"), ["deployer"]);
+    }
+
+    #[test]
+    fn test_cited_hashes() {
+        let answer = "Добавлена проверка конфликтов [ccccccc] и откат [bbbbbbb], см. также [ccccccc] и [1234567].";
+        assert_eq!(cited_hashes(answer), ["ccccccc", "bbbbbbb", "1234567"]);
+    }
+}
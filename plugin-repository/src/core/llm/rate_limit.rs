@@ -0,0 +1,167 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+use tracing::debug;
+
+use super::provider::{ChunkCallback, LlmProvider};
+use super::conversation::ChatTurn;
+use super::health::ProviderCheck;
+use super::transcript::Transcript;
+
+/// Окно ограничения частоты запросов
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Общий лимит запросов к API: не больше `requests_per_minute` за скользящую минуту
+/// и не больше `max_concurrent` одновременно. Клоны разделяют одно состояние
+#[derive(Clone)]
+pub struct RateLimiter {
+    requests_per_minute: usize,
+    started: Arc<Mutex<VecDeque<Instant>>>,
+    concurrency: Option<Arc<Semaphore>>,
+}
+
+impl RateLimiter {
+    /// 0 в любом из параметров снимает соответствующее ограничение
+    pub fn new(requests_per_minute: u32, max_concurrent: u32) -> Self {
+        Self {
+            requests_per_minute: requests_per_minute as usize,
+            started: Arc::default(),
+            concurrency: (max_concurrent > 0).then(|| Arc::new(Semaphore::new(max_concurrent as usize))),
+        }
+    }
+
+    /// Ждет свободного слота; слот параллельности освобождается вместе с возвращенным разрешением
+    pub async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        let permit = match &self.concurrency {
+            // Семафор не закрывается, ошибка невозможна
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+        if self.requests_per_minute > 0 {
+            loop {
+                let wait = {
+                    let mut started = self.started.lock().unwrap();
+                    let now = Instant::now();
+                    while started.front().is_some_and(|t| now.duration_since(*t) >= WINDOW) {
+                        started.pop_front();
+                    }
+                    if started.len() < self.requests_per_minute {
+                        started.push_back(now);
+                        break;
+                    }
+                    WINDOW - now.duration_since(started[0])
+                };
+                debug!("Лимит {} запросов/мин к LLM исчерпан, ожидание {:?}", self.requests_per_minute, wait);
+                tokio::time::sleep(wait).await;
+            }
+        }
+        permit
+    }
+}
+
+/// Провайдер, каждый запрос которого проходит через общий `RateLimiter`
+pub struct RateLimited {
+    inner: Box<dyn LlmProvider>,
+    limiter: RateLimiter,
+}
+
+impl RateLimited {
+    pub fn new(inner: Box<dyn LlmProvider>, limiter: RateLimiter) -> Self {
+        Self { inner, limiter }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for RateLimited {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn get_model_info(&self) -> &str {
+        self.inner.get_model_info()
+    }
+
+    fn for_agent(&self, agent: &str) -> Box<dyn LlmProvider> {
+        Box::new(Self::new(self.inner.for_agent(agent), self.limiter.clone()))
+    }
+
+    fn for_agent_with(&self, agent: &str, model: Option<&str>, temperature: Option<f32>, system_prompt: Option<&str>) -> Box<dyn LlmProvider> {
+        Box::new(Self::new(self.inner.for_agent_with(agent, model, temperature, system_prompt), self.limiter.clone()))
+    }
+
+    fn transcript(&self) -> &Transcript {
+        self.inner.transcript()
+    }
+
+    async fn chat_completion(&self, prompt: &str) -> Result<String> {
+        let _permit = self.limiter.acquire().await;
+        self.inner.chat_completion(prompt).await
+    }
+
+    async fn chat_conversation(&self, history: &[ChatTurn]) -> Result<String> {
+        let _permit = self.limiter.acquire().await;
+        self.inner.chat_conversation(history).await
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        let _permit = self.limiter.acquire().await;
+        self.inner.health_check().await
+    }
+
+    async fn diagnose(&self) -> Vec<ProviderCheck> {
+        let _permit = self.limiter.acquire().await;
+        self.inner.diagnose().await
+    }
+
+    async fn chat_completion_stream(&self, prompt: &str, on_chunk: ChunkCallback<'_>) -> Result<String> {
+        let _permit = self.limiter.acquire().await;
+        self.inner.chat_completion_stream(prompt, on_chunk).await
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        self.inner.supports_embeddings()
+    }
+
+    async fn text_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        let _permit = self.limiter.acquire().await;
+        self.inner.text_embedding(text).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limiter_window_and_concurrency() {
+        let limiter = RateLimiter::new(2, 1);
+        let start = Instant::now();
+
+        let first = limiter.acquire().await;
+        assert!(first.is_some());
+        // Второй запрос ждет, пока первый не отпустит слот параллельности
+        let clone = limiter.clone();
+        let second = tokio::spawn(async move {
+            clone.acquire().await;
+            Instant::now()
+        });
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(!second.is_finished());
+        drop(first);
+        assert_eq!(second.await.unwrap() - start, Duration::from_secs(1));
+
+        // Третий запрос в минуту уходит только после выхода первого из окна
+        limiter.acquire().await;
+        assert_eq!(Instant::now() - start, WINDOW);
+
+        let unlimited = RateLimiter::new(0, 0);
+        for _ in 0..100 {
+            assert!(unlimited.acquire().await.is_none());
+        }
+        assert_eq!(Instant::now() - start, WINDOW);
+    }
+}
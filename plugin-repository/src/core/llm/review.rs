@@ -0,0 +1,335 @@
+//! Код-ревью диапазона коммитов (`ai review --from v1.2.0 --to HEAD`): агент читает сам diff,
+//! а не сообщения коммитов. Diff делится на части по файлам, каждая часть уходит модели
+//! отдельным запросом с ответом по JSON-схеме, замечания частей сводятся в один отчет.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::prompts::REVIEW_PROMPT;
+use super::provider::{LlmProvider, RetryPolicy};
+use super::structured::{complete_structured, StructuredResponse};
+
+/// Предел части diff в символах по умолчанию
+pub const DEFAULT_CHUNK_CHARS: usize = 12_000;
+
+/// Допустимые значения `severity`, от серьезных к незначительным
+const SEVERITIES: &[&str] = &["high", "medium", "low"];
+
+/// Часть diff для одного запроса
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffChunk {
+    pub files: Vec<String>,
+    pub text: String,
+}
+
+/// Делит diff на части не длиннее `max_chars` по границам файлов (`diff --git`).
+/// Diff одного файла больше предела делится по hunk-ам с повтором заголовка файла,
+/// а hunk больше предела обрезается
+pub fn split_diff(diff: &str, max_chars: usize) -> Vec<DiffChunk> {
+    let mut chunks: Vec<DiffChunk> = Vec::new();
+    let mut current = DiffChunk { files: Vec::new(), text: String::new() };
+    for (file, section) in file_sections(diff) {
+        for piece in split_section(&section, max_chars) {
+            if !current.text.is_empty() && current.text.chars().count() + piece.chars().count() > max_chars {
+                chunks.push(std::mem::replace(&mut current, DiffChunk { files: Vec::new(), text: String::new() }));
+            }
+            if !current.files.contains(&file) {
+                current.files.push(file.clone());
+            }
+            current.text.push_str(&piece);
+        }
+    }
+    if !current.text.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Diff по файлам: путь (`b/...`) и текст вместе с заголовком
+fn file_sections(diff: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, String)> = Vec::new();
+    for line in diff.split_inclusive('\n') {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            let file = header.trim_end().rsplit_once(" b/").map_or(header.trim_end(), |(_, b)| b);
+            sections.push((file.to_string(), String::new()));
+        }
+        if let Some((_, text)) = sections.last_mut() {
+            text.push_str(line);
+        }
+    }
+    sections
+}
+
+/// Части diff одного файла: заголовок повторяется перед каждой частью
+fn split_section(section: &str, max_chars: usize) -> Vec<String> {
+    if section.chars().count() <= max_chars {
+        return vec![section.to_string()];
+    }
+    let (header, hunks) = match section.find("\n@@") {
+        Some(pos) => section.split_at(pos + 1),
+        None => return vec![truncate(section, max_chars)],
+    };
+    let budget = max_chars.saturating_sub(header.chars().count()).max(1);
+    let mut pieces = Vec::new();
+    let mut piece = String::new();
+    for hunk in hunks.split_inclusive('\n').fold(Vec::<String>::new(), |mut hunks, line| {
+        match hunks.last_mut() {
+            Some(hunk) if !line.starts_with("@@") => hunk.push_str(line),
+            _ => hunks.push(line.to_string()),
+        }
+        hunks
+    }) {
+        let hunk = truncate(&hunk, budget);
+        if !piece.is_empty() && piece.chars().count() + hunk.chars().count() > budget {
+            pieces.push(format!("{}{}", header, std::mem::take(&mut piece)));
+        }
+        piece.push_str(&hunk);
+    }
+    if !piece.is_empty() {
+        pieces.push(format!("{}{}", header, piece));
+    }
+    pieces
+}
+
+pub(super) fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max_chars).collect();
+    cut.push_str("\n… (обрезано)\n");
+    cut
+}
+
+/// Замечание ревью
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewFinding {
+    pub file: String,
+    pub description: String,
+    /// Только у рисков: `high`, `medium` или `low`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+}
+
+/// Ответ модели на одну часть diff
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReviewJson {
+    pub risks: Vec<ReviewFinding>,
+    pub missing_tests: Vec<ReviewFinding>,
+    pub breaking_api: Vec<ReviewFinding>,
+}
+
+impl StructuredResponse for ReviewJson {
+    const SCHEMA: &'static str = r#"{
+  "type": "object",
+  "required": ["risks", "missing_tests", "breaking_api"],
+  "properties": {
+    "risks": {
+      "type": "array",
+      "items": {
+        "type": "object",
+        "required": ["file", "severity", "description"],
+        "properties": {
+          "file": {"type": "string"},
+          "severity": {"type": "string", "enum": ["high", "medium", "low"]},
+          "description": {"type": "string"}
+        }
+      }
+    },
+    "missing_tests": {
+      "type": "array",
+      "items": {"type": "object", "required": ["file", "description"], "properties": {"file": {"type": "string"}, "description": {"type": "string"}}}
+    },
+    "breaking_api": {
+      "type": "array",
+      "items": {"type": "object", "required": ["file", "description"], "properties": {"file": {"type": "string"}, "description": {"type": "string"}}}
+    }
+  }
+}"#;
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        for risk in &self.risks {
+            match risk.severity.as_deref() {
+                Some(severity) if SEVERITIES.contains(&severity) => {}
+                other => return Err(format!("severity риска '{}' должен быть high, medium или low, получено {:?}", risk.description, other)),
+            }
+        }
+        let all = self.risks.iter().chain(&self.missing_tests).chain(&self.breaking_api);
+        match all.into_iter().find(|f| f.description.trim().is_empty()) {
+            Some(finding) => Err(format!("замечание к файлу '{}' без описания", finding.file)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Сводный результат ревью диапазона
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeReview {
+    pub from: String,
+    pub to: String,
+    pub files: usize,
+    pub chunks: usize,
+    /// Риски, серьезные первыми
+    pub risks: Vec<ReviewFinding>,
+    pub missing_tests: Vec<ReviewFinding>,
+    pub breaking_api: Vec<ReviewFinding>,
+}
+
+impl CodeReview {
+    /// Добавляет замечания части; повторы (тот же файл и текст) пропускаются
+    fn merge(&mut self, part: ReviewJson) {
+        for (target, findings) in [
+            (&mut self.risks, part.risks),
+            (&mut self.missing_tests, part.missing_tests),
+            (&mut self.breaking_api, part.breaking_api),
+        ] {
+            for finding in findings {
+                if !target.iter().any(|f| f.file == finding.file && f.description == finding.description) {
+                    target.push(finding);
+                }
+            }
+        }
+        self.risks.sort_by_key(|r| SEVERITIES.iter().position(|s| Some(*s) == r.severity.as_deref()));
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.risks.is_empty() && self.missing_tests.is_empty() && self.breaking_api.is_empty()
+    }
+
+    /// Отчет в Markdown для файла и описания PR
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("## Ревью {}..{}\n\nФайлов: {}, частей diff: {}\n", self.from, self.to, self.files, self.chunks);
+        let sections = [
+            ("⚠️ Риски", &self.risks),
+            ("🧪 Не хватает тестов", &self.missing_tests),
+            ("💥 Несовместимые изменения API", &self.breaking_api),
+        ];
+        for (title, findings) in sections {
+            if findings.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("\n### {}\n\n", title));
+            for finding in findings {
+                match &finding.severity {
+                    Some(severity) => out.push_str(&format!("- **{}** `{}`: {}\n", severity, finding.file, finding.description)),
+                    None => out.push_str(&format!("- `{}`: {}\n", finding.file, finding.description)),
+                }
+            }
+        }
+        if self.is_clean() {
+            out.push_str("\nЗамечаний нет.\n");
+        }
+        out
+    }
+}
+
+/// Агент код-ревью: diff частями с ответом по JSON-схеме
+pub struct ReviewAgent {
+    client: Box<dyn LlmProvider>,
+    retry: RetryPolicy,
+    /// Попытки исправления JSON (`[llm] json_fix_attempts`)
+    fix_attempts: u32,
+    chunk_chars: usize,
+}
+
+impl ReviewAgent {
+    pub fn new(client: Box<dyn LlmProvider>) -> Self {
+        Self { client, retry: RetryPolicy::default(), fix_attempts: 2, chunk_chars: DEFAULT_CHUNK_CHARS }
+    }
+
+    /// Политика повторов запросов (`[llm.retry]`)
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Сколько раз ответ с ошибкой JSON возвращается модели
+    pub fn with_fix_attempts(mut self, fix_attempts: u32) -> Self {
+        self.fix_attempts = fix_attempts;
+        self
+    }
+
+    /// Предел одной части diff в символах
+    pub fn with_chunk_chars(mut self, chunk_chars: usize) -> Self {
+        self.chunk_chars = chunk_chars.max(1);
+        self
+    }
+
+    /// Ревью diff между `from` и `to`: части отправляются по очереди, замечания сводятся
+    pub async fn review(&self, project_name: &str, from: &str, to: &str, diff: &str) -> Result<CodeReview> {
+        let chunks = split_diff(diff, self.chunk_chars);
+        if chunks.is_empty() {
+            anyhow::bail!("Между {} и {} нет изменений", from, to);
+        }
+        let mut review = CodeReview {
+            from: from.to_string(),
+            to: to.to_string(),
+            files: chunks.iter().flat_map(|c| &c.files).collect::<std::collections::HashSet<_>>().len(),
+            chunks: chunks.len(),
+            risks: Vec::new(),
+            missing_tests: Vec::new(),
+            breaking_api: Vec::new(),
+        };
+        for (index, chunk) in chunks.iter().enumerate() {
+            info!("🔍 Ревью части {} из {}: {}", index + 1, chunks.len(), chunk.files.join(", "));
+            let prompt = REVIEW_PROMPT
+                .replace("{project_name}", project_name)
+                .replace("{from}", from)
+                .replace("{to}", to)
+                .replace("{index}", &(index + 1).to_string())
+                .replace("{total}", &chunks.len().to_string())
+                .replace("{files}", &chunk.files.join(", "))
+                .replace("{diff}", &chunk.text);
+            let part: ReviewJson = complete_structured(self.client.as_ref(), &prompt, &self.retry, self.fix_attempts).await
+                .with_context(|| format!("Ошибка ревью части {} из {}", index + 1, chunks.len()))?;
+            review.merge(part);
+        }
+        Ok(review)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::validator::Capability;
+    use crate::core::llm::agents::LLMAgentManager;
+    use crate::utils::clock::system_clock;
+
+    fn file_diff(path: &str, hunks: &[&str]) -> String {
+        let mut diff = format!("diff --git a/{0} b/{0}\nindex 1111111..2222222 100644\n--- a/{0}\n+++ b/{0}\n", path);
+        for hunk in hunks {
+            diff.push_str(&format!("@@ -1,1 +1,1 @@\n-old\n+{}\n", hunk));
+        }
+        diff
+    }
+
+    #[tokio::test]
+    async fn test_review_splits_diff_and_merges_findings() {
+        let diff = [
+            file_diff("src/core/deployer.rs", &["a".repeat(200).as_str(), "b".repeat(10).as_str()]),
+            file_diff("README.md", &["docs"]),
+        ]
+        .concat();
+
+        // Файл больше предела делится по hunk-ам с заголовком, мелкие файлы объединяются
+        let chunks = split_diff(&diff, 380);
+        let files: Vec<Vec<&str>> = chunks.iter().map(|c| c.files.iter().map(String::as_str).collect()).collect();
+        assert_eq!(files, [vec!["src/core/deployer.rs"], vec!["src/core/deployer.rs", "README.md"]]);
+        assert!(chunks.iter().all(|c| c.text.starts_with("diff --git a/src/core/deployer.rs") && c.text.chars().count() <= 380));
+        assert_eq!(split_diff(&diff, 10_000).len(), 1);
+        assert!(split_diff("", 200).is_empty());
+
+        let part: ReviewJson = serde_json::from_str(r#"{"risks": [{"file": "a.rs", "severity": "critical", "description": "гонка"}], "missing_tests": [], "breaking_api": []}"#).unwrap();
+        assert!(part.validate().unwrap_err().contains("high, medium или low"));
+
+        // Без сети: mock-провайдер отвечает на каждую часть, повторы сводятся в одно замечание
+        let mut config = crate::config::parser::test_config();
+        config.llm.provider = "mock".to_string();
+        config.validate_for(&[Capability::Ai]).unwrap();
+        let manager = LLMAgentManager::from_config(&config, system_clock()).unwrap().with_review_chunk_chars(380);
+        let review = manager.review("Ride", "v1.2.0", "HEAD", &diff).await.unwrap();
+        assert_eq!((review.files, review.chunks, review.risks.len()), (2, 2, 1));
+        assert_eq!(manager.transcript().entries().len(), 2);
+        assert!(review.to_markdown().starts_with("## Ревью v1.2.0..HEAD\n\nФайлов: 2, частей diff: 2\n\n### ⚠️ Риски\n\n- **low**"));
+    }
+}
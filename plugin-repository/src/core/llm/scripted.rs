@@ -0,0 +1,86 @@
+//! Тестовый провайдер со сценарием ответов: общий для тестов агентов, retry и диалогов.
+//! Клоны и клиенты агентов (`for_agent`) разделяют счетчик вызовов и записанные истории.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::conversation::{self, ChatTurn};
+use super::provider::LlmProvider;
+use super::transcript::Transcript;
+
+type Reply = dyn Fn(u32, &[ChatTurn]) -> Result<String> + Send + Sync;
+
+#[derive(Clone)]
+pub(crate) struct ScriptedProvider {
+    reply: Arc<Reply>,
+    calls: Arc<AtomicU32>,
+    histories: Arc<Mutex<Vec<Vec<ChatTurn>>>>,
+    transcript: Transcript,
+}
+
+impl ScriptedProvider {
+    /// Ответ вычисляется по номеру вызова (с 1) и полученной истории
+    pub fn new(reply: impl Fn(u32, &[ChatTurn]) -> Result<String> + Send + Sync + 'static) -> Self {
+        Self {
+            reply: Arc::new(reply),
+            calls: Arc::default(),
+            histories: Arc::default(),
+            transcript: Transcript::new(vec![]),
+        }
+    }
+
+    /// Заготовленные ответы по порядку
+    pub fn replies(replies: Vec<&'static str>) -> Self {
+        let replies = Mutex::new(replies);
+        Self::new(move |call, _| {
+            let mut replies = replies.lock().unwrap();
+            anyhow::ensure!(!replies.is_empty(), "ответ на вызов {} не задан", call);
+            Ok(replies.remove(0).to_string())
+        })
+    }
+
+    /// Истории, полученные всеми клонами, в порядке вызовов
+    pub fn histories(&self) -> Vec<Vec<ChatTurn>> {
+        self.histories.lock().unwrap().clone()
+    }
+
+    /// Запросы в виде одного промпта (как у провайдеров без истории), в порядке вызовов
+    pub fn prompts(&self) -> Vec<String> {
+        self.histories.lock().unwrap().iter().map(|h| conversation::flatten(h)).collect()
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ScriptedProvider {
+    fn name(&self) -> &'static str {
+        "scripted"
+    }
+
+    fn get_model_info(&self) -> &str {
+        "model"
+    }
+
+    fn for_agent(&self, _agent: &str) -> Box<dyn LlmProvider> {
+        Box::new(self.clone())
+    }
+
+    fn transcript(&self) -> &Transcript {
+        &self.transcript
+    }
+
+    async fn chat_completion(&self, prompt: &str) -> Result<String> {
+        self.chat_conversation(&[ChatTurn::user(prompt)]).await
+    }
+
+    async fn chat_conversation(&self, history: &[ChatTurn]) -> Result<String> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        self.histories.lock().unwrap().push(history.to_vec());
+        (self.reply)(call, history)
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        Ok(true)
+    }
+}
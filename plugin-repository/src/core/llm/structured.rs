@@ -0,0 +1,219 @@
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::marker::PhantomData;
+
+use super::agents::{Assessment, ConversationalAgent};
+use super::prompts::{FIX_JSON_PROMPT, JSON_OUTPUT_INSTRUCTIONS};
+use crate::config::parser::OutputLanguage;
+use super::provider::{LlmProvider, RetryPolicy};
+
+/// Ответ агента в виде JSON: схема уходит в промпт, результат проверяется serde и `validate`
+pub trait StructuredResponse: DeserializeOwned {
+    /// JSON Schema ответа
+    const SCHEMA: &'static str;
+
+    /// Смысловые проверки сверх типов (пустые списки, пустые строки)
+    fn validate(&self) -> std::result::Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Модель так и не вернула JSON по схеме
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("LLM не вернул корректный JSON за {attempts} попыток: {error}")]
+pub struct InvalidStructuredOutput {
+    pub attempts: u32,
+    pub error: String,
+}
+
+/// Секция changelog в структурированном ответе
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChangelogSectionJson {
+    pub emoji: String,
+    pub title: String,
+    pub changes: Vec<String>,
+}
+
+/// Changelog в структурированном ответе
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChangelogJson {
+    pub sections: Vec<ChangelogSectionJson>,
+    #[serde(default)]
+    pub summary: Option<String>,
+}
+
+impl StructuredResponse for ChangelogJson {
+    const SCHEMA: &'static str = r#"{
+  "type": "object",
+  "required": ["sections"],
+  "properties": {
+    "sections": {
+      "type": "array",
+      "minItems": 1,
+      "items": {
+        "type": "object",
+        "required": ["emoji", "title", "changes"],
+        "properties": {
+          "emoji": {"type": "string"},
+          "title": {"type": "string"},
+          "changes": {"type": "array", "minItems": 1, "items": {"type": "string"}}
+        }
+      }
+    },
+    "summary": {"type": "string"}
+  }
+}"#;
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        if self.sections.is_empty() {
+            return Err("sections не может быть пустым".to_string());
+        }
+        match self.sections.iter().find(|s| s.title.trim().is_empty() || s.changes.is_empty()) {
+            Some(section) => Err(format!("секция '{}' без заголовка или без изменений", section.title)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ChangelogJson {
+    /// Markdown в том же формате, что и текстовый режим
+    pub fn to_markdown(&self, version: &str, language: OutputLanguage) -> String {
+        let mut out = format!("{}\n\n", language.changes_heading(version));
+        for section in &self.sections {
+            out.push_str(&format!("### {} {}\n\n", section.emoji, section.title));
+            for change in &section.changes {
+                out.push_str(&format!("- {}\n", change));
+            }
+            out.push('\n');
+        }
+        if let Some(summary) = self.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+            out.push_str(&format!("---\n{}\n", summary.trim()));
+        }
+        out.trim_end().to_string()
+    }
+}
+
+/// Release notes в структурированном ответе
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseNotesJson {
+    pub title: String,
+    pub highlights: Vec<String>,
+    pub body: String,
+}
+
+impl StructuredResponse for ReleaseNotesJson {
+    const SCHEMA: &'static str = r#"{
+  "type": "object",
+  "required": ["title", "highlights", "body"],
+  "properties": {
+    "title": {"type": "string", "description": "заголовок с эмодзи, без #"},
+    "highlights": {"type": "array", "minItems": 1, "items": {"type": "string"}},
+    "body": {"type": "string", "description": "подробное описание в Markdown"}
+  }
+}"#;
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        if self.title.trim().is_empty() {
+            return Err("title не может быть пустым".to_string());
+        }
+        if self.highlights.is_empty() {
+            return Err("highlights не может быть пустым".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// JSON из ответа модели: без обрамления ```json и текста вокруг объекта
+pub fn extract_json(response: &str) -> &str {
+    let trimmed = response.trim();
+    match (trimmed.find('{'), trimmed.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &trimmed[start..=end],
+        _ => trimmed,
+    }
+}
+
+/// Разбор и проверка ответа; текст ошибки уходит модели в запросе на исправление
+pub fn parse_structured<T: StructuredResponse>(response: &str) -> std::result::Result<T, String> {
+    let value: T = serde_json::from_str(extract_json(response)).map_err(|e| e.to_string())?;
+    value.validate()?;
+    Ok(value)
+}
+
+/// Диалог с ответом по схеме `T`: ответ с ошибкой разбора модель исправляет, видя ошибку
+struct StructuredQuery<'a, T> {
+    client: &'a dyn LlmProvider,
+    retry: &'a RetryPolicy,
+    fix_attempts: u32,
+    output: PhantomData<fn() -> T>,
+}
+
+impl<T: StructuredResponse> ConversationalAgent for StructuredQuery<'_, T> {
+    type Context = ();
+    type Output = T;
+
+    fn client(&self) -> &dyn LlmProvider {
+        self.client
+    }
+
+    fn retry(&self) -> &RetryPolicy {
+        self.retry
+    }
+
+    fn max_clarifications(&self) -> usize {
+        self.fix_attempts as usize
+    }
+
+    fn assess(&self, _: &(), response: &str) -> Assessment<T> {
+        match parse_structured::<T>(response) {
+            Ok(value) => Assessment::Complete(value),
+            Err(error) => Assessment::Invalid {
+                clarification: FIX_JSON_PROMPT.replace("{schema}", T::SCHEMA).replace("{error}", &error),
+                error,
+            },
+        }
+    }
+
+    fn unresolved(&self, attempts: usize, error: String) -> anyhow::Error {
+        InvalidStructuredOutput { attempts: attempts as u32, error }.into()
+    }
+}
+
+/// Запрос с ответом по схеме `T`: при ошибке разбора модель до `fix_attempts` раз получает
+/// в том же диалоге ошибку с просьбой исправить JSON
+pub async fn complete_structured<T: StructuredResponse>(
+    client: &dyn LlmProvider,
+    prompt: &str,
+    retry: &RetryPolicy,
+    fix_attempts: u32,
+) -> Result<T> {
+    let request = format!("{}\n{}", prompt, JSON_OUTPUT_INSTRUCTIONS.replace("{schema}", T::SCHEMA));
+    StructuredQuery { client, retry, fix_attempts, output: PhantomData }.converse(&request, &()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::scripted::ScriptedProvider;
+
+    #[tokio::test]
+    async fn test_structured_output_retries_with_fix_prompt() {
+        let client = ScriptedProvider::replies(vec![
+            "Вот changelog: {\"sections\": [{\"emoji\": \"🚀\", \"title\": \"Новые возможности\"}]}",
+            "```json\n{\"sections\": [{\"emoji\": \"🚀\", \"title\": \"Новые возможности\", \"changes\": [\"Экспорт в PDF\"]}]}\n```",
+        ]);
+        let changelog: ChangelogJson = complete_structured(&client, "changelog", &RetryPolicy::default(), 1).await.unwrap();
+        assert_eq!(changelog.to_markdown("1.2.0", OutputLanguage::Ru), "## Изменения 1.2.0\n\n### 🚀 Новые возможности\n\n- Экспорт в PDF");
+
+        let prompts = client.prompts();
+        assert!(prompts[0].contains("\"required\": [\"sections\"]"));
+        assert!(prompts[1].contains("missing field `changes`"));
+        assert!(prompts[1].contains("Вот changelog"));
+
+        // Пустой список проходит serde, но не `validate`; попытки исправления исчерпаны
+        let client = ScriptedProvider::replies(vec!["{\"title\": \"🎉 1.2.0\", \"highlights\": [], \"body\": \"\"}"]);
+        let err = complete_structured::<ReleaseNotesJson>(&client, "notes", &RetryPolicy::default(), 0).await.unwrap_err();
+        assert!(err.is::<InvalidStructuredOutput>());
+        assert!(err.to_string().ends_with("highlights не может быть пустым"));
+    }
+}
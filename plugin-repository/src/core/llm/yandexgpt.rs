@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
@@ -6,16 +7,49 @@ use tokio::time::timeout;
 use tracing::{info, warn, error, debug};
 use reqwest::Client;
 
+use crate::config::parser::ProxyConfig;
+use crate::core::llm::conversation::{self, ChatTurn};
+use crate::core::llm::health::{ProviderCheck, HEALTH_PROMPT};
+use crate::core::llm::key_pool::{self, KeyPool, YandexAccount};
+use crate::core::llm::provider::{ChunkCallback, LlmProvider};
+use crate::core::llm::transcript::{Transcript, TranscriptEntry};
+use crate::utils::clock::SharedClock;
+use crate::utils::network::NetworkUtils;
+
+/// Корень Foundation Models API: `completion`, `completionAsync` и `textEmbedding` лежат под ним
+pub const DEFAULT_BASE_URL: &str = "https://llm.api.cloud.yandex.net/foundationModels/v1";
+
+/// Endpoint статуса операций Yandex Cloud
+pub const DEFAULT_OPERATION_URL: &str = "https://operation.api.cloud.yandex.net/operations";
+
+/// Общий лимит потокового запроса (пауза между фрагментами — не больше `timeout`)
+const STREAM_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Системный промпт для всех запросов
+pub(crate) const SYSTEM_PROMPT: &str = "Ты - полезный AI помощник, который отвечает на русском языке.";
+pub(crate) const SYSTEM_PROMPT_EN: &str = "You are a helpful AI assistant that answers in English.";
+
 /// HTTP клиент для YandexGPT API
 #[derive(Clone)]
 pub struct YandexGPTClient {
     client: Client,
-    api_key: String,
-    folder_id: String,
+    /// Аккаунты для ротации при исчерпании квоты; общий для всех клонов агентов
+    keys: KeyPool,
     base_url: String,
+    operation_url: String,
     model: String,
     temperature: f32,
     max_tokens: u32,
+    /// Системный промпт агента из `[llm_agents]`, по умолчанию общий
+    system_prompt: String,
+    async_threshold_chars: usize,
+    async_poll_interval: Duration,
+    async_timeout: Duration,
+    /// Ожидание ответа API (`[llm] request_timeout_secs`)
+    timeout: Duration,
+    /// Имя агента для стенограммы
+    agent: String,
+    transcript: Transcript,
 }
 
 /// Запрос к YandexGPT API
@@ -26,6 +60,20 @@ struct YandexGPTRequest {
     messages: Vec<Message>,
 }
 
+/// Запрос эмбеддинга текста
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EmbeddingRequest {
+    model_uri: String,
+    text: String,
+}
+
+/// Ответ с эмбеддингом
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    embedding: Vec<f32>,
+}
+
 /// Опции генерации
 #[derive(Debug, Serialize)]
 struct CompletionOptions {
@@ -68,6 +116,130 @@ struct ResponseMessage {
     text: String,
 }
 
+/// Операция асинхронной генерации (`completionAsync` и `operations/{id}`)
+#[derive(Debug, Deserialize)]
+struct Operation {
+    id: String,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    response: Option<CompletionResult>,
+    #[serde(default)]
+    error: Option<OperationError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OperationError {
+    #[serde(default)]
+    code: i32,
+    #[serde(default)]
+    message: String,
+}
+
+impl Operation {
+    /// Текст ответа завершенной операции, `None` — операция еще выполняется
+    fn result(&self) -> Result<Option<String>> {
+        if !self.done {
+            return Ok(None);
+        }
+        if let Some(error) = &self.error {
+            return Err(anyhow::anyhow!(
+                "Операция YandexGPT {} завершилась ошибкой {}: {}",
+                self.id, error.code, error.message
+            ));
+        }
+        let result = self.response.as_ref()
+            .with_context(|| format!("Операция YandexGPT {} завершилась без ответа", self.id))?;
+        let alternative = result.alternatives.first()
+            .context("YandexGPT не вернул альтернатив в ответе")?;
+        match alternative.status.as_str() {
+            "ALTERNATIVE_STATUS_FINAL" | "ALTERNATIVE_STATUS_SUCCESS" | "ALTERNATIVE_STATUS_TRUNCATED_FINAL" => {
+                info!("✅ Получен ответ от YandexGPT ({} токенов)", result.usage.total_tokens);
+                Ok(Some(alternative.message.text.clone()))
+            }
+            other => Err(anyhow::anyhow!("YandexGPT вернул статус: {}", other)),
+        }
+    }
+}
+
+/// Строка потокового ответа: текст альтернативы накапливается от строки к строке
+#[derive(Debug, Deserialize)]
+struct StreamLine {
+    result: StreamResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamResult {
+    alternatives: Vec<Alternative>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+/// Разбирает потоковый ответ (JSON по строке) в фрагменты текста
+#[derive(Debug, Default)]
+struct StreamAccumulator {
+    buffer: Vec<u8>,
+    text: String,
+    finished: bool,
+    usage: Option<Usage>,
+}
+
+impl StreamAccumulator {
+    /// Добавляет байты ответа и возвращает новые фрагменты текста
+    fn push(&mut self, bytes: &[u8]) -> Result<Vec<String>> {
+        self.buffer.extend_from_slice(bytes);
+        let mut chunks = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            chunks.extend(self.parse_line(&line)?);
+        }
+        Ok(chunks)
+    }
+
+    /// Завершает разбор (последняя строка может прийти без перевода строки)
+    fn finish(mut self) -> Result<(String, Vec<String>)> {
+        let rest = std::mem::take(&mut self.buffer);
+        let chunks = self.parse_line(&rest)?;
+        if !self.finished {
+            return Err(anyhow::anyhow!("Поток YandexGPT оборвался до финального ответа"));
+        }
+        if let Some(usage) = &self.usage {
+            info!("✅ Получен потоковый ответ от YandexGPT ({} токенов)", usage.total_tokens);
+        }
+        Ok((self.text, chunks))
+    }
+
+    fn parse_line(&mut self, line: &[u8]) -> Result<Vec<String>> {
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let parsed: StreamLine = serde_json::from_str(line)
+            .with_context(|| format!("Ошибка парсинга потокового ответа YandexGPT: {}", YandexGPTClient::preview(line, 500)))?;
+        let alternative = parsed.result.alternatives.into_iter().next()
+            .context("YandexGPT не вернул альтернатив в ответе")?;
+        match alternative.status.as_str() {
+            "ALTERNATIVE_STATUS_PARTIAL" => {}
+            "ALTERNATIVE_STATUS_FINAL" | "ALTERNATIVE_STATUS_SUCCESS" | "ALTERNATIVE_STATUS_TRUNCATED_FINAL" => self.finished = true,
+            other => return Err(anyhow::anyhow!("YandexGPT вернул статус: {}", other)),
+        }
+        if parsed.result.usage.is_some() {
+            self.usage = parsed.result.usage;
+        }
+
+        let text = alternative.message.text;
+        let delta = match text.strip_prefix(self.text.as_str()) {
+            Some(delta) => delta.to_string(),
+            // Не накопленный текст, а отдельный фрагмент
+            None => text,
+        };
+        self.text.push_str(&delta);
+        Ok(if delta.is_empty() { vec![] } else { vec![delta] })
+    }
+}
+
 /// Статистика использования токенов
 #[derive(Debug, Deserialize)]
 struct Usage {
@@ -84,10 +256,23 @@ struct Usage {
 pub struct YandexGPTConfig {
     pub api_key: String,
     pub folder_id: String,
+    /// Дополнительные аккаунты: на них переключаются после 429 от основного
+    pub extra_accounts: Vec<YandexAccount>,
+    /// На сколько откладывается ключ после 429 без `Retry-After`
+    pub key_cooldown: Duration,
     pub model: String,
     pub temperature: f32,
     pub max_tokens: u32,
     pub timeout: Duration,
+    /// Прокси для запросов к API (`[network.proxy]` и переменные окружения)
+    pub proxy: ProxyConfig,
+    /// Корень API, например OpenAI-совместимый корпоративный шлюз вместо Yandex Cloud
+    pub base_url: String,
+    pub operation_url: String,
+    /// Порог длины промпта в символах для асинхронного режима; 0 — всегда синхронно
+    pub async_threshold_chars: usize,
+    pub async_poll_interval: Duration,
+    pub async_timeout: Duration,
 }
 
 impl Default for YandexGPTConfig {
@@ -97,11 +282,19 @@ impl Default for YandexGPTConfig {
                 .unwrap_or_else(|_| "default_key".to_string()),
             folder_id: std::env::var("DEPLOY_PLUGIN_YANDEX_FOLDER_ID")
                 .unwrap_or_else(|_| "default_folder".to_string()),
+            extra_accounts: Vec::new(),
+            key_cooldown: Duration::from_secs(60),
             // Рекомендуемый формат модели с версией
             model: "yandexgpt/latest".to_string(),
             temperature: 0.3,
             max_tokens: 2000,
-            timeout: Duration::from_secs(30),
+            timeout: Duration::from_secs(60),
+            proxy: ProxyConfig::default(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            operation_url: DEFAULT_OPERATION_URL.to_string(),
+            async_threshold_chars: 20_000,
+            async_poll_interval: Duration::from_secs(2),
+            async_timeout: Duration::from_secs(600),
         }
     }
 }
@@ -117,82 +310,288 @@ impl YandexGPTClient {
     }
     /// Создает новый экземпляр клиента
     pub fn new(config: YandexGPTConfig) -> Self {
-        let client = Client::builder()
-            .timeout(config.timeout)
-            .build()
+        let client = NetworkUtils::http_client(&config.proxy, config.timeout)
             .expect("Failed to create HTTP client");
 
+        let mut accounts = vec![YandexAccount { api_key: config.api_key, folder_id: config.folder_id }];
+        accounts.extend(config.extra_accounts);
         Self {
             client,
-            api_key: config.api_key,
-            folder_id: config.folder_id,
-            base_url: "https://llm.api.cloud.yandex.net/foundationModels/v1/completion".to_string(),
+            transcript: Transcript::new(accounts.iter().map(|a| a.api_key.clone()).collect()),
+            agent: "default".to_string(),
+            keys: KeyPool::new(accounts, config.key_cooldown),
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            operation_url: config.operation_url.trim_end_matches('/').to_string(),
             model: config.model,
             temperature: config.temperature,
             max_tokens: config.max_tokens,
+            system_prompt: SYSTEM_PROMPT.to_string(),
+            async_threshold_chars: config.async_threshold_chars,
+            async_poll_interval: config.async_poll_interval,
+            async_timeout: config.async_timeout,
+            timeout: config.timeout,
         }
     }
 
-    /// Формирует model_uri на основе текущей конфигурации
-    fn build_model_uri(&self) -> String {
+    /// Часы для отметок времени в стенограмме
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.transcript = self.transcript.with_clock(clock);
+        self
+    }
+
+    /// URL метода API относительно `base_url`
+    fn endpoint(&self, method: &str) -> String {
+        format!("{}/{}", self.base_url, method)
+    }
+
+    /// Длинные промпты (например, большой git log) не укладываются в синхронный запрос
+    fn use_async(&self, prompt: &str) -> bool {
+        self.async_threshold_chars > 0 && prompt.chars().count() > self.async_threshold_chars
+    }
+
+    /// Системный промпт и реплики диалога
+    fn messages(&self, history: &[ChatTurn]) -> Vec<Message> {
+        std::iter::once(Message { role: "system".to_string(), text: self.system_prompt.clone() })
+            .chain(history.iter().map(|turn| Message { role: turn.role.as_str().to_string(), text: turn.text.clone() }))
+            .collect()
+    }
+
+    /// Формирует model_uri для каталога аккаунта
+    fn build_model_uri(&self, folder_id: &str) -> String {
         if self.model.starts_with("gpt://") {
             self.model.clone()
         } else {
-            format!("gpt://{}/{}", self.folder_id, self.model)
+            format!("gpt://{}/{}", folder_id, self.model)
+        }
+    }
+
+    /// Отправляет запрос от имени аккаунта из пула. После 429 ключ откладывается и запрос
+    /// повторяется со следующим; когда ключи кончились, ответ 429 возвращается как есть
+    async fn send(&self, request: impl Fn(&YandexAccount) -> reqwest::RequestBuilder) -> Result<(reqwest::Response, YandexAccount)> {
+        let mut attempts = 0;
+        loop {
+            let (index, account) = self.keys.acquire();
+            // Таймаут HTTP клиента и внешний — одно и то же ограничение, сообщение у них общее
+            let response = match timeout(self.timeout, request(&account).send()).await {
+                Ok(Ok(response)) => response,
+                Ok(Err(e)) if !e.is_timeout() => {
+                    return Err(anyhow::Error::new(e).context("Ошибка выполнения запроса к YandexGPT API"));
+                }
+                _ => anyhow::bail!(
+                    "Таймаут запроса к YandexGPT API: нет ответа за {} с ([llm] request_timeout_secs)",
+                    self.timeout.as_secs()
+                ),
+            };
+            self.keys.observe(index, response.headers());
+            attempts += 1;
+            if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return Ok((response, account));
+            }
+            self.keys.exhaust(index, key_pool::retry_after(response.headers()));
+            if attempts >= self.keys.len() {
+                if self.keys.len() > 1 {
+                    let keys: Vec<String> = self.keys.status().iter()
+                        .map(|k| format!("{} — {} с", k.folder_id, k.exhausted_for.unwrap_or_default().as_secs()))
+                        .collect();
+                    warn!("Квота исчерпана у всех ключей YandexGPT: {}", keys.join(", "));
+                }
+                return Ok((response, account));
+            }
+        }
+    }
+
+    /// Проверка одного аккаунта для `ai health`: короткий синхронный запрос без ротации ключей
+    async fn diagnose_account(&self, index: usize, account: &YandexAccount) -> ProviderCheck {
+        let model_uri = self.build_model_uri(&account.folder_id);
+        let request_body = YandexGPTRequest {
+            model_uri: model_uri.clone(),
+            completion_options: CompletionOptions { stream: false, temperature: self.temperature, max_tokens: 16 },
+            messages: vec![Message { role: "user".to_string(), text: HEALTH_PROMPT.to_string() }],
+        };
+        let started = std::time::Instant::now();
+        let response = timeout(
+            self.timeout,
+            self.client
+                .post(self.endpoint("completion"))
+                .header("Authorization", format!("Api-Key {}", account.api_key))
+                .header("x-folder-id", &account.folder_id)
+                .json(&request_body)
+                .send()
+        ).await;
+        let error = match response {
+            Err(_) => Some(format!("нет ответа за {} с", self.timeout.as_secs())),
+            Ok(Err(e)) => Some(format!("ошибка соединения с {}: {}", self.base_url, e)),
+            Ok(Ok(response)) if response.status().is_success() => None,
+            Ok(Ok(response)) => {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                Some(describe_failure(status, &body, &account.folder_id, &model_uri))
+            }
+        };
+        let target = format!("ключ #{}, каталог {}", index + 1, account.folder_id);
+        ProviderCheck::new(self.name(), target, model_uri, started, error)
+    }
+
+    /// Записывает пару запрос/ответ в стенограмму
+    fn record(&self, prompt: &str, timestamp: chrono::DateTime<chrono::Utc>, started: std::time::Instant, result: &Result<String>) {
+        self.transcript.record(TranscriptEntry {
+            timestamp,
+            agent: self.agent.clone(),
+            model: self.model.clone(),
+            system: self.system_prompt.clone(),
+            prompt: prompt.to_string(),
+            response: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().map(|e| format!("{:#}", e)),
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+    }
+
+    async fn request_completion_stream(&self, prompt: &str, on_chunk: ChunkCallback<'_>) -> Result<String> {
+        info!("🤖 Потоковый запрос к YandexGPT API");
+
+        let (mut response, _) = self.send(|account| {
+            let request_body = YandexGPTRequest {
+                model_uri: self.build_model_uri(&account.folder_id),
+                completion_options: CompletionOptions {
+                    stream: true,
+                    temperature: self.temperature,
+                    max_tokens: self.max_tokens,
+                },
+                messages: self.messages(&[ChatTurn::user(prompt)]),
+            };
+            self.client
+                .post(self.endpoint("completion"))
+                .header("Authorization", format!("Api-Key {}", account.api_key))
+                .header("x-folder-id", &account.folder_id)
+                .timeout(STREAM_TIMEOUT.max(self.timeout))
+                .json(&request_body)
+        }).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let response_text = response.text().await.unwrap_or_default();
+            let error_msg = format!("YandexGPT API вернул ошибку {}: {}", status, Self::preview(&response_text, 500));
+            error!("{}", error_msg);
+            return Err(anyhow::anyhow!(error_msg));
+        }
+
+        let mut accumulator = StreamAccumulator::default();
+        loop {
+            let chunk = timeout(self.timeout, response.chunk()).await
+                .context("YandexGPT перестал присылать фрагменты ответа")?
+                .context("Ошибка чтения потокового ответа от YandexGPT")?;
+            let Some(bytes) = chunk else { break };
+            for text in accumulator.push(&bytes)? {
+                on_chunk(&text);
+            }
+        }
+        let (text, rest) = accumulator.finish()?;
+        for chunk in rest {
+            on_chunk(&chunk);
+        }
+        Ok(text)
+    }
+
+    /// Запускает `completionAsync` и опрашивает операцию до готовности
+    async fn request_completion_async(&self, history: &[ChatTurn]) -> Result<String> {
+        let chars: usize = history.iter().map(|turn| turn.text.chars().count()).sum();
+        info!("🤖 Асинхронный запрос к YandexGPT API ({} символов в промпте)", chars);
+
+        // Операция опрашивается ключом, которым запущена
+        let (response, account) = self.send(|account| {
+            let request_body = YandexGPTRequest {
+                model_uri: self.build_model_uri(&account.folder_id),
+                completion_options: CompletionOptions {
+                    stream: false,
+                    temperature: self.temperature,
+                    max_tokens: self.max_tokens,
+                },
+                messages: self.messages(history),
+            };
+            self.client
+                .post(self.endpoint("completionAsync"))
+                .header("Authorization", format!("Api-Key {}", account.api_key))
+                .header("x-folder-id", &account.folder_id)
+                .json(&request_body)
+        }).await?;
+        let mut operation = Self::parse_operation(response).await?;
+        info!("Операция YandexGPT {} запущена", operation.id);
+
+        let deadline = std::time::Instant::now() + self.async_timeout;
+        loop {
+            if let Some(text) = operation.result()? {
+                return Ok(text);
+            }
+            if std::time::Instant::now() >= deadline {
+                return Err(anyhow::anyhow!(
+                    "Операция YandexGPT {} не завершилась за {} с",
+                    operation.id, self.async_timeout.as_secs()
+                ));
+            }
+            tokio::time::sleep(self.async_poll_interval).await;
+
+            debug!("Опрос операции YandexGPT {}", operation.id);
+            let response = timeout(
+                self.timeout,
+                self.client
+                    .get(format!("{}/{}", self.operation_url, operation.id))
+                    .header("Authorization", format!("Api-Key {}", account.api_key))
+                    .send()
+            ).await
+            .context("Таймаут опроса операции YandexGPT")?
+            .context("Ошибка опроса операции YandexGPT")?;
+            operation = Self::parse_operation(response).await?;
+        }
+    }
+
+    async fn parse_operation(response: reqwest::Response) -> Result<Operation> {
+        let status = response.status();
+        let response_text = response.text().await
+            .context("Не удалось прочитать ответ от YandexGPT")?;
+        if !status.is_success() {
+            let error_msg = format!("YandexGPT API вернул ошибку {}: {}", status, Self::preview(&response_text, 500));
+            error!("{}", error_msg);
+            return Err(anyhow::anyhow!(error_msg));
         }
+        serde_json::from_str(&response_text)
+            .with_context(|| format!("Ошибка парсинга операции YandexGPT: {}", Self::preview(&response_text, 500)))
     }
 
-    /// Выполняет chat completion запрос
-    pub async fn chat_completion(&self, prompt: &str) -> Result<String> {
+    async fn request_completion(&self, history: &[ChatTurn]) -> Result<String> {
         info!("🤖 Запрос к YandexGPT API");
 
         // Диагностические логи по конфигурации
         debug!("YandexGPT raw model from config: {}", self.model);
-        debug!("YandexGPT folder_id from config: {}", self.folder_id);
-        if self.folder_id.contains("${") {
+        let folder_id = &self.keys.primary().folder_id;
+        debug!("YandexGPT folder_id from config: {}", folder_id);
+        if folder_id.contains("${") {
             warn!("folder_id содержит плейсхолдер переменной окружения. Проверьте DEPLOY_PLUGIN_YANDEX_FOLDER_ID");
         }
         if !self.model.contains('/') && !self.model.starts_with("gpt://") {
             warn!("model без суффикса версии (например, '/latest'). Текущее значение: {}", self.model);
         }
 
-        // Формируем корректный model_uri
-        let model_uri = self.build_model_uri();
-        info!("Используется модель: {}", model_uri);
+        // model_uri строится для каталога аккаунта, с которого уходит запрос
+        info!("Используется модель: {}", self.build_model_uri(folder_id));
 
-        let request_body = YandexGPTRequest {
-            model_uri,
-            completion_options: CompletionOptions {
-                stream: false,
-                temperature: self.temperature,
-                max_tokens: self.max_tokens,
-            },
-            messages: vec![
-                Message {
-                    role: "system".to_string(),
-                    text: "Ты - полезный AI помощник, который отвечает на русском языке.".to_string(),
+        let (response, _) = self.send(|account| {
+            let request_body = YandexGPTRequest {
+                model_uri: self.build_model_uri(&account.folder_id),
+                completion_options: CompletionOptions {
+                    stream: false,
+                    temperature: self.temperature,
+                    max_tokens: self.max_tokens,
                 },
-                Message {
-                    role: "user".to_string(),
-                    text: prompt.to_string(),
-                },
-            ],
-        };
-
-        debug!("Отправка запроса: {}", serde_json::to_string(&request_body)?);
-
-        let response = timeout(
-            Duration::from_secs(30),
+                messages: self.messages(history),
+            };
+            debug!("Отправка запроса: {}", serde_json::to_string(&request_body).unwrap_or_default());
             self.client
-                .post(&self.base_url)
-                .header("Authorization", format!("Api-Key {}", self.api_key))
+                .post(self.endpoint("completion"))
+                .header("Authorization", format!("Api-Key {}", account.api_key))
                 .header("Content-Type", "application/json")
-                .header("x-folder-id", &self.folder_id)
+                .header("x-folder-id", &account.folder_id)
                 .json(&request_body)
-                .send()
-        ).await
-        .context("Таймаут запроса к YandexGPT API")?
-        .context("Ошибка выполнения запроса к YandexGPT API")?;
+        }).await?;
 
         let status = response.status();
         debug!("Ответ статуса от YandexGPT: {}", status);
@@ -211,30 +610,22 @@ impl YandexGPTClient {
             if response_text.contains("invalid model_uri") {
                 // Строим альтернативный URI
                 let alt_model = if self.model.contains("yandexgpt-lite") { self.model.clone() } else { self.model.replace("yandexgpt", "yandexgpt-lite") };
-                let alt_uri = if alt_model.starts_with("gpt://") { alt_model.clone() } else { format!("gpt://{}/{}", self.folder_id, alt_model) };
-                warn!("Пробуем fallback модель: {}", alt_uri);
-
-                let alt_body = YandexGPTRequest {
-                    model_uri: alt_uri,
-                    completion_options: CompletionOptions { stream: false, temperature: self.temperature, max_tokens: self.max_tokens },
-                    messages: vec![
-                        Message { role: "system".to_string(), text: "Ты - полезный AI помощник, который отвечает на русском языке.".to_string() },
-                        Message { role: "user".to_string(), text: prompt.to_string() },
-                    ],
-                };
-
-                let alt_resp = timeout(
-                    Duration::from_secs(30),
+                let alt_uri = |folder_id: &str| if alt_model.starts_with("gpt://") { alt_model.clone() } else { format!("gpt://{}/{}", folder_id, alt_model) };
+                warn!("Пробуем fallback модель: {}", alt_uri(folder_id));
+
+                let (alt_resp, _) = self.send(|account| {
+                    let alt_body = YandexGPTRequest {
+                        model_uri: alt_uri(&account.folder_id),
+                        completion_options: CompletionOptions { stream: false, temperature: self.temperature, max_tokens: self.max_tokens },
+                        messages: self.messages(history),
+                    };
                     self.client
-                        .post(&self.base_url)
-                        .header("Authorization", format!("Api-Key {}", self.api_key))
+                        .post(self.endpoint("completion"))
+                        .header("Authorization", format!("Api-Key {}", account.api_key))
                         .header("Content-Type", "application/json")
-                        .header("x-folder-id", &self.folder_id)
+                        .header("x-folder-id", &account.folder_id)
                         .json(&alt_body)
-                        .send()
-                ).await
-                .context("Таймаут запроса к YandexGPT API (fallback)")?
-                .context("Ошибка выполнения запроса к YandexGPT API (fallback)")?;
+                }).await.context("Запрос к YandexGPT API (fallback) не удался")?;
 
                 let alt_status = alt_resp.status();
                 debug!("Fallback ответ статуса от YandexGPT: {}", alt_status);
@@ -288,31 +679,73 @@ impl YandexGPTClient {
         }
     }
 
-    /// Выполняет запрос с retry логикой
-    pub async fn chat_completion_with_retry(&self, prompt: &str, max_retries: u32) -> Result<String> {
-        let mut last_error = None;
+}
 
-        for attempt in 0..=max_retries {
-            match self.chat_completion(prompt).await {
-                Ok(response) => return Ok(response),
-                Err(e) => {
-                    warn!("Попытка {} не удалась: {}", attempt + 1, e);
-                    last_error = Some(e);
+#[async_trait]
+impl LlmProvider for YandexGPTClient {
+    fn name(&self) -> &'static str {
+        "yandexgpt"
+    }
 
-                    if attempt < max_retries {
-                        let delay = Duration::from_millis(1000 * (2_u64.pow(attempt)));
-                        info!("Повторная попытка через {:?}", delay);
-                        tokio::time::sleep(delay).await;
-                    }
-                }
-            }
-        }
+    fn get_model_info(&self) -> &str {
+        &self.model
+    }
+
+    fn for_agent(&self, agent: &str) -> Box<dyn LlmProvider> {
+        Box::new(Self { agent: agent.to_string(), ..self.clone() })
+    }
 
-        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("Все попытки провалились")))
+    fn for_agent_with(&self, agent: &str, model: Option<&str>, temperature: Option<f32>, system_prompt: Option<&str>) -> Box<dyn LlmProvider> {
+        Box::new(Self {
+            agent: agent.to_string(),
+            model: model.map_or_else(|| self.model.clone(), str::to_string),
+            temperature: temperature.unwrap_or(self.temperature),
+            system_prompt: system_prompt.map_or_else(|| self.system_prompt.clone(), str::to_string),
+            ..self.clone()
+        })
+    }
+
+    fn transcript(&self) -> &Transcript {
+        &self.transcript
+    }
+
+    /// Выполняет chat completion запрос; пара запрос/ответ попадает в стенограмму
+    async fn chat_completion(&self, prompt: &str) -> Result<String> {
+        self.chat_conversation(&[ChatTurn::user(prompt)]).await
+    }
+
+    /// Диалог уходит списком сообщений; в стенограмму попадает вся история
+    #[tracing::instrument(name = "llm.request", skip_all, fields(agent = %self.agent, model = %self.model))]
+    async fn chat_conversation(&self, history: &[ChatTurn]) -> Result<String> {
+        let started = std::time::Instant::now();
+        let timestamp = self.transcript.now();
+        let prompt = conversation::flatten(history);
+        let result = if self.use_async(&prompt) {
+            self.request_completion_async(history).await
+        } else {
+            self.request_completion(history).await
+        };
+        self.record(&prompt, timestamp, started, &result);
+        result
+    }
+
+    /// Потоковый режим (`stream: true`): фрагменты приходят по мере генерации.
+    /// Длинный промпт уходит в асинхронный режим, и ответ приходит одним фрагментом
+    #[tracing::instrument(name = "llm.request", skip_all, fields(agent = %self.agent, model = %self.model, stream = true))]
+    async fn chat_completion_stream(&self, prompt: &str, on_chunk: ChunkCallback<'_>) -> Result<String> {
+        let started = std::time::Instant::now();
+        let timestamp = self.transcript.now();
+        let result = if self.use_async(prompt) {
+            self.request_completion_async(&[ChatTurn::user(prompt)]).await.inspect(|text| on_chunk(text))
+        } else {
+            self.request_completion_stream(prompt, on_chunk).await
+        };
+        self.record(prompt, timestamp, started, &result);
+        result
     }
 
     /// Проверяет доступность API
-    pub async fn health_check(&self) -> Result<bool> {
+    async fn health_check(&self) -> Result<bool> {
         info!("🔍 Проверка доступности YandexGPT API");
 
         match self.chat_completion("Привет! Просто проверка доступности API.").await {
@@ -327,9 +760,56 @@ impl YandexGPTClient {
         }
     }
 
-    /// Получает информацию о модели
-    pub fn get_model_info(&self) -> &str {
-        &self.model
+    /// Каждый аккаунт пула проверяется отдельно: ключ, доступ к каталогу и model URI
+    async fn diagnose(&self) -> Vec<ProviderCheck> {
+        let mut checks = Vec::new();
+        for (index, account) in self.keys.accounts().iter().enumerate() {
+            checks.push(self.diagnose_account(index, account).await);
+        }
+        checks
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        true
+    }
+
+    /// Возвращает эмбеддинг текста (модель text-search-doc)
+    #[tracing::instrument(name = "llm.embedding", skip_all, fields(agent = %self.agent))]
+    async fn text_embedding(&self, text: &str) -> Result<Vec<f32>> {
+        let (response, _) = self.send(|account| {
+            let request_body = EmbeddingRequest {
+                model_uri: format!("emb://{}/text-search-doc/latest", account.folder_id),
+                text: text.to_string(),
+            };
+            self.client
+                .post(self.endpoint("textEmbedding"))
+                .header("Authorization", format!("Api-Key {}", account.api_key))
+                .header("x-folder-id", &account.folder_id)
+                .json(&request_body)
+        }).await.context("Запрос эмбеддинга к YandexGPT API не удался")?;
+
+        let status = response.status();
+        let response_text = response.text().await
+            .context("Не удалось прочитать ответ эмбеддинга от YandexGPT")?;
+        if !status.is_success() {
+            return Err(anyhow::anyhow!("YandexGPT API вернул ошибку {}: {}", status, Self::preview(&response_text, 500)));
+        }
+
+        let parsed: EmbeddingResponse = serde_json::from_str(&response_text)
+            .context("Ошибка парсинга JSON ответа эмбеддинга от YandexGPT")?;
+        Ok(parsed.embedding)
+    }
+}
+
+/// Причина отказа API понятными словами: что не так с ключом, каталогом или моделью
+fn describe_failure(status: reqwest::StatusCode, body: &str, folder_id: &str, model_uri: &str) -> String {
+    let detail = YandexGPTClient::preview(body.trim(), 200);
+    match status.as_u16() {
+        401 => format!("API-ключ недействителен (401): {}", detail),
+        403 => format!("нет доступа к каталогу {} (403), нужна роль ai.languageModels.user: {}", folder_id, detail),
+        429 => format!("квота исчерпана (429): {}", detail),
+        _ if body.contains("model_uri") => format!("model URI {} не найден ({}): {}", model_uri, status, detail),
+        _ => format!("{}: {}", status, detail),
     }
 }
 
@@ -371,15 +851,109 @@ mod tests {
         let config = YandexGPTConfig {
             api_key: "test_key".to_string(),
             folder_id: "test_folder".to_string(),
+            extra_accounts: Vec::new(),
+            key_cooldown: Duration::from_secs(60),
             model: "yandexgpt/latest".to_string(),
             temperature: 0.3,
             max_tokens: 1000,
             timeout: Duration::from_secs(10),
+            proxy: ProxyConfig::default(),
+            base_url: "https://llm-gateway.corp.example/yandex/v1/".to_string(),
+            operation_url: DEFAULT_OPERATION_URL.to_string(),
+            async_threshold_chars: 10,
+            async_poll_interval: Duration::from_secs(1),
+            async_timeout: Duration::from_secs(60),
         };
 
         let client = YandexGPTClient::new(config);
-        assert_eq!(client.folder_id, "test_folder");
+        assert_eq!(client.keys.primary().folder_id, "test_folder");
         assert_eq!(client.get_model_info(), "yandexgpt/latest");
+        assert_eq!(client.endpoint("completion"), "https://llm-gateway.corp.example/yandex/v1/completion");
+        assert!(!client.use_async("короткий"));
+        assert!(client.use_async("длинный git log"));
+        let sync_only = YandexGPTClient { async_threshold_chars: 0, ..client };
+        assert!(!sync_only.use_async(&"x".repeat(100_000)));
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout_from_llm_config() {
+        // Сервер принимает соединение и молчит
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                connections.push(socket);
+            }
+        });
+
+        let mut config = crate::config::parser::test_config();
+        config.llm.request_timeout_secs = 1;
+        config.yandexgpt.base_url = format!("http://{}", address);
+        let client = crate::core::llm::provider::provider_named(&config, "yandexgpt", crate::utils::clock::system_clock()).unwrap();
+        let started = std::time::Instant::now();
+        let error = client.chat_completion("changelog").await.unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(format!("{:#}", error).contains("нет ответа за 1 с ([llm] request_timeout_secs)"), "{:#}", error);
+    }
+
+    #[test]
+    fn test_describe_failure() {
+        let uri = "gpt://b1g/yandexgpt/latest";
+        let describe = |status: u16, body: &str| {
+            describe_failure(reqwest::StatusCode::from_u16(status).unwrap(), body, "b1g", uri)
+        };
+        assert!(describe(401, "Unknown api key").starts_with("API-ключ недействителен (401)"));
+        assert!(describe(403, "Permission denied").starts_with("нет доступа к каталогу b1g (403)"));
+        assert_eq!(
+            describe(400, r#"{"error":"invalid model_uri"}"#),
+            r#"model URI gpt://b1g/yandexgpt/latest не найден (400 Bad Request): {"error":"invalid model_uri"}"#
+        );
+    }
+
+    #[test]
+    fn test_async_operation_result() {
+        let pending: Operation = serde_json::from_str(r#"{"id":"op1","done":false}"#).unwrap();
+        assert!(pending.result().unwrap().is_none());
+
+        let done: Operation = serde_json::from_str(r#"{
+            "id": "op1", "done": true,
+            "response": {
+                "@type": "type.googleapis.com/yandex.cloud.ai.foundation_models.v1.CompletionResponse",
+                "alternatives": [{"message": {"role": "assistant", "text": "Версия 1.2.0"}, "status": "ALTERNATIVE_STATUS_FINAL"}],
+                "usage": {"inputTextTokens": "9000", "completionTokens": "10", "totalTokens": "9010"},
+                "modelVersion": "1"
+            }
+        }"#).unwrap();
+        assert_eq!(done.result().unwrap().as_deref(), Some("Версия 1.2.0"));
+
+        let failed: Operation = serde_json::from_str(
+            r#"{"id":"op1","done":true,"error":{"code":3,"message":"prompt too long"}}"#
+        ).unwrap();
+        let err = failed.result().unwrap_err().to_string();
+        assert!(err.contains("prompt too long"), "{}", err);
+    }
+
+    #[test]
+    fn test_stream_accumulator() {
+        let line = |text: &str, status: &str| {
+            format!(r#"{{"result":{{"alternatives":[{{"message":{{"role":"assistant","text":"{}"}},"status":"{}"}}],"modelVersion":"1"}}}}"#, text, status)
+        };
+        let mut accumulator = StreamAccumulator::default();
+        let first = format!("{}\n", line("## 1.2", "ALTERNATIVE_STATUS_PARTIAL"));
+        let second = format!("{}\n{}", line("## 1.2.0\\n- Фикс", "ALTERNATIVE_STATUS_PARTIAL"), line("## 1.2.0\\n- Фикс", "ALTERNATIVE_STATUS_FINAL"));
+
+        // Строка может прийти по частям
+        assert!(accumulator.push(&first.as_bytes()[..10]).unwrap().is_empty());
+        assert_eq!(accumulator.push(&first.as_bytes()[10..]).unwrap(), vec!["## 1.2"]);
+        assert_eq!(accumulator.push(second.as_bytes()).unwrap(), vec![".0\n- Фикс"]);
+        let (text, rest) = accumulator.finish().unwrap();
+        assert_eq!(text, "## 1.2.0\n- Фикс");
+        assert!(rest.is_empty());
+
+        let mut broken = StreamAccumulator::default();
+        broken.push(first.as_bytes()).unwrap();
+        assert!(broken.finish().is_err());
     }
 
     #[tokio::test]
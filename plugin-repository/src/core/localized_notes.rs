@@ -0,0 +1,128 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+use crate::core::postprocess::{ArchiveEntry, ArtifactProcessor, ProcessContext};
+
+/// Ключ change-notes в resource bundle
+pub const CHANGE_NOTES_KEY: &str = "change.notes";
+
+/// Проверяет код локали в формате Java: `en`, `de`, `pt_BR`, `zh_CN`
+pub fn validate_locale(locale: &str) -> Result<()> {
+    let (language, country) = match locale.split_once('_') {
+        Some((language, country)) => (language, Some(country)),
+        None => (locale, None),
+    };
+    let language_ok = (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_lowercase());
+    let country_ok = country.is_none_or(|c| c.len() == 2 && c.chars().all(|c| c.is_ascii_uppercase()));
+    if !language_ok || !country_ok {
+        anyhow::bail!("Некорректная локаль '{}': ожидается формат ll или ll_CC (например, en, pt_BR)", locale);
+    }
+    Ok(())
+}
+
+/// Название языка для промпта перевода
+pub fn language_name(locale: &str) -> &str {
+    match locale.split('_').next().unwrap_or(locale) {
+        "en" => "английский",
+        "de" => "немецкий",
+        "fr" => "французский",
+        "es" => "испанский",
+        "it" => "итальянский",
+        "pt" => "португальский",
+        "zh" => "китайский",
+        "ja" => "японский",
+        "ko" => "корейский",
+        "tr" => "турецкий",
+        "uk" => "украинский",
+        "ru" => "русский",
+        _ => locale,
+    }
+}
+
+/// Значение .properties: экранирование служебных символов и не-ASCII через `\uXXXX`,
+/// чтобы bundle читался и старыми ResourceBundle (ISO-8859-1), и IDE (UTF-8)
+fn escape_property(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_ascii() && !c.is_ascii_control() => out.push(c),
+            c => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    out.push_str(&format!("\\u{:04x}", unit));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Содержимое `<bundle>_<locale>.properties` с одним ключом change-notes
+pub fn render_bundle(html: &str) -> String {
+    format!("{}={}\n", CHANGE_NOTES_KEY, escape_property(html))
+}
+
+/// Добавляет в архив resource bundle с change-notes для каждой локали
+pub struct LocalizedChangeNotes {
+    /// Путь bundle внутри архива без суффикса локали: `messages/ChangeNotesBundle`
+    pub bundle: String,
+    /// Локаль -> HTML change-notes
+    pub notes: BTreeMap<String, String>,
+}
+
+impl LocalizedChangeNotes {
+    pub fn entry_name(&self, locale: &str) -> String {
+        format!("{}_{}.properties", self.bundle.trim_matches('/'), locale)
+    }
+}
+
+impl ArtifactProcessor for LocalizedChangeNotes {
+    fn name(&self) -> &str {
+        "localized-change-notes"
+    }
+
+    fn process(&self, _ctx: &ProcessContext, entries: &mut Vec<ArchiveEntry>) -> Result<()> {
+        for (locale, html) in &self.notes {
+            let name = self.entry_name(locale);
+            entries.retain(|e| e.name != name);
+            entries.push(ArchiveEntry::file(name, render_bundle(html).into_bytes()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundles_for_locales() {
+        assert!(validate_locale("en").is_ok());
+        assert!(validate_locale("pt_BR").is_ok());
+        assert!(validate_locale("EN").is_err());
+        assert!(validate_locale("en-US").is_err());
+        assert_eq!(language_name("de_AT"), "немецкий");
+
+        assert_eq!(render_bundle("<p>Готово</p>\n"), "change.notes=<p>\\u0413\\u043e\\u0442\\u043e\\u0432\\u043e</p>\\n\n");
+
+        let processor = LocalizedChangeNotes {
+            bundle: "/messages/ChangeNotesBundle".to_string(),
+            notes: BTreeMap::from([("en".to_string(), "<ul><li>Fixed</li></ul>".to_string())]),
+        };
+        let ctx = ProcessContext {
+            project_root: std::path::PathBuf::from("."),
+            plugin_id: "ru.marslab.ide.ride".to_string(),
+            version: "1.2.0".to_string(),
+            environment: None,
+        };
+        let mut entries = vec![ArchiveEntry::file("messages/ChangeNotesBundle_en.properties", b"stale".to_vec())];
+        processor.process(&ctx, &mut entries).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "messages/ChangeNotesBundle_en.properties");
+        assert_eq!(entries[0].data, b"change.notes=<ul><li>Fixed</li></ul>\n");
+    }
+}
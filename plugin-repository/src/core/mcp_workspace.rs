@@ -0,0 +1,262 @@
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::{debug, info};
+
+use crate::config::parser::{McpBuildConfig, ProxyConfig};
+use crate::utils::network::NetworkUtils;
+
+/// Заголовок ключа MCP-сервера
+const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Запас сверх лимита команды: сервер сам завершает команду по таймауту
+const HTTP_TIMEOUT_MARGIN: Duration = Duration::from_secs(60);
+
+/// Результат `POST /exec`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExecOutput {
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+    #[serde(default)]
+    pub truncated: bool,
+    pub duration_ms: u64,
+}
+
+impl ExecOutput {
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ExecRequest<'a> {
+    command: &'a str,
+    args: &'a [String],
+    cwd: &'a str,
+    timeout_secs: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct FileContent {
+    content: String,
+    checksum: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DirectoryListing {
+    files: Vec<RemoteFile>,
+}
+
+/// Файл из листинга `GET /files`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RemoteFile {
+    pub name: String,
+    pub size: u64,
+    pub modified_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    details: Option<String>,
+}
+
+/// Клиент workspace MCP-сервера: команды через `POST /exec`, файлы через `/files`
+pub struct McpWorkspace {
+    client: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+    /// Директория проекта относительно корня workspace
+    dir: String,
+    timeout_secs: u64,
+}
+
+impl McpWorkspace {
+    pub fn new(config: &McpBuildConfig, proxy: &ProxyConfig) -> Result<Self> {
+        let timeout = Duration::from_secs(config.timeout_secs) + HTTP_TIMEOUT_MARGIN;
+        Ok(Self {
+            client: NetworkUtils::http_client(proxy, timeout).context("Не удалось создать HTTP клиент для MCP-сервера")?,
+            url: config.url.trim_end_matches('/').to_string(),
+            api_key: config.api_key.clone().filter(|key| !key.is_empty()),
+            dir: Self::normalize_dir(&config.workspace_dir),
+            timeout_secs: config.timeout_secs,
+        })
+    }
+
+    fn normalize_dir(dir: &str) -> String {
+        let dir = dir.trim_matches('/');
+        let dir = dir.strip_prefix("./").unwrap_or(dir);
+        if dir == "." { String::new() } else { dir.to_string() }
+    }
+
+    /// Путь относительно корня workspace для пути внутри проекта
+    pub fn path(&self, relative: &str) -> String {
+        let relative = relative.trim_matches('/');
+        if self.dir.is_empty() {
+            relative.to_string()
+        } else if relative.is_empty() {
+            self.dir.clone()
+        } else {
+            format!("{}/{}", self.dir, relative)
+        }
+    }
+
+    /// Выполняет команду в директории проекта (`cwd` — корень workspace, если задан явно)
+    pub async fn exec(&self, command: &str, args: &[String], cwd: Option<&str>) -> Result<ExecOutput> {
+        let cwd = cwd.map(str::to_string).unwrap_or_else(|| self.path(""));
+        debug!("MCP exec в '{}': {} {}", cwd, command, args.join(" "));
+        let request = self
+            .authorized(self.client.post(format!("{}/exec", self.url)))
+            .json(&ExecRequest { command, args, cwd: &cwd, timeout_secs: self.timeout_secs });
+        let response = Self::send(request, "POST /exec").await?;
+        let output: ExecOutput = response.json().await.context("Некорректный ответ POST /exec")?;
+        if output.timed_out {
+            anyhow::bail!("Команда '{}' на MCP-сервере не завершилась за {} с", command, self.timeout_secs);
+        }
+        Ok(output)
+    }
+
+    /// Клонирует репозиторий при первом запуске и переключает workspace на коммит
+    pub async fn checkout(&self, remote: &str, commit: &str) -> Result<()> {
+        // Директории проекта еще нет — сервер ответит 404
+        let probe = self.exec("git", &strings(&["rev-parse", "--git-dir"]), None).await;
+        if !probe.as_ref().is_ok_and(ExecOutput::success) {
+            let target = if self.dir.is_empty() { "." } else { self.dir.as_str() };
+            info!("📥 Клонирование {} в MCP workspace ({})", remote, target);
+            let cloned = self.exec("git", &strings(&["clone", remote, target]), Some("")).await?;
+            Self::ensure(&cloned, "git clone")?;
+        }
+
+        let fetched = self.exec("git", &strings(&["fetch", "--tags", remote]), None).await?;
+        Self::ensure(&fetched, "git fetch")?;
+        let checked_out = self.exec("git", &strings(&["checkout", "--force", "--detach", commit]), None).await?;
+        Self::ensure(&checked_out, "git checkout")?;
+        info!("🔀 MCP workspace переключен на {}", &commit[..commit.len().min(12)]);
+        Ok(())
+    }
+
+    /// ZIP-файлы директории; отсутствующая директория — пустой список
+    pub async fn list_zips(&self, dir: &str) -> Result<BTreeMap<String, RemoteFile>> {
+        let request = self
+            .authorized(self.client.get(format!("{}/files", self.url)))
+            .query(&[("dir", dir)]);
+        let response = request.send().await.context("MCP-сервер недоступен (GET /files)")?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(BTreeMap::new());
+        }
+        let response = Self::check(response, "GET /files").await?;
+        let listing: DirectoryListing = response.json().await.context("Некорректный ответ GET /files")?;
+        Ok(listing
+            .files
+            .into_iter()
+            .filter(|f| f.name.ends_with(".zip"))
+            .map(|f| (f.name.clone(), f))
+            .collect())
+    }
+
+    /// Скачивает файл без потерь (base64) и сверяет SHA256 с ответом сервера
+    pub async fn download(&self, path: &str) -> Result<Vec<u8>> {
+        let mut url = reqwest::Url::parse(&self.url).with_context(|| format!("Некорректный адрес MCP-сервера: {}", self.url))?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Некорректный адрес MCP-сервера: {}", self.url))?
+            .pop_if_empty()
+            .push("files")
+            .push(path);
+        url.query_pairs_mut().append_pair("encoding", "base64");
+
+        let response = Self::send(self.authorized(self.client.get(url)), "GET /files/:path").await?;
+        let file: FileContent = response.json().await.context("Некорректный ответ GET /files/:path")?;
+        let data = BASE64.decode(file.content.as_bytes()).context("Некорректное base64-содержимое файла")?;
+        let checksum = format!("{:x}", Sha256::digest(&data));
+        if checksum != file.checksum {
+            anyhow::bail!("SHA256 скачанного {} ({}) не совпадает с сервером ({})", path, checksum, file.checksum);
+        }
+        Ok(data)
+    }
+
+    fn authorized(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(key) => request.header(API_KEY_HEADER, key),
+            None => request,
+        }
+    }
+
+    async fn send(request: reqwest::RequestBuilder, what: &str) -> Result<reqwest::Response> {
+        let response = request.send().await.with_context(|| format!("MCP-сервер недоступен ({})", what))?;
+        Self::check(response, what).await
+    }
+
+    async fn check(response: reqwest::Response, what: &str) -> Result<reqwest::Response> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        let reason = match serde_json::from_str::<ErrorBody>(&body) {
+            Ok(error) => error.details.unwrap_or(error.message),
+            Err(_) => body,
+        };
+        Err(anyhow::anyhow!("MCP-сервер отклонил {}: {} {}", what, status, reason))
+    }
+
+    fn ensure(output: &ExecOutput, what: &str) -> Result<()> {
+        if output.success() {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "{} в MCP workspace завершился с кодом {:?}: {}",
+            what,
+            output.exit_code,
+            output.stderr.lines().last().unwrap_or("нет вывода ошибок")
+        ))
+    }
+}
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+/// ZIP, созданные или измененные сборкой: сравнение листингов до и после
+pub fn built_zips(before: &BTreeMap<String, RemoteFile>, after: &BTreeMap<String, RemoteFile>) -> Vec<String> {
+    after
+        .iter()
+        .filter(|(name, file)| before.get(*name) != Some(*file))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, size: u64, modified: u64) -> (String, RemoteFile) {
+        let modified_at = format!("Some(SystemTime {{ tv_sec: {}, tv_nsec: 0 }})", modified);
+        (name.to_string(), RemoteFile { name: name.to_string(), size, modified_at })
+    }
+
+    #[test]
+    fn test_workspace_paths_and_built_zips() {
+        let mut config = McpBuildConfig { url: "http://build:3000/".to_string(), ..McpBuildConfig::default() };
+        let workspace = McpWorkspace::new(&config, &ProxyConfig::default()).unwrap();
+        assert_eq!(workspace.url, "http://build:3000");
+        assert_eq!(workspace.path("build/distributions"), "build/distributions");
+
+        config.workspace_dir = "./ride/".to_string();
+        let workspace = McpWorkspace::new(&config, &ProxyConfig::default()).unwrap();
+        assert_eq!(workspace.path(""), "ride");
+        assert_eq!(workspace.path("build/distributions/"), "ride/build/distributions");
+
+        let before: BTreeMap<_, _> = [file("ride-1.1.0.zip", 10, 1), file("ride-1.2.0.zip", 10, 1)].into();
+        let after: BTreeMap<_, _> =
+            [file("ride-1.1.0.zip", 10, 1), file("ride-1.2.0.zip", 12, 2), file("ride-1.3.0.zip", 11, 2)].into();
+        assert_eq!(built_zips(&before, &after), ["ride-1.2.0.zip", "ride-1.3.0.zip"]);
+        assert!(built_zips(&after, &after).is_empty());
+    }
+}
@@ -1,4 +1,40 @@
 pub mod builder;
+pub mod change_notes;
+pub mod deadline;
+pub mod deploy_layout;
 pub mod releaser;
+pub mod reproducible;
 pub mod deployer;
-pub mod llm;
\ No newline at end of file
+pub mod dry_run;
+pub mod inspector;
+pub mod llm;
+pub mod plugin_xml;
+pub mod postprocess;
+pub mod readiness;
+pub mod resources;
+pub mod audit;
+pub mod unreleased;
+pub mod remote_exec;
+pub mod checksums;
+pub mod external_version;
+pub mod gradle_problems;
+pub mod localized_notes;
+pub mod gradle_wrapper;
+pub mod build_env;
+pub mod chatops;
+pub mod release_vault;
+pub mod proofreading;
+pub mod pipeline;
+pub mod gradle_dsl;
+pub mod disk_preflight;
+pub mod update_check;
+pub mod integrity;
+pub mod mcp_workspace;
+pub mod chaos;
+pub mod interrupt;
+pub mod version_guard;
+pub mod artifact_cache;
+pub mod remediation;
+pub mod build_profile;
+pub mod compatibility;
+pub mod rollback;
@@ -0,0 +1,184 @@
+use anyhow::Result;
+use std::fmt;
+
+use crate::config::parser::{PipelineConfig, PipelineStageConfig, PipelineStageOptions};
+use crate::config::validator::Capability;
+use crate::core::inspector::DEFAULT_MAX_RESOURCE_SIZE;
+
+/// Имена этапов, из которых собираются пайплайны
+pub const STAGE_NAMES: [&str; 6] = ["build", "verify", "validate", "notes", "release", "deploy"];
+
+/// Этап пользовательского пайплайна с разобранными опциями
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stage {
+    /// Сборка артефакта с версией пайплайна
+    Build { profile: String },
+    /// Глубокая проверка собранного артефакта (как `verify-artifact`)
+    Verify { max_resource_size: u64 },
+    /// Проверки перед деплоем: конфигурация `[repository]` и ресурсы плагина
+    Validate,
+    /// Changelog и release notes через LLM
+    Notes,
+    /// Git-тег и его push
+    Release,
+    Deploy { channel: Option<String>, force: bool, rollback_on_failure: bool, allow_republish: bool },
+}
+
+impl Stage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Build { .. } => "build",
+            Stage::Verify { .. } => "verify",
+            Stage::Validate => "validate",
+            Stage::Notes => "notes",
+            Stage::Release => "release",
+            Stage::Deploy { .. } => "deploy",
+        }
+    }
+
+    /// Этапу нужна версия релиза
+    pub fn needs_version(&self) -> bool {
+        matches!(self, Stage::Build { .. } | Stage::Notes | Stage::Release)
+    }
+
+    /// Секции конфигурации, которые проверяются перед запуском
+    pub fn capability(&self) -> Option<Capability> {
+        match self {
+            Stage::Build { .. } => Some(Capability::Build),
+            Stage::Notes => Some(Capability::Ai),
+            Stage::Validate | Stage::Deploy { .. } => Some(Capability::Deploy),
+            Stage::Verify { .. } | Stage::Release => None,
+        }
+    }
+
+    fn from_config(stage: &PipelineStageConfig) -> Result<Self> {
+        let options = match stage {
+            PipelineStageConfig::Name(name) => match name.split_once(':') {
+                Some(("deploy", channel)) if !channel.trim().is_empty() => PipelineStageOptions {
+                    stage: "deploy".to_string(),
+                    channel: Some(channel.trim().to_string()),
+                    ..PipelineStageOptions::default()
+                },
+                Some(_) => anyhow::bail!("Некорректный этап '{}': аргумент через ':' поддерживает только deploy:<канал>", name),
+                None => PipelineStageOptions { stage: name.trim().to_string(), ..PipelineStageOptions::default() },
+            },
+            PipelineStageConfig::Options(options) => options.clone(),
+        };
+
+        let name = options.stage.as_str();
+        let stage = match name {
+            "build" => Stage::Build { profile: options.profile.clone().unwrap_or_else(|| "release".to_string()) },
+            "verify" => Stage::Verify { max_resource_size: options.max_resource_size.unwrap_or(DEFAULT_MAX_RESOURCE_SIZE) },
+            "validate" => Stage::Validate,
+            "notes" => Stage::Notes,
+            "release" => Stage::Release,
+            "deploy" => Stage::Deploy {
+                channel: options.channel.clone(),
+                force: options.force.unwrap_or(false),
+                rollback_on_failure: options.rollback_on_failure.unwrap_or(false),
+                allow_republish: options.allow_republish.unwrap_or(false),
+            },
+            other => anyhow::bail!("Неизвестный этап '{}': доступны {}", other, STAGE_NAMES.join(", ")),
+        };
+
+        // Опция и этап, к которому она относится
+        let unsupported: Vec<&str> = [
+            ("profile", options.profile.is_some(), "build"),
+            ("max_resource_size", options.max_resource_size.is_some(), "verify"),
+            ("channel", options.channel.is_some(), "deploy"),
+            ("force", options.force.is_some(), "deploy"),
+            ("rollback_on_failure", options.rollback_on_failure.is_some(), "deploy"),
+            ("allow_republish", options.allow_republish.is_some(), "deploy"),
+        ]
+        .into_iter()
+        .filter(|(_, set, owner)| *set && *owner != name)
+        .map(|(option, _, _)| option)
+        .collect();
+        if !unsupported.is_empty() {
+            anyhow::bail!("Опции {} не применимы к этапу {}", unsupported.join(", "), name);
+        }
+        Ok(stage)
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Build { profile } if profile != "release" => write!(f, "build(profile={})", profile),
+            Stage::Deploy { channel, force, rollback_on_failure, allow_republish } => {
+                write!(f, "deploy")?;
+                if let Some(channel) = channel {
+                    write!(f, ":{}", channel)?;
+                }
+                let flags: Vec<&str> = [("force", *force), ("rollback", *rollback_on_failure), ("allow-republish", *allow_republish)]
+                    .into_iter()
+                    .filter_map(|(flag, set)| set.then_some(flag))
+                    .collect();
+                if !flags.is_empty() {
+                    write!(f, "({})", flags.join(","))?;
+                }
+                Ok(())
+            }
+            other => write!(f, "{}", other.name()),
+        }
+    }
+}
+
+/// Разбирает и проверяет пайплайн: известные этапы, применимые опции, проверка артефакта после сборки
+pub fn parse_pipeline(name: &str, pipeline: &PipelineConfig) -> Result<Vec<Stage>> {
+    if pipeline.stages.is_empty() {
+        anyhow::bail!("Пайплайн '{}' не содержит этапов", name);
+    }
+    let mut stages = Vec::new();
+    for (i, stage) in pipeline.stages.iter().enumerate() {
+        let stage = Stage::from_config(stage).map_err(|e| anyhow::anyhow!("Пайплайн '{}', этап {}: {}", name, i + 1, e))?;
+        if matches!(stage, Stage::Verify { .. }) && !stages.iter().any(|s| matches!(s, Stage::Build { .. })) {
+            anyhow::bail!("Пайплайн '{}': этап verify проверяет собранный артефакт и должен идти после build", name);
+        }
+        stages.push(stage);
+    }
+    Ok(stages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pipeline(toml: &str) -> PipelineConfig {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn test_parse_pipeline() {
+        let nightly = pipeline(
+            r#"
+            description = "Ночная сборка"
+            stages = ["build", "verify", "validate", { stage = "deploy", channel = "nightly", force = true }]
+            "#,
+        );
+        let stages = parse_pipeline("nightly", &nightly).unwrap();
+        assert_eq!(
+            stages,
+            [
+                Stage::Build { profile: "release".to_string() },
+                Stage::Verify { max_resource_size: DEFAULT_MAX_RESOURCE_SIZE },
+                Stage::Validate,
+                Stage::Deploy { channel: Some("nightly".to_string()), force: true, rollback_on_failure: false, allow_republish: false },
+            ]
+        );
+        assert_eq!(stages.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(" -> "), "build -> verify -> validate -> deploy:nightly(force)");
+
+        let staging = parse_pipeline("staging", &pipeline(r#"stages = [{ stage = "build", profile = "debug" }, "deploy:staging"]"#)).unwrap();
+        assert_eq!(staging[0].to_string(), "build(profile=debug)");
+        assert_eq!(staging[1], Stage::Deploy { channel: Some("staging".to_string()), force: false, rollback_on_failure: false, allow_republish: false });
+
+        let error = |toml: &str| parse_pipeline("p", &pipeline(toml)).unwrap_err().to_string();
+        assert!(error(r#"stages = ["build", "package"]"#).contains("этап 2: Неизвестный этап 'package'"));
+        assert!(error(r#"stages = [{ stage = "build", channel = "beta" }]"#).contains("Опции channel не применимы к этапу build"));
+        assert!(error(r#"stages = ["build:debug"]"#).contains("deploy:<канал>"));
+        assert!(error(r#"stages = ["verify", "build"]"#).contains("после build"));
+        assert!(error("stages = []").contains("не содержит этапов"));
+        assert!(toml::from_str::<PipelineConfig>(r#"stages = ["build"]
+            step = 1"#).is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::{Context, Result};
+use std::io::{Cursor, Read};
+use std::path::Path;
+use xmltree::{Element, EmitterConfig, XMLNode};
+
+/// plugin.xml, извлеченный из артефакта
+#[derive(Debug, Clone)]
+pub struct ArtifactPluginXml {
+    /// Путь внутри архива (`lib/plugin.jar!/META-INF/plugin.xml` для вложенного JAR)
+    pub location: String,
+    pub content: String,
+}
+
+/// Ищет META-INF/plugin.xml в корне ZIP, затем во вложенных JAR
+pub fn read_from_artifact(zip_path: &Path) -> Result<ArtifactPluginXml> {
+    let file = std::fs::File::open(zip_path)
+        .with_context(|| format!("Не удалось открыть артефакт: {}", zip_path.display()))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .with_context(|| format!("Не удалось прочитать ZIP {}", zip_path.display()))?;
+
+    if let Ok(mut entry) = archive.by_name("META-INF/plugin.xml") {
+        let mut content = String::new();
+        entry.read_to_string(&mut content).context("Не удалось прочитать META-INF/plugin.xml")?;
+        return Ok(ArtifactPluginXml { location: "META-INF/plugin.xml".to_string(), content });
+    }
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let name = entry.name().to_string();
+        if !name.ends_with(".jar") {
+            continue;
+        }
+        let mut buf = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut buf)?;
+        let Ok(mut jar) = zip::ZipArchive::new(Cursor::new(buf)) else { continue };
+        let mut content = String::new();
+        if let Ok(mut inner) = jar.by_name("META-INF/plugin.xml") {
+            inner.read_to_string(&mut content)
+                .with_context(|| format!("Не удалось прочитать plugin.xml из {}", name))?;
+        } else {
+            continue;
+        }
+        return Ok(ArtifactPluginXml { location: format!("{}!/META-INF/plugin.xml", name), content });
+    }
+
+    anyhow::bail!("plugin.xml не найден в артефакте {}", zip_path.display())
+}
+
+/// Значение `<version>` из plugin.xml
+pub fn version(xml: &str) -> Result<Option<String>> {
+    let root = Element::parse(xml.as_bytes()).context("plugin.xml не является корректным XML")?;
+    Ok(root.get_child("version").and_then(|e| e.get_text()).map(|v| v.trim().to_string()))
+}
+
+/// Подставляет в plugin.xml версию и HTML change-notes (как это сделала бы публикация)
+pub fn enrich(xml: &str, version: &str, change_notes: Option<&str>) -> Result<String> {
+    let mut root = Element::parse(xml.as_bytes()).context("plugin.xml не является корректным XML")?;
+
+    set_child(&mut root, "version", XMLNode::Text(version.to_string()));
+    if let Some(notes) = change_notes {
+        set_child(&mut root, "change-notes", XMLNode::CData(notes.to_string()));
+    }
+    emit(&root)
+}
+
+/// plugin.xml в форматировании `enrich` без изменений содержимого: база для читаемого диффа
+pub fn normalize(xml: &str) -> Result<String> {
+    emit(&Element::parse(xml.as_bytes()).context("plugin.xml не является корректным XML")?)
+}
+
+fn emit(root: &Element) -> Result<String> {
+    let mut buf = Vec::new();
+    root.write_with_config(&mut buf, EmitterConfig::new().perform_indent(true))
+        .context("Сериализация plugin.xml не удалась")?;
+    Ok(String::from_utf8_lossy(&buf).to_string())
+}
+
+/// Строк контекста вокруг изменений в диффе
+const DIFF_CONTEXT: usize = 3;
+
+/// Unified diff двух версий файла (`--- a/<path>`, `+++ b/<path>`, ханки `@@`);
+/// пустая строка — изменений нет
+pub fn unified_diff(before: &str, after: &str, path: &str) -> String {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+
+    // Длины общих подпоследовательностей суффиксов (LCS): plugin.xml небольшой
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    // Правки по порядку: (' ' | '-' | '+', номер строки в old, номер строки в new, текст)
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push((' ', i, j, old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', i, j, old[i]));
+            i += 1;
+        } else {
+            ops.push(('+', i, j, new[j]));
+            j += 1;
+        }
+    }
+
+    let changed: Vec<usize> = ops.iter().enumerate().filter(|(_, op)| op.0 != ' ').map(|(k, _)| k).collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+    let mut out = format!("--- a/{}\n+++ b/{}\n", path, path);
+    let mut k = 0;
+    while k < changed.len() {
+        // Ханк объединяет изменения, между которыми не больше двух окон контекста
+        let start = changed[k].saturating_sub(DIFF_CONTEXT);
+        let mut last = changed[k];
+        while k + 1 < changed.len() && changed[k + 1] - last <= 2 * DIFF_CONTEXT {
+            k += 1;
+            last = changed[k];
+        }
+        k += 1;
+        let hunk = &ops[start..(last + DIFF_CONTEXT + 1).min(ops.len())];
+        let old_len = hunk.iter().filter(|op| op.0 != '+').count();
+        let new_len = hunk.iter().filter(|op| op.0 != '-').count();
+        let (_, old_start, new_start, _) = hunk[0];
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            if old_len == 0 { old_start } else { old_start + 1 },
+            old_len,
+            if new_len == 0 { new_start } else { new_start + 1 },
+            new_len
+        ));
+        for (tag, _, _, line) in hunk {
+            out.push_str(&format!("{}{}\n", tag, line));
+        }
+    }
+    out
+}
+
+/// Заменяет содержимое дочернего элемента или добавляет его
+fn set_child(root: &mut Element, name: &str, content: XMLNode) {
+    match root.get_mut_child(name) {
+        Some(el) => el.children = vec![content],
+        None => {
+            let mut el = Element::new(name);
+            el.children.push(content);
+            root.children.push(XMLNode::Element(el));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enrich_sets_version_and_change_notes() {
+        let xml = "<idea-plugin><id>ru.example</id><version>0.0.1</version></idea-plugin>";
+        let enriched = enrich(xml, "1.2.0", Some("<ul><li>fix</li></ul>")).unwrap();
+        assert!(enriched.contains("<version>1.2.0</version>"));
+        assert!(enriched.contains("<change-notes><![CDATA[<ul><li>fix</li></ul>]]></change-notes>"));
+        assert!(!enriched.contains("0.0.1"));
+    }
+
+    #[test]
+    fn test_unified_diff_shows_enrichment() {
+        let xml = "<idea-plugin><id>ru.example</id><name>Example</name><vendor>Ride</vendor>\
+                   <description>d</description><depends>com.intellij.modules.platform</depends>\
+                   <version>0.0.1</version></idea-plugin>";
+        let before = normalize(xml).unwrap();
+        let after = enrich(xml, "1.2.0", Some("<b>fix</b>")).unwrap();
+        let diff = unified_diff(&before, &after, "META-INF/plugin.xml");
+        let lines: Vec<&str> = diff.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "--- a/META-INF/plugin.xml",
+                "+++ b/META-INF/plugin.xml",
+                "@@ -5,5 +5,6 @@",
+                "   <vendor>Ride</vendor>",
+                "   <description>d</description>",
+                "   <depends>com.intellij.modules.platform</depends>",
+                "-  <version>0.0.1</version>",
+                "+  <version>1.2.0</version>",
+                "+  <change-notes><![CDATA[<b>fix</b>]]></change-notes>",
+                " </idea-plugin>",
+            ]
+        );
+        assert_eq!(unified_diff(&before, &before, "META-INF/plugin.xml"), "");
+    }
+}
@@ -0,0 +1,505 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{debug, info};
+use zip::write::FileOptions;
+
+use crate::config::parser::PostProcessorConfig;
+use crate::core::checksums;
+use crate::core::integrity::{signature_path, IntegrityManifest};
+use crate::models::plugin::BuildEnvironment;
+
+/// Запись архива, загруженная в память для обработки
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    pub name: String,
+    pub data: Vec<u8>,
+    pub modified: zip::DateTime,
+    pub unix_mode: Option<u32>,
+    pub is_dir: bool,
+}
+
+impl ArchiveEntry {
+    pub fn file(name: impl Into<String>, data: Vec<u8>) -> Self {
+        Self {
+            name: name.into(),
+            data,
+            modified: zip::DateTime::default(),
+            unix_mode: None,
+            is_dir: false,
+        }
+    }
+}
+
+/// Данные сборки, доступные процессорам
+#[derive(Debug, Clone)]
+pub struct ProcessContext {
+    pub project_root: PathBuf,
+    pub plugin_id: String,
+    pub version: String,
+    /// Окружение сборки, если оно снято билдером
+    pub environment: Option<BuildEnvironment>,
+}
+
+/// Пост-обработчик артефакта: получает все записи ZIP и может их менять
+pub trait ArtifactProcessor: Send + Sync {
+    fn name(&self) -> &str;
+    fn process(&self, ctx: &ProcessContext, entries: &mut Vec<ArchiveEntry>) -> Result<()>;
+}
+
+/// Обнуляет время модификации записей (1980-01-01, минимум формата ZIP)
+pub struct StripTimestamps;
+
+impl ArtifactProcessor for StripTimestamps {
+    fn name(&self) -> &str {
+        "strip-timestamps"
+    }
+
+    fn process(&self, _ctx: &ProcessContext, entries: &mut Vec<ArchiveEntry>) -> Result<()> {
+        for entry in entries.iter_mut() {
+            entry.modified = zip::DateTime::default();
+        }
+        Ok(())
+    }
+}
+
+/// Сортирует записи по имени и нормализует права доступа
+pub struct DeterministicOrder;
+
+impl ArtifactProcessor for DeterministicOrder {
+    fn name(&self) -> &str {
+        "deterministic-order"
+    }
+
+    fn process(&self, _ctx: &ProcessContext, entries: &mut Vec<ArchiveEntry>) -> Result<()> {
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        for entry in entries.iter_mut() {
+            entry.unix_mode = Some(if entry.is_dir { 0o755 } else { 0o644 });
+        }
+        Ok(())
+    }
+}
+
+/// Добавляет файлы проекта (LICENSE, THIRD-PARTY и т.п.) в архив
+pub struct InjectFiles {
+    pub files: Vec<PathBuf>,
+    pub target_dir: String,
+}
+
+impl ArtifactProcessor for InjectFiles {
+    fn name(&self) -> &str {
+        "inject-files"
+    }
+
+    fn process(&self, ctx: &ProcessContext, entries: &mut Vec<ArchiveEntry>) -> Result<()> {
+        for file in &self.files {
+            let source = ctx.project_root.join(file);
+            let data = fs::read(&source)
+                .with_context(|| format!("Не удалось прочитать файл для добавления в артефакт: {}", source.display()))?;
+            let file_name = source.file_name().unwrap_or_default().to_string_lossy();
+            let name = join_entry_name(&self.target_dir, &file_name);
+
+            entries.retain(|e| e.name != name);
+            entries.push(ArchiveEntry::file(name, data));
+        }
+        Ok(())
+    }
+}
+
+/// Добавляет build-info.json: id, версия, коммит, время и окружение сборки
+pub struct BuildInfo {
+    pub path: String,
+}
+
+impl ArtifactProcessor for BuildInfo {
+    fn name(&self) -> &str {
+        "build-info"
+    }
+
+    fn process(&self, ctx: &ProcessContext, entries: &mut Vec<ArchiveEntry>) -> Result<()> {
+        let commit = match &ctx.environment {
+            Some(env) => env.git_commit.clone(),
+            None => Command::new("git")
+                .current_dir(&ctx.project_root)
+                .args(["rev-parse", "HEAD"])
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string()),
+        };
+
+        // SOURCE_DATE_EPOCH позволяет получить воспроизводимый build-info
+        let built_at = std::env::var("SOURCE_DATE_EPOCH")
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+            .unwrap_or_else(chrono::Utc::now);
+
+        let mut info = serde_json::json!({
+            "plugin_id": ctx.plugin_id,
+            "version": ctx.version,
+            "git_commit": commit,
+            "built_at": built_at.to_rfc3339(),
+            "tool": format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+        });
+        if let Some(env) = &ctx.environment {
+            info["git_dirty"] = serde_json::json!(env.git_dirty);
+            info["environment"] = serde_json::json!({
+                "os": env.os,
+                "arch": env.arch,
+                "jdk": env.jdk,
+                "build_tool": env.build_tool,
+                "target_platform": env.target_platform,
+            });
+        }
+
+        entries.retain(|e| e.name != self.path);
+        entries.push(ArchiveEntry::file(self.path.clone(), serde_json::to_vec_pretty(&info)?));
+        Ok(())
+    }
+}
+
+/// Внешняя команда над распакованным артефактом (cwd — временная директория с содержимым)
+pub struct ExternalCommand {
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+impl ArtifactProcessor for ExternalCommand {
+    fn name(&self) -> &str {
+        &self.command
+    }
+
+    fn process(&self, ctx: &ProcessContext, entries: &mut Vec<ArchiveEntry>) -> Result<()> {
+        let workdir = tempfile::tempdir().context("Не удалось создать временную директорию")?;
+        for entry in entries.iter() {
+            let path = workdir.path().join(&entry.name);
+            if entry.is_dir {
+                fs::create_dir_all(&path)?;
+            } else {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&path, &entry.data)?;
+            }
+        }
+
+        let status = Command::new(&self.command)
+            .args(&self.args)
+            .current_dir(workdir.path())
+            .env("PLUGIN_ID", &ctx.plugin_id)
+            .env("PLUGIN_VERSION", &ctx.version)
+            .env("PROJECT_ROOT", &ctx.project_root)
+            .status()
+            .with_context(|| format!("Не удалось запустить пост-обработчик '{}'", self.command))?;
+        if !status.success() {
+            anyhow::bail!("Пост-обработчик '{}' завершился с кодом {}", self.command, status);
+        }
+
+        let previous: Vec<ArchiveEntry> = std::mem::take(entries);
+        for item in walkdir::WalkDir::new(workdir.path()).min_depth(1).sort_by_file_name() {
+            let item = item?;
+            let relative = item.path().strip_prefix(workdir.path())?;
+            let mut name = relative.components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let is_dir = item.file_type().is_dir();
+            if is_dir {
+                name.push('/');
+            }
+            // Метаданные сохраняются для записей, которые команда не трогала
+            let original = previous.iter().find(|e| e.name == name);
+            entries.push(ArchiveEntry {
+                data: if is_dir { Vec::new() } else { fs::read(item.path())? },
+                modified: original.map(|e| e.modified).unwrap_or_default(),
+                unix_mode: original.and_then(|e| e.unix_mode),
+                is_dir,
+                name,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Добавляет манифест целостности с SHA256 всех записей и, если задана `sign_command`,
+/// его отделенную подпись. Должен идти последним: следующие шаги изменили бы записи
+pub struct Integrity {
+    pub path: String,
+    pub sign_command: Option<String>,
+}
+
+impl ArtifactProcessor for Integrity {
+    fn name(&self) -> &str {
+        "integrity"
+    }
+
+    fn process(&self, ctx: &ProcessContext, entries: &mut Vec<ArchiveEntry>) -> Result<()> {
+        let signature = signature_path(&self.path);
+        entries.retain(|e| e.name != self.path && e.name != signature);
+
+        let manifest = IntegrityManifest::build(
+            &ctx.plugin_id,
+            &ctx.version,
+            &self.path,
+            entries.iter().filter(|e| !e.is_dir).map(|e| (e.name.as_str(), e.data.as_slice())),
+        );
+        let bytes = manifest.to_bytes()?;
+        let signed = match &self.sign_command {
+            Some(command) => {
+                let file_name = self.path.rsplit('/').next().unwrap_or(&self.path);
+                Some(checksums::sign_detached(&bytes, file_name, command)?)
+            }
+            None => None,
+        };
+
+        entries.push(ArchiveEntry::file(self.path.clone(), bytes));
+        if let Some(data) = signed {
+            entries.push(ArchiveEntry::file(signature, data));
+        }
+        Ok(())
+    }
+}
+
+fn join_entry_name(dir: &str, file_name: &str) -> String {
+    let dir = dir.trim_matches('/');
+    if dir.is_empty() {
+        file_name.to_string()
+    } else {
+        format!("{}/{}", dir, file_name)
+    }
+}
+
+/// Цепочка пост-обработчиков, выполняемых после сборки
+#[derive(Default)]
+pub struct PostProcessorChain {
+    processors: Vec<Box<dyn ArtifactProcessor>>,
+}
+
+impl PostProcessorChain {
+    /// Собирает цепочку из секции `[[build.post_process]]`
+    pub fn from_config(configs: &[PostProcessorConfig]) -> Self {
+        let mut chain = Self::default();
+        for config in configs {
+            chain = match config {
+                PostProcessorConfig::StripTimestamps => chain.with(StripTimestamps),
+                PostProcessorConfig::DeterministicOrder => chain.with(DeterministicOrder),
+                PostProcessorConfig::InjectFiles { files, target_dir } => chain.with(InjectFiles {
+                    files: files.iter().map(PathBuf::from).collect(),
+                    target_dir: target_dir.clone(),
+                }),
+                PostProcessorConfig::BuildInfo { path } => chain.with(BuildInfo { path: path.clone() }),
+                PostProcessorConfig::Command { command, args } => chain.with(ExternalCommand {
+                    command: command.clone(),
+                    args: args.clone(),
+                }),
+                PostProcessorConfig::Integrity { path, sign_command } => chain.with(Integrity {
+                    path: path.clone(),
+                    sign_command: sign_command.clone(),
+                }),
+            };
+        }
+        chain
+    }
+
+    /// Добавляет процессор в конец цепочки
+    pub fn with(mut self, processor: impl ArtifactProcessor + 'static) -> Self {
+        self.processors.push(Box::new(processor));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.processors.is_empty()
+    }
+
+    /// Применяет цепочку к ZIP и атомарно перезаписывает его; возвращает имена процессоров
+    pub fn apply(&self, zip_path: &Path, ctx: &ProcessContext) -> Result<Vec<String>> {
+        if self.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = read_entries(zip_path)?;
+        let mut applied = Vec::new();
+        for processor in &self.processors {
+            debug!("Пост-обработка '{}'", processor.name());
+            processor
+                .process(ctx, &mut entries)
+                .with_context(|| format!("Пост-обработчик '{}' завершился с ошибкой", processor.name()))?;
+            applied.push(processor.name().to_string());
+        }
+
+        let tmp_path = zip_path.with_extension("zip.tmp");
+        write_entries(&tmp_path, &entries)?;
+        fs::rename(&tmp_path, zip_path)
+            .with_context(|| format!("Не удалось заменить артефакт {}", zip_path.display()))?;
+
+        info!("🧰 Пост-обработка артефакта: {}", applied.join(", "));
+        Ok(applied)
+    }
+}
+
+fn read_entries(zip_path: &Path) -> Result<Vec<ArchiveEntry>> {
+    let file = fs::File::open(zip_path)
+        .with_context(|| format!("Не удалось открыть артефакт: {}", zip_path.display()))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .with_context(|| format!("Не удалось прочитать ZIP {}", zip_path.display()))?;
+
+    let mut entries = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut data)?;
+        entries.push(ArchiveEntry {
+            name: entry.name().to_string(),
+            data,
+            modified: entry.last_modified(),
+            unix_mode: entry.unix_mode(),
+            is_dir: entry.is_dir(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Пишет архив с фиксированным методом сжатия, чтобы результат зависел только от записей
+fn write_entries(path: &Path, entries: &[ArchiveEntry]) -> Result<()> {
+    let file = fs::File::create(path)
+        .with_context(|| format!("Не удалось создать {}", path.display()))?;
+    let mut writer = zip::ZipWriter::new(file);
+
+    for entry in entries {
+        let mut options = FileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .last_modified_time(entry.modified);
+        if let Some(mode) = entry.unix_mode {
+            options = options.unix_permissions(mode);
+        }
+
+        if entry.is_dir {
+            writer.add_directory(entry.name.trim_end_matches('/'), options)?;
+        } else {
+            writer.start_file(entry.name.as_str(), options)?;
+            writer.write_all(&entry.data)?;
+        }
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(root: &Path) -> ProcessContext {
+        ProcessContext {
+            project_root: root.to_path_buf(),
+            plugin_id: "ru.marslab.ide.ride".to_string(),
+            version: "1.2.0".to_string(),
+            environment: None,
+        }
+    }
+
+    fn write_zip(path: &Path, names: &[&str], modified: zip::DateTime) {
+        let mut writer = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        for name in names {
+            writer.start_file(*name, FileOptions::default().last_modified_time(modified)).unwrap();
+            writer.write_all(name.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_reproducible_chain_gives_identical_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("a.zip");
+        let second = dir.path().join("b.zip");
+        write_zip(&first, &["b.txt", "a.txt"], zip::DateTime::from_date_and_time(2024, 5, 1, 10, 0, 0).unwrap());
+        write_zip(&second, &["a.txt", "b.txt"], zip::DateTime::from_date_and_time(2025, 1, 2, 3, 4, 6).unwrap());
+
+        let chain = PostProcessorChain::from_config(&[
+            PostProcessorConfig::StripTimestamps,
+            PostProcessorConfig::DeterministicOrder,
+        ]);
+        chain.apply(&first, &context(dir.path())).unwrap();
+        chain.apply(&second, &context(dir.path())).unwrap();
+
+        assert_eq!(fs::read(&first).unwrap(), fs::read(&second).unwrap());
+    }
+
+    #[test]
+    fn test_inject_files_and_build_info() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("LICENSE"), "MIT").unwrap();
+        let zip_path = dir.path().join("ride.zip");
+        write_zip(&zip_path, &["ride/lib/ride.jar"], zip::DateTime::default());
+
+        let chain = PostProcessorChain::from_config(&[
+            PostProcessorConfig::InjectFiles { files: vec!["LICENSE".to_string()], target_dir: "ride/".to_string() },
+            PostProcessorConfig::BuildInfo { path: "ride/build-info.json".to_string() },
+        ]);
+        let applied = chain.apply(&zip_path, &context(dir.path())).unwrap();
+        assert_eq!(applied, ["inject-files", "build-info"]);
+
+        let entries = read_entries(&zip_path).unwrap();
+        let license = entries.iter().find(|e| e.name == "ride/LICENSE").unwrap();
+        assert_eq!(license.data, b"MIT");
+        let info = entries.iter().find(|e| e.name == "ride/build-info.json").unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&info.data).unwrap();
+        assert_eq!(json["version"], "1.2.0");
+        assert!(json.get("environment").is_none());
+
+        // Окружение, снятое билдером, попадает в build-info вместе с коммитом и dirty-state
+        let ctx = ProcessContext {
+            environment: Some(BuildEnvironment {
+                os: "linux".to_string(),
+                arch: "x86_64".to_string(),
+                jdk: Some("17.0.9".to_string()),
+                build_tool: Some("Gradle 8.7".to_string()),
+                target_platform: Some("IC 2024.2.5".to_string()),
+                git_commit: Some("abc123".to_string()),
+                git_dirty: Some(true),
+                tool: "deploy-pugin 0.1.0".to_string(),
+            }),
+            ..context(dir.path())
+        };
+        PostProcessorChain::from_config(&[PostProcessorConfig::BuildInfo { path: "build-info.json".to_string() }])
+            .apply(&zip_path, &ctx)
+            .unwrap();
+        let entries = read_entries(&zip_path).unwrap();
+        let info = entries.iter().find(|e| e.name == "build-info.json").unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&info.data).unwrap();
+        assert_eq!(json["git_commit"], "abc123");
+        assert_eq!(json["git_dirty"], true);
+        assert_eq!(json["environment"]["jdk"], "17.0.9");
+        assert_eq!(json["environment"]["build_tool"], "Gradle 8.7");
+        assert_eq!(json["environment"]["target_platform"], "IC 2024.2.5");
+    }
+
+    #[test]
+    fn test_integrity_manifest_is_embedded_and_signed() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("ride.zip");
+        write_zip(&zip_path, &["ride/lib/ride.jar", "ride/lib/util.jar"], zip::DateTime::default());
+
+        PostProcessorChain::from_config(&[PostProcessorConfig::Integrity {
+            path: "integrity.json".to_string(),
+            sign_command: Some("cp {input} {output}".to_string()),
+        }])
+        .apply(&zip_path, &context(dir.path()))
+        .unwrap();
+        let report = crate::core::integrity::verify_artifact(&zip_path, "integrity.json", Some("cmp {input} {signature}")).unwrap();
+        assert!(report.problems.is_empty());
+        assert_eq!(report.entries, 2);
+        assert_eq!(report.signature, crate::core::integrity::SignatureStatus::Verified);
+
+        // Подмена JAR после сборки
+        let mut entries = read_entries(&zip_path).unwrap();
+        entries.iter_mut().find(|e| e.name == "ride/lib/util.jar").unwrap().data = b"patched".to_vec();
+        write_entries(&zip_path, &entries).unwrap();
+        let report = crate::core::integrity::verify_artifact(&zip_path, "integrity.json", None).unwrap();
+        assert_eq!(report.problems.len(), 1);
+        assert!(report.problems[0].starts_with("ride/lib/util.jar: sha256"));
+        assert_eq!(report.signature, crate::core::integrity::SignatureStatus::NotChecked);
+    }
+}
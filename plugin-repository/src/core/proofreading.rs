@@ -0,0 +1,278 @@
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::config::parser::{ProofreadingConfig, ProofreadingMode};
+
+/// Частые опечатки английского текста; дополняется `[proofreading] spelling`
+const BUILTIN_SPELLING: &[(&str, &str)] = &[
+    ("accomodate", "accommodate"),
+    ("acheive", "achieve"),
+    ("adress", "address"),
+    ("alot", "a lot"),
+    ("begining", "beginning"),
+    ("beleive", "believe"),
+    ("compatability", "compatibility"),
+    ("compatable", "compatible"),
+    ("completly", "completely"),
+    ("definately", "definitely"),
+    ("dependancy", "dependency"),
+    ("dependancies", "dependencies"),
+    ("enviroment", "environment"),
+    ("explicitely", "explicitly"),
+    ("funtion", "function"),
+    ("funtionality", "functionality"),
+    ("improvment", "improvement"),
+    ("improvments", "improvements"),
+    ("independant", "independent"),
+    ("occured", "occurred"),
+    ("occurence", "occurrence"),
+    ("paramter", "parameter"),
+    ("perfomance", "performance"),
+    ("performace", "performance"),
+    ("persistant", "persistent"),
+    ("recieve", "receive"),
+    ("recieved", "received"),
+    ("reponse", "response"),
+    ("seperate", "separate"),
+    ("seperator", "separator"),
+    ("succesful", "successful"),
+    ("successfull", "successful"),
+    ("sucess", "success"),
+    ("supress", "suppress"),
+    ("teh", "the"),
+    ("threshhold", "threshold"),
+    ("unneccessary", "unnecessary"),
+    ("untill", "until"),
+    ("wich", "which"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IssueKind {
+    /// Термин написан не в каноническом регистре
+    Term,
+    Spelling,
+    /// Слово повторено подряд: «the the»
+    RepeatedWord,
+}
+
+/// Замечание вычитки
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProofreadIssue {
+    pub kind: IssueKind,
+    pub found: String,
+    pub expected: String,
+}
+
+impl fmt::Display for ProofreadIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            IssueKind::Term => write!(f, "термин «{}» → «{}»", self.found, self.expected),
+            IssueKind::Spelling => write!(f, "опечатка «{}» → «{}»", self.found, self.expected),
+            IssueKind::RepeatedWord => write!(f, "повтор слова «{}»", self.found),
+        }
+    }
+}
+
+/// Результат вычитки: текст (исправленный в режиме `fix`) и замечания
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proofread {
+    pub text: String,
+    pub issues: Vec<ProofreadIssue>,
+    pub fixed: bool,
+}
+
+/// Вычитка сгенерированного Markdown: терминология, словарь опечаток, повторы слов.
+/// Код (`` `...` ``, блоки ```), ссылки и HTML-теги не трогаются
+pub struct Proofreader {
+    terms: Vec<(String, Regex)>,
+    spelling: HashMap<String, String>,
+    mode: ProofreadingMode,
+}
+
+impl Proofreader {
+    pub fn new(config: &ProofreadingConfig) -> Self {
+        let terms = config
+            .terms
+            .iter()
+            .map(|term| term.trim())
+            .filter(|term| !term.is_empty())
+            .map(|term| (term.to_string(), term_regex(term)))
+            .collect();
+
+        let mut spelling: HashMap<String, String> = HashMap::new();
+        if config.builtin_spelling {
+            spelling.extend(BUILTIN_SPELLING.iter().map(|(w, r)| (w.to_string(), r.to_string())));
+        }
+        spelling.extend(config.spelling.iter().map(|(w, r)| (w.trim().to_lowercase(), r.trim().to_string())));
+
+        Self { terms, spelling, mode: config.mode }
+    }
+
+    pub fn check(&self, text: &str) -> Proofread {
+        let protected = Regex::new(r"(?s)```.*?```|`[^`\n]*`|\]\([^)\s]*\)|https?://[^\s)>]+|<[^>\n]+>").unwrap();
+        let mut issues = Vec::new();
+        let mut fixed = String::with_capacity(text.len());
+        let mut last = 0;
+        for m in protected.find_iter(text) {
+            fixed.push_str(&self.check_plain(&text[last..m.start()], &mut issues));
+            fixed.push_str(m.as_str());
+            last = m.end();
+        }
+        fixed.push_str(&self.check_plain(&text[last..], &mut issues));
+
+        match self.mode {
+            ProofreadingMode::Fix => Proofread { text: fixed, issues, fixed: true },
+            ProofreadingMode::Report => Proofread { text: text.to_string(), issues, fixed: false },
+        }
+    }
+
+    /// Исправленный фрагмент без кода и ссылок; замечания добавляются без дублей
+    fn check_plain(&self, text: &str, issues: &mut Vec<ProofreadIssue>) -> String {
+        let mut note = |kind: IssueKind, found: &str, expected: &str| {
+            let issue = ProofreadIssue { kind, found: found.to_string(), expected: expected.to_string() };
+            if !issues.contains(&issue) {
+                issues.push(issue);
+            }
+        };
+
+        let mut current = text.to_string();
+        for (term, re) in &self.terms {
+            let replaced = re
+                .replace_all(&current, |caps: &Captures| {
+                    let m = caps.get(0).unwrap();
+                    if m.as_str() == term || is_identifier(&current, m.start(), m.end()) {
+                        return m.as_str().to_string();
+                    }
+                    note(IssueKind::Term, m.as_str(), term);
+                    term.clone()
+                })
+                .to_string();
+            current = replaced;
+        }
+
+        let word = word_regex();
+        let current = word
+            .replace_all(&current, |caps: &Captures| {
+                let m = caps.get(0).unwrap();
+                match self.spelling.get(&m.as_str().to_lowercase()) {
+                    Some(correct) if !is_identifier(&current, m.start(), m.end()) => {
+                        let correct = match_case(m.as_str(), correct);
+                        note(IssueKind::Spelling, m.as_str(), &correct);
+                        correct
+                    }
+                    _ => m.as_str().to_string(),
+                }
+            })
+            .to_string();
+
+        // Повтор: два одинаковых слова, разделенных только пробелами в пределах строки
+        let mut result = String::with_capacity(current.len());
+        let mut last = 0;
+        let mut previous: Option<(usize, &str)> = None;
+        for m in word.find_iter(&current) {
+            if let Some((end, prev)) = previous {
+                let gap = &current[end..m.start()];
+                if !gap.is_empty() && gap.chars().all(|c| c == ' ' || c == '\t') && prev.to_lowercase() == m.as_str().to_lowercase() {
+                    note(IssueKind::RepeatedWord, &format!("{} {}", prev, m.as_str()), prev);
+                    result.push_str(&current[last..end]);
+                    last = m.end();
+                    previous = Some((m.end(), prev));
+                    continue;
+                }
+            }
+            previous = Some((m.end(), m.as_str()));
+        }
+        result.push_str(&current[last..]);
+        result
+    }
+}
+
+/// Термин целым словом без учета регистра; пробелы внутри термина — любые пробельные символы
+fn term_regex(term: &str) -> Regex {
+    let body = term.split_whitespace().map(regex::escape).collect::<Vec<_>>().join(r"\s+");
+    let start = if term.starts_with(|c: char| c.is_alphanumeric()) { r"\b" } else { "" };
+    let end = if term.ends_with(|c: char| c.is_alphanumeric()) { r"\b" } else { "" };
+    Regex::new(&format!("(?i){}{}{}", start, body, end)).unwrap()
+}
+
+fn word_regex() -> Regex {
+    Regex::new(r"\p{L}+").unwrap()
+}
+
+/// Слово — часть идентификатора или пути (`github-actions`, `kotlin.collections`, `@teh`): не правим
+fn is_identifier(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let mut after = text[end..].chars();
+    let next = after.next();
+    matches!(before, Some('_' | '/' | '\\' | '@' | '.' | '-' | '#'))
+        || matches!(next, Some('_' | '/' | '\\' | '@' | '-'))
+        || (next == Some('.') && after.next().is_some_and(|c| c.is_alphanumeric()))
+}
+
+/// Исправление в регистре исходного слова: `Recieve` -> `Receive`, `TEH` -> `THE`
+fn match_case(original: &str, correct: &str) -> String {
+    if original.chars().count() > 1 && original.chars().all(|c| !c.is_lowercase()) {
+        return correct.to_uppercase();
+    }
+    let mut chars = correct.chars();
+    match (original.chars().next(), chars.next()) {
+        (Some(first), Some(c)) if first.is_uppercase() => c.to_uppercase().chain(chars).collect(),
+        _ => correct.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proofreader(mode: ProofreadingMode) -> Proofreader {
+        Proofreader::new(&ProofreadingConfig {
+            enabled: true,
+            mode,
+            terms: vec!["IntelliJ IDEA".to_string(), "GitHub".to_string()],
+            spelling: HashMap::from([("рефакторнг".to_string(), "рефакторинг".to_string())]),
+            ..ProofreadingConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_proofread_fix_and_report() {
+        let text = "## Highlights\n\
+                    - Support for intellij  idea 2024.1 and Github login\n\
+                    - Recieve updates faster, the the cache is smaller\n\
+                    - Рефакторнг чата, see `github` and https://github.com/x and [github](https://github.com)\n\
+                    - Uses github-actions\n";
+
+        let fixed = proofreader(ProofreadingMode::Fix).check(text);
+        assert!(fixed.fixed);
+        assert_eq!(
+            fixed.text,
+            "## Highlights\n\
+             - Support for IntelliJ IDEA 2024.1 and GitHub login\n\
+             - Receive updates faster, the cache is smaller\n\
+             - Рефакторинг чата, see `github` and https://github.com/x and [GitHub](https://github.com)\n\
+             - Uses github-actions\n"
+        );
+        let issues: Vec<String> = fixed.issues.iter().map(|i| i.to_string()).collect();
+        assert_eq!(
+            issues,
+            [
+                "термин «intellij  idea» → «IntelliJ IDEA»",
+                "термин «Github» → «GitHub»",
+                "опечатка «Recieve» → «Receive»",
+                "опечатка «Рефакторнг» → «Рефакторинг»",
+                "повтор слова «the the»",
+                "термин «github» → «GitHub»",
+            ]
+        );
+
+        let report = proofreader(ProofreadingMode::Report).check(text);
+        assert!(!report.fixed);
+        assert_eq!(report.text, text);
+        assert_eq!(report.issues.len(), fixed.issues.len());
+
+        assert!(proofreader(ProofreadingMode::Fix).check("All good in IntelliJ IDEA").issues.is_empty());
+        assert_eq!(match_case("TEH", "the"), "THE");
+    }
+}
@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use colored::*;
+
+use crate::core::llm::agents::{ReadinessLevel, ReadinessReport};
+
+/// Формат вывода отчета о готовности
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReadinessFormat {
+    /// Цветной отчет для терминала
+    Console,
+    Json,
+    /// Команды workflow GitHub Actions (`::warning::`, `::error::`)
+    Github,
+}
+
+impl ReadinessFormat {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "table" | "console" => Ok(ReadinessFormat::Console),
+            "json" => Ok(ReadinessFormat::Json),
+            "github" => Ok(ReadinessFormat::Github),
+            other => Err(anyhow::anyhow!(
+                "Неизвестный формат отчета '{}': ожидается table, json или github",
+                other
+            )),
+        }
+    }
+}
+
+/// Отчет в выбранном формате
+pub fn render(report: &ReadinessReport, format: ReadinessFormat) -> Result<String> {
+    match format {
+        ReadinessFormat::Console => Ok(render_console(report)),
+        ReadinessFormat::Json => serde_json::to_string_pretty(report)
+            .context("Не удалось сериализовать отчет о готовности"),
+        ReadinessFormat::Github => Ok(render_github(report)),
+    }
+}
+
+fn yes_no(value: bool) -> ColoredString {
+    if value { "да".green() } else { "нет".yellow() }
+}
+
+/// Отчет для терминала
+pub fn render_console(report: &ReadinessReport) -> String {
+    let mut out = format!("{} Готовность релиза {}\n", "🔍".cyan(), report.version.bold());
+    out.push_str(&format!(
+        "  {} {} ({:.0}%)\n",
+        report.readiness_level.emoji(),
+        report.readiness_level.name().bold(),
+        report.readiness_score * 100.0
+    ));
+    out.push_str(&format!("  Сложность изменений: {:.0}%\n", report.complexity_score * 100.0));
+    out.push_str(&format!(
+        "  Breaking changes: {}\n",
+        if report.has_breaking_changes { "да".red() } else { "нет".green() }
+    ));
+    out.push_str(&format!("  Тесты: {}, документация: {}\n", yes_no(report.has_tests), yes_no(report.has_docs)));
+    out.push_str(&format!("  {}\n", report.analysis_summary.bright_black()));
+
+    if !report.recommendations.is_empty() {
+        out.push_str(&format!("\n{} Рекомендации:\n", "💡".yellow()));
+        for recommendation in &report.recommendations {
+            out.push_str(&format!("  • {}\n", recommendation));
+        }
+    }
+    out
+}
+
+/// Экранирование данных workflow-команды
+fn escape_data(value: &str) -> String {
+    value.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Экранирование значения свойства workflow-команды
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+fn annotation(level: &str, title: &str, message: &str) -> String {
+    format!("::{} title={}::{}\n", level, escape_property(title), escape_data(message))
+}
+
+/// Аннотации GitHub Actions: итог — error для «не готов», warning для «требует внимания»,
+/// notice иначе; каждая рекомендация — отдельный warning
+pub fn render_github(report: &ReadinessReport) -> String {
+    let title = format!("Готовность релиза {}", report.version);
+    let level = match report.readiness_level {
+        ReadinessLevel::NotReady => "error",
+        ReadinessLevel::NeedsAttention => "warning",
+        ReadinessLevel::Ready | ReadinessLevel::ReadyWithConcerns => "notice",
+    };
+
+    let mut out = annotation(
+        level,
+        &title,
+        &format!(
+            "{} ({:.0}%). {}",
+            report.readiness_level.name(),
+            report.readiness_score * 100.0,
+            report.analysis_summary
+        ),
+    );
+    if report.has_breaking_changes {
+        out.push_str(&annotation("warning", &title, "Релиз содержит breaking changes"));
+    }
+    for recommendation in &report.recommendations {
+        out.push_str(&annotation("warning", &title, recommendation));
+    }
+    out
+}
+
+/// Проходит ли релиз гейт: «не готов» всегда блокирует, в strict-режиме — и «требует внимания»
+pub fn passes_gate(report: &ReadinessReport, strict: bool) -> bool {
+    match report.readiness_level {
+        ReadinessLevel::NotReady => false,
+        ReadinessLevel::NeedsAttention => !strict,
+        ReadinessLevel::Ready | ReadinessLevel::ReadyWithConcerns => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_github_annotations() {
+        let report = ReadinessReport {
+            version: "1.2.0".to_string(),
+            readiness_level: ReadinessLevel::NeedsAttention,
+            readiness_score: 0.55,
+            has_breaking_changes: true,
+            complexity_score: 0.4,
+            has_tests: false,
+            has_docs: true,
+            recommendations: vec!["Добавьте тесты: покрытие 50%\nи выше".to_string()],
+            analysis_summary: "Анализ 12 коммитов".to_string(),
+        };
+
+        let output = render(&report, ReadinessFormat::Github).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines[0], "::warning title=Готовность релиза 1.2.0::Требует внимания (55%25). Анализ 12 коммитов");
+        assert_eq!(lines[1], "::warning title=Готовность релиза 1.2.0::Релиз содержит breaking changes");
+        assert_eq!(lines[2], "::warning title=Готовность релиза 1.2.0::Добавьте тесты: покрытие 50%25%0Aи выше");
+        assert!(passes_gate(&report, false));
+        assert!(!passes_gate(&report, true));
+
+        let json: serde_json::Value = serde_json::from_str(&render(&report, ReadinessFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["readiness_level"], "NeedsAttention");
+        assert!(ReadinessFormat::parse("xml").is_err());
+    }
+}
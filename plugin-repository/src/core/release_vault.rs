@@ -0,0 +1,211 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::llm::transcript::release_dir;
+use crate::utils::clock::{system_clock, SharedClock};
+
+/// Индекс хранилища: `.deploy-plugin/releases/index.json`
+pub const INDEX_FILE: &str = "index.json";
+pub const CHANGELOG_FILE: &str = "changelog.md";
+pub const RELEASE_NOTES_FILE: &str = "release-notes.md";
+pub const CHANGE_NOTES_FILE: &str = "change-notes.html";
+pub const READINESS_FILE: &str = "readiness.json";
+pub const BUILD_FILE: &str = "build.json";
+pub const DEPLOY_REPORT_FILE: &str = "deploy-report.json";
+/// Перенесенные коммиты patch-релиза (`release backport`)
+pub const BACKPORT_FILE: &str = "backport.json";
+/// Суммы артефактов в формате `sha256sum`
+pub const CHECKSUMS_FILE: &str = "checksums.sha256";
+
+/// Файл релиза в хранилище
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultFile {
+    pub name: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Запись индекса: все, что сохранено для версии
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultEntry {
+    pub version: String,
+    pub updated_at: DateTime<Utc>,
+    pub files: Vec<VaultFile>,
+}
+
+/// Локальное хранилище материалов релизов: `.deploy-plugin/releases/<version>/` и общий индекс
+pub struct ReleaseVault {
+    project_root: PathBuf,
+    clock: SharedClock,
+}
+
+impl ReleaseVault {
+    pub fn new(project_root: &Path) -> Self {
+        Self { project_root: project_root.to_path_buf(), clock: system_clock() }
+    }
+
+    /// Часы для `updated_at` в индексе
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn dir(&self, version: &str) -> PathBuf {
+        release_dir(&self.project_root, version)
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.project_root.join(".deploy-plugin").join("releases").join(INDEX_FILE)
+    }
+
+    /// Сохраняет файлы релиза и обновляет индекс
+    pub fn store<N: AsRef<str>>(&self, version: &str, files: &[(N, Vec<u8>)]) -> Result<VaultEntry> {
+        validate_version(version)?;
+        let dir = self.dir(version);
+        fs::create_dir_all(&dir).with_context(|| format!("Не удалось создать директорию {}", dir.display()))?;
+        for (name, content) in files {
+            let path = dir.join(name.as_ref());
+            fs::write(&path, content).with_context(|| format!("Не удалось записать {}", path.display()))?;
+        }
+        self.reindex(version)
+    }
+
+    /// Сохраняет значение как JSON
+    pub fn store_json<T: Serialize>(&self, version: &str, name: &str, value: &T) -> Result<VaultEntry> {
+        let content = serde_json::to_vec_pretty(value).with_context(|| format!("Не удалось сериализовать {}", name))?;
+        self.store(version, &[(name, content)])
+    }
+
+    /// Пересчитывает запись версии по файлам на диске (в том числе записанным в обход хранилища, как стенограмма)
+    pub fn reindex(&self, version: &str) -> Result<VaultEntry> {
+        validate_version(version)?;
+        let dir = self.dir(version);
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&dir).with_context(|| format!("Не удалось прочитать {}", dir.display()))? {
+            let path = entry?.path();
+            if !path.is_file() {
+                continue;
+            }
+            let content = fs::read(&path).with_context(|| format!("Не удалось прочитать {}", path.display()))?;
+            files.push(VaultFile {
+                name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                size: content.len() as u64,
+                sha256: format!("{:x}", Sha256::digest(&content)),
+            });
+        }
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut index = self.index()?;
+        // Время обновления меняется только вместе с содержимым
+        let updated_at = index
+            .iter()
+            .find(|e| e.version == version && e.files == files)
+            .map(|e| e.updated_at)
+            .unwrap_or_else(|| self.clock.now());
+        let entry = VaultEntry { version: version.to_string(), updated_at, files };
+        index.retain(|e| e.version != version);
+        index.push(entry.clone());
+        index.sort_by(|a, b| compare_versions(&b.version, &a.version));
+        let path = self.index_path();
+        fs::write(&path, serde_json::to_vec_pretty(&index)?)
+            .with_context(|| format!("Не удалось записать индекс {}", path.display()))?;
+        Ok(entry)
+    }
+
+    /// Индекс: версии от новых к старым
+    pub fn index(&self) -> Result<Vec<VaultEntry>> {
+        let path = self.index_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path).with_context(|| format!("Не удалось прочитать {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Поврежден индекс {}", path.display()))
+    }
+
+    /// Запись версии; None — для версии ничего не сохранено
+    pub fn show(&self, version: &str) -> Result<Option<VaultEntry>> {
+        validate_version(version)?;
+        if !self.dir(version).is_dir() {
+            return Ok(None);
+        }
+        self.reindex(version).map(Some)
+    }
+
+    /// Содержимое файла релиза
+    pub fn read(&self, version: &str, name: &str) -> Result<String> {
+        validate_version(version)?;
+        if name.contains(['/', '\\']) || name.starts_with('.') {
+            anyhow::bail!("Некорректное имя файла '{}'", name);
+        }
+        let path = self.dir(version).join(name);
+        fs::read_to_string(&path).with_context(|| format!("Файл {} не найден для v{}", name, version))
+    }
+}
+
+/// Строка `sha256sum`: `<sha256>  <имя файла>`
+pub fn checksum_line(sha256: &str, file_name: &str) -> String {
+    format!("{}  {}\n", sha256, file_name)
+}
+
+/// Версия становится именем директории: без разделителей пути и `..`
+fn validate_version(version: &str) -> Result<()> {
+    if version.is_empty() || version.contains(['/', '\\']) || version.starts_with('.') {
+        anyhow::bail!("Некорректная версия для хранилища релизов: '{}'", version);
+    }
+    Ok(())
+}
+
+/// Сравнение по semver, для нестандартных версий — как строк
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (semver::Version::parse(a), semver::Version::parse(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_index_and_show() {
+        let dir = tempfile::tempdir().unwrap();
+        let frozen = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let vault = ReleaseVault::new(dir.path())
+            .with_clock(std::sync::Arc::new(crate::utils::clock::FixedClock::new(frozen)));
+
+        let first = vault.store("1.9.0", &[(CHANGELOG_FILE, b"## 1.9.0".to_vec())]).unwrap();
+        assert_eq!(first.updated_at, frozen);
+        let entry = vault
+            .store(
+                "1.10.0",
+                &[
+                    (CHANGELOG_FILE, b"## 1.10.0".to_vec()),
+                    (CHECKSUMS_FILE, checksum_line("abc", "ride-1.10.0.zip").into_bytes()),
+                ],
+            )
+            .unwrap();
+        assert_eq!(entry.files.len(), 2);
+        assert_eq!(entry.files[0].name, CHANGELOG_FILE);
+        assert_eq!(entry.files[0].sha256, format!("{:x}", Sha256::digest(b"## 1.10.0")));
+
+        let versions: Vec<String> = vault.index().unwrap().into_iter().map(|e| e.version).collect();
+        assert_eq!(versions, ["1.10.0", "1.9.0"]);
+
+        // Файл, записанный в обход хранилища (стенограмма), попадает в индекс при просмотре
+        fs::write(vault.dir("1.9.0").join("transcript.json"), "{}").unwrap();
+        let shown = vault.show("1.9.0").unwrap().unwrap();
+        assert_eq!(shown.files.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(), [CHANGELOG_FILE, "transcript.json"]);
+        assert_eq!(vault.show("1.9.0").unwrap().unwrap().updated_at, shown.updated_at);
+        assert_eq!(vault.read("1.10.0", CHECKSUMS_FILE).unwrap(), "abc  ride-1.10.0.zip\n");
+
+        assert!(vault.show("2.0.0").unwrap().is_none());
+        assert!(vault.store::<&str>("../escape", &[]).is_err());
+        assert!(vault.read("1.9.0", "../index.json").is_err());
+    }
+}
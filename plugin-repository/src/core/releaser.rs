@@ -1,20 +1,31 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::process::Command;
 use tracing::{info, debug, warn, error};
 use chrono::{DateTime, Utc};
 use semver::Version;
+use std::collections::BTreeMap;
 
-use crate::git::GitRepository;
+use crate::git::{GitCommand, GitRepository, GitTag, ReleaseBranches, ReleaseLine, VersionBump};
 use crate::core::llm::agents::{LLMAgentManager, PluginInfo};
 use crate::models::release::ReleaseInfo;
-use crate::config::parser::ProjectConfig;
+use crate::config::parser::{GitConfig, ProjectConfig, ProofreadingConfig};
+use crate::core::chaos::{self, ChaosPoint};
+use crate::core::interrupt;
+use crate::core::proofreading::Proofreader;
+use crate::utils::clock::SharedClock;
+
+/// Push тега идет по сети: даем больше времени, чем локальным git-командам
+const PUSH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
 
 /// Менеджер релизов для автоматического управления версиями и публикацией
 pub struct ReleaseManager {
     git_repo: GitRepository,
     agent_manager: LLMAgentManager,
     project_config: ProjectConfig,
+    proofreader: Option<Proofreader>,
+    release_branches: Option<ReleaseBranches>,
+    tag_prefix: String,
+    clock: SharedClock,
 }
 
 /// Информация о планируемом релизе
@@ -86,7 +97,7 @@ pub struct ReleasePreparationResult {
 }
 
 impl ReleaseManager {
-    /// Создает новый экземпляр менеджера релизов
+    /// Создает новый экземпляр менеджера релизов; даты планируемых релизов — по часам `agent_manager`
     pub fn new(
         git_repo: GitRepository,
         agent_manager: LLMAgentManager,
@@ -94,20 +105,38 @@ impl ReleaseManager {
     ) -> Self {
         Self {
             git_repo,
+            clock: agent_manager.clock(),
             agent_manager,
             project_config,
+            proofreader: None,
+            release_branches: None,
+            tag_prefix: "v".to_string(),
         }
     }
 
+    /// Ветки релизов (`[git] release_branch_prefix`): на `release/2.1` выпускаются только версии 2.1.x
+    pub fn with_release_branches(mut self, config: &GitConfig) -> Self {
+        self.release_branches = Some(ReleaseBranches::new(&self.git_repo.path, &config.release_branch_prefix));
+        self.tag_prefix = config.tag_prefix.clone();
+        self
+    }
+
+    /// Вычитка сгенерированных changelog, release notes и переводов (`[proofreading]`)
+    pub fn with_proofreading(mut self, config: &ProofreadingConfig) -> Self {
+        self.proofreader = config.enabled.then(|| Proofreader::new(config));
+        self
+    }
+
     /// Анализирует изменения и предлагает версию для следующего релиза
     pub async fn suggest_next_version(&self) -> Result<PlannedRelease> {
         info!("🔍 Анализ изменений для предложения версии");
 
         // Получаем анализ изменений с последнего релиза
-        let (analysis, commits, latest_tag) = self.git_repo.get_changes_since_last_release().await?;
+        let line = self.release_line().await?;
+        let (analysis, commits, latest_tag) = self.changes_since_last_release(line).await?;
 
         // Определяем тип версии
-        let version_type = VersionType::from_analysis(&analysis);
+        let mut version_type = VersionType::from_analysis(&analysis);
 
         // Определяем текущую версию
         let current_version = if let Some(tag) = latest_tag {
@@ -116,9 +145,35 @@ impl ReleaseManager {
             "1.0.0".to_string()
         };
 
-        // Предлагаем новую версию
-        let suggested_version = version_type.increment(&current_version)
-            .unwrap_or_else(|_| format!("{}.0.0", current_version.parse::<Version>().unwrap_or_else(|_| Version::new(1, 0, 0)).major + 1));
+        // Версия из маркера коммита (`[[git.version_rules]]`) берется как есть, на ветке релиза — если относится к ее линии
+        let custom = match &analysis.recommended_version_bump {
+            VersionBump::Custom(version) => Some(version.clone()),
+            _ => None,
+        };
+        let custom = match (custom, line) {
+            (Some(version), Some(line)) if !line.contains(&version) => {
+                warn!("⚠️ Версия {} из маркера коммита не относится к линии {}: используется patch-версия линии", version, line);
+                None
+            }
+            (custom, _) => custom,
+        };
+
+        // Предлагаем новую версию; ветка релиза выпускает только patch-версии своей линии
+        let suggested_version = if let Some(version) = custom {
+            if Version::parse(&version).is_ok_and(|v| !v.pre.is_empty()) {
+                version_type = VersionType::PreRelease;
+            }
+            version
+        } else if let Some(line) = line {
+            if version_type != VersionType::Patch {
+                warn!("⚠️ На ветке {} изменения тянут на {:?}, но выпускается patch-версия", line.branch_name(self.release_branch_prefix()), version_type);
+            }
+            version_type = VersionType::Patch;
+            line.next_patch(Some(&current_version))
+        } else {
+            version_type.increment(&current_version)
+                .unwrap_or_else(|_| format!("{}.0.0", current_version.parse::<Version>().unwrap_or_else(|_| Version::new(1, 0, 0)).major + 1))
+        };
 
         info!("📋 Текущая версия: {}", current_version);
         info!("📈 Предлагаемая версия: {} ({:?})", suggested_version, version_type);
@@ -129,7 +184,7 @@ impl ReleaseManager {
             version_type,
             changes_count: analysis.total_commits,
             breaking_changes: analysis.breaking_changes.len(),
-            estimated_release_date: Utc::now(),
+            estimated_release_date: self.clock.now(),
             release_notes: None,
             changelog: None,
         })
@@ -148,7 +203,7 @@ impl ReleaseManager {
                     version_type: VersionType::Patch, // Будет определено позже
                     changes_count: 0,
                     breaking_changes: 0,
-                    estimated_release_date: Utc::now(),
+                    estimated_release_date: self.clock.now(),
                     release_notes: None,
                     changelog: None,
                 }
@@ -162,7 +217,17 @@ impl ReleaseManager {
         };
 
         // Получаем анализ изменений
-        let (analysis, commits, latest_tag) = self.git_repo.get_changes_since_last_release().await?;
+        let line = self.release_line().await?;
+        if let Some(line) = line.filter(|line| !line.contains(&result.release.version)) {
+            anyhow::bail!(
+                "Версия {} не относится к линии {}: на ветке {} выпускаются только версии {}.x",
+                result.release.version,
+                line,
+                line.branch_name(self.release_branch_prefix()),
+                line
+            );
+        }
+        let (analysis, commits, latest_tag) = self.changes_since_last_release(line).await?;
 
         result.release.changes_count = analysis.total_commits;
         result.release.breaking_changes = analysis.breaking_changes.len();
@@ -190,6 +255,16 @@ impl ReleaseManager {
             }
         }
 
+        // Вычитка сгенерированного контента
+        for (label, text) in [("changelog", &mut result.release.changelog), ("release notes", &mut result.release.release_notes)] {
+            if let Some(text) = text {
+                let issues = self.proofread(label, text);
+                if issues > 0 {
+                    result.warnings.push(format!("Вычитка {}: замечаний {}", label, issues));
+                }
+            }
+        }
+
         // Валидация
         let validation_result = self.validate_release_readiness(&analysis).await?;
         result.validation_issues = validation_result.issues;
@@ -203,72 +278,150 @@ impl ReleaseManager {
         Ok(result)
     }
 
+    /// Имя тега версии с префиксом `[git] tag_prefix`
+    fn tag_name(&self, version: &str) -> String {
+        format!("{}{}", self.tag_prefix, version)
+    }
+
     /// Создает релиз с тегом и аннотацией
     pub async fn create_release(&self, version: &str, message: Option<String>) -> Result<String> {
-        info!("🏷️ Создание релиза v{}", version);
+        let tag = self.tag_name(version);
+        info!("🏷️ Создание релиза {}", tag);
 
         // Проверяем, что такая версия еще не существует
         if self.tag_exists(version).await? {
-            return Err(anyhow::anyhow!("Тег v{} уже существует", version));
+            return Err(anyhow::anyhow!("Тег {} уже существует", tag));
         }
 
         // Создаем аннотированный тег
-        let tag_message = message.unwrap_or_else(|| format!("Release v{}", version));
+        let tag_message = message.unwrap_or_else(|| format!("Release {}", tag));
 
-        let output = Command::new("git")
-            .args(&["tag", "-a", &format!("v{}", version), "-m", &tag_message])
+        let output = GitCommand::new(&self.git_repo.path)
+            .args(&["tag", "-a", &tag, "-m", &tag_message])
             .output()
+            .await
             .context("Ошибка создания тега")?;
 
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
+        if !output.success() {
+            let error = output.stderr;
             return Err(anyhow::anyhow!("Git ошибка создания тега: {}", error));
         }
 
-        info!("✅ Тег v{} создан", version);
-        Ok(format!("v{}", version))
+        info!("✅ Тег {} создан", tag);
+        Ok(tag)
     }
 
     /// Публикует релиз (push тега)
     pub async fn publish_release(&self, version: &str) -> Result<()> {
-        info!("📤 Публикация релиза v{}", version);
+        let tag = self.tag_name(version);
+        info!("📤 Публикация релиза {}", tag);
+        chaos::inject(ChaosPoint::GitPush)?;
 
-        let output = Command::new("git")
-            .args(&["push", "origin", &format!("v{}", version)])
+        let output = GitCommand::new(&self.git_repo.path)
+            .args(&["push", "origin", &tag])
+            .timeout(PUSH_TIMEOUT)
             .output()
+            .await
             .context("Ошибка пуша тега")?;
 
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
+        if !output.success() {
+            let error = output.stderr;
             return Err(anyhow::anyhow!("Git ошибка пуша тега: {}", error));
         }
 
-        info!("✅ Релиз v{} опубликован", version);
+        info!("✅ Релиз {} опубликован", tag);
         Ok(())
     }
 
+    /// Убирает за релизом, прерванным по Ctrl-C: неопубликованный тег удаляется локально,
+    /// а уже опубликованный остается — его могли получить другие
+    pub async fn discard_interrupted_release(&self, version: &str) {
+        let tag = self.tag_name(version);
+        let tag_ref = format!("refs/tags/{}", tag);
+        let local = GitCommand::new(&self.git_repo.path)
+            .args(["rev-parse", "-q", "--verify", &tag_ref])
+            .output()
+            .await;
+        if !local.is_ok_and(|output| output.success()) {
+            return;
+        }
+
+        let remote = GitCommand::new(&self.git_repo.path)
+            .args(["ls-remote", "--tags", "origin", &tag_ref])
+            .timeout(PUSH_TIMEOUT)
+            .output()
+            .await;
+        match remote {
+            Ok(output) if output.success() && !output.stdout.trim().is_empty() => {
+                interrupt::record(format!("Тег {} уже опубликован в origin и оставлен", tag), false);
+            }
+            Ok(output) if output.success() => {
+                let deleted = GitCommand::new(&self.git_repo.path)
+                    .args(["tag", "-d", &tag])
+                    .output()
+                    .await
+                    .is_ok_and(|output| output.success());
+                let action = if deleted { "Удален неопубликованный тег" } else { "Не удалось удалить неопубликованный тег" };
+                interrupt::record(format!("{} {}", action, tag), deleted);
+            }
+            _ => interrupt::record(format!("Не удалось проверить тег {} в origin, локальный тег оставлен", tag), false),
+        }
+    }
+
     /// Откатывает релиз (удаляет тег локально и удаленно)
     pub async fn rollback_release(&self, version: &str) -> Result<()> {
-        warn!("⏪ Откат релиза v{}", version);
+        let tag = self.tag_name(version);
+        warn!("⏪ Откат релиза {}", tag);
 
         // Удаляем локальный тег
-        let _ = Command::new("git")
-            .args(&["tag", "-d", &format!("v{}", version)])
-            .output();
+        let _ = GitCommand::new(&self.git_repo.path)
+            .args(&["tag", "-d", &tag])
+            .output()
+            .await;
 
         // Удаляем удаленный тег
-        let _ = Command::new("git")
-            .args(&["push", "origin", "--delete", &format!("v{}", version)])
-            .output();
+        let _ = GitCommand::new(&self.git_repo.path)
+            .args(&["push", "origin", "--delete", &tag])
+            .timeout(PUSH_TIMEOUT)
+            .output()
+            .await;
 
-        warn!("⚠️ Релиз v{} откачен", version);
+        warn!("⚠️ Релиз {} откачен", tag);
         Ok(())
     }
 
+    /// Линия релизов текущей ветки; None — не ветка релиза или ветки релизов не настроены
+    pub async fn release_line(&self) -> Result<Option<ReleaseLine>> {
+        match &self.release_branches {
+            Some(branches) => branches.current_line().await,
+            None => Ok(None),
+        }
+    }
+
+    fn release_branch_prefix(&self) -> &str {
+        self.release_branches.as_ref().map(|b| b.prefix()).unwrap_or_default()
+    }
+
+    /// Изменения с последнего релиза; на ветке релиза — с последнего тега ее линии.
+    /// Линия без выпущенных версий считается от ближайшего тега, как обычная ветка
+    async fn changes_since_last_release(&self, line: Option<ReleaseLine>) -> Result<(crate::git::ReleaseAnalysis, Vec<crate::git::GitCommit>, Option<GitTag>)> {
+        let (Some(line), Some(branches)) = (line, &self.release_branches) else {
+            return self.git_repo.get_changes_since_last_release().await;
+        };
+        match branches.latest_tag(line, &self.tag_prefix).await? {
+            Some(tag) => {
+                info!("🌿 Ветка {}: изменения с {}", line.branch_name(branches.prefix()), tag);
+                let tag = self.git_repo.tags.get_tag_info(&tag).await?;
+                self.git_repo.get_changes_since_tag(Some(tag)).await
+            }
+            None => self.git_repo.get_changes_since_last_release().await,
+        }
+    }
+
     /// Проверяет существование тега
     async fn tag_exists(&self, version: &str) -> Result<bool> {
         let tags = self.git_repo.tags.get_all_tags().await?;
-        Ok(tags.iter().any(|tag| tag.name == format!("v{}", version)))
+        Ok(tags.iter().any(|tag| tag.name == self.tag_name(version)))
     }
 
     /// Генерирует changelog для релиза
@@ -312,6 +465,46 @@ impl ReleaseManager {
         Ok(formatted)
     }
 
+    /// Переводы release notes для локалей change-notes; локаль с ошибкой перевода пропускается
+    pub async fn localize_release_notes(&self, version: &str, release_notes: &str, locales: &[String]) -> BTreeMap<String, String> {
+        let plugin_info = PluginInfo {
+            name: self.project_config.name.clone(),
+            id: self.project_config.id.clone(),
+            version: version.to_string(),
+            description: None,
+        };
+
+        let mut translations = BTreeMap::new();
+        for locale in locales {
+            match self.agent_manager.translate_release_notes(version, release_notes, locale, &plugin_info).await {
+                Ok(mut text) if !text.is_empty() => {
+                    self.proofread(&format!("release notes ({})", locale), &mut text);
+                    translations.insert(locale.clone(), text);
+                }
+                Ok(_) => warn!("⚠️ Пустой перевод release notes для локали {}", locale),
+                Err(e) => warn!("⚠️ Локаль {} пропущена: {:#}", locale, e),
+            }
+        }
+        translations
+    }
+
+    /// Вычитывает текст на месте (в режиме `report` он не меняется); возвращает число замечаний
+    fn proofread(&self, label: &str, text: &mut String) -> usize {
+        let Some(proofreader) = &self.proofreader else {
+            return 0;
+        };
+        let proofread = proofreader.check(text);
+        for issue in &proofread.issues {
+            if proofread.fixed {
+                info!("✏️ Вычитка {}: исправлено: {}", label, issue);
+            } else {
+                warn!("✏️ Вычитка {}: {}", label, issue);
+            }
+        }
+        *text = proofread.text;
+        proofread.issues.len()
+    }
+
     /// Валидирует готовность к релизу
     async fn validate_release_readiness(&self, analysis: &crate::git::ReleaseAnalysis) -> Result<ReleaseValidationResult> {
         let mut issues = Vec::new();
@@ -344,12 +537,13 @@ impl ReleaseManager {
 
     /// Проверяет чистоту рабочей директории Git
     async fn is_working_tree_clean(&self) -> Result<bool> {
-        let output = Command::new("git")
-            .args(&["status", "--porcelain"])
-            .output()
+        let status = GitCommand::new(&self.git_repo.path)
+            .args(["status", "--porcelain"])
+            .run()
+            .await
             .context("Ошибка проверки статуса Git")?;
 
-        Ok(String::from_utf8_lossy(&output.stdout).trim().is_empty())
+        Ok(status.trim().is_empty())
     }
 
     /// Получает историю релизов
@@ -0,0 +1,160 @@
+//! Безопасные исправления для `validate --fix`: правки, которые не меняют смысл проекта и
+//! опубликованного индекса (недостающий `<id>`, порядок записей, пропущенные суммы, права ключа)
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use xmltree::{Element, XMLNode};
+
+/// Найденная проблема и ее исправление
+#[derive(Debug, Clone, PartialEq)]
+pub struct Remediation {
+    /// Файл, к которому относится находка
+    pub target: String,
+    pub problem: String,
+    pub fix: String,
+    /// `false` — только найдено, исправление не применялось
+    pub applied: bool,
+}
+
+impl Remediation {
+    pub fn new(target: impl Into<String>, problem: impl Into<String>, fix: impl Into<String>, applied: bool) -> Self {
+        Self { target: target.into(), problem: problem.into(), fix: fix.into(), applied }
+    }
+}
+
+/// plugin.xml с `<id>` плагина сразу после `<idea-plugin>`; `None` — id уже задан.
+/// Правка строковая, чтобы не терять форматирование и комментарии файла
+pub fn insert_plugin_id(xml: &str, plugin_id: &str) -> Result<Option<String>> {
+    let root = Element::parse(xml.as_bytes()).context("plugin.xml не является корректным XML")?;
+    if root.get_child("id").and_then(|id| id.get_text()).is_some_and(|id| !id.trim().is_empty()) {
+        return Ok(None);
+    }
+    let id = format!("<id>{}</id>", plugin_id);
+    let empty_id = regex::Regex::new(r"<id\s*/>|<id>\s*</id>").expect("корректное выражение");
+    if empty_id.is_match(xml) {
+        return Ok(Some(empty_id.replace(xml, regex::NoExpand(&id)).to_string()));
+    }
+    let open = xml.find("<idea-plugin").context("В plugin.xml нет элемента <idea-plugin>")?;
+    let end = open + xml[open..].find('>').context("Незакрытый тег <idea-plugin>")? + 1;
+    Ok(Some(format!("{}\n    {}{}", &xml[..end], id, &xml[end..])))
+}
+
+/// Добавляет `<id>` в plugin.xml проекта, если его нет
+pub fn plugin_xml_id(path: &Path, plugin_id: &str, apply: bool) -> Result<Option<Remediation>> {
+    let Ok(xml) = std::fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    let Some(fixed) = insert_plugin_id(&xml, plugin_id)? else {
+        return Ok(None);
+    };
+    if apply {
+        std::fs::write(path, fixed).with_context(|| format!("Не удалось записать {}", path.display()))?;
+    }
+    Ok(Some(Remediation::new(
+        path.display().to_string(),
+        "нет <id>: IDE не свяжет обновления с установленным плагином",
+        format!("добавлен <id>{}</id> из [project] id", plugin_id),
+        apply,
+    )))
+}
+
+/// Ключ сортировки записи индекса: id плагина, для mock-индекса — имя файла
+fn index_key(el: &Element) -> String {
+    el.attributes.get("id").or_else(|| el.attributes.get("file")).cloned().unwrap_or_default()
+}
+
+/// updatePlugins.xml с записями `<plugin>`, упорядоченными по id; `None` — порядок верный.
+/// Записи одного id сохраняют взаимный порядок, остальные узлы остаются на местах
+pub fn sort_index(xml: &str) -> Result<Option<String>> {
+    let mut root = Element::parse(xml.as_bytes()).context("Не удалось разобрать updatePlugins.xml")?;
+    let slots: Vec<usize> = root
+        .children
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| matches!(node, XMLNode::Element(el) if el.name == "plugin"))
+        .map(|(i, _)| i)
+        .collect();
+    let mut plugins: Vec<XMLNode> = slots.iter().map(|&i| root.children[i].clone()).collect();
+    let key = |node: &XMLNode| match node {
+        XMLNode::Element(el) => index_key(el),
+        _ => String::new(),
+    };
+    if plugins.windows(2).all(|pair| key(&pair[0]) <= key(&pair[1])) {
+        return Ok(None);
+    }
+    plugins.sort_by_key(key);
+    for (slot, plugin) in slots.into_iter().zip(plugins) {
+        root.children[slot] = plugin;
+    }
+    let mut buf = Vec::new();
+    root.write(&mut buf).context("Сериализация updatePlugins.xml не удалась")?;
+    Ok(Some(String::from_utf8_lossy(&buf).to_string()))
+}
+
+/// Приватный SSH-ключ, доступный группе или остальным, ssh-agent и OpenSSH отвергают;
+/// исправление — права 600
+#[cfg(unix)]
+pub fn ssh_key_permissions(path: &Path, apply: bool) -> Result<Option<Remediation>> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return Ok(None);
+    };
+    let mode = metadata.permissions().mode() & 0o777;
+    if mode & 0o077 == 0 {
+        return Ok(None);
+    }
+    if apply {
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("Не удалось изменить права {}", path.display()))?;
+    }
+    Ok(Some(Remediation::new(
+        path.display().to_string(),
+        format!("права {:o}: ключ доступен не только владельцу", mode),
+        "права изменены на 600",
+        apply,
+    )))
+}
+
+#[cfg(not(unix))]
+pub fn ssh_key_permissions(_path: &Path, _apply: bool) -> Result<Option<Remediation>> {
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remediations_fix_only_broken_files() {
+        let xml = "<idea-plugin>\n    <!-- RIDE -->\n    <name>RIDE</name>\n</idea-plugin>";
+        assert_eq!(
+            insert_plugin_id(xml, "ru.marslab.ide.ride").unwrap().unwrap(),
+            "<idea-plugin>\n    <id>ru.marslab.ide.ride</id>\n    <!-- RIDE -->\n    <name>RIDE</name>\n</idea-plugin>"
+        );
+        assert_eq!(insert_plugin_id("<idea-plugin><id/></idea-plugin>", "ru.x").unwrap().unwrap(), "<idea-plugin><id>ru.x</id></idea-plugin>");
+        assert_eq!(insert_plugin_id("<idea-plugin><id>ru.example</id></idea-plugin>", "ru.other").unwrap(), None);
+
+        let index = r#"<plugins><plugin id="ru.b" url="b-1.zip" version="1"/><plugin id="ru.a" url="a.zip" version="2"/><plugin id="ru.b" url="b-2.zip" version="3"/></plugins>"#;
+        let sorted = sort_index(index).unwrap().unwrap();
+        let position = |url: &str| sorted.find(url).unwrap();
+        assert!(position("a.zip") < position("b-1.zip") && position("b-1.zip") < position("b-2.zip"));
+        assert_eq!(sort_index(&sorted).unwrap(), None);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let dir = tempfile::tempdir().unwrap();
+            let key = dir.path().join("id_ed25519");
+            std::fs::write(&key, "key").unwrap();
+            std::fs::set_permissions(&key, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+            let found = ssh_key_permissions(&key, false).unwrap().unwrap();
+            assert!(!found.applied && found.problem.starts_with("права 644"));
+            assert_eq!(std::fs::metadata(&key).unwrap().permissions().mode() & 0o777, 0o644);
+            assert!(ssh_key_permissions(&key, true).unwrap().unwrap().applied);
+            assert_eq!(std::fs::metadata(&key).unwrap().permissions().mode() & 0o777, 0o600);
+            assert_eq!(ssh_key_permissions(&key, true).unwrap(), None);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use serde::Serialize;
+
+use crate::config::parser::RemoteCommandConfig;
+use crate::core::checksums;
+
+/// Значения переменных `{deploy_dir}`, `{xml_path}`, `{version}`, `{artifact}` для подстановки в команду
+#[derive(Debug, Clone)]
+pub struct CommandVars<'a> {
+    pub deploy_dir: &'a str,
+    pub xml_path: &'a str,
+    pub version: &'a str,
+    pub artifact: &'a str,
+}
+
+/// Команда после загрузки с подставленными переменными
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RemoteCommand {
+    pub command: String,
+    pub allow_failure: bool,
+}
+
+/// Результат выполнения команды на сервере
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteCommandResult {
+    pub command: String,
+    /// None — команда не выполнялась (сборка без SSH)
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration_ms: u64,
+    pub allow_failure: bool,
+}
+
+impl RemoteCommandResult {
+    pub fn succeeded(&self) -> bool {
+        self.exit_code.is_none_or(|code| code == 0)
+    }
+}
+
+/// Подставляет переменные в команды из конфигурации. Значения берутся в одинарные кавычки:
+/// каталог деплоя содержит канал, а его задают из CLI, HTTP API и ChatOps
+pub fn expand(commands: &[RemoteCommandConfig], vars: &CommandVars) -> Vec<RemoteCommand> {
+    let values = [
+        ("deploy_dir", vars.deploy_dir),
+        ("xml_path", vars.xml_path),
+        ("version", vars.version),
+        ("artifact", vars.artifact),
+    ];
+    commands
+        .iter()
+        .map(|c| RemoteCommand {
+            command: checksums::shell_command(&c.command, &values),
+            allow_failure: c.allow_failure,
+        })
+        .collect()
+}
+
+/// Выполняет команду через SSH exec-канал, собирая stdout/stderr и код выхода
+#[cfg(feature = "ssh")]
+pub fn exec(session: &ssh2::Session, command: &RemoteCommand) -> anyhow::Result<RemoteCommandResult> {
+    use anyhow::Context;
+    use std::io::Read;
+
+    let started = std::time::Instant::now();
+    let mut channel = session.channel_session().context("Не удалось открыть SSH канал")?;
+    channel
+        .exec(&command.command)
+        .with_context(|| format!("Не удалось запустить команду на сервере: {}", command.command))?;
+
+    let mut stdout = String::new();
+    channel.read_to_string(&mut stdout).context("Не удалось прочитать stdout команды")?;
+    let mut stderr = String::new();
+    channel.stderr().read_to_string(&mut stderr).context("Не удалось прочитать stderr команды")?;
+    channel.wait_close().ok();
+
+    Ok(RemoteCommandResult {
+        command: command.command.clone(),
+        exit_code: Some(channel.exit_status().context("Не удалось получить код выхода команды")?),
+        stdout,
+        stderr,
+        duration_ms: started.elapsed().as_millis() as u64,
+        allow_failure: command.allow_failure,
+    })
+}
+
+/// Отчет по командам после загрузки
+pub fn render(results: &[RemoteCommandResult]) -> String {
+    let mut out = String::new();
+    for result in results {
+        let status = match result.exit_code {
+            None => "пропущена".to_string(),
+            Some(_) if result.succeeded() => "ok".to_string(),
+            Some(code) if result.allow_failure => format!("код {} (допустимо)", code),
+            Some(code) => format!("код {}", code),
+        };
+        out.push_str(&format!("$ {} — {}, {} мс\n", result.command, status, result.duration_ms));
+        for line in result.stdout.lines().chain(result.stderr.lines()) {
+            out.push_str(&format!("  {}\n", line));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_and_render() {
+        let config = vec![
+            RemoteCommandConfig { command: "chmod 644 {deploy_dir}/{artifact}".to_string(), allow_failure: false },
+            RemoteCommandConfig { command: "curl -X PURGE https://plugins.example.com/{version}/".to_string(), allow_failure: true },
+        ];
+        let vars = CommandVars {
+            deploy_dir: "/var/www/plugins/ride",
+            xml_path: "/var/www/plugins/updatePlugins.xml",
+            version: "1.3.0",
+            artifact: "ride-1.3.0.zip",
+        };
+
+        let commands = expand(&config, &vars);
+        assert_eq!(commands[0].command, "chmod 644 '/var/www/plugins/ride'/'ride-1.3.0.zip'");
+        assert_eq!(commands[1].command, "curl -X PURGE https://plugins.example.com/'1.3.0'/");
+
+        // Канал из API не выполняется как команда
+        let injected = CommandVars { deploy_dir: "/var/www/plugins/ride/x; rm -rf ~", ..vars.clone() };
+        assert_eq!(expand(&config[..1], &injected)[0].command, "chmod 644 '/var/www/plugins/ride/x; rm -rf ~'/'ride-1.3.0.zip'");
+
+        let results = vec![RemoteCommandResult {
+            command: commands[1].command.clone(),
+            exit_code: Some(7),
+            stdout: String::new(),
+            stderr: "curl: (7) Failed to connect".to_string(),
+            duration_ms: 12,
+            allow_failure: true,
+        }];
+        assert!(!results[0].succeeded());
+        assert_eq!(
+            render(&results),
+            "$ curl -X PURGE https://plugins.example.com/'1.3.0'/ — код 7 (допустимо), 12 мс\n  curl: (7) Failed to connect\n"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+
+/// Отпечаток содержимого ZIP: запись -> sha256 данных.
+/// Время модификации и порядок записей не учитываются; вложенные JAR
+/// разворачиваются в записи вида `lib/x.jar!/a/B.class`, поэтому
+/// JAR с разными timestamp, но одинаковым содержимым считаются равными.
+pub type ContentDigest = BTreeMap<String, String>;
+
+/// Отличающаяся запись
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct EntryDiff {
+    pub entry: String,
+    pub first: Option<String>,
+    pub second: Option<String>,
+}
+
+/// Результат сравнения двух сборок
+#[derive(Debug, Clone, Serialize)]
+pub struct ReproducibilityReport {
+    pub first: PathBuf,
+    pub second: PathBuf,
+    pub entries: usize,
+    pub differences: Vec<EntryDiff>,
+}
+
+impl ReproducibilityReport {
+    pub fn is_reproducible(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+/// Считает отпечаток содержимого артефакта
+pub fn digest_artifact(path: &Path) -> Result<ContentDigest> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Не удалось открыть артефакт: {}", path.display()))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .with_context(|| format!("Не удалось прочитать ZIP {}", path.display()))?;
+    let mut digest = ContentDigest::new();
+    collect(&mut archive, "", &mut digest)?;
+    Ok(digest)
+}
+
+fn collect<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, prefix: &str, digest: &mut ContentDigest) -> Result<()> {
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = format!("{}{}", prefix, entry.name());
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut data)
+            .with_context(|| format!("Не удалось прочитать запись {}", name))?;
+
+        if name.ends_with(".jar") {
+            if let Ok(mut inner) = zip::ZipArchive::new(Cursor::new(data.clone())) {
+                collect(&mut inner, &format!("{}!/", name), digest)?;
+                continue;
+            }
+        }
+        digest.insert(name, format!("{:x}", Sha256::digest(&data)));
+    }
+    Ok(())
+}
+
+/// Сравнивает два артефакта по содержимому
+pub fn compare(first: &Path, second: &Path) -> Result<ReproducibilityReport> {
+    let a = digest_artifact(first)?;
+    let b = digest_artifact(second)?;
+
+    let mut names: Vec<&String> = a.keys().chain(b.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    let differences = names
+        .iter()
+        .filter(|name| a.get(**name) != b.get(**name))
+        .map(|name| EntryDiff {
+            entry: name.to_string(),
+            first: a.get(*name).cloned(),
+            second: b.get(*name).cloned(),
+        })
+        .collect();
+
+    Ok(ReproducibilityReport {
+        first: first.to_path_buf(),
+        second: second.to_path_buf(),
+        entries: names.len(),
+        differences,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::FileOptions;
+
+    fn jar(class_body: &[u8], modified: zip::DateTime) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        writer.start_file("a/B.class", FileOptions::default().last_modified_time(modified)).unwrap();
+        writer.write_all(class_body).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn plugin_zip(path: &Path, class_body: &[u8], modified: zip::DateTime, extra: Option<&str>) {
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        let options = FileOptions::default().last_modified_time(modified);
+        writer.start_file("ride/lib/ride.jar", options).unwrap();
+        writer.write_all(&jar(class_body, modified)).unwrap();
+        if let Some(name) = extra {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(b"x").unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_timestamps_are_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("a.zip");
+        let second = dir.path().join("b.zip");
+        plugin_zip(&first, b"class", zip::DateTime::from_date_and_time(2024, 1, 1, 0, 0, 0).unwrap(), None);
+        plugin_zip(&second, b"class", zip::DateTime::from_date_and_time(2025, 6, 1, 12, 30, 0).unwrap(), None);
+
+        let report = compare(&first, &second).unwrap();
+        assert!(report.is_reproducible());
+        assert_eq!(report.entries, 1);
+    }
+
+    #[test]
+    fn test_differences_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("a.zip");
+        let second = dir.path().join("b.zip");
+        plugin_zip(&first, b"class-v1", zip::DateTime::default(), None);
+        plugin_zip(&second, b"class-v2", zip::DateTime::default(), Some("ride/build.txt"));
+
+        let report = compare(&first, &second).unwrap();
+        let entries: Vec<_> = report.differences.iter().map(|d| d.entry.as_str()).collect();
+        assert_eq!(entries, ["ride/build.txt", "ride/lib/ride.jar!/a/B.class"]);
+        assert!(report.differences[0].first.is_none());
+    }
+}
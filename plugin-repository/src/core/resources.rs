@@ -0,0 +1,142 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use crate::config::parser::RepositoryConfig;
+
+/// Подкаталог ресурсов в каталоге артефакта
+pub const RESOURCES_DIR: &str = "resources";
+
+/// Маркеры сгенерированного блока ресурсов в description
+const LISTING_START: &str = "<!-- deploy-plugin:resources -->";
+const LISTING_END: &str = "<!-- /deploy-plugin:resources -->";
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "svg", "webp"];
+
+/// Иконка и дополнительные файлы (скриншоты, документация), публикуемые вместе с плагином
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PluginResources {
+    pub icon: Option<PathBuf>,
+    pub files: Vec<PathBuf>,
+}
+
+/// Ресурс, загруженный в репозиторий
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublishedResource {
+    pub name: String,
+    pub url: String,
+}
+
+impl PluginResources {
+    pub fn from_config(repository: &RepositoryConfig) -> Self {
+        Self {
+            icon: repository.icon.as_ref().map(PathBuf::from),
+            files: repository.resources.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.icon.is_none() && self.files.is_empty()
+    }
+
+    /// Все файлы для загрузки: иконка первой
+    pub fn all(&self) -> Vec<PathBuf> {
+        self.icon.iter().chain(self.files.iter()).cloned().collect()
+    }
+
+    /// Проверяет, что файлы существуют и их имена не совпадают (в репозитории они лежат в одном каталоге)
+    pub fn validate(&self) -> Result<()> {
+        let mut names = Vec::new();
+        for path in self.all() {
+            if !path.is_file() {
+                anyhow::bail!("Файл ресурса плагина не найден: {}", path.display());
+            }
+            let name = file_name(&path);
+            if names.contains(&name) {
+                anyhow::bail!("Ресурсы плагина с одинаковым именем файла: {}", name);
+            }
+            names.push(name);
+        }
+        Ok(())
+    }
+}
+
+pub fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
+
+fn is_image(name: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.as_str()))
+}
+
+/// HTML-блок для description: иконка, скриншоты как изображения, остальное — ссылками
+pub fn listing_html(icon: Option<&PublishedResource>, files: &[PublishedResource]) -> String {
+    let mut html = String::from(LISTING_START);
+    if let Some(icon) = icon {
+        html.push_str(&format!("<p><img src=\"{}\" alt=\"icon\" width=\"64\" height=\"64\"/></p>", icon.url));
+    }
+
+    let (images, docs): (Vec<_>, Vec<_>) = files.iter().partition(|r| is_image(&r.name));
+    for image in images {
+        html.push_str(&format!("<p><img src=\"{}\" alt=\"{}\"/></p>", image.url, image.name));
+    }
+    if !docs.is_empty() {
+        html.push_str("<ul>");
+        for doc in docs {
+            html.push_str(&format!("<li><a href=\"{}\">{}</a></li>", doc.url, doc.name));
+        }
+        html.push_str("</ul>");
+    }
+    html.push_str(LISTING_END);
+    html
+}
+
+/// Убирает ранее сгенерированный блок ресурсов
+pub fn strip_listing(description: &str) -> String {
+    match (description.find(LISTING_START), description.find(LISTING_END)) {
+        (Some(start), Some(end)) if start < end => {
+            format!("{}{}", &description[..start], &description[end + LISTING_END.len()..])
+                .trim_end()
+                .to_string()
+        }
+        _ => description.to_string(),
+    }
+}
+
+/// Description с актуальным блоком ресурсов (повторный деплой не дублирует блок)
+pub fn with_listing(description: &str, listing: &str) -> String {
+    let base = strip_listing(description);
+    if base.is_empty() {
+        listing.to_string()
+    } else {
+        format!("{}\n{}", base, listing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(name: &str) -> PublishedResource {
+        PublishedResource { name: name.to_string(), url: format!("https://plugins.example.com/ride/resources/{}", name) }
+    }
+
+    #[test]
+    fn test_listing_is_replaced_on_redeploy() {
+        let listing = listing_html(Some(&resource("pluginIcon.svg")), &[resource("screenshot.png"), resource("guide.pdf")]);
+        assert_eq!(
+            listing,
+            "<!-- deploy-plugin:resources --><p><img src=\"https://plugins.example.com/ride/resources/pluginIcon.svg\" alt=\"icon\" width=\"64\" height=\"64\"/></p>\
+             <p><img src=\"https://plugins.example.com/ride/resources/screenshot.png\" alt=\"screenshot.png\"/></p>\
+             <ul><li><a href=\"https://plugins.example.com/ride/resources/guide.pdf\">guide.pdf</a></li></ul><!-- /deploy-plugin:resources -->"
+        );
+
+        let first = with_listing("<p>AI assistant</p>", &listing);
+        let second = with_listing(&first, &listing_html(None, &[resource("guide.pdf")]));
+        assert_eq!(second.matches(LISTING_START).count(), 1);
+        assert!(second.starts_with("<p>AI assistant</p>\n"));
+        assert!(!second.contains("pluginIcon.svg"));
+    }
+}
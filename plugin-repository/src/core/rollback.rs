@@ -0,0 +1,136 @@
+//! Откат опубликованной версии одной командой (`rollback <version>`): запись плагина в
+//! updatePlugins.xml возвращается на предыдущий релиз, тег версии удаляется локально и в origin,
+//! по запросу с сервера удаляется и артефакт. План строится до изменений, поэтому `--dry-run`
+//! показывает ровно то, что будет сделано.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::core::audit::AuditEntry;
+use crate::core::deployer::Promotion;
+use crate::git::{GitCommand, GitTag};
+use crate::utils::clock::Clock;
+
+/// Ожидание `git push origin --delete` (как у публикации тега)
+const PUSH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Что сделает откат версии
+#[derive(Debug, Clone)]
+pub struct RollbackPlan {
+    /// Откатываемая версия
+    pub version: String,
+    /// Переключение индекса на предыдущий релиз
+    pub index: Promotion,
+    /// Тег версии; `None` — тега нет или он оставляется (`--keep-tag`)
+    pub tag: Option<String>,
+    /// Файлы версии на сервере, которые будут удалены (`--delete-artifact`)
+    pub artifacts: Vec<PathBuf>,
+}
+
+impl RollbackPlan {
+    /// Шаги отката по порядку выполнения
+    pub fn steps(&self) -> Vec<String> {
+        let mut steps = vec![format!(
+            "updatePlugins.xml: {} -> {} ({})",
+            self.version, self.index.to_version, self.index.url
+        )];
+        for artifact in &self.artifacts {
+            steps.push(format!("удалить с сервера {}", artifact.display()));
+        }
+        if let Some(tag) = &self.tag {
+            steps.push(format!("удалить тег {} локально и в origin", tag));
+        }
+        steps
+    }
+
+    /// Запись журнала аудита: `version` — восстановленная версия, `previous_version` — откаченная
+    pub fn audit_entry(&self, reason: Option<String>, clock: &dyn Clock) -> AuditEntry {
+        let mut entry = AuditEntry::new("rollback", &self.index.to_version, clock);
+        entry.previous_version = Some(self.version.clone());
+        entry.reason = reason;
+        entry.details = self.steps().join("; ");
+        entry
+    }
+}
+
+/// Последний релиз (без pre-release) младше `version` по тегам `v<версия>`
+pub fn previous_release(tags: &[GitTag], version: &str) -> Option<String> {
+    let current = semver::Version::parse(version).ok()?;
+    tags.iter()
+        .filter_map(|tag| tag.name.strip_prefix('v').and_then(|v| semver::Version::parse(v).ok()))
+        .filter(|v| v.pre.is_empty() && *v < current)
+        .max()
+        .map(|v| v.to_string())
+}
+
+/// Удаляет тег локально и в origin; отсутствие тега в origin — не ошибка
+pub async fn remove_tag(repository_path: &Path, tag: &str) -> Result<()> {
+    let local = GitCommand::new(repository_path).args(["tag", "-d", tag]).output().await?;
+    if !local.success() {
+        anyhow::bail!("Не удалось удалить тег {}: {}", tag, local.stderr.trim());
+    }
+    info!("🗑️ Тег {} удален локально", tag);
+
+    let remote = GitCommand::new(repository_path)
+        .args(["push", "origin", "--delete", tag])
+        .timeout(PUSH_TIMEOUT)
+        .output()
+        .await;
+    match remote {
+        Ok(output) if output.success() => info!("🗑️ Тег {} удален в origin", tag),
+        Ok(output) => warn!("⚠️ Тег {} не удален в origin: {}", tag, output.stderr.trim()),
+        Err(e) => warn!("⚠️ Тег {} не удален в origin: {:#}", tag, e),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(name: &str) -> GitTag {
+        GitTag {
+            name: name.to_string(),
+            commit_hash: String::new(),
+            commit_message: String::new(),
+            author: String::new(),
+            date: chrono::Utc::now(),
+            is_annotated: false,
+        }
+    }
+
+    #[test]
+    fn test_rollback_plan_targets_previous_release() {
+        let tags: Vec<GitTag> = ["v1.4.0", "v1.3.2", "v1.4.0-rc.1", "v1.3.10", "nightly", "v1.5.0"].map(tag).into();
+        assert_eq!(previous_release(&tags, "1.4.0").as_deref(), Some("1.3.10"));
+        assert_eq!(previous_release(&tags, "1.3.2"), None);
+
+        let plan = RollbackPlan {
+            version: "1.4.0".to_string(),
+            index: Promotion {
+                from_version: Some("1.4.0".to_string()),
+                to_version: "1.3.10".to_string(),
+                url: "https://plugins.example.com/ride/ride-1.3.10.zip".to_string(),
+                remote_path: PathBuf::from("/var/www/plugins/ride/ride-1.3.10.zip"),
+                xml: String::new(),
+            },
+            tag: Some("v1.4.0".to_string()),
+            artifacts: vec![PathBuf::from("/var/www/plugins/ride/ride-1.4.0.zip")],
+        };
+        assert_eq!(
+            plan.steps(),
+            [
+                "updatePlugins.xml: 1.4.0 -> 1.3.10 (https://plugins.example.com/ride/ride-1.3.10.zip)",
+                "удалить с сервера /var/www/plugins/ride/ride-1.4.0.zip",
+                "удалить тег v1.4.0 локально и в origin",
+            ]
+        );
+        let clock = crate::utils::clock::FixedClock::new("2024-05-01T12:00:00Z".parse().unwrap());
+        let entry = plan.audit_entry(Some("ломает индексацию".to_string()), &clock);
+        assert_eq!(entry.timestamp, clock.now());
+        assert_eq!((entry.action.as_str(), entry.version.as_str()), ("rollback", "1.3.10"));
+        assert_eq!(entry.previous_version.as_deref(), Some("1.4.0"));
+        assert!(entry.details.contains("удалить тег v1.4.0"));
+    }
+}
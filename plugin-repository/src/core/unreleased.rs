@@ -0,0 +1,176 @@
+use colored::*;
+use serde::Serialize;
+
+use crate::core::releaser::VersionType;
+use crate::git::{ChangeType, GitCommit, ReleaseAnalysis, VersionBump};
+
+/// Типы изменений, ради которых стоит выпускать версию
+const RELEASABLE: &[ChangeType] = &[ChangeType::Breaking, ChangeType::Feature, ChangeType::Fix, ChangeType::Improvement];
+
+/// Порядок групп в отчете
+const GROUP_ORDER: &[ChangeType] = &[
+    ChangeType::Breaking,
+    ChangeType::Feature,
+    ChangeType::Fix,
+    ChangeType::Improvement,
+    ChangeType::Refactoring,
+    ChangeType::Documentation,
+    ChangeType::Testing,
+    ChangeType::Chore,
+    ChangeType::Other,
+];
+
+/// Коммит без релиза
+#[derive(Debug, Clone, Serialize)]
+pub struct UnreleasedCommit {
+    pub short_hash: String,
+    pub message: String,
+    pub author: String,
+}
+
+/// Коммиты одного типа
+#[derive(Debug, Clone, Serialize)]
+pub struct UnreleasedGroup {
+    pub change_type: ChangeType,
+    pub commits: Vec<UnreleasedCommit>,
+}
+
+/// Изменения с последнего релизного тега
+#[derive(Debug, Clone, Serialize)]
+pub struct UnreleasedReport {
+    pub last_tag: Option<String>,
+    pub total_commits: usize,
+    pub groups: Vec<UnreleasedGroup>,
+    pub recommended_bump: VersionBump,
+    /// Следующая версия по рекомендации анализатора (если тег — semver)
+    pub next_version: Option<String>,
+    /// Есть ли изменения, требующие релиза (breaking, feature, fix, improvement)
+    pub release_warranted: bool,
+}
+
+impl UnreleasedReport {
+    pub fn build(last_tag: Option<&str>, tag_prefix: &str, commits: &[GitCommit], analysis: &ReleaseAnalysis) -> Self {
+        let groups: Vec<UnreleasedGroup> = GROUP_ORDER
+            .iter()
+            .map(|change_type| UnreleasedGroup {
+                change_type: change_type.clone(),
+                commits: commits
+                    .iter()
+                    .filter(|c| ChangeType::from_message(&c.message) == *change_type)
+                    .map(|c| UnreleasedCommit {
+                        short_hash: c.short_hash.clone(),
+                        message: c.message.lines().next().unwrap_or_default().to_string(),
+                        author: c.author.clone(),
+                    })
+                    .collect(),
+            })
+            .filter(|g| !g.commits.is_empty())
+            .collect();
+
+        let release_warranted = groups.iter().any(|g| RELEASABLE.contains(&g.change_type));
+        let next_version = last_tag
+            .filter(|_| release_warranted)
+            .and_then(|tag| VersionType::from_analysis(analysis).increment(tag.trim_start_matches(tag_prefix)).ok());
+
+        Self {
+            last_tag: last_tag.map(str::to_string),
+            total_commits: commits.len(),
+            groups,
+            recommended_bump: analysis.recommended_version_bump.clone(),
+            next_version,
+            release_warranted,
+        }
+    }
+
+    /// Отчет для терминала
+    pub fn render_table(&self) -> String {
+        let since = self.last_tag.as_deref().unwrap_or("начала истории");
+        let mut out = format!("{} Не выпущено с {}: {} коммитов\n", "📦".cyan(), since.bright_blue(), self.total_commits);
+
+        for group in &self.groups {
+            out.push_str(&format!(
+                "\n{} {} ({})\n",
+                group.change_type.emoji(),
+                group.change_type.name().bold(),
+                group.commits.len()
+            ));
+            for commit in &group.commits {
+                out.push_str(&format!("  {} {}\n", commit.short_hash.bright_black(), commit.message));
+            }
+        }
+
+        out.push('\n');
+        if self.release_warranted {
+            let bump = match &self.recommended_bump {
+                VersionBump::Major => "major".to_string(),
+                VersionBump::Minor => "minor".to_string(),
+                VersionBump::Patch => "patch".to_string(),
+                VersionBump::Custom(v) => v.clone(),
+            };
+            out.push_str(&format!("{} Рекомендуется релиз: {}", "✅".green(), bump.bold()));
+            if let Some(next) = &self.next_version {
+                out.push_str(&format!(" → {}", next.bright_green()));
+            }
+            out.push('\n');
+        } else {
+            out.push_str(&format!("{} Изменений, требующих релиза, нет\n", "💤".yellow()));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    fn commit(hash: &str, message: &str) -> GitCommit {
+        GitCommit {
+            hash: hash.to_string(),
+            short_hash: hash.to_string(),
+            message: message.to_string(),
+            author: "dev".to_string(),
+            email: "dev@example.com".to_string(),
+            date: Utc::now(),
+            files_changed: 1,
+            insertions: 1,
+            deletions: 0,
+            files: Vec::new(),
+            pull_request: None,
+            diff_summary: None,
+        }
+    }
+
+    fn analysis(bump: VersionBump, feature: bool) -> ReleaseAnalysis {
+        let mut change_summary = HashMap::new();
+        if feature {
+            change_summary.insert(ChangeType::Feature, 1);
+        }
+        ReleaseAnalysis {
+            version_from: "v1.2.0".to_string(),
+            version_to: None,
+            total_commits: 2,
+            change_summary,
+            impact_distribution: HashMap::new(),
+            breaking_changes: vec![],
+            recommended_version_bump: bump,
+            confidence: 0.9,
+        }
+    }
+
+    #[test]
+    fn test_grouping_and_release_decision() {
+        let commits = [commit("a1", "docs: readme"), commit("b2", "feat: deploy promote"), commit("c3", "fix: proxy auth")];
+        let report = UnreleasedReport::build(Some("v1.2.0"), "v", &commits, &analysis(VersionBump::Minor, true));
+
+        let types: Vec<_> = report.groups.iter().map(|g| g.change_type.clone()).collect();
+        assert_eq!(types, [ChangeType::Feature, ChangeType::Fix, ChangeType::Documentation]);
+        assert!(report.release_warranted);
+        assert_eq!(report.next_version.as_deref(), Some("1.3.0"));
+
+        let docs_only = UnreleasedReport::build(Some("v1.2.0"), "v", &commits[..1], &analysis(VersionBump::Patch, false));
+        assert!(!docs_only.release_warranted);
+        assert_eq!(docs_only.next_version, None);
+    }
+}
@@ -0,0 +1,192 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::config::parser::{NetworkConfig, UpdatesConfig};
+use crate::utils::network::NetworkUtils;
+
+/// Непустое значение отключает проверку независимо от конфигурации
+pub const NO_UPDATE_CHECK_ENV: &str = "DEPLOY_PLUGIN_NO_UPDATE_CHECK";
+/// Результат последней проверки (в каталоге кэша пользователя): сеть не трогается чаще `interval_hours`
+const CACHE_FILE: &str = "deploy-pugin/update-check.json";
+/// Проверка не должна заметно задерживать запуск команды
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Секции config.toml, нужные проверке; остальное (и ошибки в нем) разбирает сама команда
+#[derive(Debug, Default, Deserialize)]
+struct Settings {
+    #[serde(default)]
+    updates: UpdatesConfig,
+    #[serde(default)]
+    network: NetworkConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedCheck {
+    checked_at: DateTime<Utc>,
+    latest: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+}
+
+/// Предупреждает, если вышла версия новее текущей. Ошибки сети и кэша не мешают команде
+pub async fn check(config_file: &str) {
+    if std::env::var(NO_UPDATE_CHECK_ENV).is_ok_and(|v| !v.is_empty()) || std::env::var("CI").is_ok() {
+        return;
+    }
+    let settings: Settings = std::fs::read_to_string(config_file)
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default();
+    if !settings.updates.check {
+        return;
+    }
+
+    let Some(cache_dir) = user_cache_dir(|name| std::env::var_os(name)) else {
+        debug!("Каталог кэша пользователя не определен: проверка обновлений пропущена");
+        return;
+    };
+
+    let current = env!("CARGO_PKG_VERSION");
+    match latest_version(&settings, &cache_dir.join(CACHE_FILE)).await {
+        Ok(Some(latest)) if is_newer(&latest, current) => warn!(
+            "⬆️ Доступна новая версия deploy-pugin {} (текущая {}). Отключить проверку: [updates] check = false или {}=1",
+            latest,
+            current,
+            NO_UPDATE_CHECK_ENV
+        ),
+        Ok(_) => debug!("deploy-pugin {} — актуальная версия", current),
+        Err(e) => debug!("Проверка обновлений не выполнена: {:#}", e),
+    }
+}
+
+/// Каталог кэша пользователя, а не рабочий каталог: проверка общая для всех проектов
+fn user_cache_dir(env: impl Fn(&str) -> Option<std::ffi::OsString>) -> Option<PathBuf> {
+    let var = |name: &str| env(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+    if cfg!(windows) {
+        return var("LOCALAPPDATA");
+    }
+    if cfg!(target_os = "macos") {
+        return var("HOME").map(|home| home.join("Library/Caches"));
+    }
+    var("XDG_CACHE_HOME").filter(|p| p.is_absolute()).or_else(|| var("HOME").map(|home| home.join(".cache")))
+}
+
+async fn latest_version(settings: &Settings, cache_path: &Path) -> Result<Option<String>> {
+    let cached = std::fs::read_to_string(cache_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<CachedCheck>(&content).ok());
+    let interval = ChronoDuration::hours(settings.updates.interval_hours as i64);
+    if let Some(cached) = cached.filter(|c| Utc::now() - c.checked_at < interval) {
+        return Ok(cached.latest);
+    }
+
+    // Неудачная попытка тоже кэшируется: без сети проверка не повторяется на каждом запуске
+    let latest = fetch_latest(settings).await;
+    let entry = CachedCheck { checked_at: Utc::now(), latest: latest.as_ref().ok().cloned().flatten() };
+    if let Err(e) = save_cache(cache_path, &entry) {
+        debug!("Не удалось сохранить {}: {:#}", cache_path.display(), e);
+    }
+    latest
+}
+
+async fn fetch_latest(settings: &Settings) -> Result<Option<String>> {
+    let client = NetworkUtils::http_client(&settings.network.proxy, REQUEST_TIMEOUT)?;
+    let releases: Vec<Release> = client
+        .get(&settings.updates.url)
+        .header(reqwest::header::USER_AGENT, concat!("deploy-pugin/", env!("CARGO_PKG_VERSION")))
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .await
+        .with_context(|| format!("Запрос {}", settings.updates.url))?
+        .error_for_status()?
+        .json()
+        .await
+        .context("Некорректный ответ со списком релизов")?;
+    Ok(latest_release(&releases, &settings.updates.tag_prefix))
+}
+
+/// Наибольшая стабильная версия среди тегов с префиксом утилиты
+fn latest_release(releases: &[Release], tag_prefix: &str) -> Option<String> {
+    releases
+        .iter()
+        .filter(|r| !r.draft && !r.prerelease)
+        .filter_map(|r| r.tag_name.strip_prefix(tag_prefix))
+        .filter_map(|v| semver::Version::parse(v).ok())
+        .filter(|v| v.pre.is_empty())
+        .max()
+        .map(|v| v.to_string())
+}
+
+fn is_newer(latest: &str, current: &str) -> bool {
+    match (semver::Version::parse(latest), semver::Version::parse(current)) {
+        (Ok(latest), Ok(current)) => latest > current,
+        _ => false,
+    }
+}
+
+fn save_cache(path: &Path, entry: &CachedCheck) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(entry)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_release_and_cache() {
+        let releases: Vec<Release> = serde_json::from_str(
+            r#"[
+                {"tag_name": "deploy-plugin-v1.3.0-rc.1", "prerelease": true},
+                {"tag_name": "deploy-plugin-v1.2.0"},
+                {"tag_name": "v9.9.9"},
+                {"tag_name": "deploy-plugin-v1.10.0", "draft": true},
+                {"tag_name": "deploy-plugin-v1.4.0"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(latest_release(&releases, "deploy-plugin-v").as_deref(), Some("1.4.0"));
+        assert_eq!(latest_release(&releases, "ride-v"), None);
+        assert!(is_newer("1.4.0", "1.2.3"));
+        assert!(!is_newer("1.2.3", "1.2.3"));
+        assert!(!is_newer("garbage", "1.2.3"));
+
+        // Свежий кэш отвечает без обращения к сети (url недоступен)
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join(CACHE_FILE);
+        save_cache(&cache, &CachedCheck { checked_at: Utc::now(), latest: Some("2.0.0".to_string()) }).unwrap();
+        let settings = Settings {
+            updates: UpdatesConfig { url: "http://127.0.0.1:9/releases".to_string(), ..UpdatesConfig::default() },
+            ..Settings::default()
+        };
+        let latest = tokio_test::block_on(latest_version(&settings, &cache)).unwrap();
+        assert_eq!(latest.as_deref(), Some("2.0.0"));
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn test_user_cache_dir() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.into())
+        };
+        assert_eq!(user_cache_dir(env(&[("XDG_CACHE_HOME", "/var/cache/u"), ("HOME", "/home/u")])), Some(PathBuf::from("/var/cache/u")));
+        // Относительный XDG_CACHE_HOME игнорируется по спецификации XDG
+        assert_eq!(user_cache_dir(env(&[("XDG_CACHE_HOME", "cache"), ("HOME", "/home/u")])), Some(PathBuf::from("/home/u/.cache")));
+        assert_eq!(user_cache_dir(env(&[("HOME", "")])), None);
+        assert_eq!(user_cache_dir(env(&[])), None);
+    }
+}
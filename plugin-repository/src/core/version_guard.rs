@@ -0,0 +1,123 @@
+//! Проверка перед деплоем: версия в имени ZIP, `<version>` в plugin.xml и публикуемый тег
+//! должны совпадать. Иначе IDE видит в индексе одну версию, а в артефакте другую, и
+//! отказывается обновлять плагин.
+
+use anyhow::Result;
+use std::path::Path;
+use tracing::info;
+
+use super::plugin_xml;
+
+/// Версия релиза из одного источника
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionEntry {
+    pub name: &'static str,
+    /// Откуда взято значение: имя файла, путь plugin.xml в архиве, тег
+    pub origin: String,
+    /// `None` — версию определить не удалось
+    pub version: Option<String>,
+}
+
+/// Версии артефакта и тега расходятся
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{}", format_conflict(.sources))]
+pub struct VersionConflict {
+    pub sources: Vec<VersionEntry>,
+}
+
+/// Извлекает версию из имени zip вида name-1.2.3.zip
+pub fn version_from_filename(filename: &str) -> Option<String> {
+    let re = regex::Regex::new(r"-(\d+\.\d+\.\d+(?:-[A-Za-z0-9.]+)*)\.zip$").ok()?;
+    re.captures(filename).map(|caps| caps[1].to_string())
+}
+
+/// Версии артефакта из всех источников; тег `v<version>` добавляется, если релиз известен
+pub fn sources(artifact: &Path, release_version: Option<&str>) -> Vec<VersionEntry> {
+    let file_name = artifact.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let mut sources = vec![VersionEntry {
+        name: "имя ZIP",
+        version: version_from_filename(&file_name),
+        origin: file_name,
+    }];
+    sources.push(match plugin_xml::read_from_artifact(artifact) {
+        Ok(xml) => VersionEntry {
+            name: "plugin.xml",
+            version: plugin_xml::version(&xml.content).ok().flatten(),
+            origin: xml.location,
+        },
+        Err(e) => VersionEntry { name: "plugin.xml", origin: e.to_string(), version: None },
+    });
+    if let Some(version) = release_version {
+        sources.push(VersionEntry { name: "git-тег", origin: format!("v{}", version), version: Some(version.to_string()) });
+    }
+    sources
+}
+
+/// Все источники должны дать одну и ту же версию
+pub fn compare(sources: Vec<VersionEntry>) -> std::result::Result<String, VersionConflict> {
+    let first = sources.first().and_then(|s| s.version.clone());
+    match first {
+        Some(version) if sources.iter().all(|s| s.version.as_ref() == Some(&version)) => Ok(version),
+        _ => Err(VersionConflict { sources }),
+    }
+}
+
+/// Проверяет артефакт перед деплоем; ошибка `VersionConflict` показывает все значения
+pub fn check(artifact: &Path, release_version: Option<&str>) -> Result<()> {
+    let version = compare(sources(artifact, release_version))?;
+    info!("🔖 Версия артефакта {} согласована: {}", artifact.display(), version);
+    Ok(())
+}
+
+fn format_conflict(sources: &[VersionEntry]) -> String {
+    // Эталон — тег (он уже выбран релизом), без тега — имя файла
+    let expected = sources.iter().rev().find_map(|s| s.version.as_deref());
+    let name_width = sources.iter().map(|s| s.name.chars().count()).max().unwrap_or(0);
+    let origin_width = sources.iter().map(|s| s.origin.chars().count()).max().unwrap_or(0);
+    let mut lines = vec!["Версии релиза не совпадают, IDE не сможет обновить плагин:".to_string()];
+    for source in sources {
+        let mark = if source.version.is_some() && source.version.as_deref() == expected { " " } else { "✘" };
+        lines.push(format!(
+            "  {} {:<name_width$}  {:<origin_width$}  {}",
+            mark,
+            source.name,
+            source.origin,
+            source.version.as_deref().unwrap_or("—"),
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn artifact(dir: &Path, file_name: &str, version: &str) -> std::path::PathBuf {
+        let path = dir.join(file_name);
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        zip.start_file("META-INF/plugin.xml", zip::write::FileOptions::default()).unwrap();
+        write!(zip, "<idea-plugin><id>ru.marslab.ide.ride</id><version>{}</version></idea-plugin>", version).unwrap();
+        zip.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_version_conflict_lists_all_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        let consistent = artifact(dir.path(), "ride-1.2.0.zip", "1.2.0");
+        assert!(check(&consistent, Some("1.2.0")).is_ok());
+        assert!(check(&consistent, None).is_ok());
+
+        let stale = artifact(dir.path(), "ride-1.2.0-rc.1.zip", "1.1.0");
+        let err = check(&stale, Some("1.2.0")).unwrap_err();
+        assert!(err.is::<VersionConflict>());
+        assert_eq!(
+            err.to_string(),
+            "Версии релиза не совпадают, IDE не сможет обновить плагин:\n  \
+             ✘ имя ZIP     ride-1.2.0-rc.1.zip  1.2.0-rc.1\n  \
+             ✘ plugin.xml  META-INF/plugin.xml  1.1.0\n  \
+             \u{20} git-тег     v1.2.0               1.2.0"
+        );
+    }
+}
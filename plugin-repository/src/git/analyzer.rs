@@ -1,8 +1,12 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use tracing::{info, debug, warn};
-use super::history::{GitHistory, GitCommit, ChangeType};
+use super::exclusions::CommitFilter;
+use super::pull_requests::PullRequestEnricher;
+use super::history::{GitHistory, GitCommit, ChangeType, LogWindow};
+use super::version_rules::{self, VersionSource};
 
 /// Анализатор изменений для определения типа и влияния коммитов
 #[derive(Debug, Clone)]
@@ -10,6 +14,8 @@ pub struct ChangeAnalyzer {
     repository_path: std::path::PathBuf,
     git_history: GitHistory,
     change_patterns: HashMap<ChangeType, Vec<String>>,
+    /// Источники явной версии по маркерам коммитов (`[[git.version_rules]]`)
+    version_sources: Vec<Arc<dyn VersionSource>>,
 }
 
 /// Детальный анализ изменений
@@ -56,6 +62,30 @@ pub enum VersionBump {
 
 impl ChangeAnalyzer {
     /// Создает новый анализатор изменений
+    /// Анализ без коммитов, исключенных фильтром
+    pub fn with_exclusions(mut self, filter: CommitFilter) -> Self {
+        self.git_history = self.git_history.with_exclusions(filter);
+        self
+    }
+
+    /// Типы изменений определяются по заголовкам PR
+    pub fn with_pull_requests(mut self, enricher: PullRequestEnricher) -> Self {
+        self.git_history = self.git_history.with_pull_requests(enricher);
+        self
+    }
+
+    /// Анализ только коммитов из окна дат и авторов
+    pub fn with_window(mut self, window: LogWindow) -> Self {
+        self.git_history = self.git_history.with_window(window);
+        self
+    }
+
+    /// Маркер версии в коммите диапазона дает `VersionBump::Custom` вместо major/minor/patch
+    pub fn with_version_sources(mut self, sources: Vec<Arc<dyn VersionSource>>) -> Self {
+        self.version_sources = sources;
+        self
+    }
+
     pub fn new<P: AsRef<std::path::Path>>(repository_path: P) -> Self {
         let path = repository_path.as_ref().to_path_buf();
         let git_history = GitHistory::new(&path);
@@ -140,6 +170,7 @@ impl ChangeAnalyzer {
             repository_path: path,
             git_history,
             change_patterns,
+            version_sources: Vec::new(),
         }
     }
 
@@ -188,7 +219,10 @@ impl ChangeAnalyzer {
             }
         }
 
-        let recommended_bump = self.recommend_version_bump(&change_summary, &breaking_changes);
+        let recommended_bump = match self.custom_version(from_ref, to_ref, &commits).await? {
+            Some(version) => VersionBump::Custom(version),
+            None => self.recommend_version_bump(&change_summary, &breaking_changes),
+        };
         let confidence = self.calculate_analysis_confidence(&change_summary, total_commits);
 
         Ok(ReleaseAnalysis {
@@ -340,6 +374,29 @@ impl ChangeAnalyzer {
         }
     }
 
+    /// Версия из маркера самого нового коммита диапазона; маркер с версией не по semver
+    /// пропускается с предупреждением
+    async fn custom_version(&self, from_ref: Option<&str>, to_ref: Option<&str>, commits: &[GitCommit]) -> Result<Option<String>> {
+        if self.version_sources.is_empty() || commits.is_empty() {
+            return Ok(None);
+        }
+        let messages = self.git_history.get_full_messages(from_ref, to_ref).await?;
+        for commit in commits {
+            let Some(message) = messages.get(&commit.hash) else { continue };
+            let Some(version) = self.version_sources.iter().find_map(|source| source.version_for(message)) else {
+                continue;
+            };
+            match version_rules::normalize(&version) {
+                Ok(version) => {
+                    info!("🏷️ Версия {} задана коммитом {}", version, commit.short_hash);
+                    return Ok(Some(version));
+                }
+                Err(e) => warn!("⚠️ Маркер версии в коммите {} пропущен: {}", commit.short_hash, e),
+            }
+        }
+        Ok(None)
+    }
+
     /// Рекомендует изменение версии
     fn recommend_version_bump(&self, change_summary: &HashMap<ChangeType, usize>, breaking_changes: &[String]) -> VersionBump {
         // Если есть критические изменения - major version
@@ -473,6 +530,9 @@ mod tests {
             files_changed: 1,
             insertions,
             deletions,
+            files: Vec::new(),
+            pull_request: None,
+            diff_summary: None,
         }
     }
 
@@ -538,4 +598,39 @@ mod tests {
         let bump = analyzer.recommend_version_bump(&change_summary, &[]);
         assert!(matches!(bump, VersionBump::Major));
     }
+
+    #[tokio::test]
+    async fn test_version_rules_force_custom_bump() {
+        use crate::config::parser::VersionRuleConfig;
+
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git").args(args).current_dir(repo).status().unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        for message in ["feat: search", "chore: prepare\n\nRelease: 3.0.0-rc1", "feat: bump\n\nRelease: next", "fix: typo"] {
+            git(&["-c", "user.name=Dev", "-c", "user.email=dev@example.com", "commit", "-q", "--allow-empty", "-m", message]);
+        }
+
+        let rules = [
+            VersionRuleConfig { trailer: Some("release".to_string()), ..Default::default() },
+            VersionRuleConfig { pattern: Some(r"^hotfix: (?P<version>\S+)".to_string()), ..Default::default() },
+        ];
+        let analyzer = ChangeAnalyzer::new(repo);
+        let plain = analyzer.analyze_changes(None, None).await.unwrap();
+        assert!(matches!(plain.recommended_version_bump, VersionBump::Minor));
+
+        // «Release: next» — не semver: срабатывает более старый маркер
+        let analyzer = analyzer.with_version_sources(version_rules::from_configs(&rules).unwrap());
+        let analysis = analyzer.analyze_changes(None, None).await.unwrap();
+        assert!(matches!(analysis.recommended_version_bump, VersionBump::Custom(ref v) if v == "3.0.0-rc1"));
+
+        let fixed = VersionRuleConfig { pattern: Some("^hotfix".to_string()), version: Some("v2.0.1".to_string()), ..Default::default() };
+        assert_eq!(version_rules::from_config(&fixed).unwrap().version_for("docs\nhotfix now").as_deref(), Some("v2.0.1"));
+        let no_group = VersionRuleConfig { pattern: Some("^release".to_string()), ..Default::default() };
+        assert!(version_rules::from_config(&no_group).unwrap_err().to_string().contains("(?P<version>...)"));
+        assert!(version_rules::from_config(&VersionRuleConfig::default()).is_err());
+    }
 }
\ No newline at end of file
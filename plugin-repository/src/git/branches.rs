@@ -0,0 +1,414 @@
+//! Ветки релизов (`release/2.1`): создание, перенос коммитов (backport) и определение линии
+//! версий, из которой выпускаются patch-релизы
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use semver::Version;
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::command::GitCommand;
+use super::exclusions::glob_regex;
+
+/// Трейлер коммита, отмечающий его для переноса в ветку релиза: `Release-Branch: 2.1`
+pub const RELEASE_BRANCH_TRAILER: &str = "Release-Branch";
+
+/// Push ветки идет по сети, как и push тега
+const PUSH_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Линия версий `major.minor`, которую обслуживает ветка релиза
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReleaseLine {
+    pub major: u64,
+    pub minor: u64,
+}
+
+impl ReleaseLine {
+    /// `2.1` или `v2.1`; полная версия (`2.1.3`) — ошибка: ветка обслуживает всю линию
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim().trim_start_matches('v');
+        let parts: Vec<&str> = value.split('.').collect();
+        let [major, minor] = parts.as_slice() else {
+            anyhow::bail!("Линия релизов задается как major.minor (например, 2.1), получено '{}'", value);
+        };
+        Ok(Self {
+            major: major.parse().with_context(|| format!("Некорректная major-версия '{}'", major))?,
+            minor: minor.parse().with_context(|| format!("Некорректная minor-версия '{}'", minor))?,
+        })
+    }
+
+    /// Линия по имени ветки: `release/2.1` при префиксе `release/`
+    pub fn from_branch(branch: &str, prefix: &str) -> Option<Self> {
+        branch.strip_prefix(prefix).and_then(|line| Self::parse(line).ok())
+    }
+
+    pub fn branch_name(&self, prefix: &str) -> String {
+        format!("{}{}", prefix, self)
+    }
+
+    pub fn contains(&self, version: &str) -> bool {
+        Version::parse(version.trim_start_matches('v')).is_ok_and(|v| v.major == self.major && v.minor == self.minor)
+    }
+
+    /// Следующая patch-версия линии; без выпущенных версий — `major.minor.0`
+    pub fn next_patch(&self, latest: Option<&str>) -> String {
+        match latest.and_then(|v| Version::parse(v.trim_start_matches('v')).ok()) {
+            Some(v) if self.contains(&v.to_string()) => format!("{}.{}.{}", self.major, self.minor, v.patch + 1),
+            _ => format!("{}.{}.0", self.major, self.minor),
+        }
+    }
+
+    /// Отмечен ли коммит для этой линии трейлером `Release-Branch: 2.1` (или `release/2.1`)
+    fn marks(&self, message: &str, prefix: &str) -> bool {
+        message.lines().filter_map(|line| line.split_once(':')).any(|(key, value)| {
+            key.trim().eq_ignore_ascii_case(RELEASE_BRANCH_TRAILER)
+                && value.split(',').any(|v| {
+                    let v = v.trim();
+                    Self::parse(v.strip_prefix(prefix).unwrap_or(v)).is_ok_and(|line| line == *self)
+                })
+        })
+    }
+}
+
+/// Как разрешен перенос коммита
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "resolution", content = "files")]
+pub enum PickResolution {
+    Clean,
+    /// Конфликт только в пробелах и отступах
+    Whitespace,
+    /// Конфликтующие файлы из `backport_keep_paths` оставлены в версии ветки
+    KeptBranchFiles(Vec<String>),
+    /// Изменения уже есть в ветке
+    AlreadyApplied,
+}
+
+/// Перенесенный коммит
+#[derive(Debug, Clone, Serialize)]
+pub struct PickedCommit {
+    pub hash: String,
+    pub subject: String,
+    #[serde(flatten)]
+    pub resolution: PickResolution,
+}
+
+impl fmt::Display for PickResolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PickResolution::Clean => write!(f, "без конфликтов"),
+            PickResolution::Whitespace => write!(f, "конфликт в пробелах разрешен"),
+            PickResolution::KeptBranchFiles(files) => write!(f, "оставлена версия ветки: {}", files.join(", ")),
+            PickResolution::AlreadyApplied => write!(f, "изменения уже есть в ветке"),
+        }
+    }
+}
+
+impl fmt::Display for ReleaseLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Что сделать при создании ветки релиза
+#[derive(Debug, Clone)]
+pub struct BranchPlan {
+    pub branch: String,
+    /// Коммит, от которого создается ветка
+    pub base: String,
+    /// Ref, указанный пользователем (тег, ветка, коммит)
+    pub base_ref: String,
+    /// Коммиты для cherry-pick в порядке применения: (hash, тема)
+    pub picks: Vec<(String, String)>,
+}
+
+/// Операции с ветками релизов
+#[derive(Debug, Clone)]
+pub struct ReleaseBranches {
+    repository_path: PathBuf,
+    prefix: String,
+}
+
+impl ReleaseBranches {
+    pub fn new<P: AsRef<Path>>(repository_path: P, prefix: &str) -> Self {
+        Self { repository_path: repository_path.as_ref().to_path_buf(), prefix: prefix.to_string() }
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Линия релизов текущей ветки; None — не ветка релиза (или detached HEAD)
+    pub async fn current_line(&self) -> Result<Option<ReleaseLine>> {
+        let output = self.git(["symbolic-ref", "--quiet", "--short", "HEAD"]).output().await?;
+        if !output.success() {
+            return Ok(None);
+        }
+        Ok(ReleaseLine::from_branch(output.stdout.trim(), &self.prefix))
+    }
+
+    /// Последний тег линии, достижимый из HEAD: база для changelog следующего patch-релиза
+    pub async fn latest_tag(&self, line: ReleaseLine, tag_prefix: &str) -> Result<Option<String>> {
+        let pattern = format!("{}{}.*", tag_prefix, line);
+        let tags = self
+            .git(["tag", "--merged", "HEAD", "--list", pattern.as_str(), "--sort=-v:refname"])
+            .run()
+            .await
+            .context("Не удалось получить теги линии релизов")?;
+        Ok(tags
+            .lines()
+            .map(str::trim)
+            .find(|tag| tag.strip_prefix(tag_prefix).is_some_and(|v| line.contains(v)))
+            .map(str::to_string))
+    }
+
+    /// План ветки: база и коммиты для переноса — явно указанные и отмеченные трейлером
+    /// в `base..main_branch`
+    pub async fn plan(&self, line: ReleaseLine, base_ref: &str, main_branch: &str, picks: &[String], pick_marked: bool) -> Result<BranchPlan> {
+        let branch = line.branch_name(&self.prefix);
+        if self.resolve(&format!("refs/heads/{}", branch)).await.is_ok() {
+            anyhow::bail!("Ветка {} уже существует", branch);
+        }
+        let base = self
+            .resolve(&format!("{}^{{commit}}", base_ref))
+            .await
+            .with_context(|| format!("Ref '{}' не найден", base_ref))?;
+
+        let mut plan_picks = Vec::new();
+        for pick in picks {
+            let hash = self.resolve(&format!("{}^{{commit}}", pick)).await.with_context(|| format!("Коммит '{}' не найден", pick))?;
+            let subject = self.git(["log", "-1", "--format=%s", hash.as_str()]).run().await?.trim().to_string();
+            plan_picks.push((hash, subject));
+        }
+        if pick_marked {
+            let range = format!("{}..{}", base, main_branch);
+            let log = self
+                .git(["log", "--reverse", "--format=%H%x1f%s%x1f%B%x1e", range.as_str()])
+                .run()
+                .await
+                .with_context(|| format!("Не удалось прочитать историю {}", range))?;
+            for record in log.split('\x1e').map(str::trim).filter(|r| !r.is_empty()) {
+                let mut fields = record.splitn(3, '\x1f');
+                let (Some(hash), Some(subject), Some(body)) = (fields.next(), fields.next(), fields.next()) else {
+                    continue;
+                };
+                if line.marks(body, &self.prefix) && !plan_picks.iter().any(|(h, _)| h == hash) {
+                    plan_picks.push((hash.to_string(), subject.to_string()));
+                }
+            }
+        }
+
+        Ok(BranchPlan { branch, base, base_ref: base_ref.to_string(), picks: plan_picks })
+    }
+
+    /// Создает ветку, переключается на нее и переносит коммиты плана.
+    /// При конфликте cherry-pick отменяется, ветка остается с уже перенесенными коммитами
+    pub async fn create(&self, plan: &BranchPlan) -> Result<()> {
+        self.ensure_clean("созданием ветки релиза").await?;
+        self.git(["checkout", "-b", plan.branch.as_str(), plan.base.as_str()])
+            .run()
+            .await
+            .with_context(|| format!("Не удалось создать ветку {}", plan.branch))?;
+        info!("🌿 Создана ветка {} от {}", plan.branch, plan.base_ref);
+
+        self.pick_all(&plan.branch, &plan.picks, &[]).await?;
+        Ok(())
+    }
+
+    /// Переносит коммиты в существующую ветку релиза; ветка остается текущей для сборки и релиза.
+    /// `keep_paths` — glob-шаблоны файлов, конфликты в которых разрешаются в пользу ветки
+    pub async fn backport(&self, branch: &str, commits: &[String], keep_paths: &[String]) -> Result<Vec<PickedCommit>> {
+        self.ensure_clean("переносом коммитов").await?;
+        let mut picks = Vec::new();
+        for commit in commits {
+            let hash = self.resolve(&format!("{}^{{commit}}", commit)).await.with_context(|| format!("Коммит '{}' не найден", commit))?;
+            let subject = self.git(["log", "-1", "--format=%s", hash.as_str()]).run().await?.trim().to_string();
+            picks.push((hash, subject));
+        }
+        // Локальной ветки может не быть: checkout создаст ее из origin/<branch>
+        self.git(["checkout", branch])
+            .run()
+            .await
+            .with_context(|| format!("Ветка {} не найдена: создайте ее командой release branch", branch))?;
+        info!("🌿 Текущая ветка: {}", branch);
+
+        let keep_paths: Vec<Regex> = keep_paths.iter().map(|p| glob_regex(p)).collect();
+        self.pick_all(branch, &picks, &keep_paths).await
+    }
+
+    async fn pick_all(&self, branch: &str, picks: &[(String, String)], keep_paths: &[Regex]) -> Result<Vec<PickedCommit>> {
+        let mut picked = Vec::new();
+        for (index, (hash, subject)) in picks.iter().enumerate() {
+            let short = &hash[..hash.len().min(8)];
+            let resolution = self.cherry_pick(hash, keep_paths).await.with_context(|| {
+                format!(
+                    "Перенос {} «{}» ({} из {}) не выполнен. В ветке {} перенесено коммитов: {}; перенесите остальные вручную",
+                    short,
+                    subject,
+                    index + 1,
+                    picks.len(),
+                    branch,
+                    index
+                )
+            })?;
+            match &resolution {
+                PickResolution::AlreadyApplied => warn!("⚠️ {} {} пропущен: {}", short, subject, resolution),
+                _ => info!("🍒 Перенесен {} {} ({})", short, subject, resolution),
+            }
+            picked.push(PickedCommit { hash: hash.clone(), subject: subject.clone(), resolution });
+        }
+        Ok(picked)
+    }
+
+    /// cherry-pick с разрешением тривиальных конфликтов; остальные конфликты отменяются
+    async fn cherry_pick(&self, hash: &str, keep_paths: &[Regex]) -> Result<PickResolution> {
+        let output = self.git(["cherry-pick", "-x", hash]).output().await?;
+        if output.success() {
+            return Ok(PickResolution::Clean);
+        }
+        if output.stderr.contains("now empty") {
+            self.git(["cherry-pick", "--skip"]).run().await?;
+            return Ok(PickResolution::AlreadyApplied);
+        }
+
+        // Повтор без учета пробелов: конфликты из-за переформатирования
+        let _ = self.git(["cherry-pick", "--abort"]).output().await;
+        let output = self.git(["cherry-pick", "-x", "--strategy-option=ignore-all-space", hash]).output().await?;
+        if output.success() {
+            return Ok(PickResolution::Whitespace);
+        }
+        if output.stderr.contains("now empty") {
+            self.git(["cherry-pick", "--skip"]).run().await?;
+            return Ok(PickResolution::AlreadyApplied);
+        }
+
+        let conflicted: Vec<String> = self
+            .git(["diff", "--name-only", "--diff-filter=U"])
+            .run()
+            .await?
+            .lines()
+            .map(str::to_string)
+            .collect();
+        let trivial = !conflicted.is_empty() && conflicted.iter().all(|file| keep_paths.iter().any(|re| re.is_match(file)));
+        if trivial {
+            self.git(["checkout", "--ours", "--"]).args(&conflicted).run().await?;
+            self.git(["add", "--"]).args(&conflicted).run().await?;
+            // Кроме оставленных файлов коммит ничего не менял
+            if self.git(["diff", "--cached", "--quiet"]).output().await?.success() {
+                self.git(["cherry-pick", "--skip"]).run().await?;
+                return Ok(PickResolution::AlreadyApplied);
+            }
+            self.git(["commit", "--no-edit"]).run().await?;
+            return Ok(PickResolution::KeptBranchFiles(conflicted));
+        }
+
+        let _ = self.git(["cherry-pick", "--abort"]).output().await;
+        if conflicted.is_empty() {
+            anyhow::bail!("{}", output.stderr.trim());
+        }
+        anyhow::bail!("конфликт в {}", conflicted.join(", "))
+    }
+
+    async fn ensure_clean(&self, action: &str) -> Result<()> {
+        let status = self.git(["status", "--porcelain", "--untracked-files=no"]).run().await?;
+        if !status.trim().is_empty() {
+            anyhow::bail!("Рабочая директория не чиста: закоммитьте или спрячьте изменения перед {}", action);
+        }
+        Ok(())
+    }
+
+    /// Push ветки с upstream: следующие `release` на ней выпускают patch-версии линии
+    pub async fn push(&self, branch: &str, remote: &str) -> Result<()> {
+        let output = self.git(["push", "-u", remote, branch]).timeout(PUSH_TIMEOUT).output().await?;
+        if !output.success() {
+            warn!("⚠️ Push ветки {} не выполнен", branch);
+            anyhow::bail!("Git ошибка push ветки {}: {}", branch, output.stderr.trim());
+        }
+        info!("📤 Ветка {} отправлена в {}", branch, remote);
+        Ok(())
+    }
+
+    async fn resolve(&self, rev: &str) -> Result<String> {
+        Ok(self.git(["rev-parse", "--verify", "--quiet", rev]).run().await?.trim().to_string())
+    }
+
+    fn git<I, S>(&self, args: I) -> GitCommand
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        GitCommand::new(&self.repository_path).args(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn git(repo: &Path, args: &[&str]) {
+        GitCommand::new(repo).args(args).run().await.unwrap();
+    }
+
+    async fn commit(repo: &Path, message: &str, file: &str) {
+        std::fs::write(repo.join(file), message).unwrap();
+        git(repo, &["add", "-A"]).await;
+        git(repo, &["commit", "-qm", message]).await;
+    }
+
+    #[tokio::test]
+    async fn test_release_line_and_branch() {
+        let line = ReleaseLine::parse("v2.1").unwrap();
+        assert_eq!(line.branch_name("release/"), "release/2.1");
+        assert_eq!(ReleaseLine::from_branch("release/2.1", "release/"), Some(line));
+        assert_eq!(ReleaseLine::from_branch("main", "release/"), None);
+        assert!(ReleaseLine::parse("2.1.3").is_err());
+        assert!(line.contains("2.1.7") && !line.contains("2.10.0"));
+        assert_eq!(line.next_patch(Some("v2.1.4")), "2.1.5");
+        assert_eq!(line.next_patch(Some("2.2.0")), "2.1.0");
+        assert_eq!(line.next_patch(None), "2.1.0");
+        assert!(line.marks("fix: crash\n\nRelease-Branch: 2.0, release/2.1", "release/"));
+        assert!(!line.marks("fix: crash\n\nRelease-Branch: 2.2", "release/"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        git(repo, &["init", "-q", "-b", "main"]).await;
+        git(repo, &["config", "user.name", "t"]).await;
+        git(repo, &["config", "user.email", "t@t"]).await;
+        commit(repo, "feat: chat", "a.txt").await;
+        git(repo, &["tag", "v2.1.0"]).await;
+        commit(repo, "feat: 2.2 work", "b.txt").await;
+        commit(repo, "fix: crash\n\nRelease-Branch: 2.1", "c.txt").await;
+
+        let branches = ReleaseBranches::new(repo, "release/");
+        let plan = branches.plan(line, "v2.1.0", "main", &[], true).await.unwrap();
+        assert_eq!(plan.picks.iter().map(|(_, s)| s.as_str()).collect::<Vec<_>>(), ["fix: crash"]);
+        branches.create(&plan).await.unwrap();
+        assert_eq!(branches.current_line().await.unwrap(), Some(line));
+        assert_eq!(branches.latest_tag(line, "v").await.unwrap().as_deref(), Some("v2.1.0"));
+        assert!(!repo.join("b.txt").exists() && repo.join("c.txt").exists());
+        assert!(branches.plan(line, "v2.1.0", "main", &[], false).await.unwrap_err().to_string().contains("уже существует"));
+
+        // Backport: конфликт в CHANGELOG.md решается в пользу ветки, конфликт в коде — отмена
+        commit(repo, "## 2.1.1", "CHANGELOG.md").await;
+        commit(repo, "fix: branch-only", "a.txt").await;
+        git(repo, &["checkout", "-q", "main"]).await;
+        std::fs::write(repo.join("c.txt"), "fix: crash v2").unwrap();
+        commit(repo, "## 2.2.0", "CHANGELOG.md").await;
+        let fix = GitCommand::new(repo).args(["rev-parse", "HEAD"]).run().await.unwrap().trim().to_string();
+        commit(repo, "fix: other", "a.txt").await;
+        let other = GitCommand::new(repo).args(["rev-parse", "HEAD"]).run().await.unwrap().trim().to_string();
+        let keep = ["CHANGELOG.md".to_string()];
+
+        let picked = branches.backport("release/2.1", std::slice::from_ref(&fix), &keep).await.unwrap();
+        assert_eq!(picked[0].resolution, PickResolution::KeptBranchFiles(vec!["CHANGELOG.md".to_string()]));
+        assert_eq!(std::fs::read_to_string(repo.join("CHANGELOG.md")).unwrap(), "## 2.1.1");
+        assert_eq!(std::fs::read_to_string(repo.join("c.txt")).unwrap(), "fix: crash v2");
+
+        let err = branches.backport("release/2.1", &[other], &keep).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("конфликт в a.txt"));
+        let status = GitCommand::new(repo).args(["status", "--porcelain"]).run().await.unwrap();
+        assert!(status.trim().is_empty());
+    }
+}
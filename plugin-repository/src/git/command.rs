@@ -0,0 +1,149 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+use tracing::debug;
+
+/// Таймаут git-команды по умолчанию
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+/// Лимит stdout/stderr (байт): защищает от неограниченного роста памяти на огромных историях
+pub const MAX_OUTPUT: usize = 64 * 1024 * 1024;
+
+/// Асинхронный запуск git: tokio::process, таймаут, контролируемое окружение и лимит вывода.
+/// Не блокирует runtime, как `std::process::Command` внутри async fn.
+#[derive(Debug, Clone)]
+pub struct GitCommand {
+    repository_path: PathBuf,
+    args: Vec<String>,
+    timeout: Duration,
+}
+
+/// Вывод завершившейся git-команды
+#[derive(Debug, Clone)]
+pub struct GitOutput {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl GitOutput {
+    pub fn success(&self) -> bool {
+        self.status.success()
+    }
+}
+
+impl GitCommand {
+    pub fn new<P: AsRef<Path>>(repository_path: P) -> Self {
+        Self {
+            repository_path: repository_path.as_ref().to_path_buf(),
+            args: Vec::new(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.args.extend(args.into_iter().map(|a| a.as_ref().to_string()));
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Выполняет команду; ненулевой код выхода ошибкой не считается
+    pub async fn output(&self) -> Result<GitOutput> {
+        debug!("git {}", self.args.join(" "));
+        let mut command = Command::new("git");
+        command
+            .current_dir(&self.repository_path)
+            .args(&self.args)
+            // Вывод разбирается программно: без локализации, пейджера и интерактивных запросов
+            .env("LC_ALL", "C")
+            .env("GIT_PAGER", "cat")
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("Не удалось запустить git {}", self.args.join(" ")))?;
+        let stdout = child.stdout.take().context("stdout git недоступен")?;
+        let stderr = child.stderr.take().context("stderr git недоступен")?;
+
+        let run = async {
+            let (stdout, stderr) = tokio::join!(read_capped(stdout, MAX_OUTPUT), read_capped(stderr, MAX_OUTPUT));
+            let status = child.wait().await?;
+            Ok::<_, std::io::Error>((status, stdout?, stderr?))
+        };
+        // По таймауту future сбрасывается, и kill_on_drop завершает процесс
+        let (status, (stdout, stdout_truncated), (stderr, _)) = tokio::time::timeout(self.timeout, run)
+            .await
+            .map_err(|_| anyhow::anyhow!("git {} не завершился за {:?}", self.args.join(" "), self.timeout))?
+            .with_context(|| format!("Ошибка выполнения git {}", self.args.join(" ")))?;
+
+        if stdout_truncated {
+            anyhow::bail!(
+                "Вывод git {} превысил лимит {} байт",
+                self.args.join(" "),
+                MAX_OUTPUT
+            );
+        }
+
+        Ok(GitOutput { status, stdout, stderr })
+    }
+
+    /// Выполняет команду и возвращает stdout; ненулевой код выхода — ошибка с текстом stderr
+    pub async fn run(&self) -> Result<String> {
+        let output = self.output().await?;
+        if !output.success() {
+            anyhow::bail!("git {} завершился с ошибкой: {}", self.args.join(" "), output.stderr.trim());
+        }
+        Ok(output.stdout)
+    }
+}
+
+/// Читает поток до конца, сохраняя не более `limit` байт; остаток вычитывается, чтобы git не блокировался на pipe
+async fn read_capped<R: AsyncRead + Unpin>(mut reader: R, limit: usize) -> std::io::Result<(String, bool)> {
+    let mut kept = Vec::new();
+    let mut truncated = false;
+    let mut chunk = [0u8; 8192];
+    loop {
+        let n = reader.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        let room = limit.saturating_sub(kept.len());
+        kept.extend_from_slice(&chunk[..n.min(room)]);
+        truncated |= n > room;
+    }
+    Ok((String::from_utf8_lossy(&kept).to_string(), truncated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_output_limits_and_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        GitCommand::new(dir.path()).args(["init", "-q"]).run().await.unwrap();
+
+        let branch = GitCommand::new(dir.path()).args(["symbolic-ref", "--short", "HEAD"]).run().await.unwrap();
+        assert!(!branch.trim().is_empty());
+
+        let err = GitCommand::new(dir.path()).args(["rev-parse", "--verify", "missing-ref"]).run().await.unwrap_err();
+        assert!(err.to_string().contains("git rev-parse --verify missing-ref завершился с ошибкой"));
+
+        let (kept, truncated) = read_capped(&b"0123456789"[..], 4).await.unwrap();
+        assert_eq!((kept.as_str(), truncated), ("0123", true));
+    }
+}
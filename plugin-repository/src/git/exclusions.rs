@@ -0,0 +1,172 @@
+//! Исключение коммитов из анализа и changelog: боты, служебные сообщения, правки только lock-файлов
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use tracing::info;
+
+use super::history::GitCommit;
+use crate::config::parser::ChangelogExcludeConfig;
+
+/// Почему коммит исключен
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExclusionReason {
+    Author,
+    Message,
+    /// Все измененные файлы совпали с `paths`
+    Paths,
+}
+
+/// Скомпилированные правила `[changelog.exclude]`
+#[derive(Debug, Clone)]
+pub struct CommitFilter {
+    authors: Vec<Regex>,
+    messages: Vec<Regex>,
+    paths: Vec<Regex>,
+}
+
+impl CommitFilter {
+    pub fn new(config: &ChangelogExcludeConfig) -> Result<Self> {
+        let compile = |patterns: &[String], key: &str, case_insensitive: bool| -> Result<Vec<Regex>> {
+            patterns
+                .iter()
+                .map(|p| {
+                    let pattern = if case_insensitive { format!("(?i){}", p) } else { p.clone() };
+                    Regex::new(&pattern).with_context(|| format!("[changelog.exclude] {}: некорректное регулярное выражение '{}'", key, p))
+                })
+                .collect()
+        };
+        Ok(Self {
+            authors: compile(&config.authors, "authors", true)?,
+            messages: compile(&config.messages, "messages", false)?,
+            paths: config.paths.iter().map(|p| glob_regex(p)).collect(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.authors.is_empty() && self.messages.is_empty() && self.paths.is_empty()
+    }
+
+    pub fn reason(&self, commit: &GitCommit) -> Option<ExclusionReason> {
+        let author = format!("{} <{}>", commit.author, commit.email);
+        if self.authors.iter().any(|re| re.is_match(&author)) {
+            return Some(ExclusionReason::Author);
+        }
+        if self.messages.iter().any(|re| re.is_match(&commit.message)) {
+            return Some(ExclusionReason::Message);
+        }
+        // Коммит без файлов (merge, пустой) по путям не исключается
+        if !self.paths.is_empty()
+            && !commit.files.is_empty()
+            && commit.files.iter().all(|file| self.paths.iter().any(|re| re.is_match(file)))
+        {
+            return Some(ExclusionReason::Paths);
+        }
+        None
+    }
+
+    /// Оставляет коммиты, не попавшие под правила; итог исключения пишется в лог
+    pub fn apply(&self, commits: Vec<GitCommit>) -> Vec<GitCommit> {
+        if self.is_empty() {
+            return commits;
+        }
+        let mut kept = Vec::with_capacity(commits.len());
+        let mut excluded = [0usize; 3];
+        for commit in commits {
+            match self.reason(&commit) {
+                Some(reason) => excluded[reason as usize] += 1,
+                None => kept.push(commit),
+            }
+        }
+        let [by_author, by_message, by_paths] = excluded;
+        if by_author + by_message + by_paths > 0 {
+            info!(
+                "🚫 Исключено коммитов: {} (автор: {}, сообщение: {}, только служебные файлы: {})",
+                by_author + by_message + by_paths,
+                by_author,
+                by_message,
+                by_paths
+            );
+        }
+        kept
+    }
+}
+
+/// Glob в регулярное выражение: `*` и `?` в пределах сегмента, `**/` — любое число каталогов.
+/// Шаблон без `/` совпадает с именем файла в любом каталоге, как в .gitignore
+pub(crate) fn glob_regex(glob: &str) -> Regex {
+    let glob = glob.trim().trim_start_matches("./");
+    let anchored = glob.contains('/');
+    let mut pattern = String::from(if anchored { "^" } else { "(^|/)" });
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    pattern.push_str("(.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            other => pattern.push_str(&regex::escape(&other.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).expect("escaped glob is a valid regex")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn commit(author: &str, message: &str, files: &[&str]) -> GitCommit {
+        GitCommit {
+            hash: message.to_string(),
+            short_hash: message.to_string(),
+            message: message.to_string(),
+            author: author.to_string(),
+            email: format!("{}@users.noreply.github.com", author),
+            date: Utc::now(),
+            files_changed: files.len() as u32,
+            insertions: 1,
+            deletions: 0,
+            files: files.iter().map(|f| f.to_string()).collect(),
+            pull_request: None,
+            diff_summary: None,
+        }
+    }
+
+    #[test]
+    fn test_commit_filter() {
+        let filter = CommitFilter::new(&ChangelogExcludeConfig {
+            authors: vec!["dependabot".to_string(), r"renovate\[bot\]".to_string()],
+            messages: vec![r"^chore\(deps\)".to_string(), "^Merge branch".to_string()],
+            paths: vec!["*.lock".to_string(), "gradle/**/*.toml".to_string(), "package-lock.json".to_string()],
+        })
+        .unwrap();
+
+        let bump = commit("Dependabot[bot]", "Bump kotlin from 1.9 to 2.0", &["build.gradle.kts"]);
+        assert_eq!(filter.reason(&bump), Some(ExclusionReason::Author));
+        assert_eq!(filter.reason(&commit("renovate[bot]", "Update deps", &[])), Some(ExclusionReason::Author));
+        assert_eq!(filter.reason(&commit("alice", "chore(deps): bump", &["a.kt"])), Some(ExclusionReason::Message));
+        assert_eq!(
+            filter.reason(&commit("alice", "Update lockfiles", &["yarn.lock", "web/Cargo.lock", "gradle/libs.versions.toml", "ui/package-lock.json"])),
+            Some(ExclusionReason::Paths)
+        );
+        assert_eq!(filter.reason(&commit("alice", "feat: chat", &["yarn.lock", "src/Chat.kt"])), None);
+        assert_eq!(filter.reason(&commit("alice", "feat: empty", &[])), None);
+        assert_eq!(filter.reason(&commit("alice", "fix: build", &["src/gradle/x.toml"])), None);
+
+        let kept = filter.apply(vec![bump, commit("alice", "feat: chat", &["src/Chat.kt"])]);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].message, "feat: chat");
+
+        assert!(CommitFilter::new(&ChangelogExcludeConfig::default()).unwrap().is_empty());
+        let invalid = ChangelogExcludeConfig { messages: vec!["(".to_string()], ..Default::default() };
+        assert!(CommitFilter::new(&invalid).unwrap_err().to_string().contains("messages"));
+    }
+}
@@ -1,9 +1,13 @@
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::path::Path;
-use std::process::Command;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use tracing::{info, debug, warn};
+use tracing::{info, debug, warn, instrument};
+use super::command::GitCommand;
+use super::exclusions::CommitFilter;
+use super::pull_requests::{PullRequest, PullRequestEnricher};
+use crate::config::parser::OutputLanguage;
 
 /// Модель git коммита
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,12 +21,105 @@ pub struct GitCommit {
     pub files_changed: u32,
     pub insertions: u32,
     pub deletions: u32,
+    /// Измененные файлы из `--numstat`
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// PR/MR, на который ссылается коммит (`[pull_requests]`)
+    #[serde(default)]
+    pub pull_request: Option<PullRequest>,
+    /// Что меняет коммит по его diff (`[changelog.diff_summary]`)
+    #[serde(default)]
+    pub diff_summary: Option<String>,
+}
+
+impl GitCommit {
+    /// Строка для LLM: тема, а при наличии PR — его номер, метки и описание;
+    /// описание diff — отдельной строкой
+    pub fn changelog_input(&self) -> String {
+        let mut input = self.message.clone();
+        if let Some(pr) = &self.pull_request {
+            input.push_str(&format!(" (PR #{}", pr.number));
+            if !pr.labels.is_empty() {
+                input.push_str(&format!(", метки: {}", pr.labels.join(", ")));
+            }
+            input.push(')');
+            if let Some(body) = &pr.body {
+                for line in body.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                    input.push_str(&format!("\n    > {}", line));
+                }
+            }
+        }
+        if let Some(summary) = &self.diff_summary {
+            input.push_str(&format!("\n    Изменения в коде: {}", summary));
+        }
+        input
+    }
+}
+
+/// Окно истории для дайджестов: даты коммита и авторы (`git log --since/--before/--author`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogWindow {
+    /// Коммиты начиная с этого дня
+    pub since: Option<NaiveDate>,
+    /// Коммиты до начала этого дня (не включая его)
+    pub until: Option<NaiveDate>,
+    /// Подстроки или регулярные выражения автора; коммит подходит под любое из них
+    pub authors: Vec<String>,
+}
+
+impl LogWindow {
+    pub fn is_empty(&self) -> bool {
+        self.since.is_none() && self.until.is_none() && self.authors.is_empty()
+    }
+
+    /// Окно задано датами — диапазон тегов по умолчанию не нужен
+    pub fn has_dates(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let (Some(since), Some(until)) = (self.since, self.until) {
+            if since >= until {
+                anyhow::bail!("Начало окна {} должно быть раньше конца {}", since, until);
+            }
+        }
+        Ok(())
+    }
+
+    /// Аргументы `git log`; границы — полночь по локальному времени
+    pub fn git_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(since) = self.since {
+            args.push(format!("--since={} 00:00:00", since));
+        }
+        if let Some(until) = self.until {
+            args.push(format!("--before={} 00:00:00", until));
+        }
+        args.extend(self.authors.iter().map(|author| format!("--author={}", author)));
+        args
+    }
+}
+
+/// Диапазон `git log` между двумя точками; без границ — вся история до HEAD
+fn log_range(from_ref: Option<&str>, to_ref: Option<&str>) -> String {
+    match (from_ref, to_ref) {
+        (Some(from), Some(to)) => format!("{}..{}", from, to),
+        (Some(from), None) => format!("{}..HEAD", from),
+        (None, Some(to)) => format!("HEAD..{}", to),
+        (None, None) => "HEAD".to_string(),
+    }
 }
 
 /// Анализатор git истории
 #[derive(Debug, Clone)]
 pub struct GitHistory {
     repository_path: std::path::PathBuf,
+    /// Правила исключения коммитов из анализа и changelog (`[changelog.exclude]`)
+    exclusions: Option<CommitFilter>,
+    /// Данные PR вместо тем коммитов
+    pull_requests: Option<PullRequestEnricher>,
+    /// Ограничение истории по датам и авторам
+    window: LogWindow,
 }
 
 impl GitHistory {
@@ -30,69 +127,119 @@ impl GitHistory {
     pub fn new<P: AsRef<Path>>(repository_path: P) -> Self {
         Self {
             repository_path: repository_path.as_ref().to_path_buf(),
+            exclusions: None,
+            pull_requests: None,
+            window: LogWindow::default(),
         }
     }
 
+    /// Подставлять заголовки, метки и описания PR в коммиты со ссылкой на PR
+    pub fn with_pull_requests(mut self, enricher: PullRequestEnricher) -> Self {
+        self.pull_requests = Some(enricher);
+        self
+    }
+
+    /// Исключать коммиты ботов, служебные коммиты и правки только lock-файлов
+    pub fn with_exclusions(mut self, filter: CommitFilter) -> Self {
+        self.exclusions = Some(filter);
+        self
+    }
+
+    /// Учитывать только коммиты из окна дат и от указанных авторов
+    pub fn with_window(mut self, window: LogWindow) -> Self {
+        self.window = window;
+        self
+    }
+
     /// Получает историю коммитов между двумя точками
+    #[instrument(name = "git.log", skip(self))]
     pub async fn get_commits_between(&self, from_ref: Option<&str>, to_ref: Option<&str>) -> Result<Vec<GitCommit>> {
         info!("📜 Получение истории коммитов между {:?} и {:?}", from_ref, to_ref);
 
-        let range = match (from_ref, to_ref) {
-            (Some(from), Some(to)) => format!("{}..{}", from, to),
-            (Some(from), None) => format!("{}..HEAD", from),
-            (None, Some(to)) => format!("HEAD..{}", to),
-            (None, None) => "HEAD".to_string(),
-        };
+        let range = log_range(from_ref, to_ref);
 
-        debug!("Диапазон коммитов: {}", range);
+        debug!("Диапазон коммитов: {} {:?}", range, self.window);
 
-        let output = Command::new("git")
-            .current_dir(&self.repository_path)
-            .args(&["log", "--pretty=format:%H|%h|%s|%an|%ae|%ai", "--numstat", &range])
+        let output = GitCommand::new(&self.repository_path)
+            .args(["log", "--pretty=format:%H|%h|%s|%an|%ae|%ai", "--numstat"])
+            .args(self.window.git_args())
+            .args([&range])
             .output()
+            .await
             .context("Ошибка выполнения git log")?;
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
+        if !output.success() {
+            let error_msg = output.stderr;
             return Err(anyhow::anyhow!(
                 "Git log завершился с ошибкой: {}",
                 error_msg
             ));
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let commits = self.parse_git_log(&stdout)?;
+        let stdout = output.stdout;
+        let mut commits = self.parse_git_log(&stdout)?;
+        if let Some(enricher) = &self.pull_requests {
+            enricher.enrich(&mut commits).await;
+        }
 
         info!("Получено {} коммитов", commits.len());
         Ok(commits)
     }
 
+    /// Полные сообщения (тема и тело с трейлерами) коммитов диапазона: полный hash -> сообщение
+    #[instrument(name = "git.log", skip(self))]
+    pub async fn get_full_messages(&self, from_ref: Option<&str>, to_ref: Option<&str>) -> Result<HashMap<String, String>> {
+        let output = GitCommand::new(&self.repository_path)
+            .args(["log", "--format=%H%x1f%B%x1e"])
+            .args(self.window.git_args())
+            .args([&log_range(from_ref, to_ref)])
+            .output()
+            .await
+            .context("Ошибка выполнения git log")?;
+
+        if !output.success() {
+            return Err(anyhow::anyhow!("Git log завершился с ошибкой: {}", output.stderr));
+        }
+
+        Ok(output.stdout
+            .split('\x1e')
+            .filter_map(|record| record.trim().split_once('\x1f'))
+            .map(|(hash, message)| (hash.to_string(), message.trim().to_string()))
+            .collect())
+    }
+
     /// Получает последние N коммитов
+    #[instrument(name = "git.log", skip(self))]
     pub async fn get_recent_commits(&self, limit: u32) -> Result<Vec<GitCommit>> {
         info!("📜 Получение последних {} коммитов", limit);
 
-        let output = Command::new("git")
-            .current_dir(&self.repository_path)
-            .args(&["log", "--pretty=format:%H|%h|%s|%an|%ae|%ai", "--numstat", &format!("-{}", limit)])
+        let output = GitCommand::new(&self.repository_path)
+            .args(["log", "--pretty=format:%H|%h|%s|%an|%ae|%ai", "--numstat", &format!("-{}", limit)])
+            .args(self.window.git_args())
             .output()
+            .await
             .context("Ошибка выполнения git log")?;
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
+        if !output.success() {
+            let error_msg = output.stderr;
             return Err(anyhow::anyhow!(
                 "Git log завершился с ошибкой: {}",
                 error_msg
             ));
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let commits = self.parse_git_log(&stdout)?;
+        let stdout = output.stdout;
+        let mut commits = self.parse_git_log(&stdout)?;
+        if let Some(enricher) = &self.pull_requests {
+            enricher.enrich(&mut commits).await;
+        }
 
         info!("Получено {} коммитов", commits.len());
         Ok(commits)
     }
 
     /// Получает коммиты, изменяющие определённые файлы
+    #[instrument(name = "git.log", skip(self))]
     pub async fn get_commits_for_files(&self, file_patterns: &[&str]) -> Result<Vec<GitCommit>> {
         info!("📜 Получение коммитов для файлов: {:?}", file_patterns);
 
@@ -105,21 +252,21 @@ impl GitHistory {
 
         args.extend(file_patterns.iter());
 
-        let output = Command::new("git")
-            .current_dir(&self.repository_path)
+        let output = GitCommand::new(&self.repository_path)
             .args(&args)
             .output()
+            .await
             .context("Ошибка выполнения git log")?;
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
+        if !output.success() {
+            let error_msg = output.stderr;
             return Err(anyhow::anyhow!(
                 "Git log завершился с ошибкой: {}",
                 error_msg
             ));
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = output.stdout;
         let commits = self.parse_git_log(&stdout)?;
 
         info!("Получено {} коммитов для файлов", commits.len());
@@ -161,6 +308,9 @@ impl GitHistory {
                         files_changed: 0,
                         insertions: 0,
                         deletions: 0,
+                        files: Vec::new(),
+                        pull_request: None,
+                        diff_summary: None,
                     });
                 }
             } else if let Some(ref mut commit) = current_commit {
@@ -169,6 +319,9 @@ impl GitHistory {
                     commit.insertions += insertions;
                     commit.deletions += deletions;
                     commit.files_changed += 1;
+                    if let Some(path) = line.splitn(3, '\t').nth(2) {
+                        commit.files.push(path.to_string());
+                    }
                 }
             }
         }
@@ -178,7 +331,10 @@ impl GitHistory {
             commits.push(commit);
         }
 
-        Ok(commits)
+        match &self.exclusions {
+            Some(filter) => Ok(filter.apply(commits)),
+            None => Ok(commits),
+        }
     }
 
     /// Парсит строку статистики файлов
@@ -223,40 +379,107 @@ impl GitHistory {
         Ok(changelog)
     }
 
+    /// Diff содержимого между двумя ref (`git diff from..to`), без цвета и внешних diff-программ
+    #[instrument(name = "git.diff", skip(self))]
+    pub async fn get_diff(&self, from: &str, to: &str) -> Result<String> {
+        let output = GitCommand::new(&self.repository_path)
+            .args(["diff", "--no-color", "--no-ext-diff", &format!("{}..{}", from, to)])
+            .output()
+            .await
+            .context("Ошибка выполнения git diff")?;
+
+        if !output.success() {
+            return Err(anyhow::anyhow!("Git diff завершился с ошибкой: {}", output.stderr.trim()));
+        }
+        Ok(output.stdout)
+    }
+
+    /// Изменения одного коммита: `--stat` и патч без цвета; у merge-коммита — относительно первого родителя
+    #[instrument(name = "git.show", skip(self))]
+    pub async fn get_commit_diff(&self, hash: &str) -> Result<String> {
+        let output = GitCommand::new(&self.repository_path)
+            .args(["show", "--no-color", "--no-ext-diff", "--first-parent", "-m", "--stat", "--patch", "--format=", hash])
+            .output()
+            .await
+            .context("Ошибка выполнения git show")?;
+
+        if !output.success() {
+            return Err(anyhow::anyhow!("Git show завершился с ошибкой: {}", output.stderr.trim()));
+        }
+        Ok(output.stdout)
+    }
+
+    /// Получает список измененных файлов для коммитов: полный hash -> пути
+    #[instrument(name = "git.log", skip(self))]
+    pub async fn get_commit_files(&self, from_ref: Option<&str>, limit: usize) -> Result<HashMap<String, Vec<String>>> {
+        let range = match from_ref {
+            Some(from) => format!("{}..HEAD", from),
+            None => "HEAD".to_string(),
+        };
+
+        let output = GitCommand::new(&self.repository_path)
+            .args(["log", "--pretty=format:@@%H", "--name-only", &format!("-{}", limit), &range])
+            .output()
+            .await
+            .context("Ошибка выполнения git log")?;
+
+        if !output.success() {
+            let error_msg = output.stderr;
+            return Err(anyhow::anyhow!("Git log завершился с ошибкой: {}", error_msg));
+        }
+
+        let mut files: HashMap<String, Vec<String>> = HashMap::new();
+        let mut current: Option<String> = None;
+        for line in output.stdout.lines() {
+            let line = line.trim();
+            if let Some(hash) = line.strip_prefix("@@") {
+                current = Some(hash.to_string());
+                files.entry(hash.to_string()).or_default();
+            } else if let (Some(hash), false) = (&current, line.is_empty()) {
+                files.entry(hash.clone()).or_default().push(line.to_string());
+            }
+        }
+
+        debug!("Получены файлы для {} коммитов", files.len());
+        Ok(files)
+    }
+
     /// Проверяет, является ли репозиторий git репозиторием
     pub fn is_git_repository(&self) -> bool {
         self.repository_path.join(".git").exists()
     }
 
     /// Получает текущую ветку
+    #[instrument(name = "git.branch", skip(self))]
     pub async fn get_current_branch(&self) -> Result<String> {
-        let output = Command::new("git")
-            .current_dir(&self.repository_path)
+        let output = GitCommand::new(&self.repository_path)
             .args(&["rev-parse", "--abbrev-ref", "HEAD"])
             .output()
+            .await
             .context("Ошибка определения текущей ветки")?;
 
-        if !output.status.success() {
+        if !output.success() {
             return Err(anyhow::anyhow!("Не удалось определить текущую ветку"));
         }
 
-        let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let branch = output.stdout.trim().to_string();
         Ok(branch)
     }
 
     /// Получает информацию о тегах
+    #[instrument(name = "git.tags", skip(self))]
     pub async fn get_tags(&self) -> Result<Vec<String>> {
-        let output = Command::new("git")
-            .current_dir(&self.repository_path)
+        let output = GitCommand::new(&self.repository_path)
             .args(&["tag", "--sort=-version:refname"])
             .output()
+            .await
             .context("Ошибка получения тегов")?;
 
-        if !output.status.success() {
+        if !output.success() {
             return Err(anyhow::anyhow!("Не удалось получить список тегов"));
         }
 
-        let tags: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        let tags: Vec<String> = output.stdout
             .lines()
             .filter_map(|line| {
                 let line = line.trim();
@@ -343,4 +566,74 @@ impl ChangeType {
             ChangeType::Other => "Другое",
         }
     }
-}
\ No newline at end of file
+
+    /// Заголовок секции changelog на языке сгенерированного контента
+    pub fn title(&self, language: OutputLanguage) -> &'static str {
+        if language == OutputLanguage::Ru {
+            return self.name();
+        }
+        match self {
+            ChangeType::Feature => "New features",
+            ChangeType::Fix => "Bug fixes",
+            ChangeType::Breaking => "Breaking changes",
+            ChangeType::Improvement => "Improvements",
+            ChangeType::Documentation => "Documentation",
+            ChangeType::Testing => "Testing",
+            ChangeType::Refactoring => "Refactoring",
+            ChangeType::Chore => "Maintenance",
+            ChangeType::Other => "Other",
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(repo: &Path, message: &str, author: &str, date: &str) {
+        std::fs::write(repo.join("log.txt"), message).unwrap();
+        let run = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(repo)
+                .env("GIT_COMMITTER_DATE", date)
+                .env("GIT_AUTHOR_DATE", date)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        run(&["add", "-A"]);
+        run(&["-c", "user.email=dev@example.com", "commit", "-qm", message, "--author", author]);
+    }
+
+    #[tokio::test]
+    async fn test_log_window_filters_dates_and_authors() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        GitCommand::new(repo).args(["init", "-q"]).run().await.unwrap();
+        commit(repo, "feat: december", "Anna <anna@example.com>", "2023-12-31T12:00:00");
+        commit(repo, "feat: january", "Anna <anna@example.com>", "2024-01-15T12:00:00");
+        commit(repo, "fix: january", "Boris <boris@example.com>", "2024-01-31T23:00:00");
+        commit(repo, "feat: february", "Anna <anna@example.com>", "2024-02-01T00:30:00");
+
+        let date = |s: &str| Some(NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap());
+        let messages = |window: LogWindow| async move {
+            GitHistory::new(repo)
+                .with_window(window)
+                .get_commits_between(None, None)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|c| c.message)
+                .collect::<Vec<_>>()
+        };
+
+        let january = LogWindow { since: date("2024-01-01"), until: date("2024-02-01"), authors: vec![] };
+        assert_eq!(messages(january.clone()).await, ["fix: january", "feat: january"]);
+        let anna = LogWindow { authors: vec!["anna@".to_string()], ..january };
+        assert_eq!(messages(anna).await, ["feat: january"]);
+        assert_eq!(messages(LogWindow::default()).await.len(), 4);
+
+        let reversed = LogWindow { since: date("2024-02-01"), until: date("2024-01-01"), authors: vec![] };
+        assert!(reversed.validate().is_err());
+    }
+}
@@ -10,15 +10,28 @@ pub mod history;
 pub mod tags;
 pub mod analyzer;
 pub mod error;
-
-pub use history::{GitHistory, GitCommit, ChangeType};
+pub mod exclusions;
+pub mod pull_requests;
+pub mod branches;
+pub mod command;
+pub mod range_cache;
+pub mod version_rules;
+
+pub use history::{GitHistory, GitCommit, ChangeType, LogWindow};
 pub use tags::{GitTags, GitTag};
 pub use analyzer::{ChangeAnalyzer, ChangeAnalysis, ReleaseAnalysis, ImpactLevel, VersionBump};
+pub use command::GitCommand;
+pub use exclusions::CommitFilter;
+pub use pull_requests::PullRequestEnricher;
+pub use branches::{ReleaseBranches, ReleaseLine};
+pub use range_cache::RangeCache;
 pub use error::{GitError, GitOperationResult, GitErrorHandler, GitValidator, ValidationResult, RecoveryAction};
 
 use anyhow::Result;
 use std::path::Path;
 
+use crate::config::parser::{Config, OutputLanguage};
+
 /// Единый интерфейс для работы с Git репозиторием
 #[derive(Debug, Clone)]
 pub struct GitRepository {
@@ -26,6 +39,10 @@ pub struct GitRepository {
     pub history: GitHistory,
     pub tags: GitTags,
     pub analyzer: ChangeAnalyzer,
+    /// Кэш анализа диапазонов `(from, to)` (`[git] range_cache`)
+    range_cache: Option<RangeCache>,
+    /// Язык changelog (`[llm] output_language`)
+    language: OutputLanguage,
     error_handler: GitErrorHandler,
     validator: GitValidator,
 }
@@ -40,11 +57,61 @@ impl GitRepository {
             history: GitHistory::new(&path),
             tags: GitTags::new(&path),
             analyzer: ChangeAnalyzer::new(&path),
+            range_cache: None,
+            language: OutputLanguage::default(),
             error_handler: GitErrorHandler::new(&path),
             validator: GitValidator::new(&path),
         }
     }
 
+    /// Репозиторий с настройками истории из конфигурации: исключение коммитов и данные PR
+    pub fn from_config<P: AsRef<Path>>(repository_path: P, config: &Config) -> Result<Self> {
+        let mut repo = Self::new(repository_path).with_exclusions(CommitFilter::new(&config.changelog.exclude)?);
+        if let Some(enricher) = PullRequestEnricher::from_config(&config.pull_requests, &config.network.proxy)? {
+            repo = repo.with_pull_requests(enricher);
+        }
+        repo.language = config.llm.output_language;
+        repo.analyzer = repo.analyzer.with_version_sources(version_rules::from_configs(&config.git.version_rules)?);
+        if config.git.range_cache {
+            // Правила версий входят в ключ: от них зависит recommended_version_bump
+            let settings = format!("{:?}|{:?}|{:?}", config.changelog.exclude, config.pull_requests, config.git.version_rules);
+            let cache = RangeCache::new(repo.path.join(range_cache::RANGE_CACHE_DIR), &settings);
+            repo = repo.with_range_cache(cache);
+        }
+        Ok(repo)
+    }
+
+    /// Включает кэш анализа диапазонов коммитов
+    pub fn with_range_cache(mut self, cache: RangeCache) -> Self {
+        self.range_cache = Some(cache);
+        self
+    }
+
+    /// Применяет правила исключения к истории и анализу изменений
+    pub fn with_exclusions(mut self, filter: CommitFilter) -> Self {
+        self.history = self.history.with_exclusions(filter.clone());
+        self.analyzer = self.analyzer.with_exclusions(filter);
+        self
+    }
+
+    /// Подставляет данные PR в историю и анализ изменений (кэш PR у них общий)
+    pub fn with_pull_requests(mut self, enricher: PullRequestEnricher) -> Self {
+        self.history = self.history.with_pull_requests(enricher.clone());
+        self.analyzer = self.analyzer.with_pull_requests(enricher);
+        self
+    }
+
+    /// Ограничивает историю и анализ изменений окном дат и авторами
+    pub fn with_window(mut self, window: LogWindow) -> Self {
+        // Выборки по окну дат и авторам разовые: кэш ключуется только диапазоном
+        if !window.is_empty() {
+            self.range_cache = None;
+        }
+        self.history = self.history.with_window(window.clone());
+        self.analyzer = self.analyzer.with_window(window);
+        self
+    }
+
     /// Проверяет, является ли директория git репозиторием
     pub fn is_valid_repository(&self) -> bool {
         self.history.is_git_repository()
@@ -52,15 +119,43 @@ impl GitRepository {
 
     /// Получает полную информацию о последних изменениях
     pub async fn get_full_analysis(&self, from_tag: Option<&str>, to_tag: Option<&str>) -> Result<(ReleaseAnalysis, Vec<GitCommit>)> {
+        let cached = match (&self.range_cache, from_tag) {
+            (Some(cache), Some(from)) => match (
+                range_cache::resolve(&self.path, from).await,
+                range_cache::resolve(&self.path, to_tag.unwrap_or("HEAD")).await,
+            ) {
+                (Some(from), Some(to)) => Some((cache, from, to)),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some((cache, from, to)) = &cached {
+            if let Some((mut analysis, commits)) = cache.get(from, to) {
+                // Диапазон мог быть задан другими именами (тег вместо SHA)
+                analysis.version_from = from_tag.unwrap_or("HEAD").to_string();
+                analysis.version_to = to_tag.map(str::to_string);
+                return Ok((analysis, commits));
+            }
+        }
+
         let analysis = self.analyzer.analyze_changes(from_tag, to_tag).await?;
         let commits = self.history.get_commits_between(from_tag, to_tag).await?;
+        if let Some((cache, from, to)) = cached {
+            if let Err(e) = cache.store(&from, &to, &analysis, &commits) {
+                tracing::warn!("Анализ диапазона не сохранен в кэш: {:#}", e);
+            }
+        }
         Ok((analysis, commits))
     }
 
     /// Получает сводку изменений с последнего тега
     pub async fn get_changes_since_last_release(&self) -> Result<(ReleaseAnalysis, Vec<GitCommit>, Option<GitTag>)> {
         let latest_tag = self.tags.get_latest_tag().await?;
+        self.get_changes_since_tag(latest_tag).await
+    }
 
+    /// Сводка изменений с указанного тега до HEAD; без тега — последние коммиты
+    pub async fn get_changes_since_tag(&self, latest_tag: Option<GitTag>) -> Result<(ReleaseAnalysis, Vec<GitCommit>, Option<GitTag>)> {
         let (analysis, commits) = if let Some(ref tag) = latest_tag {
             self.get_full_analysis(Some(&tag.name), Some("HEAD")).await?
         } else {
@@ -79,13 +174,15 @@ impl GitRepository {
         let mut changelog = String::new();
 
         // Заголовок
-        if let (Some(from), Some(to)) = (from_tag, to_tag) {
-            changelog.push_str(&format!("## Изменения с {} по {}\n\n", from, to));
-        } else if let Some(to) = to_tag {
-            changelog.push_str(&format!("## Изменения для {}\n\n", to));
-        } else {
-            changelog.push_str("## Последние изменения\n\n");
-        }
+        let heading = match (from_tag, to_tag, self.language) {
+            (Some(from), Some(to), OutputLanguage::Ru) => format!("Изменения с {} по {}", from, to),
+            (Some(from), Some(to), OutputLanguage::En) => format!("Changes from {} to {}", from, to),
+            (None, Some(to), OutputLanguage::Ru) => format!("Изменения для {}", to),
+            (None, Some(to), OutputLanguage::En) => format!("Changes in {}", to),
+            (_, None, OutputLanguage::Ru) => "Последние изменения".to_string(),
+            (_, None, OutputLanguage::En) => "Recent changes".to_string(),
+        };
+        changelog.push_str(&format!("## {}\n\n", heading));
 
         // Группируем коммиты по типам изменений
         let mut grouped_commits: std::collections::HashMap<ChangeType, Vec<&GitCommit>> = std::collections::HashMap::new();
@@ -111,7 +208,7 @@ impl GitRepository {
         for change_type in &type_order {
             if let Some(commits_of_type) = grouped_commits.get(change_type) {
                 if !commits_of_type.is_empty() {
-                    changelog.push_str(&format!("### {} {}\n\n", change_type.emoji(), change_type.name()));
+                    changelog.push_str(&format!("### {} {}\n\n", change_type.emoji(), change_type.title(self.language)));
 
                     for commit in commits_of_type {
                         changelog.push_str(&format!("- {} ({}): {}\n",
@@ -126,10 +223,19 @@ impl GitRepository {
 
         // Добавляем статистику
         changelog.push_str("---\n");
-        changelog.push_str(&format!("**Статистика:** {} коммитов\n", commits.len()));
-
-        if !analysis.breaking_changes.is_empty() {
-            changelog.push_str(&format!("**⚠️ Критических изменений:** {}\n", analysis.breaking_changes.len()));
+        match self.language {
+            OutputLanguage::Ru => {
+                changelog.push_str(&format!("**Статистика:** {} коммитов\n", commits.len()));
+                if !analysis.breaking_changes.is_empty() {
+                    changelog.push_str(&format!("**⚠️ Критических изменений:** {}\n", analysis.breaking_changes.len()));
+                }
+            }
+            OutputLanguage::En => {
+                changelog.push_str(&format!("**Statistics:** {} commits\n", commits.len()));
+                if !analysis.breaking_changes.is_empty() {
+                    changelog.push_str(&format!("**⚠️ Breaking changes:** {}\n", analysis.breaking_changes.len()));
+                }
+            }
         }
 
         Ok(changelog)
@@ -341,4 +447,4 @@ mod tests {
         assert_eq!(analysis.total_commits, 1);
         assert!(analysis.change_summary.contains_key(&ChangeType::Feature));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,279 @@
+//! Обогащение коммитов данными PR (GitHub) и MR (GitLab).
+//!
+//! Squash-мерж часто оставляет в истории неинформативную тему («Update Foo.kt (#123)»),
+//! а смысл изменения — в заголовке, метках и описании PR. Коммиты со ссылкой на PR
+//! получают его заголовок вместо темы, а метки и описание идут в changelog как контекст.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use super::history::GitCommit;
+use crate::config::parser::{ProxyConfig, PullRequestsConfig};
+use crate::utils::network::NetworkUtils;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// PR/MR, на который ссылается коммит
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub labels: Vec<String>,
+    pub body: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Provider {
+    GitHub,
+    GitLab,
+}
+
+/// Загружает PR по ссылкам из тем коммитов; повторные ссылки берутся из кэша
+#[derive(Debug, Clone)]
+pub struct PullRequestEnricher {
+    provider: Provider,
+    api_url: String,
+    repository: String,
+    token: Option<String>,
+    max_body_length: usize,
+    client: reqwest::Client,
+    cache: Arc<Mutex<HashMap<u64, Option<PullRequest>>>>,
+}
+
+impl PullRequestEnricher {
+    /// None — интеграция выключена в `[pull_requests]`
+    pub fn from_config(config: &PullRequestsConfig, proxy: &ProxyConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        let (provider, default_api) = match config.provider.as_str() {
+            "github" => (Provider::GitHub, "https://api.github.com"),
+            "gitlab" => (Provider::GitLab, "https://gitlab.com/api/v4"),
+            other => anyhow::bail!("Неподдерживаемый [pull_requests] provider '{}'", other),
+        };
+        Ok(Some(Self {
+            provider,
+            api_url: config.api_url.as_deref().unwrap_or(default_api).trim_end_matches('/').to_string(),
+            repository: config.repository.trim().to_string(),
+            token: config.token.clone().filter(|t| !t.trim().is_empty()),
+            max_body_length: config.max_body_length,
+            client: NetworkUtils::http_client(proxy, REQUEST_TIMEOUT)?,
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }))
+    }
+
+    /// Подставляет данные PR в коммиты. Ошибка API не прерывает генерацию: остаются темы коммитов
+    pub async fn enrich(&self, commits: &mut [GitCommit]) {
+        let mut enriched = 0;
+        for commit in commits.iter_mut() {
+            let Some(number) = reference(&commit.message, self.provider) else {
+                continue;
+            };
+            let pull_request = match self.get(number).await {
+                Ok(Some(pull_request)) => pull_request,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("⚠️ Не удалось получить PR #{}: {:#}. Дальше используются темы коммитов", number, e);
+                    return;
+                }
+            };
+            commit.message = enriched_subject(&commit.message, &pull_request);
+            commit.pull_request = Some(pull_request);
+            enriched += 1;
+        }
+        if enriched > 0 {
+            info!("🔗 Коммитов с данными PR: {}", enriched);
+        }
+    }
+
+    async fn get(&self, number: u64) -> Result<Option<PullRequest>> {
+        if let Some(cached) = self.cache.lock().expect("pull request cache poisoned").get(&number) {
+            return Ok(cached.clone());
+        }
+        let fetched = self.fetch(number).await?;
+        self.cache.lock().expect("pull request cache poisoned").insert(number, fetched.clone());
+        Ok(fetched)
+    }
+
+    async fn fetch(&self, number: u64) -> Result<Option<PullRequest>> {
+        let request = match self.provider {
+            Provider::GitHub => {
+                let url = format!("{}/repos/{}/pulls/{}", self.api_url, self.repository, number);
+                let request = self.client.get(url).header(reqwest::header::ACCEPT, "application/vnd.github+json");
+                match &self.token {
+                    Some(token) => request.bearer_auth(token),
+                    None => request,
+                }
+            }
+            Provider::GitLab => {
+                let project = self.repository.replace('/', "%2F");
+                let url = format!("{}/projects/{}/merge_requests/{}", self.api_url, project, number);
+                let request = self.client.get(url);
+                match &self.token {
+                    Some(token) => request.header("PRIVATE-TOKEN", token),
+                    None => request,
+                }
+            }
+        };
+        let response = request
+            .header(reqwest::header::USER_AGENT, concat!("deploy-pugin/", env!("CARGO_PKG_VERSION")))
+            .send()
+            .await
+            .context("Запрос к API провайдера")?;
+        // Ссылка на чужой или удаленный PR — не ошибка
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            debug!("PR #{} не найден в {}", number, self.repository);
+            return Ok(None);
+        }
+        let json: serde_json::Value = response.error_for_status()?.json().await.context("Некорректный ответ API")?;
+        Ok(Some(self.parse(number, &json)))
+    }
+
+    fn parse(&self, number: u64, json: &serde_json::Value) -> PullRequest {
+        let text = |key: &str| json.get(key).and_then(|v| v.as_str()).map(str::trim).filter(|s| !s.is_empty());
+        // GitHub: labels — объекты с name; GitLab: строки
+        let labels = json
+            .get("labels")
+            .and_then(|v| v.as_array())
+            .map(|labels| {
+                labels
+                    .iter()
+                    .filter_map(|l| l.as_str().or_else(|| l.get("name").and_then(|n| n.as_str())))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let body_key = match self.provider {
+            Provider::GitHub => "body",
+            Provider::GitLab => "description",
+        };
+        let url_key = match self.provider {
+            Provider::GitHub => "html_url",
+            Provider::GitLab => "web_url",
+        };
+        PullRequest {
+            number,
+            title: text("title").unwrap_or_default().to_string(),
+            labels,
+            body: text(body_key).map(|body| truncate(&strip_html_comments(body), self.max_body_length)),
+            url: text(url_key).map(str::to_string),
+        }
+    }
+}
+
+/// Номер PR из темы коммита: `Merge pull request #12 from ...`, `Title (#12)` для GitHub, `!12` для GitLab
+fn reference(subject: &str, provider: Provider) -> Option<u64> {
+    let re = match provider {
+        Provider::GitHub => Regex::new(r"^Merge pull request #(\d+)|\(#(\d+)\)\s*$").unwrap(),
+        Provider::GitLab => Regex::new(r"!(\d+)\b").unwrap(),
+    };
+    let caps = re.captures(subject)?;
+    caps.iter().skip(1).flatten().next()?.as_str().parse().ok()
+}
+
+/// Заголовок PR вместо темы коммита. Тип conventional commits сохраняется: из темы коммита,
+/// а если его нет и там — выводится из меток PR
+fn enriched_subject(subject: &str, pull_request: &PullRequest) -> String {
+    let conventional = Regex::new(r"^[a-z]+(\([^)]*\))?!?:\s").unwrap();
+    let title = pull_request.title.trim();
+    if title.is_empty() {
+        return subject.to_string();
+    }
+    if conventional.is_match(title) {
+        return title.to_string();
+    }
+    let prefix = conventional
+        .find(subject)
+        .map(|m| m.as_str().to_string())
+        .or_else(|| label_type(&pull_request.labels).map(|t| format!("{}: ", t)));
+    format!("{}{}", prefix.unwrap_or_default(), title)
+}
+
+fn label_type(labels: &[String]) -> Option<&'static str> {
+    labels.iter().find_map(|label| match label.to_lowercase().as_str() {
+        "bug" | "fix" | "bugfix" => Some("fix"),
+        "feature" | "enhancement" => Some("feat"),
+        "documentation" | "docs" => Some("docs"),
+        "dependencies" | "chore" => Some("chore"),
+        "refactoring" | "refactor" => Some("refactor"),
+        _ => None,
+    })
+}
+
+/// Шаблоны PR оставляют подсказки в `<!-- -->`: в LLM они не нужны
+fn strip_html_comments(text: &str) -> String {
+    Regex::new(r"(?s)<!--.*?-->").unwrap().replace_all(text, "").trim().to_string()
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pull_request(title: &str, labels: &[&str]) -> PullRequest {
+        PullRequest { number: 12, title: title.to_string(), labels: labels.iter().map(|l| l.to_string()).collect(), body: None, url: None }
+    }
+
+    #[test]
+    fn test_references_and_subjects() {
+        assert_eq!(reference("Merge pull request #12 from alice/chat", Provider::GitHub), Some(12));
+        assert_eq!(reference("Update ChatPanel.kt (#345)", Provider::GitHub), Some(345));
+        assert_eq!(reference("fix: see #7 for details", Provider::GitHub), None);
+        assert_eq!(reference("Resolve \"Chat history\" (!45)", Provider::GitLab), Some(45));
+        assert_eq!(reference("feat!: drop 2023.x", Provider::GitLab), None);
+
+        assert_eq!(enriched_subject("Update ChatPanel.kt (#345)", &pull_request("Chat history search", &["enhancement"])), "feat: Chat history search");
+        assert_eq!(enriched_subject("fix(chat): update (#3)", &pull_request("Scroll jumps on new message", &["bug"])), "fix(chat): Scroll jumps on new message");
+        assert_eq!(enriched_subject("Merge pull request #1 from a/b", &pull_request("feat(ui): dark theme", &[])), "feat(ui): dark theme");
+        assert_eq!(enriched_subject("Misc (#2)", &pull_request("Cleanup", &["question"])), "Cleanup");
+        assert_eq!(enriched_subject("Misc (#2)", &pull_request("", &[])), "Misc (#2)");
+
+        let github = PullRequestEnricher::from_config(
+            &PullRequestsConfig { enabled: true, repository: "Aristman/ride".to_string(), max_body_length: 20, ..PullRequestsConfig::default() },
+            &ProxyConfig::default(),
+        )
+        .unwrap()
+        .unwrap();
+        let parsed = github.parse(
+            12,
+            &serde_json::json!({
+                "title": " Chat history search ",
+                "body": "<!-- Describe the change -->\nAdds full-text search over chat history",
+                "labels": [{"name": "enhancement"}, {"name": "chat"}],
+                "html_url": "https://github.com/Aristman/ride/pull/12"
+            }),
+        );
+        assert_eq!(parsed.title, "Chat history search");
+        assert_eq!(parsed.labels, ["enhancement", "chat"]);
+        assert_eq!(parsed.body.as_deref(), Some("Adds full-text searc…"));
+        assert!(PullRequestEnricher::from_config(&PullRequestsConfig::default(), &ProxyConfig::default()).unwrap().is_none());
+
+        let commit = GitCommit {
+            hash: "abc".to_string(),
+            short_hash: "abc".to_string(),
+            message: "feat: Chat history search".to_string(),
+            author: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            date: chrono::Utc::now(),
+            files_changed: 1,
+            insertions: 1,
+            deletions: 0,
+            files: Vec::new(),
+            pull_request: Some(parsed),
+            diff_summary: None,
+        };
+        assert_eq!(commit.changelog_input(), "feat: Chat history search (PR #12, метки: enhancement, chat)\n    > Adds full-text searc…");
+    }
+}
@@ -0,0 +1,113 @@
+//! Кэш анализа диапазона коммитов: `ReleaseAnalysis` и список коммитов по паре SHA
+//! `(from, to)`. История между неизменными коммитами не меняется, поэтому повторные
+//! `status unreleased`, `ai` и `release` над тем же диапазоном не запускают git log и анализ.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+use super::analyzer::ReleaseAnalysis;
+use super::command::GitCommand;
+use super::history::GitCommit;
+
+/// Каталог кэша относительно корня проекта
+pub const RANGE_CACHE_DIR: &str = ".deploy-plugin/cache/ranges";
+
+#[derive(Serialize, Deserialize)]
+struct CachedRange {
+    analysis: ReleaseAnalysis,
+    commits: Vec<GitCommit>,
+}
+
+/// Кэш диапазонов одного репозитория
+#[derive(Debug, Clone)]
+pub struct RangeCache {
+    dir: PathBuf,
+    /// Отпечаток настроек истории (исключения, PR, версия инструмента): с другими
+    /// настройками тот же диапазон анализируется заново
+    fingerprint: String,
+}
+
+impl RangeCache {
+    pub fn new(dir: impl Into<PathBuf>, settings: &str) -> Self {
+        let settings = format!("{}|{}", env!("CARGO_PKG_VERSION"), settings);
+        let digest = format!("{:x}", Sha256::digest(settings.as_bytes()));
+        Self { dir: dir.into(), fingerprint: digest[..12].to_string() }
+    }
+
+    fn path(&self, from: &str, to: &str) -> PathBuf {
+        self.dir.join(format!("{}..{}-{}.json", from, to, self.fingerprint))
+    }
+
+    /// Анализ и коммиты диапазона; поврежденная запись считается промахом
+    pub fn get(&self, from: &str, to: &str) -> Option<(ReleaseAnalysis, Vec<GitCommit>)> {
+        let content = std::fs::read_to_string(self.path(from, to)).ok()?;
+        let cached: CachedRange = serde_json::from_str(&content).ok()?;
+        debug!("Анализ {}..{} взят из кэша", &from[..from.len().min(8)], &to[..to.len().min(8)]);
+        Some((cached.analysis, cached.commits))
+    }
+
+    /// Сохраняет анализ диапазона; запись через временный файл, чтобы параллельный
+    /// запуск (pre-commit hook) не прочитал ее наполовину
+    pub fn store(&self, from: &str, to: &str, analysis: &ReleaseAnalysis, commits: &[GitCommit]) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Не удалось создать каталог кэша {}", self.dir.display()))?;
+        let json = serde_json::to_vec(&CachedRange { analysis: analysis.clone(), commits: commits.to_vec() })?;
+        let mut file = tempfile::NamedTempFile::new_in(&self.dir).context("Не удалось создать временный файл кэша")?;
+        std::io::Write::write_all(&mut file, &json)?;
+        file.persist(self.path(from, to)).context("Не удалось сохранить анализ диапазона в кэш")?;
+        Ok(())
+    }
+}
+
+/// SHA коммита, на который указывает `rev`; `None`, если ссылка не разрешается
+pub async fn resolve(repository_path: &Path, rev: &str) -> Option<String> {
+    let output = GitCommand::new(repository_path)
+        .args(["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", rev)])
+        .output()
+        .await
+        .ok()?;
+    output.success().then(|| output.stdout.trim().to_string()).filter(|sha| !sha.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::VersionBump;
+
+    #[test]
+    fn test_range_cache_keys_by_sha_pair_and_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RangeCache::new(dir.path(), "exclude=[]");
+        let analysis = ReleaseAnalysis {
+            version_from: "v1.0.0".to_string(),
+            version_to: None,
+            total_commits: 1,
+            change_summary: Default::default(),
+            impact_distribution: Default::default(),
+            breaking_changes: vec![],
+            recommended_version_bump: VersionBump::Patch,
+            confidence: 0.8,
+        };
+        let commit: GitCommit = serde_json::from_value(serde_json::json!({
+            "hash": "b".repeat(40), "short_hash": "bbbbbbb", "message": "fix: сборка", "author": "dev",
+            "email": "dev@example.com", "date": "2026-01-01T00:00:00Z", "files_changed": 1, "insertions": 1, "deletions": 0
+        }))
+        .unwrap();
+
+        let (from, to) = ("a".repeat(40), "b".repeat(40));
+        assert!(cache.get(&from, &to).is_none());
+        cache.store(&from, &to, &analysis, std::slice::from_ref(&commit)).unwrap();
+        let (cached, commits) = cache.get(&from, &to).unwrap();
+        assert_eq!((cached.total_commits, commits[0].message.as_str()), (1, "fix: сборка"));
+
+        // Другие правила исключения или другой конец диапазона — промах
+        assert!(RangeCache::new(dir.path(), "exclude=[\"bot\"]").get(&from, &to).is_none());
+        assert!(cache.get(&from, &"c".repeat(40)).is_none());
+
+        std::fs::write(cache.path(&from, &to), "{").unwrap();
+        assert!(cache.get(&from, &to).is_none());
+    }
+}
@@ -1,10 +1,11 @@
 use anyhow::{Context, Result};
 use std::path::Path;
-use std::process::Command;
+use std::sync::{Arc, Mutex};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use tracing::{info, debug, warn};
+use tracing::{info, debug, warn, instrument};
 use super::history::GitCommit;
+use super::command::GitCommand;
 
 /// Модель git тега
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,10 +18,23 @@ pub struct GitTag {
     pub is_annotated: bool,
 }
 
+/// Сторона диапазона, для которой разрешается алиас
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeSide {
+    From,
+    To,
+}
+
+/// Формат `for-each-ref`: для аннотированных тегов поля `*` указывают на коммит,
+/// для легковесных они пусты, а обычные поля уже описывают коммит
+const TAG_REF_FORMAT: &str = "--format=%(refname:short)%00%(objecttype)%00%(objectname)%00%(*objectname)%00%(subject)%00%(*subject)%00%(authorname)%00%(*authorname)%00%(committerdate:iso-strict)%00%(*committerdate:iso-strict)";
+
 /// Менеджер работы с git тегами
 #[derive(Debug, Clone)]
 pub struct GitTags {
     repository_path: std::path::PathBuf,
+    /// Метаданные тегов, загруженные одним вызовом `for-each-ref` на запуск
+    cache: Arc<Mutex<Option<Vec<GitTag>>>>,
 }
 
 impl GitTags {
@@ -28,34 +42,53 @@ impl GitTags {
     pub fn new<P: AsRef<Path>>(repository_path: P) -> Self {
         Self {
             repository_path: repository_path.as_ref().to_path_buf(),
+            cache: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Сбрасывает кэш тегов (после создания/удаления тега)
+    pub fn invalidate_cache(&self) {
+        *self.cache.lock().expect("tag cache poisoned") = None;
+    }
+
+    /// Ищет тег в кэше по имени
+    async fn find_cached(&self, tag_name: &str) -> Result<Option<GitTag>> {
+        let tags = self.get_all_tags().await?;
+        Ok(tags.into_iter().find(|t| t.name == tag_name))
+    }
+
     /// Получает все теги в репозитории
+    #[instrument(name = "git.for-each-ref", skip(self))]
     pub async fn get_all_tags(&self) -> Result<Vec<GitTag>> {
+        if let Some(tags) = self.cache.lock().expect("tag cache poisoned").as_ref() {
+            debug!("Теги взяты из кэша ({} шт.)", tags.len());
+            return Ok(tags.clone());
+        }
+
         info!("🏷️ Получение всех тегов репозитория");
 
-        // Получаем список тегов с информацией о коммитах
-        let output = Command::new("git")
-            .current_dir(&self.repository_path)
-            .args(&[
-                "tag", "--sort=-version:refname", "--format=%(refname:short)%00%(objectname)%00%(contents:subject)%00%(authorname)%00%(creatordate)",
+        // Один вызов for-each-ref вместо git show/cat-file на каждый тег
+        let output = GitCommand::new(&self.repository_path)
+            .args([
+                "for-each-ref", "--sort=-version:refname", TAG_REF_FORMAT, "refs/tags",
             ])
             .output()
+            .await
             .context("Ошибка получения списка тегов")?;
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
+        if !output.success() {
+            let error_msg = output.stderr;
             return Err(anyhow::anyhow!(
-                "Git tag завершился с ошибкой: {}",
+                "Git for-each-ref завершился с ошибкой: {}",
                 error_msg
             ));
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = output.stdout;
         let tags = self.parse_tags_output(&stdout)?;
 
         info!("Получено {} тегов", tags.len());
+        *self.cache.lock().expect("tag cache poisoned") = Some(tags.clone());
         Ok(tags)
     }
 
@@ -63,21 +96,21 @@ impl GitTags {
     pub async fn get_latest_tag(&self) -> Result<Option<GitTag>> {
         info!("🏷️ Получение последнего тега");
 
-        let output = Command::new("git")
-            .current_dir(&self.repository_path)
+        let output = GitCommand::new(&self.repository_path)
             .args(&[
                 "describe", "--tags", "--abbrev=0"
             ])
             .output()
+            .await
             .context("Ошибка получения последнего тега")?;
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
+        if !output.success() {
+            let error_msg = output.stderr;
             warn!("Не удалось получить последний тег: {}", error_msg);
             return Ok(None);
         }
 
-        let tag_name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let tag_name = output.stdout.trim().to_string();
 
         if tag_name.is_empty() {
             return Ok(None);
@@ -88,27 +121,34 @@ impl GitTags {
     }
 
     /// Получает информацию о конкретном теге
+    #[instrument(name = "git.show", skip(self))]
     pub async fn get_tag_info(&self, tag_name: &str) -> Result<GitTag> {
         debug!("Получение информации о теге: {}", tag_name);
 
+        if let Some(tag) = self.find_cached(tag_name).await? {
+            return Ok(tag);
+        }
+
+        // Не тег (ветка, коммит) — читаем коммит напрямую
+
         // Используем короткий формат одной строки, без diff и аннотаций
-        let output = Command::new("git")
-            .current_dir(&self.repository_path)
+        let output = GitCommand::new(&self.repository_path)
             .args(&[
                 "show", "-s", "--no-patch", "--pretty=%H|%s|%an|%cI", tag_name
             ])
             .output()
+            .await
             .context("Ошибка получения информации о теге")?;
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
+        if !output.success() {
+            let error_msg = output.stderr;
             return Err(anyhow::anyhow!(
                 "Не удалось получить информацию о теге {}: {}",
                 tag_name, error_msg
             ));
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = output.stdout;
         let line_opt = stdout
             .lines()
             .map(|l| l.trim())
@@ -138,20 +178,18 @@ impl GitTags {
             .map(|d| d.with_timezone(&Utc))
             .unwrap_or_else(|_| Utc::now());
 
-        // Проверяем, является ли тег аннотированным
-        let is_annotated = self.is_annotated_tag(tag_name).await?;
-
         Ok(GitTag {
             name: tag_name.to_string(),
             commit_hash,
             commit_message,
             author,
             date,
-            is_annotated,
+            is_annotated: false,
         })
     }
 
     /// Создает новый тег
+    #[instrument(name = "git.tag", skip(self))]
     pub async fn create_tag(&self, tag_name: &str, message: Option<&str>) -> Result<()> {
         info!("🏷️ Создание тега: {}", tag_name);
 
@@ -165,52 +203,55 @@ impl GitTags {
 
         args.push(tag_name);
 
-        let output = Command::new("git")
-            .current_dir(&self.repository_path)
+        let output = GitCommand::new(&self.repository_path)
             .args(&args)
             .output()
+            .await
             .context("Ошибка создания тега")?;
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
+        if !output.success() {
+            let error_msg = output.stderr;
             return Err(anyhow::anyhow!(
                 "Не удалось создать тег {}: {}",
                 tag_name, error_msg
             ));
         }
 
+        self.invalidate_cache();
         info!("✅ Тег {} успешно создан", tag_name);
         Ok(())
     }
 
     /// Удаляет тег
+    #[instrument(name = "git.tag-delete", skip(self))]
     pub async fn delete_tag(&self, tag_name: &str) -> Result<()> {
         info!("🗑️ Удаление тега: {}", tag_name);
 
-        let output = Command::new("git")
-            .current_dir(&self.repository_path)
+        let output = GitCommand::new(&self.repository_path)
             .args(&["tag", "-d", tag_name])
             .output()
+            .await
             .context("Ошибка удаления тега")?;
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
+        if !output.success() {
+            let error_msg = output.stderr;
             return Err(anyhow::anyhow!(
                 "Не удалось удалить тег {}: {}",
                 tag_name, error_msg
             ));
         }
 
+        self.invalidate_cache();
         info!("✅ Тег {} успешно удален", tag_name);
         Ok(())
     }
 
     /// Получает коммиты между двумя тегами
+    #[instrument(name = "git.log", skip(self))]
     pub async fn get_commits_between_tags(&self, from_tag: &str, to_tag: &str) -> Result<Vec<GitCommit>> {
         info!("📜 Получение коммитов между тегами {}..{}", from_tag, to_tag);
 
-        let output = Command::new("git")
-            .current_dir(&self.repository_path)
+        let output = GitCommand::new(&self.repository_path)
             .args(&[
                 "log",
                 "--pretty=format:%H|%h|%s|%an|%ae|%ai",
@@ -218,23 +259,34 @@ impl GitTags {
                 &format!("{}..{}", from_tag, to_tag)
             ])
             .output()
+            .await
             .context("Ошибка получения коммитов между тегами")?;
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
+        if !output.success() {
+            let error_msg = output.stderr;
             return Err(anyhow::anyhow!(
                 "Не удалось получить коммиты между тегами: {}",
                 error_msg
             ));
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = output.stdout;
         let commits = self.parse_git_log(&stdout)?;
 
         info!("Получено {} коммитов между тегами", commits.len());
         Ok(commits)
     }
 
+    /// Число коммитов от тега до HEAD (`git rev-list --count`)
+    pub async fn count_commits_since(&self, tag_name: &str) -> Result<usize> {
+        let count = GitCommand::new(&self.repository_path)
+            .args(["rev-list", "--count", &format!("{}..HEAD", tag_name)])
+            .run()
+            .await
+            .with_context(|| format!("Не удалось посчитать коммиты после {}", tag_name))?;
+        count.trim().parse().with_context(|| format!("Некорректный вывод git rev-list: {}", count.trim()))
+    }
+
     /// Получает коммиты от последнего тега до HEAD
     pub async fn get_commits_since_last_tag(&self) -> Result<Vec<GitCommit>> {
         if let Some(latest_tag) = self.get_latest_tag().await? {
@@ -287,16 +339,35 @@ impl GitTags {
         Some(format!("{}.{}.{}", major, minor, patch + 1))
     }
 
-    /// Проверяет, является ли тег аннотированным
-    async fn is_annotated_tag(&self, tag_name: &str) -> Result<bool> {
-        let output = Command::new("git")
-            .current_dir(&self.repository_path)
-            .args(&["cat-file", "-p", &format!("refs/tags/{}", tag_name)])
-            .output()
-            .context("Ошибка проверки типа тега")?;
+    /// Разрешает диапазон changelog с поддержкой алиасов:
+    /// `last-release`, `previous-release`, `last-minor`, `last-major`,
+    /// частичные версии (`v1.2`) и `--between A..B`
+    pub async fn resolve_range(
+        &self,
+        since: Option<&str>,
+        to: Option<&str>,
+        between: Option<&str>,
+    ) -> Result<(Option<String>, Option<String>)> {
+        let (since, to) = match between {
+            Some(range) => {
+                let (from, to) = range.split_once("..").ok_or_else(|| {
+                    anyhow::anyhow!("Диапазон должен иметь вид <from>..<to>, получено: {}", range)
+                })?;
+                (Some(from.trim()), Some(to.trim()).filter(|t| !t.is_empty()))
+            }
+            None => (since, to),
+        };
+
+        if since.is_none() && to.is_none() {
+            return Ok((None, None));
+        }
 
-        // Аннотированные теги содержат информацию о теге
-        Ok(output.status.success())
+        let tags = self.get_all_tags().await?;
+        let from = since.map(|s| resolve_tag_alias(&tags, s, RangeSide::From)).transpose()?;
+        let to = to.map(|t| resolve_tag_alias(&tags, t, RangeSide::To)).transpose()?;
+
+        debug!("Диапазон разрешен: {:?}..{:?}", from, to);
+        Ok((from, to))
     }
 
     /// Парсит вывод git log (используется в GitHistory)
@@ -334,6 +405,9 @@ impl GitTags {
                         files_changed: 0,
                         insertions: 0,
                         deletions: 0,
+                        files: Vec::new(),
+                        pull_request: None,
+                        diff_summary: None,
                     });
                 }
             } else if let Some(ref mut commit) = current_commit {
@@ -342,6 +416,9 @@ impl GitTags {
                     commit.insertions += insertions;
                     commit.deletions += deletions;
                     commit.files_changed += 1;
+                    if let Some(path) = line.splitn(3, '\t').nth(2) {
+                        commit.files.push(path.to_string());
+                    }
                 }
             }
         }
@@ -378,7 +455,7 @@ impl GitTags {
         }
     }
 
-    /// Парсит вывод git tags
+    /// Парсит вывод `git for-each-ref` (см. `TAG_REF_FORMAT`)
     fn parse_tags_output(&self, output: &str) -> Result<Vec<GitTag>> {
         let mut tags = Vec::new();
 
@@ -387,30 +464,26 @@ impl GitTags {
                 continue;
             }
 
-            // Формат: tag_name|commit_hash|message|author|date
-            let parts: Vec<&str> = line.split('\x00').collect();
-            if parts.len() < 5 {
+            // Формат: name|type|object|*object|subject|*subject|author|*author|date|*date
+            let parts: Vec<&str> = line.split('\x00').map(str::trim).collect();
+            if parts.len() < 10 {
                 continue;
             }
 
-            let tag_name = parts[0].trim().to_string();
-            let commit_hash = parts[1].trim().to_string();
-            let commit_message = parts[2].trim().to_string();
-            let author = parts[3].trim().to_string();
-            let date_str = parts[4].trim();
+            let is_annotated = parts[1] == "tag";
+            // Для аннотированного тега берем поля коммита, на который он указывает
+            let field = |own: usize| if is_annotated { parts[own + 1] } else { parts[own] };
 
+            let date_str = field(8);
             let date = DateTime::parse_from_rfc3339(date_str)
                 .unwrap_or_else(|_| DateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S %z").unwrap_or_else(|_| Utc::now().into()))
                 .with_timezone(&Utc);
 
-            // Проверяем, является ли тег аннотированным
-            let is_annotated = false; // TODO: сделать async при необходимости
-
             tags.push(GitTag {
-                name: tag_name,
-                commit_hash,
-                commit_message,
-                author,
+                name: parts[0].to_string(),
+                commit_hash: field(2).to_string(),
+                commit_message: field(4).to_string(),
+                author: field(6).to_string(),
                 date,
                 is_annotated,
             });
@@ -420,3 +493,141 @@ impl GitTags {
     }
 }
 
+/// Версия из имени тега: префикс до первой цифры отбрасывается (`v1.2.3` -> 1.2.3)
+fn tag_version(name: &str) -> Option<semver::Version> {
+    let start = name.find(|c: char| c.is_ascii_digit())?;
+    semver::Version::parse(&name[start..]).ok()
+}
+
+/// Разрешает алиас или частичную версию в имя тега.
+/// Имена, не похожие на алиас и не найденные среди тегов, возвращаются как есть (ветки, хэши).
+pub fn resolve_tag_alias(tags: &[GitTag], alias: &str, side: RangeSide) -> Result<String> {
+    let mut releases: Vec<(semver::Version, &GitTag)> = tags
+        .iter()
+        .filter_map(|t| tag_version(&t.name).map(|v| (v, t)))
+        .filter(|(v, _)| v.pre.is_empty())
+        .collect();
+    // Новые версии первыми
+    releases.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let pick = |found: Option<&(semver::Version, &GitTag)>, what: &str| -> Result<String> {
+        found
+            .map(|(_, t)| t.name.clone())
+            .ok_or_else(|| anyhow::anyhow!("Не найден тег для '{}' ({})", alias, what))
+    };
+
+    match alias {
+        "HEAD" => Ok(alias.to_string()),
+        "last-release" | "latest" => pick(releases.first(), "нет релизных тегов"),
+        "previous-release" => pick(releases.get(1), "меньше двух релизных тегов"),
+        "last-minor" => pick(
+            releases.iter().find(|(v, _)| v.patch == 0),
+            "нет тегов вида X.Y.0",
+        ),
+        "last-major" => pick(
+            releases.iter().find(|(v, _)| v.minor == 0 && v.patch == 0),
+            "нет тегов вида X.0.0",
+        ),
+        _ if tags.iter().any(|t| t.name == alias) => Ok(alias.to_string()),
+        _ => {
+            // Частичная версия: v1.2 или 1 — последний тег этой серии
+            let start = alias.find(|c: char| c.is_ascii_digit());
+            let parts: Option<Vec<u64>> = start.and_then(|s| {
+                alias[s..].split('.').map(|p| p.parse().ok()).collect()
+            });
+            match parts {
+                Some(parts) if !parts.is_empty() && parts.len() < 3 => {
+                    let series = releases.iter().find(|(v, _)| {
+                        v.major == parts[0] && parts.get(1).is_none_or(|m| v.minor == *m)
+                    });
+                    let side_name = match side {
+                        RangeSide::From => "начало диапазона",
+                        RangeSide::To => "конец диапазона",
+                    };
+                    pick(series, side_name)
+                }
+                _ => Ok(alias.to_string()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(name: &str) -> GitTag {
+        GitTag {
+            name: name.to_string(),
+            commit_hash: String::new(),
+            commit_message: String::new(),
+            author: String::new(),
+            date: Utc::now(),
+            is_annotated: false,
+        }
+    }
+
+    fn sample_tags() -> Vec<GitTag> {
+        ["v1.4.1", "v1.4.0", "v1.3.2", "v1.2.5", "v1.2.0", "v2.0.0-rc.1", "v1.0.0", "nightly"]
+            .iter()
+            .map(|n| tag(n))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_count_commits_since_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let command = GitCommand::new(dir.path()).args(args);
+            async move { command.run().await.unwrap() }
+        };
+        let commit = ["-c", "user.name=dev", "-c", "user.email=dev@example.com", "commit", "-q", "--allow-empty", "-m", "fix: сборка"];
+        git(&["init", "-q"]).await;
+        git(&commit).await;
+        git(&["tag", "v1.0.0"]).await;
+
+        let tags = GitTags::new(dir.path());
+        assert_eq!(tags.count_commits_since("v1.0.0").await.unwrap(), 0);
+        git(&commit).await;
+        git(&commit).await;
+        assert_eq!(tags.count_commits_since("v1.0.0").await.unwrap(), 2);
+        assert!(tags.count_commits_since("v9.9.9").await.is_err());
+    }
+
+    #[test]
+    fn test_parse_for_each_ref_output() {
+        let annotated = ["v1.1.0", "tag", "aaa", "c1", "Release 1.1", "feat: chat", "", "Alice", "2024-05-01T10:00:00+03:00", "2024-04-30T09:00:00+03:00"];
+        let lightweight = ["v1.0.0", "commit", "c0", "", "init", "", "Bob", "", "2024-01-01T00:00:00+00:00", ""];
+        let output = format!("{}\n{}\n", annotated.join("\x00"), lightweight.join("\x00"));
+        let tags = GitTags::new(".").parse_tags_output(&output).unwrap();
+
+        assert_eq!(tags.len(), 2);
+        assert!(tags[0].is_annotated);
+        assert_eq!(tags[0].commit_hash, "c1");
+        assert_eq!(tags[0].commit_message, "feat: chat");
+        assert_eq!(tags[0].author, "Alice");
+        assert_eq!(tags[0].date.to_rfc3339(), "2024-04-30T06:00:00+00:00");
+        assert!(!tags[1].is_annotated);
+        assert_eq!(tags[1].commit_hash, "c0");
+        assert_eq!(tags[1].author, "Bob");
+    }
+
+    #[test]
+    fn test_resolve_named_aliases() {
+        let tags = sample_tags();
+        assert_eq!(resolve_tag_alias(&tags, "last-release", RangeSide::From).unwrap(), "v1.4.1");
+        assert_eq!(resolve_tag_alias(&tags, "previous-release", RangeSide::From).unwrap(), "v1.4.0");
+        assert_eq!(resolve_tag_alias(&tags, "last-minor", RangeSide::From).unwrap(), "v1.4.0");
+        assert_eq!(resolve_tag_alias(&tags, "last-major", RangeSide::From).unwrap(), "v1.0.0");
+    }
+
+    #[test]
+    fn test_resolve_partial_versions_and_passthrough() {
+        let tags = sample_tags();
+        assert_eq!(resolve_tag_alias(&tags, "v1.2", RangeSide::From).unwrap(), "v1.2.5");
+        assert_eq!(resolve_tag_alias(&tags, "1.4", RangeSide::To).unwrap(), "v1.4.1");
+        assert_eq!(resolve_tag_alias(&tags, "nightly", RangeSide::From).unwrap(), "nightly");
+        assert_eq!(resolve_tag_alias(&tags, "main", RangeSide::To).unwrap(), "main");
+        assert!(resolve_tag_alias(&tags, "v3.1", RangeSide::To).is_err());
+    }
+}
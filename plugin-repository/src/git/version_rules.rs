@@ -0,0 +1,85 @@
+//! Явная версия релиза по маркеру в коммите (`[[git.version_rules]]`): трейлер
+//! `Release: 3.0.0-rc1` или совпадение регулярного выражения задают версию точно,
+//! и анализатор возвращает `VersionBump::Custom` без обращения к LLM
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::sync::Arc;
+
+use crate::config::parser::VersionRuleConfig;
+
+/// Источник явной версии по полному сообщению коммита (тема и тело)
+pub trait VersionSource: std::fmt::Debug + Send + Sync {
+    /// Версия, которую задает коммит; `None` — маркера нет
+    fn version_for(&self, message: &str) -> Option<String>;
+}
+
+/// Трейлер `<key>: <version>`; с `version` в правиле — фиксированная версия при любом значении
+#[derive(Debug)]
+pub struct TrailerVersion {
+    key: String,
+    version: Option<String>,
+}
+
+impl VersionSource for TrailerVersion {
+    fn version_for(&self, message: &str) -> Option<String> {
+        message.lines().filter_map(|line| line.split_once(':')).find_map(|(key, value)| {
+            if !key.trim().eq_ignore_ascii_case(&self.key) {
+                return None;
+            }
+            self.version.clone().or_else(|| Some(value.trim().to_string())).filter(|v| !v.is_empty())
+        })
+    }
+}
+
+/// Регулярное выражение по сообщению: версия из группы `version` или фиксированная из правила
+#[derive(Debug)]
+pub struct PatternVersion {
+    pattern: Regex,
+    version: Option<String>,
+}
+
+impl VersionSource for PatternVersion {
+    fn version_for(&self, message: &str) -> Option<String> {
+        let captures = self.pattern.captures(message)?;
+        match (&self.version, captures.name("version")) {
+            (Some(version), _) => Some(version.clone()),
+            (None, Some(version)) => Some(version.as_str().trim().to_string()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Источник версии из правила конфигурации
+pub fn from_config(rule: &VersionRuleConfig) -> Result<Arc<dyn VersionSource>> {
+    if let Some(version) = &rule.version {
+        normalize(version).with_context(|| format!("[[git.version_rules]] version '{}' не является версией semver", version))?;
+    }
+    match (&rule.trailer, &rule.pattern) {
+        (Some(key), None) if !key.trim().is_empty() => {
+            Ok(Arc::new(TrailerVersion { key: key.trim().to_string(), version: rule.version.clone() }))
+        }
+        (None, Some(pattern)) => {
+            // Многострочный режим: `^` и `$` — границы строк сообщения
+            let pattern_re = Regex::new(&format!("(?m){}", pattern))
+                .with_context(|| format!("[[git.version_rules]] pattern: некорректное регулярное выражение '{}'", pattern))?;
+            if rule.version.is_none() && !pattern_re.capture_names().any(|name| name == Some("version")) {
+                anyhow::bail!("[[git.version_rules]] pattern '{}': нужна группа (?P<version>...) или version в правиле", pattern);
+            }
+            Ok(Arc::new(PatternVersion { pattern: pattern_re, version: rule.version.clone() }))
+        }
+        _ => anyhow::bail!("[[git.version_rules]]: в правиле должен быть задан либо trailer, либо pattern"),
+    }
+}
+
+/// Источники версий всех правил в порядке конфигурации
+pub fn from_configs(rules: &[VersionRuleConfig]) -> Result<Vec<Arc<dyn VersionSource>>> {
+    rules.iter().map(from_config).collect()
+}
+
+/// Версия без префикса `v`, проверенная по semver
+pub fn normalize(version: &str) -> Result<String> {
+    let version = version.trim().trim_start_matches('v');
+    semver::Version::parse(version).with_context(|| format!("'{}' не является версией semver", version))?;
+    Ok(version.to_string())
+}
@@ -1,15 +1,15 @@
 use clap::{Parser, Subcommand};
 use anyhow::Result;
 
+// utils первым: его макросы вывода out!/outln!/out_errln! (utils::output) видны во всем крейте
+#[macro_use]
+mod utils;
 mod cli;
 mod commands;
 mod core;
 mod config;
 mod git;
 mod models;
-mod utils;
-
-use tracing_subscriber;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -29,6 +29,19 @@ struct Args {
     /// Уровень логирования
     #[arg(short, long, default_value = "info")]
     log_level: String,
+
+    /// Цвет вывода: auto (по умолчанию: только в терминале и без NO_COLOR), always, never
+    #[arg(long, default_value = "auto")]
+    color: String,
+
+    /// Вывод без эмодзи (для CI и архивирования логов)
+    #[arg(long, env = "DEPLOY_PLUGIN_NO_EMOJI")]
+    no_emoji: bool,
+
+    /// Тестовый режим: случайные сбои на границах этапов (`llm=0.5,sftp-rename,git-push`),
+    /// работает только при DEPLOY_PLUGIN_CHAOS=1
+    #[arg(long, hide = true)]
+    chaos: Option<String>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -47,32 +60,83 @@ enum Commands {
     Validate(cli::validate::ValidateCommand),
     /// Статус
     Status(cli::status::StatusCommand),
+    /// Глубокая проверка ZIP артефакта
+    VerifyArtifact(cli::verify_artifact::VerifyArtifactCommand),
+    /// Проверка воспроизводимости сборки
+    VerifyReproducible(cli::verify_reproducible::VerifyReproducibleCommand),
+    /// HTTP API для запуска публикации
+    Serve(cli::serve::ServeCommand),
+    /// Запуск пайплайна из [pipelines]
+    Run(cli::run::RunCommand),
+    /// Обслуживание config.toml (миграция схемы)
+    Config(cli::config::ConfigCommand),
+    /// Откат опубликованной версии: индекс, тег и артефакт
+    Rollback(cli::rollback::RollbackCommand),
+}
+
+impl Commands {
+    /// Имя команды для отчетов об использовании
+    fn name(&self) -> &'static str {
+        match self {
+            Commands::Build(_) => "build",
+            Commands::Release(_) => "release",
+            Commands::Publish(_) => "publish",
+            Commands::Deploy(_) => "deploy",
+            Commands::Ai(_) => "ai",
+            Commands::Validate(_) => "validate",
+            Commands::Status(_) => "status",
+            Commands::VerifyArtifact(_) => "verify-artifact",
+            Commands::VerifyReproducible(_) => "verify-reproducible",
+            Commands::Serve(_) => "serve",
+            Commands::Run(_) => "run",
+            Commands::Config(_) => "config",
+            Commands::Rollback(_) => "rollback",
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
+    let started = std::time::Instant::now();
+    let command_name = args.command.name();
 
-    // Инициализация логирования
-    tracing_subscriber::fmt()
-        .with_max_level(match args.log_level.as_str() {
-            "debug" => tracing::Level::DEBUG,
-            "info" => tracing::Level::INFO,
-            "warn" => tracing::Level::WARN,
-            "error" => tracing::Level::ERROR,
-            _ => tracing::Level::INFO,
-        })
-        .init();
-
-    // Загрузка переменных окружения из .env файла
+    // Загрузка переменных окружения из .env файла (до логирования: там могут быть OTEL_*)
     dotenv::dotenv().ok();
     // Дополнительная попытка: загрузить .env из поддиректории, если запускаем из корня монорепозитория
     if std::env::var("DEPLOY_PLUGIN_YANDEX_FOLDER_ID").is_err() || std::env::var("DEPLOY_PLUGIN_YANDEX_API_KEY").is_err() {
         let _ = dotenv::from_filename("plugin-repository/.env");
     }
 
+    // Политика цвета и эмодзи действует и на логи, поэтому задается до их инициализации
+    utils::output::init(utils::output::ColorMode::parse(&args.color)?, args.no_emoji);
+
+    // Инициализация логирования и (с фичей otel) экспорта трассировки
+    let level = match args.log_level.as_str() {
+        "debug" => tracing::Level::DEBUG,
+        "info" => tracing::Level::INFO,
+        "warn" => tracing::Level::WARN,
+        "error" => tracing::Level::ERROR,
+        _ => tracing::Level::INFO,
+    };
+    let _telemetry = utils::telemetry::init(level, &args.config);
+
+    if let Some(spec) = &args.chaos {
+        core::chaos::init(spec)?;
+    }
+
+    // Проверка новой версии утилиты (кэшируется, отключается [updates] check = false)
+    if !matches!(args.command, Commands::Config(_)) {
+        core::update_check::check(&args.config).await;
+    }
+
+    // Пайплайны с откатом при Ctrl-C; остальные команды прерываются как обычно
+    if matches!(args.command, Commands::Publish(_) | Commands::Run(_) | Commands::Deploy(_)) {
+        core::interrupt::install();
+    }
+
     // Обработка команд
-    match args.command {
+    let result = match args.command {
         Commands::Build(cmd) => {
             commands::build::handle_build_command(cmd, &args.config).await
         }
@@ -94,5 +158,45 @@ async fn main() -> Result<()> {
         Commands::Status(cmd) => {
             commands::status::handle_status_command(cmd, &args.config).await
         }
+        Commands::VerifyArtifact(cmd) => {
+            commands::verify_artifact::handle_verify_artifact_command(cmd, &args.config).await
+        }
+        Commands::VerifyReproducible(cmd) => {
+            commands::verify_reproducible::handle_verify_reproducible_command(cmd, &args.config).await
+        }
+        Commands::Serve(cmd) => {
+            commands::serve::handle_serve_command(cmd, &args.config).await
+        }
+        Commands::Run(cmd) => {
+            commands::run::handle_run_command(cmd, &args.config).await
+        }
+        Commands::Config(cmd) => {
+            commands::config::handle_config_command(cmd, &args.config).await
+        }
+        Commands::Rollback(cmd) => {
+            commands::rollback::handle_rollback_command(cmd, &args.config).await
+        }
+    };
+
+    // Отчет о результате ([telemetry.usage], только при явном включении)
+    if let Some(usage) = &_telemetry.usage {
+        usage.send(command_name, started, &result).await;
+    }
+
+    // После Ctrl-C — итог очистки вместо трассировки ошибки
+    if let Some(interrupted) = result.as_ref().err().and_then(|e| e.downcast_ref::<core::interrupt::Interrupted>()) {
+        out_errln!("⛔ {}", interrupted);
+        out_errln!("{}", core::interrupt::report());
+        drop(_telemetry);
+        std::process::exit(core::interrupt::EXIT_CODE);
+    }
+
+    // Штатные коды выхода (например, «нечего релизить») — без сообщения об ошибке
+    if let Some(status) = result.as_ref().err().and_then(|e| e.downcast_ref::<utils::exit::ExitStatus>()) {
+        tracing::info!("{}", status.message);
+        let code = status.code;
+        drop(_telemetry);
+        std::process::exit(code);
     }
+    result
 }
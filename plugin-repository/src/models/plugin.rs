@@ -80,4 +80,25 @@ pub struct BuildResult {
     pub build_time: DateTime<Utc>,
     pub logs: Vec<String>,
     pub errors: Vec<String>,
+    /// Окружение, в котором собран артефакт
+    #[serde(default)]
+    pub environment: Option<BuildEnvironment>,
+}
+
+/// Окружение сборки: позволяет по опубликованному ZIP восстановить, чем и из чего он собран
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildEnvironment {
+    pub os: String,
+    pub arch: String,
+    /// Версия JDK из `java -version`
+    pub jdk: Option<String>,
+    /// Система сборки и ее версия: `Gradle 8.7`, `Maven 3.9.6`
+    pub build_tool: Option<String>,
+    /// Целевая платформа из IntelliJ Gradle DSL: `IC 2024.2.5`
+    #[serde(default)]
+    pub target_platform: Option<String>,
+    pub git_commit: Option<String>,
+    /// Есть незакоммиченные изменения (`git status --porcelain`)
+    pub git_dirty: Option<bool>,
+    pub tool: String,
 }
\ No newline at end of file
@@ -0,0 +1,108 @@
+//! Источники времени и случайности для ядра.
+//!
+//! Команда создает одни часы на запуск и передает их LLM-провайдерам и менеджеру агентов
+//! (`LLMAgentManager::from_config`), а от него — менеджеру релизов; билдер, хранилище релизов
+//! и деплойер получают те же часы через `with_clock`, запись аудита — через `AuditEntry::new`.
+//! Источник джиттера повторов запросов к LLM задается в `RetryPolicy::random`.
+//! Напрямую `Utc::now()` и `thread_rng()` не вызываются: тесты подставляют фиксированные значения
+//! и сравнивают результаты целиком.
+
+use chrono::{DateTime, Utc};
+use rand::{thread_rng, Rng};
+use std::fmt::Debug;
+use std::sync::Arc;
+#[cfg(test)]
+use std::sync::Mutex;
+
+/// Текущее время
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Источник случайных чисел (джиттер retry)
+pub trait RandomSource: Debug + Send + Sync {
+    /// Случайное число в диапазоне `0..bound` (`bound` > 0)
+    fn below(&self, bound: u64) -> u64;
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Системное время
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Системный генератор
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadRandom;
+
+impl RandomSource for ThreadRandom {
+    fn below(&self, bound: u64) -> u64 {
+        thread_rng().gen_range(0..bound.max(1))
+    }
+}
+
+/// Системные часы для конструкторов по умолчанию
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// Замороженное время; `advance` сдвигает его вручную
+#[cfg(test)]
+#[derive(Debug)]
+pub struct FixedClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+impl FixedClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self { now: Mutex::new(now) }
+    }
+
+    pub fn advance(&self, by: chrono::Duration) {
+        *self.now.lock().expect("clock poisoned") += by;
+    }
+}
+
+#[cfg(test)]
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().expect("clock poisoned")
+    }
+}
+
+/// Всегда одно и то же значение (ограниченное `bound`)
+#[cfg(test)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixedRandom(pub u64);
+
+#[cfg(test)]
+impl RandomSource for FixedRandom {
+    fn below(&self, bound: u64) -> u64 {
+        self.0.min(bound.saturating_sub(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_sources() {
+        let at = DateTime::parse_from_rfc3339("2024-03-01T10:00:00Z").unwrap().with_timezone(&Utc);
+        let clock = FixedClock::new(at);
+        assert_eq!(clock.now(), at);
+        clock.advance(chrono::Duration::minutes(5));
+        assert_eq!(clock.now(), at + chrono::Duration::minutes(5));
+
+        assert_eq!(FixedRandom(7).below(100), 7);
+        assert_eq!(FixedRandom(7).below(5), 4);
+        assert!(ThreadRandom.below(3) < 3);
+    }
+}
@@ -0,0 +1,24 @@
+/// Код выхода «нечего релизить»: 1 — ошибка, 2 — ошибка аргументов clap
+pub const NOTHING_TO_RELEASE: i32 = 3;
+
+/// Завершение команды с заданным кодом выхода, не являющееся сбоем
+/// (например, «нечего релизить» для cron/CI)
+#[derive(Debug)]
+pub struct ExitStatus {
+    pub code: i32,
+    pub message: String,
+}
+
+impl ExitStatus {
+    pub fn new(code: i32, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+impl std::fmt::Display for ExitStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ExitStatus {}
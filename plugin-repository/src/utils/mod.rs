@@ -1,3 +1,9 @@
+#[macro_use]
+pub mod output;
 pub mod fs;
 pub mod network;
-pub mod progress;
\ No newline at end of file
+pub mod progress;
+pub mod telemetry;
+pub mod exit;
+pub mod usage;
+pub mod clock;
\ No newline at end of file
@@ -1,33 +1,98 @@
-use anyhow::Result;
-use rand::{thread_rng, Rng};
+use anyhow::{Context, Result};
 use std::time::Duration;
+use tracing::debug;
 
-/// Утилиты сети: retry с экспоненциальной задержкой и джиттером
+use crate::config::parser::ProxyConfig;
+
+/// Итоговые настройки прокси: конфигурация + переменные окружения
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProxySettings {
+    pub http: Option<String>,
+    pub https: Option<String>,
+    pub no_proxy: Vec<String>,
+    pub auth: Option<(String, String)>,
+}
+
+impl ProxySettings {
+    /// Объединяет `[network.proxy]` с HTTPS_PROXY/HTTP_PROXY/ALL_PROXY/NO_PROXY
+    /// (в верхнем или нижнем регистре); явный `url` из конфигурации важнее окружения
+    pub fn resolve(config: &ProxyConfig, env: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| {
+            env(name)
+                .or_else(|| env(&name.to_lowercase()))
+                .filter(|v| !v.trim().is_empty())
+        };
+
+        let (http, https) = match &config.url {
+            Some(url) => (Some(url.clone()), Some(url.clone())),
+            None => {
+                let all = var("ALL_PROXY");
+                (var("HTTP_PROXY").or_else(|| all.clone()), var("HTTPS_PROXY").or(all))
+            }
+        };
+
+        let mut no_proxy = config.no_proxy.clone();
+        if let Some(list) = var("NO_PROXY") {
+            no_proxy.extend(list.split(',').map(|h| h.trim().to_string()).filter(|h| !h.is_empty()));
+        }
+        no_proxy.dedup();
+
+        let auth = config
+            .username
+            .as_ref()
+            .map(|user| (user.clone(), config.password.clone().unwrap_or_default()));
+
+        Self { http, https, no_proxy, auth }
+    }
+
+    /// Дополняет прокси авторизацией и исключениями NO_PROXY
+    fn apply(&self, mut proxy: reqwest::Proxy) -> reqwest::Proxy {
+        if let Some((user, password)) = &self.auth {
+            proxy = proxy.basic_auth(user, password);
+        }
+        proxy.no_proxy(reqwest::NoProxy::from_string(&self.no_proxy.join(",")))
+    }
+}
+
+/// Утилиты сети
 pub struct NetworkUtils;
 
 impl NetworkUtils {
-    /// Выполняет операцию с retry (экспоненциальная задержка + джиттер)
-    pub async fn retry<F, Fut, T>(mut op: F, attempts: u32, base_delay_ms: u64) -> Result<T>
-    where
-        F: FnMut() -> Fut,
-        Fut: std::future::Future<Output = Result<T>>,
-    {
-        let mut last_err: Option<anyhow::Error> = None;
-        for i in 0..attempts {
-            match op().await {
-                Ok(v) => return Ok(v),
-                Err(e) => {
-                    last_err = Some(e);
-                    if i + 1 < attempts {
-                        let exp = 2u64.saturating_pow(i);
-                        let jitter: u64 = thread_rng().gen_range(0..(base_delay_ms / 2).max(1));
-                        let delay = base_delay_ms.saturating_mul(exp) + jitter;
-                        tokio::time::sleep(Duration::from_millis(delay)).await;
-                    }
-                }
-            }
+    /// HTTP-клиент с учетом прокси. Все исходящие HTTP(S) запросы
+    /// (LLM, интеграции, HTTP-бэкенды деплоя) должны создаваться через него
+    pub fn http_client(proxy: &ProxyConfig, timeout: Duration) -> Result<reqwest::Client> {
+        let settings = ProxySettings::resolve(proxy, |name| std::env::var(name).ok());
+
+        // Автоопределение reqwest отключаем: прокси задается только из ProxySettings
+        let mut builder = reqwest::Client::builder().timeout(timeout).no_proxy();
+        if let Some(url) = &settings.https {
+            debug!("🌐 HTTPS прокси: {}", redact_credentials(url));
+            let proxy = reqwest::Proxy::https(url).with_context(|| format!("Некорректный URL прокси: {}", url))?;
+            builder = builder.proxy(settings.apply(proxy));
+        }
+        if let Some(url) = &settings.http {
+            debug!("🌐 HTTP прокси: {}", redact_credentials(url));
+            let proxy = reqwest::Proxy::http(url).with_context(|| format!("Некорректный URL прокси: {}", url))?;
+            builder = builder.proxy(settings.apply(proxy));
+        }
+
+        builder.build().context("Не удалось создать HTTP клиент")
+    }
+
+    /// Проверяет URL прокси из конфигурации
+    pub fn validate_proxy(proxy: &ProxyConfig) -> Result<()> {
+        if let Some(url) = &proxy.url {
+            reqwest::Proxy::all(url).with_context(|| format!("Некорректный URL прокси: {}", url))?;
         }
-        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("retry: неизвестная ошибка")))
+        Ok(())
+    }
+}
+
+/// Скрывает логин/пароль в URL прокси для логов
+fn redact_credentials(url: &str) -> String {
+    match (url.find("://"), url.rfind('@')) {
+        (Some(scheme), Some(at)) if at > scheme => format!("{}***{}", &url[..scheme + 3], &url[at..]),
+        _ => url.to_string(),
     }
 }
 
@@ -35,19 +100,31 @@ impl NetworkUtils {
 mod tests {
     use super::*;
 
-    #[tokio::test]
-    async fn test_retry_succeeds_eventually() {
-        let mut count = 0;
-        let res: Result<i32> = NetworkUtils::retry(
-            || {
-                count += 1;
-                async move {
-                    if count < 3 { Err(anyhow::anyhow!("fail")) } else { Ok(42) }
-                }
-            },
-            5,
-            10,
-        ).await;
-        assert_eq!(res.unwrap(), 42);
+    #[test]
+    fn test_proxy_resolution() {
+        let env = |name: &str| match name {
+            "https_proxy" => Some("http://env-proxy:3128".to_string()),
+            "NO_PROXY" => Some("localhost, .corp.example.com".to_string()),
+            _ => None,
+        };
+
+        let settings = ProxySettings::resolve(&ProxyConfig::default(), env);
+        assert_eq!(settings.https.as_deref(), Some("http://env-proxy:3128"));
+        assert_eq!(settings.http, None);
+        assert_eq!(settings.no_proxy, ["localhost", ".corp.example.com"]);
+
+        let config = ProxyConfig {
+            url: Some("http://proxy.corp:8080".to_string()),
+            no_proxy: vec!["10.0.0.0/8".to_string()],
+            username: Some("ci".to_string()),
+            password: None,
+        };
+        let settings = ProxySettings::resolve(&config, env);
+        assert_eq!(settings.http.as_deref(), Some("http://proxy.corp:8080"));
+        assert_eq!(settings.https.as_deref(), Some("http://proxy.corp:8080"));
+        assert_eq!(settings.no_proxy, ["10.0.0.0/8", "localhost", ".corp.example.com"]);
+        assert_eq!(settings.auth, Some(("ci".to_string(), String::new())));
+
+        assert_eq!(redact_credentials("http://user:pw@proxy:3128"), "http://***@proxy:3128");
     }
-}
\ No newline at end of file
+}
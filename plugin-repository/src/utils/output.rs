@@ -0,0 +1,170 @@
+//! Политика вывода: цвет (auto/always/never) и эмодзи.
+//!
+//! Сообщения команд выводятся макросами `out!`/`outln!`/`out_errln!` (`#[macro_use]` в main.rs),
+//! которые подчиняются политике. JSON, XML и содержимое файлов печатаются `println!`/`print!`
+//! из std без изменений. Логи tracing проходят через [`LogWriter`], цвета `colored`
+//! и прогресс-баров отключаются глобально.
+
+use anyhow::Result;
+use std::io::{self, IsTerminal, Write};
+use std::sync::OnceLock;
+
+/// `print!` по политике вывода: текст проходит через [`render`]
+macro_rules! out {
+    ($($arg:tt)*) => { ::std::print!("{}", $crate::utils::output::render(&::std::format!($($arg)*))) };
+}
+
+/// `println!` по политике вывода: текст проходит через [`render`]
+macro_rules! outln {
+    () => { ::std::println!() };
+    ($($arg:tt)*) => { ::std::println!("{}", $crate::utils::output::render(&::std::format!($($arg)*))) };
+}
+
+/// `eprintln!` по политике вывода: текст проходит через [`render`]
+macro_rules! out_errln {
+    () => { ::std::eprintln!() };
+    ($($arg:tt)*) => { ::std::eprintln!("{}", $crate::utils::output::render(&::std::format!($($arg)*))) };
+}
+
+/// Режим цвета `--color`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Цвет только в терминале и без NO_COLOR
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            other => Err(anyhow::anyhow!("Неизвестный режим цвета '{}': ожидается auto, always или never", other)),
+        }
+    }
+
+    /// Итоговое решение; в режиме auto непустой NO_COLOR (https://no-color.org) отключает цвет
+    pub fn enabled(self, no_color: Option<&str>, is_terminal: bool) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => no_color.is_none_or(str::is_empty) && is_terminal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputPolicy {
+    pub color: bool,
+    pub emoji: bool,
+}
+
+impl Default for OutputPolicy {
+    fn default() -> Self {
+        Self { color: true, emoji: true }
+    }
+}
+
+static POLICY: OnceLock<OutputPolicy> = OnceLock::new();
+
+/// Устанавливает политику до инициализации логирования
+pub fn init(color: ColorMode, no_emoji: bool) -> OutputPolicy {
+    let no_color = std::env::var("NO_COLOR").ok();
+    let policy = OutputPolicy {
+        color: color.enabled(no_color.as_deref(), io::stdout().is_terminal()),
+        emoji: !no_emoji,
+    };
+    colored::control::set_override(policy.color);
+    console::set_colors_enabled(policy.color);
+    console::set_colors_enabled_stderr(policy.color);
+    *POLICY.get_or_init(|| policy)
+}
+
+pub fn policy() -> OutputPolicy {
+    POLICY.get().copied().unwrap_or_default()
+}
+
+/// Текст для вывода по текущей политике
+pub fn render(text: &str) -> String {
+    if policy().emoji {
+        text.to_string()
+    } else {
+        strip_emoji(text)
+    }
+}
+
+/// Удаляет эмодзи вместе с пробелом после них: `✅ Сборка завершена` -> `Сборка завершена`
+pub fn strip_emoji(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if !is_emoji(c) {
+            result.push(c);
+            continue;
+        }
+        // Модификаторы и составные эмодзи (ZWJ-последовательности) удаляются целиком
+        while chars.peek().is_some_and(|&next| is_emoji(next)) {
+            chars.next();
+        }
+        let at_word_start = result.chars().next_back().is_none_or(char::is_whitespace);
+        if at_word_start && chars.peek() == Some(&' ') {
+            chars.next();
+        }
+    }
+    result
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF // пиктограммы, смайлы, транспорт, флаги
+            | 0x2600..=0x27BF // символы и dingbats: ✅ ❌ ⚠ ✨
+            | 0x2300..=0x23FF // ⏱ ⏪ ⌛
+            | 0x2B00..=0x2BFF // ⭐ ⬆
+            | 0xFE0F // вариационный селектор эмодзи
+            | 0x200D // zero width joiner
+            | 0x20E3 // keycap
+    )
+}
+
+/// Writer логов tracing: применяет политику эмодзи к каждой записи
+pub struct LogWriter<W: Write>(pub W);
+
+impl<W: Write> Write for LogWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match std::str::from_utf8(buf) {
+            Ok(text) if !policy().emoji => self.0.write_all(strip_emoji(text).as_bytes())?,
+            _ => self.0.write_all(buf)?,
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_mode_and_strip_emoji() {
+        assert!(ColorMode::Auto.enabled(None, true));
+        assert!(ColorMode::Auto.enabled(Some(""), true));
+        assert!(!ColorMode::Auto.enabled(Some("1"), true));
+        assert!(!ColorMode::Auto.enabled(None, false));
+        assert!(ColorMode::Always.enabled(Some("1"), false));
+        assert!(!ColorMode::Never.enabled(None, true));
+        assert_eq!(ColorMode::parse("NEVER").unwrap(), ColorMode::Never);
+        assert!(ColorMode::parse("sometimes").is_err());
+
+        assert_eq!(strip_emoji("✅ Сборка завершена"), "Сборка завершена");
+        assert_eq!(strip_emoji("  🏷️ Версия: 1.2.0"), "  Версия: 1.2.0");
+        assert_eq!(strip_emoji("\n🚀 Новые возможности (3)"), "\nНовые возможности (3)");
+        assert_eq!(strip_emoji("Готово 🎉"), "Готово ");
+        assert_eq!(strip_emoji("👨‍💻 Автор → main • 2"), "Автор → main • 2");
+    }
+}
@@ -20,6 +20,30 @@ impl ProgressBar {
         Self { inner: Some(bar) }
     }
 
+    /// Прогресс передачи файла: байты, скорость и оставшееся время
+    pub fn new_transfer<S: Into<String>>(total_bytes: u64, name: S) -> Self {
+        let bar = IndicatifBar::new(total_bytes);
+        bar.set_style(
+            ProgressStyle::with_template(
+                "{msg} [{bar:30.cyan/blue}] {bytes}/{total_bytes} {bytes_per_sec} ETA {eta}",
+            )
+            .unwrap()
+            .progress_chars("=> "),
+        );
+        bar.set_message(name.into());
+        Self { inner: Some(bar) }
+    }
+
+    pub fn inc(&self, delta: u64) {
+        if let Some(ref bar) = self.inner {
+            bar.inc(delta);
+        }
+    }
+
+    pub fn position(&self) -> u64 {
+        self.inner.as_ref().map(|bar| bar.position()).unwrap_or(0)
+    }
+
     pub fn set_message<S: Into<String>>(&self, msg: S) {
         if let Some(ref bar) = self.inner {
             bar.set_message(msg.into());
@@ -50,5 +74,11 @@ mod tests {
         pb.set_message("Работаем...");
         pb.tick();
         pb.finish_with_message("Готово");
+
+        let transfer = ProgressBar::new_transfer(1024, "ride-1.3.0.zip");
+        transfer.inc(512);
+        transfer.inc(512);
+        assert_eq!(transfer.position(), 1024);
+        transfer.finish_with_message("ride-1.3.0.zip");
     }
 }
\ No newline at end of file
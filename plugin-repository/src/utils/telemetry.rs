@@ -0,0 +1,196 @@
+use tracing::Level;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::config::parser::{Config, TelemetryConfig};
+use crate::utils::output::{self, LogWriter};
+use crate::utils::usage::UsageReporter;
+
+/// Имя сервиса в трассировке по умолчанию
+const DEFAULT_SERVICE_NAME: &str = "deploy-pugin";
+/// OTLP/HTTP коллектор по умолчанию
+const DEFAULT_ENDPOINT: &str = "http://localhost:4318";
+
+/// Итоговые настройки экспорта: `[telemetry]` + стандартные переменные OTEL_*
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetrySettings {
+    pub enabled: bool,
+    pub endpoint: String,
+    pub service_name: String,
+}
+
+impl TelemetrySettings {
+    /// Экспорт включается `[telemetry] enabled = true` или переменной OTEL_EXPORTER_OTLP_ENDPOINT;
+    /// OTEL_SDK_DISABLED=true отключает его в любом случае
+    pub fn resolve(config: &TelemetryConfig, env: impl Fn(&str) -> Option<String>) -> Self {
+        let env_endpoint = env("OTEL_EXPORTER_OTLP_ENDPOINT").filter(|v| !v.trim().is_empty());
+        let disabled = env("OTEL_SDK_DISABLED").is_some_and(|v| v.eq_ignore_ascii_case("true"));
+
+        Self {
+            enabled: !disabled && (config.enabled || env_endpoint.is_some()),
+            endpoint: env_endpoint
+                .or_else(|| config.endpoint.clone())
+                .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string()),
+            service_name: env("OTEL_SERVICE_NAME")
+                .or_else(|| config.service_name.clone())
+                .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string()),
+        }
+    }
+}
+
+/// Завершает экспорт при выходе: отправляет накопленные spans
+pub struct TelemetryGuard {
+    #[cfg(feature = "otel")]
+    active: bool,
+    /// Отчеты о результатах команд, если включены в `[telemetry.usage]`
+    pub usage: Option<UsageReporter>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if self.active {
+            opentelemetry::global::shutdown_tracer_provider();
+        }
+    }
+}
+
+/// Инициализирует логирование и, если включено, экспорт spans в OTLP.
+/// Конфигурация читается без ошибок: команды без config.toml работают как раньше.
+pub fn init(level: Level, config_file: &str) -> TelemetryGuard {
+    let config = Config::load_from_file(config_file).ok();
+    let telemetry = config.as_ref().map(|c| c.telemetry.clone()).unwrap_or_default();
+    let proxy = config.as_ref().map(|c| c.network.proxy.clone()).unwrap_or_default();
+    let settings = TelemetrySettings::resolve(&telemetry, |name| std::env::var(name).ok());
+
+    let registry = tracing_subscriber::registry()
+        .with(LevelFilter::from_level(level))
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(output::policy().color)
+                .with_writer(|| LogWriter(std::io::stdout())),
+        );
+
+    #[cfg(feature = "otel")]
+    let active = {
+        let exporter = if settings.enabled { Some(otel::layer(&settings, &proxy)) } else { None };
+        match exporter {
+            Some(Ok(layer)) => {
+                registry.with(layer).init();
+                tracing::info!("📡 Экспорт трассировки в {} ({})", settings.endpoint, settings.service_name);
+                true
+            }
+            Some(Err(e)) => {
+                registry.init();
+                tracing::warn!("Экспорт трассировки отключен: {:#}", e);
+                false
+            }
+            None => {
+                registry.init();
+                false
+            }
+        }
+    };
+    #[cfg(not(feature = "otel"))]
+    {
+        let _ = proxy;
+        registry.init();
+        if settings.enabled {
+            tracing::warn!("Экспорт трассировки запрошен, но бинарь собран без фичи 'otel'");
+        }
+    }
+
+    let usage = match config.as_ref().map(|c| UsageReporter::from_config(c, |name| std::env::var(name).ok())) {
+        Some(Ok(usage)) => usage,
+        Some(Err(e)) => {
+            tracing::warn!("Отчеты об использовании отключены: {:#}", e);
+            None
+        }
+        None => None,
+    };
+    if let Some(usage) = &usage {
+        usage.announce();
+    }
+
+    TelemetryGuard {
+        #[cfg(feature = "otel")]
+        active,
+        usage,
+    }
+}
+
+#[cfg(feature = "otel")]
+mod otel {
+    use anyhow::{Context, Result};
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::{runtime, trace, Resource};
+    use tracing_subscriber::registry::LookupSpan;
+
+    use super::TelemetrySettings;
+    use crate::config::parser::ProxyConfig;
+    use crate::utils::network::NetworkUtils;
+
+    /// Слой tracing -> OTLP/HTTP с пакетной отправкой (прокси берется из `[network.proxy]`)
+    pub fn layer<S>(settings: &TelemetrySettings, proxy: &ProxyConfig) -> Result<impl tracing_subscriber::Layer<S>>
+    where
+        S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let client = NetworkUtils::http_client(proxy, std::time::Duration::from_secs(10))?;
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .http()
+                    .with_http_client(client)
+                    .with_endpoint(&settings.endpoint),
+            )
+            .with_trace_config(
+                trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                    "service.name",
+                    settings.service_name.clone(),
+                )])),
+            )
+            .install_batch(runtime::Tokio)
+            .context("Не удалось инициализировать OTLP экспорт")?;
+
+        Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_resolution() {
+        let config = TelemetryConfig {
+            enabled: false,
+            endpoint: Some("http://tempo.corp:4318".to_string()),
+            service_name: None,
+            ..TelemetryConfig::default()
+        };
+
+        let settings = TelemetrySettings::resolve(&config, |_| None);
+        assert!(!settings.enabled);
+        assert_eq!(settings.endpoint, "http://tempo.corp:4318");
+        assert_eq!(settings.service_name, DEFAULT_SERVICE_NAME);
+
+        let env = |name: &str| match name {
+            "OTEL_EXPORTER_OTLP_ENDPOINT" => Some("http://collector:4318".to_string()),
+            "OTEL_SERVICE_NAME" => Some("ride-ci".to_string()),
+            _ => None,
+        };
+        let settings = TelemetrySettings::resolve(&config, env);
+        assert!(settings.enabled);
+        assert_eq!(settings.endpoint, "http://collector:4318");
+        assert_eq!(settings.service_name, "ride-ci");
+
+        let disabled = |name: &str| match name {
+            "OTEL_SDK_DISABLED" => Some("true".to_string()),
+            _ => env(name),
+        };
+        assert!(!TelemetrySettings::resolve(&config, disabled).enabled);
+    }
+}
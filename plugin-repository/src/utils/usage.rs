@@ -0,0 +1,223 @@
+//! Анонимные отчеты о результатах команд (`[telemetry.usage]`, выключены по умолчанию).
+//!
+//! Отчет содержит только команду, длительность, этапы и категорию сбоя: ни путей,
+//! ни имени проекта, ни текста ошибок. По ним видно, какие этапы чаще падают на агентах сборки.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+use crate::config::parser::Config;
+use crate::core::deadline::DeadlineExceeded;
+use crate::git::GitError;
+use crate::utils::exit::ExitStatus;
+use crate::utils::network::NetworkUtils;
+
+/// Непустое значение отключает отчеты независимо от конфигурации (как и DO_NOT_TRACK=1)
+pub const NO_USAGE_REPORT_ENV: &str = "DEPLOY_PLUGIN_NO_USAGE_REPORT";
+/// Версия формата отчета
+const SCHEMA: u32 = 1;
+/// Отправка не должна заметно задерживать завершение команды
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Этапы пайплайна, выполненные за запуск (журнал `Deadline`)
+static STAGES: Mutex<Vec<StageOutcome>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StageOutcome {
+    pub stage: String,
+    pub duration_ms: u64,
+    pub completed: bool,
+}
+
+/// Записывает завершение этапа для отчета
+pub fn record_stage(stage: &str, duration: Duration, completed: bool) {
+    STAGES.lock().expect("usage stages poisoned").push(StageOutcome {
+        stage: stage.to_string(),
+        duration_ms: duration.as_millis() as u64,
+        completed,
+    });
+}
+
+/// Отчет о запуске команды
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageReport {
+    pub schema: u32,
+    pub tool_version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub ci: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    pub command: String,
+    pub duration_ms: u64,
+    /// `success` или `failure`; штатный код выхода (нечего релизить) — `success`
+    pub outcome: &'static str,
+    pub exit_code: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<&'static str>,
+    /// Этап, на котором команда упала
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_stage: Option<String>,
+    pub stages: Vec<StageOutcome>,
+}
+
+impl UsageReport {
+    pub fn new(command: &str, duration: Duration, result: &Result<()>, stages: Vec<StageOutcome>, agent: Option<String>) -> Self {
+        let exit_status = result.as_ref().err().and_then(|e| e.downcast_ref::<ExitStatus>());
+        let (outcome, exit_code, category) = match (result, exit_status) {
+            (Ok(()), _) => ("success", 0, None),
+            (Err(_), Some(status)) => ("success", status.code, None),
+            (Err(e), None) => ("failure", 1, Some(categorize(e))),
+        };
+        let failed_stage = if outcome == "failure" {
+            stages.iter().rev().find(|s| !s.completed).map(|s| s.stage.clone())
+        } else {
+            None
+        };
+        Self {
+            schema: SCHEMA,
+            tool_version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            ci: std::env::var("CI").is_ok(),
+            agent,
+            command: command.to_string(),
+            duration_ms: duration.as_millis() as u64,
+            outcome,
+            exit_code,
+            category,
+            failed_stage,
+            stages,
+        }
+    }
+}
+
+/// Категория сбоя по типам ошибок в цепочке: текст ошибки в отчет не попадает
+pub fn categorize(error: &anyhow::Error) -> &'static str {
+    if error.downcast_ref::<DeadlineExceeded>().is_some() {
+        return "timeout";
+    }
+    for cause in error.chain() {
+        if cause.is::<GitError>() {
+            return "git";
+        }
+        if cause.is::<reqwest::Error>() {
+            return "network";
+        }
+        if cause.is::<toml::de::Error>() || cause.is::<toml_edit::TomlError>() {
+            return "config";
+        }
+        if cause.is::<std::io::Error>() {
+            return "io";
+        }
+    }
+    // Ошибки валидации и загрузки конфигурации — строки с общим контекстом
+    if error.chain().any(|cause| {
+        let text = cause.to_string();
+        text.starts_with("Валидация конфигурации") || text.starts_with("Не удалось загрузить конфигурацию")
+    }) {
+        return "config";
+    }
+    "other"
+}
+
+/// Отправитель отчетов; создается, только если отчеты включены
+#[derive(Debug, Clone)]
+pub struct UsageReporter {
+    endpoint: String,
+    agent: Option<String>,
+    client: reqwest::Client,
+}
+
+impl UsageReporter {
+    /// None — отчеты выключены (по умолчанию, DO_NOT_TRACK=1 или DEPLOY_PLUGIN_NO_USAGE_REPORT)
+    pub fn from_config(config: &Config, env: impl Fn(&str) -> Option<String>) -> Result<Option<Self>> {
+        let usage = &config.telemetry.usage;
+        let opted_out = env(NO_USAGE_REPORT_ENV).is_some_and(|v| !v.is_empty()) || env("DO_NOT_TRACK").is_some_and(|v| v == "1");
+        if !usage.enabled || opted_out {
+            return Ok(None);
+        }
+        let endpoint = usage
+            .endpoint
+            .clone()
+            .filter(|e| !e.trim().is_empty())
+            .context("[telemetry.usage] enabled = true требует endpoint")?;
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            anyhow::bail!("[telemetry.usage] endpoint должен начинаться с http:// или https://");
+        }
+        Ok(Some(Self {
+            endpoint,
+            agent: usage.agent.clone().filter(|a| !a.trim().is_empty() && !a.contains("${")),
+            client: NetworkUtils::http_client(&config.network.proxy, REQUEST_TIMEOUT)?,
+        }))
+    }
+
+    /// Сообщает в лог, что отчеты включены и что в них попадает
+    pub fn announce(&self) {
+        info!(
+            "📊 Отчеты об использовании включены ([telemetry.usage]): команда, длительность и категория результата отправляются в {}. Отключить: {}=1",
+            self.endpoint,
+            NO_USAGE_REPORT_ENV
+        );
+    }
+
+    /// Отправляет отчет о завершившейся команде; ошибки отправки не влияют на результат команды
+    pub async fn send(&self, command: &str, started: Instant, result: &Result<()>) {
+        let stages = STAGES.lock().expect("usage stages poisoned").clone();
+        let report = UsageReport::new(command, started.elapsed(), result, stages, self.agent.clone());
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .header(reqwest::header::USER_AGENT, concat!("deploy-pugin/", env!("CARGO_PKG_VERSION")))
+            .json(&report)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+        match response {
+            Ok(_) => info!(
+                "📊 Отчет отправлен: {} — {}{}",
+                report.command,
+                report.outcome,
+                report.category.map(|c| format!(" ({})", c)).unwrap_or_default()
+            ),
+            Err(e) => debug!("Отчет об использовании не отправлен: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_and_opt_in() {
+        let stages = vec![
+            StageOutcome { stage: "build".to_string(), duration_ms: 1200, completed: true },
+            StageOutcome { stage: "deploy".to_string(), duration_ms: 300, completed: false },
+        ];
+        let failed: Result<()> = Err(anyhow::Error::new(std::io::Error::other("scp")).context("Этап deploy не выполнен"));
+        let report = UsageReport::new("run", Duration::from_secs(2), &failed, stages.clone(), Some("agent-7".to_string()));
+        assert_eq!((report.outcome, report.exit_code, report.category), ("failure", 1, Some("io")));
+        assert_eq!(report.failed_stage.as_deref(), Some("deploy"));
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(!json.contains("scp") && json.contains("\"agent\":\"agent-7\""));
+
+        let nothing: Result<()> = Err(ExitStatus::new(3, "Нечего релизить").into());
+        let report = UsageReport::new("status", Duration::ZERO, &nothing, stages, None);
+        assert_eq!((report.outcome, report.exit_code, report.failed_stage), ("success", 3, None));
+        assert_eq!(categorize(&anyhow::anyhow!("Валидация конфигурации не пройдена")), "config");
+
+        // Выключено по умолчанию; включение требует endpoint; DO_NOT_TRACK важнее конфигурации
+        let mut config = crate::config::parser::test_config();
+        assert!(UsageReporter::from_config(&config, |_| None).unwrap().is_none());
+        config.telemetry.usage.enabled = true;
+        assert!(UsageReporter::from_config(&config, |_| None).is_err());
+        config.telemetry.usage.endpoint = Some("https://usage.corp/ingest".to_string());
+        assert!(UsageReporter::from_config(&config, |_| None).unwrap().is_some());
+        let do_not_track = |name: &str| (name == "DO_NOT_TRACK").then(|| "1".to_string());
+        assert!(UsageReporter::from_config(&config, do_not_track).unwrap().is_none());
+    }
+}
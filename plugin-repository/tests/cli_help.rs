@@ -8,8 +8,17 @@ fn help_main_and_subcommands() {
     cmd.arg("--help").assert().success();
 
     // subcommands help
-    for sub in ["build", "release", "deploy", "ai", "validate", "status"] {
+    for sub in ["build", "release", "deploy", "ai", "validate", "status", "verify-artifact", "verify-reproducible"] {
         let mut c = Command::cargo_bin("deploy-pugin").unwrap();
         c.args([sub, "--help"]).assert().success();
     }
+
+    let mut c = Command::cargo_bin("deploy-pugin").unwrap();
+    c.args(["release", "readiness", "--help"]).assert().success();
+
+    let mut c = Command::cargo_bin("deploy-pugin").unwrap();
+    c.args(["deploy", "promote", "--help"]).assert().success();
+
+    let mut c = Command::cargo_bin("deploy-pugin").unwrap();
+    c.args(["status", "unreleased", "--help"]).assert().success();
 }
@@ -85,3 +85,42 @@ fn pipeline_status_validate_smoke() {
         .assert()
         .success();
 }
+
+#[test]
+fn ai_changelog_with_mock_provider_offline() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let dir = tmp.path();
+
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=dev", "-c", "user.email=dev@example.com"])
+            .args(args)
+            .assert()
+            .success();
+    };
+    git(&["init", "-q"]);
+    git(&["commit", "-q", "--allow-empty", "-m", "init"]);
+    git(&["tag", "v1.0.0"]);
+    git(&["commit", "-q", "--allow-empty", "-m", "fix: сборка"]);
+
+    // Полный конфиг с provider = "mock": ключи YandexGPT не нужны
+    write_config(dir);
+    let cfg = fs::read_to_string(dir.join("config.toml")).unwrap().replace("provider = \"yandexgpt\"", "provider = \"mock\"");
+    fs::write(dir.join("config.toml"), cfg).unwrap();
+
+    Command::cargo_bin("deploy-pugin")
+        .unwrap()
+        .current_dir(dir)
+        .env("REPOSITORY_URL", "http://example.com/updatePlugins.xml")
+        .env("SSH_HOST", "example.com")
+        .env("SSH_USER", "user")
+        .env("DEPLOY_PATH", "/tmp/plugins/")
+        .env("XML_PATH", "/tmp/updatePlugins.xml")
+        .env("DEPLOY_PLUGIN_YANDEX_API_KEY", "")
+        .env("DEPLOY_PLUGIN_YANDEX_FOLDER_ID", "")
+        .args(["ai", "changelog"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Changelog подготовлен mock-провайдером"));
+}